@@ -0,0 +1,76 @@
+#!/usr/bin/env python3
+# Add the missing SHA-256 checksums to runtime-config.json
+#
+# Downloads every platform URL that has no checksum yet and writes its hash
+# next to the URL. Entries that already have a checksum are left alone, so
+# run it after adding or bumping a version and review the diff.
+# Usage: ./scripts/fill-checksums.py [--verify]
+#   --verify  also re-download the URLs that have a checksum and report mismatches
+
+import hashlib
+import json
+import sys
+import urllib.request
+from pathlib import Path
+
+CONFIG = Path(__file__).resolve().parent.parent / "src-tauri" / "runtime-config.json"
+
+
+def sha256_of(url):
+    digest = hashlib.sha256()
+    request = urllib.request.Request(url, headers={"User-Agent": "campp-fill-checksums"})
+    with urllib.request.urlopen(request) as response:
+        for chunk in iter(lambda: response.read(1 << 20), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+def with_checksums(version, checksums):
+    # Keep "checksums" right before "urls", like the hand-written entries
+    result = {}
+    for key, value in version.items():
+        if key == "checksums":
+            continue
+        if key == "urls":
+            result["checksums"] = checksums
+        result[key] = value
+    return result
+
+
+def main():
+    verify = "--verify" in sys.argv[1:]
+    config = json.loads(CONFIG.read_text())
+    failed = False
+
+    for name, binary in config["binaries"].items():
+        versions = binary.get("versions", [])
+        for index, version in enumerate(versions):
+            if "url" in version:
+                # Single-URL entries (phpMyAdmin, Adminer)
+                if "checksum" not in version or verify:
+                    print(f"{version['id']}: {version['url']}")
+                    digest = sha256_of(version["url"])
+                    if version.get("checksum", digest) != digest:
+                        print(f"  MISMATCH: configured {version['checksum']}, downloaded {digest}")
+                        failed = True
+                    version.setdefault("checksum", digest)
+                continue
+
+            checksums = dict(version.get("checksums", {}))
+            for platform, url in version.get("urls", {}).items():
+                if platform in checksums and not verify:
+                    continue
+                print(f"{version['id']} {platform}: {url}")
+                digest = sha256_of(url)
+                if checksums.get(platform, digest) != digest:
+                    print(f"  MISMATCH: configured {checksums[platform]}, downloaded {digest}")
+                    failed = True
+                checksums.setdefault(platform, digest)
+            versions[index] = with_checksums(version, checksums)
+
+    CONFIG.write_text(json.dumps(config, indent=2) + "\n")
+    return 1 if failed else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,568 @@
+//! Local certificate authority
+//!
+//! HTTPS project sites get their certificates from Caddy's internal PKI
+//! (`tls internal`). CAMPP keeps Caddy's storage in its own config directory
+//! and names the CA, so the root certificate is at a known path and is easy
+//! to recognise in the system trust store. Caddy is told not to install the
+//! root itself; trusting it is an explicit step with the platform's tools:
+//! the System keychain on macOS, the distribution's CA anchors on Linux (plus
+//! the NSS database Chrome and Firefox read, when it exists) and the current
+//! user's Root store on Windows.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the CA in Caddy's PKI config
+pub const CA_NAME: &str = "CAMPP Local CA";
+/// Common name of the root certificate, as the trust store shows it
+pub const ROOT_CN: &str = "CAMPP Local CA Root";
+const INTERMEDIATE_CN: &str = "CAMPP Local CA Intermediate";
+
+/// Caddy's storage directory, holding the CA and the issued certificates
+pub fn storage_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("caddy-data")
+}
+
+/// The root certificate of the local CA, once Caddy created it
+pub fn root_cert_path(config_dir: &Path) -> PathBuf {
+    storage_dir(config_dir).join("pki").join("authorities").join("local").join("root.crt")
+}
+
+/// Caddyfile global options putting the PKI in CAMPP's storage
+pub fn global_options(config_dir: &Path) -> Result<String, String> {
+    let storage = storage_dir(config_dir);
+    let storage = storage.to_str().ok_or("Invalid config path")?;
+    if storage.contains('"') || storage.contains('\n') || storage.contains('}') || storage.contains('{') {
+        return Err("Invalid config path: contains characters not allowed in Caddyfile".to_string());
+    }
+    Ok(format!(
+        "    storage file_system {{\n        root \"{}\"\n    }}\n    skip_install_trust\n    pki {{\n        ca local {{\n            name \"{}\"\n            root_cn \"{}\"\n            intermediate_cn \"{}\"\n        }}\n    }}\n",
+        storage.replace('\\', "/"),
+        CA_NAME,
+        ROOT_CN,
+        INTERMEDIATE_CN
+    ))
+}
+
+/// State of the local CA
+#[derive(Debug, Clone, Serialize)]
+pub struct CaStatus {
+    /// The root certificate exists
+    pub created: bool,
+    pub root_cert_path: String,
+    pub common_name: Option<String>,
+    /// Expiry of the root, seconds since the Unix epoch
+    pub expires_at: Option<u64>,
+    /// SHA-256 of the root certificate, colon separated hex
+    pub fingerprint: Option<String>,
+    /// The system trust store holds this root
+    pub trusted: bool,
+}
+
+pub fn status(config_dir: &Path) -> CaStatus {
+    let path = root_cert_path(config_dir);
+    let der = fs::read_to_string(&path).ok().and_then(|pem| pem_certificates(&pem).into_iter().next());
+    let info = der.as_deref().and_then(parse_certificate);
+    CaStatus {
+        created: der.is_some(),
+        root_cert_path: path.to_string_lossy().to_string(),
+        common_name: info.as_ref().and_then(|i| i.subject_cn.clone()),
+        expires_at: info.as_ref().map(|i| i.not_after),
+        fingerprint: der.as_deref().map(fingerprint),
+        trusted: der.is_some() && is_trusted(&path),
+    }
+}
+
+/// Create the CA without waiting for Caddy to start
+///
+/// `caddy validate` provisions the PKI app, which generates the root and
+/// intermediate in storage when they do not exist yet.
+pub fn create(caddy: &Path, config_dir: &Path) -> Result<(), String> {
+    let root = root_cert_path(config_dir);
+    if root.is_file() {
+        return Ok(());
+    }
+    fs::create_dir_all(config_dir)
+        .map_err(|e| format!("Failed to create {}: {}", config_dir.display(), e))?;
+    let caddyfile = config_dir.join("Caddyfile.ca");
+    fs::write(&caddyfile, format!("{{\n{}}}\n", global_options(config_dir)?))
+        .map_err(|e| format!("Failed to write {}: {}", caddyfile.display(), e))?;
+    let result = crate::process::config_watch::validate_caddyfile(caddy, &caddyfile);
+    let _ = fs::remove_file(&caddyfile);
+    result.map_err(|e| format!("Caddy could not create the local CA: {}", e))?;
+    if !root.is_file() {
+        return Err(format!("Caddy did not create {}", root.display()));
+    }
+    Ok(())
+}
+
+/// Add the root to the system trust store
+///
+/// Prompts for administrator rights on macOS and Linux; Windows asks the
+/// user to confirm adding a root to their own store.
+pub fn trust(config_dir: &Path) -> Result<(), String> {
+    let root = root_cert_path(config_dir);
+    if !root.is_file() {
+        return Err("The local CA has not been created yet".to_string());
+    }
+    platform::trust(&root)?;
+    platform::trust_nss(&root);
+    Ok(())
+}
+
+/// Remove the root from the system trust store
+pub fn untrust(config_dir: &Path) -> Result<(), String> {
+    let root = root_cert_path(config_dir);
+    platform::untrust_nss();
+    if root.is_file() && !is_trusted(&root) {
+        return Ok(());
+    }
+    platform::untrust(&root)
+}
+
+fn is_trusted(root: &Path) -> bool {
+    platform::is_trusted(root)
+}
+
+/// A certificate Caddy issued for a site
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteCertificate {
+    /// The `.crt` file in Caddy's storage
+    pub path: String,
+    #[serde(flatten)]
+    pub info: CertificateInfo,
+    /// Issued by the CAMPP local CA
+    pub local_ca: bool,
+    pub expired: bool,
+}
+
+/// Certificates in Caddy's storage, sorted by subject
+///
+/// Caddy keeps them in `certificates/<issuer>/<host>/<host>.crt`, the site
+/// certificate first and then its chain. Files that do not parse are skipped.
+pub fn site_certificates(config_dir: &Path) -> Vec<SiteCertificate> {
+    let now = crate::util::now();
+    let certificates_dir = storage_dir(config_dir).join("certificates");
+    let mut certificates = Vec::new();
+    for issuer in fs::read_dir(&certificates_dir).into_iter().flatten().flatten() {
+        for host in fs::read_dir(issuer.path()).into_iter().flatten().flatten() {
+            let path = host.path().join(format!("{}.crt", host.file_name().to_string_lossy()));
+            let Ok(pem) = fs::read_to_string(&path) else { continue };
+            let Some(info) = pem_certificates(&pem).first().and_then(|der| parse_certificate(der)) else {
+                tracing::warn!("Skipping unreadable certificate {}", path.display());
+                continue;
+            };
+            certificates.push(SiteCertificate {
+                path: path.to_string_lossy().to_string(),
+                local_ca: info.issuer_cn.as_deref() == Some(INTERMEDIATE_CN),
+                expired: info.not_after < now,
+                info,
+            });
+        }
+    }
+    certificates.sort_by(|a, b| a.info.subject_cn.cmp(&b.info.subject_cn).then(a.path.cmp(&b.path)));
+    certificates
+}
+
+/// SHA-256 of a DER certificate, as `AB:CD:...`
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der).iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+/// DER certificates in PEM text
+pub fn pem_certificates(pem: &str) -> Vec<Vec<u8>> {
+    use base64::Engine;
+    let mut certificates = Vec::new();
+    let mut body: Option<String> = None;
+    for line in pem.lines().map(str::trim) {
+        match line {
+            "-----BEGIN CERTIFICATE-----" => body = Some(String::new()),
+            "-----END CERTIFICATE-----" => {
+                if let Some(der) = body.take().and_then(|b| base64::engine::general_purpose::STANDARD.decode(b).ok()) {
+                    certificates.push(der);
+                }
+            }
+            line => {
+                if let Some(ref mut body) = body {
+                    body.push_str(line);
+                }
+            }
+        }
+    }
+    certificates
+}
+
+/// The parts of a certificate shown to the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CertificateInfo {
+    pub subject_cn: Option<String>,
+    /// DNS names and IP addresses of the subjectAltName extension
+    pub subject_alt_names: Vec<String>,
+    pub issuer_cn: Option<String>,
+    /// Seconds since the Unix epoch
+    pub not_before: u64,
+    pub not_after: u64,
+}
+
+/// Read the names and validity of a DER certificate
+pub fn parse_certificate(der: &[u8]) -> Option<CertificateInfo> {
+    let (_, certificate, _) = der_item(der, 0)?;
+    let (_, tbs, _) = der_item(certificate, 0)?;
+    let mut pos = 0;
+    let (tag, _, next) = der_item(tbs, pos)?;
+    // [0] version is optional
+    if tag == 0xA0 {
+        pos = next;
+    }
+    let (_, _, pos) = der_item(tbs, pos)?; // serial number
+    let (_, _, pos) = der_item(tbs, pos)?; // signature algorithm
+    let (_, issuer, pos) = der_item(tbs, pos)?;
+    let (_, validity, pos) = der_item(tbs, pos)?;
+    let (_, subject, pos) = der_item(tbs, pos)?;
+    let (not_before_tag, not_before, next) = der_item(validity, 0)?;
+    let (not_after_tag, not_after, _) = der_item(validity, next)?;
+
+    // Public key info, then the optional unique IDs and [3] extensions
+    let (_, _, mut pos) = der_item(tbs, pos)?;
+    let mut subject_alt_names = Vec::new();
+    while let Some((tag, content, next)) = der_item(tbs, pos) {
+        if tag == 0xA3 {
+            subject_alt_names = alt_names(content).unwrap_or_default();
+        }
+        pos = next;
+    }
+    Some(CertificateInfo {
+        subject_cn: common_name(subject),
+        subject_alt_names,
+        issuer_cn: common_name(issuer),
+        not_before: der_time(not_before_tag, not_before)?,
+        not_after: der_time(not_after_tag, not_after)?,
+    })
+}
+
+/// Tag, content and the offset after the DER item at `pos`
+fn der_item(data: &[u8], pos: usize) -> Option<(u8, &[u8], usize)> {
+    let tag = *data.get(pos)?;
+    let first = *data.get(pos + 1)? as usize;
+    let (len, start) = if first < 0x80 {
+        (first, pos + 2)
+    } else {
+        let count = first & 0x7F;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let bytes = data.get(pos + 2..pos + 2 + count)?;
+        (bytes.iter().fold(0usize, |len, b| (len << 8) | *b as usize), pos + 2 + count)
+    };
+    let content = data.get(start..start.checked_add(len)?)?;
+    Some((tag, content, start + len))
+}
+
+/// The commonName attribute of an X.501 name
+fn common_name(name: &[u8]) -> Option<String> {
+    const CN_OID: [u8; 3] = [0x55, 0x04, 0x03];
+    let mut pos = 0;
+    while let Some((_, set, next)) = der_item(name, pos) {
+        let mut inner = 0;
+        while let Some((_, attribute, after)) = der_item(set, inner) {
+            let (_, oid, value_pos) = der_item(attribute, 0)?;
+            if oid == CN_OID {
+                let (_, value, _) = der_item(attribute, value_pos)?;
+                return Some(String::from_utf8_lossy(value).to_string());
+            }
+            inner = after;
+        }
+        pos = next;
+    }
+    None
+}
+
+/// Names of the subjectAltName extension, in `[3] { SEQUENCE OF Extension }`
+fn alt_names(extensions: &[u8]) -> Option<Vec<String>> {
+    const SAN_OID: [u8; 3] = [0x55, 0x1D, 0x11];
+    let (_, extensions, _) = der_item(extensions, 0)?;
+    let mut pos = 0;
+    while let Some((_, extension, next)) = der_item(extensions, pos) {
+        let (_, oid, value_pos) = der_item(extension, 0)?;
+        if oid == SAN_OID {
+            // The OCTET STRING value, after the optional critical flag
+            let (_, value, after) = der_item(extension, value_pos)?;
+            let value = match der_item(extension, after) {
+                Some((0x04, value, _)) => value,
+                _ => value,
+            };
+            let (_, general_names, _) = der_item(value, 0)?;
+            let mut names = Vec::new();
+            let mut name_pos = 0;
+            while let Some((tag, name, next)) = der_item(general_names, name_pos) {
+                match (tag, name.len()) {
+                    // dNSName
+                    (0x82, _) => names.push(String::from_utf8_lossy(name).to_string()),
+                    // iPAddress
+                    (0x87, 4) => names.push(std::net::Ipv4Addr::new(name[0], name[1], name[2], name[3]).to_string()),
+                    (0x87, 16) => {
+                        let octets: [u8; 16] = name.try_into().ok()?;
+                        names.push(std::net::Ipv6Addr::from(octets).to_string());
+                    }
+                    _ => {}
+                }
+                name_pos = next;
+            }
+            return Some(names);
+        }
+        pos = next;
+    }
+    None
+}
+
+/// UTCTime (`YYMMDDHHMMSSZ`) or GeneralizedTime (`YYYYMMDDHHMMSSZ`) as Unix seconds
+fn der_time(tag: u8, value: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(value).ok()?.strip_suffix('Z')?;
+    let (year, rest) = match tag {
+        0x17 => {
+            let year: i64 = text.get(..2)?.parse().ok()?;
+            (if year < 50 { 2000 + year } else { 1900 + year }, text.get(2..)?)
+        }
+        0x18 => (text.get(..4)?.parse().ok()?, text.get(4..)?),
+        _ => return None,
+    };
+    let field = |i: usize| -> Option<i64> { rest.get(i..i + 2)?.parse().ok() };
+    let (month, day, hour, minute, second) = (field(0)?, field(2)?, field(4)?, field(6)?, field(8)?);
+
+    // Days since 1970-01-01 in the proleptic Gregorian calendar
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use crate::elevate::shell_quote;
+
+    const KEYCHAIN: &str = "/Library/Keychains/System.keychain";
+
+    pub fn trust(root: &Path) -> Result<(), String> {
+        let script = format!(
+            "security add-trusted-cert -d -r trustRoot -k {} {}",
+            KEYCHAIN,
+            shell_quote(&root.to_string_lossy())
+        );
+        crate::elevate::run(&script, "trusting the local CA")
+    }
+
+    pub fn untrust(root: &Path) -> Result<(), String> {
+        let script = format!(
+            "security remove-trusted-cert -d {} ; security delete-certificate -c {} {}",
+            shell_quote(&root.to_string_lossy()),
+            shell_quote(ROOT_CN),
+            KEYCHAIN
+        );
+        crate::elevate::run(&script, "removing the local CA")
+    }
+
+    pub fn is_trusted(root: &Path) -> bool {
+        crate::process::run_quiet("security", &["verify-cert", "-c", &root.to_string_lossy()])
+    }
+
+    // Chrome and Firefox use the keychain on macOS
+    pub fn trust_nss(_root: &Path) {}
+
+    pub fn untrust_nss() {}
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use crate::elevate::shell_quote;
+
+    /// Anchor directory and refresh command of each distribution family
+    const ANCHORS: &[(&str, &str)] = &[
+        ("/usr/local/share/ca-certificates", "update-ca-certificates"),
+        ("/etc/pki/ca-trust/source/anchors", "update-ca-trust"),
+        ("/etc/ca-certificates/trust-source/anchors", "trust extract-compat"),
+    ];
+    const ANCHOR_FILE: &str = "campp-local-ca.crt";
+
+    fn anchor() -> Result<(PathBuf, &'static str), String> {
+        ANCHORS
+            .iter()
+            .find(|(dir, _)| Path::new(dir).is_dir())
+            .map(|(dir, refresh)| (Path::new(dir).join(ANCHOR_FILE), *refresh))
+            .ok_or_else(|| "No system CA directory found, add the root certificate to your browser manually".to_string())
+    }
+
+    pub fn trust(root: &Path) -> Result<(), String> {
+        let (anchor, refresh) = anchor()?;
+        let script = format!(
+            "cp {} {} && chmod 644 {} && {}",
+            shell_quote(&root.to_string_lossy()),
+            shell_quote(&anchor.to_string_lossy()),
+            shell_quote(&anchor.to_string_lossy()),
+            refresh
+        );
+        crate::elevate::run(&script, "trusting the local CA")
+    }
+
+    pub fn untrust(_root: &Path) -> Result<(), String> {
+        let (anchor, refresh) = anchor()?;
+        if !anchor.exists() {
+            return Ok(());
+        }
+        let script = format!("rm -f {} && {}", shell_quote(&anchor.to_string_lossy()), refresh);
+        crate::elevate::run(&script, "removing the local CA")
+    }
+
+    pub fn is_trusted(root: &Path) -> bool {
+        match (anchor(), fs::read(root)) {
+            (Ok((anchor, _)), Ok(root)) => fs::read(anchor).is_ok_and(|installed| installed == root),
+            _ => false,
+        }
+    }
+
+    fn nss_db() -> Option<String> {
+        let db = dirs::home_dir()?.join(".pki").join("nssdb");
+        db.is_dir().then(|| format!("sql:{}", db.to_string_lossy()))
+    }
+
+    /// Chrome and Firefox on Linux read the user's NSS database, not the
+    /// system anchors; `certutil` is optional
+    pub fn trust_nss(root: &Path) {
+        if let Some(db) = nss_db() {
+            if !crate::process::run_quiet("certutil", &["-d", &db, "-A", "-t", "C,,", "-n", CA_NAME, "-i", &root.to_string_lossy()]) {
+                tracing::warn!("Could not add the local CA to {}, is certutil installed?", db);
+            }
+        }
+    }
+
+    pub fn untrust_nss() {
+        if let Some(db) = nss_db() {
+            crate::process::run_quiet("certutil", &["-d", &db, "-D", "-n", CA_NAME]);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use crate::process::manager::configure_no_window;
+    use std::process::Command;
+
+    pub fn trust(root: &Path) -> Result<(), String> {
+        let output = configure_no_window(Command::new("certutil"))
+            .args(["-user", "-addstore", "Root"])
+            .arg(root)
+            .output()
+            .map_err(|e| format!("Failed to run certutil: {}", e))?;
+        if !output.status.success() {
+            return Err("Adding the local CA was cancelled or denied".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn untrust(_root: &Path) -> Result<(), String> {
+        if !crate::process::run_quiet("certutil", &["-user", "-delstore", "Root", ROOT_CN]) {
+            return Err("Removing the local CA was cancelled or denied".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_trusted(_root: &Path) -> bool {
+        crate::process::run_quiet("certutil", &["-user", "-verifystore", "Root", ROOT_CN])
+    }
+
+    // Chrome and Edge use the Windows store, Firefox imports it by default
+    pub fn trust_nss(_root: &Path) {}
+
+    pub fn untrust_nss() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed, CN=CAMPP Test Root, valid 2024-01-01 to 2034-01-01
+    const TEST_ROOT: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBiTCCAS+gAwIBAgIUFGo8sIzQ6nMXODbblS5dts+xlEIwCgYIKoZIzj0EAwIw
+GjEYMBYGA1UEAwwPQ0FNUFAgVGVzdCBSb290MB4XDTI0MDEwMTAwMDAwMFoXDTM0
+MDEwMTAwMDAwMFowGjEYMBYGA1UEAwwPQ0FNUFAgVGVzdCBSb290MFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAErlCa+FZhxiXlJhfqI+5dj4y4CC0VfaIiNQ1qMTby
+8rGBD4TrnJr1295ZZn2EP3dqw+oXuKoGsqQXhksYahxU2qNTMFEwHQYDVR0OBBYE
+FB0Q0I8e4HXaJzvEdT2HQXZ2ogSiMB8GA1UdIwQYMBaAFB0Q0I8e4HXaJzvEdT2H
+QXZ2ogSiMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDSAAwRQIhAPxFmgPU
+qg8KIpsj9b+C6JtHyCF0zyVCNs/+Na2ajHrFAiBFbzgeWVlmde2qntEH/OxJBCC3
+1nzE97+PghMUydRDkQ==
+-----END CERTIFICATE-----
+";
+
+    // Self-signed, CN=shop.test with DNS and IP names, valid 2024-01-01 to 2024-04-01
+    const TEST_SITE: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBpzCCAUygAwIBAgIUUJ+fjg/tvdjmEkCPmsRPrv8hbGIwCgYIKoZIzj0EAwIw
+FDESMBAGA1UEAwwJc2hvcC50ZXN0MB4XDTI0MDEwMTAwMDAwMFoXDTI0MDQwMTAw
+MDAwMFowFDESMBAGA1UEAwwJc2hvcC50ZXN0MFkwEwYHKoZIzj0CAQYIKoZIzj0D
+AQcDQgAEj8cSRgU9xo6pmDzlWcQoPK4VragSnq1Yq2R/26fk5ugFKpnKeXaA8YQ+
+ibw8Raf0YmtBUxIAfmx+r1RMi5lIQKN8MHowHQYDVR0OBBYEFInGr568pxKDZqLz
+BA7ohRXjqze4MB8GA1UdIwQYMBaAFInGr568pxKDZqLzBA7ohRXjqze4MA8GA1Ud
+EwEB/wQFMAMBAf8wJwYDVR0RBCAwHoIJc2hvcC50ZXN0ggsqLnNob3AudGVzdIcE
+fwAAATAKBggqhkjOPQQDAgNJADBGAiEAocWFAue65BF7WlX++azRZQwhB55dVDxx
+Qv14jHTtk1gCIQCTeixdfzyPIsZdq0a8FjF7U1+mFpZ9vTxsxxKmJp0+nQ==
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_parse_certificate() {
+        let der = pem_certificates(TEST_ROOT);
+        assert_eq!(der.len(), 1);
+        let info = parse_certificate(&der[0]).unwrap();
+        assert_eq!(info.subject_cn.as_deref(), Some("CAMPP Test Root"));
+        assert_eq!(info.issuer_cn.as_deref(), Some("CAMPP Test Root"));
+        assert!(info.subject_alt_names.is_empty());
+        assert_eq!(info.not_before, 1_704_067_200);
+        assert_eq!(info.not_after, 2_019_686_400);
+        assert_eq!(fingerprint(&der[0]).len(), 32 * 3 - 1);
+    }
+
+    #[test]
+    fn test_site_certificates() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = storage_dir(temp_dir.path()).join("certificates").join("local").join("shop.test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("shop.test.crt"), format!("{}{}", TEST_SITE, TEST_ROOT)).unwrap();
+        let broken = dir.parent().unwrap().join("broken.test");
+        fs::create_dir_all(&broken).unwrap();
+        fs::write(broken.join("broken.test.crt"), "not a certificate").unwrap();
+
+        let certificates = site_certificates(temp_dir.path());
+        assert_eq!(certificates.len(), 1);
+        let certificate = &certificates[0];
+        assert_eq!(certificate.info.subject_cn.as_deref(), Some("shop.test"));
+        assert_eq!(certificate.info.subject_alt_names, vec!["shop.test", "*.shop.test", "127.0.0.1"]);
+        assert_eq!(certificate.info.not_after, 1_711_929_600);
+        assert!(certificate.expired);
+        assert!(!certificate.local_ca);
+    }
+
+    #[test]
+    fn test_der_time() {
+        assert_eq!(der_time(0x17, b"700101000000Z"), Some(0));
+        assert_eq!(der_time(0x18, b"20000301000000Z"), Some(951_868_800));
+        assert_eq!(der_time(0x17, b"700101000000"), None);
+    }
+
+    #[test]
+    fn test_status_without_ca() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let status = status(temp_dir.path());
+        assert!(!status.created);
+        assert!(!status.trusted);
+        assert!(global_options(temp_dir.path()).unwrap().contains("skip_install_trust"));
+    }
+}
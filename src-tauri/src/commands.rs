@@ -2,11 +2,15 @@
 //!
 //! This module contains all Tauri commands that are exposed to the frontend.
 
-use crate::process::{ServiceMap, ServiceState, ServiceType};
+use crate::process::manager::RestartReport;
+use crate::process::{RestartStrategy, ServiceMap, ServiceState, ServiceType};
 use crate::runtime::deps::DependencyCheckResult;
-use crate::runtime::downloader::{DownloadProgress, RuntimeDownloader};
+use crate::operations::{Operation, OperationKind, OperationProgress};
+use crate::runtime::downloader::{BinaryComponent, DownloadProgress, DownloadStep, ProgressCallback, RuntimeDownloader};
 use crate::runtime::packages::{PackageSelection, PackagesConfig};
-use crate::config::AppSettings;
+use crate::config::apply::{AppliedChange, ApplyAction, ApplyProgress, ApplyReport, PendingChange, SettingsChanged};
+use crate::config::{AppSettings, BasicAuthCredentials, SecurityHeader};
+use crate::error::CamppError;
 use crate::AppState;
 use crate::ProcessManager;
 use std::fs;
@@ -18,7 +22,7 @@ use tauri::{Emitter, Manager, State};
 ///
 /// Only allows opening known app directories (runtime, download, config, project root).
 #[tauri::command]
-pub async fn open_folder(path: String) -> Result<(), String> {
+pub async fn open_folder(path: String) -> Result<(), CamppError> {
     use tauri_plugin_opener::reveal_item_in_dir;
 
     let path_obj = std::path::Path::new(&path);
@@ -30,7 +34,7 @@ pub async fn open_folder(path: String) -> Result<(), String> {
     if let Ok(runtime_dir) = downloader.get_runtime_dir() {
         allowed_dirs.push(runtime_dir);
     }
-    allowed_dirs.push(std::env::temp_dir().join("campp-download"));
+    allowed_dirs.push(crate::runtime::download_cache::download_dir());
 
     let settings = crate::config::AppSettings::load();
     allowed_dirs.push(std::path::PathBuf::from(&settings.project_root));
@@ -38,6 +42,9 @@ pub async fn open_folder(path: String) -> Result<(), String> {
     if let Some(data_dir) = dirs::data_local_dir() {
         allowed_dirs.push(data_dir.join("campp"));
     }
+    if let Some(data_dir) = crate::config::data_dir::data_dir_override() {
+        allowed_dirs.push(data_dir);
+    }
 
     // Canonicalize the requested path and check it's under an allowed directory
     let canonical = path_obj.canonicalize()
@@ -48,7 +55,7 @@ pub async fn open_folder(path: String) -> Result<(), String> {
     });
 
     if !is_allowed {
-        return Err(format!("Access denied: path is not within an allowed directory"));
+        return Err(format!("Access denied: path is not within an allowed directory").into());
     }
 
     reveal_item_in_dir(&canonical)
@@ -57,227 +64,2597 @@ pub async fn open_folder(path: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Open the user manual in the system's default application using tauri-plugin-opener
+/// Reveal an app directory in the file manager
 ///
-/// This command locates the MANUAL.html resource file and reveals it in the
-/// file manager using tauri-plugin-opener for cross-platform compatibility.
-/// Users can then open it with their preferred browser or HTML viewer.
+/// The directory is resolved by the caller, so unlike `open_folder` no
+/// allowlist is needed.
+fn reveal_app_dir(dir: &std::path::Path) -> Result<(), CamppError> {
+    if !dir.is_dir() {
+        return Err(format!("Folder does not exist: {}", dir.display()).into());
+    }
+    tauri_plugin_opener::reveal_item_in_dir(dir)
+        .map_err(|e| format!("Failed to open folder: {}", e).into())
+}
+
+/// Open the projects root, or one project in it, in the file manager
 #[tauri::command]
-pub async fn open_manual(app: tauri::AppHandle) -> Result<(), String> {
-    use tauri::Manager;
-    use tauri_plugin_opener::reveal_item_in_dir;
+pub async fn open_project_folder(name: Option<String>) -> Result<(), CamppError> {
+    let settings = AppSettings::load();
+    let projects_dir = settings.projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+    match name {
+        Some(name) => {
+            let mut components = std::path::Path::new(&name).components();
+            if !matches!((components.next(), components.next()), (Some(std::path::Component::Normal(_)), None)) {
+                return Err(CamppError::invalid_input(format!("Invalid project name: {}", name)));
+            }
+            reveal_app_dir(&projects_dir.join(name))
+        }
+        None => reveal_app_dir(&projects_dir),
+    }
+}
 
-    let resource_dir = app
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
+/// Code editors installed on this machine
+#[tauri::command]
+pub async fn list_editors() -> Result<Vec<crate::editor::Editor>, CamppError> {
+    Ok(tokio::task::spawn_blocking(crate::editor::detect).await?)
+}
 
-    let manual_path = resource_dir.join("MANUAL.html");
+/// Open a project in `editor`, or in the editor chosen in settings, or the
+/// first one installed
+#[tauri::command]
+pub async fn open_in_editor(project: String, editor: Option<crate::editor::Editor>) -> Result<(), CamppError> {
+    tokio::task::spawn_blocking(move || {
+        let project = crate::projects::ProjectRegistry::open()?.get(&project)?;
+        let editor = editor
+            .or(AppSettings::load().editor)
+            .or_else(|| crate::editor::detect().into_iter().next())
+            .ok_or("No supported editor found, install VS Code, PhpStorm or Sublime Text")?;
+        crate::editor::open(editor, std::path::Path::new(&project.path))
+    }).await??;
+    Ok(())
+}
 
-    // Ensure the manual exists
-    if !manual_path.exists() {
-        return Err(format!("Manual not found at: {}", manual_path.display()));
-    }
+/// Projects registered in CAMPP with their URL, databases and activity
+///
+/// Whether the databases exist is only known while MySQL is running.
+#[tauri::command]
+pub async fn list_projects(state: State<'_, AppState>) -> Result<Vec<crate::projects::summary::ProjectSummary>, CamppError> {
+    let (paths, mysql_running, web_port) = {
+        let manager = state.process_manager.lock()?;
+        (
+            manager.get_runtime_paths(),
+            manager.status(ServiceType::MySQL) == ServiceState::Running,
+            manager.get_service_port(ServiceType::Caddy),
+        )
+    };
+    let summaries = tokio::task::spawn_blocking(move || {
+        let settings = AppSettings::load();
+        let existing = match (mysql_running, paths) {
+            (true, Some(paths)) => crate::database::mysql::list_databases(&paths, settings.mysql_port, &settings.mysql_root_password)
+                .map_err(|e| tracing::warn!("Failed to list databases: {}", e))
+                .ok(),
+            _ => None,
+        };
+        let web_port = web_port.unwrap_or(settings.web_port);
+        crate::projects::ProjectRegistry::open()?.list().map(|projects| {
+            projects
+                .into_iter()
+                .map(|project| crate::projects::summary::summarize(project, web_port, settings.https_port, existing.as_deref()))
+                .collect::<Vec<_>>()
+        })
+    }).await??;
+    Ok(summaries)
+}
 
-    // Use tauri-plugin-opener to reveal the file in the file manager
-    // This is cross-platform and lets the user choose how to open it
-    reveal_item_in_dir(&manual_path)
-        .map_err(|e| format!("Failed to open manual: {}", e))?;
+/// Create an empty project folder in the projects root and register it
+#[tauri::command]
+pub async fn create_project(name: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    crate::projects::validate_project_name(&name).map_err(CamppError::invalid_input)?;
+    let project = tokio::task::spawn_blocking(move || {
+        let projects_dir = AppSettings::load().projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+        crate::projects::ProjectRegistry::open()?.create(&projects_dir, &name)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
 
-    Ok(())
+/// Create a Laravel project with Composer, set up for the bundled database
+///
+/// composer.phar is downloaded on first use. The project database is created
+/// when MySQL is running, otherwise only `.env` refers to it.
+#[tauri::command]
+pub async fn create_laravel_project(name: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    crate::projects::validate_project_name(&name).map_err(CamppError::invalid_input)?;
+    let (paths, mysql_running) = {
+        let manager = state.process_manager.lock()?;
+        (manager.get_runtime_paths(), manager.status(ServiceType::MySQL) == ServiceState::Running)
+    };
+    let paths = match paths {
+        Some(paths) => paths,
+        None => crate::runtime::locator::locate_runtime_binaries()?,
+    };
+    let app_paths = crate::runtime::locator::get_app_data_paths()?;
+    let settings = AppSettings::load();
+    let projects_dir = settings.projects_dir(&app_paths.projects_dir);
+    // Fail before downloading anything
+    crate::projects::ProjectRegistry::open()?.check_new(&projects_dir, &name)?;
+
+    let op = Arc::new(Operation::start(OperationKind::CreateProject, false, format!("Creating Laravel project '{}'", name)));
+    let project = op.run(async {
+        op.progress("downloading_composer", None, "Getting Composer");
+        let phar = crate::projects::composer::ensure_composer(&app_paths.runtime_dir).await?;
+
+        let op = op.clone();
+        tokio::task::spawn_blocking(move || {
+            let registry = crate::projects::ProjectRegistry::open()?;
+            let project = crate::projects::laravel::create(&paths, &phar, &registry, &projects_dir, &settings, &name, &op)?;
+            if mysql_running {
+                let database = crate::projects::laravel::database_name(&name);
+                if let Err(e) = crate::database::mysql::create_database(&paths, settings.mysql_port, &settings.mysql_root_password, &database) {
+                    tracing::warn!("Project '{}' was created without its database: {}", name, e);
+                }
+            }
+            Ok(project)
+        }).await.map_err(|e| format!("Task error: {}", e))?
+    }).await?;
+
+    reload_project_sites(&state).await;
+    Ok(project)
 }
 
-// Global state for download progress
-static DOWNLOAD_PROGRESS: Mutex<Option<DownloadProgress>> = Mutex::new(None);
+/// Register an existing folder or clone a git URL as a project
+///
+/// With `link`, a folder outside the projects root is symlinked into it.
+/// Databases listed in the project's `campp.json` or named in its `.env` or
+/// `wp-config.php` are created when MySQL is running.
+#[tauri::command]
+pub async fn import_project(
+    source: String,
+    name: Option<String>,
+    link: bool,
+    state: State<'_, AppState>,
+) -> Result<crate::projects::Project, CamppError> {
+    if let Some(ref name) = name {
+        crate::projects::validate_project_name(name).map_err(CamppError::invalid_input)?;
+    }
+    let (paths, mysql_running) = {
+        let manager = state.process_manager.lock()?;
+        (manager.get_runtime_paths(), manager.status(ServiceType::MySQL) == ServiceState::Running)
+    };
+    let settings = AppSettings::load();
+    let projects_dir = settings.projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+
+    let op = Arc::new(Operation::start(OperationKind::Import, false, format!("Importing {}", source)));
+    let project = op.run(async {
+        let op = op.clone();
+        tokio::task::spawn_blocking(move || {
+            let registry = crate::projects::ProjectRegistry::open()?;
+            let project = crate::projects::import::import(&registry, &projects_dir, &source, name.as_deref(), link, &op)?;
+            if let Some(warning) = project.manifest().and_then(|manifest| manifest.php_ini_ignored()) {
+                tracing::warn!("Project '{}': {}", project.name, warning);
+                op.progress("checking_php", None, warning);
+            }
+            let installed_php = crate::runtime::packages::get_php_package(&crate::runtime::packages::get_selected_package_ids().php);
+            if let (Some(manifest), Some(php)) = (project.manifest(), installed_php) {
+                let has_backend = manifest.php.as_deref()
+                    .is_some_and(|version| crate::process::php_pool::backend_port(&settings, settings.php_port, version).is_some());
+                if let Some(warning) = manifest.php_mismatch(&php.version).filter(|_| !has_backend) {
+                    tracing::warn!("Project '{}': {}", project.name, warning);
+                    op.progress("checking_php", None, warning);
+                }
+            }
+            if let (true, Some(paths)) = (mysql_running, paths) {
+                for database in project.required_databases() {
+                    op.progress("creating_database", None, format!("Creating database '{}'", database));
+                    if let Err(e) = crate::database::mysql::create_database(&paths, settings.mysql_port, &settings.mysql_root_password, &database) {
+                        tracing::warn!("Project '{}' was imported without database '{}': {}", project.name, database, e);
+                    }
+                }
+            }
+            Ok(project)
+        }).await.map_err(|e| format!("Task error: {}", e))?
+    }).await?;
 
-/// Start a service
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Zip a project, with `include_database` also dumping the MySQL databases
+/// its config files name
+///
+/// The archive goes to `path`, or to the exports folder of the app data.
 #[tauri::command]
-pub async fn start_service(
-    service: ServiceType,
+pub async fn export_project(
+    name: String,
+    include_database: bool,
+    path: Option<String>,
     state: State<'_, AppState>,
-) -> Result<ServiceMap, String> {
-    let pm = state.process_manager.clone();
+) -> Result<crate::projects::export::ExportReport, CamppError> {
+    let project = crate::projects::ProjectRegistry::open()?.get(&name)?;
+    let paths = if include_database {
+        let manager = state.process_manager.lock()?;
+        if manager.status(ServiceType::MySQL) != ServiceState::Running {
+            return Err(CamppError::ServiceNotRunning { service: ServiceType::MySQL });
+        }
+        manager.get_runtime_paths()
+    } else {
+        None
+    };
+    let base_dir = crate::runtime::locator::get_app_data_paths()?.base_dir;
+
+    let op = Arc::new(Operation::start(OperationKind::Export, false, format!("Exporting project '{}'", name)));
+    let report = op.run(async {
+        let op = op.clone();
+        tokio::task::spawn_blocking(move || {
+            let dest = match path {
+                Some(path) => std::path::PathBuf::from(path),
+                None => base_dir.join("exports").join(format!("{}-{}.zip", project.name, crate::util::now())),
+            };
+
+            // Removed when dropped, also when a dump fails
+            let staging = tempfile::Builder::new()
+                .prefix(&format!("campp-export-{}-", project.name))
+                .tempdir()
+                .map_err(|e| format!("Failed to create a staging directory: {}", e))?;
+            let mut dumps = Vec::new();
+            if let Some(ref paths) = paths {
+                let settings = AppSettings::load();
+                for database in crate::database::usage::project_mysql_databases(std::path::Path::new(&project.path)) {
+                    op.progress("dumping", None, format!("Dumping database '{}'", database));
+                    let dump = staging.path().join(format!("{}.sql", database));
+                    crate::database::mysql::dump_database(paths, settings.mysql_port, &settings.mysql_root_password, &database, &dump)?;
+                    dumps.push((database, dump));
+                }
+            }
+
+            crate::projects::export::export_project(&project, &dest, &dumps, &op)
+        }).await.map_err(|e| format!("Task error: {}", e))?
+    }).await?;
+
+    Ok(report)
+}
+
+/// Copy a project to `new_name`, including copies of its MySQL databases
+///
+/// The copy's config files point at the copied databases. MySQL has to be
+/// running when the project uses a database.
+#[tauri::command]
+pub async fn duplicate_project(src: String, new_name: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    crate::projects::validate_project_name(&new_name).map_err(CamppError::invalid_input)?;
+    let source = crate::projects::ProjectRegistry::open()?.get(&src)?;
+    let databases: Vec<(String, String)> = source.required_databases()
+        .into_iter()
+        .map(|database| {
+            let copy = crate::projects::duplicate::copied_database_name(&database, &source.name, &new_name);
+            (database, copy)
+        })
+        .collect();
+    let paths = if databases.is_empty() {
+        None
+    } else {
+        let manager = state.process_manager.lock()?;
+        if manager.status(ServiceType::MySQL) != ServiceState::Running {
+            return Err(CamppError::ServiceNotRunning { service: ServiceType::MySQL });
+        }
+        manager.get_runtime_paths()
+    };
+    let settings = AppSettings::load();
+    let projects_dir = settings.projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+
+    let op = Arc::new(Operation::start(OperationKind::Duplicate, false, format!("Duplicating project '{}'", src)));
+    let project = op.run(async {
+        let op = op.clone();
+        tokio::task::spawn_blocking(move || {
+            let registry = crate::projects::ProjectRegistry::open()?;
+            let copy_databases = || {
+                let Some(paths) = paths else { return Ok(()) };
+                let total = databases.len();
+                let mut created: Vec<&str> = Vec::new();
+                for (i, (database, copy)) in databases.iter().enumerate() {
+                    op.progress("copying_database", Some((100 * i / total) as u8), format!("Copying database '{}' to '{}'", database, copy));
+                    if let Err(e) = crate::database::mysql::copy_database(&paths, settings.mysql_port, &settings.mysql_root_password, database, copy) {
+                        // Drop the copies made so far, the project is not created
+                        for copy in created {
+                            if let Err(e) = crate::database::mysql::drop_database(&paths, settings.mysql_port, &settings.mysql_root_password, copy) {
+                                tracing::warn!("Failed to drop database '{}': {}", copy, e);
+                            }
+                        }
+                        return Err(e);
+                    }
+                    created.push(copy);
+                }
+                Ok(())
+            };
+            crate::projects::duplicate::duplicate(&registry, &projects_dir, &source, &new_name, &databases, &op, copy_databases)
+        }).await.map_err(|e| format!("Task error: {}", e))?
+    }).await?;
+
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Register the folders in the projects directory that are not projects yet,
+/// detecting their framework and document root
+#[tauri::command]
+pub async fn scan_projects(state: State<'_, AppState>) -> Result<Vec<crate::projects::Project>, CamppError> {
+    let registered = tokio::task::spawn_blocking(|| {
+        let projects_dir = AppSettings::load().projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+        crate::projects::import::scan(&crate::projects::ProjectRegistry::open()?, &projects_dir)
+    }).await??;
+    if !registered.is_empty() {
+        reload_project_sites(&state).await;
+    }
+    Ok(registered)
+}
+
+/// Unregister a project, with `delete_files` also deleting its folder
+///
+/// The hosts file entries of its domain and aliases are removed as well, and
+/// the firewall port if it was shared on the LAN.
+#[tauri::command]
+pub async fn delete_project(name: String, delete_files: bool, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let projects_dir = AppSettings::load().projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+        let project = crate::projects::ProjectRegistry::open()?.delete(&projects_dir, &name, delete_files)?;
+        // A leftover entry only points at loopback, it must not undo the delete
+        if let Err(e) = crate::hosts::update_hosts(&[], &project.hostnames()) {
+            tracing::warn!("Failed to remove hosts entries of project '{}': {}", project.name, e);
+        }
+        if let Some(share) = &project.lan_share {
+            crate::projects::share::close_port(share);
+        }
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Serve a project from `docroot`, a folder inside it such as `public` or
+/// `web`; empty serves the project folder itself
+#[tauri::command]
+pub async fn set_project_docroot(name: String, docroot: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let docroot = docroot.trim().trim_matches(['/', '\\']).to_string();
+    crate::projects::validate_docroot(&docroot).map_err(CamppError::invalid_input)?;
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        let dir = std::path::Path::new(&project.path).join(&docroot);
+        if !dir.is_dir() {
+            return Err(format!("Folder {} does not exist", dir.display()));
+        }
+        project.docroot = docroot;
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Add a PHP binary projects can be pinned to, e.g. a system `php-fpm8.1`
+///
+/// Its version is read from the binary; one already added for the same
+/// version is replaced. The listener starts right away when PHP is running.
+#[tauri::command]
+pub async fn add_php_version(binary: String, state: State<'_, AppState>) -> Result<crate::process::php_pool::PhpBackend, CamppError> {
+    let binary = std::path::PathBuf::from(binary.trim());
+    if !binary.is_absolute() || !binary.is_file() {
+        return Err(CamppError::invalid_input(format!("Not a PHP binary: {}", binary.display())));
+    }
+    let backend = tokio::task::spawn_blocking(move || {
+        let output = crate::runtime::validate::run_binary("PHP", &binary, &["-v"])?;
+        let version = crate::runtime::validate::parse_version(&output)
+            .ok_or_else(|| format!("{} did not report a PHP version", binary.display()))?;
+        let backend = crate::process::php_pool::PhpBackend {
+            version: crate::process::php_pool::major_minor(&version),
+            binary,
+        };
+        let mut settings = AppSettings::load();
+        match settings.php_versions.iter_mut().find(|b| b.version == backend.version) {
+            Some(existing) => *existing = backend.clone(),
+            None => settings.php_versions.push(backend.clone()),
+        }
+        settings.save()?;
+        Ok::<_, String>(backend)
+    }).await??;
+    restart_php_pool(&state).await?;
+    Ok(backend)
+}
 
+/// Remove an extra PHP version, projects pinned to it fall back to the main PHP
+#[tauri::command]
+pub async fn remove_php_version(version: String, state: State<'_, AppState>) -> Result<(), CamppError> {
     tokio::task::spawn_blocking(move || {
+        let mut settings = AppSettings::load();
+        let before = settings.php_versions.len();
+        settings.php_versions.retain(|b| b.version != version);
+        if settings.php_versions.len() == before {
+            return Err(format!("PHP {} was not added", version));
+        }
+        settings.save()
+    }).await??;
+    restart_php_pool(&state).await
+}
+
+/// Pin a project to an extra PHP version, None goes back to the main PHP
+#[tauri::command]
+pub async fn set_project_php_version(name: String, version: Option<String>, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let version = version.map(|v| crate::process::php_pool::major_minor(&v));
+        if let Some(ref version) = version {
+            let settings = AppSettings::load();
+            if crate::process::php_pool::backend_port(&settings, settings.php_port, version).is_none() {
+                return Err(format!("PHP {} has not been added, add its binary first", version));
+            }
+        }
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.php_version = version;
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Restart the extra PHP listeners and route the sites to them
+async fn restart_php_pool(state: &State<'_, AppState>) -> Result<(), CamppError> {
+    let pm = state.process_manager.clone();
+    let failures = tokio::task::spawn_blocking(move || {
         let mut manager = pm.lock()
             .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        Ok::<_, String>(manager.restart_php_pool())
+    }).await??;
+    reload_project_sites(state).await;
+    match failures.first() {
+        Some((version, e)) => Err(format!("PHP {}: {}", version, e).into()),
+        None => Ok(()),
+    }
+}
 
-        // Initialize if needed - propagate error if this fails
+/// Serve a project as a static site without PHP, with `spa_fallback`
+/// answering unknown paths with `index.html`
+///
+/// Turning it off goes back to the detected framework.
+#[tauri::command]
+pub async fn set_project_static(name: String, static_site: bool, spa_fallback: bool, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.framework = if static_site {
+            crate::projects::Framework::Static
+        } else {
+            match crate::projects::detect::detect(std::path::Path::new(&project.path)).framework {
+                crate::projects::Framework::Static => crate::projects::Framework::Php,
+                framework => framework,
+            }
+        };
+        project.spa_fallback = static_site && spa_fallback;
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Serve a project over HTTPS on its `.localhost` host, domain and aliases
+///
+/// The certificates come from the local CA, which has to be trusted first so
+/// browsers accept them.
+#[tauri::command]
+pub async fn set_project_https(name: String, enabled: bool, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        if enabled {
+            let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+            let ca = crate::ca::status(&config_dir);
+            if !ca.created {
+                return Err("The local CA has not been created yet, create and trust it before enabling HTTPS".to_string());
+            }
+            if !ca.trusted {
+                return Err("The local CA is not trusted yet, trust it before enabling HTTPS".to_string());
+            }
+        }
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.https = enabled;
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Redirect plain HTTP to HTTPS for a project, or None to follow the
+/// `https_redirect` setting
+#[tauri::command]
+pub async fn set_project_https_redirect(name: String, enabled: Option<bool>, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.https_redirect = enabled;
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Share a project on the LAN until it is unshared or CAMPP exits, e.g. to
+/// try it on a phone; returns the URL to open on the other device
+///
+/// Opens the port in the firewall where needed, which may prompt for
+/// administrator rights.
+#[tauri::command]
+pub async fn share_on_lan(name: String, state: State<'_, AppState>) -> Result<String, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        crate::projects::share::share(&registry, &name)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project.lan_share.map(|share| share.url()).unwrap_or_default())
+}
+
+/// Stop sharing a project on the LAN and close its firewall port
+#[tauri::command]
+pub async fn unshare_from_lan(name: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        crate::projects::share::unshare(&registry, &name)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// QR code of the URL a phone can open a project on
+///
+/// The public URL while the tunnel runs for the project, otherwise the LAN
+/// share URL.
+#[tauri::command]
+pub async fn get_share_qr(
+    project: String,
+    format: Option<crate::projects::share::QrFormat>,
+    state: State<'_, AppState>,
+) -> Result<crate::projects::share::ShareQr, CamppError> {
+    let lan_share = crate::projects::ProjectRegistry::open()?.get(&project)?.lan_share;
+    let tunnel = get_tunnel_status(state).await?;
+    let url = match tunnel.url {
+        Some(url) if tunnel.project.as_deref() == Some(project.as_str()) => url,
+        _ => lan_share.map(|share| share.url()).ok_or_else(|| CamppError::invalid_input(format!(
+            "Project '{}' is neither shared on the LAN nor tunnelled", project
+        )))?,
+    };
+    Ok(crate::projects::share::qr_code(&url, format.unwrap_or_default())?)
+}
+
+/// Start a public tunnel to a project, or to the localhost site without one
+///
+/// Restarts the tunnel when it runs for another project. Returns once the
+/// client has started; the public URL follows as a "tunnel-url" event when
+/// the tunnel has connected.
+#[tauri::command]
+pub async fn start_tunnel(
+    project: Option<String>,
+    provider: Option<crate::process::tunnel::TunnelProvider>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<crate::process::tunnel::TunnelStatus, CamppError> {
+    use crate::process::tunnel::{self, TunnelStatus, TunnelUrl};
+
+    if let Some(ref name) = project {
+        crate::projects::ProjectRegistry::open()?.get(name)?;
+    }
+    let mut settings = AppSettings::load();
+    settings.tunnel.project = project.clone();
+    if let Some(provider) = provider {
+        settings.tunnel.provider = provider;
+    }
+    // Never started with the other services, enabling it only allows starting it
+    if !settings.is_enabled(ServiceType::Tunnel) {
+        settings.enabled_services.push(ServiceType::Tunnel);
+    }
+    settings.save()?;
+
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
         manager.initialize()?;
+        manager.stop(ServiceType::Tunnel)?;
+        let result = manager.start(ServiceType::Tunnel);
+        manager.update_health();
+        result.map_err(|e| start_error(&manager, ServiceType::Tunnel, e))
+    }).await??;
+
+    let (provider, port) = (settings.tunnel.provider, settings.tunnel.port);
+    tauri::async_runtime::spawn(async move {
+        match tunnel::wait_for_public_url(provider, port, tunnel::PUBLIC_URL_TIMEOUT).await {
+            Ok(url) => {
+                tracing::info!("Tunnel is up at {}", url);
+                let _ = app.emit(tunnel::TUNNEL_URL_EVENT, TunnelUrl { project, url });
+            }
+            Err(e) => tracing::warn!("{}", e),
+        }
+    });
 
-        // Start the service
-        let result = manager.start(service);
+    let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+    Ok(TunnelStatus {
+        provider,
+        project: settings.tunnel.project,
+        running: true,
+        url: None,
+        has_ngrok_authtoken: tunnel::read_authtoken(&config_dir).is_some(),
+    })
+}
 
-        // Update health and return statuses regardless of start result
+/// Stop the public tunnel
+#[tauri::command]
+pub async fn stop_tunnel(state: State<'_, AppState>) -> Result<crate::process::tunnel::TunnelStatus, CamppError> {
+    state.process_manager.lock()?.stop(ServiceType::Tunnel)?;
+    get_tunnel_status(state).await
+}
+
+/// State of the public tunnel, with its URL once it has connected
+#[tauri::command]
+pub async fn get_tunnel_status(state: State<'_, AppState>) -> Result<crate::process::tunnel::TunnelStatus, CamppError> {
+    let (running, settings) = {
+        let manager = state.process_manager.lock()?;
+        (manager.status(ServiceType::Tunnel).is_running(), manager.get_settings().tunnel.clone())
+    };
+    let url = match running {
+        true => crate::process::tunnel::public_url(settings.provider, settings.port).await,
+        false => None,
+    };
+    let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+    Ok(crate::process::tunnel::TunnelStatus {
+        provider: settings.provider,
+        project: settings.project,
+        running,
+        url,
+        has_ngrok_authtoken: crate::process::tunnel::read_authtoken(&config_dir).is_some(),
+    })
+}
+
+/// Save the ngrok authtoken, an empty one removes it
+///
+/// It is kept in a file of its own, not in settings.
+#[tauri::command]
+pub async fn set_ngrok_authtoken(token: String, state: State<'_, AppState>) -> Result<crate::process::tunnel::TunnelStatus, CamppError> {
+    let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+    crate::process::tunnel::save_authtoken(&config_dir, &token)?;
+    get_tunnel_status(state).await
+}
+
+/// Turn an optional service on or off, e.g. Mailpit or MinIO
+///
+/// Starts or stops it right away. Mailpit and Memcached are also named in
+/// php.ini, so a running PHP is restarted to send `mail()` to Mailpit or load
+/// the memcached extension, or no longer do. MinIO's credentials and the
+/// SFTP/FTP account are generated when they first start.
+#[tauri::command]
+pub async fn set_service_enabled(service: ServiceType, enabled: bool, state: State<'_, AppState>) -> Result<ServiceMap, CamppError> {
+    if !service.is_optional() {
+        return Err(CamppError::invalid_input(format!("{} cannot be turned off", service.display_name())));
+    }
+    let mut settings = AppSettings::load();
+    settings.enabled_services.retain(|s| *s != service);
+    if enabled {
+        settings.enabled_services.push(service);
+    }
+    settings.save()?;
+
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        manager.initialize()?;
+        let result = if enabled {
+            manager.start(service).map_err(|e| start_error(&manager, service, e))
+        } else {
+            manager.stop(service).map_err(CamppError::from)
+        };
+        let in_php_ini = matches!(service, ServiceType::Mailpit | ServiceType::Memcached);
+        if result.is_ok() && in_php_ini && manager.status(ServiceType::PhpFpm).is_running() {
+            manager.restart(ServiceType::PhpFpm)?;
+        }
         manager.update_health();
         let statuses = manager.get_all_statuses();
+        result.map(|_| statuses)
+    }).await?
+}
 
-        result?;
-        Ok(statuses)
-    }).await.map_err(|e| format!("Task error: {}", e))?
+/// Whether Mailpit is enabled and running, with the address of its web UI
+#[tauri::command]
+pub async fn get_mailpit_status(state: State<'_, AppState>) -> Result<crate::process::mailpit::MailpitStatus, CamppError> {
+    let settings = AppSettings::load();
+    Ok(crate::process::mailpit::MailpitStatus {
+        enabled: settings.is_enabled(ServiceType::Mailpit),
+        running: state.process_manager.lock()?.status(ServiceType::Mailpit).is_running(),
+        smtp_port: settings.mailpit.smtp_port,
+        ui_url: settings.mailpit.ui_url(),
+    })
 }
 
-/// Stop a service
+/// Whether Memcached is enabled and running, and whether its binary and the
+/// PHP extension were found
 #[tauri::command]
-pub async fn stop_service(
-    service: ServiceType,
-    state: State<'_, AppState>,
-) -> Result<ServiceMap, String> {
-    let pm = state.process_manager.clone();
+pub async fn get_memcached_status(state: State<'_, AppState>) -> Result<crate::process::memcached::MemcachedStatus, CamppError> {
+    let settings = AppSettings::load();
+    let (running, paths) = {
+        let manager = state.process_manager.lock()?;
+        (manager.status(ServiceType::Memcached).is_running(), manager.get_runtime_paths())
+    };
+    let paths = match paths {
+        Some(paths) => Some(paths),
+        None => tokio::task::spawn_blocking(|| crate::runtime::locator::locate_runtime_binaries().ok()).await?,
+    };
+    let binary = crate::process::memcached::binary(&settings.memcached);
+    let php_extension = paths.and_then(|paths| crate::process::memcached::php_extension(&settings.memcached, &paths.php_ext_dir));
+    Ok(crate::process::memcached::MemcachedStatus {
+        enabled: settings.is_enabled(ServiceType::Memcached),
+        running,
+        port: settings.memcached.port,
+        binary_found: binary.is_file(),
+        binary: binary.to_string_lossy().to_string(),
+        php_extension: php_extension.map(|path| path.to_string_lossy().to_string()),
+    })
+}
+
+/// Whether MinIO is enabled and running, with its endpoint and credentials
+#[tauri::command]
+pub async fn get_minio_status(state: State<'_, AppState>) -> Result<crate::process::minio::MinioStatus, CamppError> {
+    let settings = AppSettings::load();
+    let minio_dir = crate::process::minio::minio_dir(&crate::runtime::locator::get_app_data_paths()?.base_dir);
+    Ok(crate::process::minio::MinioStatus {
+        enabled: settings.is_enabled(ServiceType::Minio),
+        running: state.process_manager.lock()?.status(ServiceType::Minio).is_running(),
+        endpoint: settings.minio.endpoint(),
+        console_url: settings.minio.console_url(),
+        credentials: crate::process::minio::read_credentials(&minio_dir),
+    })
+}
+
+/// Whether the SFTP/FTP server is enabled and running, with its account
+#[tauri::command]
+pub async fn get_ftp_status(state: State<'_, AppState>) -> Result<crate::process::ftp::FtpStatus, CamppError> {
+    let settings = AppSettings::load();
+    let app_paths = crate::runtime::locator::get_app_data_paths()?;
+    let sftpgo_dir = crate::process::ftp::sftpgo_dir(&app_paths.config_dir);
+    Ok(crate::process::ftp::FtpStatus {
+        enabled: settings.is_enabled(ServiceType::Ftp),
+        running: state.process_manager.lock()?.status(ServiceType::Ftp).is_running(),
+        sftp_port: settings.ftp.sftp_port,
+        ftp_port: settings.ftp.ftp_port,
+        root: settings.projects_dir(&app_paths.projects_dir).to_string_lossy().to_string(),
+        credentials: crate::process::ftp::read_credentials(&sftpgo_dir),
+    })
+}
 
+/// Give the SFTP/FTP account a new password, restarting a running server
+#[tauri::command]
+pub async fn reset_ftp_password(state: State<'_, AppState>) -> Result<crate::process::ftp::FtpStatus, CamppError> {
+    let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+    crate::process::ftp::reset_credentials(&crate::process::ftp::sftpgo_dir(&config_dir))?;
+
+    let pm = state.process_manager.clone();
     tokio::task::spawn_blocking(move || {
         let mut manager = pm.lock()
             .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        if manager.status(ServiceType::Ftp).is_running() {
+            manager.restart(ServiceType::Ftp)?;
+        }
+        Ok::<_, CamppError>(())
+    }).await??;
 
-        // Stop the service
-        manager.stop(service)?;
+    get_ftp_status(state).await
+}
 
-        // Update health and return statuses
-        manager.update_health();
-        Ok(manager.get_all_statuses())
-    }).await.map_err(|e| format!("Task error: {}", e))?
+/// Scripts in a project's package.json
+#[tauri::command]
+pub async fn get_package_scripts(project: String) -> Result<Vec<String>, CamppError> {
+    let project = crate::projects::ProjectRegistry::open()?.get(&project)?;
+    Ok(crate::process::npm::package_scripts(std::path::Path::new(&project.path))?)
+}
+
+/// Run `npm run <script>` in a project with the bundled Node.js
+///
+/// The script keeps running until it is stopped or CAMPP exits. With `port`,
+/// the dev server the script starts there serves the project's site: Caddy
+/// proxies to it once it listens, and serves the project with PHP again
+/// when the script ends.
+#[tauri::command]
+pub async fn run_npm_script(
+    project: String,
+    script: String,
+    port: Option<u16>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<crate::process::npm::NpmScript, CamppError> {
+    let pm = state.process_manager.clone();
+    let (project, started) = tokio::task::spawn_blocking(move || {
+        let project = crate::projects::ProjectRegistry::open()?.get(&project)?;
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        if manager.get_runtime_paths().is_none() {
+            manager.initialize()?;
+        }
+        let paths = manager.get_runtime_paths().ok_or("Runtime binaries not located")?;
+        let started = manager.npm_scripts().start(&paths, &project, &script, port)?;
+        Ok::<_, CamppError>((project, started))
+    }).await??;
+
+    if let Some(port) = port {
+        let (name, pid) = (project.name, started.pid);
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<AppState>();
+            let running = || state.process_manager.lock().is_ok_and(|mut m| m.npm_scripts().is_running(pid));
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+            while running() && !crate::process::npm::is_listening(port) {
+                if std::time::Instant::now() >= deadline {
+                    tracing::warn!("Nothing listens on port {} yet, the site is not proxied to the dev server", port);
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+            if running() {
+                set_dev_server_port(&state, &name, Some(port)).await;
+            }
+            while running() {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+            set_dev_server_port(&state, &name, None).await;
+        });
+    }
+    Ok(started)
+}
+
+/// Stop an npm script started with `run_npm_script`
+#[tauri::command]
+pub async fn stop_npm_script(project: String, script: String, state: State<'_, AppState>) -> Result<crate::process::npm::NpmScript, CamppError> {
+    let stopped = state.process_manager.lock()?.npm_scripts().stop(&project, &script)?;
+    if stopped.port.is_some() {
+        set_dev_server_port(&state, &project, None).await;
+    }
+    Ok(stopped)
+}
+
+/// Point a project's site at a dev server, or back at PHP with None
+async fn set_dev_server_port(state: &State<'_, AppState>, project: &str, port: Option<u16>) {
+    let changed = crate::projects::ProjectRegistry::open()
+        .and_then(|registry| crate::process::npm::set_dev_server_port(&registry, project, port));
+    match changed {
+        Ok(true) => reload_project_sites(state).await,
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Failed to update the dev server of project '{}': {}", project, e),
+    }
+}
+
+/// npm scripts that are still running
+#[tauri::command]
+pub async fn list_npm_scripts(state: State<'_, AppState>) -> Result<Vec<crate::process::npm::NpmScript>, CamppError> {
+    Ok(state.process_manager.lock()?.npm_scripts().list())
+}
+
+/// Read a page of the log of a project's npm script
+#[tauri::command]
+pub async fn get_npm_script_logs(
+    project: String,
+    script: String,
+    query: Option<crate::process::logs::LogQuery>,
+) -> Result<crate::process::logs::LogPage, CamppError> {
+    let logs_dir = crate::runtime::locator::get_app_data_paths()?.logs_dir;
+    let path = logs_dir.join(crate::process::npm::log_file_name(&project, &script));
+    let query = query.unwrap_or_default();
+    tokio::task::spawn_blocking(move || crate::process::logs::read_page(&path, &query))
+        .await?
+        .map_err(Into::into)
+}
+
+/// Set extra Caddy directives for a project's site block, None removes them
+///
+/// The Caddyfile with the snippet is checked with `caddy validate` before
+/// it is saved, so a typo is reported here instead of breaking Caddy.
+#[tauri::command]
+pub async fn set_project_caddy_snippet(name: String, snippet: Option<String>, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let snippet = snippet.filter(|s| !s.trim().is_empty());
+    if let Some(ref snippet) = snippet {
+        crate::config::generator::check_snippet(snippet).map_err(CamppError::invalid_input)?;
+    }
+    let paths = state.process_manager.lock()?.get_runtime_paths();
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.caddy_snippet = snippet;
+        if project.caddy_snippet.is_some() {
+            validate_project_site(paths, &registry, &project)?;
+        }
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Allow or stop using the `Caddyfile.project` in a project's folder
+///
+/// The file comes with the project's code, so it is ignored until allowed.
+/// Allowing it checks it like a snippet and with `caddy validate`, and
+/// covers its current content only: once it changes it has to be allowed
+/// again.
+#[tauri::command]
+pub async fn set_project_caddy_file_trusted(name: String, trusted: bool, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let paths = state.process_manager.lock()?.get_runtime_paths();
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.trusted_caddy_file = None;
+        if trusted {
+            let content = project.caddy_file()
+                .ok_or_else(|| format!("Project '{}' has no {}", name, crate::projects::SNIPPET_FILE))?;
+            crate::config::generator::check_snippet(&content)?;
+            project.trusted_caddy_file = Some(crate::projects::snippet_hash(&content));
+            validate_project_site(paths, &registry, &project)?;
+        }
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Run `caddy validate` on the Caddyfile with `project` as it would be saved
+///
+/// Skipped when Caddy is not installed yet.
+fn validate_project_site(
+    paths: Option<crate::runtime::locator::RuntimePaths>,
+    registry: &crate::projects::ProjectRegistry,
+    project: &crate::projects::Project,
+) -> Result<(), String> {
+    let Some(paths) = paths.or_else(|| crate::runtime::locator::locate_runtime_binaries().ok()) else {
+        return Ok(());
+    };
+    let settings = AppSettings::load();
+    let mut projects = registry.list()?;
+    projects.retain(|p| p.name != project.name);
+    projects.push(project.clone());
+    let content = crate::config::generator::caddyfile_content(&paths, settings.web_port, settings.php_port, &settings, &projects)?;
+    let candidate = std::env::temp_dir().join(format!("campp-validate-{}.Caddyfile", std::process::id()));
+    fs::write(&candidate, content)
+        .map_err(|e| format!("Failed to write {}: {}", candidate.display(), e))?;
+    let result = crate::process::config_watch::validate_caddyfile(&paths.caddy, &candidate);
+    let _ = fs::remove_file(&candidate);
+    result.map_err(|e| format!("Caddy rejected the directives: {}", e))
+}
+
+/// Replace the environment variables passed to a project's PHP requests
+///
+/// They take precedence over the `env` of the project's `campp.json`.
+#[tauri::command]
+pub async fn set_project_env(
+    name: String,
+    env: std::collections::BTreeMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<crate::projects::Project, CamppError> {
+    for (key, value) in &env {
+        crate::projects::manifest::validate_setting(key, value).map_err(CamppError::invalid_input)?;
+    }
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        project.env = env;
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Serve a project on `<name>.<tld>`, adding the hosts file entry unless
+/// the wildcard DNS resolves it
+///
+/// Writing the hosts file may prompt for administrator rights.
+#[tauri::command]
+pub async fn register_project_domain(name: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        let domain = crate::projects::project_domain(&project.name, &AppSettings::load().domain_tld);
+        if project.domain.as_ref() != Some(&domain) {
+            registry.check_hostname(&domain)?;
+        }
+        if !resolves_without_hosts_entry(&domain) {
+            crate::hosts::add_host(&domain)?;
+        }
+        project.domain = Some(domain);
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Stop serving a project on its domain and remove the hosts file entry
+#[tauri::command]
+pub async fn unregister_project_domain(name: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        if let Some(domain) = project.domain.take() {
+            crate::hosts::remove_host(&domain)?;
+            registry.insert(project.clone())?;
+        }
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// State of the wildcard DNS for project domains
+#[tauri::command]
+pub async fn get_dns_status(state: State<'_, AppState>) -> Result<crate::process::dns::DnsStatus, CamppError> {
+    let running_port = state.process_manager.lock()?.dns_port();
+    Ok(tokio::task::spawn_blocking(move || dns_status(running_port)).await?)
+}
+
+/// Turn the wildcard DNS on or off, starting or stopping it right away
+#[tauri::command]
+pub async fn set_dns_enabled(enabled: bool, state: State<'_, AppState>) -> Result<crate::process::dns::DnsStatus, CamppError> {
+    let mut settings = AppSettings::load();
+    settings.dns.enabled = enabled;
+    settings.save()?;
+    let running_port = {
+        let mut manager = state.process_manager.lock()?;
+        if enabled {
+            manager.start_dns()?;
+        } else {
+            manager.stop_dns();
+        }
+        manager.dns_port()
+    };
+    Ok(tokio::task::spawn_blocking(move || dns_status(running_port)).await?)
+}
+
+/// Send the OS resolver's queries for the project TLD to the wildcard DNS
+///
+/// Prompts for administrator rights.
+#[tauri::command]
+pub async fn configure_dns_resolver(state: State<'_, AppState>) -> Result<crate::process::dns::DnsStatus, CamppError> {
+    let running_port = state.process_manager.lock()?.dns_port();
+    Ok(tokio::task::spawn_blocking(move || {
+        let settings = AppSettings::load();
+        crate::process::dns::configure_resolver(&settings.domain_tld, settings.dns.port)?;
+        Ok::<_, String>(dns_status(running_port))
+    }).await??)
+}
+
+/// Undo `configure_dns_resolver`
+#[tauri::command]
+pub async fn remove_dns_resolver(state: State<'_, AppState>) -> Result<crate::process::dns::DnsStatus, CamppError> {
+    let running_port = state.process_manager.lock()?.dns_port();
+    Ok(tokio::task::spawn_blocking(move || {
+        crate::process::dns::remove_resolver(&AppSettings::load().domain_tld)?;
+        Ok::<_, String>(dns_status(running_port))
+    }).await??)
+}
+
+/// Change the TLD of project domains, e.g. to `localhost` where `.test` is
+/// reserved
+///
+/// Domains and aliases under the old TLD move to the new one with a single
+/// hosts file write, and a configured resolver moves along; both may prompt
+/// for administrator rights. Nothing moves when a new domain is already
+/// another project's. `save_settings` refuses TLD changes, they go through here.
+#[tauri::command]
+pub async fn set_domain_tld(tld: String, state: State<'_, AppState>) -> Result<crate::process::dns::DnsStatus, CamppError> {
+    let tld = crate::projects::validate_tld(&tld).map_err(CamppError::invalid_input)?;
+    let changed = tokio::task::spawn_blocking(move || {
+        let mut settings = AppSettings::load();
+        let old = std::mem::replace(&mut settings.domain_tld, tld.clone());
+        if old == tld {
+            return Ok::<_, String>(false);
+        }
+
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let moved: Vec<(crate::projects::Project, crate::projects::Project)> = registry.list()?
+            .into_iter()
+            .map(|project| {
+                let moved = project.moved_to_tld(&old, &tld);
+                (project, moved)
+            })
+            .filter(|(project, moved)| project != moved)
+            .collect();
+        for (project, moved) in &moved {
+            for host in moved.hostnames().into_iter().filter(|host| !project.hostnames().contains(host)) {
+                registry.check_hostname(&host)?;
+            }
+        }
+        // Resolve the new domains before the old ones are dropped
+        let resolver_moves = crate::process::dns::resolver_configured(&old);
+        if resolver_moves {
+            crate::process::dns::configure_resolver(&tld, settings.dns.port)?;
+        }
+        let remove: Vec<String> = moved.iter().flat_map(|(project, _)| project.hostnames()).collect();
+        let add: Vec<String> = match resolver_moves && settings.dns.enabled {
+            true => Vec::new(),
+            false => moved.iter()
+                .flat_map(|(_, moved)| moved.hostnames())
+                .filter(|host| !host.ends_with(".localhost"))
+                .collect(),
+        };
+        crate::hosts::update_hosts(&add, &remove)?;
+        for (_, project) in moved {
+            registry.insert(project)?;
+        }
+        settings.save()?;
+
+        // A leftover resolver for the old TLD is harmless
+        if resolver_moves {
+            if let Err(e) = crate::process::dns::remove_resolver(&old) {
+                tracing::warn!("Failed to remove the resolver for .{}: {}", old, e);
+            }
+        }
+        Ok(true)
+    }).await??;
+
+    let running_port = {
+        let mut manager = state.process_manager.lock()?;
+        if changed && manager.dns_port().is_some() {
+            manager.start_dns()?;
+        }
+        manager.dns_port()
+    };
+    if changed {
+        reload_project_sites(&state).await;
+    }
+    Ok(tokio::task::spawn_blocking(move || dns_status(running_port)).await?)
+}
+
+/// Whether `host` resolves to loopback without a hosts file entry: browsers
+/// resolve `*.localhost` on their own, and the OS sends the project TLD to
+/// the wildcard DNS once its resolver is configured
+fn resolves_without_hosts_entry(host: &str) -> bool {
+    let settings = AppSettings::load();
+    let tld = &settings.domain_tld;
+    host.ends_with(".localhost")
+        || (host.ends_with(&format!(".{}", tld))
+            && settings.dns.enabled
+            && crate::process::dns::resolver_configured(tld))
+}
+
+fn dns_status(running_port: Option<u16>) -> crate::process::dns::DnsStatus {
+    let settings = AppSettings::load();
+    crate::process::dns::DnsStatus {
+        enabled: settings.dns.enabled,
+        running_port,
+        port: settings.dns.port,
+        resolver_configured: crate::process::dns::resolver_configured(&settings.domain_tld),
+        tld: settings.domain_tld,
+    }
+}
+
+/// State of the local CA issuing HTTPS certificates for project sites
+#[tauri::command]
+pub async fn get_ca_status() -> Result<crate::ca::CaStatus, CamppError> {
+    Ok(tokio::task::spawn_blocking(|| {
+        let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+        Ok::<_, String>(crate::ca::status(&config_dir))
+    }).await??)
+}
+
+/// Create the local CA with Caddy, if it does not exist yet
+#[tauri::command]
+pub async fn create_local_ca(state: State<'_, AppState>) -> Result<crate::ca::CaStatus, CamppError> {
+    let paths = state.process_manager.lock()?.get_runtime_paths();
+    Ok(tokio::task::spawn_blocking(move || {
+        let paths = match paths {
+            Some(paths) => paths,
+            None => crate::runtime::locator::locate_runtime_binaries()?,
+        };
+        crate::ca::create(&paths.caddy, &paths.config_dir)?;
+        Ok::<_, String>(crate::ca::status(&paths.config_dir))
+    }).await??)
+}
+
+/// Add the local CA to the system trust store, creating it first if needed
+///
+/// Prompts for administrator rights; browsers pick the change up after a restart.
+#[tauri::command]
+pub async fn trust_local_ca(state: State<'_, AppState>) -> Result<crate::ca::CaStatus, CamppError> {
+    let paths = state.process_manager.lock()?.get_runtime_paths();
+    Ok(tokio::task::spawn_blocking(move || {
+        let paths = match paths {
+            Some(paths) => paths,
+            None => crate::runtime::locator::locate_runtime_binaries()?,
+        };
+        crate::ca::create(&paths.caddy, &paths.config_dir)?;
+        crate::ca::trust(&paths.config_dir)?;
+        Ok::<_, String>(crate::ca::status(&paths.config_dir))
+    }).await??)
+}
+
+/// Remove the local CA from the system trust store; the CA itself is kept
+#[tauri::command]
+pub async fn untrust_local_ca() -> Result<crate::ca::CaStatus, CamppError> {
+    Ok(tokio::task::spawn_blocking(|| {
+        let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+        crate::ca::untrust(&config_dir)?;
+        Ok::<_, String>(crate::ca::status(&config_dir))
+    }).await??)
+}
+
+/// Certificates Caddy issued for local sites, to debug "connection not
+/// private" errors: the names they cover, their expiry and issuer
+#[tauri::command]
+pub async fn get_certificates() -> Result<Vec<crate::ca::SiteCertificate>, CamppError> {
+    Ok(tokio::task::spawn_blocking(|| {
+        let config_dir = crate::runtime::locator::get_app_data_paths()?.config_dir;
+        Ok::<_, String>(crate::ca::site_certificates(&config_dir))
+    }).await??)
+}
+
+/// Entries CAMPP manages in the hosts file
+#[tauri::command]
+pub async fn list_hosts_entries() -> Result<Vec<crate::hosts::HostsEntry>, CamppError> {
+    Ok(tokio::task::spawn_blocking(crate::hosts::entries).await??)
+}
+
+/// Rewrite the CAMPP block of the hosts file to the domains and aliases of
+/// the registered projects, dropping entries left behind by deleted ones
+///
+/// Prompts for administrator rights only when the block changes.
+#[tauri::command]
+pub async fn sync_hosts_file() -> Result<Vec<crate::hosts::HostsEntry>, CamppError> {
+    Ok(tokio::task::spawn_blocking(|| {
+        let hosts: Vec<String> = crate::projects::registered().iter().flat_map(|p| p.hostnames()).collect();
+        crate::hosts::sync_hosts(&hosts)?;
+        crate::hosts::entries()
+    }).await??)
+}
+
+/// Serve a project on one more host name, e.g. `api.shop.test`, adding the
+/// hosts file entry unless it resolves without one
+///
+/// Writing the hosts file may prompt for administrator rights.
+#[tauri::command]
+pub async fn add_project_alias(name: String, alias: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let alias = crate::projects::validate_alias(&alias).map_err(CamppError::invalid_input)?;
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        registry.check_hostname(&alias)?;
+        if !resolves_without_hosts_entry(&alias) {
+            crate::hosts::add_host(&alias)?;
+        }
+        project.aliases.push(alias);
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Stop serving a project on `alias` and remove its hosts file entry
+#[tauri::command]
+pub async fn remove_project_alias(name: String, alias: String, state: State<'_, AppState>) -> Result<crate::projects::Project, CamppError> {
+    let project = tokio::task::spawn_blocking(move || {
+        let registry = crate::projects::ProjectRegistry::open()?;
+        let mut project = registry.get(&name)?;
+        let alias = alias.trim().to_ascii_lowercase();
+        let index = project.aliases.iter().position(|a| *a == alias)
+            .ok_or_else(|| format!("Project '{}' has no alias {}", name, alias))?;
+        crate::hosts::remove_host(&alias)?;
+        project.aliases.remove(index);
+        registry.insert(project.clone())?;
+        Ok::<_, String>(project)
+    }).await??;
+    reload_project_sites(&state).await;
+    Ok(project)
+}
+
+/// Apply the project site blocks to Caddy, if it is running
+async fn reload_project_sites(state: &State<'_, AppState>) {
+    if caddy_ports_if_running(state).is_ok() {
+        if let Err(e) = reload_caddy_config(state).await {
+            tracing::warn!("Failed to reload Caddy for project sites: {}", e);
+        }
+    }
+}
+
+/// Create the welcome project after the first runtime install
+///
+/// A failure only warns, the runtime itself is installed.
+async fn create_welcome_project(state: &State<'_, AppState>) {
+    let result = tokio::task::spawn_blocking(|| {
+        let mut settings = AppSettings::load();
+        let projects_dir = settings.projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+        let project = crate::projects::welcome::ensure(&crate::projects::ProjectRegistry::open()?, &projects_dir, &mut settings)?;
+        settings.save()?;
+        Ok::<_, String>(project)
+    }).await;
+    match result {
+        Ok(Ok(Some(project))) => {
+            tracing::info!("Created the welcome project in {}", project.path);
+            reload_project_sites(state).await;
+        }
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => tracing::warn!("Failed to create the welcome project: {}", e),
+        Err(e) => tracing::warn!("Failed to create the welcome project: {}", e),
+    }
+}
+
+/// Open the directory of generated service configs in the file manager
+#[tauri::command]
+pub async fn open_config_folder() -> Result<(), CamppError> {
+    reveal_app_dir(&crate::runtime::locator::get_app_data_paths()?.config_dir)
+}
+
+/// Open the service logs directory in the file manager
+#[tauri::command]
+pub async fn open_logs_folder() -> Result<(), CamppError> {
+    reveal_app_dir(&crate::runtime::locator::get_app_data_paths()?.logs_dir)
+}
+
+/// Open the data directory of a database service in the file manager
+#[tauri::command]
+pub async fn open_database_folder(service: ServiceType) -> Result<(), CamppError> {
+    let paths = crate::runtime::locator::get_app_data_paths()?;
+    match service {
+        ServiceType::MySQL => reveal_app_dir(&paths.mysql_data_dir),
+        ServiceType::PostgreSQL => reveal_app_dir(&paths.pgsql_data_dir),
+        _ => Err(CamppError::invalid_input(format!("{} has no data directory", service.display_name()))),
+    }
+}
+
+/// Resolved config, log, data and project paths, for "open file" links in the UI
+#[tauri::command]
+pub async fn get_paths(state: State<'_, AppState>) -> Result<crate::runtime::locator::PathsReport, CamppError> {
+    let runtime_paths = state.process_manager.lock()?.get_runtime_paths();
+    let app_paths = crate::runtime::locator::get_app_data_paths()?;
+    let runtime_paths = runtime_paths.or_else(|| crate::runtime::locator::locate_runtime_binaries().ok());
+    Ok(crate::runtime::locator::PathsReport::new(&app_paths, runtime_paths.as_ref(), &AppSettings::load()))
+}
+
+/// Open the user manual in the system's default application using tauri-plugin-opener
+///
+/// This command locates the MANUAL.html resource file and reveals it in the
+/// file manager using tauri-plugin-opener for cross-platform compatibility.
+/// Users can then open it with their preferred browser or HTML viewer.
+#[tauri::command]
+pub async fn open_manual(app: tauri::AppHandle) -> Result<(), CamppError> {
+    use tauri::Manager;
+    use tauri_plugin_opener::reveal_item_in_dir;
+
+    let resource_dir = app
+        .path()
+        .resource_dir()
+        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
+
+    let manual_path = resource_dir.join("MANUAL.html");
+
+    // Ensure the manual exists
+    if !manual_path.exists() {
+        return Err(format!("Manual not found at: {}", manual_path.display()).into());
+    }
+
+    // Use tauri-plugin-opener to reveal the file in the file manager
+    // This is cross-platform and lets the user choose how to open it
+    reveal_item_in_dir(&manual_path)
+        .map_err(|e| format!("Failed to open manual: {}", e))?;
+
+    Ok(())
+}
+
+/// URL of a page served by Caddy, computed from the current settings
+#[tauri::command]
+pub async fn get_site_url(target: crate::config::urls::SiteTarget) -> Result<String, CamppError> {
+    let settings = AppSettings::load();
+    let projects_dir = settings.projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+    crate::config::urls::site_url(&target, &settings, &projects_dir)
+        .map_err(CamppError::invalid_input)
+}
+
+/// Open a page served by Caddy in the default browser and return its URL
+#[tauri::command]
+pub async fn open_in_browser(target: crate::config::urls::SiteTarget) -> Result<String, CamppError> {
+    let url = get_site_url(target).await?;
+    tauri_plugin_opener::open_url(&url, None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", url, e))?;
+    Ok(url)
+}
+
+// Global state for download progress
+static DOWNLOAD_PROGRESS: Mutex<Option<DownloadProgress>> = Mutex::new(None);
+
+/// Emit download progress as `download-progress` and as progress of `op`
+fn download_progress(app: tauri::AppHandle, op: Arc<Operation>) -> ProgressCallback {
+    Box::new(move |progress| {
+        let _ = app.emit("download-progress", &progress);
+        let step = match progress.step {
+            DownloadStep::Downloading => "downloading",
+            DownloadStep::Extracting => "extracting",
+            DownloadStep::Installing => "installing",
+            DownloadStep::Complete => "complete",
+            DownloadStep::Error(_) => "error",
+        };
+        op.progress(step, Some(progress.overall_percent), format!("{} {}", progress.component_display, progress.version));
+
+        // Store latest progress
+        if let Ok(mut p) = DOWNLOAD_PROGRESS.lock() {
+            *p = Some(progress);
+        }
+    })
+}
+
+/// Last progress of every running download, initialization, backup and import
+#[tauri::command]
+pub async fn list_operations() -> Result<Vec<OperationProgress>, CamppError> {
+    Ok(crate::operations::running())
+}
+
+/// Stop a running operation, it reports the `cancelled` step once stopped
+#[tauri::command]
+pub async fn cancel_operation(id: String) -> Result<(), CamppError> {
+    crate::operations::cancel(&id).map_err(CamppError::invalid_input)
+}
+
+/// Start a service
+#[tauri::command]
+pub async fn start_service(
+    service: ServiceType,
+    state: State<'_, AppState>,
+) -> Result<ServiceMap, CamppError> {
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        // Initialize if needed - propagate error if this fails
+        manager.initialize()?;
+
+        // Start the service
+        let result = manager.start(service);
+
+        // Update health and return statuses regardless of start result
+        manager.update_health();
+        let statuses = manager.get_all_statuses();
+
+        result.map_err(|e| start_error(&manager, service, e))?;
+        Ok(statuses)
+    }).await?
+}
+
+/// Classify a failed start: a missing binary, a port taken by another
+/// application, or a process that died during startup with its log excerpt
+fn start_error(manager: &ProcessManager, service: ServiceType, message: String) -> CamppError {
+    // Failures before spawning, e.g. a disabled service, leave the state alone
+    let Some(paths) = manager.get_runtime_paths().filter(|_| manager.status(service) == ServiceState::Error) else {
+        return message.into();
+    };
+
+    let binary = crate::process::health::service_binary(&paths, manager.get_settings(), service);
+    if !binary.exists() {
+        return CamppError::BinaryMissing { path: binary.to_string_lossy().to_string() };
+    }
+
+    if let Some(port) = manager.get_service_port(service) {
+        if !crate::config::is_port_available(port) {
+            let owner = crate::config::port_owner(port).map(|o| o.to_string());
+            return CamppError::PortInUse { port, owner };
+        }
+    }
+
+    CamppError::init_failed(service, message, &paths.logs_dir)
+}
+
+/// Stop a service
+#[tauri::command]
+pub async fn stop_service(
+    service: ServiceType,
+    state: State<'_, AppState>,
+) -> Result<ServiceMap, CamppError> {
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        // Stop the service
+        manager.stop(service)?;
+
+        // Update health and return statuses
+        manager.update_health();
+        Ok(manager.get_all_statuses())
+    }).await?
+}
+
+/// Restart a service, gracefully unless another strategy is requested
+///
+/// `reload_config` re-applies the Caddyfile without a restart and `rolling`
+/// replaces PHP-FPM workers while the master keeps serving.
+#[tauri::command]
+pub async fn restart_service(
+    service: ServiceType,
+    strategy: Option<RestartStrategy>,
+    state: State<'_, AppState>,
+) -> Result<RestartReport, CamppError> {
+    let strategy = strategy.unwrap_or_default();
+
+    if strategy == RestartStrategy::ReloadConfig {
+        if !strategy.supports(service) {
+            return Err(format!("Config reload is not supported for {}", service.display_name()).into());
+        }
+        let begin = std::time::Instant::now();
+        reload_caddy_config(&state).await?;
+        let elapsed = begin.elapsed().as_millis() as u64;
+        return Ok(RestartReport { service, strategy, stop_ms: 0, start_ms: elapsed, total_ms: elapsed });
+    }
+
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        // Initialize if needed
+        manager.initialize()?;
+
+        let result = manager.restart_with(service, strategy);
+        manager.update_health();
+        result.map_err(|e| start_error(&manager, service, e))
+    }).await?
+}
+
+/// Start all enabled services in dependency order
+#[tauri::command]
+pub async fn start_all_services(state: State<'_, AppState>) -> Result<ServiceMap, CamppError> {
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        manager.initialize()?;
+        let failures = manager.start_all();
+
+        manager.update_health();
+        if failures.is_empty() {
+            Ok(manager.get_all_statuses())
+        } else if let [(service, e)] = failures.as_slice() {
+            Err(start_error(&manager, *service, e.clone()))
+        } else {
+            let messages: Vec<String> = failures.iter()
+                .map(|(service, e)| format!("{}: {}", service.display_name(), e))
+                .collect();
+            Err(format!("Some services failed to start:\n{}", messages.join("\n")).into())
+        }
+    }).await?
+}
+
+/// Suspend all running services, Caddy first so no new requests come in
+#[tauri::command]
+pub async fn pause_stack(state: State<'_, AppState>) -> Result<ServiceMap, CamppError> {
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        manager.update_health();
+        let mut failures = Vec::new();
+        for service in ServiceType::start_order().into_iter().rev() {
+            if manager.status(service) != ServiceState::Running {
+                continue;
+            }
+            if let Err(e) = manager.pause(service) {
+                failures.push(format!("{}: {}", service.display_name(), e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(manager.get_all_statuses())
+        } else {
+            Err(format!("Some services could not be paused:\n{}", failures.join("\n")))
+        }
+    }).await?.map_err(Into::into)
+}
+
+/// Resume services suspended with `pause_stack`, backends first
+#[tauri::command]
+pub async fn resume_stack(state: State<'_, AppState>) -> Result<ServiceMap, CamppError> {
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        let mut failures = Vec::new();
+        for service in ServiceType::start_order() {
+            if let Err(e) = manager.resume(service) {
+                failures.push(format!("{}: {}", service.display_name(), e));
+            }
+        }
+        manager.update_health();
+
+        if failures.is_empty() {
+            Ok(manager.get_all_statuses())
+        } else {
+            Err(format!("Some services could not be resumed:\n{}", failures.join("\n")))
+        }
+    }).await?.map_err(Into::into)
+}
+
+/// Get the status of all services
+#[tauri::command]
+pub async fn get_all_statuses(
+    state: State<'_, AppState>,
+) -> Result<ServiceMap, CamppError> {
+    let mut manager = state.process_manager.lock()
+        .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+    manager.update_health();
+    Ok(manager.get_all_statuses())
+}
+
+/// Run every readiness probe and report the result of each
+#[tauri::command]
+pub async fn health_check(state: State<'_, AppState>) -> Result<crate::process::health::HealthReport, CamppError> {
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let ctx = {
+            let mut manager = pm.lock()?;
+            // Locate binaries even before the first start, for the binary checks
+            if manager.get_runtime_paths().is_none() {
+                let _ = manager.initialize();
+            }
+            manager.update_health();
+            crate::process::health::HealthContext {
+                paths: manager.get_runtime_paths(),
+                settings: AppSettings::load(),
+                services: manager.get_all_statuses(),
+                data_dir: crate::runtime::locator::get_app_data_paths()?.base_dir,
+            }
+        };
+        Ok(crate::process::health::run_checks(&ctx))
+    }).await?
+}
+
+/// Return the last lines of a service log and, with `follow`, emit every new
+/// line as a `log-line` event until called again without it
+#[tauri::command]
+pub async fn tail_service_log(service: ServiceType, follow: bool, app: tauri::AppHandle) -> Result<Vec<String>, CamppError> {
+    use crate::process::logs;
+
+    let logs_dir = crate::runtime::locator::get_app_data_paths()?.logs_dir;
+    if !follow {
+        logs::stop_following(service);
+    }
+    let lines = tokio::task::spawn_blocking({
+        let path = logs::log_path(&logs_dir, service);
+        move || logs::last_lines(&path, logs::TAIL_LINES)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    if follow {
+        logs::follow(&logs_dir, service, move |line| {
+            let _ = app.emit("log-line", &line);
+        })?;
+    }
+    Ok(lines)
+}
+
+/// Read a page of a service log, filtered by level and text
+///
+/// `access_log` reads Caddy's shared access log instead of its error log.
+/// Lines are returned newest last, with Caddy's JSON lines parsed.
+#[tauri::command]
+pub async fn get_service_logs(
+    service: ServiceType,
+    query: Option<crate::process::logs::LogQuery>,
+    access_log: Option<bool>,
+) -> Result<crate::process::logs::LogPage, CamppError> {
+    use crate::process::logs;
+
+    let logs_dir = crate::runtime::locator::get_app_data_paths()?.logs_dir;
+    let path = match access_log.unwrap_or(false) {
+        true if service != ServiceType::Caddy => return Err(format!("{} has no access log", service.display_name()).into()),
+        true => logs_dir.join("caddy-access.log"),
+        false => logs::log_path(&logs_dir, service),
+    };
+    let query = query.unwrap_or_default();
+    tokio::task::spawn_blocking(move || logs::read_page(&path, &query))
+        .await?
+        .map_err(Into::into)
+}
+
+/// Empty the logs of a service to start a clean debugging session
+///
+/// With `archive`, the current contents are kept as a rotated file.
+#[tauri::command]
+pub async fn clear_service_logs(service: ServiceType, archive: Option<bool>) -> Result<Vec<String>, CamppError> {
+    let logs_dir = crate::runtime::locator::get_app_data_paths()?.logs_dir;
+    let policy = archive.unwrap_or(false).then(|| AppSettings::load().log_rotation);
+    tokio::task::spawn_blocking(move || {
+        crate::process::rotation::clear_service_logs(&logs_dir, service, policy.as_ref())
+    })
+    .await?
+    .map_err(Into::into)
+}
+
+/// Get app settings
+#[tauri::command]
+pub async fn get_settings() -> Result<crate::config::AppSettings, CamppError> {
+    Ok(crate::config::AppSettings::load())
+}
+
+/// List the `CAMPP_*` environment variables overriding saved settings
+#[tauri::command]
+pub async fn get_env_overrides() -> Result<Vec<crate::config::env::EnvOverride>, CamppError> {
+    Ok(crate::config::env::active_overrides().to_vec())
+}
+
+/// Save app settings
+#[tauri::command]
+pub async fn save_settings(
+    settings: crate::config::AppSettings,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CamppError> {
+    apply_settings(settings, &app, &state).await.map_err(Into::into)
+}
+
+/// Validate and save settings, then apply them to the running services
+///
+/// Emits "settings-changed" once the settings are saved, with whatever could
+/// not be applied still listed as pending.
+async fn apply_settings(settings: AppSettings, app: &tauri::AppHandle, state: &State<'_, AppState>) -> Result<(), String> {
+    // Port warnings are expected for services that are already running, only errors block saving
+    let validation = settings.validate();
+    if !validation.is_valid() {
+        let messages: Vec<&str> = validation.errors.iter().map(|e| e.message.as_str()).collect();
+        return Err(messages.join("\n"));
+    }
+
+    let old_settings = crate::config::AppSettings::load();
+    if settings.domain_tld != old_settings.domain_tld {
+        return Err("The domain TLD is changed with set_domain_tld, which moves the project domains along".to_string());
+    }
+
+    // Save the settings first
+    settings.save()?;
+
+    // If PostgreSQL password changed, remove .password_set flag so it gets re-applied on start
+    if old_settings.postgres_root_password != settings.postgres_root_password {
+        let paths = state.process_manager.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?
+            .get_runtime_paths();
+        if let Some(paths) = paths {
+            let _ = fs::remove_file(paths.pgsql_data_dir.join(".password_set"));
+        }
+    }
+
+    let report = apply_changes(&settings, Some(app), state).await?;
+
+    let changed_fields = crate::config::apply::changed_fields(&old_settings, &settings);
+    if !changed_fields.is_empty() {
+        let pending = {
+            let manager = state.process_manager.lock()
+                .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+            crate::config::apply::plan_changes(&manager.get_applied_settings(), &settings)
+        };
+        let _ = app.emit("settings-changed", SettingsChanged::new(old_settings, settings, changed_fields, pending));
+    }
+
+    let errors = report.errors();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Settings saved, but some changes failed to apply:\n{}", errors.join("\n")))
+    }
+}
+
+/// List what applying the saved settings would do to the running services
+#[tauri::command]
+pub async fn get_pending_changes(state: State<'_, AppState>) -> Result<Vec<PendingChange>, CamppError> {
+    let manager = state.process_manager.lock()
+        .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+    Ok(crate::config::apply::plan_changes(&manager.get_applied_settings(), &AppSettings::load()))
+}
+
+/// Apply the saved settings with the fewest restarts, emitting "apply-progress"
+#[tauri::command]
+pub async fn apply_pending_changes(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<ApplyReport, CamppError> {
+    apply_changes(&AppSettings::load(), Some(&app), &state).await.map_err(Into::into)
+}
+
+/// Plan and run the actions that bring running services in line with `settings`
+///
+/// Passwords are changed first, while the services still run with the old
+/// ports and credentials. The other actions run one at a time in plan order.
+async fn apply_changes(
+    settings: &AppSettings,
+    app: Option<&tauri::AppHandle>,
+    state: &State<'_, AppState>,
+) -> Result<ApplyReport, String> {
+    let pm = state.process_manager.clone();
+    let plan = {
+        let settings = settings.clone();
+        let pm = pm.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut manager = pm.lock()
+                .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+            let plan = crate::config::apply::plan_changes(&manager.get_applied_settings(), &settings);
+
+            for change in plan.iter().filter(|c| c.action != ApplyAction::Stop) {
+                let password_changed = |field: &str| change.fields.iter().any(|f| f == field);
+                match change.service {
+                    ServiceType::MySQL if password_changed("mysql_root_password") => {
+                        apply_mysql_password(&manager, &settings.mysql_root_password);
+                    }
+                    ServiceType::PostgreSQL if password_changed("postgres_root_password") => {
+                        apply_postgres_password(&manager, &settings.postgres_root_password);
+                    }
+                    _ => {}
+                }
+            }
+
+            manager.update_ports(&settings);
+            Ok::<_, String>(plan)
+        }).await.map_err(|e| format!("Task error: {}", e))??
+    };
+
+    let total = plan.len();
+    let mut report = ApplyReport::default();
+    for (i, change) in plan.into_iter().enumerate() {
+        if let Some(app) = app {
+            let _ = app.emit("apply-progress", ApplyProgress {
+                step: i + 1,
+                total,
+                service: change.service,
+                action: change.action,
+            });
+        }
+
+        let result = match change.action {
+            ApplyAction::UpdatePassword => Ok(()),
+            ApplyAction::ReloadConfig => reload_caddy_config(state).await,
+            ApplyAction::Restart | ApplyAction::Stop => {
+                let pm = pm.clone();
+                let (service, action) = (change.service, change.action);
+                tokio::task::spawn_blocking(move || {
+                    let mut manager = pm.lock()
+                        .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+                    if action == ApplyAction::Stop {
+                        manager.stop(service)
+                    } else {
+                        manager.restart_with(service, RestartStrategy::Graceful).map(|_| ())
+                    }
+                }).await.map_err(|e| format!("Task error: {}", e))?
+            }
+        };
+
+        if result.is_ok() {
+            if let Ok(mut manager) = pm.lock() {
+                manager.mark_applied(change.service);
+            }
+        }
+        report.changes.push(AppliedChange { change, error: result.err() });
+    }
+
+    // Services the plan did not touch are up to date as well
+    let mut manager = pm.lock()
+        .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+    for service in ServiceType::start_order() {
+        if !report.changes.iter().any(|c| c.change.service == service) {
+            manager.mark_applied(service);
+        }
+    }
+    // The wildcard DNS is not a service, start_dns restarts it only when its
+    // port or the TLD changed
+    if manager.dns_port().is_some() {
+        if settings.dns.enabled {
+            if let Err(e) = manager.start_dns() {
+                tracing::warn!("Failed to restart the wildcard DNS: {}", e);
+            }
+        } else {
+            manager.stop_dns();
+        }
+    }
+    manager.update_health();
+
+    Ok(report)
+}
+
+/// Export settings and runtime vhosts to a portable JSON file
+#[tauri::command]
+pub async fn export_settings(path: String, state: State<'_, AppState>) -> Result<(), CamppError> {
+    let vhosts = match caddy_ports_if_running(&state) {
+        Ok((web_port, _)) => crate::process::caddy_admin::CaddyAdmin::new()?
+            .list_vhosts(web_port)
+            .await?,
+        Err(_) => Vec::new(),
+    };
+    crate::config::portable::export_to_file(std::path::Path::new(&path), &AppSettings::load(), &vhosts).map_err(Into::into)
+}
+
+/// Import a file written by `export_settings` into the active profile
+///
+/// Settings are applied like `save_settings`; vhosts are added to Caddy when
+/// it is running.
+#[tauri::command]
+pub async fn import_settings(path: String, app: tauri::AppHandle, state: State<'_, AppState>) -> Result<crate::config::portable::ImportSummary, CamppError> {
+    let imported = crate::config::portable::import_from_file(std::path::Path::new(&path))?;
+    let _awake = crate::process::power::SleepInhibitor::acquire("Importing settings");
+    let mut settings = imported.settings;
+    let local = AppSettings::load();
+    settings.active_profile = local.active_profile.clone();
+    settings.keep_secrets_of(&local);
+    // The registered project domains use the local TLD
+    settings.domain_tld = local.domain_tld.clone();
+
+    let validation = settings.validate();
+    if !validation.is_valid() {
+        let messages: Vec<&str> = validation.errors.iter().map(|e| e.message.as_str()).collect();
+        return Err(format!("Imported settings are invalid:\n{}", messages.join("\n")).into());
+    }
+
+    let mut summary = crate::config::portable::ImportSummary {
+        settings: settings.clone(),
+        vhosts_added: Vec::new(),
+        errors: Vec::new(),
+    };
+    // Settings are applied as a whole, cancelling skips the remaining vhosts
+    let op = Operation::start(OperationKind::Import, true, format!("Importing {}", path));
+    op.progress("applying_settings", Some(10), "Applying imported settings");
+    if let Err(e) = apply_settings(settings, &app, &state).await {
+        summary.errors.push(e);
+    }
+
+    if !imported.vhosts.is_empty() {
+        match caddy_ports_if_running(&state) {
+            Ok((web_port, php_port)) => {
+                let admin = crate::process::caddy_admin::CaddyAdmin::new()?;
+                let total = imported.vhosts.len();
+                for (i, vhost) in imported.vhosts.iter().enumerate() {
+                    if op.is_cancelled() {
+                        summary.errors.push(format!("Import cancelled, {} virtual hosts were not added", total - i));
+                        break;
+                    }
+                    op.progress("adding_vhosts", Some((50 + 50 * i / total) as u8), format!("Adding {}", vhost.host));
+                    match admin.add_vhost(vhost, web_port, php_port).await {
+                        Ok(()) => summary.vhosts_added.push(vhost.host.clone()),
+                        Err(e) => summary.errors.push(format!("{}: {}", vhost.host, e)),
+                    }
+                }
+            }
+            Err(_) => summary.errors.push(format!(
+                "Caddy is not running, {} virtual hosts were not added",
+                imported.vhosts.len()
+            )),
+        }
+    }
+
+    op.finish(&op.check_cancelled());
+    Ok(summary)
+}
+
+/// Protect /phpmyadmin with HTTP basic auth
+///
+/// The password is hashed with bcrypt before being stored; only the hash is
+/// written to settings.json and the Caddyfile.
+#[tauri::command]
+pub async fn set_phpmyadmin_auth(
+    username: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<(), CamppError> {
+    let username = username.trim().to_string();
+    if username.is_empty()
+        || !username.chars().all(|c| c.is_ascii_alphanumeric() || "._-@".contains(c))
+    {
+        return Err(CamppError::invalid_input("Username may only contain letters, digits, '.', '_', '-' and '@'"));
+    }
+    if password.is_empty() {
+        return Err(CamppError::invalid_input("Password must not be empty"));
+    }
+
+    // bcrypt is deliberately slow, keep it off the async runtime
+    let password_hash = tokio::task::spawn_blocking(move || {
+        bcrypt::hash(&password, bcrypt::DEFAULT_COST)
+    }).await
+        .map_err(|e| format!("Task error: {}", e))?
+        .map_err(|e| format!("Failed to hash password: {}", e))?;
+
+    let mut settings = AppSettings::load();
+    settings.phpmyadmin_auth = Some(BasicAuthCredentials { username, password_hash });
+    settings.save()?;
+
+    restart_caddy_if_running(state.process_manager.clone()).await.map_err(Into::into)
+}
+
+/// Remove basic auth protection from /phpmyadmin
+#[tauri::command]
+pub async fn clear_phpmyadmin_auth(state: State<'_, AppState>) -> Result<(), CamppError> {
+    let mut settings = AppSettings::load();
+    if settings.phpmyadmin_auth.take().is_none() {
+        return Ok(());
+    }
+    settings.save()?;
+
+    restart_caddy_if_running(state.process_manager.clone()).await.map_err(Into::into)
+}
+
+/// Get the response headers Caddy adds to the main site
+#[tauri::command]
+pub async fn get_security_headers() -> Result<Vec<SecurityHeader>, CamppError> {
+    Ok(AppSettings::load().security_headers)
+}
+
+/// Replace the response headers Caddy adds to the main site
+///
+/// An empty list disables the `header` block entirely.
+#[tauri::command]
+pub async fn set_security_headers(
+    headers: Vec<SecurityHeader>,
+    state: State<'_, AppState>,
+) -> Result<(), CamppError> {
+    let mut cleaned = Vec::with_capacity(headers.len());
+    for header in headers {
+        let name = header.name.trim().to_string();
+        if name.is_empty()
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("Invalid header name: '{}'", header.name).into());
+        }
+        if header.value.contains('\n') || header.value.contains('\r') {
+            return Err(format!("Header '{}' value must be a single line", name).into());
+        }
+        if cleaned.iter().any(|h: &SecurityHeader| h.name.eq_ignore_ascii_case(&name)) {
+            return Err(format!("Duplicate header: '{}'", name).into());
+        }
+        cleaned.push(SecurityHeader { name, value: header.value.trim().to_string() });
+    }
+
+    let mut settings = AppSettings::load();
+    settings.security_headers = cleaned;
+    settings.save()?;
+
+    restart_caddy_if_running(state.process_manager.clone()).await.map_err(Into::into)
+}
+
+/// Restore the default security headers
+#[tauri::command]
+pub async fn reset_security_headers(state: State<'_, AppState>) -> Result<Vec<SecurityHeader>, CamppError> {
+    let mut settings = AppSettings::load();
+    settings.security_headers = AppSettings::default().security_headers;
+    settings.save()?;
+
+    restart_caddy_if_running(state.process_manager.clone()).await?;
+    Ok(settings.security_headers)
+}
+
+/// Restart Caddy if it is running so a regenerated Caddyfile takes effect
+async fn restart_caddy_if_running(pm: Arc<Mutex<ProcessManager>>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        if manager.status(ServiceType::Caddy) == ServiceState::Running {
+            manager.restart(ServiceType::Caddy)?;
+            manager.update_health();
+        }
+
+        Ok(())
+    }).await.map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Aggregate the security posture of the local stack
+#[tauri::command]
+pub async fn get_security_report(state: State<'_, AppState>) -> Result<crate::config::security::SecurityReport, CamppError> {
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let paths = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?
+            .get_runtime_paths();
+        let settings = AppSettings::load();
+        let installed = RuntimeDownloader::new()?.get_installed_components();
+
+        Ok(crate::config::security::build_security_report(&settings, paths.as_ref(), &installed))
+    }).await?
+}
+
+/// Add a virtual host to the running Caddy instance without restarting it
+#[tauri::command]
+pub async fn caddy_add_vhost(host: String, root: String, state: State<'_, AppState>) -> Result<(), CamppError> {
+    let root_path = std::path::Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(format!("Document root does not exist: {}", root).into());
+    }
+
+    let (web_port, php_port) = caddy_ports_if_running(&state)?;
+    let vhost = crate::process::caddy_admin::VhostRoute { host, root };
+    crate::process::caddy_admin::CaddyAdmin::new()?
+        .add_vhost(&vhost, web_port, php_port)
+        .await.map_err(Into::into)
+}
+
+/// Remove a virtual host previously added with `caddy_add_vhost`
+#[tauri::command]
+pub async fn caddy_remove_vhost(host: String, state: State<'_, AppState>) -> Result<(), CamppError> {
+    caddy_ports_if_running(&state)?;
+    crate::process::caddy_admin::CaddyAdmin::new()?
+        .remove_vhost(&host)
+        .await.map_err(Into::into)
+}
+
+/// List virtual hosts added at runtime through the Caddy admin API
+#[tauri::command]
+pub async fn caddy_list_vhosts(state: State<'_, AppState>) -> Result<Vec<crate::process::caddy_admin::VhostRoute>, CamppError> {
+    let (web_port, _) = caddy_ports_if_running(&state)?;
+    crate::process::caddy_admin::CaddyAdmin::new()?
+        .list_vhosts(web_port)
+        .await.map_err(Into::into)
+}
+
+/// Request counts, status codes and in-flight requests of the local sites
+#[tauri::command]
+pub async fn get_web_server_stats(state: State<'_, AppState>) -> Result<crate::process::caddy_admin::WebServerStats, CamppError> {
+    caddy_ports_if_running(&state)?;
+    crate::process::caddy_admin::CaddyAdmin::new()?
+        .stats()
+        .await.map_err(Into::into)
+}
+
+/// Get the running Caddy JSON config
+#[tauri::command]
+pub async fn caddy_get_config(state: State<'_, AppState>) -> Result<serde_json::Value, CamppError> {
+    caddy_ports_if_running(&state)?;
+    crate::process::caddy_admin::CaddyAdmin::new()?
+        .get_config()
+        .await.map_err(Into::into)
+}
+
+/// Regenerate the Caddyfile and apply it to the running Caddy without a restart
+///
+/// The phpMyAdmin and Adminer configs are rewritten as well, PHP reads them on
+/// every request. The Caddyfile replaces the whole running config, so routes added with
+/// `caddy_add_vhost` are added again afterwards.
+#[tauri::command]
+pub async fn caddy_reload_config(state: State<'_, AppState>) -> Result<(), CamppError> {
+    reload_caddy_config(&state).await.map_err(Into::into)
+}
+
+async fn reload_caddy_config(state: &State<'_, AppState>) -> Result<(), String> {
+    let (web_port, php_port) = caddy_ports_if_running(state).map_err(|e| e.to_string())?;
+    let (paths, mysql_port) = {
+        let manager = state.process_manager.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        let paths = manager.get_runtime_paths().ok_or("Runtime paths not initialized")?;
+        (paths, manager.get_service_port(ServiceType::MySQL))
+    };
+
+    let caddyfile_path = paths.config_dir.join("Caddyfile");
+    let settings = AppSettings::load();
+    crate::process::manager::generate_db_client_configs(&paths, &settings, mysql_port.unwrap_or(settings.mysql_port))?;
+    if !crate::config::generated::keep_hand_edited(&caddyfile_path, &settings) {
+        crate::config::generator::generate_caddyfile(&caddyfile_path, &paths, web_port, php_port, &settings, &crate::projects::registered())?;
+    }
+    let caddyfile = fs::read_to_string(&caddyfile_path)
+        .map_err(|e| format!("Failed to read Caddyfile: {}", e))?;
+
+    let admin = crate::process::caddy_admin::CaddyAdmin::new()?;
+    if !admin.is_available().await {
+        return Err("Caddy admin API is not reachable".to_string());
+    }
+    let vhosts = admin.list_vhosts(web_port).await.unwrap_or_default();
+    admin.load_caddyfile(&caddyfile).await?;
+
+    let mut dropped = Vec::new();
+    for vhost in &vhosts {
+        if let Err(e) = admin.add_vhost(vhost, web_port, php_port).await {
+            tracing::warn!("Failed to add vhost {} again: {}", vhost.host, e);
+            dropped.push(vhost.host.clone());
+        }
+    }
+    if !dropped.is_empty() {
+        return Err(format!("Caddy reloaded, but these runtime vhosts were dropped: {}", dropped.join(", ")));
+    }
+    Ok(())
+}
+
+/// Get the web and PHP ports, failing if Caddy is not running
+fn caddy_ports_if_running(state: &State<'_, AppState>) -> Result<(u16, u16), CamppError> {
+    let manager = state.process_manager.lock()?;
+
+    if manager.status(ServiceType::Caddy) != ServiceState::Running {
+        return Err(CamppError::ServiceNotRunning { service: ServiceType::Caddy });
+    }
+
+    let web_port = manager.get_service_port(ServiceType::Caddy).unwrap_or(ServiceType::Caddy.default_port());
+    let php_port = manager.get_service_port(ServiceType::PhpFpm).unwrap_or(ServiceType::PhpFpm.default_port());
+    Ok((web_port, php_port))
+}
+
+/// Move a service to a new port, updating settings, dependent configs and restarting
+/// affected services in order
+#[tauri::command]
+pub async fn set_service_port(
+    service: ServiceType,
+    port: u16,
+    update_project_envs: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<crate::process::manager::PortChangeResult, CamppError> {
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        if manager.get_service_port(service) != Some(port) && !crate::config::is_port_available(port) {
+            let owner = crate::config::port_owner(port).map(|o| o.to_string());
+            return Err(CamppError::PortInUse { port, owner });
+        }
+        let result = manager.set_service_port(service, port, update_project_envs.unwrap_or(false));
+        manager.update_health();
+        result.map_err(Into::into)
+    }).await?
+}
+
+/// Compare running services with the saved settings and report stale configuration
+#[tauri::command]
+pub async fn check_config_drift(state: State<'_, AppState>) -> Result<Vec<crate::process::drift::DriftItem>, CamppError> {
+    crate::process::drift::check_drift(state.process_manager.clone()).await.map_err(Into::into)
+}
+
+/// List settings profiles
+#[tauri::command]
+pub async fn list_profiles() -> Result<Vec<String>, CamppError> {
+    crate::config::profiles::list_profiles().map_err(Into::into)
+}
+
+/// Create a profile from the current settings
+#[tauri::command]
+pub async fn create_profile(name: String) -> Result<(), CamppError> {
+    crate::config::profiles::create_profile(&name).map_err(Into::into)
+}
+
+/// Delete a profile other than the active one
+#[tauri::command]
+pub async fn delete_profile(name: String) -> Result<(), CamppError> {
+    crate::config::profiles::delete_profile(&name).map_err(Into::into)
+}
+
+/// Switch to another profile: stop services, swap settings and restart what was running
+#[tauri::command]
+pub async fn switch_profile(name: String, state: State<'_, AppState>) -> Result<AppSettings, CamppError> {
+    crate::config::profiles::validate_profile_name(&name)?;
+    let pm = state.process_manager.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+
+        let running_services: Vec<ServiceType> = ServiceType::start_order()
+            .into_iter()
+            .filter(|ty| manager.status(*ty).is_running())
+            .collect();
+        for service in running_services.iter().rev() {
+            manager.stop(*service)?;
+        }
+
+        let settings = match crate::config::profiles::activate_profile(&name) {
+            Ok(settings) => settings,
+            Err(e) => {
+                // Nothing was swapped, bring the old profile back up
+                for service in &running_services {
+                    let _ = manager.start(*service);
+                }
+                return Err(e);
+            }
+        };
+        manager.update_ports(&settings);
+
+        let mut failures = Vec::new();
+        for service in running_services.into_iter().filter(|s| settings.is_enabled(*s)) {
+            if let Err(e) = manager.start(service) {
+                failures.push(format!("{}: {}", service.display_name(), e));
+            }
+        }
+        manager.update_health();
+
+        if failures.is_empty() {
+            Ok(settings)
+        } else {
+            Err(format!("Switched to profile '{}', but some services failed to start:\n{}", name, failures.join("\n")))
+        }
+    }).await?.map_err(Into::into)
+}
+
+/// Validate settings (check port conflicts, valid paths)
+#[tauri::command]
+pub async fn validate_settings(settings: crate::config::AppSettings) -> crate::config::settings::ValidationResult {
+    settings.validate()
+}
+
+/// Render the config files `settings` would produce and diff them against
+/// the current ones, without writing anything
+#[tauri::command]
+pub async fn preview_configs(
+    settings: AppSettings,
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::config::preview::ConfigPreview>, CamppError> {
+    let validation = settings.validate();
+    if !validation.is_valid() {
+        let messages: Vec<&str> = validation.errors.iter().map(|e| e.message.as_str()).collect();
+        return Err(CamppError::invalid_input(messages.join("\n")));
+    }
+
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let (paths, running) = {
+            let manager = pm.lock()?;
+            let running = manager.get_applied_settings().into_iter()
+                .find(|(service, _)| *service == ServiceType::MySQL)
+                .map(|(_, applied)| applied);
+            (manager.get_runtime_paths(), running)
+        };
+        let mut paths = match paths {
+            Some(paths) => paths,
+            None => crate::runtime::locator::locate_runtime_binaries()?,
+        };
+        paths.projects_dir = settings.projects_dir(&crate::runtime::locator::get_app_data_paths()?.projects_dir);
+        let running = running.unwrap_or_else(AppSettings::load);
+        crate::config::preview::preview_configs(&paths, &settings, &running).map_err(Into::into)
+    }).await?
+}
+
+/// Check if specific ports are available, naming the process holding each taken one
+#[tauri::command]
+pub async fn check_ports(web_port: u16, php_port: u16, mysql_port: u16, postgres_port: u16) -> serde_json::Value {
+    let check = |port: u16| {
+        let available = crate::config::is_port_available(port);
+        let owner = if available { None } else { crate::config::port_owner(port) };
+        serde_json::json!({
+            "port": port,
+            "available": available,
+            "owner": owner
+        })
+    };
+
+    serde_json::json!({
+        "web": check(web_port),
+        "php": check(php_port),
+        "mysql": check(mysql_port),
+        "postgres": check(postgres_port)
+    })
+}
+
+/// Kill the process holding `port`, returning what was killed
+///
+/// Refuses CAMPP itself and its own services, which are stopped with `stop_service`.
+#[tauri::command]
+pub async fn kill_port_owner(port: u16, state: State<'_, AppState>) -> Result<crate::config::PortOwner, CamppError> {
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let owner = crate::config::port_owner(port)
+            .ok_or_else(|| format!("Could not find the process listening on port {}", port))?;
+        if owner.pid == std::process::id() {
+            return Err(CamppError::invalid_input(format!("Port {} is used by CAMPP itself", port)));
+        }
+        if let Some(service) = pm.lock()?.service_with_pid(owner.pid) {
+            return Err(CamppError::invalid_input(format!(
+                "Port {} is used by {}, stop it instead", port, service.display_name()
+            )));
+        }
+
+        crate::process::killer::kill_pid(owner.pid)?;
+        tracing::info!("Killed {} holding port {}", owner, port);
+
+        // The port is released once the process has exited
+        for _ in 0..20 {
+            if crate::config::is_port_available(port) {
+                return Ok(owner);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        Err(format!("{} was killed but port {} is still in use", owner, port).into())
+    }).await?
+}
+
+/// Next free port after the configured port of `service`, skipping ports
+/// assigned to the other services
+#[tauri::command]
+pub async fn suggest_free_port(service: ServiceType) -> Result<u16, CamppError> {
+    let settings = AppSettings::load();
+    let taken: Vec<u16> = ServiceType::start_order().into_iter()
+        .filter(|s| *s != service)
+        .map(|s| ProcessManager::port_for_service(s, &settings))
+        .collect();
+    let mut port = ProcessManager::port_for_service(service, &settings);
+    loop {
+        port = port.checked_add(1).ok_or("No free port left")?;
+        if !taken.contains(&port) && crate::config::is_port_available(port) {
+            return Ok(port);
+        }
+    }
+}
+
+/// Check if runtime binaries are already installed
+#[tauri::command]
+pub async fn check_runtime_installed() -> Result<bool, CamppError> {
+    let downloader = RuntimeDownloader::new()?;
+    Ok(downloader.is_installed())
 }
 
-/// Restart a service
+/// Drop a MySQL database, dumping it to the backups folder first
 #[tauri::command]
-pub async fn restart_service(
-    service: ServiceType,
+pub async fn drop_database(
+    name: String,
     state: State<'_, AppState>,
-) -> Result<ServiceMap, String> {
+) -> Result<crate::database::backup::DestructiveOpResult, CamppError> {
     let pm = state.process_manager.clone();
-
     tokio::task::spawn_blocking(move || {
-        let mut manager = pm.lock()
-            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        // The dump can take a while, the lock is only held for the checks
+        let paths = {
+            let manager = pm.lock()
+                .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+            if manager.status(ServiceType::MySQL) != ServiceState::Running {
+                return Err(CamppError::ServiceNotRunning { service: ServiceType::MySQL });
+            }
+            manager.get_runtime_paths().ok_or("Runtime paths not initialized")?
+        };
+        let settings = AppSettings::load();
+
+        let backup = crate::database::backup::backup_mysql_database(&paths, &settings, &name, "drop_database")
+            .map_err(|e| format!("Backup failed, database was not dropped: {}", e))?;
+        crate::database::mysql::drop_database(&paths, settings.mysql_port, &settings.mysql_root_password, &name)?;
+
+        Ok(crate::database::backup::DestructiveOpResult {
+            message: format!("Database '{}' dropped", name),
+            backup,
+        })
+    }).await?
+}
 
-        // Initialize if needed
-        manager.initialize()?;
+/// Copy a MySQL connection snippet for `database` to the clipboard and return it
+#[tauri::command]
+pub async fn copy_connection_string(
+    format: crate::database::dsn::DsnFormat,
+    database: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, CamppError> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
 
-        // Restart the service
-        let result = manager.restart(service);
+    let database = database.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    if let Some(ref name) = database {
+        crate::database::mysql::validate_database_name(name).map_err(CamppError::invalid_input)?;
+    }
+    let info = crate::database::dsn::ConnectionInfo::mysql(&AppSettings::load());
+    let snippet = crate::database::dsn::connection_string(format, &info, database.as_deref());
+    app.clipboard().write_text(snippet.clone())
+        .map_err(|e| format!("Failed to write to the clipboard: {}", e))?;
+    Ok(snippet)
+}
 
-        manager.update_health();
-        let statuses = manager.get_all_statuses();
+/// Map databases to the projects configured to use them
+///
+/// When MySQL is running, its databases and live connections are included.
+#[tauri::command]
+pub async fn get_database_usage(state: State<'_, AppState>) -> Result<Vec<crate::database::usage::DatabaseUsage>, CamppError> {
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let (paths, mysql_running) = {
+            let manager = pm.lock()
+                .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+            let paths = manager.get_runtime_paths().ok_or("Runtime paths not initialized")?;
+            (paths, manager.status(ServiceType::MySQL) == ServiceState::Running)
+        };
+        let settings = AppSettings::load();
+        Ok(crate::database::usage::get_database_usage(&paths, &settings, mysql_running))
+    }).await?
+}
 
-        result?;
-        Ok(statuses)
-    }).await.map_err(|e| format!("Task error: {}", e))?
+/// List automatic backups, newest first
+#[tauri::command]
+pub async fn list_backups() -> Result<Vec<crate::database::backup::BackupRecord>, CamppError> {
+    crate::database::backup::list_backups().map_err(Into::into)
 }
 
-/// Get the status of all services
+/// Restore default settings, backing up settings.json and generated configs first
 #[tauri::command]
-pub async fn get_all_statuses(
+pub async fn reset_settings(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<ServiceMap, String> {
-    let mut manager = state.process_manager.lock()
-        .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+) -> Result<crate::database::backup::DestructiveOpResult, CamppError> {
+    let backup = tokio::task::spawn_blocking(|| crate::database::backup::snapshot_settings("reset_settings"))
+        .await.map_err(|e| format!("Task error: {}", e))??;
+
+    // The reset applies to the active profile. The root passwords are those
+    // of the initialized data directories and stay, as does the phpMyAdmin
+    // login, and the TLD the project domains use.
+    let local = AppSettings::load();
+    let mut settings = AppSettings {
+        active_profile: local.active_profile.clone(),
+        domain_tld: local.domain_tld.clone(),
+        ..Default::default()
+    };
+    settings.keep_secrets_of(&local);
+    apply_settings(settings, &app, &state).await
+        .map_err(|e| format!("{}\nPrevious settings were backed up to {}", e, backup.path))?;
 
-    manager.update_health();
-    Ok(manager.get_all_statuses())
+    Ok(crate::database::backup::DestructiveOpResult {
+        message: "Settings were reset to defaults".to_string(),
+        backup: Some(backup),
+    })
 }
 
-/// Get app settings
+/// Re-apply settings from a backup taken by `reset_settings`
 #[tauri::command]
-pub async fn get_settings() -> Result<crate::config::AppSettings, String> {
-    Ok(crate::config::AppSettings::load())
+pub async fn restore_settings_backup(
+    id: String,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, CamppError> {
+    let mut settings = tokio::task::spawn_blocking(move || crate::database::backup::read_settings_backup(&id))
+        .await.map_err(|e| format!("Task error: {}", e))??;
+    // The registered project domains use the current TLD
+    settings.domain_tld = AppSettings::load().domain_tld;
+    apply_settings(settings, &app, &state).await?;
+    Ok(AppSettings::load())
 }
 
-/// Save app settings
+/// Delete a backup
 #[tauri::command]
-pub async fn save_settings(settings: crate::config::AppSettings, state: State<'_, AppState>) -> Result<(), String> {
-    let old_settings = crate::config::AppSettings::load();
-    let mysql_changed = old_settings.mysql_root_password != settings.mysql_root_password;
-    let postgres_changed = old_settings.postgres_root_password != settings.postgres_root_password;
-
-    // Save the settings first
-    settings.save()?;
+pub async fn delete_backup(id: String) -> Result<(), CamppError> {
+    tokio::task::spawn_blocking(move || crate::database::backup::delete_backup(&id))
+        .await?.map_err(Into::into)
+}
 
+/// Make a broken installation consistent again without touching user data
+///
+/// If Caddy is running, the regenerated Caddyfile is applied live so project
+/// sites are registered again; routes added with `caddy_add_vhost` are kept,
+/// and reported if one could not be added back.
+#[tauri::command]
+pub async fn repair_installation(state: State<'_, AppState>) -> Result<crate::runtime::repair::RepairReport, CamppError> {
     let pm = state.process_manager.clone();
-
-    tokio::task::spawn_blocking(move || {
+    let mut report = tokio::task::spawn_blocking(move || {
         let mut manager = pm.lock()
             .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        Ok::<_, String>(crate::runtime::repair::repair_installation(&mut manager))
+    }).await.map_err(|e| format!("Task error: {}", e))??;
 
-        // If PostgreSQL password changed, remove .password_set flag so it gets re-applied on start
-        if postgres_changed {
-            if let Some(paths) = manager.get_runtime_paths() {
-                let flag = paths.pgsql_data_dir.join(".password_set");
-                let _ = fs::remove_file(&flag);
-            }
-        }
-
-        // Get the current running services before updating ports
-        let running_services: Vec<ServiceType> = manager.get_all_statuses()
-            .iter()
-            .filter(|(_, s)| s.state == ServiceState::Running)
-            .map(|(ty, _)| *ty)
-            .collect();
-
-        // Update ports in the process manager
-        manager.update_ports(&settings);
-
-        // If MySQL password changed and MySQL is running, apply inline before restart
-        if mysql_changed && running_services.contains(&ServiceType::MySQL) {
-            apply_mysql_password(&manager, &settings.mysql_root_password);
-        }
-        // If PostgreSQL password changed and PostgreSQL is running, apply inline
-        if postgres_changed && running_services.contains(&ServiceType::PostgreSQL) {
-            apply_postgres_password(&manager, &settings.postgres_root_password);
-        }
-
-        // Restart any running services with new configuration
-        for service in running_services {
-            let _ = manager.stop(service);
-            let _ = manager.start(service);
+    if caddy_ports_if_running(&state).is_ok() {
+        match reload_caddy_config(&state).await {
+            Ok(()) => report.actions.push("Reloaded the Caddy config from the project registry".to_string()),
+            Err(e) => report.errors.push(format!("Failed to reload Caddy: {}", e)),
         }
+    }
 
-        Ok(())
-    }).await.map_err(|e| format!("Task error: {}", e))?
-}
-
-/// Validate settings (check port conflicts, valid paths)
-#[tauri::command]
-pub async fn validate_settings(settings: crate::config::AppSettings) -> Result<Vec<String>, Vec<String>> {
-    settings.validate()
-}
-
-/// Check if specific ports are available
-#[tauri::command]
-pub async fn check_ports(web_port: u16, php_port: u16, mysql_port: u16, postgres_port: u16) -> serde_json::Value {
-    use crate::config::is_port_available;
-
-    serde_json::json!({
-        "web": {
-            "port": web_port,
-            "available": is_port_available(web_port)
-        },
-        "php": {
-            "port": php_port,
-            "available": is_port_available(php_port)
-        },
-        "mysql": {
-            "port": mysql_port,
-            "available": is_port_available(mysql_port)
-        },
-        "postgres": {
-            "port": postgres_port,
-            "available": is_port_available(postgres_port)
-        }
-    })
+    Ok(report)
 }
 
-/// Check if runtime binaries are already installed
+/// Look for common failure modes and suggest a fix for each
 #[tauri::command]
-pub async fn check_runtime_installed() -> Result<bool, String> {
-    let downloader = RuntimeDownloader::new()?;
-    Ok(downloader.is_installed())
+pub async fn doctor(state: State<'_, AppState>) -> Result<Vec<crate::runtime::doctor::Finding>, CamppError> {
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let ctx = {
+            let mut manager = pm.lock()?;
+            manager.update_health();
+            crate::runtime::doctor::DoctorContext {
+                app_paths: crate::runtime::locator::get_app_data_paths()?,
+                paths: manager.get_runtime_paths().or_else(|| crate::runtime::locator::locate_runtime_binaries().ok()),
+                settings: AppSettings::load(),
+                services: manager.get_all_statuses(),
+            }
+        };
+        Ok(crate::runtime::doctor::run_doctor(&ctx))
+    }).await?
 }
 
 /// Reset installation (for testing/debug - deletes runtime directory)
 #[tauri::command]
-pub async fn reset_installation(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn reset_installation(state: State<'_, AppState>) -> Result<String, CamppError> {
     let pm = state.process_manager.clone();
-    do_reset_installation(pm).await
+    do_reset_installation(pm).await.map_err(Into::into)
 }
 
 /// Core reset logic, usable from both Tauri commands and menu handlers
@@ -294,8 +2671,24 @@ pub async fn do_reset_installation(pm: Arc<Mutex<ProcessManager>>) -> Result<Str
     // Kill any lingering processes that may still hold file locks
     kill_runtime_processes();
 
+    // Read-only files cannot be deleted on Windows
+    crate::runtime::hardening::relax_if_hardened();
+
     let paths = crate::runtime::locator::get_app_data_paths()?;
 
+    // Snapshot data and config while every service is stopped, abort the reset if that fails
+    let settings = AppSettings::load();
+    let mut backups = Vec::new();
+    for (dir, source) in [
+        (&paths.mysql_data_dir, "mysql-data"),
+        (&paths.pgsql_data_dir, "pgsql-data"),
+        (&paths.config_dir, "config"),
+    ] {
+        let snapshot = crate::database::backup::snapshot_directory(&settings, dir, source, "reset_installation")
+            .map_err(|e| format!("Backup before reset failed, nothing was deleted: {}", e))?;
+        backups.extend(snapshot);
+    }
+
     // Delete runtime binaries, config, data, and logs — but preserve projects
     for dir in [&paths.runtime_dir, &paths.config_dir, &paths.mysql_data_dir, &paths.pgsql_data_dir, &paths.logs_dir] {
         if dir.exists() {
@@ -309,7 +2702,12 @@ pub async fn do_reset_installation(pm: Arc<Mutex<ProcessManager>>) -> Result<Str
         let _ = fs::remove_file(&settings_path);
     }
 
-    Ok("Installation reset. Run the app again to see first-run wizard.".to_string())
+    let mut message = "Installation reset. Run the app again to see first-run wizard.".to_string();
+    if !backups.is_empty() {
+        let ids: Vec<&str> = backups.iter().map(|b| b.id.as_str()).collect();
+        message.push_str(&format!(" Backups: {}", ids.join(", ")));
+    }
+    Ok(message)
 }
 
 fn kill_runtime_processes() {
@@ -379,18 +2777,39 @@ fn remove_dir_all_with_retry(path: &std::path::Path) -> Result<(), String> {
     }
 }
 
+/// Make runtime binaries read-only and record an integrity manifest
+#[tauri::command]
+pub async fn harden_runtime() -> Result<usize, CamppError> {
+    tokio::task::spawn_blocking(crate::runtime::hardening::harden_runtime)
+        .await?.map_err(Into::into)
+}
+
+/// Restore write permission on runtime binaries, e.g. before a manual upgrade
+#[tauri::command]
+pub async fn relax_runtime() -> Result<usize, CamppError> {
+    tokio::task::spawn_blocking(crate::runtime::hardening::relax_runtime)
+        .await?.map_err(Into::into)
+}
+
+/// Compare runtime binaries with the integrity manifest
+#[tauri::command]
+pub async fn verify_runtime_integrity() -> Result<crate::runtime::hardening::IntegrityReport, CamppError> {
+    tokio::task::spawn_blocking(crate::runtime::hardening::verify_runtime)
+        .await?.map_err(Into::into)
+}
+
 /// Get the runtime directory path
 #[tauri::command]
-pub async fn get_runtime_dir() -> Result<String, String> {
+pub async fn get_runtime_dir() -> Result<String, CamppError> {
     let downloader = RuntimeDownloader::new()?;
     downloader
         .get_runtime_dir()
-        .map(|p| p.to_string_lossy().to_string())
+        .map(|p| p.to_string_lossy().to_string()).map_err(Into::into)
 }
 
 /// Get the installation directory (where the exe is located)
 #[tauri::command]
-pub async fn get_install_dir() -> Result<String, String> {
+pub async fn get_install_dir() -> Result<String, CamppError> {
     #[cfg(target_os = "windows")]
     {
         let exe_path = std::env::current_exe()
@@ -410,39 +2829,123 @@ pub async fn get_install_dir() -> Result<String, String> {
 
 /// Get the download directory path (where ZIP files are stored)
 #[tauri::command]
-pub async fn get_download_dir() -> Result<String, String> {
-    let temp_dir = std::env::temp_dir().join("campp-download");
-    Ok(temp_dir.to_string_lossy().to_string())
+pub async fn get_download_dir() -> Result<String, CamppError> {
+    Ok(crate::runtime::download_cache::download_dir().to_string_lossy().to_string())
+}
+
+/// Delete downloaded archives, keeping the `keep` most recent ones (none by
+/// default) and those protected for offline reinstall
+#[tauri::command]
+pub async fn cleanup_downloads(keep: Option<usize>) -> Result<crate::runtime::download_cache::CleanupReport, CamppError> {
+    tokio::task::spawn_blocking(move || {
+        let policy = AppSettings::load().download_cache;
+        let installed = RuntimeDownloader::new()
+            .map(|d| d.get_installed_components())
+            .unwrap_or_default();
+        crate::runtime::download_cache::cleanup_downloads(keep.unwrap_or(0), &policy, &installed)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+/// Find Caddy, PHP and MariaDB/MySQL binaries already installed on the system
+#[tauri::command]
+pub async fn detect_system_binaries() -> Result<Vec<crate::runtime::system::SystemBinary>, CamppError> {
+    tokio::task::spawn_blocking(crate::runtime::system::detect_system_binaries)
+        .await
+        .map_err(Into::into)
+}
+
+/// Use the system binary at `path` for `component` instead of the downloaded
+/// one, or go back to the downloaded one when `path` is None
+#[tauri::command]
+pub async fn set_binary_override(
+    component: BinaryComponent,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<AppSettings, CamppError> {
+    let binary = match path {
+        Some(path) => {
+            let binary = tokio::task::spawn_blocking(move || {
+                crate::runtime::system::inspect(component, std::path::Path::new(&path))
+            })
+            .await
+            .map_err(|e| format!("Task error: {}", e))??;
+            if let Some(reason) = &binary.incompatible {
+                return Err(CamppError::invalid_input(reason.clone()));
+            }
+            Some(binary.path)
+        }
+        None => None,
+    };
+
+    let mut settings = AppSettings::load();
+    settings.binary_overrides.set(component, binary)?;
+    settings.save()?;
+
+    if let Ok(mut manager) = state.process_manager.lock() {
+        manager.invalidate_runtime_paths();
+    }
+    Ok(settings)
 }
 
 /// Download and install runtime binaries
+///
+/// With `manifest_version`, the published runtime manifest of that version
+/// replaces the bundled runtime-config.json.
 #[tauri::command]
-pub async fn download_runtime(app: tauri::AppHandle) -> Result<String, String> {
+pub async fn download_runtime(
+    app: tauri::AppHandle,
+    manifest_version: Option<String>,
+    php_version: Option<String>,
+    database: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, CamppError> {
     // Ensure config is loaded from Tauri's resource directory
     if let Ok(resource_dir) = app.path().resource_dir() {
         crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
     }
-    let downloader = RuntimeDownloader::new()?;
-    let app_clone = app.clone();
-
-    // Emit progress updates via Tauri events
-    downloader
-        .download_all(Box::new(move |progress| {
-            let _ = app_clone.emit("download-progress", &progress);
+    if let Some(version) = manifest_version {
+        let config = crate::runtime::updates::fetch_manifest(Some(&version)).await?;
+        tracing::info!("Installing from runtime manifest {}", version);
+        crate::runtime::packages::set_runtime_config(config);
+    }
+    let downloader = if php_version.is_some() || database.is_some() {
+        // Remember the choice so later upgrades stay on this line
+        let mut settings = AppSettings::load();
+        if let Some(php_version) = php_version {
+            crate::runtime::upgrade::select_version(&mut settings.package_selection, BinaryComponent::Php, &php_version)?;
+        }
+        if let Some(database) = database {
+            crate::runtime::packages::select_database(&mut settings.package_selection, &database)?;
+        }
+        settings.save()?;
+        RuntimeDownloader::with_packages(settings.package_selection)?
+    } else {
+        RuntimeDownloader::new()?
+    };
 
-            // Store latest progress
-            if let Ok(mut p) = DOWNLOAD_PROGRESS.lock() {
-                *p = Some(progress);
-            }
-        }))
+    let op = Arc::new(Operation::start(OperationKind::Download, true, "Installing runtime binaries"));
+    op.run(downloader.download_all(download_progress(app.clone(), op.clone())))
         .await?;
+    create_welcome_project(&state).await;
 
     Ok("Runtime binaries installed successfully".to_string())
 }
 
+/// Compare installed component versions with a published runtime manifest,
+/// the latest one unless `manifest_version` is given
+#[tauri::command]
+pub async fn check_runtime_updates(manifest_version: Option<String>) -> Result<Vec<crate::runtime::updates::RuntimeUpdate>, CamppError> {
+    let config = crate::runtime::updates::fetch_manifest(manifest_version.as_deref()).await?;
+    let downloader = RuntimeDownloader::new()?;
+    let platform_key = crate::runtime::downloader::Platform::current().url_key();
+    Ok(crate::runtime::updates::available_updates(&config, &downloader.get_installed_components(), &platform_key))
+}
+
 /// Stop all running services (for cleanup on app exit)
 #[tauri::command]
-pub async fn cleanup_all_services(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn cleanup_all_services(state: State<'_, AppState>) -> Result<String, CamppError> {
     let pm = state.process_manager.clone();
 
     tokio::task::spawn_blocking(move || {
@@ -451,46 +2954,93 @@ pub async fn cleanup_all_services(state: State<'_, AppState>) -> Result<String,
 
         manager.stop_all()?;
         Ok("All services stopped".to_string())
-    }).await.map_err(|e| format!("Task error: {}", e))?
+    }).await?
 }
 
 /// Get all available runtime packages
 #[tauri::command]
-pub async fn get_available_packages_cmd() -> Result<PackagesConfig, String> {
+pub async fn get_available_packages_cmd() -> Result<PackagesConfig, CamppError> {
     Ok(crate::runtime::packages::get_available_packages())
 }
 
+/// PHP versions in the runtime manifest that can be installed on this platform
+#[tauri::command]
+pub async fn list_available_php_versions(app: tauri::AppHandle) -> Result<Vec<crate::runtime::packages::AvailableVersion>, CamppError> {
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
+    }
+    let config = crate::runtime::packages::get_config().ok_or("runtime-config.json could not be loaded")?;
+    let platform_keys = crate::runtime::downloader::Platform::current().url_keys();
+    Ok(crate::runtime::packages::available_versions(&config.binaries.php.versions, &platform_keys))
+}
+
+/// MariaDB and MySQL versions in the runtime manifest that can be installed
+/// on this platform, e.g. MariaDB 10.11 LTS next to 11.x
+#[tauri::command]
+pub async fn list_available_database_versions(app: tauri::AppHandle) -> Result<Vec<crate::runtime::packages::AvailableVersion>, CamppError> {
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
+    }
+    let config = crate::runtime::packages::get_config().ok_or("runtime-config.json could not be loaded")?;
+    let platform_keys = crate::runtime::downloader::Platform::current().url_keys();
+    Ok(crate::runtime::packages::available_database_versions(&config, &platform_keys))
+}
+
 /// Download and install runtime binaries with custom package selection
 #[tauri::command]
 pub async fn download_runtime_with_packages(
     package_selection: PackageSelection,
     app: tauri::AppHandle,
-) -> Result<String, String> {
+    state: State<'_, AppState>,
+) -> Result<String, CamppError> {
     // Ensure config is loaded from Tauri's resource directory
     if let Ok(resource_dir) = app.path().resource_dir() {
         crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
     }
     let downloader = RuntimeDownloader::with_packages(package_selection)?;
-    let app_clone = app.clone();
 
-    // Emit progress updates via Tauri events
-    downloader
-        .download_all(Box::new(move |progress| {
-            let _ = app_clone.emit("download-progress", &progress);
+    let op = Arc::new(Operation::start(OperationKind::Download, true, "Installing runtime binaries"));
+    op.run(downloader.download_all(download_progress(app.clone(), op.clone())))
+        .await?;
+    create_welcome_project(&state).await;
 
-            // Store latest progress
-            if let Ok(mut p) = DOWNLOAD_PROGRESS.lock() {
-                *p = Some(progress);
-            }
-        }))
+    Ok("Runtime binaries installed successfully".to_string())
+}
+
+/// Download and install only the given components, leaving the rest of the runtime untouched
+#[tauri::command]
+pub async fn download_runtime_components(
+    components: Vec<BinaryComponent>,
+    package_selection: Option<PackageSelection>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, CamppError> {
+    if components.is_empty() {
+        return Err(CamppError::invalid_input("No components selected"));
+    }
+    // Ensure config is loaded from Tauri's resource directory
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
+    }
+    let downloader = match package_selection {
+        Some(selection) => RuntimeDownloader::with_packages(selection)?,
+        None => RuntimeDownloader::new()?,
+    };
+
+    let op = Arc::new(Operation::start(OperationKind::Download, true, "Installing runtime components"));
+    op.run(downloader.download_components(download_progress(app.clone(), op.clone()), &components))
         .await?;
 
+    if let Ok(mut manager) = state.process_manager.lock() {
+        manager.invalidate_runtime_paths();
+    }
+
     Ok("Runtime binaries installed successfully".to_string())
 }
 
 /// Get the current package selection from settings
 #[tauri::command]
-pub async fn get_package_selection() -> Result<PackageSelection, String> {
+pub async fn get_package_selection() -> Result<PackageSelection, CamppError> {
     let settings = AppSettings::load();
     Ok(settings.package_selection)
 }
@@ -499,7 +3049,7 @@ pub async fn get_package_selection() -> Result<PackageSelection, String> {
 #[tauri::command]
 pub async fn update_package_selection(
     package_selection: PackageSelection,
-) -> Result<(), String> {
+) -> Result<(), CamppError> {
     let mut settings = AppSettings::load();
     settings.package_selection = package_selection;
     settings.save()?;
@@ -512,7 +3062,7 @@ pub async fn update_db_passwords(
     state: State<'_, AppState>,
     mysql_password: String,
     postgres_password: String,
-) -> Result<(), String> {
+) -> Result<(), CamppError> {
     let old_settings = AppSettings::load();
 
     let mysql_changed = old_settings.mysql_root_password != mysql_password;
@@ -538,7 +3088,7 @@ pub async fn update_db_passwords(
         }
 
         Ok::<(), String>(())
-    }).await.map_err(|e| format!("Task error: {}", e))?
+    }).await?.map_err(Into::into)
 }
 
 fn apply_mysql_password(manager: &crate::ProcessManager, password: &str) {
@@ -672,20 +3222,20 @@ fn apply_postgres_password(manager: &crate::ProcessManager, password: &str) {
 
 /// Get the selected package IDs from runtime-config.json
 #[tauri::command]
-pub async fn get_selected_package_ids() -> Result<PackageSelection, String> {
+pub async fn get_selected_package_ids() -> Result<PackageSelection, CamppError> {
     Ok(crate::runtime::packages::get_selected_package_ids())
 }
 
 /// Reload the runtime configuration from runtime-config.json
 #[tauri::command]
-pub async fn reload_runtime_config() -> Result<String, String> {
+pub async fn reload_runtime_config() -> Result<String, CamppError> {
     crate::runtime::packages::reload_runtime_config();
     Ok("Runtime configuration reloaded successfully".to_string())
 }
 
 /// Get the installed runtime versions
 #[tauri::command]
-pub async fn get_installed_versions() -> Result<std::collections::HashMap<String, String>, String> {
+pub async fn get_installed_versions() -> Result<std::collections::HashMap<String, String>, CamppError> {
     let downloader = RuntimeDownloader::new()?;
     let runtime_dir = downloader.get_runtime_dir()?;
 
@@ -710,7 +3260,7 @@ pub async fn get_installed_versions() -> Result<std::collections::HashMap<String
 
 /// Check for existing components before download
 #[tauri::command]
-pub async fn check_existing_components() -> Result<std::collections::HashMap<String, String>, String> {
+pub async fn check_existing_components() -> Result<std::collections::HashMap<String, String>, CamppError> {
     let downloader = RuntimeDownloader::new()?;
     Ok(downloader.get_installed_components())
 }
@@ -721,28 +3271,21 @@ pub async fn download_runtime_with_skip(
     package_selection: PackageSelection,
     skip_list: Vec<String>,
     app: tauri::AppHandle,
-) -> Result<String, String> {
+    state: State<'_, AppState>,
+) -> Result<String, CamppError> {
     // Ensure config is loaded from Tauri's resource directory
     if let Ok(resource_dir) = app.path().resource_dir() {
         crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
     }
     let downloader = RuntimeDownloader::with_packages(package_selection)?;
-    let app_clone = app.clone();
 
     // Convert Vec<String> to Vec<&str> for the skip_list
     let skip_refs: Vec<&str> = skip_list.iter().map(|s| s.as_str()).collect();
 
-    // Emit progress updates via Tauri events
-    downloader
-        .download_all_with_skip(Box::new(move |progress| {
-            let _ = app_clone.emit("download-progress", &progress);
-
-            // Store latest progress
-            if let Ok(mut p) = DOWNLOAD_PROGRESS.lock() {
-                *p = Some(progress);
-            }
-        }), &skip_refs)
+    let op = Arc::new(Operation::start(OperationKind::Download, true, "Installing runtime binaries"));
+    op.run(downloader.download_all_with_skip(download_progress(app.clone(), op.clone()), &skip_refs))
         .await?;
+    create_welcome_project(&state).await;
 
     Ok("Runtime binaries installed successfully".to_string())
 }
@@ -758,19 +3301,21 @@ pub async fn check_system_dependencies() -> DependencyCheckResult {
 pub async fn uninstall_component(
     component: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let valid_components = ["caddy", "php", "mysql", "mariadb", "phpmyadmin", "postgresql", "adminer"];
-    if !valid_components.contains(&component.as_str()) {
-        return Err(format!("Invalid component: {}", component));
-    }
+) -> Result<(), CamppError> {
+    let Some(binary) = BinaryComponent::from_binary_name(&component) else {
+        return Err(format!("Invalid component: {}", component).into());
+    };
 
     // Stop the corresponding service if it maps to one
-    let service_type = match component.as_str() {
-        "caddy" => Some(ServiceType::Caddy),
-        "php" => Some(ServiceType::PhpFpm),
-        "mysql" | "mariadb" => Some(ServiceType::MySQL),
-        "postgresql" => Some(ServiceType::PostgreSQL),
-        _ => None,
+    let service_type = match binary {
+        BinaryComponent::Caddy => Some(ServiceType::Caddy),
+        BinaryComponent::Php => Some(ServiceType::PhpFpm),
+        BinaryComponent::MySQL | BinaryComponent::MariaDB => Some(ServiceType::MySQL),
+        BinaryComponent::PostgreSQL => Some(ServiceType::PostgreSQL),
+        BinaryComponent::Mailpit => Some(ServiceType::Mailpit),
+        BinaryComponent::Minio => Some(ServiceType::Minio),
+        BinaryComponent::Cloudflared => Some(ServiceType::Tunnel),
+        BinaryComponent::PhpMyAdmin | BinaryComponent::Adminer | BinaryComponent::Node => None,
     };
 
     if let Some(st) = service_type {
@@ -784,11 +3329,259 @@ pub async fn uninstall_component(
     }
 
     let downloader = RuntimeDownloader::new()?;
-    downloader.uninstall_component(&component)?;
+    crate::runtime::hardening::relax_if_hardened();
+    let result = downloader.uninstall_component(&component);
+    crate::runtime::hardening::harden_if_enabled();
+    result?;
+
+    if let Ok(mut manager) = state.process_manager.lock() {
+        manager.invalidate_runtime_paths();
+    }
 
     Ok(())
 }
 
+/// Disk space used by each installed component, the database data
+/// directories, logs, backups and the download cache
+#[tauri::command]
+pub async fn get_runtime_disk_usage() -> Result<crate::runtime::disk_usage::DiskUsage, CamppError> {
+    tokio::task::spawn_blocking(crate::runtime::disk_usage::get_disk_usage)
+        .await?
+        .map_err(Into::into)
+}
+
+/// Upgrade an installed component to another version from the manifest,
+/// keeping its data and configs
+///
+/// The service is stopped while its files are swapped and started again
+/// afterwards. MariaDB runs mariadb-upgrade once it is back up.
+#[tauri::command]
+pub async fn upgrade_component(
+    component: BinaryComponent,
+    version: String,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, CamppError> {
+    // Ensure config is loaded from Tauri's resource directory
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
+    }
+    let mut settings = AppSettings::load();
+    let mut selection = settings.package_selection.clone();
+    crate::runtime::upgrade::select_version(&mut selection, component, &version)?;
+    let downloader = RuntimeDownloader::with_packages(selection.clone())?;
+
+    let installed = downloader.get_installed_components();
+    let installed_version = installed.get(component.binary_name()).ok_or(format!("{} is not installed", component.name()))?;
+    crate::runtime::upgrade::check_data_compatible(component, installed_version, &downloader.get_component_version(&component))?;
+
+    let service_type = match component {
+        BinaryComponent::Caddy => Some(ServiceType::Caddy),
+        BinaryComponent::Php => Some(ServiceType::PhpFpm),
+        BinaryComponent::MySQL | BinaryComponent::MariaDB => Some(ServiceType::MySQL),
+        BinaryComponent::PostgreSQL => Some(ServiceType::PostgreSQL),
+        _ => None,
+    };
+
+    // Stop the service so its binaries can be replaced
+    let mut was_running = false;
+    if let Some(st) = service_type {
+        let pm = state.process_manager.clone();
+        was_running = tokio::task::spawn_blocking(move || {
+            let mut manager = pm.lock()
+                .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+            let running = manager.status(st) == ServiceState::Running;
+            if running {
+                manager.stop(st)?;
+            }
+            Ok::<bool, String>(running)
+        }).await.map_err(|e| format!("Task error: {}", e))??;
+    }
+
+    // Snapshot the data directory while the server is stopped, a new major
+    // version may rewrite it in a way the old binaries cannot read
+    let data_source = match component {
+        BinaryComponent::MySQL | BinaryComponent::MariaDB => Some("mysql-data"),
+        BinaryComponent::PostgreSQL => Some("pgsql-data"),
+        _ => None,
+    };
+    let mut snapshot = None;
+    if let Some(source) = data_source {
+        let snapshot_settings = settings.clone();
+        let taken = tokio::task::spawn_blocking(move || {
+            let paths = crate::runtime::locator::get_app_data_paths()?;
+            let dir = if source == "mysql-data" { paths.mysql_data_dir } else { paths.pgsql_data_dir };
+            crate::database::backup::snapshot_directory(&snapshot_settings, &dir, source, "upgrade_component")
+        }).await.map_err(|e| format!("Task error: {}", e))?;
+        match taken {
+            Ok(record) => snapshot = record,
+            Err(e) => {
+                if let Some(st) = service_type.filter(|_| was_running) {
+                    let pm = state.process_manager.clone();
+                    let _ = tokio::task::spawn_blocking(move || {
+                        if let Ok(mut manager) = pm.lock() {
+                            let _ = manager.start(st);
+                        }
+                    }).await;
+                }
+                return Err(format!("Backup before upgrade failed, {} was not upgraded: {}", component.name(), e).into());
+            }
+        }
+    }
+
+    let op = Arc::new(Operation::start(OperationKind::Download, true, format!("Upgrading {}", component.name())));
+    let result = op
+        .run(downloader.upgrade_component(component, download_progress(app.clone(), op.clone())))
+        .await;
+
+    if result.is_ok() {
+        settings.package_selection = selection;
+        settings.save()?;
+    }
+
+    // Restart with the new binaries, or the old ones if the upgrade failed
+    let pm = state.process_manager.clone();
+    let restarted = tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        manager.invalidate_runtime_paths();
+        let Some(st) = service_type.filter(|_| was_running) else {
+            return Ok(None);
+        };
+        manager.start(st)?;
+        if component != BinaryComponent::MariaDB {
+            return Ok(None);
+        }
+        let paths = manager.get_runtime_paths().ok_or("Runtime paths not initialized")?;
+        let settings = AppSettings::load();
+        crate::database::mysql::run_upgrade(&paths, settings.mysql_port, &settings.mysql_root_password).map(Some)
+    }).await.map_err(|e| format!("Task error: {}", e))?;
+
+    result?;
+    match restarted {
+        Ok(Some(output)) => tracing::info!("mariadb-upgrade: {}", output),
+        Ok(None) => {}
+        Err(e) => return Err(format!("{} was upgraded but failed to restart: {}", component.name(), e).into()),
+    }
+
+    let mut message = format!("{} upgraded to {}", component.name(), downloader.get_component_version(&component));
+    if let Some(record) = snapshot {
+        message.push_str(&format!(", data backed up as {}", record.id));
+    }
+    Ok(message)
+}
+
+/// Update phpMyAdmin to `version`, or the newest release in the runtime
+/// config, carrying over its config.inc.php and tmp directory
+///
+/// Caddy serves phpMyAdmin from its versioned directory, so a running Caddy
+/// is restarted to pick up the new path.
+#[tauri::command]
+pub async fn update_phpmyadmin(
+    version: Option<String>,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, CamppError> {
+    // Ensure config is loaded from Tauri's resource directory
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        crate::runtime::packages::load_config_from_resource_dir(&resource_dir);
+    }
+    let version = match version {
+        Some(version) => version,
+        None => {
+            let config = crate::runtime::packages::get_config().ok_or("Runtime config not loaded")?;
+            crate::runtime::upgrade::latest_phpmyadmin(&config)
+                .map(|v| v.id.clone())
+                .ok_or("No phpMyAdmin versions available")?
+        }
+    };
+    let mut settings = AppSettings::load();
+    let mut selection = settings.package_selection.clone();
+    crate::runtime::upgrade::select_version(&mut selection, BinaryComponent::PhpMyAdmin, &version)?;
+    let downloader = RuntimeDownloader::with_packages(selection.clone())?;
+
+    let target = downloader.get_component_version(&BinaryComponent::PhpMyAdmin);
+    match downloader.get_installed_components().get(BinaryComponent::PhpMyAdmin.binary_name()) {
+        None => return Err("phpMyAdmin is not installed".into()),
+        Some(installed) if *installed == target => return Ok(format!("phpMyAdmin {} is already installed", target)),
+        Some(_) => {}
+    }
+
+    let op = Arc::new(Operation::start(OperationKind::Download, true, "Upgrading phpMyAdmin"));
+    op.run(downloader.upgrade_component(BinaryComponent::PhpMyAdmin, download_progress(app.clone(), op.clone())))
+        .await?;
+    settings.package_selection = selection;
+    settings.save()?;
+
+    let pm = state.process_manager.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        manager.invalidate_runtime_paths();
+        if manager.status(ServiceType::Caddy) == ServiceState::Running {
+            manager.restart(ServiceType::Caddy)?;
+        }
+        Ok::<(), String>(())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+    .map_err(|e| format!("phpMyAdmin was updated but Caddy failed to restart: {}", e))?;
+
+    Ok(format!("phpMyAdmin updated to {}", target))
+}
+
+/// Zip logs, redacted settings and configs, versions and service states
+/// into one archive for a bug report
+///
+/// Written to `path`, or to the diagnostics folder of the data directory.
+#[tauri::command]
+pub async fn export_diagnostics(path: Option<String>, state: State<'_, AppState>) -> Result<crate::runtime::diagnostics::DiagnosticsReport, CamppError> {
+    use crate::runtime::diagnostics::{export_bundle, BundleSources, SystemInfo};
+
+    let (services, runtime_paths) = {
+        let mut manager = state.process_manager.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        manager.update_health();
+        let statuses = manager.get_all_statuses();
+        let services: Vec<_> = ServiceType::start_order()
+            .iter()
+            .filter_map(|service| statuses.get(service).cloned())
+            .collect();
+        (services, manager.get_runtime_paths())
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let app_paths = crate::runtime::locator::get_app_data_paths()?;
+        let dest = match path {
+            Some(path) => std::path::PathBuf::from(path),
+            None => app_paths.base_dir.join("diagnostics").join(format!("campp-diagnostics-{}.zip", crate::util::now())),
+        };
+
+        let mut configs = vec![
+            app_paths.config_dir.join("Caddyfile"),
+            app_paths.config_dir.join("php.ini"),
+            app_paths.config_dir.join("php-fpm.conf"),
+            app_paths.pgsql_data_dir.join("postgresql.conf"),
+            app_paths.pgsql_data_dir.join("pg_hba.conf"),
+        ];
+        if let Some(ref paths) = runtime_paths {
+            configs.push(paths.phpmyadmin.join("config.inc.php"));
+        }
+        let sources = BundleSources { logs_dir: app_paths.logs_dir, configs };
+
+        let system = SystemInfo {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            portable: crate::config::data_dir::is_portable(),
+            data_dir: app_paths.base_dir.to_string_lossy().to_string(),
+            installed: RuntimeDownloader::new()?.get_installed_components(),
+            services,
+        };
+        export_bundle(&dest, &sources, &AppSettings::load(), &system)
+    }).await?.map_err(Into::into)
+}
+
 /// Get debug info for troubleshooting (version, paths, config status)
 #[tauri::command]
 pub async fn get_debug_info(app: tauri::AppHandle) -> serde_json::Value {
@@ -826,6 +3619,7 @@ pub async fn get_debug_info(app: tauri::AppHandle) -> serde_json::Value {
         "resourceDirFiles": resource_files,
         "runtimeDir": runtime_dir,
         "configLoaded": config_loaded,
+        "portableMode": crate::config::data_dir::is_portable(),
         "os": std::env::consts::OS,
         "arch": std::env::consts::ARCH,
     })
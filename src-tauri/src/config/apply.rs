@@ -0,0 +1,289 @@
+//! Minimal set of actions to bring running services in line with settings
+//!
+//! Each running service remembers the settings it was started with. The plan
+//! compares those with the saved settings and picks the cheapest action that
+//! applies every changed field: a password change over a client connection,
+//! a Caddyfile reload through the admin API, a restart, or a stop for
+//! services that were disabled. Fields that only matter to CAMPP itself
+//! (auto start, backups, download cache) need no action at all.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::service_args::ServiceArgs;
+use super::AppSettings;
+use crate::process::ServiceType;
+
+/// Ordered from cheapest to most disruptive, the strongest one wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyAction {
+    /// Change the root password over a client connection
+    UpdatePassword,
+    /// Re-apply the generated Caddyfile through the admin API
+    ReloadConfig,
+    Restart,
+    /// The service was disabled in settings
+    Stop,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PendingChange {
+    pub service: ServiceType,
+    pub action: ApplyAction,
+    /// Changed settings fields that call for the action
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedChange {
+    #[serde(flatten)]
+    pub change: PendingChange,
+    /// Set when the action failed, the service keeps its old settings
+    pub error: Option<String>,
+}
+
+/// Emitted as "apply-progress" before each action runs
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplyProgress {
+    /// 1-based
+    pub step: usize,
+    pub total: usize,
+    pub service: ServiceType,
+    pub action: ApplyAction,
+}
+
+/// Outcome of `apply_pending_changes`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ApplyReport {
+    pub changes: Vec<AppliedChange>,
+}
+
+impl ApplyReport {
+    pub fn errors(&self) -> Vec<String> {
+        self.changes
+            .iter()
+            .filter_map(|c| c.error.as_ref().map(|e| format!("{}: {}", c.change.service.display_name(), e)))
+            .collect()
+    }
+}
+
+/// Emitted as "settings-changed" after settings were saved, without secrets
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsChanged {
+    pub old: AppSettings,
+    pub new: AppSettings,
+    /// Top-level fields that differ, e.g. "web_port"
+    pub changed_fields: Vec<String>,
+    /// Actions that still have to run for the new settings to take effect
+    pub pending: Vec<PendingChange>,
+    /// Services among `pending` that need a restart
+    pub restart_required: Vec<ServiceType>,
+}
+
+impl SettingsChanged {
+    pub fn new(old: AppSettings, new: AppSettings, changed_fields: Vec<String>, pending: Vec<PendingChange>) -> Self {
+        let restart_required = pending
+            .iter()
+            .filter(|c| c.action == ApplyAction::Restart)
+            .map(|c| c.service)
+            .collect();
+        Self { old: old.without_secrets(), new: new.without_secrets(), changed_fields, pending, restart_required }
+    }
+}
+
+/// What a changed top-level field requires from each service
+const FIELD_EFFECTS: &[(&str, &[(ServiceType, ApplyAction)])] = &[
+    ("web_port", &[(ServiceType::Caddy, ApplyAction::Restart)]),
+    ("https_port", &[(ServiceType::Caddy, ApplyAction::Restart)]),
+    ("https_redirect", &[(ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("php_port", &[(ServiceType::PhpFpm, ApplyAction::Restart), (ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    // A Caddy reload also rewrites the phpMyAdmin and Adminer configs, and
+    // the welcome project's site carries the MySQL port and password
+    ("mysql_port", &[(ServiceType::MySQL, ApplyAction::Restart), (ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("postgres_port", &[(ServiceType::PostgreSQL, ApplyAction::Restart), (ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("mysql_root_password", &[(ServiceType::MySQL, ApplyAction::UpdatePassword), (ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("postgres_root_password", &[(ServiceType::PostgreSQL, ApplyAction::UpdatePassword), (ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    // Also the FTP root
+    ("project_root", &[(ServiceType::Caddy, ApplyAction::ReloadConfig), (ServiceType::Ftp, ApplyAction::Restart)]),
+    ("phpmyadmin_auth", &[(ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("security_headers", &[(ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("access_log", &[(ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    ("enabled_services", &[(ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    // php.ini names the SMTP port
+    ("mailpit", &[(ServiceType::Mailpit, ApplyAction::Restart), (ServiceType::PhpFpm, ApplyAction::Restart)]),
+    ("memcached", &[(ServiceType::Memcached, ApplyAction::Restart)]),
+    ("minio", &[(ServiceType::Minio, ApplyAction::Restart)]),
+    ("ftp", &[(ServiceType::Ftp, ApplyAction::Restart)]),
+    ("tunnel", &[(ServiceType::Tunnel, ApplyAction::Restart)]),
+    // The extra PHP versions run next to the main PHP and stop and start with it
+    ("php_versions", &[(ServiceType::PhpFpm, ApplyAction::Restart), (ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    (
+        "binary_overrides",
+        &[
+            (ServiceType::Caddy, ApplyAction::Restart),
+            (ServiceType::PhpFpm, ApplyAction::Restart),
+            (ServiceType::MySQL, ApplyAction::Restart),
+        ],
+    ),
+    // Project hosts; set_domain_tld moves the projects and save_settings
+    // refuses the field, this covers switching to a profile with another TLD
+    ("domain_tld", &[(ServiceType::Caddy, ApplyAction::ReloadConfig)]),
+    // The wildcard DNS is not a service, apply_changes restarts it when its
+    // port or the TLD changed
+    ("dns", &[]),
+    (
+        "bind_address",
+        &[
+            (ServiceType::Caddy, ApplyAction::ReloadConfig),
+            (ServiceType::MySQL, ApplyAction::Restart),
+            (ServiceType::PostgreSQL, ApplyAction::Restart),
+        ],
+    ),
+];
+
+/// Plan the actions for `running` services, each paired with the settings it
+/// was started with
+///
+/// Stops come first, dependents before backends, then the other actions in
+/// start order so Caddy is reloaded against backends that are already up.
+pub fn plan_changes(running: &[(ServiceType, AppSettings)], pending: &AppSettings) -> Vec<PendingChange> {
+    let mut changes: Vec<PendingChange> = Vec::new();
+
+    for (service, applied) in running {
+        let service = *service;
+        let mut change: Option<PendingChange> = None;
+        let mut require = |action: ApplyAction, field: &str| {
+            let c = change.get_or_insert_with(|| PendingChange { service, action, fields: Vec::new() });
+            c.action = c.action.max(action);
+            c.fields.push(field.to_string());
+        };
+
+        if !pending.is_enabled(service) {
+            require(ApplyAction::Stop, "enabled_services");
+        }
+        // php.ini sends mail to Mailpit and loads the memcached extension
+        // only while they are enabled
+        let php_ini_changed = [ServiceType::Mailpit, ServiceType::Memcached]
+            .into_iter()
+            .any(|s| applied.is_enabled(s) != pending.is_enabled(s));
+        if service == ServiceType::PhpFpm && php_ini_changed {
+            require(ApplyAction::Restart, "enabled_services");
+        }
+
+        for field in changed_fields(applied, pending) {
+            let effects = FIELD_EFFECTS.iter().find(|(f, _)| *f == field).map(|(_, e)| *e).unwrap_or(&[]);
+            for (_, action) in effects.iter().filter(|(s, _)| *s == service) {
+                require(*action, &field);
+            }
+        }
+
+        if applied.extra_args.get(service) != pending.extra_args.get(service) {
+            require(ApplyAction::Restart, ServiceArgs::field(service));
+        }
+
+        changes.extend(change);
+    }
+
+    let position = |s: ServiceType| ServiceType::start_order().iter().position(|o| *o == s).unwrap_or(0);
+    changes.sort_by_key(|c| match c.action {
+        ApplyAction::Stop => (0, usize::MAX - position(c.service)),
+        _ => (1, position(c.service)),
+    });
+    changes
+}
+
+/// Top-level settings fields whose values differ
+pub fn changed_fields(applied: &AppSettings, pending: &AppSettings) -> Vec<String> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(applied), serde_json::to_value(pending)) else {
+        return Vec::new();
+    };
+    new.into_iter()
+        .filter(|(key, value)| old.get(key) != Some(value))
+        .map(|(key, _)| key)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running(settings: &AppSettings) -> Vec<(ServiceType, AppSettings)> {
+        ServiceType::start_order()
+            .into_iter()
+            .filter(|s| settings.is_enabled(*s))
+            .map(|s| (s, settings.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_minimal_actions() {
+        let applied = AppSettings::default();
+        assert!(plan_changes(&running(&applied), &applied).is_empty());
+
+        // Harmless fields need nothing, headers only a Caddy reload
+        let pending = AppSettings { auto_backup: false, security_headers: Vec::new(), ..applied.clone() };
+        let plan = plan_changes(&running(&applied), &pending);
+        assert_eq!(plan, vec![PendingChange {
+            service: ServiceType::Caddy,
+            action: ApplyAction::ReloadConfig,
+            fields: vec!["security_headers".to_string()],
+        }]);
+
+        // A PHP port change restarts PHP before reloading Caddy
+        let pending = AppSettings { php_port: 9001, ..applied.clone() };
+        let plan = plan_changes(&running(&applied), &pending);
+        let actions: Vec<_> = plan.iter().map(|c| (c.service, c.action)).collect();
+        assert_eq!(actions, vec![
+            (ServiceType::PhpFpm, ApplyAction::Restart),
+            (ServiceType::Caddy, ApplyAction::ReloadConfig),
+        ]);
+
+        // A MySQL port change restarts MySQL, Caddy only needs a reload
+        let pending = AppSettings { mysql_port: 3310, ..applied.clone() };
+        let plan = plan_changes(&running(&applied), &pending);
+        let actions: Vec<_> = plan.iter().map(|c| (c.service, c.action)).collect();
+        assert_eq!(actions, vec![
+            (ServiceType::MySQL, ApplyAction::Restart),
+            (ServiceType::Caddy, ApplyAction::ReloadConfig),
+        ]);
+    }
+
+    #[test]
+    fn test_plan_strongest_action_and_stops_first() {
+        let applied = AppSettings::default();
+        let mut pending = AppSettings { web_port: 8081, security_headers: Vec::new(), ..applied.clone() };
+        pending.enabled_services.retain(|s| *s != ServiceType::PostgreSQL);
+        pending.extra_args.mysql = vec!["--max-connections=50".to_string()];
+
+        let plan = plan_changes(&running(&applied), &pending);
+        assert_eq!(plan[0].service, ServiceType::PostgreSQL);
+        assert_eq!(plan[0].action, ApplyAction::Stop);
+        assert_eq!(plan[1].service, ServiceType::MySQL);
+        assert_eq!(plan[1].fields, vec!["extra_args.mysql"]);
+
+        let caddy = plan.iter().find(|c| c.service == ServiceType::Caddy).unwrap();
+        assert_eq!(caddy.action, ApplyAction::Restart);
+        assert!(caddy.fields.contains(&"security_headers".to_string()));
+        assert!(!plan.iter().any(|c| c.service == ServiceType::PhpFpm));
+
+        // Enabling Mailpit changes php.ini
+        let mut pending = applied.clone();
+        pending.enabled_services.push(ServiceType::Mailpit);
+        let plan = plan_changes(&running(&applied), &pending);
+        assert_eq!(plan.iter().map(|c| (c.service, c.action)).collect::<Vec<_>>(), vec![
+            (ServiceType::PhpFpm, ApplyAction::Restart),
+            (ServiceType::Caddy, ApplyAction::ReloadConfig),
+        ]);
+    }
+
+    #[test]
+    fn test_settings_changed_without_secrets() {
+        let old = AppSettings::default();
+        let new = AppSettings { mysql_root_password: "secret".to_string(), ..old.clone() };
+        let fields = changed_fields(&old, &new);
+        let event = SettingsChanged::new(old, new, fields, Vec::new());
+        assert_eq!(event.changed_fields, vec!["mysql_root_password".to_string()]);
+        assert!(!serde_json::to_string(&event).unwrap().contains("secret"));
+    }
+}
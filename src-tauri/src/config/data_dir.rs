@@ -0,0 +1,64 @@
+//! Where CAMPP keeps its data
+//!
+//! By default everything lives under the platform data directory (beside the
+//! executable on Windows). `CAMPP_DATA_DIR` moves it anywhere. Portable mode,
+//! enabled by a `campp.portable` file next to the executable, keeps runtime,
+//! config, databases, logs and projects in `campp-data/` beside it so CAMPP
+//! can run from a USB stick.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const DATA_DIR_VAR: &str = "CAMPP_DATA_DIR";
+/// Marker file that turns portable mode on
+pub const PORTABLE_MARKER: &str = "campp.portable";
+/// Data directory created beside the executable in portable mode
+pub const PORTABLE_DATA_DIR: &str = "campp-data";
+
+#[derive(Debug, Clone)]
+struct DataDirOverride {
+    path: PathBuf,
+    portable: bool,
+}
+
+/// Data directory replacing the platform default, if any
+///
+/// Resolved once at startup. `CAMPP_DATA_DIR` wins over portable mode.
+pub fn data_dir_override() -> Option<PathBuf> {
+    resolved().as_ref().map(|o| o.path.clone())
+}
+
+/// Whether CAMPP runs in portable mode
+pub fn is_portable() -> bool {
+    resolved().as_ref().is_some_and(|o| o.portable)
+}
+
+fn resolved() -> &'static Option<DataDirOverride> {
+    static DATA_DIR: OnceLock<Option<DataDirOverride>> = OnceLock::new();
+    DATA_DIR.get_or_init(|| {
+        if let Some(path) = std::env::var_os(DATA_DIR_VAR).filter(|v| !v.is_empty()) {
+            return Some(DataDirOverride { path: PathBuf::from(path), portable: false });
+        }
+        let exe = std::env::current_exe().ok()?;
+        portable_data_dir(exe.parent()?).map(|path| DataDirOverride { path, portable: true })
+    })
+}
+
+fn portable_data_dir(exe_dir: &Path) -> Option<PathBuf> {
+    exe_dir.join(PORTABLE_MARKER).is_file().then(|| exe_dir.join(PORTABLE_DATA_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_portable_marker() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(portable_data_dir(temp_dir.path()), None);
+
+        std::fs::write(temp_dir.path().join(PORTABLE_MARKER), "").unwrap();
+        assert_eq!(portable_data_dir(temp_dir.path()), Some(temp_dir.path().join("campp-data")));
+    }
+}
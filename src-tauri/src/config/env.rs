@@ -0,0 +1,150 @@
+//! Environment variable overrides
+//!
+//! `CAMPP_*` variables are read once at startup and layered over
+//! settings.json when settings are loaded. They are never saved: `save`
+//! writes the on-disk value back for every field still holding its override,
+//! so CI scripts can run the stack on other ports without touching the user's
+//! configuration. `CAMPP_DATA_DIR` is handled by `data_dir`.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+use crate::process::ServiceType;
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Port,
+    Text,
+    Bool,
+    /// Comma-separated service names, e.g. "caddy,php-fpm"
+    Services,
+}
+
+/// Environment variable, settings field and value type
+const VARIABLES: &[(&str, &str, Kind)] = &[
+    ("CAMPP_WEB_PORT", "web_port", Kind::Port),
+    ("CAMPP_PHP_PORT", "php_port", Kind::Port),
+    ("CAMPP_MYSQL_PORT", "mysql_port", Kind::Port),
+    ("CAMPP_POSTGRES_PORT", "postgres_port", Kind::Port),
+    ("CAMPP_PROJECT_ROOT", "project_root", Kind::Text),
+    ("CAMPP_BIND_ADDRESS", "bind_address", Kind::Text),
+    ("CAMPP_MYSQL_ROOT_PASSWORD", "mysql_root_password", Kind::Text),
+    ("CAMPP_POSTGRES_ROOT_PASSWORD", "postgres_root_password", Kind::Text),
+    ("CAMPP_AUTO_START", "auto_start_services", Kind::Bool),
+    ("CAMPP_ENABLED_SERVICES", "enabled_services", Kind::Services),
+];
+
+/// An active override, as shown in the UI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvOverride {
+    pub variable: String,
+    /// Settings field name, e.g. "web_port"
+    pub field: String,
+    pub value: Value,
+}
+
+/// Overrides read from the process environment at startup
+pub fn active_overrides() -> &'static [EnvOverride] {
+    static OVERRIDES: OnceLock<Vec<EnvOverride>> = OnceLock::new();
+    OVERRIDES.get_or_init(|| read_overrides(|name| std::env::var(name).ok()))
+}
+
+/// Layer the overrides over serialized settings
+pub fn apply(settings: &mut Map<String, Value>, overrides: &[EnvOverride]) {
+    for o in overrides {
+        settings.insert(o.field.clone(), o.value.clone());
+    }
+}
+
+/// Put the saved value back for every field that still holds its override
+pub fn strip(settings: &mut Map<String, Value>, saved: &Map<String, Value>, overrides: &[EnvOverride]) {
+    for o in overrides {
+        if settings.get(&o.field) != Some(&o.value) {
+            continue;
+        }
+        match saved.get(&o.field) {
+            Some(value) => settings.insert(o.field.clone(), value.clone()),
+            None => settings.remove(&o.field),
+        };
+    }
+}
+
+fn read_overrides(get: impl Fn(&str) -> Option<String>) -> Vec<EnvOverride> {
+    let mut overrides = Vec::new();
+    for (variable, field, kind) in VARIABLES {
+        let Some(raw) = get(variable) else { continue };
+        match parse(*kind, raw.trim()) {
+            Ok(value) => overrides.push(EnvOverride {
+                variable: variable.to_string(),
+                field: field.to_string(),
+                value,
+            }),
+            Err(e) => tracing::warn!("Ignoring {}: {}", variable, e),
+        }
+    }
+    overrides
+}
+
+fn parse(kind: Kind, raw: &str) -> Result<Value, String> {
+    match kind {
+        Kind::Port => match raw.parse::<u16>() {
+            Ok(port) if port > 0 => Ok(Value::from(port)),
+            _ => Err(format!("'{}' is not a valid port", raw)),
+        },
+        Kind::Text if raw.is_empty() => Err("value is empty".to_string()),
+        Kind::Text => Ok(Value::from(raw)),
+        Kind::Bool => match raw.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Value::Bool(true)),
+            "0" | "false" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err(format!("'{}' is not a boolean", raw)),
+        },
+        Kind::Services => {
+            let services = raw
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    serde_json::from_value::<ServiceType>(Value::from(s))
+                        .map_err(|_| format!("unknown service '{}'", s))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            serde_json::to_value(services).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppSettings;
+
+    #[test]
+    fn test_overrides_layer_and_strip() {
+        let overrides = read_overrides(|name| match name {
+            "CAMPP_WEB_PORT" => Some("8181".to_string()),
+            "CAMPP_ENABLED_SERVICES" => Some("caddy, php-fpm".to_string()),
+            "CAMPP_MYSQL_PORT" => Some("not-a-port".to_string()),
+            _ => None,
+        });
+        assert_eq!(overrides.len(), 2);
+
+        let saved = AppSettings::default();
+        let Value::Object(saved_json) = serde_json::to_value(&saved).unwrap() else { unreachable!() };
+        let mut json = saved_json.clone();
+        apply(&mut json, &overrides);
+        let mut effective: AppSettings = serde_json::from_value(Value::Object(json)).unwrap();
+        assert_eq!(effective.web_port, 8181);
+        assert_eq!(effective.enabled_services, vec![ServiceType::Caddy, ServiceType::PhpFpm]);
+        assert_eq!(effective.mysql_port, saved.mysql_port);
+
+        // The user changed the PHP port, the overridden web port is not saved
+        effective.php_port = 9010;
+        let Value::Object(mut json) = serde_json::to_value(&effective).unwrap() else { unreachable!() };
+        strip(&mut json, &saved_json, &overrides);
+        let written: AppSettings = serde_json::from_value(Value::Object(json)).unwrap();
+        assert_eq!(written.web_port, saved.web_port);
+        assert_eq!(written.enabled_services, saved.enabled_services);
+        assert_eq!(written.php_port, 9010);
+    }
+}
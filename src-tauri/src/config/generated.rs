@@ -0,0 +1,104 @@
+//! Fingerprints of the config files CAMPP generates
+//!
+//! Each generated file's SHA-256 is recorded in `.campp-generated.json` next
+//! to it. A file whose content no longer matches was edited by hand, which
+//! lets the config watcher tell user edits from CAMPP's own rewrites.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::AppSettings;
+
+const FINGERPRINT_FILE: &str = ".campp-generated.json";
+
+/// Write a generated config file and record its fingerprint
+pub fn write_generated(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let (Some(fingerprints_path), Some(name)) = (fingerprints_path(path), file_key(path)) else {
+        return Ok(());
+    };
+    let mut fingerprints = read_fingerprints(&fingerprints_path);
+    fingerprints.insert(name, hash(content.as_bytes()));
+    if let Ok(json) = serde_json::to_string_pretty(&fingerprints) {
+        let _ = fs::write(&fingerprints_path, json);
+    }
+    Ok(())
+}
+
+/// Whether `path` exists and differs from what CAMPP last generated
+///
+/// Files without a recorded fingerprint predate the tracking and count as
+/// generated.
+pub fn is_hand_edited(path: &Path) -> bool {
+    let (Some(fingerprints_path), Some(name)) = (fingerprints_path(path), file_key(path)) else {
+        return false;
+    };
+    let Some(expected) = read_fingerprints(&fingerprints_path).remove(&name) else {
+        return false;
+    };
+    match fs::read(path) {
+        Ok(content) => hash(&content) != expected,
+        Err(_) => false,
+    }
+}
+
+/// Whether a service start should keep `path` instead of regenerating it
+///
+/// Hand edits are only preserved while config watching is enabled, otherwise
+/// settings always win.
+pub fn keep_hand_edited(path: &Path, settings: &AppSettings) -> bool {
+    let keep = settings.watch_config_files && is_hand_edited(path);
+    if keep {
+        tracing::info!("Keeping hand-edited {}", path.display());
+    }
+    keep
+}
+
+/// Fingerprint file recording `path`
+pub(crate) fn fingerprints_path(path: &Path) -> Option<PathBuf> {
+    path.parent().map(|dir| dir.join(FINGERPRINT_FILE))
+}
+
+fn file_key(path: &Path) -> Option<String> {
+    path.file_name().map(|n| n.to_string_lossy().to_string())
+}
+
+fn read_fingerprints(path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn hash(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_hand_edit_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("php.ini");
+
+        fs::write(&path, "memory_limit = 128M\n").unwrap();
+        assert!(!is_hand_edited(&path), "untracked files count as generated");
+
+        write_generated(&path, "memory_limit = 256M\n").unwrap();
+        assert!(!is_hand_edited(&path));
+
+        fs::write(&path, "memory_limit = 1G\n").unwrap();
+        assert!(is_hand_edited(&path));
+
+        let watching = AppSettings { watch_config_files: true, ..Default::default() };
+        assert!(keep_hand_edited(&path, &watching));
+        assert!(!keep_hand_edited(&path, &AppSettings::default()));
+    }
+}
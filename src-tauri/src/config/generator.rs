@@ -1,9 +1,21 @@
+use crate::config::{AccessLogFormat, AppSettings};
+use crate::process::ServiceType;
+use crate::projects::manifest::ProjectManifest;
+use crate::projects::{Framework, Project};
 use crate::runtime::locator::RuntimePaths;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn generate_caddyfile(path: &PathBuf, paths: &RuntimePaths, port: u16, php_port: u16) -> Result<(), String> {
+pub fn generate_caddyfile(path: &Path, paths: &RuntimePaths, port: u16, php_port: u16, settings: &AppSettings, projects: &[Project]) -> Result<(), String> {
+    super::generated::write_generated(path, &caddyfile_content(paths, port, php_port, settings, projects)?)
+}
+
+/// Caddyfile for `settings`, without writing it
+///
+/// Every project gets its own site block on `<name>.localhost`, its
+/// registered domain and its aliases.
+pub fn caddyfile_content(paths: &RuntimePaths, port: u16, php_port: u16, settings: &AppSettings, projects: &[Project]) -> Result<String, String> {
     let projects_raw = paths.projects_dir
         .to_str()
         .ok_or("Invalid project path")?;
@@ -11,17 +23,33 @@ pub fn generate_caddyfile(path: &PathBuf, paths: &RuntimePaths, port: u16, php_p
     if projects_raw.contains('"') || projects_raw.contains('\n') || projects_raw.contains('}') || projects_raw.contains('{') {
         return Err("Invalid project path: contains characters not allowed in Caddyfile".to_string());
     }
-    let projects = projects_raw.replace('\\', "/");
-    let log_file = paths.logs_dir.join("caddy-access.log")
-        .to_str()
-        .ok_or("Invalid log path")?
-        .replace('\\', "/");
+    let projects_root = projects_raw.replace('\\', "/");
 
     let mut content = String::new();
-    content.push_str(&format!("http://localhost:{} {{\n", port));
+    content.push_str("# Global options\n");
+    content.push_str("{\n");
+    content.push_str(&format!("    admin {}\n", crate::process::caddy_admin::CADDY_ADMIN_ADDR));
+    // Request counters served on the admin endpoint at /metrics
+    content.push_str("    metrics\n");
+    // Certificates for HTTPS sites come from the CAMPP local CA
+    content.push_str(&crate::ca::global_options(&paths.config_dir)?);
+    content.push_str(&format!("    https_port {}\n", settings.https_port));
+    // Caddy's automatic redirects would listen on port 80
+    content.push_str("    auto_https disable_redirects\n");
+    content.push_str("}\n\n");
+    if settings.is_lan_exposed() {
+        // Requests from the LAN carry the machine's IP or name as Host
+        content.push_str(&format!("http://:{} {{\n", port));
+    } else {
+        content.push_str(&format!("http://localhost:{} {{\n", port));
+    }
+    content.push_str(&format!("    bind {}\n\n", settings.listen_addresses().join(" ")));
 
-    // Add phpMyAdmin route only if installed
-    if paths.phpmyadmin.join("index.php").exists() {
+    // Without PHP only static files can be served
+    let php_enabled = settings.is_enabled(ServiceType::PhpFpm);
+
+    // Add phpMyAdmin route only if installed and usable
+    if php_enabled && settings.is_enabled(ServiceType::MySQL) && paths.phpmyadmin.join("index.php").exists() {
         let phpmyadmin = paths.phpmyadmin
             .to_str()
             .ok_or("Invalid phpMyAdmin path")?
@@ -31,6 +59,11 @@ pub fn generate_caddyfile(path: &PathBuf, paths: &RuntimePaths, port: u16, php_p
         content.push_str("\n");
         content.push_str("    # Handle phpMyAdmin requests - handle_path strips the /phpmyadmin prefix\n");
         content.push_str("    handle_path /phpmyadmin/* {\n");
+        if let Some(ref auth) = settings.phpmyadmin_auth {
+            content.push_str("        basic_auth {\n");
+            content.push_str(&format!("            {} {}\n", auth.username, auth.password_hash));
+            content.push_str("        }\n");
+        }
         content.push_str(&format!("        root * \"{}\"\n", phpmyadmin));
         content.push_str(&format!("        php_fastcgi 127.0.0.1:{}\n", php_port));
         content.push_str("        file_server browse\n");
@@ -40,7 +73,8 @@ pub fn generate_caddyfile(path: &PathBuf, paths: &RuntimePaths, port: u16, php_p
 
     // Add Adminer route if directory exists
     let adminer_php = paths.adminer.join("adminer.php");
-    if adminer_php.exists() {
+    let any_database = settings.is_enabled(ServiceType::MySQL) || settings.is_enabled(ServiceType::PostgreSQL);
+    if php_enabled && any_database && adminer_php.exists() {
         let adminer = paths.adminer
             .to_str()
             .ok_or("Invalid Adminer path")?
@@ -57,48 +91,356 @@ pub fn generate_caddyfile(path: &PathBuf, paths: &RuntimePaths, port: u16, php_p
         content.push_str("    }\n");
         content.push_str("\n");
     }
+    push_project_routes(&mut content, &paths.projects_dir, php_port, settings, projects);
     content.push_str("    # Root directory for serving files (default project root)\n");
-    content.push_str(&format!("    root * \"{}\"\n", projects));
-    content.push_str("\n");
-    content.push_str("    # Serve PHP files via FastCGI\n");
-    content.push_str(&format!("    php_fastcgi 127.0.0.1:{} {{\n", php_port));
-    content.push_str("        index index.php\n");
-    content.push_str("    }\n");
+    content.push_str(&format!("    root * \"{}\"\n", projects_root));
     content.push_str("\n");
+    if php_enabled {
+        content.push_str("    # Serve PHP files via FastCGI\n");
+        content.push_str(&format!("    php_fastcgi 127.0.0.1:{} {{\n", php_port));
+        content.push_str("        index index.php\n");
+        content.push_str("    }\n");
+        content.push_str("\n");
+    }
     content.push_str("    # File server for project files\n");
     content.push_str("    file_server browse\n");
     content.push_str("\n");
-    content.push_str("    # Logging\n");
-    content.push_str("    log {\n");
-    content.push_str(&format!("        output file \"{}\"\n", log_file));
-    content.push_str("        format json\n");
-    content.push_str("    }\n");
-    content.push_str("\n");
+    push_access_log(&mut content, paths, settings, "localhost")?;
     content.push_str("    # Encode responses\n");
     content.push_str("    encode gzip\n");
-    content.push_str("\n");
-    content.push_str("    # Security headers\n");
-    content.push_str("    header {\n");
-    content.push_str("        X-Content-Type-Options nosniff\n");
-    content.push_str("        X-Frame-Options SAMEORIGIN\n");
-    content.push_str("        Referrer-Policy no-referrer\n");
-    content.push_str("    }\n");
+    if !settings.security_headers.is_empty() {
+        content.push_str("\n");
+        content.push_str("    # Security headers\n");
+        content.push_str("    header {\n");
+        for header in &settings.security_headers {
+            content.push_str(&format!("        {} \"{}\"\n", header.name, header.value.replace('"', "\\\"")));
+        }
+        content.push_str("    }\n");
+    }
     content.push_str("}\n");
 
-    let mut file = File::create(path)
-        .map_err(|e| format!("Failed to create Caddyfile: {}", e))?;
-    file.write_all(content.as_bytes())
-        .map_err(|e| format!("Failed to write Caddyfile: {}", e))?;
+    for project in projects {
+        // A project with an odd path must not keep Caddy from starting
+        if let Err(e) = push_project_site(&mut content, paths, port, php_port, settings, project) {
+            tracing::warn!("Skipping project '{}': {}", project.name, e);
+        }
+    }
+
+    Ok(content)
+}
+
+/// Append the site block serving `project` on its own host name
+///
+/// `php_fastcgi` falls back to `index.php` for paths that are not files,
+/// which is the front controller rewrite frameworks such as Laravel expect.
+///
+/// The project's `campp.json` can move the document root and adds its
+/// `env` settings as FastCGI parameters.
+fn push_project_site(content: &mut String, paths: &RuntimePaths, port: u16, php_port: u16, settings: &AppSettings, project: &Project) -> Result<(), String> {
+    let manifest = project.manifest().unwrap_or_default();
+    let docroot = caddy_docroot(project, &manifest)?;
+    let host = crate::projects::site_host(&project.name);
+    let mut hosts = vec![host.clone()];
+    // With the `localhost` TLD the domain is the site host itself
+    for hostname in project.hostnames() {
+        if !hosts.contains(&hostname) {
+            hosts.push(hostname);
+        }
+    }
+    let addresses: Vec<String> = hosts
+        .iter()
+        .map(|host| if project.https {
+            crate::projects::https_origin(host, settings.https_port)
+        } else {
+            format!("http://{}:{}", host, port)
+        })
+        .collect();
+
+    let body = match project.dev_server_port {
+        Some(port) => dev_server_body(port),
+        None => project_site_body(paths, php_port, settings, project, &manifest, &docroot, &host)?,
+    };
+
+    content.push_str(&format!("\n# Project {}\n", project.name));
+    content.push_str(&format!("{} {{\n", addresses.join(", ")));
+    content.push_str(&format!("    bind {}\n", settings.listen_addresses().join(" ")));
+    if project.https {
+        content.push_str("    tls internal\n");
+    }
+    content.push('\n');
+    content.push_str(&body);
+    content.push_str("}\n");
+
+    if project.https && project.https_redirect.unwrap_or(settings.https_redirect) {
+        let addresses: Vec<String> = hosts.iter().map(|host| format!("http://{}:{}", host, port)).collect();
+        content.push_str(&format!("\n# Project {}, HTTP to HTTPS\n", project.name));
+        content.push_str(&format!("{} {{\n", addresses.join(", ")));
+        content.push_str(&format!("    bind {}\n", settings.listen_addresses().join(" ")));
+        content.push_str(&format!(
+            "    redir {}{{uri}} 308\n",
+            crate::projects::https_origin("{host}", settings.https_port)
+        ));
+        content.push_str("}\n");
+    }
 
+    if let Some(share) = &project.lan_share {
+        if share.is_bindable() {
+            // Any Host, a phone only knows the IP
+            content.push_str(&format!("\n# Project {} shared on the LAN\n", project.name));
+            content.push_str(&format!("http://:{} {{\n", share.port));
+            content.push_str(&format!("    bind {}\n\n", share.address));
+            content.push_str(&body);
+            content.push_str("}\n");
+        } else {
+            tracing::warn!("Not sharing project '{}': {} is no longer a local address", project.name, share.address);
+        }
+    }
     Ok(())
 }
 
-pub fn generate_php_ini(path: &PathBuf, paths: &RuntimePaths) -> Result<(), String> {
+/// Site block directives proxying to a running dev server
+///
+/// Dev servers like Vite only answer their own host name.
+fn dev_server_body(port: u16) -> String {
+    format!("    reverse_proxy localhost:{} {{\n        header_up Host {{upstream_hostport}}\n    }}\n", port)
+}
+
+/// Directives of a project's site block, from the document root on
+fn project_site_body(paths: &RuntimePaths, php_port: u16, settings: &AppSettings, project: &Project, manifest: &ProjectManifest, docroot: &str, host: &str) -> Result<String, String> {
+    let mut block = String::new();
+    block.push_str(&format!("    root * \"{}\"\n", docroot));
+    if settings.is_enabled(ServiceType::PhpFpm) && project.framework != Framework::Static {
+        let php_port = project_php_port(settings, php_port, project, manifest);
+        let mut env = project.php_env(Some(manifest));
+        if project.name == crate::projects::welcome::WELCOME_PROJECT {
+            env.extend(crate::projects::welcome::database_env(settings));
+        }
+        let params: Vec<(String, String)> = env.into_iter().collect();
+        if params.is_empty() {
+            block.push_str(&format!("    php_fastcgi 127.0.0.1:{}\n", php_port));
+        } else {
+            block.push_str(&format!("    php_fastcgi 127.0.0.1:{} {{\n", php_port));
+            for (key, value) in params {
+                block.push_str(&format!("        env {} {}\n", key, caddy_quote(&value)));
+            }
+            block.push_str("    }\n");
+        }
+    }
+    push_rewrite_preset(&mut block, project, "    ");
+    push_spa_fallback(&mut block, project, "    ");
+    block.push_str("    file_server\n");
+    block.push('\n');
+    for (source, snippet) in project.caddy_snippets() {
+        // A broken snippet must not take the whole site down
+        if let Err(e) = check_snippet(&snippet) {
+            tracing::warn!("Ignoring Caddy directives of project '{}' from {}: {}", project.name, source, e);
+            continue;
+        }
+        block.push_str(&format!("    # From {}\n", source));
+        for line in snippet.lines() {
+            if line.trim().is_empty() {
+                block.push('\n');
+            } else {
+                block.push_str(&format!("    {}\n", line.trim_end()));
+            }
+        }
+        block.push('\n');
+    }
+    push_access_log(&mut block, paths, settings, host)?;
+    block.push_str("    encode gzip\n");
+    Ok(block)
+}
+
+/// `value` as a single Caddyfile token
+///
+/// Project settings are validated to need no escaping, the database password
+/// of the welcome project is not: backticks take it verbatim.
+fn caddy_quote(value: &str) -> String {
+    if !value.contains(['"', '\\']) {
+        format!("\"{}\"", value)
+    } else if !value.contains('`') {
+        format!("`{}`", value)
+    } else {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    }
+}
+
+/// Directives a snippet may not use: `import` reads other files and `root`
+/// would serve something other than the project's folder
+const FORBIDDEN_SNIPPET_DIRECTIVES: [&str; 2] = ["import", "root"];
+
+/// Check that `snippet` only holds directives for one site block
+///
+/// Its braces must balance so it cannot close the site block and open
+/// another one, and it may not use `import` or `root`; `caddy validate`
+/// checks the directives themselves.
+pub fn check_snippet(snippet: &str) -> Result<(), String> {
+    let mut depth = 0i32;
+    for (number, line) in snippet.lines().enumerate() {
+        let directive = line.split_whitespace().next().unwrap_or_default();
+        if FORBIDDEN_SNIPPET_DIRECTIVES.contains(&directive) {
+            return Err(format!("Line {}: '{}' is not allowed in project directives", number + 1, directive));
+        }
+        let mut quoted = false;
+        let mut escaped = false;
+        let mut prev = ' ';
+        for c in line.chars() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => quoted = !quoted,
+                // Comments start a token, `{path}#top` is not one
+                '#' if !quoted && prev.is_whitespace() => break,
+                '{' if !quoted => depth += 1,
+                '}' if !quoted => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return Err(format!("Line {}: '}}' closes a block that was not opened", number + 1));
+                    }
+                }
+                _ => {}
+            }
+            prev = c;
+        }
+    }
+    if depth != 0 {
+        return Err("A '{' is never closed".to_string());
+    }
+    Ok(())
+}
+
+/// FastCGI port of the PHP version `project` is pinned to
+///
+/// A pinned version without its own listener falls back to the main PHP on
+/// `php_port`.
+fn project_php_port(settings: &AppSettings, php_port: u16, project: &Project, manifest: &ProjectManifest) -> u16 {
+    project
+        .wanted_php(Some(manifest))
+        .and_then(|version| crate::process::php_pool::backend_port(settings, php_port, &version))
+        .unwrap_or(php_port)
+}
+
+/// Directives the project's framework needs besides `php_fastcgi`
+///
+/// Laravel and Symfony only need the front controller fallback that
+/// `php_fastcgi` already does. WordPress lets anyone upload files, so
+/// scripts under `wp-content/uploads` are never run.
+fn push_rewrite_preset(content: &mut String, project: &Project, indent: &str) {
+    if project.framework == Framework::WordPress {
+        content.push_str(&format!("{}@uploads_php path /wp-content/uploads/*.php
+", indent));
+        content.push_str(&format!("{}respond @uploads_php 403
+", indent));
+    }
+}
+
+/// Serve `index.html` for paths that are not files, for single-page apps
+fn push_spa_fallback(content: &mut String, project: &Project, indent: &str) {
+    if project.framework == Framework::Static && project.spa_fallback {
+        content.push_str(&format!("{}try_files {{path}} {{path}}/ /index.html\n", indent));
+    }
+}
+
+/// The project's document root as written in the Caddyfile
+fn caddy_docroot(project: &Project, manifest: &ProjectManifest) -> Result<String, String> {
+    let docroot_path = project.site_root(Some(manifest));
+    let docroot_raw = docroot_path
+        .to_str()
+        .ok_or("Invalid document root")?;
+    if docroot_raw.contains('"') || docroot_raw.contains('\n') || docroot_raw.contains('}') || docroot_raw.contains('{') {
+        return Err("Invalid document root: contains characters not allowed in Caddyfile".to_string());
+    }
+    Ok(docroot_raw.replace('\\', "/"))
+}
+
+/// Route `localhost/<name>/` to the document root of each project that is
+/// not served from `<projects_root>/<name>` itself
+///
+/// Without it the root `file_server` would list the repository of a
+/// framework project instead of running its `public/` front controller.
+fn push_project_routes(content: &mut String, projects_dir: &Path, php_port: u16, settings: &AppSettings, projects: &[Project]) {
+    for project in projects {
+        let manifest = project.manifest().unwrap_or_default();
+        let is_static = project.framework == Framework::Static;
+        if !is_static && project.site_root(Some(&manifest)) == projects_dir.join(&project.name) {
+            continue;
+        }
+        let docroot = match caddy_docroot(project, &manifest) {
+            Ok(docroot) => docroot,
+            Err(e) => {
+                tracing::warn!("Not routing /{}/ to project '{}': {}", project.name, project.name, e);
+                continue;
+            }
+        };
+        content.push_str(&format!("    # Project {}, served from its document root\n", project.name));
+        content.push_str(&format!("    redir /{0} /{0}/\n", project.name));
+        content.push_str(&format!("    handle_path /{}/* {{\n", project.name));
+        content.push_str(&format!("        root * \"{}\"\n", docroot));
+        if settings.is_enabled(ServiceType::PhpFpm) && !is_static {
+            content.push_str(&format!("        php_fastcgi 127.0.0.1:{}\n", project_php_port(settings, php_port, project, &manifest)));
+        }
+        push_rewrite_preset(content, project, "        ");
+        push_spa_fallback(content, project, "        ");
+        content.push_str("        file_server\n");
+        content.push_str("    }\n");
+        content.push('\n');
+    }
+}
+
+/// Append the `log` directive for a site block according to the access log settings
+fn push_access_log(content: &mut String, paths: &RuntimePaths, settings: &AppSettings, site: &str) -> Result<(), String> {
+    if !settings.access_log.enabled {
+        return Ok(());
+    }
+
+    let file_name = if settings.access_log.per_site {
+        format!("access-{}.log", site)
+    } else {
+        "caddy-access.log".to_string()
+    };
+    let log_file = paths.logs_dir.join(file_name)
+        .to_str()
+        .ok_or("Invalid log path")?
+        .replace('\\', "/");
+    let format = match settings.access_log.format {
+        AccessLogFormat::Json => "json",
+        AccessLogFormat::Console => "console",
+    };
+
+    content.push_str("    # Logging\n");
+    content.push_str("    log {\n");
+    content.push_str(&format!("        output file \"{}\"\n", log_file));
+    content.push_str(&format!("        format {}\n", format));
+    content.push_str("    }\n");
+    content.push_str("\n");
+    Ok(())
+}
+
+pub fn generate_php_ini(path: &PathBuf, paths: &RuntimePaths, settings: &AppSettings) -> Result<(), String> {
+    super::generated::write_generated(path, &php_ini_content(paths, settings)?)?;
+
+    // Also copy php.ini to the PHP runtime directory so CLI usage works out of
+    // the box, but never into the directory of a system PHP
+    if settings.binary_overrides.php.is_some() {
+        return Ok(());
+    }
     let php_dir = paths.php_cgi.parent()
         .ok_or("Cannot determine PHP directory")?;
+    let runtime_ini = php_dir.join("php.ini");
+    if let Some(parent) = runtime_ini.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::copy(path, &runtime_ini);
 
-    let ext_dir = php_dir.join("ext");
-    let ext_dir_str = ext_dir.to_string_lossy().replace('\\', "/");
+    Ok(())
+}
+
+/// php.ini for the located runtime, without writing it
+///
+/// `mail()` goes to Mailpit and the memcached extension is loaded while the
+/// services are enabled.
+pub fn php_ini_content(paths: &RuntimePaths, settings: &AppSettings) -> Result<String, String> {
+    let ext_dir_str = paths.php_ext_dir.to_string_lossy().replace('\\', "/");
 
     let error_log = paths.logs_dir.join("php-errors.log")
         .to_string_lossy()
@@ -107,7 +449,7 @@ pub fn generate_php_ini(path: &PathBuf, paths: &RuntimePaths) -> Result<(), Stri
         .to_string_lossy()
         .replace('\\', "/");
 
-    let php_ini_content = format!(r#"; CAMPP PHP Configuration
+    let mut php_ini_content = format!(r#"; CAMPP PHP Configuration
 ; Basic PHP settings for development
 
 [PHP]
@@ -181,32 +523,46 @@ realpath_cache_size=8192K
 realpath_cache_ttl=300
 "#, error_log, ext_dir_str, session_path, session_path);
 
-    let mut file = File::create(path)
-        .map_err(|e| format!("Failed to create php.ini: {}", e))?;
-    file.write_all(php_ini_content.as_bytes())
-        .map_err(|e| format!("Failed to write php.ini: {}", e))?;
-
-    // Also copy php.ini to the PHP runtime directory so CLI usage works out of the box
-    let runtime_ini = php_dir.join("php.ini");
-    if let Some(parent) = runtime_ini.parent() {
-        let _ = fs::create_dir_all(parent);
+    if settings.is_enabled(ServiceType::Memcached) {
+        match crate::process::memcached::php_ini_extension(&settings.memcached, &paths.php_ext_dir) {
+            Some(line) => php_ini_content.push_str(&format!("\n; Memcached\n{}", line)),
+            None => tracing::warn!("Memcached is enabled but the memcached PHP extension is not set or in {}", paths.php_ext_dir.display()),
+        }
+    }
+    if settings.is_enabled(ServiceType::Mailpit) {
+        php_ini_content.push_str(&crate::process::mailpit::php_ini_mail(&paths.mailpit, &settings.mailpit));
     }
-    let _ = fs::copy(path, &runtime_ini);
 
-    Ok(())
+    Ok(php_ini_content)
 }
 
 pub fn generate_php_fpm_conf(path: &PathBuf, paths: &RuntimePaths, php_port: u16) -> Result<(), String> {
+    let mut file = File::create(path)
+        .map_err(|e| format!("Failed to create php-fpm.conf: {}", e))?;
+    file.write_all(php_fpm_conf_content(paths, php_port).as_bytes())
+        .map_err(|e| format!("Failed to write php-fpm.conf: {}", e))?;
+
+    Ok(())
+}
+
+/// php-fpm.conf listening on `php_port`, without writing it
+pub fn php_fpm_conf_content(paths: &RuntimePaths, php_port: u16) -> String {
+    php_fpm_listener_conf(paths, php_port, "php-fpm.log", "php-sessions")
+}
+
+/// php-fpm.conf listening on `php_port` with its error log and session
+/// directory under the logs directory named `log_name` and `sessions_dir`
+pub fn php_fpm_listener_conf(paths: &RuntimePaths, php_port: u16, log_name: &str, sessions_dir: &str) -> String {
     let user = std::env::var("USER")
         .or_else(|_| std::env::var("USERNAME"))
         .unwrap_or_else(|_| "nobody".to_string());
 
-    let fpm_conf_content = format!(
+    format!(
         r#"; CAMPP PHP-FPM Configuration
 ; Optimized for phpMyAdmin performance
 
 [global]
-error_log = {logs_dir}/php-fpm.log
+error_log = {logs_dir}/{log_name}
 log_level = warning
 
 [www]
@@ -226,9 +582,9 @@ pm.max_requests = 1000
 
 ; Request settings for phpMyAdmin
 request_terminate_timeout = 300
-php_admin_value[error_log] = {logs_dir}/php-fpm.log
+php_admin_value[error_log] = {logs_dir}/{log_name}
 php_admin_flag[log_errors] = on
-php_value[session.save_path] = {logs_dir}/php-sessions
+php_value[session.save_path] = {logs_dir}/{sessions_dir}
 
 ; Performance tuning
 php_value[memory_limit] = 256M
@@ -236,14 +592,9 @@ php_value[memory_limit] = 256M
         logs_dir = paths.logs_dir.display().to_string().replace('\\', "/"),
         user = user,
         php_port = php_port,
-    );
-
-    let mut file = File::create(path)
-        .map_err(|e| format!("Failed to create php-fpm.conf: {}", e))?;
-    file.write_all(fpm_conf_content.as_bytes())
-        .map_err(|e| format!("Failed to write php-fpm.conf: {}", e))?;
-
-    Ok(())
+        log_name = log_name,
+        sessions_dir = sessions_dir,
+    )
 }
 
 pub fn generate_phpmyadmin_config(paths: &RuntimePaths, mysql_port: u16, mysql_root_password: &str) -> Result<(), String> {
@@ -336,18 +687,22 @@ $cfg['FirstDayOfCalendar'] = 1;
 $cfg['ExecTimeLimit'] = 0;
 "#, blowfish_secret, escaped_password, mysql_port, allow_no_password, upload_dir_str, upload_dir_str, tmp_dir_str);
 
-    let mut file = File::create(&config_path)
-        .map_err(|e| format!("Failed to create phpMyAdmin config: {}", e))?;
-    file.write_all(config_content.as_bytes())
-        .map_err(|e| format!("Failed to write phpMyAdmin config: {}", e))?;
-
-    Ok(())
+    super::generated::write_generated(&config_path, &config_content)
 }
 
 /// Generate PostgreSQL configuration file
-pub fn generate_postgresql_conf(data_dir: &PathBuf, port: u16) -> Result<(), String> {
+pub fn generate_postgresql_conf(data_dir: &PathBuf, port: u16, listen_addresses: &[String]) -> Result<(), String> {
     let path = data_dir.join("postgresql.conf");
+    let mut file = File::create(&path)
+        .map_err(|e| format!("Failed to create postgresql.conf: {}", e))?;
+    file.write_all(postgresql_conf_content(port, listen_addresses).as_bytes())
+        .map_err(|e| format!("Failed to write postgresql.conf: {}", e))?;
+
+    Ok(())
+}
 
+/// postgresql.conf for `port`, without writing it
+pub fn postgresql_conf_content(port: u16, listen_addresses: &[String]) -> String {
     // Platform-specific shared memory type
     #[cfg(target_os = "windows")]
     let shared_memory_type = "windows";
@@ -356,12 +711,12 @@ pub fn generate_postgresql_conf(data_dir: &PathBuf, port: u16) -> Result<(), Str
     #[cfg(target_os = "linux")]
     let shared_memory_type = "posix";
 
-    let content = format!(
+    format!(
         r#"# CAMPP PostgreSQL Configuration
 # Generated automatically
 
 # Connection settings
-listen_addresses = '127.0.0.1'
+listen_addresses = '{}'
 port = {}
 max_connections = 100
 
@@ -385,27 +740,25 @@ lc_numeric = 'C'
 lc_time = 'C'
 default_text_search_config = 'pg_catalog.english'
 "#,
-        port, shared_memory_type
-    );
-
-    let mut file = File::create(&path)
-        .map_err(|e| format!("Failed to create postgresql.conf: {}", e))?;
-    file.write_all(content.as_bytes())
-        .map_err(|e| format!("Failed to write postgresql.conf: {}", e))?;
-
-    Ok(())
+        listen_addresses.join(","), port, shared_memory_type
+    )
 }
 
 /// Generate PostgreSQL client authentication config
-pub fn generate_pg_hba_conf(data_dir: &PathBuf, auth_method: &str) -> Result<(), String> {
+///
+/// With `allow_network`, remote clients may connect too, but only with a password.
+pub fn generate_pg_hba_conf(data_dir: &PathBuf, auth_method: &str, allow_network: bool) -> Result<(), String> {
     let path = data_dir.join("pg_hba.conf");
 
-    let content = format!(r#"# CAMPP PostgreSQL Client Authentication
+    let mut content = format!(r#"# CAMPP PostgreSQL Client Authentication
 # TYPE  DATABASE  USER  ADDRESS       METHOD
 local   all       all                 {auth_method}
 host    all       all   127.0.0.1/32  {auth_method}
 host    all       all   ::1/128        {auth_method}
 "#, auth_method = auth_method);
+    if allow_network && auth_method != "trust" {
+        content.push_str("host    all       all   all           scram-sha-256\n");
+    }
 
     let mut file = File::create(&path)
         .map_err(|e| format!("Failed to create pg_hba.conf: {}", e))?;
@@ -555,10 +908,388 @@ h1 {{ font-size: 1.8rem; margin-bottom: 0.5rem; color: #fff; }}
         escaped_pg_pw = escaped_pg_pw,
     );
 
-    let mut file = File::create(&index_path)
-        .map_err(|e| format!("Failed to create Adminer index.php: {}", e))?;
-    file.write_all(content.as_bytes())
-        .map_err(|e| format!("Failed to write Adminer index.php: {}", e))?;
+    super::generated::write_generated(&index_path, &content)
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BasicAuthCredentials;
+    use tempfile::TempDir;
+
+    fn mock_runtime_paths(temp_dir: &TempDir) -> RuntimePaths {
+        let root = temp_dir.path();
+        RuntimePaths {
+            caddy: root.join("caddy"),
+            php_cgi: root.join("php").join("php-cgi"),
+            php_ini: root.join("config").join("php.ini"),
+            php_ext_dir: root.join("php").join("ext"),
+            mysql: root.join("mysql").join("bin").join("mysqld"),
+            phpmyadmin: root.join("phpmyadmin"),
+            pgsql_dir: root.join("postgresql"),
+            adminer: root.join("adminer"),
+            cloudflared: root.join("cloudflared").join("cloudflared"),
+            mailpit: root.join("mailpit").join("mailpit"),
+            node_dir: root.join("node"),
+            minio: root.join("minio").join("minio"),
+            minio_dir: root.join("minio-data"),
+            mysql_data_dir: root.join("mysql").join("data"),
+            pgsql_data_dir: root.join("pgsql").join("data"),
+            logs_dir: root.join("logs"),
+            config_dir: root.join("config"),
+            projects_dir: root.join("projects"),
+        }
+    }
+
+    fn render_caddyfile(paths: &RuntimePaths, settings: &AppSettings) -> String {
+        fs::create_dir_all(&paths.config_dir).unwrap();
+        let caddyfile = paths.config_dir.join("Caddyfile");
+        generate_caddyfile(&caddyfile, paths, 8080, 9000, settings, &[]).unwrap();
+        fs::read_to_string(&caddyfile).unwrap()
+    }
+
+    #[test]
+    fn test_caddyfile_phpmyadmin_without_auth() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        fs::create_dir_all(&paths.phpmyadmin).unwrap();
+        fs::write(paths.phpmyadmin.join("index.php"), "<?php").unwrap();
+
+        let content = render_caddyfile(&paths, &AppSettings::default());
+
+        assert!(content.contains("handle_path /phpmyadmin/*"));
+        assert!(!content.contains("basic_auth"));
+    }
+
+    #[test]
+    fn test_caddyfile_project_sites() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let project = Project {
+            name: "my_shop".to_string(),
+            path: paths.projects_dir.join("my_shop").to_string_lossy().to_string(),
+            docroot: "public".to_string(),
+            framework: crate::projects::Framework::Laravel,
+            created_at: 1,
+            domain: Some("my-shop.test".to_string()),
+            aliases: vec!["api.my-shop.test".to_string()],
+            ..Default::default()
+        };
+        let broken = Project { name: "broken".to_string(), path: "/srv/{x}".to_string(), ..project.clone() };
+
+        // Under the localhost TLD the domain is the site host
+        let moved = project.moved_to_tld("test", "localhost");
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[moved]).unwrap();
+        assert!(content.contains("http://my-shop.localhost:8080, http://api.my-shop.localhost:8080 {"));
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project, broken]).unwrap();
+        let docroot = paths.projects_dir.join("my_shop").join("public").to_string_lossy().replace('\\', "/");
+        assert!(content.contains(&format!("http://my-shop.localhost:8080, http://my-shop.test:8080, http://api.my-shop.test:8080 {{\n    bind 127.0.0.1\n\n    root * \"{}\"\n    php_fastcgi 127.0.0.1:9000\n", docroot)));
+        assert!(!content.contains("broken.localhost"));
+
+        // localhost/my_shop/ runs public/ instead of listing the repository
+        assert!(content.contains(&format!(
+            "    redir /my_shop /my_shop/\n    handle_path /my_shop/* {{\n        root * \"{}\"\n        php_fastcgi 127.0.0.1:9000\n        file_server\n    }}\n",
+            docroot
+        )));
+        assert!(!content.contains("handle_path /broken/*"));
+    }
+
+    #[test]
+    fn test_caddyfile_https_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let project = Project {
+            name: "shop".to_string(),
+            path: paths.projects_dir.join("shop").to_string_lossy().to_string(),
+            framework: crate::projects::Framework::Php,
+            created_at: 1,
+            domain: Some("shop.test".to_string()),
+            https: true,
+            ..Default::default()
+        };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project.clone()]).unwrap();
+        assert!(content.contains("    https_port 8443\n    auto_https disable_redirects\n"));
+        assert!(content.contains("https://shop.localhost:8443, https://shop.test:8443 {\n    bind 127.0.0.1\n    tls internal\n\n"));
+        assert!(!content.contains("http://shop.test"));
+
+        // Redirect from the setting, and the project's own choice over it
+        let settings = AppSettings { https_redirect: true, ..AppSettings::default() };
+        let content = caddyfile_content(&paths, 8080, 9000, &settings, &[project.clone()]).unwrap();
+        assert!(content.contains(
+            "http://shop.localhost:8080, http://shop.test:8080 {\n    bind 127.0.0.1\n    redir https://{host}:8443{uri} 308\n}\n"
+        ));
+        let project = Project { https_redirect: Some(false), ..project };
+        let content = caddyfile_content(&paths, 8080, 9000, &settings, &[project]).unwrap();
+        assert!(!content.contains("http://shop.test"));
+    }
+
+    #[test]
+    fn test_caddyfile_lan_share() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let project = Project {
+            name: "shop".to_string(),
+            path: paths.projects_dir.join("shop").to_string_lossy().to_string(),
+            framework: crate::projects::Framework::Php,
+            created_at: 1,
+            lan_share: Some(crate::projects::share::LanShare { address: "127.0.0.1".to_string(), port: 8100 }),
+            ..Default::default()
+        };
+        let gone = Project {
+            name: "gone".to_string(),
+            lan_share: Some(crate::projects::share::LanShare { address: "192.0.2.1".to_string(), port: 8101 }),
+            ..project.clone()
+        };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project, gone]).unwrap();
+        let docroot = paths.projects_dir.join("shop").to_string_lossy().replace('\\', "/");
+        assert!(content.contains(&format!("# Project shop shared on the LAN\nhttp://:8100 {{\n    bind 127.0.0.1\n\n    root * \"{}\"\n", docroot)));
+        assert!(content.contains("http://gone.localhost:8080"));
+        assert!(!content.contains("http://:8101"));
+    }
+
+    #[test]
+    fn test_caddyfile_static_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let project = Project {
+            name: "docs".to_string(),
+            path: paths.projects_dir.join("docs").to_string_lossy().to_string(),
+            docroot: "dist".to_string(),
+            framework: Framework::Static,
+            created_at: 1,
+            spa_fallback: true,
+            ..Default::default()
+        };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project.clone()]).unwrap();
+        let site = content.split("# Project docs\n").nth(1).unwrap();
+        assert!(!site.contains("php_fastcgi"));
+        assert!(site.contains("    try_files {path} {path}/ /index.html\n    file_server\n"));
+        let route = content.split("handle_path /docs/* {\n").nth(1).unwrap().split("    }\n").next().unwrap();
+        assert!(!route.contains("php_fastcgi"));
+        assert!(route.contains("try_files"));
+
+        // Proxied to the dev server while its script runs
+        let project = Project { dev_server_port: Some(5173), ..project };
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project]).unwrap();
+        let site = content.split("# Project docs\n").nth(1).unwrap();
+        assert!(site.contains("    reverse_proxy localhost:5173 {\n        header_up Host {upstream_hostport}\n    }\n"));
+        assert!(!site.split("}\n").next().unwrap().contains("file_server"));
+    }
+
+    #[test]
+    fn test_caddyfile_wordpress_preset() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let project = Project {
+            name: "blog".to_string(),
+            path: temp_dir.path().join("code").join("blog").to_string_lossy().to_string(),
+            framework: Framework::WordPress,
+            created_at: 1,
+            ..Default::default()
+        };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project]).unwrap();
+        let preset = "@uploads_php path /wp-content/uploads/*.php\n";
+        let site = content.split("# Project blog\n").nth(1).unwrap();
+        assert!(site.contains(&format!("    php_fastcgi 127.0.0.1:9000\n    {}    respond @uploads_php 403\n", preset)));
+        let route = content.split("handle_path /blog/* {\n").nth(1).unwrap().split("    }\n").next().unwrap();
+        assert!(route.contains(&format!("        {}", preset)));
+    }
+
+    #[test]
+    fn test_caddyfile_project_snippets() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let dir = paths.projects_dir.join("shop");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(crate::projects::SNIPPET_FILE), "redir /old /new 301\n").unwrap();
+        let project = Project {
+            name: "shop".to_string(),
+            path: dir.to_string_lossy().to_string(),
+            framework: Framework::Php,
+            created_at: 1,
+            caddy_snippet: Some("handle /api/* {\n    reverse_proxy 127.0.0.1:3000\n}".to_string()),
+            trusted_caddy_file: Some(crate::projects::snippet_hash("redir /old /new 301\n")),
+            ..Default::default()
+        };
+        let broken = Project { name: "broken".to_string(), caddy_snippet: Some("}\nhttp://evil {".to_string()), ..project.clone() };
+        // Caddyfile.project is only used once allowed
+        let untrusted = Project { name: "untrusted".to_string(), caddy_snippet: None, trusted_caddy_file: None, ..project.clone() };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project, broken, untrusted]).unwrap();
+        let site = |name: &str| content.split(&format!("# Project {}\n", name)).nth(1).unwrap().split("# Project ").next().unwrap().to_string();
+        assert!(site("shop").contains("    # From Caddyfile.project\n    redir /old /new 301\n"));
+        assert!(site("shop").contains("    # From settings\n    handle /api/* {\n        reverse_proxy 127.0.0.1:3000\n    }\n"));
+        // Only the settings snippet of broken is rejected, its site is still served
+        assert!(site("broken").contains("# From Caddyfile.project"));
+        assert!(!site("broken").contains("evil"));
+        assert!(!site("untrusted").contains("redir"));
+    }
+
+    #[test]
+    fn test_caddyfile_welcome_env() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let settings = AppSettings { mysql_root_password: "p\"w\\d".to_string(), ..Default::default() };
+        let welcome = Project {
+            name: crate::projects::welcome::WELCOME_PROJECT.to_string(),
+            path: paths.projects_dir.join("welcome").to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &settings, &[welcome]).unwrap();
+        assert!(content.contains("        env DB_PASSWORD `p\"w\\d`\n"));
+        assert!(content.contains(&format!("        env DB_PORT \"{}\"\n", settings.mysql_port)));
+    }
+
+    #[test]
+    fn test_check_snippet() {
+        assert!(check_snippet("header X-Frame-Options \"{deny}\"\nrespond /health 200 # ok {").is_ok());
+        assert!(check_snippet("rewrite * /index.html#top}").is_err());
+        assert!(check_snippet("route {").is_err());
+        assert!(check_snippet("import /etc/caddy/other").is_err());
+        assert!(check_snippet("handle /files/* {\n    root * /etc\n}").is_err());
+    }
+
+    #[test]
+    fn test_caddyfile_pinned_php() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let settings = AppSettings {
+            php_versions: vec![crate::process::php_pool::PhpBackend {
+                version: "8.1".to_string(),
+                binary: PathBuf::from("/usr/sbin/php-fpm8.1"),
+            }],
+            ..Default::default()
+        };
+        let legacy = Project {
+            name: "legacy".to_string(),
+            path: paths.projects_dir.join("legacy").to_string_lossy().to_string(),
+            framework: crate::projects::Framework::Php,
+            created_at: 1,
+            php_version: Some("8.1".to_string()),
+            ..Default::default()
+        };
+        let current = Project { name: "current".to_string(), php_version: Some("8.4".to_string()), ..legacy.clone() };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &settings, &[legacy, current]).unwrap();
+        let site = |name: &str| content.split(&format!("# Project {}\n", name)).nth(1).unwrap().split("}\n").next().unwrap().to_string();
+        assert!(site("legacy").contains("php_fastcgi 127.0.0.1:9001\n"));
+        // No 8.4 listener, the main PHP serves it
+        assert!(site("current").contains("php_fastcgi 127.0.0.1:9000\n"));
+    }
+
+    #[test]
+    fn test_caddyfile_project_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        let dir = paths.projects_dir.join("blog");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(crate::projects::manifest::MANIFEST_FILE),
+            r#"{"docroot": "web", "env": {"APP_ENV": "local"}, "php_ini": {"memory_limit": "512M"}}"#,
+        ).unwrap();
+        let project = Project {
+            name: "blog".to_string(),
+            path: dir.to_string_lossy().to_string(),
+            framework: crate::projects::Framework::Php,
+            created_at: 1,
+            env: [("APP_ENV", "testing"), ("API_KEY", "secret")].map(|(k, v)| (k.to_string(), v.to_string())).into(),
+            ..Default::default()
+        };
+
+        let content = caddyfile_content(&paths, 8080, 9000, &AppSettings::default(), &[project]).unwrap();
+        let docroot = dir.join("web").to_string_lossy().replace('\\', "/");
+        assert!(content.contains(&format!(
+            "    root * \"{}\"\n    php_fastcgi 127.0.0.1:9000 {{\n        env API_KEY \"secret\"\n        env APP_ENV \"testing\"\n    }}\n",
+            docroot
+        )));
+        assert!(!content.contains("PHP_VALUE"));
+    }
+
+    #[test]
+    fn test_caddyfile_disabled_services() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        fs::create_dir_all(&paths.phpmyadmin).unwrap();
+        fs::write(paths.phpmyadmin.join("index.php"), "<?php").unwrap();
+
+        let settings = AppSettings {
+            enabled_services: vec![ServiceType::Caddy, ServiceType::PhpFpm],
+            ..Default::default()
+        };
+        let content = render_caddyfile(&paths, &settings);
+        assert!(!content.contains("/phpmyadmin"));
+        assert!(content.contains("php_fastcgi"));
+
+        let settings = AppSettings {
+            enabled_services: vec![ServiceType::Caddy],
+            ..Default::default()
+        };
+        let content = render_caddyfile(&paths, &settings);
+        assert!(!content.contains("php_fastcgi"));
+        assert!(content.contains("file_server browse"));
+    }
+
+    #[test]
+    fn test_caddyfile_phpmyadmin_with_basic_auth() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+        fs::create_dir_all(&paths.phpmyadmin).unwrap();
+        fs::write(paths.phpmyadmin.join("index.php"), "<?php").unwrap();
+
+        let mut settings = AppSettings::default();
+        settings.phpmyadmin_auth = Some(BasicAuthCredentials {
+            username: "admin".to_string(),
+            password_hash: "$2b$12$hash".to_string(),
+        });
+
+        let content = render_caddyfile(&paths, &settings);
+
+        assert!(content.contains("basic_auth {\n            admin $2b$12$hash\n        }"));
+    }
+
+    #[test]
+    fn test_caddyfile_security_headers() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+
+        let content = render_caddyfile(&paths, &AppSettings::default());
+        assert!(content.contains("X-Frame-Options \"SAMEORIGIN\""));
+
+        let mut settings = AppSettings::default();
+        settings.security_headers.retain(|h| h.name != "X-Frame-Options");
+        let content = render_caddyfile(&paths, &settings);
+        assert!(content.contains("X-Content-Type-Options \"nosniff\""));
+        assert!(!content.contains("X-Frame-Options"));
+
+        settings.security_headers.clear();
+        let content = render_caddyfile(&paths, &settings);
+        assert!(!content.contains("header {"));
+    }
+
+    #[test]
+    fn test_caddyfile_access_log_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = mock_runtime_paths(&temp_dir);
+
+        let content = render_caddyfile(&paths, &AppSettings::default());
+        assert!(content.contains("caddy-access.log"));
+        assert!(content.contains("format json"));
+
+        let mut settings = AppSettings::default();
+        settings.access_log.per_site = true;
+        settings.access_log.format = AccessLogFormat::Console;
+        let content = render_caddyfile(&paths, &settings);
+        assert!(content.contains("access-localhost.log"));
+        assert!(content.contains("format console"));
+
+        settings.access_log.enabled = false;
+        let content = render_caddyfile(&paths, &settings);
+        assert!(!content.contains("log {"));
+    }
 }
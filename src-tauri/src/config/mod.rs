@@ -1,9 +1,19 @@
+pub mod apply;
+pub mod data_dir;
+pub mod env;
+pub mod generated;
 pub mod generator;
+pub mod portable;
+pub mod preview;
 pub mod ports;
+pub mod profiles;
+pub mod security;
+pub mod service_args;
 pub mod settings;
+pub mod urls;
 
-pub use ports::{find_available_port, is_port_available, is_port_in_use};
-pub use settings::{AppSettings, DEFAULT_PORTS};
+pub use ports::{find_available_port, is_port_available, is_port_in_use, port_owner, PortOwner};
+pub use settings::{AccessLogFormat, AppSettings, BasicAuthCredentials, SecurityHeader, DEFAULT_PORTS};
 
 #[cfg(test)]
 mod tests {
@@ -24,6 +34,22 @@ mod tests {
         assert_eq!(settings.mysql_port, 3307);
     }
 
+    #[test]
+    fn test_validate_reports_fields() {
+        let settings = AppSettings {
+            php_port: 8080,
+            mysql_port: 0,
+            ..Default::default()
+        };
+        let result = settings.validate();
+
+        assert!(!result.is_valid());
+        let php = result.errors.iter().find(|e| e.field == "php_port").unwrap();
+        assert_eq!(php.code, "port_duplicate");
+        let mysql = result.errors.iter().find(|e| e.field == "mysql_port").unwrap();
+        assert_eq!(mysql.code, "port_zero");
+    }
+
     #[test]
     fn test_is_port_available() {
         // Port 1 is typically unavailable (reserved)
@@ -37,4 +63,41 @@ mod tests {
         let port = find_available_port(8080);
         assert!(port >= 8080);
     }
+
+    #[test]
+    fn test_project_root() {
+        let default_dir = std::path::Path::new("/data/campp/projects");
+        assert_eq!(AppSettings::default().projects_dir(default_dir), default_dir);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let settings = AppSettings {
+            project_root: temp_dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        assert_eq!(settings.projects_dir(default_dir), temp_dir.path());
+        assert!(!settings.validate().errors.iter().any(|e| e.field == "project_root"));
+
+        let settings = AppSettings {
+            project_root: temp_dir.path().join("missing").to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let result = settings.validate();
+        assert!(result.errors.iter().any(|e| e.field == "project_root" && e.code == "not_found"));
+    }
+
+    #[test]
+    fn test_bind_address() {
+        let settings = AppSettings::default();
+        assert!(!settings.is_lan_exposed());
+        assert_eq!(settings.listen_addresses(), vec!["127.0.0.1"]);
+
+        let settings = AppSettings { bind_address: "0.0.0.0".to_string(), ..Default::default() };
+        assert!(settings.is_lan_exposed());
+        assert_eq!(settings.listen_addresses(), vec!["0.0.0.0"]);
+        let result = settings.validate();
+        assert!(result.warnings.iter().any(|w| w.code == "mysql_exposed"));
+
+        let settings = AppSettings { bind_address: "my-laptop".to_string(), ..Default::default() };
+        assert!(settings.validate().errors.iter().any(|e| e.code == "invalid_address"));
+    }
 }
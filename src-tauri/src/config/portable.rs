@@ -0,0 +1,158 @@
+//! Portable settings export/import
+//!
+//! The exported JSON carries the settings and the runtime vhost list, with
+//! machine-specific paths rewritten so the file can be shared between
+//! machines: a project root inside the home directory becomes `~/...` and
+//! vhost roots inside the project root become `{project_root}/...`. Secrets
+//! are left out: the database root passwords and the phpMyAdmin login belong
+//! to this machine's data directories, an import keeps the local ones.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::AppSettings;
+use crate::process::caddy_admin::VhostRoute;
+
+/// Bumped when the file layout changes incompatibly
+pub const PORTABLE_FORMAT_VERSION: u32 = 1;
+
+const PROJECT_ROOT_PLACEHOLDER: &str = "{project_root}";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableConfig {
+    pub format_version: u32,
+    /// CAMPP version that wrote the file
+    pub app_version: String,
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub vhosts: Vec<VhostRoute>,
+}
+
+/// Outcome of `import_settings`
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportSummary {
+    pub settings: AppSettings,
+    pub vhosts_added: Vec<String>,
+    /// Non-fatal problems: services that failed to restart, vhosts not added
+    pub errors: Vec<String>,
+}
+
+/// Write settings and vhosts to `path`
+pub fn export_to_file(path: &Path, settings: &AppSettings, vhosts: &[VhostRoute]) -> Result<(), String> {
+    let home = dirs::home_dir();
+    let config = to_portable(settings, vhosts, home.as_deref());
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Read an exported file, with paths expanded for this machine
+pub fn import_from_file(path: &Path) -> Result<PortableConfig, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let config: PortableConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Not a CAMPP settings export: {}", e))?;
+    if config.format_version > PORTABLE_FORMAT_VERSION {
+        return Err(format!(
+            "Settings were exported by a newer CAMPP ({}), please update first",
+            config.app_version
+        ));
+    }
+    Ok(from_portable(config, dirs::home_dir().as_deref()))
+}
+
+fn to_portable(settings: &AppSettings, vhosts: &[VhostRoute], home: Option<&Path>) -> PortableConfig {
+    let project_root = Path::new(&settings.project_root);
+    let vhosts = vhosts
+        .iter()
+        .map(|v| VhostRoute {
+            host: v.host.clone(),
+            root: match Path::new(&v.root).strip_prefix(project_root) {
+                Ok(rel) => join_portable(PROJECT_ROOT_PLACEHOLDER, rel),
+                Err(_) => v.root.clone(),
+            },
+        })
+        .collect();
+
+    let mut settings = settings.without_secrets();
+    if let Some(rel) = home.and_then(|h| project_root.strip_prefix(h).ok()) {
+        settings.project_root = join_portable("~", rel);
+    }
+
+    PortableConfig {
+        format_version: PORTABLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings,
+        vhosts,
+    }
+}
+
+fn from_portable(mut config: PortableConfig, home: Option<&Path>) -> PortableConfig {
+    if let (Some(rest), Some(home)) = (strip_placeholder(&config.settings.project_root, "~"), home) {
+        config.settings.project_root = home.join(rest).to_string_lossy().to_string();
+    }
+
+    let project_root = Path::new(&config.settings.project_root).to_path_buf();
+    for vhost in &mut config.vhosts {
+        if let Some(rest) = strip_placeholder(&vhost.root, PROJECT_ROOT_PLACEHOLDER) {
+            vhost.root = project_root.join(rest).to_string_lossy().to_string();
+        }
+    }
+    config
+}
+
+/// `prefix/a/b` with forward slashes, whatever the exporting platform
+fn join_portable(prefix: &str, rel: &Path) -> String {
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    if rel.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{}/{}", prefix, rel)
+    }
+}
+
+/// The part after `prefix/`, or "" for `prefix` itself
+fn strip_placeholder<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    match value.strip_prefix(prefix)? {
+        "" => Some(""),
+        rest => rest.strip_prefix('/'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_portable_roundtrip() {
+        let home = PathBuf::from("/home/teacher");
+        let settings = AppSettings {
+            project_root: home.join("campp").join("projects").to_string_lossy().to_string(),
+            web_port: 8088,
+            mysql_root_password: "secret".to_string(),
+            ..Default::default()
+        };
+        let vhosts = vec![
+            VhostRoute {
+                host: "shop.test".to_string(),
+                root: home.join("campp/projects/shop/public").to_string_lossy().to_string(),
+            },
+            VhostRoute { host: "docs.test".to_string(), root: "/srv/docs".to_string() },
+        ];
+
+        let portable = to_portable(&settings, &vhosts, Some(&home));
+        assert_eq!(portable.settings.project_root, "~/campp/projects");
+        assert!(portable.settings.mysql_root_password.is_empty());
+        assert_eq!(portable.vhosts[0].root, "{project_root}/shop/public");
+        assert_eq!(portable.vhosts[1].root, "/srv/docs");
+
+        let student_home = PathBuf::from("/home/student");
+        let imported = from_portable(portable, Some(&student_home));
+        assert_eq!(PathBuf::from(&imported.settings.project_root), student_home.join("campp/projects"));
+        assert_eq!(PathBuf::from(&imported.vhosts[0].root), student_home.join("campp/projects/shop/public"));
+        assert_eq!(imported.settings.web_port, 8088);
+    }
+}
@@ -49,10 +49,165 @@ pub fn is_port_in_use(port: u16) -> bool {
     ).is_ok()
 }
 
+/// A process listening on a TCP port
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PortOwner {
+    pub pid: u32,
+    /// Executable name, e.g. "httpd" or "mysqld.exe"
+    pub name: String,
+    /// Full path of the executable, when it could be read
+    pub path: Option<String>,
+}
+
+impl std::fmt::Display for PortOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (PID {})", self.name, self.pid)
+    }
+}
+
+/// Find the process listening on `port`
+///
+/// Reads procfs on Linux and falls back to lsof, which also covers macOS.
+/// Windows uses netstat and tasklist.
+pub fn port_owner(port: u16) -> Option<PortOwner> {
+    #[cfg(target_os = "windows")]
+    {
+        let netstat = crate::process::manager::configure_no_window(std::process::Command::new("netstat"))
+            .args(["-ano", "-p", "TCP"])
+            .output()
+            .ok()?;
+        let pid = parse_netstat(&String::from_utf8_lossy(&netstat.stdout), port)?;
+        let tasklist = crate::process::manager::configure_no_window(std::process::Command::new("tasklist"))
+            .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+            .output()
+            .ok()?;
+        // "mysqld.exe","1234","Console","1","120,000 K"
+        let name = String::from_utf8_lossy(&tasklist.stdout)
+            .split(',')
+            .next()
+            .map(|n| n.trim().trim_matches('"').to_string())
+            .filter(|n| !n.is_empty() && !n.starts_with("INFO:"))
+            .unwrap_or_else(|| "unknown".to_string());
+        Some(PortOwner { pid, name, path: None })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        #[cfg(target_os = "linux")]
+        if let Some(owner) = proc_port_owner(port) {
+            return Some(owner);
+        }
+        let output = std::process::Command::new("lsof")
+            .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-Fpc"])
+            .output()
+            .ok()?;
+        parse_lsof(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// First process in `lsof -F pc` output: a `p<pid>` line followed by `c<command>`
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn parse_lsof(output: &str) -> Option<PortOwner> {
+    let mut lines = output.lines();
+    let pid = lines.find_map(|l| l.strip_prefix('p'))?.trim().parse().ok()?;
+    let name = lines.find_map(|l| l.strip_prefix('c')).unwrap_or("unknown").trim().to_string();
+    Some(PortOwner { pid, name, path: None })
+}
+
+/// Find the listening socket in /proc/net, then the process holding it open
+///
+/// Only processes of the current user can be inspected, others are left to lsof.
+#[cfg(target_os = "linux")]
+fn proc_port_owner(port: u16) -> Option<PortOwner> {
+    let inode = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find_map(|content| parse_proc_net_tcp(&content, port))?;
+    let socket = format!("socket:[{}]", inode);
+
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let holds_socket = fds
+            .flatten()
+            .any(|fd| std::fs::read_link(fd.path()).is_ok_and(|target| target.as_os_str() == socket.as_str()));
+        if holds_socket {
+            let name = std::fs::read_to_string(entry.path().join("comm"))
+                .map(|comm| comm.trim().to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            let path = std::fs::read_link(entry.path().join("exe"))
+                .ok()
+                .map(|exe| exe.to_string_lossy().to_string());
+            return Some(PortOwner { pid, name, path });
+        }
+    }
+    None
+}
+
+/// Inode of the socket listening on `port` in /proc/net/tcp or tcp6
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net_tcp(content: &str, port: u16) -> Option<u64> {
+    const TCP_LISTEN: &str = "0A";
+    content.lines().skip(1).find_map(|line| {
+        // sl, local address, remote address, state, queues, timers, retransmits, uid, timeout, inode
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let local_port = u16::from_str_radix(fields.get(1)?.rsplit(':').next()?, 16).ok()?;
+        if local_port != port || *fields.get(3)? != TCP_LISTEN {
+            return None;
+        }
+        fields.get(9)?.parse().ok().filter(|inode| *inode != 0)
+    })
+}
+
+/// PID listening on `port` in `netstat -ano` output
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_netstat(output: &str, port: u16) -> Option<u32> {
+    let suffix = format!(":{}", port);
+    output.lines().find_map(|line| {
+        // Proto, local address, foreign address, state, PID
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            [proto, local, _, "LISTENING", pid] if proto.starts_with("TCP") && local.ends_with(&suffix) => pid.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_port_owner() {
+        assert_eq!(parse_lsof("p4242\ncnginx\n"), Some(PortOwner { pid: 4242, name: "nginx".to_string(), path: None }));
+        assert_eq!(parse_lsof(""), None);
+
+        let netstat = "  Proto  Local Address          Foreign Address        State           PID\n\
+                       TCP    0.0.0.0:80             0.0.0.0:0              LISTENING       4\n\
+                       TCP    127.0.0.1:8080         0.0.0.0:0              LISTENING       5120\n\
+                       TCP    127.0.0.1:50000        127.0.0.1:8080         ESTABLISHED     77\n";
+        assert_eq!(parse_netstat(netstat, 8080), Some(5120));
+        assert_eq!(parse_netstat(netstat, 3307), None);
+
+        let proc_net_tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+                            0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 81234 1 0000000000000000 100 0 0 10 0\n\
+                            1: 0100007F:C350 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 81300 1 0000000000000000 20 4 30 10 -1\n";
+        assert_eq!(parse_proc_net_tcp(proc_net_tcp, 8080), Some(81234));
+        assert_eq!(parse_proc_net_tcp(proc_net_tcp, 50000), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_port_owner_finds_own_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let owner = proc_port_owner(port).unwrap();
+        assert_eq!(owner.pid, std::process::id());
+    }
+
     #[test]
     fn test_is_port_available_for_unused_port() {
         // Port 59999 is unlikely to be in use
@@ -0,0 +1,216 @@
+//! Config dry-run
+//!
+//! Renders the config files a set of proposed settings would produce and
+//! diffs each against what is on disk, without writing anything, so changes
+//! can be reviewed before they are applied.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use super::{generated, generator, AppSettings};
+use crate::process::ServiceType;
+use crate::runtime::locator::RuntimePaths;
+
+/// Unchanged lines shown around every change
+const DIFF_CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigPreview {
+    /// File name, e.g. "Caddyfile"
+    pub name: String,
+    /// Where the file is written, None for options passed on the command line
+    pub path: Option<String>,
+    pub content: String,
+    /// Unified diff against the current file, empty when unchanged
+    pub diff: String,
+    pub changed: bool,
+    /// The current file was edited by hand and is kept instead of regenerated
+    pub hand_edited: bool,
+}
+
+impl ConfigPreview {
+    fn new(name: &str, path: Option<&Path>, current: &str, content: String) -> Self {
+        let diff = unified_diff(current, &content, name);
+        Self {
+            name: name.to_string(),
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            changed: !diff.is_empty(),
+            diff,
+            content,
+            hand_edited: false,
+        }
+    }
+
+    /// Preview of a generated file, compared with its current content
+    fn file(name: &str, path: &Path, content: String, settings: &AppSettings) -> Self {
+        let current = fs::read_to_string(path).unwrap_or_default();
+        let mut preview = Self::new(name, Some(path), &current, content);
+        preview.hand_edited = settings.watch_config_files && generated::is_hand_edited(path);
+        preview
+    }
+}
+
+/// Render the configs of every enabled service for `settings`
+///
+/// `running` is the settings MySQL was started with, its options are passed
+/// on the command line so there is no file on disk to compare with.
+pub fn preview_configs(paths: &RuntimePaths, settings: &AppSettings, running: &AppSettings) -> Result<Vec<ConfigPreview>, String> {
+    let mut previews = Vec::new();
+
+    if settings.is_enabled(ServiceType::Caddy) {
+        let content = generator::caddyfile_content(paths, settings.web_port, settings.php_port, settings, &crate::projects::registered())?;
+        previews.push(ConfigPreview::file("Caddyfile", &paths.config_dir.join("Caddyfile"), content, settings));
+    }
+
+    if settings.is_enabled(ServiceType::PhpFpm) {
+        previews.push(ConfigPreview::file("php.ini", &paths.php_ini, generator::php_ini_content(paths, settings)?, settings));
+        if paths.php_cgi.file_name().is_some_and(|n| n == "php-fpm") {
+            let content = generator::php_fpm_conf_content(paths, settings.php_port);
+            previews.push(ConfigPreview::file("php-fpm.conf", &paths.config_dir.join("php-fpm.conf"), content, settings));
+        }
+    }
+
+    if settings.is_enabled(ServiceType::MySQL) {
+        let current = mysql_options(paths, running);
+        previews.push(ConfigPreview::new("my.cnf", None, &current, mysql_options(paths, settings)));
+    }
+
+    if settings.is_enabled(ServiceType::PostgreSQL) {
+        let content = generator::postgresql_conf_content(settings.postgres_port, &settings.listen_addresses());
+        previews.push(ConfigPreview::file("postgresql.conf", &paths.pgsql_data_dir.join("postgresql.conf"), content, settings));
+    }
+
+    Ok(previews)
+}
+
+/// The mysqld command line options, in option file syntax
+fn mysql_options(paths: &RuntimePaths, settings: &AppSettings) -> String {
+    let mut content = String::from("[mysqld]\n");
+    content.push_str(&format!("datadir={}\n", paths.mysql_data_dir.to_string_lossy().replace('\\', "/")));
+    content.push_str(&format!("port={}\n", settings.mysql_port));
+    content.push_str(&format!("bind-address={}\n", crate::process::manager::mysql_bind_address(settings, &paths.mysql)));
+    content.push_str("skip-name-resolve\n");
+    for arg in settings.extra_args.get(ServiceType::MySQL) {
+        content.push_str(arg.trim_start_matches('-'));
+        content.push('\n');
+    }
+    content
+}
+
+/// Line diff of `old` and `new` in unified format, empty when they are equal
+pub fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    // Edit script as (tag, line, old line number, new line number)
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', a[i], i, j));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', a[i], i, j));
+            i += 1;
+        } else {
+            ops.push(('+', b[j], i, j));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = ops.iter().enumerate().filter(|(_, op)| op.0 != ' ').map(|(k, _)| k).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Group changes whose context overlaps into hunks
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &k in &changes {
+        let start = k.saturating_sub(DIFF_CONTEXT);
+        let end = (k + DIFF_CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- {} (current)\n+++ {} (proposed)\n", name, name);
+    for (start, end) in hunks {
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+        // Ranges of zero lines are numbered by the line before them
+        let old_start = if old_len == 0 { hunk[0].2 } else { hunk[0].2 + 1 };
+        let new_start = if new_len == 0 { hunk[0].3 } else { hunk[0].3 + 1 };
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_len, new_start, new_len));
+        for (tag, line, _, _) in hunk {
+            diff.push(*tag);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "x"), "");
+
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n";
+        assert_eq!(
+            unified_diff(old, new, "x"),
+            "--- x (current)\n+++ x (proposed)\n@@ -2,8 +2,9 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n 9\n+10\n"
+        );
+        assert_eq!(unified_diff("", "a\n", "x"), "--- x (current)\n+++ x (proposed)\n@@ -0,0 +1,1 @@\n+a\n");
+    }
+
+    #[test]
+    fn test_preview_writes_nothing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let paths = RuntimePaths {
+            caddy: root.join("caddy"),
+            php_cgi: root.join("php").join("php-cgi"),
+            php_ini: root.join("config").join("php.ini"),
+            php_ext_dir: root.join("php").join("ext"),
+            mysql: root.join("mysql").join("bin").join("mysqld"),
+            phpmyadmin: root.join("phpmyadmin"),
+            pgsql_dir: root.join("postgresql"),
+            adminer: root.join("adminer"),
+            cloudflared: root.join("cloudflared").join("cloudflared"),
+            mailpit: root.join("mailpit").join("mailpit"),
+            node_dir: root.join("node"),
+            minio: root.join("minio").join("minio"),
+            minio_dir: root.join("minio-data"),
+            mysql_data_dir: root.join("mysql").join("data"),
+            pgsql_data_dir: root.join("pgsql").join("data"),
+            logs_dir: root.join("logs"),
+            config_dir: root.join("config"),
+            projects_dir: root.join("projects"),
+        };
+        let running = AppSettings::default();
+        let proposed = AppSettings { mysql_port: 3310, ..Default::default() };
+
+        let previews = preview_configs(&paths, &proposed, &running).unwrap();
+        let names: Vec<&str> = previews.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Caddyfile", "php.ini", "my.cnf", "postgresql.conf"]);
+        assert!(previews[0].changed && previews[0].diff.contains("+    metrics"));
+        assert!(previews[2].diff.contains("-port=3307\n+port=3310\n"));
+        assert!(!paths.config_dir.exists());
+    }
+}
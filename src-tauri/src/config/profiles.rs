@@ -0,0 +1,158 @@
+//! Named settings profiles
+//!
+//! A profile is a complete `AppSettings` snapshot stored as
+//! `config/profiles/<name>.json`. The active profile is always mirrored in
+//! settings.json, which remains the file every other module reads.
+
+use std::fs;
+use std::path::PathBuf;
+
+use super::AppSettings;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+fn profiles_dir() -> Result<PathBuf, String> {
+    AppSettings::config_dir()
+        .map(|dir| dir.join("profiles"))
+        .ok_or_else(|| "Cannot determine profiles directory".to_string())
+}
+
+fn profile_path(name: &str) -> Result<PathBuf, String> {
+    validate_profile_name(name)?;
+    Ok(profiles_dir()?.join(format!("{}.json", name)))
+}
+
+/// Profile names become file names, keep them simple
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > 64
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// List profile names, always including the active one
+pub fn list_profiles() -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = match fs::read_dir(profiles_dir()?) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|e| {
+                let path = e.path();
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    path.file_stem().map(|s| s.to_string_lossy().to_string())
+                } else {
+                    None
+                }
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    let active = AppSettings::load().active_profile;
+    if !names.contains(&active) {
+        names.push(active);
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Load a stored profile
+pub fn load_profile(name: &str) -> Result<AppSettings, String> {
+    let path = profile_path(name)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|_| format!("Profile '{}' does not exist", name))?;
+    let mut settings: AppSettings = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse profile '{}': {}", name, e))?;
+    settings.active_profile = name.to_string();
+    Ok(settings)
+}
+
+/// Store settings under a profile name
+pub fn save_profile(name: &str, settings: &AppSettings) -> Result<(), String> {
+    let path = profile_path(name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create profiles directory: {}", e))?;
+    }
+
+    let mut settings = settings.clone();
+    settings.active_profile = name.to_string();
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write profile '{}': {}", name, e))
+}
+
+/// Create a profile as a copy of the current settings
+pub fn create_profile(name: &str) -> Result<(), String> {
+    if profile_path(name)?.exists() || AppSettings::load().active_profile == name {
+        return Err(format!("Profile '{}' already exists", name));
+    }
+    save_profile(name, &AppSettings::load())
+}
+
+/// Delete a profile; the active profile cannot be deleted
+pub fn delete_profile(name: &str) -> Result<(), String> {
+    if AppSettings::load().active_profile == name {
+        return Err("Cannot delete the active profile, switch to another profile first".to_string());
+    }
+    let path = profile_path(name)?;
+    if !path.exists() {
+        return Err(format!("Profile '{}' does not exist", name));
+    }
+    fs::remove_file(&path)
+        .map_err(|e| format!("Failed to delete profile '{}': {}", name, e))
+}
+
+/// Make `name` the active profile
+///
+/// The current settings are written back to their own profile first so no
+/// changes are lost, then the target profile replaces settings.json. The
+/// database root passwords are not switched: every profile shares the same
+/// data directories, so the target takes the passwords the servers have.
+pub fn activate_profile(name: &str) -> Result<AppSettings, String> {
+    let current = AppSettings::load();
+    if current.active_profile == name {
+        return Ok(current);
+    }
+
+    let mut target = load_profile(name)?;
+    keep_database_passwords(&mut target, &current);
+    save_profile(&current.active_profile, &current)?;
+    target.save()?;
+    Ok(target)
+}
+
+/// Give `target` the database root passwords of `current`
+fn keep_database_passwords(target: &mut AppSettings, current: &AppSettings) {
+    target.mysql_root_password = current.mysql_root_password.clone();
+    target.postgres_root_password = current.postgres_root_password.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("default").is_ok());
+        assert!(validate_profile_name("client-work_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../settings").is_err());
+        assert!(validate_profile_name("has space").is_err());
+    }
+
+    #[test]
+    fn test_keep_database_passwords() {
+        let current = AppSettings { mysql_root_password: "mine".into(), postgres_root_password: "pg".into(), ..AppSettings::default() };
+        let mut target = AppSettings { mysql_root_password: "stale".into(), ..AppSettings::default() };
+        keep_database_passwords(&mut target, &current);
+        assert_eq!(target.mysql_root_password, "mine");
+        assert_eq!(target.postgres_root_password, "pg");
+    }
+}
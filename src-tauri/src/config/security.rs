@@ -0,0 +1,354 @@
+//! Security posture report for the local stack
+//!
+//! Inspects the generated configs, settings and installed components and
+//! reports anything that weakens the default (localhost-only) posture.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::config::AppSettings;
+use crate::runtime::locator::RuntimePaths;
+use crate::runtime::packages::get_available_packages;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+/// A single check in the security report
+#[derive(Debug, Clone, Serialize)]
+pub struct SecurityFinding {
+    pub id: String,
+    pub title: String,
+    pub severity: Severity,
+    /// True when the check found nothing to fix
+    pub passed: bool,
+    pub detail: String,
+    /// Human-readable description of how to fix it
+    pub fix: Option<String>,
+    /// Command the frontend can invoke to apply the fix, if one exists
+    pub fix_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SecurityReport {
+    pub findings: Vec<SecurityFinding>,
+    /// Highest severity among failed checks, `None` if everything passed
+    pub worst: Option<Severity>,
+}
+
+impl SecurityFinding {
+    fn pass(id: &str, title: &str, detail: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            severity: Severity::Info,
+            passed: true,
+            detail: detail.into(),
+            fix: None,
+            fix_command: None,
+        }
+    }
+
+    fn fail(id: &str, title: &str, severity: Severity, detail: impl Into<String>, fix: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            severity,
+            passed: false,
+            detail: detail.into(),
+            fix: Some(fix.to_string()),
+            fix_command: None,
+        }
+    }
+
+    fn with_command(mut self, command: &str) -> Self {
+        self.fix_command = Some(command.to_string());
+        self
+    }
+}
+
+/// Build the security report
+///
+/// `installed` maps component names to their installed version, as returned by
+/// `RuntimeDownloader::get_installed_components`.
+pub fn build_security_report(
+    settings: &AppSettings,
+    paths: Option<&RuntimePaths>,
+    installed: &HashMap<String, String>,
+) -> SecurityReport {
+    let mut findings = vec![
+        check_skip_grant_tables(paths),
+        check_empty_passwords(settings),
+        check_lan_exposure(paths),
+        check_directory_listing(paths),
+        check_phpmyadmin_exposure(settings, paths),
+        check_expose_php(paths),
+    ];
+    findings.extend(check_outdated_components(installed));
+
+    let worst = findings.iter().filter(|f| !f.passed).map(|f| f.severity).max();
+
+    SecurityReport { findings, worst }
+}
+
+/// MySQL option files must not disable the privilege system
+fn check_skip_grant_tables(paths: Option<&RuntimePaths>) -> SecurityFinding {
+    const ID: &str = "mysql_skip_grant_tables";
+    const TITLE: &str = "MySQL privilege checks";
+
+    let Some(paths) = paths else {
+        return SecurityFinding::pass(ID, TITLE, "Runtime not installed");
+    };
+
+    let mut candidates = vec![
+        paths.mysql_data_dir.join("my.cnf"),
+        paths.mysql_data_dir.join("my.ini"),
+        paths.config_dir.join("my.cnf"),
+        paths.config_dir.join("my.ini"),
+    ];
+    // <runtime>/mysql/bin/mysqld -> <runtime>/mysql
+    if let Some(mysql_root) = paths.mysql.parent().and_then(|p| p.parent()) {
+        candidates.push(mysql_root.join("my.cnf"));
+        candidates.push(mysql_root.join("my.ini"));
+    }
+
+    for file in candidates {
+        let Ok(content) = fs::read_to_string(&file) else { continue };
+        let enabled = content.lines().any(|line| {
+            let line = line.trim();
+            !line.starts_with('#') && !line.starts_with(';')
+                && line.replace('_', "-").starts_with("skip-grant-tables")
+        });
+        if enabled {
+            return SecurityFinding::fail(
+                ID,
+                TITLE,
+                Severity::High,
+                format!("skip-grant-tables is enabled in {}; anyone can log in as any user", file.display()),
+                "Remove skip-grant-tables from the option file and restart MySQL",
+            );
+        }
+    }
+
+    SecurityFinding::pass(ID, TITLE, "skip-grant-tables is not enabled")
+}
+
+fn check_empty_passwords(settings: &AppSettings) -> SecurityFinding {
+    const ID: &str = "empty_db_passwords";
+    const TITLE: &str = "Database root passwords";
+
+    let mut empty = Vec::new();
+    if settings.mysql_root_password.is_empty() {
+        empty.push("MySQL");
+    }
+    if settings.postgres_root_password.is_empty() {
+        empty.push("PostgreSQL");
+    }
+
+    if empty.is_empty() {
+        return SecurityFinding::pass(ID, TITLE, "All database root accounts have a password");
+    }
+
+    SecurityFinding::fail(
+        ID,
+        TITLE,
+        Severity::Medium,
+        format!("{} root account has no password", empty.join(" and ")),
+        "Set a root password in Settings",
+    )
+    .with_command("update_db_passwords")
+}
+
+/// Caddy binds every interface unless the site block says otherwise
+fn check_lan_exposure(paths: Option<&RuntimePaths>) -> SecurityFinding {
+    const ID: &str = "lan_exposure";
+    const TITLE: &str = "Network exposure";
+
+    let Some(caddyfile) = read_caddyfile(paths) else {
+        return SecurityFinding::pass(ID, TITLE, "Caddyfile has not been generated yet");
+    };
+
+    let bound_to_loopback = caddyfile.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with("bind ")
+            && line.split_whitespace().skip(1).all(|addr| addr == "127.0.0.1" || addr == "::1" || addr == "localhost")
+    });
+
+    if bound_to_loopback {
+        return SecurityFinding::pass(ID, TITLE, "Web server only listens on localhost");
+    }
+
+    SecurityFinding::fail(
+        ID,
+        TITLE,
+        Severity::Medium,
+        "Web server listens on all network interfaces and may be reachable from the LAN",
+        "Set the bind address to 127.0.0.1 in Settings",
+    )
+}
+
+fn check_directory_listing(paths: Option<&RuntimePaths>) -> SecurityFinding {
+    const ID: &str = "directory_listing";
+    const TITLE: &str = "Directory listing";
+
+    let Some(caddyfile) = read_caddyfile(paths) else {
+        return SecurityFinding::pass(ID, TITLE, "Caddyfile has not been generated yet");
+    };
+
+    if caddyfile.lines().any(|line| line.trim() == "file_server browse") {
+        return SecurityFinding::fail(
+            ID,
+            TITLE,
+            Severity::Low,
+            "Directories without an index file are listed in the browser",
+            "Remove `browse` from file_server if project files should not be listed",
+        );
+    }
+
+    SecurityFinding::pass(ID, TITLE, "Directory listing is disabled")
+}
+
+fn check_phpmyadmin_exposure(settings: &AppSettings, paths: Option<&RuntimePaths>) -> SecurityFinding {
+    const ID: &str = "phpmyadmin_exposure";
+    const TITLE: &str = "phpMyAdmin access";
+
+    let installed = paths.is_some_and(|p| p.phpmyadmin.join("index.php").exists());
+    if !installed {
+        return SecurityFinding::pass(ID, TITLE, "phpMyAdmin is not installed");
+    }
+    if settings.phpmyadmin_auth.is_some() {
+        return SecurityFinding::pass(ID, TITLE, "/phpmyadmin is protected with basic auth");
+    }
+
+    // Without a root password phpMyAdmin logs straight in
+    let severity = if settings.mysql_root_password.is_empty() {
+        Severity::High
+    } else {
+        Severity::Medium
+    };
+
+    SecurityFinding::fail(
+        ID,
+        TITLE,
+        severity,
+        "/phpmyadmin is served without additional authentication",
+        "Protect /phpmyadmin with a username and password",
+    )
+    .with_command("set_phpmyadmin_auth")
+}
+
+fn check_expose_php(paths: Option<&RuntimePaths>) -> SecurityFinding {
+    const ID: &str = "expose_php";
+    const TITLE: &str = "PHP version disclosure";
+
+    let Some(content) = paths.and_then(|p| fs::read_to_string(&p.php_ini).ok()) else {
+        return SecurityFinding::pass(ID, TITLE, "php.ini has not been generated yet");
+    };
+
+    let exposed = content.lines().any(|line| {
+        let line = line.trim();
+        if line.starts_with(';') {
+            return false;
+        }
+        match line.split_once('=') {
+            Some((key, value)) => {
+                key.trim() == "expose_php"
+                    && matches!(value.trim().to_ascii_lowercase().as_str(), "on" | "1" | "true" | "yes")
+            }
+            None => false,
+        }
+    });
+
+    if exposed {
+        return SecurityFinding::fail(
+            ID,
+            TITLE,
+            Severity::Low,
+            "expose_php is On; responses advertise the PHP version",
+            "Set expose_php = Off in php.ini",
+        );
+    }
+
+    SecurityFinding::pass(ID, TITLE, "expose_php is Off")
+}
+
+/// Flag installed components whose version is end-of-life in runtime-config.json
+fn check_outdated_components(installed: &HashMap<String, String>) -> Vec<SecurityFinding> {
+    let packages = get_available_packages();
+
+    let mut known: Vec<(&str, &str, bool)> = Vec::new();
+    for pkg in &packages.php {
+        known.push(("php", &pkg.version, pkg.eol));
+    }
+    for pkg in &packages.mysql {
+        known.push(("mysql", &pkg.version, pkg.eol));
+    }
+    for pkg in &packages.mariadb {
+        known.push(("mariadb", &pkg.version, pkg.eol));
+    }
+    for pkg in &packages.postgresql {
+        known.push(("postgresql", &pkg.version, pkg.eol));
+    }
+    for pkg in &packages.phpmyadmin {
+        known.push(("phpmyadmin", &pkg.version, pkg.eol));
+    }
+    for pkg in &packages.adminer {
+        known.push(("adminer", &pkg.version, pkg.eol));
+    }
+
+    let mut components: Vec<_> = installed.iter().collect();
+    components.sort();
+
+    components
+        .into_iter()
+        .filter(|(component, version)| {
+            known.iter().any(|(c, v, eol)| *eol && c == component && v == version)
+        })
+        .map(|(component, version)| {
+            SecurityFinding::fail(
+                &format!("outdated_{}", component),
+                &format!("{} version", component),
+                Severity::Medium,
+                format!("{} {} is end-of-life and no longer receives security fixes", component, version),
+                "Select a supported version in Settings and reinstall the component",
+            )
+        })
+        .collect()
+}
+
+fn read_caddyfile(paths: Option<&RuntimePaths>) -> Option<String> {
+    paths.and_then(|p| fs::read_to_string(p.config_dir.join("Caddyfile")).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_passwords_flagged() {
+        let settings = AppSettings::default();
+        let report = build_security_report(&settings, None, &HashMap::new());
+
+        let finding = report.findings.iter().find(|f| f.id == "empty_db_passwords").unwrap();
+        assert!(!finding.passed);
+        assert_eq!(finding.fix_command.as_deref(), Some("update_db_passwords"));
+        assert_eq!(report.worst, Some(Severity::Medium));
+    }
+
+    #[test]
+    fn test_passwords_set_passes() {
+        let mut settings = AppSettings::default();
+        settings.mysql_root_password = "secret".to_string();
+        settings.postgres_root_password = "secret".to_string();
+        let report = build_security_report(&settings, None, &HashMap::new());
+
+        assert!(report.findings.iter().all(|f| f.passed));
+        assert_eq!(report.worst, None);
+    }
+}
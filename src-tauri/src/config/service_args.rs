@@ -0,0 +1,213 @@
+//! Per-service extra command-line arguments
+//!
+//! Advanced users can append their own flags to each service command line,
+//! e.g. `--innodb-flush-log-at-trx-commit=2` for MariaDB. Flags that CAMPP
+//! already controls (ports, data and config paths) or that weaken security
+//! are refused, both when settings are saved and again when a service starts,
+//! in case settings.json was edited by hand.
+
+use serde::{Deserialize, Serialize};
+
+use crate::process::ServiceType;
+
+/// Caddy `run` flags; the config and adapter are always ours
+const CADDY_DENIED: &[&str] = &["--config", "--adapter", "--envfile", "--resume", "--pingback"];
+
+/// php-fpm / php-cgi flags
+const PHP_DENIED: &[&str] = &[
+    "-c", "--php-ini", "-y", "--fpm-config", "-b", "-p", "--prefix",
+    "-D", "--daemonize", "-R", "--allow-to-run-as-root", "-n", "--no-php-ini",
+];
+
+/// mysqld / mariadbd options, compared with '_' normalized to '-'
+const MYSQL_DENIED: &[&str] = &[
+    "--datadir", "--port", "--bind-address", "--init-file", "--skip-grant-tables",
+    "--user", "--basedir", "--plugin-dir", "--plugin-load", "--plugin-load-add",
+    "--defaults-file", "--defaults-extra-file", "--no-defaults", "--socket",
+    "--skip-networking", "--secure-file-priv", "--local-infile",
+];
+
+/// mysqld short flags: port, datadir, user, basedir and log warnings
+const MYSQL_DENIED_FLAGS: &[&str] = &["-P", "-h", "-u", "-b", "-W"];
+
+/// postgres short flags
+const POSTGRES_DENIED_FLAGS: &[&str] = &["-D", "-p", "-h", "-k", "-O", "-c", "--single", "--boot"];
+
+/// postgres settings refused both as `-c name=value` and `--name=value`
+const POSTGRES_DENIED_SETTINGS: &[&str] = &[
+    "data_directory", "config_file", "hba_file", "ident_file", "port",
+    "listen_addresses", "unix_socket_directories", "allow_system_table_mods",
+];
+
+/// cloudflared and ngrok flags; the origin, local API and config are ours
+const TUNNEL_DENIED: &[&str] = &[
+    "--url", "--metrics", "--config", "--http-host-header", "--origin-server-name",
+    "--hostname", "--host-header", "--authtoken", "--web-addr", "--log",
+];
+
+/// Mailpit flags; the ports and the loopback binding are ours
+const MAILPIT_DENIED: &[&str] = &["--smtp", "--listen", "-s", "-l"];
+
+/// Memcached flags; the port and the loopback binding are ours
+const MEMCACHED_DENIED: &[&str] = &["--listen", "--port", "--udp-port", "-l", "-p", "-U", "--user", "-u"];
+
+/// MinIO flags; the data directory, ports and loopback binding are ours
+const MINIO_DENIED: &[&str] = &["--address", "--console-address", "--config-dir", "-C"];
+
+/// SFTPGo portable flags; the root, account, ports and log are ours
+const FTP_DENIED: &[&str] = &[
+    "--directory", "-d", "--username", "-u", "--password", "-p", "--permissions", "-g",
+    "--sftpd-port", "-s", "--ftpd-port", "--log-file-path", "-l",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceArgs {
+    #[serde(default)]
+    pub caddy: Vec<String>,
+    #[serde(default)]
+    pub php: Vec<String>,
+    #[serde(default)]
+    pub mysql: Vec<String>,
+    #[serde(default)]
+    pub postgres: Vec<String>,
+    #[serde(default)]
+    pub tunnel: Vec<String>,
+    #[serde(default)]
+    pub mailpit: Vec<String>,
+    #[serde(default)]
+    pub memcached: Vec<String>,
+    #[serde(default)]
+    pub minio: Vec<String>,
+    #[serde(default)]
+    pub ftp: Vec<String>,
+}
+
+impl ServiceArgs {
+    /// Settings field name of the arguments for `service`, e.g. "extra_args.mysql"
+    pub fn field(service: ServiceType) -> &'static str {
+        match service {
+            ServiceType::Caddy => "extra_args.caddy",
+            ServiceType::PhpFpm => "extra_args.php",
+            ServiceType::MySQL => "extra_args.mysql",
+            ServiceType::PostgreSQL => "extra_args.postgres",
+            ServiceType::Tunnel => "extra_args.tunnel",
+            ServiceType::Mailpit => "extra_args.mailpit",
+            ServiceType::Memcached => "extra_args.memcached",
+            ServiceType::Minio => "extra_args.minio",
+            ServiceType::Ftp => "extra_args.ftp",
+        }
+    }
+
+    pub fn get(&self, service: ServiceType) -> &[String] {
+        match service {
+            ServiceType::Caddy => &self.caddy,
+            ServiceType::PhpFpm => &self.php,
+            ServiceType::MySQL => &self.mysql,
+            ServiceType::PostgreSQL => &self.postgres,
+            ServiceType::Tunnel => &self.tunnel,
+            ServiceType::Mailpit => &self.mailpit,
+            ServiceType::Memcached => &self.memcached,
+            ServiceType::Minio => &self.minio,
+            ServiceType::Ftp => &self.ftp,
+        }
+    }
+
+    /// Arguments for `service`, or an error naming the first refused flag
+    pub fn checked(&self, service: ServiceType) -> Result<&[String], String> {
+        let args = self.get(service);
+        match check_args(service, args) {
+            Some(message) => Err(message),
+            None => Ok(args),
+        }
+    }
+}
+
+/// Return why `args` are refused for `service`, if they are
+pub fn check_args(service: ServiceType, args: &[String]) -> Option<String> {
+    for (i, arg) in args.iter().enumerate() {
+        if arg.trim().is_empty() || arg.contains(['\n', '\r', '\0']) {
+            return Some(format!("{} custom arguments must not be empty or span lines", service.display_name()));
+        }
+
+        let name = arg.split('=').next().unwrap_or(arg);
+        let denied = match service {
+            ServiceType::Caddy => CADDY_DENIED.contains(&name),
+            ServiceType::PhpFpm => PHP_DENIED.iter().any(|d| *d == name || (d.len() == 2 && arg.starts_with(d))),
+            ServiceType::MySQL => mysql_denied(name),
+            ServiceType::PostgreSQL => postgres_denied(name, args.get(i + 1)),
+            ServiceType::Tunnel => TUNNEL_DENIED.contains(&name),
+            ServiceType::Mailpit => MAILPIT_DENIED.contains(&name),
+            ServiceType::Memcached => MEMCACHED_DENIED.contains(&name),
+            ServiceType::Minio => MINIO_DENIED.contains(&name),
+            ServiceType::Ftp => FTP_DENIED.contains(&name),
+        };
+        if denied {
+            return Some(format!(
+                "{} argument '{}' is not allowed, CAMPP manages it or it weakens security",
+                service.display_name(), arg
+            ));
+        }
+    }
+    None
+}
+
+fn mysql_denied(name: &str) -> bool {
+    if !name.starts_with("--") {
+        return MYSQL_DENIED_FLAGS.iter().any(|d| name.starts_with(d));
+    }
+    let name = name.replace('_', "-");
+    let name = name.strip_prefix("--loose-").map(|n| format!("--{}", n)).unwrap_or(name);
+    // mysqld accepts any unambiguous prefix of an option name
+    name.len() > 3 && MYSQL_DENIED.iter().any(|d| d.starts_with(name.as_str()) || name == *d)
+}
+
+fn postgres_denied(name: &str, next: Option<&String>) -> bool {
+    if name == "-c" {
+        // Allowed only when the setting itself is harmless
+        let setting = next.map(|n| n.split('=').next().unwrap_or(n).replace('-', "_"));
+        return setting.is_none_or(|s| POSTGRES_DENIED_SETTINGS.contains(&s.as_str()));
+    }
+    if POSTGRES_DENIED_FLAGS.iter().any(|d| *d == name || (d.len() == 2 && name.starts_with(d))) {
+        return true;
+    }
+    name.strip_prefix("--")
+        .is_some_and(|n| POSTGRES_DENIED_SETTINGS.contains(&n.replace('-', "_").as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_allowed_args() {
+        assert!(check_args(ServiceType::MySQL, &args(&["--innodb-flush-log-at-trx-commit=2", "--max_connections=500"])).is_none());
+        assert!(check_args(ServiceType::Caddy, &args(&["--watch"])).is_none());
+        assert!(check_args(ServiceType::PhpFpm, &args(&["-d", "memory_limit=512M"])).is_none());
+        assert!(check_args(ServiceType::PostgreSQL, &args(&["-c", "shared_buffers=256MB", "--work-mem=64MB"])).is_none());
+    }
+
+    #[test]
+    fn test_denied_args() {
+        assert!(check_args(ServiceType::MySQL, &args(&["--skip-grant-tables"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["--skip_grant"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["--loose-datadir=/tmp"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["-P", "3307"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["-P3307"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["-h", "/tmp/x"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["-uroot"])).is_some());
+        assert!(check_args(ServiceType::Caddy, &args(&["--config=/etc/Caddyfile"])).is_some());
+        assert!(check_args(ServiceType::PhpFpm, &args(&["-c/etc/php.ini"])).is_some());
+        assert!(check_args(ServiceType::PostgreSQL, &args(&["-c", "listen_addresses=*"])).is_some());
+        assert!(check_args(ServiceType::PostgreSQL, &args(&["--listen-addresses=*"])).is_some());
+        assert!(check_args(ServiceType::PostgreSQL, &args(&["-p5432"])).is_some());
+        assert!(check_args(ServiceType::MySQL, &args(&["--a\n--b"])).is_some());
+        assert!(check_args(ServiceType::Tunnel, &args(&["--url=http://example.com"])).is_some());
+        assert!(check_args(ServiceType::Mailpit, &args(&["--listen=0.0.0.0:8025"])).is_some());
+        assert!(check_args(ServiceType::Minio, &args(&["--address=0.0.0.0:9000"])).is_some());
+        assert!(check_args(ServiceType::Ftp, &args(&["--directory=/"])).is_some());
+    }
+}
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use crate::process::ServiceType;
 use crate::runtime::packages::PackageSelection;
+use super::service_args::ServiceArgs;
+use crate::process::rotation::LogRotationSettings;
+use crate::runtime::download_cache::DownloadCacheSettings;
+use crate::runtime::system::BinaryOverrides;
 
 pub const DEFAULT_PORTS: Ports = Ports {
     web: 8080,
@@ -19,6 +26,110 @@ pub struct Ports {
 }
 
 fn default_postgres_port() -> u16 { 5433 }
+fn default_https_port() -> u16 { 8443 }
+fn default_domain_tld() -> String { crate::projects::DEFAULT_DOMAIN_TLD.to_string() }
+fn default_auto_backup_max_mb() -> u64 { 512 }
+fn default_enabled_services() -> Vec<ServiceType> {
+    vec![ServiceType::Caddy, ServiceType::PhpFpm, ServiceType::MySQL, ServiceType::PostgreSQL]
+}
+fn default_download_concurrency() -> usize { 3 }
+fn default_bind_address() -> String { "127.0.0.1".to_string() }
+fn default_profile() -> String { super::profiles::DEFAULT_PROFILE.to_string() }
+
+/// HTTP basic auth credentials protecting the /phpmyadmin route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasicAuthCredentials {
+    pub username: String,
+    /// bcrypt hash of the password, as expected by Caddy's `basic_auth`
+    pub password_hash: String,
+}
+
+/// A response header added by Caddy to every request on the main site
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl SecurityHeader {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+fn default_security_headers() -> Vec<SecurityHeader> {
+    vec![
+        SecurityHeader::new("X-Content-Type-Options", "nosniff"),
+        SecurityHeader::new("X-Frame-Options", "SAMEORIGIN"),
+        SecurityHeader::new("Referrer-Policy", "no-referrer"),
+    ]
+}
+
+/// Encoding of Caddy access logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessLogFormat {
+    #[default]
+    Json,
+    /// Caddy's human-readable line format (Caddy v2 has no Apache CLF encoder)
+    #[serde(alias = "common")]
+    Console,
+}
+
+/// Access log options for the Caddyfile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessLogSettings {
+    #[serde(default = "crate::util::default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: AccessLogFormat,
+    /// Write one `access-<host>.log` per site instead of a shared caddy-access.log
+    #[serde(default)]
+    pub per_site: bool,
+}
+
+impl Default for AccessLogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            format: AccessLogFormat::Json,
+            per_site: false,
+        }
+    }
+}
+
+/// A single validation problem tied to a settings field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// AppSettings field name, e.g. "web_port"
+    pub field: String,
+    /// Machine-readable code, e.g. "port_in_use"
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationResult {
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationResult {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn error(&mut self, field: &str, code: &str, message: String) {
+        self.errors.push(ValidationIssue { field: field.to_string(), code: code.to_string(), message });
+    }
+
+    fn warning(&mut self, field: &str, code: &str, message: String) {
+        self.warnings.push(ValidationIssue { field: field.to_string(), code: code.to_string(), message });
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
@@ -27,6 +138,14 @@ pub struct AppSettings {
     pub mysql_port: u16,
     #[serde(default = "default_postgres_port")]
     pub postgres_port: u16,
+    /// Port of the HTTPS project sites; 443 needs administrator rights on
+    /// macOS and Linux
+    #[serde(default = "default_https_port")]
+    pub https_port: u16,
+    /// Redirect plain HTTP to HTTPS for HTTPS projects without their own
+    /// choice
+    #[serde(default)]
+    pub https_redirect: bool,
     pub project_root: String,
     #[serde(default)]
     pub auto_start_services: bool,
@@ -36,6 +155,100 @@ pub struct AppSettings {
     pub mysql_root_password: String,
     #[serde(default)]
     pub postgres_root_password: String,
+    /// When set, phpMyAdmin is served behind Caddy `basic_auth`
+    #[serde(default)]
+    pub phpmyadmin_auth: Option<BasicAuthCredentials>,
+    #[serde(default = "default_security_headers")]
+    pub security_headers: Vec<SecurityHeader>,
+    /// Make runtime binaries read-only after installation and verify them at startup
+    #[serde(default)]
+    pub harden_runtime: bool,
+    /// Take a backup before destructive operations (drop database, reset, upgrades)
+    #[serde(default = "crate::util::default_true")]
+    pub auto_backup: bool,
+    /// Skip automatic backups larger than this
+    #[serde(default = "default_auto_backup_max_mb")]
+    pub auto_backup_max_mb: u64,
+    #[serde(default)]
+    pub access_log: AccessLogSettings,
+    /// Size and age limits for the service logs
+    #[serde(default)]
+    pub log_rotation: LogRotationSettings,
+    /// Name of the profile these settings belong to
+    #[serde(default = "default_profile")]
+    pub active_profile: String,
+    /// Extra command-line arguments appended to each service
+    #[serde(default)]
+    pub extra_args: ServiceArgs,
+    /// Cleanup policy for the temporary download directory
+    #[serde(default)]
+    pub download_cache: DownloadCacheSettings,
+    /// Install runtime archives that have no checksum in runtime-config.json
+    #[serde(default)]
+    pub allow_unverified_downloads: bool,
+    /// Use published runtime manifests without a valid signature
+    #[serde(default)]
+    pub allow_unsigned_manifests: bool,
+    /// Runtime components downloaded at the same time
+    #[serde(default = "default_download_concurrency")]
+    pub download_concurrency: usize,
+    /// Services managed by CAMPP; the others are never started or configured
+    #[serde(default = "default_enabled_services")]
+    pub enabled_services: Vec<ServiceType>,
+    /// Address Caddy and the databases listen on: loopback, 0.0.0.0/:: or one interface.
+    /// PHP-FPM always stays on loopback, an exposed FastCGI port allows running arbitrary code.
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+    /// Reload services when the Caddyfile or php.ini is edited by hand, and
+    /// keep those edits instead of regenerating the files on start
+    #[serde(default)]
+    pub watch_config_files: bool,
+    /// System-installed binaries used instead of downloaded ones
+    #[serde(default)]
+    pub binary_overrides: BinaryOverrides,
+    /// Extra PHP versions projects can be pinned to, served next to the main PHP
+    #[serde(default)]
+    pub php_versions: Vec<crate::process::php_pool::PhpBackend>,
+    /// Editor projects are opened in; None uses the first one installed
+    #[serde(default)]
+    pub editor: Option<crate::editor::Editor>,
+    /// The welcome project was created after the first runtime install; it
+    /// is not recreated once the user deletes it
+    #[serde(default)]
+    pub welcome_project_created: bool,
+    /// Wildcard DNS answering every project domain with loopback
+    #[serde(default)]
+    pub dns: crate::process::dns::DnsSettings,
+    /// TLD of project domains, e.g. `test` for `shop.test`
+    #[serde(default = "default_domain_tld")]
+    pub domain_tld: String,
+    /// Public tunnel to a project
+    #[serde(default)]
+    pub tunnel: crate::process::tunnel::TunnelSettings,
+    /// Mailpit SMTP and web UI ports
+    #[serde(default)]
+    pub mailpit: crate::process::mailpit::MailpitSettings,
+    /// Memcached port, memory and binary
+    #[serde(default)]
+    pub memcached: crate::process::memcached::MemcachedSettings,
+    /// MinIO API and console ports
+    #[serde(default)]
+    pub minio: crate::process::minio::MinioSettings,
+    /// SFTP and FTP ports and binary
+    #[serde(default)]
+    pub ftp: crate::process::ftp::FtpSettings,
+}
+
+fn default_project_root() -> String {
+    super::data_dir::data_dir_override()
+        .unwrap_or_else(|| {
+            dirs::data_local_dir()
+                .unwrap_or_else(|| dirs::home_dir().unwrap_or_default())
+                .join("campp")
+        })
+        .join("projects")
+        .to_string_lossy()
+        .to_string()
 }
 
 impl Default for AppSettings {
@@ -45,29 +258,145 @@ impl Default for AppSettings {
             php_port: DEFAULT_PORTS.php,
             mysql_port: DEFAULT_PORTS.mysql,
             postgres_port: DEFAULT_PORTS.postgres,
-            project_root: dirs::data_local_dir()
-                .unwrap_or_else(|| dirs::home_dir().unwrap_or_default())
-                .join("campp")
-                .join("projects")
-                .to_string_lossy()
-                .to_string(),
+            https_port: default_https_port(),
+            https_redirect: false,
+            project_root: default_project_root(),
             auto_start_services: false,
             package_selection: PackageSelection::default(),
             mysql_root_password: String::new(),
             postgres_root_password: String::new(),
+            phpmyadmin_auth: None,
+            security_headers: default_security_headers(),
+            harden_runtime: false,
+            auto_backup: true,
+            auto_backup_max_mb: default_auto_backup_max_mb(),
+            access_log: AccessLogSettings::default(),
+            log_rotation: LogRotationSettings::default(),
+            active_profile: default_profile(),
+            extra_args: ServiceArgs::default(),
+            download_cache: DownloadCacheSettings::default(),
+            allow_unverified_downloads: false,
+            allow_unsigned_manifests: false,
+            download_concurrency: default_download_concurrency(),
+            enabled_services: default_enabled_services(),
+            bind_address: default_bind_address(),
+            watch_config_files: false,
+            binary_overrides: BinaryOverrides::default(),
+            php_versions: Vec::new(),
+            editor: None,
+            welcome_project_created: false,
+            dns: Default::default(),
+            domain_tld: default_domain_tld(),
+            tunnel: Default::default(),
+            mailpit: Default::default(),
+            memcached: Default::default(),
+            minio: Default::default(),
+            ftp: Default::default(),
         }
     }
 }
 
 impl AppSettings {
+    /// The same settings with the database root passwords and the phpMyAdmin
+    /// login blanked, for anything that leaves settings.json
+    pub fn without_secrets(&self) -> Self {
+        Self {
+            mysql_root_password: String::new(),
+            postgres_root_password: String::new(),
+            phpmyadmin_auth: None,
+            ..self.clone()
+        }
+    }
+
+    /// Take the secrets of `local`, for settings that come without them
+    pub fn keep_secrets_of(&mut self, local: &AppSettings) {
+        self.mysql_root_password = local.mysql_root_password.clone();
+        self.postgres_root_password = local.postgres_root_password.clone();
+        self.phpmyadmin_auth = local.phpmyadmin_auth.clone();
+    }
+
     /// Get the path to the settings file
     fn settings_path() -> Option<PathBuf> {
+        if let Some(data_dir) = super::data_dir::data_dir_override() {
+            return Some(data_dir.join("config").join("settings.json"));
+        }
         dirs::data_local_dir()
             .map(|p| p.join("campp").join("config").join("settings.json"))
     }
 
+    pub fn is_enabled(&self, service: ServiceType) -> bool {
+        self.enabled_services.contains(&service)
+    }
+
+    /// Whether services are reachable from other machines
+    pub fn is_lan_exposed(&self) -> bool {
+        self.bind_address.parse::<IpAddr>().is_ok_and(|ip| !ip.is_loopback())
+    }
+
+    /// Addresses Caddy and the databases listen on
+    ///
+    /// Loopback is kept next to a specific interface so local clients and
+    /// health checks can keep connecting to 127.0.0.1.
+    pub fn listen_addresses(&self) -> Vec<String> {
+        match self.bind_address.parse::<IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => vec![ip.to_string()],
+            Ok(ip) if !ip.is_loopback() => vec!["127.0.0.1".to_string(), ip.to_string()],
+            _ => vec!["127.0.0.1".to_string()],
+        }
+    }
+
+    /// Directory projects are served from
+    ///
+    /// The default project root predates the setting taking effect, so it
+    /// keeps resolving to `default`, the app data projects directory.
+    pub fn projects_dir(&self, default: &Path) -> PathBuf {
+        if self.project_root.trim().is_empty() || self.project_root == default_project_root() {
+            default.to_path_buf()
+        } else {
+            PathBuf::from(&self.project_root)
+        }
+    }
+
+    /// Directory holding settings.json and other app-level config
+    pub fn config_dir() -> Option<PathBuf> {
+        Self::settings_path().and_then(|p| p.parent().map(|p| p.to_path_buf()))
+    }
+
     /// Load settings from file, or return defaults if file doesn't exist
+    ///
+    /// `CAMPP_*` environment overrides are layered on top.
     pub fn load() -> Self {
+        let saved = Self::load_saved();
+        let overrides = super::env::active_overrides();
+        if overrides.is_empty() {
+            return saved;
+        }
+
+        let Ok(Value::Object(mut json)) = serde_json::to_value(&saved) else { return saved };
+        super::env::apply(&mut json, overrides);
+        match serde_json::from_value(Value::Object(json)) {
+            Ok(settings) => settings,
+            Err(e) => {
+                tracing::warn!("Failed to apply environment overrides: {}", e);
+                saved
+            }
+        }
+    }
+
+    /// Settings from the content of a settings.json, e.g. a backed up one
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let mut settings = serde_json::from_str::<Self>(content).map_err(|e| e.to_string())?;
+        // Saved relative to the data directory in portable mode
+        if let Some(data_dir) = super::data_dir::data_dir_override() {
+            if !settings.project_root.is_empty() && Path::new(&settings.project_root).is_relative() {
+                settings.project_root = data_dir.join(&settings.project_root).to_string_lossy().to_string();
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Settings as saved in settings.json, without environment overrides
+    fn load_saved() -> Self {
         let path = match Self::settings_path() {
             Some(p) => p,
             None => return Self::default(),
@@ -79,7 +408,7 @@ impl AppSettings {
 
         match fs::read_to_string(&path) {
             Ok(content) => {
-                match serde_json::from_str(&content) {
+                match Self::from_json(&content) {
                     Ok(settings) => settings,
                     Err(e) => {
                         tracing::warn!("Failed to parse settings file: {}, using defaults", e);
@@ -105,7 +434,28 @@ impl AppSettings {
                 .map_err(|e| format!("Failed to create config directory: {}", e))?;
         }
 
-        let content = serde_json::to_string_pretty(self)
+        let mut json = serde_json::to_value(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+        // Environment overrides are never persisted
+        let overrides = super::env::active_overrides();
+        if let (Value::Object(json), false) = (&mut json, overrides.is_empty()) {
+            if let Ok(Value::Object(saved)) = serde_json::to_value(Self::load_saved()) {
+                super::env::strip(json, &saved, overrides);
+            }
+        }
+
+        // A USB stick can be mounted elsewhere next time, keep the project root relative
+        if super::data_dir::is_portable() {
+            if let Some(data_dir) = super::data_dir::data_dir_override() {
+                if let Ok(rel) = Path::new(&self.project_root).strip_prefix(&data_dir) {
+                    let rel = rel.to_string_lossy().replace('\\', "/");
+                    json["project_root"] = Value::from(if rel.is_empty() { ".".to_string() } else { rel });
+                }
+            }
+        }
+
+        let content = serde_json::to_string_pretty(&json)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
         fs::write(&path, content)
@@ -115,57 +465,141 @@ impl AppSettings {
     }
 
     /// Validate settings (check for port conflicts, valid paths, etc.)
-    pub fn validate(&self) -> Result<Vec<String>, Vec<String>> {
-        let mut warnings = Vec::new();
-        let mut errors = Vec::new();
+    ///
+    /// Errors block saving; warnings are informational (e.g. a port that is
+    /// busy because the service itself is running on it).
+    pub fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::default();
 
         // Check if project root exists
         let project_path = PathBuf::from(&self.project_root);
-        if !project_path.exists() {
-            warnings.push(format!(
-                "Project root '{}' does not exist. It will be created when services start.",
+        if self.project_root.trim().is_empty() {
+            result.error("project_root", "required", "Project root must not be empty".to_string());
+        } else if self.project_root == default_project_root() {
+            if !project_path.exists() {
+                result.warning("project_root", "not_found", format!(
+                    "Project root '{}' does not exist. It will be created when services start.",
+                    self.project_root
+                ));
+            }
+        } else if !project_path.is_dir() {
+            result.error("project_root", "not_found", format!(
+                "Project root '{}' does not exist or is not a directory",
                 self.project_root
             ));
+        } else if let Err(e) = fs::read_dir(&project_path) {
+            result.error("project_root", "not_readable", format!(
+                "Project root '{}' is not readable: {}",
+                self.project_root, e
+            ));
         }
 
-        // Check for port conflicts
-        if let Err(e) = std::net::TcpListener::bind(format!("127.0.0.1:{}", self.web_port)) {
-            warnings.push(format!(
-                "Web port {} may be in use: {}",
-                self.web_port, e
-            ));
+        // Ports of disabled services are never bound
+        let ports: Vec<(&str, &str, u16)> = [
+            (ServiceType::Caddy, "web_port", "Web", self.web_port),
+            (ServiceType::Caddy, "https_port", "HTTPS", self.https_port),
+            (ServiceType::PhpFpm, "php_port", "PHP-FPM", self.php_port),
+            (ServiceType::MySQL, "mysql_port", "MySQL", self.mysql_port),
+            (ServiceType::PostgreSQL, "postgres_port", "PostgreSQL", self.postgres_port),
+            (ServiceType::Tunnel, "tunnel.port", "Tunnel", self.tunnel.port),
+            (ServiceType::Mailpit, "mailpit.smtp_port", "Mailpit SMTP", self.mailpit.smtp_port),
+            (ServiceType::Mailpit, "mailpit.ui_port", "Mailpit web UI", self.mailpit.ui_port),
+            (ServiceType::Memcached, "memcached.port", "Memcached", self.memcached.port),
+            (ServiceType::Minio, "minio.port", "MinIO", self.minio.port),
+            (ServiceType::Minio, "minio.console_port", "MinIO console", self.minio.console_port),
+            (ServiceType::Ftp, "ftp.sftp_port", "SFTP", self.ftp.sftp_port),
+            (ServiceType::Ftp, "ftp.ftp_port", "FTP", self.ftp.ftp_port),
+        ]
+        .into_iter()
+        .filter(|(service, ..)| self.is_enabled(*service))
+        .map(|(_, field, label, port)| (field, label, port))
+        .collect();
+
+        for (i, (field, label, port)) in ports.iter().enumerate() {
+            // Check for valid port ranges
+            if *port == 0 {
+                result.error(field, "port_zero", "Port numbers must be greater than 0".to_string());
+                continue;
+            }
+
+            // Two services can never share a port
+            if let Some((_, other, _)) = ports[..i].iter().find(|(_, _, p)| p == port) {
+                result.error(field, "port_duplicate", format!(
+                    "Port {} is already assigned to {}", port, other
+                ));
+                continue;
+            }
+
+            // Check for port conflicts
+            if let Err(e) = std::net::TcpListener::bind(format!("127.0.0.1:{}", port)) {
+                let message = match super::port_owner(*port) {
+                    Some(owner) => format!("{} port {} is in use by {}", label, port, owner),
+                    None => format!("{} port {} may be in use: {}", label, port, e),
+                };
+                result.warning(field, "port_in_use", message);
+            }
         }
 
-        if let Err(e) = std::net::TcpListener::bind(format!("127.0.0.1:{}", self.php_port)) {
-            warnings.push(format!(
-                "PHP-FPM port {} may be in use: {}",
-                self.php_port, e
-            ));
+        self.validate_bind_address(&mut result);
+        self.validate_domain_tld(&mut result);
+
+        for service in ServiceType::start_order() {
+            if let Some(message) = super::service_args::check_args(service, self.extra_args.get(service)) {
+                result.error(ServiceArgs::field(service), "arg_denied", message);
+            }
         }
 
-        if let Err(e) = std::net::TcpListener::bind(format!("127.0.0.1:{}", self.mysql_port)) {
-            warnings.push(format!(
-                "MySQL port {} may be in use: {}",
-                self.mysql_port, e
-            ));
+        result
+    }
+
+    fn validate_domain_tld(&self, result: &mut ValidationResult) {
+        match crate::projects::validate_tld(&self.domain_tld).as_deref() {
+            Err(e) => result.error("domain_tld", "invalid_tld", e.to_string()),
+            Ok("local") => result.warning("domain_tld", "mdns_tld",
+                ".local is resolved with mDNS (Bonjour, Avahi), lookups of project domains can be slow or fail".to_string()),
+            // HSTS preloaded TLDs
+            Ok(tld @ ("dev" | "app" | "page")) => result.warning("domain_tld", "https_only_tld", format!(
+                "Browsers only open .{} sites over HTTPS, turn on HTTPS for every project", tld
+            )),
+            Ok(_) => {}
         }
+    }
 
-        if let Err(e) = std::net::TcpListener::bind(format!("127.0.0.1:{}", self.postgres_port)) {
-            warnings.push(format!(
-                "PostgreSQL port {} may be in use: {}",
-                self.postgres_port, e
-            ));
+    fn validate_bind_address(&self, result: &mut ValidationResult) {
+        let ip = match self.bind_address.parse::<IpAddr>() {
+            Ok(ip) => ip,
+            Err(_) => {
+                result.error("bind_address", "invalid_address", format!(
+                    "'{}' is not an IP address, use 127.0.0.1, 0.0.0.0 or an interface address",
+                    self.bind_address
+                ));
+                return;
+            }
+        };
+        if ip.is_loopback() {
+            return;
         }
 
-        // Check for valid port ranges
-        if self.web_port == 0 || self.php_port == 0 || self.mysql_port == 0 || self.postgres_port == 0 {
-            errors.push("Port numbers must be greater than 0".to_string());
+        if !ip.is_unspecified() && std::net::TcpListener::bind((ip, 0)).is_err() {
+            result.error("bind_address", "address_unavailable", format!(
+                "No network interface on this machine has the address {}", ip
+            ));
+            return;
         }
 
-        if errors.is_empty() {
-            Ok(warnings)
-        } else {
-            Err(errors)
+        result.warning("bind_address", "lan_exposed", format!(
+            "Caddy and the databases will be reachable from other machines on the network via {}", ip
+        ));
+        if self.is_enabled(ServiceType::MySQL) {
+            let mut message = format!(
+                "WARNING: MariaDB will accept connections from the network on port {}. \
+                 Only do this on a trusted network.",
+                self.mysql_port
+            );
+            if self.mysql_root_password.is_empty() {
+                message.push_str(" The root account has NO PASSWORD, set one first.");
+            }
+            result.warning("bind_address", "mysql_exposed", message);
         }
     }
 }
@@ -0,0 +1,78 @@
+//! URLs of the pages CAMPP serves
+//!
+//! Built from the current settings so the frontend never hard-codes a port
+//! that may have been changed.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::AppSettings;
+
+/// A page served by Caddy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SiteTarget {
+    /// The projects root
+    Site,
+    #[serde(rename = "phpmyadmin")]
+    PhpMyAdmin,
+    Adminer,
+    /// A directory of the projects root, served under its name
+    Project { name: String },
+    /// A virtual host added with `caddy_add_vhost`
+    Vhost { host: String },
+}
+
+/// URL of `target`, e.g. `http://localhost:8080/phpmyadmin/`
+///
+/// Caddy always listens on loopback, so `localhost` works whatever the bind
+/// address is.
+pub fn site_url(target: &SiteTarget, settings: &AppSettings, projects_dir: &Path) -> Result<String, String> {
+    let origin = |host: &str| match settings.web_port {
+        80 => format!("http://{}", host),
+        port => format!("http://{}:{}", host, port),
+    };
+
+    match target {
+        SiteTarget::Site => Ok(format!("{}/", origin("localhost"))),
+        SiteTarget::PhpMyAdmin => Ok(format!("{}/phpmyadmin/", origin("localhost"))),
+        SiteTarget::Adminer => Ok(format!("{}/adminer/", origin("localhost"))),
+        SiteTarget::Project { name } => {
+            let valid = !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\']);
+            if !valid || !projects_dir.join(name).is_dir() {
+                return Err(format!("Project '{}' not found in {}", name, projects_dir.display()));
+            }
+            Ok(format!("{}/{}/", origin("localhost"), name))
+        }
+        SiteTarget::Vhost { host } => {
+            let host = host.trim().to_ascii_lowercase();
+            if host.is_empty() || !host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+                return Err(format!("Invalid host name: {}", host));
+            }
+            Ok(format!("{}/", origin(&host)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_site_url() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("blog")).unwrap();
+        let settings = AppSettings { web_port: 8081, ..Default::default() };
+
+        assert_eq!(site_url(&SiteTarget::PhpMyAdmin, &settings, temp_dir.path()).unwrap(), "http://localhost:8081/phpmyadmin/");
+        let blog = SiteTarget::Project { name: "blog".to_string() };
+        assert_eq!(site_url(&blog, &settings, temp_dir.path()).unwrap(), "http://localhost:8081/blog/");
+        let missing = SiteTarget::Project { name: "../blog".to_string() };
+        assert!(site_url(&missing, &settings, temp_dir.path()).is_err());
+
+        let settings = AppSettings { web_port: 80, ..Default::default() };
+        let vhost = SiteTarget::Vhost { host: "Shop.test".to_string() };
+        assert_eq!(site_url(&vhost, &settings, temp_dir.path()).unwrap(), "http://shop.test/");
+    }
+}
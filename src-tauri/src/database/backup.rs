@@ -0,0 +1,291 @@
+//! Automatic backups taken before destructive operations
+//!
+//! Backups live in `<app data>/backups`, outside every directory that
+//! `reset_installation` removes, and are indexed in `backups.json` so the
+//! frontend can list them and offer an undo.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppSettings;
+use crate::operations::{Operation, OperationKind};
+use crate::runtime::locator::{get_app_data_paths, RuntimePaths};
+use crate::util::{copy_dir, dir_size, now};
+
+const INDEX_FILE: &str = "backups.json";
+/// `source` of settings snapshots
+const SETTINGS_SOURCE: &str = "settings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    /// SQL dump of a single database
+    DatabaseDump,
+    /// Copy of a directory (data dir, component files)
+    Snapshot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub id: String,
+    pub kind: BackupKind,
+    /// What was backed up, e.g. a database name or "mysql-data"
+    pub source: String,
+    /// The operation that triggered the backup
+    pub reason: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+/// Result of a destructive operation, with the backup taken beforehand
+#[derive(Debug, Clone, Serialize)]
+pub struct DestructiveOpResult {
+    pub message: String,
+    pub backup: Option<BackupRecord>,
+}
+
+/// Directory holding all backups
+pub fn backups_dir() -> Result<PathBuf, String> {
+    Ok(get_app_data_paths()?.base_dir.join("backups"))
+}
+
+/// List recorded backups, newest first
+pub fn list_backups() -> Result<Vec<BackupRecord>, String> {
+    let mut records = read_index(&backups_dir()?);
+    records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+    Ok(records)
+}
+
+/// Delete a backup and remove it from the index
+pub fn delete_backup(id: &str) -> Result<(), String> {
+    let dir = backups_dir()?;
+    let mut records = read_index(&dir);
+    let index = records.iter().position(|r| r.id == id)
+        .ok_or_else(|| format!("Backup not found: {}", id))?;
+    let record = records.remove(index);
+
+    let path = PathBuf::from(&record.path);
+    // Never follow a tampered index outside the backups directory
+    if path.starts_with(&dir) && path.exists() {
+        let result = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        result.map_err(|e| format!("Failed to delete backup: {}", e))?;
+    }
+
+    write_index(&dir, &records)
+}
+
+/// Dump a MySQL database before it is dropped
+///
+/// Returns `Ok(None)` when automatic backups are disabled or the database is
+/// larger than the configured threshold.
+pub fn backup_mysql_database(
+    paths: &RuntimePaths,
+    settings: &AppSettings,
+    name: &str,
+    reason: &str,
+) -> Result<Option<BackupRecord>, String> {
+    if !settings.auto_backup {
+        return Ok(None);
+    }
+
+    // On-disk size is a good enough estimate of the dump size
+    let estimate = dir_size(&paths.mysql_data_dir.join(name));
+    if exceeds_threshold(settings, estimate, name) {
+        return Ok(None);
+    }
+
+    let dir = backups_dir()?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create backups directory: {}", e))?;
+
+    let _awake = crate::process::power::SleepInhibitor::acquire("Backing up database");
+    let id = new_id(name);
+    let dest = dir.join(format!("{}.sql", id));
+    let op = Operation::start(OperationKind::Backup, false, format!("Backing up database '{}'", name));
+    op.progress("dumping", None, format!("Dumping '{}' to {}", name, dest.display()));
+    let dumped = super::mysql::dump_database(paths, settings.mysql_port, &settings.mysql_root_password, name, &dest);
+    op.finish(&dumped);
+    dumped?;
+
+    let size_bytes = fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+    record(&dir, BackupRecord {
+        id,
+        kind: BackupKind::DatabaseDump,
+        source: name.to_string(),
+        reason: reason.to_string(),
+        path: dest.to_string_lossy().to_string(),
+        size_bytes,
+        created_at: now(),
+    }).map(Some)
+}
+
+/// Copy a directory into the backups folder
+///
+/// The caller is responsible for making sure nothing is writing to `src`
+/// (stop the service first). Returns `Ok(None)` when automatic backups are
+/// disabled, `src` is missing or empty, or it exceeds the size threshold.
+pub fn snapshot_directory(
+    settings: &AppSettings,
+    src: &Path,
+    source: &str,
+    reason: &str,
+) -> Result<Option<BackupRecord>, String> {
+    if !settings.auto_backup || !src.is_dir() {
+        return Ok(None);
+    }
+
+    let size_bytes = dir_size(src);
+    if size_bytes == 0 || exceeds_threshold(settings, size_bytes, source) {
+        return Ok(None);
+    }
+
+    let dir = backups_dir()?;
+    let _awake = crate::process::power::SleepInhibitor::acquire("Taking a backup snapshot");
+    let id = new_id(source);
+    let dest = dir.join(&id);
+    if let Err(e) = copy_dir(src, &dest, &[]) {
+        let _ = fs::remove_dir_all(&dest);
+        return Err(e);
+    }
+
+    record(&dir, BackupRecord {
+        id,
+        kind: BackupKind::Snapshot,
+        source: source.to_string(),
+        reason: reason.to_string(),
+        path: dest.to_string_lossy().to_string(),
+        size_bytes,
+        created_at: now(),
+    }).map(Some)
+}
+
+/// Copy settings.json and the generated config files before a settings reset
+///
+/// Taken whatever `auto_backup` says: the files are small and the backup is
+/// what makes the reset undoable.
+pub fn snapshot_settings(reason: &str) -> Result<BackupRecord, String> {
+    let settings_dir = AppSettings::config_dir().ok_or("Cannot determine settings directory")?;
+    let config_dir = get_app_data_paths()?.config_dir;
+
+    let dir = backups_dir()?;
+    let id = new_id(SETTINGS_SOURCE);
+    let dest = dir.join(&id);
+    let result = if settings_dir.is_dir() {
+        copy_dir(&settings_dir, &dest, &[])
+    } else {
+        fs::create_dir_all(&dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))
+    };
+    // On Windows the generated configs live beside the executable
+    let result = result.and_then(|_| match config_dir != settings_dir && config_dir.is_dir() {
+        true => copy_dir(&config_dir, &dest.join("generated"), &[]),
+        false => Ok(()),
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&dest);
+        return Err(e);
+    }
+
+    record(&dir, BackupRecord {
+        id,
+        kind: BackupKind::Snapshot,
+        source: SETTINGS_SOURCE.to_string(),
+        reason: reason.to_string(),
+        size_bytes: dir_size(&dest),
+        path: dest.to_string_lossy().to_string(),
+        created_at: now(),
+    })
+}
+
+/// Settings stored in a `snapshot_settings` backup
+pub fn read_settings_backup(id: &str) -> Result<AppSettings, String> {
+    let record = list_backups()?
+        .into_iter()
+        .find(|r| r.id == id && r.source == SETTINGS_SOURCE)
+        .ok_or_else(|| format!("Settings backup not found: {}", id))?;
+    let path = Path::new(&record.path).join("settings.json");
+    if !path.exists() {
+        // Taken before settings were ever saved
+        return Ok(AppSettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    AppSettings::from_json(&content)
+        .map_err(|e| format!("Failed to parse backed up settings: {}", e))
+}
+
+fn exceeds_threshold(settings: &AppSettings, size_bytes: u64, source: &str) -> bool {
+    let limit = settings.auto_backup_max_mb.saturating_mul(1024 * 1024);
+    if size_bytes > limit {
+        tracing::warn!(
+            "Skipping automatic backup of {}: {} MB exceeds the {} MB limit",
+            source, size_bytes / (1024 * 1024), settings.auto_backup_max_mb
+        );
+        return true;
+    }
+    false
+}
+
+fn record(dir: &Path, record: BackupRecord) -> Result<BackupRecord, String> {
+    let mut records = read_index(dir);
+    records.push(record.clone());
+    write_index(dir, &records)?;
+    tracing::info!("Backup of {} saved to {}", record.source, record.path);
+    Ok(record)
+}
+
+fn read_index(dir: &Path) -> Vec<BackupRecord> {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(dir: &Path, records: &[BackupRecord]) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create backups directory: {}", e))?;
+    let json = serde_json::to_string_pretty(records)
+        .map_err(|e| format!("Failed to serialize backups index: {}", e))?;
+    fs::write(dir.join(INDEX_FILE), json)
+        .map_err(|e| format!("Failed to write backups index: {}", e))
+}
+
+fn new_id(source: &str) -> String {
+    format!("{}-{}", now(), source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_threshold() {
+        let settings = AppSettings { auto_backup_max_mb: 1, ..Default::default() };
+        assert!(!exceeds_threshold(&settings, 1024 * 1024, "db"));
+        assert!(exceeds_threshold(&settings, 1024 * 1024 + 1, "db"));
+    }
+
+    #[test]
+    fn test_index_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        assert!(read_index(dir).is_empty());
+
+        record(dir, BackupRecord {
+            id: "1-db".to_string(),
+            kind: BackupKind::DatabaseDump,
+            source: "db".to_string(),
+            reason: "drop_database".to_string(),
+            path: dir.join("1-db.sql").to_string_lossy().to_string(),
+            size_bytes: 10,
+            created_at: 1,
+        }).unwrap();
+
+        let records = read_index(dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].kind, BackupKind::DatabaseDump);
+    }
+}
@@ -0,0 +1,144 @@
+//! Connection strings
+//!
+//! Ready-to-paste MySQL connection snippets built from the current port and
+//! root password, in the forms PHP and Java projects expect.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DsnFormat {
+    /// `new mysqli(...)`
+    Mysqli,
+    /// `new PDO(...)`
+    Pdo,
+    /// The `DB_*` block of a Laravel `.env`
+    LaravelEnv,
+    Jdbc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+}
+
+impl ConnectionInfo {
+    /// Root credentials of the bundled server
+    ///
+    /// Clients run on this machine, so the host is loopback whatever the
+    /// bind address is.
+    pub fn mysql(settings: &AppSettings) -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: settings.mysql_port,
+            user: "root".to_string(),
+            password: settings.mysql_root_password.clone(),
+        }
+    }
+}
+
+/// Snippet connecting to `database`, or to no database in particular
+pub fn connection_string(format: DsnFormat, info: &ConnectionInfo, database: Option<&str>) -> String {
+    let database = database.unwrap_or_default();
+    match format {
+        DsnFormat::Mysqli => format!(
+            "$mysqli = new mysqli('{}', '{}', '{}', '{}', {});",
+            php_escape(&info.host),
+            php_escape(&info.user),
+            php_escape(&info.password),
+            php_escape(database),
+            info.port
+        ),
+        DsnFormat::Pdo => {
+            let mut dsn = format!("mysql:host={};port={}", info.host, info.port);
+            if !database.is_empty() {
+                dsn.push_str(&format!(";dbname={}", database));
+            }
+            format!(
+                "$pdo = new PDO('{};charset=utf8mb4', '{}', '{}');",
+                php_escape(&dsn),
+                php_escape(&info.user),
+                php_escape(&info.password)
+            )
+        }
+        DsnFormat::LaravelEnv => [
+            ("DB_CONNECTION", "mysql"),
+            ("DB_HOST", info.host.as_str()),
+            ("DB_PORT", &info.port.to_string()),
+            ("DB_DATABASE", database),
+            ("DB_USERNAME", &info.user),
+            ("DB_PASSWORD", &info.password),
+        ]
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, env_value(value)))
+        .collect(),
+        DsnFormat::Jdbc => format!(
+            "jdbc:mysql://{}:{}/{}?user={}&password={}",
+            info.host,
+            info.port,
+            url_encode(database),
+            url_encode(&info.user),
+            url_encode(&info.password)
+        ),
+    }
+}
+
+/// Contents of a single-quoted PHP string
+fn php_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Quote values dotenv would otherwise split or treat as a comment
+fn env_value(value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii_alphanumeric() || "._-/:@".contains(c)) {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "\\$"))
+    }
+}
+
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_string() {
+        let info = ConnectionInfo {
+            host: "127.0.0.1".to_string(),
+            port: 3307,
+            user: "root".to_string(),
+            password: "it's #1".to_string(),
+        };
+
+        assert_eq!(
+            connection_string(DsnFormat::Mysqli, &info, Some("shop")),
+            "$mysqli = new mysqli('127.0.0.1', 'root', 'it\\'s #1', 'shop', 3307);"
+        );
+        assert_eq!(
+            connection_string(DsnFormat::Pdo, &info, None),
+            "$pdo = new PDO('mysql:host=127.0.0.1;port=3307;charset=utf8mb4', 'root', 'it\\'s #1');"
+        );
+        let env = connection_string(DsnFormat::LaravelEnv, &info, Some("shop"));
+        assert!(env.contains("DB_PORT=3307\nDB_DATABASE=shop\n"));
+        assert!(env.ends_with("DB_PASSWORD=\"it's #1\"\n"));
+        assert_eq!(
+            connection_string(DsnFormat::Jdbc, &info, Some("shop")),
+            "jdbc:mysql://127.0.0.1:3307/shop?user=root&password=it%27s%20%231"
+        );
+    }
+}
@@ -1,2 +1,5 @@
+pub mod backup;
+pub mod dsn;
 pub mod mysql;
 pub mod postgres;
+pub mod usage;
@@ -1,10 +1,22 @@
 use crate::runtime::locator::RuntimePaths;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+use crate::operations::{Operation, OperationKind};
 use crate::process::manager::configure_no_window;
 
+/// Whether the located server binary is MariaDB rather than MySQL
+pub fn is_mariadb(paths: &RuntimePaths) -> bool {
+    paths.mysql.file_stem().is_some_and(|stem| stem == "mariadbd")
+        || paths.mysql.ancestors().skip(1).take(2).any(|dir| {
+            dir.file_name().is_some_and(|name| name.to_string_lossy().starts_with("mariadb"))
+        })
+}
+
 pub fn initialize_mysql(paths: &RuntimePaths) -> Result<(), String> {
+    let is_mariadb = is_mariadb(paths);
+    let server_name = if is_mariadb { "MariaDB" } else { "MySQL" };
     let mysql_dir = paths.mysql_data_dir.join("mysql");
     if mysql_dir.exists() {
         let entries: Vec<_> = mysql_dir.read_dir()
@@ -19,50 +31,62 @@ pub fn initialize_mysql(paths: &RuntimePaths) -> Result<(), String> {
         });
 
         if has_sdi_files {
-            #[cfg(target_os = "linux")]
-            tracing::info!("MariaDB data directory already initialized");
-            #[cfg(not(target_os = "linux"))]
-            tracing::info!("MySQL data directory already initialized");
+            tracing::info!("{} data directory already initialized", server_name);
             return Ok(());
         }
     }
 
-    #[cfg(target_os = "linux")]
-    fs::create_dir_all(&paths.mysql_data_dir)
-        .map_err(|e| format!("Failed to create MariaDB data directory: {}", e))?;
-    #[cfg(not(target_os = "linux"))]
+    let op = Operation::start(OperationKind::DatabaseInit, false, format!("Initializing {} data directory", server_name));
+    let result = initialize_data_dir(paths, is_mariadb, &op);
+    op.finish(&result);
+    result
+}
+
+/// mariadb-install-db for the server in `bin_dir`
+///
+/// The downloaded archives keep it in `scripts/`, packaged servers install
+/// it next to the other binaries, on PATH.
+fn find_install_db_script(bin_dir: &Path) -> Result<PathBuf, String> {
+    let mut tried = Vec::new();
+    if let Some(base_dir) = bin_dir.parent() {
+        for name in ["mariadb-install-db", "mysql_install_db"] {
+            tried.push(base_dir.join("scripts").join(name));
+        }
+    }
+    for name in ["mariadb-install-db", "mysql_install_db"] {
+        tried.push(bin_dir.join(name));
+    }
+    if let Some(script) = tried.iter().find(|path| path.is_file()) {
+        return Ok(script.clone());
+    }
+    if let Some(script) = crate::runtime::system::candidates(&["mariadb-install-db", "mysql_install_db"]).into_iter().next() {
+        return Ok(script);
+    }
+
+    let tried: Vec<String> = tried.iter().map(|path| format!("  - {}", path.display())).collect();
+    Err(format!(
+        "MariaDB installation script not found. Tried:\n{}\nand mariadb-install-db on PATH.\n\
+        Please ensure the MariaDB runtime was downloaded correctly.",
+        tried.join("\n")
+    ))
+}
+
+/// Create the system tables of a new data directory
+fn initialize_data_dir(paths: &RuntimePaths, is_mariadb: bool, op: &Operation) -> Result<(), String> {
+    let server_name = if is_mariadb { "MariaDB" } else { "MySQL" };
+    let mysql_dir = paths.mysql_data_dir.join("mysql");
     fs::create_dir_all(&paths.mysql_data_dir)
-        .map_err(|e| format!("Failed to create MySQL data directory: {}", e))?;
+        .map_err(|e| format!("Failed to create {} data directory: {}", server_name, e))?;
 
     let data_dir_str = paths.mysql_data_dir.to_string_lossy().replace('\\', "/");
 
-    #[cfg(target_os = "linux")]
-    {
-        tracing::info!("MariaDB 12.x: Initializing data directory using mariadb-install-db");
+    if is_mariadb {
+        tracing::info!("MariaDB: Initializing data directory using mariadb-install-db");
 
         let mariadbd_dir = paths.mysql.parent()
             .ok_or("Failed to get MariaDB binary directory")?;
 
-        let install_db_script = mariadbd_dir.parent()
-            .ok_or("Failed to get MariaDB base directory")?
-            .join("scripts")
-            .join("mariadb-install-db");
-
-        if !install_db_script.exists() {
-            let install_db_script_fallback = mariadbd_dir.parent()
-                .ok_or("Failed to get MariaDB base directory")?
-                .join("scripts")
-                .join("mysql_install_db");
-
-            if !install_db_script_fallback.exists() {
-                return Err(format!(
-                    "MariaDB installation script not found. Tried:\n  - {}\n  - {}\n\
-                    Please ensure the MariaDB runtime was downloaded correctly.",
-                    install_db_script.display(),
-                    install_db_script_fallback.display()
-                ));
-            }
-        }
+        let install_db_script = find_install_db_script(mariadbd_dir)?;
 
         let init_log_path = paths.logs_dir.join("mysql_init.log");
         let init_log_file = fs::File::create(&init_log_path)
@@ -70,11 +94,12 @@ pub fn initialize_mysql(paths: &RuntimePaths) -> Result<(), String> {
 
         let mut cmd = configure_no_window(Command::new(&install_db_script));
         cmd.arg(format!("--datadir={}", data_dir_str))
-            .arg(format!("--basedir={}", mariadbd_dir.parent().unwrap().display()))
+            .arg(format!("--basedir={}", mariadbd_dir.parent().unwrap_or(mariadbd_dir).display()))
             .arg("--user=")
             .stdout(Stdio::from(init_log_file.try_clone().unwrap()))
             .stderr(Stdio::from(init_log_file));
 
+        op.progress("installing_system_tables", None, "Running mariadb-install-db");
         let mut child = cmd.spawn()
             .map_err(|e| format!("Failed to start MariaDB initialization: {}", e))?;
 
@@ -124,8 +149,7 @@ pub fn initialize_mysql(paths: &RuntimePaths) -> Result<(), String> {
         }
     }
 
-    #[cfg(not(target_os = "linux"))]
-    {
+    if !is_mariadb {
         tracing::info!("MySQL 8.x: Initializing data directory at: {}", data_dir_str);
 
         let mysqld = &paths.mysql;
@@ -134,6 +158,7 @@ pub fn initialize_mysql(paths: &RuntimePaths) -> Result<(), String> {
         let init_log_file = fs::File::create(&init_log_path)
             .map_err(|e| format!("Failed to create init log file: {}", e))?;
 
+        op.progress("installing_system_tables", None, "Running mysqld --initialize-insecure");
         let mut child = configure_no_window(Command::new(mysqld))
             .arg("--initialize-insecure")
             .arg("--datadir")
@@ -193,36 +218,255 @@ pub fn initialize_mysql(paths: &RuntimePaths) -> Result<(), String> {
     Ok(())
 }
 
-pub fn create_database(name: &str) -> Result<(), String> {
-    let _ = name;
-    // TODO: Implement database creation
+/// Create a database on the running server, if it does not exist yet
+pub fn create_database(paths: &RuntimePaths, port: u16, password: &str, name: &str) -> Result<(), String> {
+    validate_database_name(name)?;
+
+    let client = client_binary(paths, &["mysql", "mariadb"])?;
+    let mut cmd = client_command(&client, port, password);
+    cmd.arg("-e")
+        .arg(format!("CREATE DATABASE IF NOT EXISTS `{}` CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;", name))
+        .stdout(Stdio::null());
+
+    let output = cmd.output()
+        .map_err(|e| format!("Failed to run {}: {}", client.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to create database '{}': {}", name,
+            String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    tracing::info!("Created database '{}'", name);
     Ok(())
 }
 
-pub fn drop_database(name: &str) -> Result<(), String> {
-    let _ = name;
-    // TODO: Implement database deletion
+/// Drop a database on the running server
+pub fn drop_database(paths: &RuntimePaths, port: u16, password: &str, name: &str) -> Result<(), String> {
+    validate_database_name(name)?;
+    if is_system_database(name) {
+        return Err(format!("Refusing to drop system database '{}'", name));
+    }
+
+    let client = client_binary(paths, &["mysql", "mariadb"])?;
+    let mut cmd = client_command(&client, port, password);
+    cmd.arg("-e").arg(format!("DROP DATABASE `{}`;", name))
+        .stdout(Stdio::null());
+
+    let output = cmd.output()
+        .map_err(|e| format!("Failed to run {}: {}", client.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to drop database '{}': {}", name,
+            String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    tracing::info!("Dropped database '{}'", name);
     Ok(())
 }
 
-pub fn list_databases() -> Result<Vec<String>, String> {
-    // TODO: Implement database listing
-    Ok(vec![])
+/// Dump a single database to `dest` as SQL
+pub fn dump_database(paths: &RuntimePaths, port: u16, password: &str, name: &str, dest: &Path) -> Result<(), String> {
+    validate_database_name(name)?;
+
+    let dumper = client_binary(paths, &["mysqldump", "mariadb-dump"])?;
+    let out_file = fs::File::create(dest)
+        .map_err(|e| format!("Failed to create dump file: {}", e))?;
+
+    let mut cmd = client_command(&dumper, port, password);
+    cmd.arg("--single-transaction")
+        .arg("--routines")
+        .arg("--triggers")
+        .arg("--databases").arg(name)
+        .stdout(Stdio::from(out_file));
+
+    let output = cmd.output()
+        .map_err(|e| format!("Failed to run {}: {}", dumper.display(), e))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(dest);
+        return Err(format!("Failed to dump database '{}': {}", name,
+            String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(())
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ConnectionInfo {
-    pub host: String,
-    pub port: u16,
-    pub user: String,
-    pub password: String,
+/// Create `dest` as a copy of database `src`, piping mysqldump into the client
+///
+/// Fails when `dest` already exists rather than mixing tables into it, and
+/// drops `dest` again when the copy fails.
+pub fn copy_database(paths: &RuntimePaths, port: u16, password: &str, src: &str, dest: &str) -> Result<(), String> {
+    validate_database_name(src)?;
+    if list_databases(paths, port, password)?.iter().any(|name| name == dest) {
+        return Err(format!("Database '{}' already exists", dest));
+    }
+    create_database(paths, port, password, dest)?;
+    let copied = pipe_database(paths, port, password, src, dest);
+    if copied.is_err() {
+        let _ = drop_database(paths, port, password, dest);
+    }
+    copied
 }
 
-pub fn get_connection_info() -> ConnectionInfo {
-    ConnectionInfo {
-        host: "127.0.0.1".to_string(),
-        port: 3307,
-        user: "root".to_string(),
-        password: String::new(),
+/// Dump `src` straight into the existing database `dest`
+fn pipe_database(paths: &RuntimePaths, port: u16, password: &str, src: &str, dest: &str) -> Result<(), String> {
+    let dumper = client_binary(paths, &["mysqldump", "mariadb-dump"])?;
+    let client = client_binary(paths, &["mysql", "mariadb"])?;
+    // Without --databases the dump has no CREATE DATABASE/USE, so it restores into `dest`
+    let mut dump = client_command(&dumper, port, password)
+        .arg("--single-transaction")
+        .arg("--routines")
+        .arg("--triggers")
+        .arg(src)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", dumper.display(), e))?;
+    let dump_out = dump.stdout.take().ok_or("Failed to read the dump output")?;
+
+    let restore = client_command(&client, port, password)
+        .arg(dest)
+        .stdin(Stdio::from(dump_out))
+        .stdout(Stdio::null())
+        .output();
+    let dumped = dump.wait_with_output()
+        .map_err(|e| format!("Failed to wait for {}: {}", dumper.display(), e))?;
+    let restore = restore.map_err(|e| format!("Failed to run {}: {}", client.display(), e))?;
+
+    if !dumped.status.success() {
+        return Err(format!("Failed to dump database '{}': {}", src,
+            String::from_utf8_lossy(&dumped.stderr).trim()));
     }
+    if !restore.status.success() {
+        return Err(format!("Failed to restore into database '{}': {}", dest,
+            String::from_utf8_lossy(&restore.stderr).trim()));
+    }
+
+    tracing::info!("Copied database '{}' to '{}'", src, dest);
+    Ok(())
+}
+
+/// Upgrade the system tables of the running server after its binaries were
+/// replaced with a newer version
+pub fn run_upgrade(paths: &RuntimePaths, port: u16, password: &str) -> Result<String, String> {
+    let upgrader = client_binary(paths, &["mariadb-upgrade", "mysql_upgrade"])?;
+    let output = client_command(&upgrader, port, password)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", upgrader.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", upgrader.display(),
+            String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Read a server variable from the running server (`SHOW VARIABLES`)
+pub fn show_variable(paths: &RuntimePaths, port: u16, password: &str, name: &str) -> Result<String, String> {
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid variable name: '{}'", name));
+    }
+
+    // Rows are [name, value]
+    query_rows(paths, port, password, &format!("SHOW VARIABLES LIKE '{}';", name))?
+        .into_iter()
+        .find_map(|row| row.get(1).map(|value| value.trim().to_string()))
+        .ok_or_else(|| format!("Variable {} not found", name))
+}
+
+/// Run a query on the running server and return its rows as tab-split columns
+pub fn query_rows(paths: &RuntimePaths, port: u16, password: &str, sql: &str) -> Result<Vec<Vec<String>>, String> {
+    let client = client_binary(paths, &["mysql", "mariadb"])?;
+    let mut cmd = client_command(&client, port, password);
+    cmd.arg("-N").arg("-B")
+        .arg("-e").arg(sql);
+
+    let output = cmd.output()
+        .map_err(|e| format!("Failed to run {}: {}", client.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("Query failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.split('\t').map(String::from).collect())
+        .collect())
+}
+
+/// Check the running server answers with `mysqladmin ping`
+pub fn ping(paths: &RuntimePaths, port: u16, password: &str) -> Result<(), String> {
+    let admin = client_binary(paths, &["mysqladmin", "mariadb-admin"])?;
+    let output = client_command(&admin, port, password)
+        .arg("ping")
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", admin.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("Server did not answer: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Ask the running server to flush its tables and exit with `mysqladmin shutdown`
+pub fn shutdown(paths: &RuntimePaths, port: u16, password: &str) -> Result<(), String> {
+    let admin = client_binary(paths, &["mysqladmin", "mariadb-admin"])?;
+    let output = client_command(&admin, port, password)
+        .arg("shutdown")
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", admin.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("Shutdown failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Database names are interpolated into SQL, only allow unquoted identifier characters
+pub(crate) fn validate_database_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > 64
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '-')
+    {
+        return Err(format!("Invalid database name: '{}'", name));
+    }
+    Ok(())
+}
+
+pub(crate) fn is_system_database(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "mysql" | "information_schema" | "performance_schema" | "sys"
+    )
+}
+
+/// Find a client tool in the same bin/ directory as mysqld
+fn client_binary(paths: &RuntimePaths, names: &[&str]) -> Result<PathBuf, String> {
+    let bin_dir = paths.mysql.parent().ok_or("Failed to get MySQL binary directory")?;
+    names
+        .iter()
+        .map(|name| {
+            #[cfg(target_os = "windows")]
+            let path = bin_dir.join(format!("{}.exe", name));
+            #[cfg(not(target_os = "windows"))]
+            let path = bin_dir.join(name);
+            path
+        })
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("{} not found in {}", names.join(" or "), bin_dir.display()))
+}
+
+fn client_command(binary: &Path, port: u16, password: &str) -> Command {
+    let mut cmd = configure_no_window(Command::new(binary));
+    cmd.arg("-h").arg("127.0.0.1")
+        .arg("-P").arg(port.to_string())
+        .arg("-u").arg("root")
+        .stdin(Stdio::null());
+    if !password.is_empty() {
+        cmd.arg(format!("-p{}", password));
+    }
+    cmd
+}
+
+/// User databases on the running server, without the system schemas
+pub fn list_databases(paths: &RuntimePaths, port: u16, password: &str) -> Result<Vec<String>, String> {
+    Ok(query_rows(paths, port, password, "SHOW DATABASES;")?
+        .into_iter()
+        .filter_map(|row| row.into_iter().next())
+        .filter(|name| !is_system_database(name))
+        .collect())
 }
@@ -112,3 +112,21 @@ pub fn initialize_postgresql(pgsql_dir: &Path, data_dir: &Path, logs_dir: &Path)
     tracing::info!("PostgreSQL initialization completed successfully");
     Ok(())
 }
+
+/// Check the running server accepts connections with `pg_isready`
+pub fn ping(pgsql_dir: &Path, port: u16) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let pg_isready = pgsql_dir.join("bin").join("pg_isready.exe");
+    #[cfg(not(target_os = "windows"))]
+    let pg_isready = pgsql_dir.join("bin").join("pg_isready");
+
+    let output = configure_no_window(Command::new(&pg_isready))
+        .args(["-h", "127.0.0.1", "-p", &port.to_string(), "-t", "3"])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", pg_isready.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("Server did not answer: {}", String::from_utf8_lossy(&output.stdout).trim()));
+    }
+    Ok(())
+}
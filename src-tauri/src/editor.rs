@@ -0,0 +1,157 @@
+//! Opening projects in a code editor
+//!
+//! Editors are found through their command-line launcher on PATH or in the
+//! locations their installers use, which differ per platform: VS Code ships
+//! `code.cmd` on Windows and keeps `code` inside the app bundle on macOS, and
+//! PhpStorm installs a versioned folder per release on Windows.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::process::manager::configure_no_window;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Editor {
+    VsCode,
+    PhpStorm,
+    Sublime,
+}
+
+pub const EDITORS: [Editor; 3] = [Editor::VsCode, Editor::PhpStorm, Editor::Sublime];
+
+impl Editor {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Editor::VsCode => "Visual Studio Code",
+            Editor::PhpStorm => "PhpStorm",
+            Editor::Sublime => "Sublime Text",
+        }
+    }
+
+    /// Launcher names looked up on PATH
+    fn commands(&self) -> &'static [&'static str] {
+        match self {
+            Editor::VsCode => &["code"],
+            Editor::PhpStorm => &["phpstorm", "phpstorm.sh", "phpstorm64"],
+            Editor::Sublime => &["subl", "sublime_text"],
+        }
+    }
+
+    /// Launchers outside PATH, where the installers put them
+    fn install_locations(&self) -> Vec<PathBuf> {
+        #[cfg(target_os = "windows")]
+        {
+            let mut roots = Vec::new();
+            if let Some(local) = dirs::data_local_dir() {
+                roots.push(local.join("Programs"));
+            }
+            for var in ["ProgramFiles", "ProgramFiles(x86)"] {
+                if let Some(dir) = std::env::var_os(var) {
+                    roots.push(PathBuf::from(dir));
+                }
+            }
+            let mut found = Vec::new();
+            for root in roots {
+                match self {
+                    Editor::VsCode => found.push(root.join("Microsoft VS Code").join("bin").join("code.cmd")),
+                    Editor::PhpStorm => {
+                        found.push(root.join("PhpStorm").join("bin").join("phpstorm64.exe"));
+                        // e.g. JetBrains\PhpStorm 2024.3\bin, newest release last
+                        let mut releases: Vec<PathBuf> = std::fs::read_dir(root.join("JetBrains"))
+                            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+                            .unwrap_or_default();
+                        releases.retain(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("PhpStorm")));
+                        releases.sort();
+                        found.extend(releases.into_iter().rev().map(|p| p.join("bin").join("phpstorm64.exe")));
+                    }
+                    Editor::Sublime => found.push(root.join("Sublime Text").join("subl.exe")),
+                }
+            }
+            found
+        }
+        #[cfg(target_os = "macos")]
+        {
+            let apps = [PathBuf::from("/Applications")]
+                .into_iter()
+                .chain(dirs::home_dir().map(|home| home.join("Applications")));
+            apps.map(|apps| match self {
+                Editor::VsCode => apps.join("Visual Studio Code.app/Contents/Resources/app/bin/code"),
+                Editor::PhpStorm => apps.join("PhpStorm.app/Contents/MacOS/phpstorm"),
+                Editor::Sublime => apps.join("Sublime Text.app/Contents/SharedSupport/bin/subl"),
+            })
+            .collect()
+        }
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            let mut found = vec![PathBuf::from("/snap/bin").join(self.commands()[0])];
+            if let Some(home) = dirs::home_dir() {
+                // JetBrains Toolbox puts its shell scripts here
+                found.push(home.join(".local/share/JetBrains/Toolbox/scripts").join(self.commands()[0]));
+            }
+            found
+        }
+    }
+
+    /// The launcher of this editor, if it is installed
+    pub fn launcher(&self) -> Option<PathBuf> {
+        let file_names: Vec<String> = self
+            .commands()
+            .iter()
+            .flat_map(|name| {
+                if cfg!(target_os = "windows") {
+                    vec![format!("{}.cmd", name), format!("{}.exe", name)]
+                } else {
+                    vec![name.to_string()]
+                }
+            })
+            .collect();
+        let on_path: Vec<PathBuf> = std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|dir| file_names.iter().map(move |name| dir.join(name)))
+            .collect();
+        on_path.into_iter().chain(self.install_locations()).find(|path| path.is_file())
+    }
+}
+
+/// Editors installed on this machine
+pub fn detect() -> Vec<Editor> {
+    EDITORS.into_iter().filter(|editor| editor.launcher().is_some()).collect()
+}
+
+/// Open `dir` in `editor`
+pub fn open(editor: Editor, dir: &Path) -> Result<(), String> {
+    let launcher = editor
+        .launcher()
+        .ok_or_else(|| format!("{} is not installed or its launcher is not on PATH", editor.display_name()))?;
+
+    let mut child = configure_no_window(Command::new(&launcher))
+        .arg(dir)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", editor.display_name(), e))?;
+
+    // The launchers hand the folder to the editor and return, or keep running
+    // as the editor itself; reap it whenever it exits instead of waiting
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_names() {
+        assert_eq!(serde_json::to_string(&EDITORS).unwrap(), r#"["vscode","phpstorm","sublime"]"#);
+        assert_eq!(serde_json::from_str::<Editor>(r#""phpstorm""#).unwrap(), Editor::PhpStorm);
+    }
+}
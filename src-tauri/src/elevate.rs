@@ -0,0 +1,89 @@
+//! Running system changes as administrator
+//!
+//! Resolver and trust store changes need administrator rights. The script is
+//! run through the platform's own prompt: pkexec on Linux, an AppleScript
+//! `with administrator privileges` dialog on macOS and UAC on Windows, where
+//! the script is PowerShell instead of `sh`.
+
+use std::process::Command;
+
+/// Run `script` as administrator, prompting the user
+///
+/// `what` describes the change for the error message, e.g. "configuring the
+/// resolver".
+pub fn run(script: &str, what: &str) -> Result<(), String> {
+    let output = command(script)
+        .output()
+        .map_err(|e| format!("Failed to request administrator rights: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("{} was cancelled or denied", capitalize(what))
+        } else {
+            format!("Failed {}: {}", what, stderr)
+        });
+    }
+    Ok(())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Quote `value` for `sh`
+#[cfg(not(target_os = "windows"))]
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Quote `value` as a PowerShell single-quoted string
+#[cfg(target_os = "windows")]
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(target_os = "linux")]
+fn command(script: &str) -> Command {
+    let mut cmd = Command::new("pkexec");
+    cmd.arg("sh").arg("-c").arg(script);
+    cmd
+}
+
+#[cfg(target_os = "macos")]
+fn command(script: &str) -> Command {
+    let mut cmd = Command::new("osascript");
+    cmd.arg("-e").arg(format!(
+        "do shell script \"{}\" with administrator privileges",
+        script.replace('\\', "\\\\").replace('"', "\\\"")
+    ));
+    cmd
+}
+
+#[cfg(target_os = "windows")]
+fn command(script: &str) -> Command {
+    let outer = format!(
+        "$p = Start-Process -FilePath powershell -ArgumentList '-NoProfile','-Command',{} -Verb RunAs -Wait -PassThru -WindowStyle Hidden; exit $p.ExitCode",
+        shell_quote(script)
+    );
+    let mut cmd = crate::process::manager::configure_no_window(Command::new("powershell"));
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", &outer]);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        #[cfg(not(target_os = "windows"))]
+        assert_eq!(shell_quote("it's here"), "'it'\\''s here'");
+        #[cfg(target_os = "windows")]
+        assert_eq!(shell_quote("it's here"), "'it''s here'");
+        assert_eq!(capitalize("configuring the resolver"), "Configuring the resolver");
+    }
+}
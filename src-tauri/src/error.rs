@@ -1,51 +1,148 @@
+//! Errors returned by Tauri commands
+//!
+//! Every command fails with a `CamppError`. It is serialized as an object
+//! with a `kind` tag, a human-readable `message` and the fields of its
+//! variant, so the frontend can show the message as before and offer
+//! specific remediation for the kinds it knows. Internal helpers keep
+//! returning `String` errors, which become `Other` when they reach a command.
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
 use std::fmt;
 
-#[derive(Debug)]
-pub enum AppError {
-    Io(std::io::Error),
-    Process(String),
-    Config(String),
-    Download(String),
-    Database(String),
-    Settings(String),
+use crate::process::ServiceType;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CamppError {
+    /// A port CAMPP needs is taken, by `owner` when it could be identified
+    PortInUse { port: u16, owner: Option<String> },
+    /// A runtime binary is not installed where it is expected
+    BinaryMissing { path: String },
+    /// A service exited or failed while starting
+    InitFailed { service: ServiceType, message: String, log_excerpt: Vec<String> },
+    ServiceNotRunning { service: ServiceType },
+    InvalidInput { message: String },
+    /// Lock poisoning, a panicked task and other bugs
+    Internal { message: String },
+    Other { message: String },
+}
+
+/// Lines of the service log attached to `InitFailed`
+const LOG_EXCERPT_LINES: usize = 20;
+
+impl CamppError {
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        CamppError::InvalidInput { message: message.into() }
+    }
+
+    /// `InitFailed` with the end of the service log in `logs_dir`
+    pub fn init_failed(service: ServiceType, message: impl Into<String>, logs_dir: &std::path::Path) -> Self {
+        let log = crate::process::logs::log_path(logs_dir, service);
+        CamppError::InitFailed {
+            service,
+            message: message.into(),
+            log_excerpt: crate::process::logs::last_lines(&log, LOG_EXCERPT_LINES).unwrap_or_default(),
+        }
+    }
+
+    /// Stable identifier of the variant, e.g. "port_in_use"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CamppError::PortInUse { .. } => "port_in_use",
+            CamppError::BinaryMissing { .. } => "binary_missing",
+            CamppError::InitFailed { .. } => "init_failed",
+            CamppError::ServiceNotRunning { .. } => "service_not_running",
+            CamppError::InvalidInput { .. } => "invalid_input",
+            CamppError::Internal { .. } => "internal",
+            CamppError::Other { .. } => "other",
+        }
+    }
 }
 
-impl fmt::Display for AppError {
+impl fmt::Display for CamppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            AppError::Io(e) => write!(f, "IO error: {}", e),
-            AppError::Process(msg) => write!(f, "Process error: {}", msg),
-            AppError::Config(msg) => write!(f, "Config error: {}", msg),
-            AppError::Download(msg) => write!(f, "Download error: {}", msg),
-            AppError::Database(msg) => write!(f, "Database error: {}", msg),
-            AppError::Settings(msg) => write!(f, "Settings error: {}", msg),
+            CamppError::PortInUse { port, owner: Some(owner) } => write!(f, "Port {} is already in use by {}", port, owner),
+            CamppError::PortInUse { port, owner: None } => write!(f, "Port {} is already in use by another application", port),
+            CamppError::BinaryMissing { path } => write!(f, "Binary not found: {}", path),
+            CamppError::InitFailed { service, message, .. } => write!(f, "{} failed to start: {}", service.display_name(), message),
+            CamppError::ServiceNotRunning { service } => write!(f, "{} is not running", service.display_name()),
+            CamppError::InvalidInput { message } | CamppError::Internal { message } | CamppError::Other { message } => {
+                write!(f, "{}", message)
+            }
         }
     }
 }
 
-impl std::error::Error for AppError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl std::error::Error for CamppError {}
+
+impl Serialize for CamppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
         match self {
-            AppError::Io(e) => Some(e),
-            _ => None,
+            CamppError::PortInUse { port, owner } => {
+                map.serialize_entry("port", port)?;
+                map.serialize_entry("owner", owner)?;
+            }
+            CamppError::BinaryMissing { path } => map.serialize_entry("path", path)?,
+            CamppError::InitFailed { service, log_excerpt, .. } => {
+                map.serialize_entry("service", service)?;
+                map.serialize_entry("log_excerpt", log_excerpt)?;
+            }
+            CamppError::ServiceNotRunning { service } => map.serialize_entry("service", service)?,
+            CamppError::InvalidInput { .. } | CamppError::Internal { .. } | CamppError::Other { .. } => {}
         }
+        map.end()
+    }
+}
+
+impl From<String> for CamppError {
+    fn from(message: String) -> Self {
+        CamppError::Other { message }
+    }
+}
+
+impl From<&str> for CamppError {
+    fn from(message: &str) -> Self {
+        CamppError::Other { message: message.to_string() }
     }
 }
 
-impl From<std::io::Error> for AppError {
+impl From<std::io::Error> for CamppError {
     fn from(e: std::io::Error) -> Self {
-        AppError::Io(e)
+        CamppError::Other { message: format!("IO error: {}", e) }
     }
 }
 
-impl From<AppError> for tauri::ipc::InvokeError {
-    fn from(e: AppError) -> Self {
-        tauri::ipc::InvokeError::from(e.to_string())
+impl From<tokio::task::JoinError> for CamppError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        CamppError::Internal { message: format!("Task error: {}", e) }
     }
 }
 
-impl From<AppError> for serde_json::Value {
-    fn from(e: AppError) -> Self {
-        serde_json::json!({ "error": e.to_string() })
+impl<T> From<std::sync::PoisonError<T>> for CamppError {
+    fn from(e: std::sync::PoisonError<T>) -> Self {
+        CamppError::Internal { message: format!("Failed to acquire process manager lock: {}", e) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_with_kind_and_message() {
+        let json = serde_json::to_value(CamppError::PortInUse { port: 8080, owner: Some("nginx".to_string()) }).unwrap();
+        assert_eq!(json["kind"], "port_in_use");
+        assert_eq!(json["message"], "Port 8080 is already in use by nginx");
+        assert_eq!(json["port"], 8080);
+
+        let json = serde_json::to_value(CamppError::ServiceNotRunning { service: ServiceType::PhpFpm }).unwrap();
+        assert_eq!(json["service"], "php-fpm");
+
+        let json = serde_json::to_value(CamppError::from("Settings file is corrupt")).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "other", "message": "Settings file is corrupt" }));
     }
 }
@@ -0,0 +1,235 @@
+//! Hosts file entries for project domains
+//!
+//! CAMPP only touches the lines between its own marker comments, so entries
+//! added by the user or other tools are left alone. The hosts file is owned
+//! by the administrator: when it cannot be written directly, the new content
+//! is staged in a temp file and copied over it through [`crate::elevate`].
+
+use serde::Serialize;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+const BEGIN_MARKER: &str = "# BEGIN CAMPP";
+const END_MARKER: &str = "# END CAMPP";
+
+/// Address project domains resolve to
+pub const LOOPBACK: &str = "127.0.0.1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostsEntry {
+    pub ip: String,
+    pub host: String,
+}
+
+/// Location of the system hosts file
+pub fn hosts_file_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        PathBuf::from(root).join("System32").join("drivers").join("etc").join("hosts")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        PathBuf::from("/etc/hosts")
+    }
+}
+
+/// Point `host` at the loopback address
+pub fn add_host(host: &str) -> Result<(), String> {
+    update_hosts(&[host.to_string()], &[])
+}
+
+/// Remove the entry CAMPP added for `host`
+pub fn remove_host(host: &str) -> Result<(), String> {
+    update_hosts(&[], &[host.to_string()])
+}
+
+/// Entries CAMPP manages in the hosts file
+pub fn entries() -> Result<Vec<HostsEntry>, String> {
+    Ok(managed_entries(&read_hosts(&hosts_file_path())?))
+}
+
+/// Add and remove several hosts with a single write, so there is at most
+/// one elevation prompt
+///
+/// Nothing is written when the entries are already as requested.
+pub fn update_hosts(add: &[String], remove: &[String]) -> Result<(), String> {
+    let path = hosts_file_path();
+    let content = read_hosts(&path)?;
+    match changed_entries(&managed_entries(&content), add, remove) {
+        Some(entries) => write_hosts(&path, &with_entries(&content, &entries)),
+        None => Ok(()),
+    }
+}
+
+/// `current` with the hosts in `remove` dropped and those in `add` pointed
+/// at loopback, None when that changes nothing
+fn changed_entries(current: &[HostsEntry], add: &[String], remove: &[String]) -> Option<Vec<HostsEntry>> {
+    let mut entries: Vec<HostsEntry> = current.iter().filter(|e| !remove.contains(&e.host)).cloned().collect();
+    for host in add {
+        if !entries.iter().any(|e| e.host == *host) {
+            entries.push(HostsEntry { ip: LOOPBACK.to_string(), host: host.clone() });
+        }
+    }
+    (entries != current).then_some(entries)
+}
+
+/// Make the CAMPP block hold exactly `hosts`, dropping stale entries
+pub fn sync_hosts(hosts: &[String]) -> Result<(), String> {
+    let current: Vec<String> = entries()?.into_iter().map(|e| e.host).collect();
+    let stale: Vec<String> = current.into_iter().filter(|host| !hosts.contains(host)).collect();
+    update_hosts(hosts, &stale)
+}
+
+fn read_hosts(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Entries inside the CAMPP block of hosts file `content`
+pub fn managed_entries(content: &str) -> Vec<HostsEntry> {
+    let mut inside = false;
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        match line.trim() {
+            BEGIN_MARKER => inside = true,
+            END_MARKER => inside = false,
+            line if inside && !line.starts_with('#') => {
+                let mut fields = line.split_whitespace();
+                if let (Some(ip), Some(host)) = (fields.next(), fields.next()) {
+                    entries.push(HostsEntry { ip: ip.to_string(), host: host.to_string() });
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// `content` with its CAMPP block replaced by `entries`
+///
+/// The block is dropped entirely when `entries` is empty.
+pub fn with_entries(content: &str, entries: &[HostsEntry]) -> String {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines = Vec::new();
+    let mut inside = false;
+    for line in content.lines() {
+        match line.trim() {
+            BEGIN_MARKER => inside = true,
+            END_MARKER => inside = false,
+            _ if inside => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+
+    if !entries.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(BEGIN_MARKER.to_string());
+        lines.extend(entries.iter().map(|e| format!("{} {}", e.ip, e.host)));
+        lines.push(END_MARKER.to_string());
+    }
+
+    let mut result = lines.join(newline);
+    result.push_str(newline);
+    result
+}
+
+fn write_hosts(path: &Path, content: &str) -> Result<(), String> {
+    match fs::write(path, content) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => write_elevated(path, content),
+        Err(e) => Err(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+/// Copy `content` over `path` as administrator, prompting the user
+fn write_elevated(path: &Path, content: &str) -> Result<(), String> {
+    let staged = stage(content)?;
+    let result = crate::elevate::run(&copy_script(&staged, path), "updating the hosts file");
+    let _ = fs::remove_file(&staged);
+    result
+}
+
+/// Write `content` to a new file in the temp directory
+///
+/// The file must not exist yet, a link planted under its name by another
+/// user would otherwise redirect the write.
+fn stage(content: &str) -> Result<PathBuf, String> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let staged = std::env::temp_dir().join(format!("campp-hosts-{}-{}", std::process::id(), nanos));
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&staged)
+        .map_err(|e| format!("Failed to stage hosts file: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to stage hosts file: {}", e))?;
+    Ok(staged)
+}
+
+/// Script copying `src` over `dest`
+fn copy_script(src: &Path, dest: &Path) -> String {
+    use crate::elevate::shell_quote;
+    let (src, dest) = (shell_quote(&src.to_string_lossy()), shell_quote(&dest.to_string_lossy()));
+    if cfg!(target_os = "windows") {
+        format!("Copy-Item -LiteralPath {} -Destination {} -Force", src, dest)
+    } else {
+        format!("cp {} {}", src, dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(host: &str) -> HostsEntry {
+        HostsEntry { ip: LOOPBACK.to_string(), host: host.to_string() }
+    }
+
+    #[test]
+    fn test_managed_block() {
+        let original = "127.0.0.1 localhost\n::1 localhost\n";
+        let content = with_entries(original, &[entry("shop.test"), entry("blog.test")]);
+        assert_eq!(
+            content,
+            "127.0.0.1 localhost\n::1 localhost\n\n# BEGIN CAMPP\n127.0.0.1 shop.test\n127.0.0.1 blog.test\n# END CAMPP\n"
+        );
+        assert_eq!(managed_entries(&content), vec![entry("shop.test"), entry("blog.test")]);
+
+        // Other lines are kept, the block moves to the end
+        let content = with_entries(&format!("{}10.0.0.1 nas\n", content), &[entry("blog.test")]);
+        assert_eq!(content, "127.0.0.1 localhost\n::1 localhost\n\n10.0.0.1 nas\n\n# BEGIN CAMPP\n127.0.0.1 blog.test\n# END CAMPP\n");
+
+        assert_eq!(with_entries(&content, &[]), "127.0.0.1 localhost\n::1 localhost\n\n10.0.0.1 nas\n");
+    }
+
+    #[test]
+    fn test_changed_entries() {
+        let current = vec![entry("shop.test"), entry("old.test")];
+        let hosts = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            changed_entries(&current, &hosts(&["api.shop.test", "shop.test"]), &hosts(&["old.test"])),
+            Some(vec![entry("shop.test"), entry("api.shop.test")])
+        );
+        assert_eq!(changed_entries(&current, &hosts(&["shop.test"]), &hosts(&["blog.test"])), None);
+    }
+
+    #[test]
+    fn test_windows_line_endings() {
+        let content = with_entries("127.0.0.1 localhost\r\n", &[entry("shop.test")]);
+        assert_eq!(content, "127.0.0.1 localhost\r\n\r\n# BEGIN CAMPP\r\n127.0.0.1 shop.test\r\n# END CAMPP\r\n");
+        assert_eq!(managed_entries(&content), vec![entry("shop.test")]);
+    }
+}
@@ -1,10 +1,17 @@
 // Modules
+mod ca;
 mod commands;
 mod config;
 mod database;
+mod editor;
+mod elevate;
 mod error;
+mod hosts;
+mod operations;
 mod process;
+mod projects;
 mod runtime;
+mod util;
 
 // Re-exports
 pub use process::{ServiceInfo, ServiceMap, ServiceState, ServiceType};
@@ -35,6 +42,7 @@ pub fn run() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             // Another instance was launched — show and focus the existing window
             if let Some(window) = app.get_webview_window("main") {
@@ -57,20 +65,77 @@ pub fn run() {
                 // Create debug menu items with IDs
                 let open_download_folder = MenuItem::with_id(app, "open-download-folder", "View Download Folder (ZIP files)", true, None::<&str>)?;
                 let open_runtime_folder = MenuItem::with_id(app, "open-runtime-folder", "Open Runtime Folder", true, None::<&str>)?;
+                let open_config_folder = MenuItem::with_id(app, "open-config-folder", "Open Config Folder", true, None::<&str>)?;
+                let open_logs_folder = MenuItem::with_id(app, "open-logs-folder", "Open Logs Folder", true, None::<&str>)?;
 
                 // Create submenu with items
-                let debug_menu = Submenu::with_items(app, "Debug", true, &[&open_download_folder, &open_runtime_folder])?;
+                let debug_menu = Submenu::with_items(app, "Debug", true, &[&open_download_folder, &open_runtime_folder, &open_config_folder, &open_logs_folder])?;
                 let menu = Menu::with_items(app, &[&debug_menu])?;
                 app.set_menu(menu)?;
             }
 
+            for o in config::env::active_overrides() {
+                tracing::info!("Setting {} overridden by {}", o.field, o.variable);
+            }
+            if let Some(data_dir) = config::data_dir::data_dir_override() {
+                let source = if config::data_dir::is_portable() { "portable mode" } else { config::data_dir::DATA_DIR_VAR };
+                tracing::info!("Data directory set by {}: {}", source, data_dir.display());
+            }
+
+            if let Ok(app_paths) = runtime::locator::get_app_data_paths() {
+                if let Err(e) = process::tunnel::migrate_authtoken(&app_paths.config_dir) {
+                    tracing::warn!("Failed to move the ngrok authtoken out of settings: {}", e);
+                }
+            }
+            if let Err(e) = projects::ProjectRegistry::open().and_then(|registry| projects::welcome::forget_stored_credentials(&registry)) {
+                tracing::warn!("Failed to remove the database password from the welcome project: {}", e);
+            }
+            if let Err(e) = projects::ProjectRegistry::open().and_then(|registry| process::npm::forget_dev_servers(&registry)) {
+                tracing::warn!("Failed to clear the dev server ports of projects: {}", e);
+            }
+            // Shares left over from a crash
+            end_lan_shares();
+
             // Setup system tray
             setup_system_tray(app)?;
 
+            // Push every service state transition to the frontend
+            let handle = app.handle().clone();
+            if let Ok(mut manager) = app.state::<AppState>().process_manager.lock() {
+                manager.set_emitter(Arc::new(move |change| {
+                    let _ = handle.emit(process::STATE_CHANGED_EVENT, &change);
+                }));
+            }
+            let handle = app.handle().clone();
+            operations::set_emitter(Arc::new(move |progress| {
+                let _ = handle.emit(operations::PROGRESS_EVENT, progress);
+            }));
+
+            // Periodically check running services for stale configuration
+            spawn_health_monitor(app.handle().clone());
+            spawn_drift_monitor(app.handle().clone());
+            spawn_maintenance_scheduler();
+            spawn_log_rotation();
+            spawn_config_watcher(app.handle().clone());
+
+            // Check hardened runtime binaries for modifications
+            if config::AppSettings::load().harden_runtime {
+                let handle = app.handle().clone();
+                std::thread::spawn(move || match runtime::hardening::verify_runtime() {
+                    Ok(report) if !report.is_clean() => {
+                        tracing::warn!("Runtime integrity check failed: {:?}", report);
+                        let _ = handle.emit("runtime-integrity-warning", &report);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Runtime integrity check error: {}", e),
+                });
+            }
+
             Ok(())
         })
         .on_window_event(|window, event| {
-            // Handle window close event - minimize to tray instead of closing
+            // Handle window close event - minimize to tray instead of closing.
+            // Services keep running until the app exits, see `stop_services_on_exit`.
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 window.hide().unwrap();
                 api.prevent_close();
@@ -81,19 +146,70 @@ pub fn run() {
             commands::start_service,
             commands::stop_service,
             commands::restart_service,
+            commands::start_all_services,
+            commands::pause_stack,
+            commands::resume_stack,
             commands::get_all_statuses,
+            commands::health_check,
+            commands::tail_service_log,
+            commands::get_service_logs,
+            commands::clear_service_logs,
+            // Database commands
+            commands::drop_database,
+            commands::copy_connection_string,
+            commands::get_database_usage,
+            commands::list_backups,
+            commands::delete_backup,
             // Settings commands
             commands::get_settings,
+            commands::get_env_overrides,
             commands::save_settings,
             commands::validate_settings,
+            commands::preview_configs,
+            commands::get_pending_changes,
+            commands::apply_pending_changes,
+            commands::export_settings,
+            commands::import_settings,
+            commands::reset_settings,
+            commands::restore_settings_backup,
             commands::check_ports,
+            commands::kill_port_owner,
+            commands::suggest_free_port,
+            commands::set_service_port,
+            commands::check_config_drift,
+            // Profile commands
+            commands::list_profiles,
+            commands::create_profile,
+            commands::delete_profile,
+            commands::switch_profile,
+            // Security commands
+            commands::set_phpmyadmin_auth,
+            commands::clear_phpmyadmin_auth,
+            commands::get_security_headers,
+            commands::set_security_headers,
+            commands::reset_security_headers,
+            commands::get_security_report,
+            commands::harden_runtime,
+            commands::relax_runtime,
+            commands::verify_runtime_integrity,
+            // Caddy admin API commands
+            commands::caddy_add_vhost,
+            commands::caddy_remove_vhost,
+            commands::caddy_list_vhosts,
+            commands::caddy_get_config,
+            commands::get_web_server_stats,
+            commands::caddy_reload_config,
             // Runtime download commands
             commands::check_runtime_installed,
             commands::check_system_dependencies,
             commands::download_runtime,
+            commands::check_runtime_updates,
             commands::download_runtime_with_packages,
+            commands::download_runtime_components,
             commands::download_runtime_with_skip,
             commands::get_available_packages_cmd,
+            commands::list_available_php_versions,
+            commands::list_available_database_versions,
             commands::get_package_selection,
             commands::update_package_selection,
             commands::update_db_passwords,
@@ -103,16 +219,262 @@ pub fn run() {
             commands::check_existing_components,
             commands::get_runtime_dir,
             commands::get_download_dir,
+            commands::cleanup_downloads,
+            commands::detect_system_binaries,
+            commands::set_binary_override,
             commands::get_install_dir,
             commands::open_folder,
             commands::open_manual,
+            commands::open_project_folder,
+            commands::list_projects,
+            commands::create_project,
+            commands::create_laravel_project,
+            commands::import_project,
+            commands::scan_projects,
+            commands::export_project,
+            commands::delete_project,
+            commands::duplicate_project,
+            commands::set_project_docroot,
+            commands::set_project_env,
+            commands::set_project_static,
+            commands::set_project_https,
+            commands::set_project_https_redirect,
+            commands::share_on_lan,
+            commands::unshare_from_lan,
+            commands::get_share_qr,
+            commands::start_tunnel,
+            commands::stop_tunnel,
+            commands::get_tunnel_status,
+            commands::set_ngrok_authtoken,
+            commands::set_service_enabled,
+            commands::get_mailpit_status,
+            commands::get_memcached_status,
+            commands::get_minio_status,
+            commands::get_ftp_status,
+            commands::reset_ftp_password,
+            commands::get_package_scripts,
+            commands::run_npm_script,
+            commands::stop_npm_script,
+            commands::list_npm_scripts,
+            commands::get_npm_script_logs,
+            commands::set_project_caddy_snippet,
+            commands::set_project_caddy_file_trusted,
+            commands::add_php_version,
+            commands::remove_php_version,
+            commands::set_project_php_version,
+            commands::list_editors,
+            commands::open_in_editor,
+            commands::register_project_domain,
+            commands::unregister_project_domain,
+            commands::add_project_alias,
+            commands::remove_project_alias,
+            commands::list_hosts_entries,
+            commands::sync_hosts_file,
+            commands::get_dns_status,
+            commands::set_dns_enabled,
+            commands::configure_dns_resolver,
+            commands::remove_dns_resolver,
+            commands::set_domain_tld,
+            commands::get_ca_status,
+            commands::create_local_ca,
+            commands::trust_local_ca,
+            commands::untrust_local_ca,
+            commands::get_certificates,
+            commands::open_config_folder,
+            commands::open_logs_folder,
+            commands::get_paths,
+            commands::open_database_folder,
+            commands::get_site_url,
+            commands::open_in_browser,
             commands::reset_installation,
+            commands::repair_installation,
+            commands::doctor,
+            commands::list_operations,
+            commands::cancel_operation,
             commands::cleanup_all_services,
             commands::uninstall_component,
+            commands::upgrade_component,
+            commands::update_phpmyadmin,
+            commands::get_runtime_disk_usage,
             commands::get_debug_info,
+            commands::export_diagnostics,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                stop_services_on_exit(app);
+            }
+        });
+}
+
+/// Stop every service before the app exits, whether it is quit from the tray,
+/// the OS (e.g. Cmd+Q) or anything calling `AppHandle::exit`, so no orphaned
+/// server keeps the ports after CAMPP is gone
+fn stop_services_on_exit(app: &AppHandle) {
+    if let Some(state) = app.try_state::<AppState>() {
+        match state.process_manager.lock() {
+            Ok(mut manager) => {
+                let _ = manager.stop_all();
+            }
+            Err(e) => tracing::error!("Failed to stop services on exit: {}", e),
+        }
+    }
+    end_lan_shares();
+}
+
+/// Stop sharing projects on the LAN and close their firewall ports
+fn end_lan_shares() {
+    match projects::ProjectRegistry::open().and_then(|registry| projects::share::unshare_all(&registry)) {
+        Ok(unshared) if !unshared.is_empty() => tracing::info!("Stopped sharing on the LAN: {}", unshared.join(", ")),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to stop sharing projects on the LAN: {}", e),
+    }
+}
+
+/// Check the service processes periodically, so a crash is emitted as a
+/// `service-state-changed` event without the frontend polling
+fn spawn_health_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(process::HEALTH_CHECK_INTERVAL_SECS)).await;
+
+            let Some(state) = app.try_state::<AppState>() else { continue };
+            let Ok(mut manager) = state.process_manager.lock() else { continue };
+            manager.update_health();
+        }
+    });
+}
+
+/// Emit `config-drift` whenever the set of drifted settings changes
+fn spawn_drift_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_reported = Vec::new();
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(process::drift::DRIFT_CHECK_INTERVAL_SECS)).await;
+
+            let Some(state) = app.try_state::<AppState>() else { continue };
+            let items = match process::drift::check_drift(state.process_manager.clone()).await {
+                Ok(items) => items,
+                Err(e) => {
+                    tracing::warn!("Config drift check failed: {}", e);
+                    continue;
+                }
+            };
+
+            if items != last_reported {
+                let _ = app.emit("config-drift", &items);
+                last_reported = items;
+            }
+        }
+    });
+}
+
+/// Run housekeeping shortly after startup and then periodically
+fn spawn_maintenance_scheduler() {
+    tauri::async_runtime::spawn(async move {
+        // Keep startup I/O for the services
+        let mut delay = std::time::Duration::from_secs(60);
+        loop {
+            tokio::time::sleep(delay).await;
+            delay = std::time::Duration::from_secs(runtime::maintenance::MAINTENANCE_INTERVAL_SECS);
+
+            if let Err(e) = tokio::task::spawn_blocking(runtime::maintenance::run_maintenance).await {
+                tracing::warn!("Maintenance task failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Rotate logs that outgrew the rotation policy while their service runs
+fn spawn_log_rotation() {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(process::rotation::ROTATION_INTERVAL_SECS)).await;
+
+            let Ok(paths) = runtime::locator::get_app_data_paths() else { continue };
+            let policy = config::AppSettings::load().log_rotation;
+            let result = tokio::task::spawn_blocking(move || process::rotation::rotate_all(&paths.logs_dir, &policy)).await;
+            if let Err(e) = result {
+                tracing::warn!("Log rotation failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Apply hand edits to the Caddyfile and php.ini, emitting `config-file-changed`
+fn spawn_config_watcher(app: AppHandle) {
+    let config_dir = match runtime::locator::get_app_data_paths() {
+        Ok(paths) => paths.config_dir,
+        Err(e) => {
+            tracing::warn!("Config watcher disabled: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&config_dir) {
+        tracing::warn!("Config watcher disabled: {}", e);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let result = process::config_watch::watch_config_dir(&config_dir, |file, path| {
+            let Some(state) = app.try_state::<AppState>() else { return };
+            let service = file.service();
+            tracing::info!("{} was edited, applying to {}", file.file_name(), service.display_name());
+
+            let result = apply_config_edit(&state, file, &path);
+            let change = process::config_watch::ConfigFileChange {
+                file: file.file_name().to_string(),
+                service,
+                applied: matches!(result, Ok(true)),
+                error: result.err(),
+            };
+            if let Some(ref e) = change.error {
+                tracing::warn!("Hand edit of {} not applied: {}", file.file_name(), e);
+            }
+            let _ = app.emit("config-file-changed", &change);
+        });
+        if let Err(e) = result {
+            tracing::warn!("Config watcher stopped: {}", e);
+        }
+    });
+}
+
+/// Validate an edited file and apply it if its service is running
+///
+/// Returns whether the running service picked the edit up.
+fn apply_config_edit(state: &AppState, file: process::config_watch::WatchedFile, path: &std::path::Path) -> Result<bool, String> {
+    use process::config_watch::{validate_caddyfile, validate_php_ini, WatchedFile};
+
+    let (paths, running) = {
+        let manager = state.process_manager.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        (manager.get_runtime_paths(), manager.status(file.service()) == ServiceState::Running)
+    };
+    // Kept as is, the next start picks the file up
+    let (Some(paths), true) = (paths, running) else { return Ok(false) };
+
+    match file {
+        WatchedFile::Caddyfile => {
+            validate_caddyfile(&paths.caddy, path)?;
+            let caddyfile = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read Caddyfile: {}", e))?;
+            tauri::async_runtime::block_on(async {
+                process::caddy_admin::CaddyAdmin::new()?.load_caddyfile(&caddyfile).await
+            })?;
+        }
+        WatchedFile::PhpIni => {
+            validate_php_ini(&paths.php_cgi, path)?;
+            // Nothing waits for the old process with the lock held: PHP-FPM
+            // rereads php.ini when it reloads its workers, PHP-CGI is replaced
+            let mut manager = state.process_manager.lock()
+                .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+            if manager.restart_with(ServiceType::PhpFpm, process::RestartStrategy::Rolling).is_err() {
+                manager.restart_with(ServiceType::PhpFpm, process::RestartStrategy::Force)?;
+            }
+        }
+    }
+    Ok(true)
 }
 
 fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
@@ -132,6 +494,16 @@ fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
                 }
             });
         }
+        "open-config-folder" => {
+            tauri::async_runtime::spawn(async move {
+                let _ = commands::open_config_folder().await;
+            });
+        }
+        "open-logs-folder" => {
+            tauri::async_runtime::spawn(async move {
+                let _ = commands::open_logs_folder().await;
+            });
+        }
         // Tray menu items
         "tray-show" => {
             if let Some(window) = app.get_webview_window("main") {
@@ -145,12 +517,8 @@ fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
             }
         }
         "tray-quit" => {
-            // Cleanup services before quitting
-            if let Some(state) = app.try_state::<AppState>() {
-                let pm = state.process_manager.clone();
-                let _ = pm.lock().unwrap().stop_all();
-            }
-            std::process::exit(0);
+            // Services are stopped by the ExitRequested handler
+            app.exit(0);
         }
         _ => {}
     }
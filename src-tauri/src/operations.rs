@@ -0,0 +1,215 @@
+//! Long-running operations
+//!
+//! Downloads, database initialization, backups, imports and project setup
+//! all report their progress as one `operation-progress` event, so the UI
+//! can show a single task list. Every operation has an id that
+//! `cancel_operation` takes; cancellable operations stop at their next
+//! cancellation point and report the `cancelled` step.
+
+use serde::Serialize;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Event carrying an `OperationProgress`
+pub const PROGRESS_EVENT: &str = "operation-progress";
+
+/// Error of an operation stopped by `cancel_operation`
+pub const CANCELLED: &str = "Operation cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    Download,
+    DatabaseInit,
+    Backup,
+    Import,
+    CreateProject,
+    Export,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OperationProgress {
+    pub id: String,
+    pub kind: OperationKind,
+    /// Current step, "done", "failed" or "cancelled" once finished
+    pub step: String,
+    /// None while the length of the step is unknown
+    pub percent: Option<u8>,
+    pub message: String,
+    pub cancellable: bool,
+}
+
+impl OperationProgress {
+    pub fn is_finished(&self) -> bool {
+        matches!(self.step.as_str(), "done" | "failed" | "cancelled")
+    }
+}
+
+/// Receives every progress update, e.g. to emit it to the frontend
+pub type ProgressEmitter = Arc<dyn Fn(&OperationProgress) + Send + Sync>;
+
+static EMITTER: OnceLock<ProgressEmitter> = OnceLock::new();
+/// Last progress of every running operation, with its cancel flag
+static RUNNING: Mutex<Vec<(OperationProgress, Arc<AtomicBool>)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Report progress of all later operations to `emitter`
+pub fn set_emitter(emitter: ProgressEmitter) {
+    let _ = EMITTER.set(emitter);
+}
+
+/// Last progress of every running operation, for a UI opened mid-operation
+pub fn running() -> Vec<OperationProgress> {
+    RUNNING.lock()
+        .map(|running| running.iter().map(|(progress, _)| progress.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Ask the operation `id` to stop
+pub fn cancel(id: &str) -> Result<(), String> {
+    let running = RUNNING.lock().map_err(|e| format!("Failed to acquire operations lock: {}", e))?;
+    let (progress, flag) = running.iter()
+        .find(|(progress, _)| progress.id == id)
+        .ok_or_else(|| format!("No running operation with id {}", id))?;
+    if !progress.cancellable {
+        return Err(format!("{} cannot be cancelled", progress.message));
+    }
+    flag.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+fn publish(progress: OperationProgress) {
+    if let Some(emit) = EMITTER.get() {
+        emit(&progress);
+    }
+    let Ok(mut running) = RUNNING.lock() else { return };
+    let position = running.iter().position(|(p, _)| p.id == progress.id);
+    match position {
+        Some(i) if progress.is_finished() => {
+            running.remove(i);
+        }
+        Some(i) => running[i].0 = progress,
+        None => {}
+    }
+}
+
+/// Handle of a running operation
+///
+/// Dropping it before `finish` reports the operation as failed, so an early
+/// return never leaves a task stuck in the UI.
+pub struct Operation {
+    id: String,
+    kind: OperationKind,
+    cancellable: bool,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Operation {
+    /// Register a new operation and report its first step
+    pub fn start(kind: OperationKind, cancellable: bool, message: impl Into<String>) -> Self {
+        let id = format!("op-{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let progress = OperationProgress {
+            id: id.clone(),
+            kind,
+            step: "started".to_string(),
+            percent: Some(0),
+            message: message.into(),
+            cancellable,
+        };
+        if let Ok(mut running) = RUNNING.lock() {
+            running.push((progress.clone(), cancelled.clone()));
+        }
+        publish(progress);
+        Self { id, kind, cancellable, cancelled }
+    }
+
+    pub fn progress(&self, step: &str, percent: Option<u8>, message: impl Into<String>) {
+        publish(OperationProgress {
+            id: self.id.clone(),
+            kind: self.kind,
+            step: step.to_string(),
+            percent: percent.map(|p| p.min(100)),
+            message: message.into(),
+            cancellable: self.cancellable,
+        });
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Cancellation point for synchronous work
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Report the outcome of the operation
+    pub fn finish<T, E: Display>(&self, result: &Result<T, E>) {
+        let last = running().into_iter().find(|p| p.id == self.id);
+        let message = last.as_ref().map(|p| p.message.clone()).unwrap_or_default();
+        match result {
+            Ok(_) => self.progress("done", Some(100), message),
+            Err(_) if self.is_cancelled() => self.progress("cancelled", last.and_then(|p| p.percent), CANCELLED),
+            Err(e) => self.progress("failed", last.and_then(|p| p.percent), e.to_string()),
+        }
+    }
+
+    /// Run `task` until it completes or the operation is cancelled, then
+    /// report the outcome
+    pub async fn run<T>(&self, task: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+        let result = tokio::select! {
+            result = task => result,
+            _ = self.cancellation() => Err(CANCELLED.to_string()),
+        };
+        self.finish(&result);
+        result
+    }
+
+    async fn cancellation(&self) {
+        while !self.is_cancelled() {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        if running().iter().any(|p| p.id == self.id) {
+            self.progress("failed", None, "Operation was interrupted");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_lifecycle() {
+        let op = Operation::start(OperationKind::Import, true, "Importing settings");
+        op.progress("applying", Some(50), "Applying settings");
+        let progress = running().into_iter().find(|p| p.id == op.id).unwrap();
+        assert_eq!((progress.step.as_str(), progress.percent), ("applying", Some(50)));
+
+        cancel(&op.id).unwrap();
+        assert_eq!(op.check_cancelled(), Err(CANCELLED.to_string()));
+        op.finish(&op.check_cancelled());
+        assert!(!running().iter().any(|p| p.id == op.id));
+        assert!(cancel(&op.id).is_err());
+
+        let op = Operation::start(OperationKind::DatabaseInit, false, "Initializing MySQL");
+        assert!(cancel(&op.id).is_err());
+        let id = op.id.clone();
+        drop(op);
+        assert!(!running().iter().any(|p| p.id == id));
+    }
+}
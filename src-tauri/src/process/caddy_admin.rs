@@ -0,0 +1,415 @@
+//! Caddy admin API client
+//!
+//! Caddy exposes a REST endpoint on localhost that accepts config changes at
+//! runtime. Routes added here take effect immediately without regenerating
+//! the Caddyfile or restarting Caddy; they live until Caddy is restarted, at
+//! which point the Caddyfile is the source of truth again.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Address of the Caddy admin endpoint, written to the Caddyfile global options
+pub const CADDY_ADMIN_ADDR: &str = "127.0.0.1:2019";
+
+/// Prefix of the `@id` given to routes managed through the admin API
+const ROUTE_ID_PREFIX: &str = "campp-vhost-";
+
+/// Live traffic of the local sites, from Caddy's Prometheus metrics
+///
+/// Counters start at zero whenever Caddy starts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WebServerStats {
+    pub total_requests: u64,
+    /// Responses by status code, e.g. "200" or "404"
+    pub status_codes: BTreeMap<String, u64>,
+    /// Requests being served right now
+    pub active_connections: u64,
+}
+
+/// A virtual host added at runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VhostRoute {
+    pub host: String,
+    pub root: String,
+}
+
+pub struct CaddyAdmin {
+    base_url: String,
+    client: Client,
+}
+
+impl CaddyAdmin {
+    pub fn new() -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            base_url: format!("http://{}", CADDY_ADMIN_ADDR),
+            client,
+        })
+    }
+
+    /// Check whether the admin endpoint is reachable
+    pub async fn is_available(&self) -> bool {
+        self.client
+            .get(format!("{}/config/", self.base_url))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// Get the full running JSON config
+    pub async fn get_config(&self) -> Result<Value, String> {
+        self.get_json("/config/").await
+    }
+
+    /// Request counters, enabled by the `metrics` global option
+    pub async fn stats(&self) -> Result<WebServerStats, String> {
+        let response = self.client
+            .get(format!("{}/metrics", self.base_url))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Caddy admin API: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Caddy admin API returned {}", response.status()));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Invalid response from Caddy admin API: {}", e))?;
+        Ok(parse_metrics(&text))
+    }
+
+    /// Replace the running config with a Caddyfile, without restarting Caddy
+    pub async fn load_caddyfile(&self, caddyfile: &str) -> Result<(), String> {
+        let response = self.client
+            .post(format!("{}/load", self.base_url))
+            .header("Content-Type", "text/caddyfile")
+            .body(caddyfile.to_string())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Caddy admin API: {}", e))?;
+
+        check_response(response).await
+    }
+
+    /// Add a virtual host serving `root` with PHP on `php_port`
+    ///
+    /// The route is inserted ahead of the main localhost site so its host
+    /// matcher is evaluated first. An existing route for the same host is replaced.
+    pub async fn add_vhost(&self, vhost: &VhostRoute, web_port: u16, php_port: u16) -> Result<(), String> {
+        validate_host(&vhost.host)?;
+
+        let server = self.find_server(web_port).await?;
+        let id = route_id(&vhost.host);
+
+        // Ignore the error, the route usually does not exist yet
+        let _ = self.delete(&format!("/id/{}", id)).await;
+
+        let response = self.client
+            .put(format!("{}/config/apps/http/servers/{}/routes/0", self.base_url, server))
+            .json(&build_vhost_route(vhost, php_port))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Caddy admin API: {}", e))?;
+
+        check_response(response).await
+    }
+
+    /// Remove a virtual host previously added with `add_vhost`
+    pub async fn remove_vhost(&self, host: &str) -> Result<(), String> {
+        validate_host(host)?;
+        self.delete(&format!("/id/{}", route_id(host))).await
+    }
+
+    /// List virtual hosts added through the admin API
+    pub async fn list_vhosts(&self, web_port: u16) -> Result<Vec<VhostRoute>, String> {
+        let server = self.find_server(web_port).await?;
+        let routes = self.get_json(&format!("/config/apps/http/servers/{}/routes", server)).await?;
+
+        Ok(routes
+            .as_array()
+            .map(|routes| routes.iter().filter_map(parse_vhost_route).collect())
+            .unwrap_or_default())
+    }
+
+    /// Find the name of the HTTP server listening on `web_port` (usually "srv0")
+    async fn find_server(&self, web_port: u16) -> Result<String, String> {
+        let servers = self.get_json("/config/apps/http/servers").await?;
+        let suffix = format!(":{}", web_port);
+
+        servers
+            .as_object()
+            .and_then(|servers| {
+                servers.iter().find(|(_, server)| {
+                    server["listen"]
+                        .as_array()
+                        .is_some_and(|listen| listen.iter().any(|l| l.as_str().is_some_and(|l| l.ends_with(&suffix))))
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .ok_or_else(|| format!("No Caddy server is listening on port {}", web_port))
+    }
+
+    async fn get_json(&self, path: &str) -> Result<Value, String> {
+        let response = self.client
+            .get(format!("{}{}", self.base_url, path))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Caddy admin API: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Caddy admin API returned {}", response.status()));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid response from Caddy admin API: {}", e))
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), String> {
+        let response = self.client
+            .delete(format!("{}{}", self.base_url, path))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Caddy admin API: {}", e))?;
+
+        check_response(response).await
+    }
+}
+
+async fn check_response(response: reqwest::Response) -> Result<(), String> {
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("Caddy admin API returned {}: {}", status, body.trim()))
+}
+
+fn route_id(host: &str) -> String {
+    format!("{}{}", ROUTE_ID_PREFIX, host)
+}
+
+/// Hostnames are interpolated into route IDs and URLs, keep them strict
+fn validate_host(host: &str) -> Result<(), String> {
+    let valid = !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid host name: '{}'", host))
+    }
+}
+
+/// JSON equivalent of a Caddyfile site block with `root`, `php_fastcgi` and `file_server browse`
+fn build_vhost_route(vhost: &VhostRoute, php_port: u16) -> Value {
+    let root = vhost.root.replace('\\', "/");
+
+    json!({
+        "@id": route_id(&vhost.host),
+        "match": [{ "host": [vhost.host] }],
+        "handle": [{
+            "handler": "subroute",
+            "routes": [
+                { "handle": [{ "handler": "vars", "root": root }] },
+                {
+                    // Directories containing index.php get a trailing slash
+                    "match": [{
+                        "file": { "try_files": ["{http.request.uri.path}/index.php"] },
+                        "not": [{ "path": ["*/"] }]
+                    }],
+                    "handle": [{
+                        "handler": "static_response",
+                        "headers": { "Location": ["{http.request.orig_uri.path}/"] },
+                        "status_code": 308
+                    }]
+                },
+                {
+                    "match": [{
+                        "file": {
+                            "try_files": ["{http.request.uri.path}", "{http.request.uri.path}/index.php", "index.php"],
+                            "split_path": [".php"]
+                        }
+                    }],
+                    "handle": [{ "handler": "rewrite", "uri": "{http.matchers.file.relative}" }]
+                },
+                {
+                    "match": [{ "path": ["*.php"] }],
+                    "handle": [{
+                        "handler": "reverse_proxy",
+                        "transport": { "protocol": "fastcgi", "split_path": [".php"] },
+                        "upstreams": [{ "dial": format!("127.0.0.1:{}", php_port) }]
+                    }]
+                },
+                { "handle": [{ "handler": "file_server", "browse": {} }] }
+            ]
+        }],
+        "terminal": true
+    })
+}
+
+fn parse_vhost_route(route: &Value) -> Option<VhostRoute> {
+    let host = route["@id"].as_str()?.strip_prefix(ROUTE_ID_PREFIX)?.to_string();
+    let root = route["handle"][0]["routes"][0]["handle"][0]["root"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    Some(VhostRoute { host, root })
+}
+
+/// One sample of the Prometheus text format: `name{label="value"} 1`
+struct Sample {
+    name: String,
+    labels: HashMap<String, String>,
+    value: f64,
+}
+
+fn parse_sample(line: &str) -> Option<Sample> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (name, rest) = match line.find('{') {
+        Some(open) => (&line[..open], &line[open + 1..]),
+        None => line.split_once(' ')?,
+    };
+    let mut labels = HashMap::new();
+    let mut rest = rest;
+    if line.contains('{') {
+        // Values are quoted and may contain escaped quotes, commas and braces
+        loop {
+            rest = rest.trim_start_matches([',', ' ']);
+            if let Some(after) = rest.strip_prefix('}') {
+                rest = after;
+                break;
+            }
+            let (key, after) = rest.split_once("=\"")?;
+            let mut value = String::new();
+            let mut chars = after.char_indices();
+            let end = loop {
+                match chars.next()? {
+                    (_, '\\') => value.push(chars.next()?.1),
+                    (i, '"') => break i,
+                    (_, c) => value.push(c),
+                }
+            };
+            labels.insert(key.to_string(), value);
+            rest = &after[end + 1..];
+        }
+    }
+
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    Some(Sample { name: name.to_string(), labels, value })
+}
+
+/// Fold Caddy's per-handler metrics into totals
+///
+/// Every handler a request passes through is instrumented, so summing all
+/// of them counts a request several times. The handler with the most
+/// requests is the outermost one, which sees every request exactly once.
+fn parse_metrics(text: &str) -> WebServerStats {
+    let samples: Vec<Sample> = text.lines().filter_map(parse_sample).collect();
+    let handler = |sample: &Sample| sample.labels.get("handler").cloned().unwrap_or_default();
+
+    let mut per_handler: HashMap<String, f64> = HashMap::new();
+    for sample in samples.iter().filter(|s| s.name == "caddy_http_requests_total") {
+        *per_handler.entry(handler(sample)).or_default() += sample.value;
+    }
+    let Some((outer, total)) = per_handler.into_iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+        return WebServerStats::default();
+    };
+
+    let mut stats = WebServerStats { total_requests: total as u64, ..Default::default() };
+    for sample in samples.iter().filter(|s| handler(s) == outer) {
+        match sample.name.as_str() {
+            "caddy_http_request_duration_seconds_count" => {
+                let code = sample.labels.get("code").cloned().unwrap_or_default();
+                *stats.status_codes.entry(code).or_default() += sample.value as u64;
+            }
+            "caddy_http_requests_in_flight" => stats.active_connections += sample.value as u64,
+            _ => {}
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_host() {
+        assert!(validate_host("myapp.localhost").is_ok());
+        assert!(validate_host("my-app.test").is_ok());
+        assert!(validate_host("").is_err());
+        assert!(validate_host("bad_host.localhost").is_err());
+        assert!(validate_host("-app.localhost").is_err());
+        assert!(validate_host("app..localhost").is_err());
+        assert!(validate_host("app/../x").is_err());
+    }
+
+    #[test]
+    fn test_vhost_route_roundtrip() {
+        let vhost = VhostRoute {
+            host: "myapp.localhost".to_string(),
+            root: "C:\\campp\\projects\\myapp".to_string(),
+        };
+        let route = build_vhost_route(&vhost, 9000);
+
+        assert_eq!(route["@id"], "campp-vhost-myapp.localhost");
+        assert_eq!(route["match"][0]["host"][0], "myapp.localhost");
+
+        let parsed = parse_vhost_route(&route).unwrap();
+        assert_eq!(parsed.host, "myapp.localhost");
+        assert_eq!(parsed.root, "C:/campp/projects/myapp");
+    }
+
+    #[test]
+    fn test_parse_metrics() {
+        let text = r#"# HELP caddy_http_requests_total Counter of HTTP(S) requests made.
+# TYPE caddy_http_requests_total counter
+caddy_http_requests_total{handler="subroute",server="srv0"} 12
+caddy_http_requests_total{handler="file_server",server="srv0"} 9
+caddy_http_requests_total{handler="reverse_proxy",server="srv0"} 3
+caddy_http_request_duration_seconds_count{code="200",handler="subroute",method="GET",server="srv0"} 10
+caddy_http_request_duration_seconds_count{code="404",handler="subroute",method="GET",server="srv0"} 2
+caddy_http_request_duration_seconds_count{code="200",handler="file_server",method="GET",server="srv0"} 9
+caddy_http_requests_in_flight{handler="subroute",server="srv0"} 1
+caddy_http_requests_in_flight{handler="file_server",server="srv0"} 1
+"#;
+        let stats = parse_metrics(text);
+        assert_eq!(stats.total_requests, 12);
+        assert_eq!(stats.status_codes, BTreeMap::from([("200".to_string(), 10), ("404".to_string(), 2)]));
+        assert_eq!(stats.active_connections, 1);
+
+        let sample = parse_sample(r#"x{path="a\"b,}"} 2.5e0"#).unwrap();
+        assert_eq!(sample.labels["path"], "a\"b,}");
+        assert_eq!(sample.value, 2.5);
+        assert_eq!(parse_metrics("go_goroutines 12\n"), WebServerStats::default());
+    }
+
+    #[test]
+    fn test_unmanaged_routes_ignored() {
+        assert!(parse_vhost_route(&json!({ "match": [{ "host": ["localhost"] }] })).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//! Hot reload of hand-edited config files
+//!
+//! Watches the config directory for changes to the Caddyfile and php.ini.
+//! Rewrites by CAMPP itself match the recorded fingerprint and are ignored.
+//! Hand edits are validated with the service binary, then applied: the
+//! Caddyfile through the admin API, php.ini by restarting PHP.
+
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::manager::configure_no_window;
+use super::ServiceType;
+
+/// Editors write a file in several steps, wait for them to settle
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WatchedFile {
+    Caddyfile,
+    PhpIni,
+}
+
+impl WatchedFile {
+    const ALL: [WatchedFile; 2] = [WatchedFile::Caddyfile, WatchedFile::PhpIni];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            WatchedFile::Caddyfile => "Caddyfile",
+            WatchedFile::PhpIni => "php.ini",
+        }
+    }
+
+    pub fn service(&self) -> ServiceType {
+        match self {
+            WatchedFile::Caddyfile => ServiceType::Caddy,
+            WatchedFile::PhpIni => ServiceType::PhpFpm,
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?;
+        Self::ALL.into_iter().find(|f| name == f.file_name())
+    }
+}
+
+/// Emitted as "config-file-changed" after a hand edit was handled
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileChange {
+    pub file: String,
+    pub service: ServiceType,
+    /// Whether the running service picked up the edit
+    pub applied: bool,
+    pub error: Option<String>,
+}
+
+/// Watch `config_dir` and call `on_edit` for each hand-edited file while
+/// `watch_config_files` is enabled
+///
+/// Blocks for as long as the watcher runs, call it on a dedicated thread.
+pub fn watch_config_dir(config_dir: &Path, on_edit: impl Fn(WatchedFile, PathBuf)) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to create config watcher: {}", e))?;
+    watcher
+        .watch(config_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", config_dir.display(), e))?;
+
+    // Content last handed to `on_edit`, so a batch of writes is applied once
+    let mut handled: HashMap<WatchedFile, Vec<u8>> = HashMap::new();
+
+    while let Ok(event) = rx.recv() {
+        let mut touched = HashSet::new();
+        let mut collect = |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                touched.extend(event.paths.iter().filter_map(|p| WatchedFile::from_path(p)));
+            }
+        };
+        collect(event);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect(event);
+        }
+
+        // Checked per batch so the setting applies without restarting CAMPP
+        if !crate::config::AppSettings::load().watch_config_files {
+            continue;
+        }
+        for file in touched {
+            let path = config_dir.join(file.file_name());
+            if !crate::config::generated::is_hand_edited(&path) {
+                continue;
+            }
+            let Ok(content) = fs::read(&path) else { continue };
+            if handled.get(&file) == Some(&content) {
+                continue;
+            }
+            handled.insert(file, content);
+            on_edit(file, path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a hand-edited Caddyfile with `caddy validate`
+pub fn validate_caddyfile(caddy: &Path, caddyfile: &Path) -> Result<(), String> {
+    let output = configure_no_window(Command::new(caddy))
+        .arg("validate")
+        .arg("--config")
+        .arg(caddyfile)
+        .arg("--adapter")
+        .arg("caddyfile")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run caddy validate: {}", e))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("invalid Caddyfile");
+    Err(message.trim().to_string())
+}
+
+/// Load a hand-edited php.ini with the PHP binary, which reports syntax
+/// errors but still exits successfully
+pub fn validate_php_ini(php: &Path, php_ini: &Path) -> Result<(), String> {
+    let output = configure_no_window(Command::new(php))
+        .arg("-c")
+        .arg(php_ini)
+        .arg("-v")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run PHP: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.lines().chain(stdout.lines()).find(|l| l.contains("syntax error") || l.contains("Parse error")) {
+        Some(line) => Err(line.trim().to_string()),
+        None if !output.status.success() => Err(format!("PHP exited with {}", output.status)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watched_file_from_path() {
+        assert_eq!(WatchedFile::from_path(Path::new("/cfg/Caddyfile")), Some(WatchedFile::Caddyfile));
+        assert_eq!(WatchedFile::from_path(Path::new("/cfg/php.ini")), Some(WatchedFile::PhpIni));
+        assert_eq!(WatchedFile::from_path(Path::new("/cfg/php-fpm.conf")), None);
+        assert_eq!(WatchedFile::PhpIni.service(), ServiceType::PhpFpm);
+    }
+}
@@ -0,0 +1,371 @@
+//! Wildcard DNS for project domains
+//!
+//! A tiny UDP responder that answers every name under the project TLD with
+//! the loopback address, so new projects and aliases work without touching
+//! the hosts file. It runs as a thread inside CAMPP, owned by the process
+//! manager next to the services. The OS only asks it about that TLD once the
+//! resolver is configured: `/etc/resolver/<tld>` on macOS, a systemd-resolved
+//! drop-in on Linux and an NRPT rule on Windows, each written with the
+//! platform's elevation prompt.
+
+use serde::{Deserialize, Serialize};
+use std::net::UdpSocket;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const TYPE_A: u16 = 1;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+const RCODE_FORMERR: u16 = 1;
+const RCODE_NXDOMAIN: u16 = 3;
+const RCODE_REFUSED: u16 = 5;
+/// Answers are cheap, keep them short-lived so a disabled resolver is noticed
+const TTL_SECS: u32 = 60;
+
+/// Wildcard DNS options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsSettings {
+    /// Start the resolver with the other services
+    #[serde(default)]
+    pub enabled: bool,
+    /// UDP port on 127.0.0.1
+    #[serde(default = "default_dns_port")]
+    pub port: u16,
+}
+
+impl Default for DnsSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: default_dns_port() }
+    }
+}
+
+/// Windows' NRPT cannot name a port; elsewhere a high port needs no root
+fn default_dns_port() -> u16 {
+    if cfg!(target_os = "windows") { 53 } else { 5300 }
+}
+
+/// State of the wildcard DNS and the OS resolver pointing at it
+#[derive(Debug, Clone, Serialize)]
+pub struct DnsStatus {
+    pub enabled: bool,
+    /// Port it listens on, None when it is not running
+    pub running_port: Option<u16>,
+    pub port: u16,
+    pub tld: String,
+    pub resolver_configured: bool,
+}
+
+/// The resolver thread
+pub struct DnsServer {
+    port: u16,
+    tld: String,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DnsServer {
+    /// Answer `*.<tld>` queries on 127.0.0.1:`port`
+    pub fn start(port: u16, tld: &str) -> Result<Self, String> {
+        let socket = UdpSocket::bind(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to listen on 127.0.0.1:{} for DNS: {}", port, e))?;
+        // Wake up regularly to notice a stop request
+        socket.set_read_timeout(Some(Duration::from_millis(250)))
+            .map_err(|e| format!("Failed to configure DNS socket: {}", e))?;
+        let port = socket.local_addr().map(|addr| addr.port()).unwrap_or(port);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let tld = tld.to_ascii_lowercase();
+        let thread = {
+            let stop = stop.clone();
+            let tld = tld.clone();
+            std::thread::Builder::new()
+                .name("campp-dns".to_string())
+                .spawn(move || serve(socket, &tld, &stop))
+                .map_err(|e| format!("Failed to start DNS thread: {}", e))?
+        };
+        tracing::info!("Wildcard DNS for *.{} listening on 127.0.0.1:{}", tld, port);
+        Ok(Self { port, tld, stop, thread: Some(thread) })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn tld(&self) -> &str {
+        &self.tld
+    }
+
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for DnsServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn serve(socket: UdpSocket, tld: &str, stop: &AtomicBool) {
+    let mut buf = [0u8; 512];
+    while !stop.load(Ordering::Relaxed) {
+        let (len, peer) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => continue,
+            Err(e) => {
+                tracing::debug!("DNS receive failed: {}", e);
+                continue;
+            }
+        };
+        if let Some(reply) = answer(&buf[..len], tld) {
+            let _ = socket.send_to(&reply, peer);
+        }
+    }
+}
+
+/// Reply to the DNS `query`, None when it is too short to answer at all
+///
+/// A and ANY queries under `tld` get 127.0.0.1; other types under it get an
+/// empty answer so clients fall back to IPv4, and names outside it are
+/// refused, this is not a general purpose resolver.
+pub fn answer(query: &[u8], tld: &str) -> Option<Vec<u8>> {
+    if query.len() < 12 {
+        return None;
+    }
+    let flags = u16::from_be_bytes([query[2], query[3]]);
+    let is_response = flags & 0x8000 != 0;
+    if is_response {
+        return None;
+    }
+    let opcode = flags & 0x7800;
+    let recursion_desired = flags & 0x0100;
+    let question_count = u16::from_be_bytes([query[4], query[5]]);
+
+    let reply = |rcode: u16, question: &[u8], answers: u16| {
+        let mut reply = Vec::with_capacity(12 + question.len() + 16);
+        reply.extend_from_slice(&query[..2]);
+        // Response, authoritative
+        reply.extend_from_slice(&(0x8000 | 0x0400 | opcode | recursion_desired | rcode).to_be_bytes());
+        reply.extend_from_slice(&(if question.is_empty() { 0u16 } else { 1 }).to_be_bytes());
+        reply.extend_from_slice(&answers.to_be_bytes());
+        reply.extend_from_slice(&[0, 0, 0, 0]);
+        reply.extend_from_slice(question);
+        reply
+    };
+
+    if opcode != 0 || question_count != 1 {
+        return Some(reply(RCODE_FORMERR, &[], 0));
+    }
+    let Some((name, question_end)) = read_name(query, 12) else {
+        return Some(reply(RCODE_FORMERR, &[], 0));
+    };
+    if query.len() < question_end + 4 {
+        return Some(reply(RCODE_FORMERR, &[], 0));
+    }
+    let question = &query[12..question_end + 4];
+    let qtype = u16::from_be_bytes([query[question_end], query[question_end + 1]]);
+    let qclass = u16::from_be_bytes([query[question_end + 2], query[question_end + 3]]);
+
+    let in_tld = name == tld || name.ends_with(&format!(".{}", tld));
+    if !in_tld {
+        return Some(reply(RCODE_REFUSED, question, 0));
+    }
+    if qclass != CLASS_IN {
+        return Some(reply(RCODE_NXDOMAIN, question, 0));
+    }
+    if qtype != TYPE_A && qtype != TYPE_ANY {
+        return Some(reply(0, question, 0));
+    }
+
+    let mut reply = reply(0, question, 1);
+    // Name: pointer to the question at offset 12
+    reply.extend_from_slice(&[0xC0, 0x0C]);
+    reply.extend_from_slice(&TYPE_A.to_be_bytes());
+    reply.extend_from_slice(&CLASS_IN.to_be_bytes());
+    reply.extend_from_slice(&TTL_SECS.to_be_bytes());
+    reply.extend_from_slice(&4u16.to_be_bytes());
+    reply.extend_from_slice(&[127, 0, 0, 1]);
+    Some(reply)
+}
+
+/// The lowercased name starting at `offset` and the offset after it
+///
+/// Queries never compress their only question, pointers are rejected.
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    loop {
+        let len = *packet.get(offset)? as usize;
+        offset += 1;
+        if len == 0 {
+            break;
+        }
+        if len > 63 {
+            return None;
+        }
+        let label = packet.get(offset..offset + len)?;
+        labels.push(String::from_utf8_lossy(label).to_ascii_lowercase());
+        offset += len;
+    }
+    Some((labels.join("."), offset))
+}
+
+/// Where the OS resolver is pointed at CAMPP for `tld`
+pub fn resolver_config_path(tld: &str) -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        Some(PathBuf::from("/etc/resolver").join(tld))
+    } else if cfg!(target_os = "linux") {
+        Some(PathBuf::from(format!("/etc/systemd/resolved.conf.d/campp-{}.conf", tld)))
+    } else {
+        None
+    }
+}
+
+/// Resolver config sending `tld` queries to 127.0.0.1:`port`
+#[cfg(not(target_os = "windows"))]
+fn resolver_config(tld: &str, port: u16) -> String {
+    if cfg!(target_os = "macos") {
+        format!("# Written by CAMPP\nnameserver 127.0.0.1\nport {}\n", port)
+    } else {
+        format!("# Written by CAMPP\n[Resolve]\nDNS=127.0.0.1:{}\nDomains=~{}\n", port, tld)
+    }
+}
+
+/// Whether the OS resolver sends `tld` queries to CAMPP
+pub fn resolver_configured(tld: &str) -> bool {
+    match resolver_config_path(tld) {
+        Some(path) => path.is_file(),
+        None => nrpt_rule_exists(tld),
+    }
+}
+
+/// Point the OS resolver at 127.0.0.1:`port` for `tld`, prompting for
+/// administrator rights
+pub fn configure_resolver(tld: &str, port: u16) -> Result<(), String> {
+    if cfg!(target_os = "windows") && port != 53 {
+        return Err(format!("Windows can only send DNS queries to port 53, the resolver listens on {}", port));
+    }
+    crate::elevate::run(&configure_script(tld, port), "configuring the resolver")
+}
+
+/// Stop sending `tld` queries to CAMPP
+pub fn remove_resolver(tld: &str) -> Result<(), String> {
+    if !resolver_configured(tld) {
+        return Ok(());
+    }
+    crate::elevate::run(&remove_script(tld), "removing the resolver config")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn configure_script(tld: &str, port: u16) -> String {
+    use crate::elevate::shell_quote;
+    let path = resolver_config_path(tld).unwrap_or_default();
+    let dir = path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+    let mut script = format!(
+        "mkdir -p {} && printf '%s' {} > {}",
+        shell_quote(&dir),
+        shell_quote(&resolver_config(tld, port)),
+        shell_quote(&path.to_string_lossy())
+    );
+    if cfg!(target_os = "linux") {
+        script.push_str(" && systemctl restart systemd-resolved");
+    }
+    script
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove_script(tld: &str) -> String {
+    use crate::elevate::shell_quote;
+    let path = resolver_config_path(tld).unwrap_or_default();
+    let mut script = format!("rm -f {}", shell_quote(&path.to_string_lossy()));
+    if cfg!(target_os = "linux") {
+        script.push_str(" && systemctl restart systemd-resolved");
+    }
+    script
+}
+
+#[cfg(target_os = "windows")]
+fn configure_script(tld: &str, _port: u16) -> String {
+    format!(
+        "Get-DnsClientNrptRule | Where-Object {{ $_.Namespace -eq '.{0}' }} | Remove-DnsClientNrptRule -Force; Add-DnsClientNrptRule -Namespace '.{0}' -NameServers '127.0.0.1'",
+        tld
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn remove_script(tld: &str) -> String {
+    format!("Get-DnsClientNrptRule | Where-Object {{ $_.Namespace -eq '.{}' }} | Remove-DnsClientNrptRule -Force", tld)
+}
+
+#[cfg(target_os = "windows")]
+fn nrpt_rule_exists(tld: &str) -> bool {
+    let script = format!("if (Get-DnsClientNrptRule | Where-Object {{ $_.Namespace -eq '.{}' }}) {{ exit 0 }} else {{ exit 1 }}", tld);
+    super::manager::configure_no_window(std::process::Command::new("powershell"))
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn nrpt_rule_exists(_tld: &str) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut packet = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+        packet.extend_from_slice(&qtype.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn test_answer() {
+        let q = query("API.Shop.test", TYPE_A);
+        let reply = answer(&q, "test").unwrap();
+        assert_eq!(&reply[..2], &[0x12, 0x34]);
+        // Response, authoritative, recursion desired copied, no error
+        assert_eq!(u16::from_be_bytes([reply[2], reply[3]]), 0x8500);
+        assert_eq!(u16::from_be_bytes([reply[6], reply[7]]), 1);
+        assert_eq!(&reply[12..q.len()], &q[12..]);
+        assert_eq!(&reply[reply.len() - 4..], &[127, 0, 0, 1]);
+
+        // AAAA: no records, clients use the A answer
+        let reply = answer(&query("shop.test", 28), "test").unwrap();
+        assert_eq!(u16::from_be_bytes([reply[6], reply[7]]), 0);
+        assert_eq!(reply[3] & 0x0F, 0);
+
+        let reply = answer(&query("example.com", TYPE_A), "test").unwrap();
+        assert_eq!(u16::from_be_bytes([reply[2], reply[3]]) & 0x0F, RCODE_REFUSED);
+        assert!(answer(&query("nottest", TYPE_A), "test").is_some_and(|r| r[3] & 0x0F == RCODE_REFUSED as u8));
+
+        assert!(answer(&[0; 4], "test").is_none());
+        let truncated = &q[..q.len() - 2];
+        assert_eq!(answer(truncated, "test").unwrap()[3] & 0x0F, RCODE_FORMERR as u8);
+    }
+
+    #[test]
+    fn test_server() {
+        let mut server = DnsServer::start(0, "test").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        client.send_to(&query("shop.test", TYPE_A), ("127.0.0.1", server.port())).unwrap();
+        let mut buf = [0u8; 512];
+        let len = client.recv(&mut buf).unwrap();
+        assert_eq!(&buf[len - 4..len], &[127, 0, 0, 1]);
+        server.stop();
+    }
+}
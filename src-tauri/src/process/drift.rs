@@ -0,0 +1,299 @@
+//! Stale config drift detection
+//!
+//! Compares what is actually in effect in the running services with what the
+//! saved settings say should be, e.g. a service still listening on its old
+//! port after a settings change, or a config file edited after the service
+//! started. Every drift item can be resolved by restarting the service.
+//!
+//! Caddy and MySQL are asked directly, through the admin API and `SHOW
+//! VARIABLES`. PHP has no such interface: `php -i` with CAMPP's php.ini shows
+//! the values PHP loads from the file, which are compared with the php.ini
+//! the settings generate, and a php.ini modified after PHP-FPM started means
+//! the running pool has not loaded it yet.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use super::caddy_admin::CaddyAdmin;
+use super::manager::ProcessManager;
+use super::ServiceType;
+use crate::config::AppSettings;
+use crate::runtime::locator::RuntimePaths;
+
+/// Interval between background drift checks
+pub const DRIFT_CHECK_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DriftItem {
+    pub service: ServiceType,
+    /// What drifted, e.g. "port" or a config file name
+    pub setting: String,
+    pub expected: String,
+    pub actual: String,
+    /// Command that resolves the drift
+    pub action: String,
+}
+
+impl DriftItem {
+    fn new(service: ServiceType, setting: &str, expected: impl ToString, actual: impl ToString) -> Self {
+        Self {
+            service,
+            setting: setting.to_string(),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+            action: "restart_service".to_string(),
+        }
+    }
+}
+
+/// Snapshot of running services taken under the manager lock
+struct RunningService {
+    service: ServiceType,
+    port: u16,
+    started_at: SystemTime,
+}
+
+/// Run all drift checks against the currently running services
+pub async fn check_drift(pm: Arc<Mutex<ProcessManager>>) -> Result<Vec<DriftItem>, String> {
+    let (running, paths) = {
+        let manager = pm.lock()
+            .map_err(|e| format!("Failed to acquire process manager lock: {}", e))?;
+        let running: Vec<RunningService> = [ServiceType::Caddy, ServiceType::PhpFpm, ServiceType::MySQL, ServiceType::PostgreSQL]
+            .into_iter()
+            .filter_map(|service| {
+                manager.get_started(service)
+                    .map(|(port, started_at)| RunningService { service, port, started_at })
+            })
+            .collect();
+        (running, manager.get_runtime_paths())
+    };
+
+    if running.is_empty() {
+        return Ok(Vec::new());
+    }
+    let settings = AppSettings::load();
+
+    let mut items = check_ports_and_files(&running, &settings, paths.as_ref());
+
+    if running.iter().any(|r| r.service == ServiceType::Caddy) {
+        items.extend(check_caddy(&settings).await);
+    }
+
+    if let Some(paths) = paths.clone().filter(|_| running.iter().any(|r| r.service == ServiceType::PhpFpm)) {
+        let settings = settings.clone();
+        let php_items = tokio::task::spawn_blocking(move || check_php(&paths, &settings))
+            .await
+            .map_err(|e| format!("Task error: {}", e))?;
+        items.extend(php_items);
+    }
+
+    if let (Some(mysql), Some(paths)) = (running.iter().find(|r| r.service == ServiceType::MySQL), paths.clone()) {
+        let port = mysql.port;
+        let settings = settings.clone();
+        let mysql_items = tokio::task::spawn_blocking(move || check_mysql(&paths, port, &settings))
+            .await
+            .map_err(|e| format!("Task error: {}", e))?;
+        items.extend(mysql_items);
+    }
+
+    Ok(items)
+}
+
+/// Ports the services were started on, and config files touched since then
+fn check_ports_and_files(running: &[RunningService], settings: &AppSettings, paths: Option<&RuntimePaths>) -> Vec<DriftItem> {
+    let mut items = Vec::new();
+
+    for r in running {
+        let expected_port = match r.service {
+            ServiceType::Caddy => settings.web_port,
+            ServiceType::PhpFpm => settings.php_port,
+            ServiceType::MySQL => settings.mysql_port,
+            ServiceType::PostgreSQL => settings.postgres_port,
+            ServiceType::Tunnel => settings.tunnel.port,
+            ServiceType::Mailpit => settings.mailpit.smtp_port,
+            ServiceType::Memcached => settings.memcached.port,
+            ServiceType::Minio => settings.minio.port,
+            ServiceType::Ftp => settings.ftp.sftp_port,
+        };
+        if r.port != expected_port {
+            items.push(DriftItem::new(r.service, "port", expected_port, r.port));
+        }
+
+        let Some(paths) = paths else { continue };
+        // The Caddyfile is left out, it can be applied live through the admin API
+        let files: Vec<PathBuf> = match r.service {
+            ServiceType::Caddy => Vec::new(),
+            ServiceType::PhpFpm => vec![paths.php_ini.clone(), paths.config_dir.join("php-fpm.conf")],
+            ServiceType::MySQL | ServiceType::Tunnel | ServiceType::Mailpit | ServiceType::Memcached | ServiceType::Minio | ServiceType::Ftp => Vec::new(),
+            ServiceType::PostgreSQL => vec![paths.pgsql_data_dir.join("postgresql.conf")],
+        };
+        for file in files {
+            let modified = fs::metadata(&file).and_then(|m| m.modified());
+            if matches!(modified, Ok(m) if m > r.started_at) {
+                let name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                items.push(DriftItem::new(r.service, &name, "unchanged since start", "modified after start"));
+            }
+        }
+    }
+
+    items
+}
+
+/// Compare Caddy's live JSON config with the expected web and PHP ports
+async fn check_caddy(settings: &AppSettings) -> Vec<DriftItem> {
+    let Ok(admin) = CaddyAdmin::new() else { return Vec::new() };
+    match admin.get_config().await {
+        Ok(config) => caddy_config_drift(&config, settings),
+        Err(e) => {
+            tracing::debug!("Skipping Caddy drift check: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn caddy_config_drift(config: &Value, settings: &AppSettings) -> Vec<DriftItem> {
+    let mut items = Vec::new();
+    let servers = &config["apps"]["http"]["servers"];
+
+    let listen: Vec<&str> = servers
+        .as_object()
+        .map(|servers| {
+            servers.values()
+                .filter_map(|s| s["listen"].as_array())
+                .flatten()
+                .filter_map(|l| l.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    let web_suffix = format!(":{}", settings.web_port);
+    if !listen.is_empty() && !listen.iter().any(|l| l.ends_with(&web_suffix)) {
+        items.push(DriftItem::new(ServiceType::Caddy, "listen", web_suffix, listen.join(", ")));
+    }
+
+    let mut dials = Vec::new();
+    collect_fastcgi_dials(servers, &mut dials);
+    let expected_dial = format!("127.0.0.1:{}", settings.php_port);
+    if let Some(stale) = dials.iter().find(|d| **d != expected_dial) {
+        items.push(DriftItem::new(ServiceType::Caddy, "php_fastcgi", expected_dial, stale));
+    }
+
+    items
+}
+
+/// Find the upstream addresses of every fastcgi reverse_proxy handler
+fn collect_fastcgi_dials(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if map.get("handler").and_then(Value::as_str) == Some("reverse_proxy")
+                && map["transport"]["protocol"].as_str() == Some("fastcgi")
+            {
+                if let Some(upstreams) = map.get("upstreams").and_then(Value::as_array) {
+                    out.extend(upstreams.iter().filter_map(|u| u["dial"].as_str().map(String::from)));
+                }
+            }
+            map.values().for_each(|v| collect_fastcgi_dials(v, out));
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_fastcgi_dials(v, out)),
+        _ => {}
+    }
+}
+
+/// php.ini directives that depend on settings and paths, the rest of the
+/// generated php.ini is fixed
+const PHP_INI_DIRECTIVES: &[&str] = &["extension_dir", "error_log", "session.save_path", "sendmail_path", "SMTP", "smtp_port"];
+
+/// Compare `php -i` with the php.ini the settings generate
+fn check_php(paths: &RuntimePaths, settings: &AppSettings) -> Vec<DriftItem> {
+    let expected = match crate::config::generator::php_ini_content(paths, settings) {
+        Ok(expected) => expected,
+        Err(e) => {
+            tracing::debug!("Skipping PHP drift check: {}", e);
+            return Vec::new();
+        }
+    };
+    let php_ini = paths.php_ini.to_string_lossy().to_string();
+    match crate::runtime::validate::run_binary("PHP", &paths.php_cgi, &["-c", &php_ini, "-i"]) {
+        Ok(info) => php_ini_drift(&expected, &info),
+        Err(e) => {
+            tracing::debug!("Skipping PHP drift check: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Directives of `expected_ini` whose value in phpinfo output differs
+fn php_ini_drift(expected_ini: &str, info: &str) -> Vec<DriftItem> {
+    expected_ini.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+        .filter(|(key, _)| PHP_INI_DIRECTIVES.contains(key))
+        .filter_map(|(key, value)| {
+            let actual = crate::runtime::system::phpinfo_value(info, key).unwrap_or_else(|| "no value".to_string());
+            (actual != value).then(|| DriftItem::new(ServiceType::PhpFpm, &format!("{} (php -i)", key), value, actual))
+        })
+        .collect()
+}
+
+/// Ask MySQL which port it is really listening on
+fn check_mysql(paths: &RuntimePaths, running_port: u16, settings: &AppSettings) -> Vec<DriftItem> {
+    match crate::database::mysql::show_variable(paths, running_port, &settings.mysql_root_password, "port") {
+        Ok(actual) if actual != settings.mysql_port.to_string() => {
+            vec![DriftItem::new(ServiceType::MySQL, "port (SHOW VARIABLES)", settings.mysql_port, actual)]
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            tracing::debug!("Skipping MySQL drift check: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_caddy_config_drift() {
+        let settings = AppSettings::default();
+        let config = json!({ "apps": { "http": { "servers": { "srv0": {
+            "listen": [":8080"],
+            "routes": [{ "handle": [{ "handler": "subroute", "routes": [{ "handle": [{
+                "handler": "reverse_proxy",
+                "transport": { "protocol": "fastcgi" },
+                "upstreams": [{ "dial": "127.0.0.1:9000" }]
+            }] }] }] }]
+        } } } } });
+        assert!(caddy_config_drift(&config, &settings).is_empty());
+
+        let settings = AppSettings { web_port: 8081, php_port: 9001, ..Default::default() };
+        let items = caddy_config_drift(&config, &settings);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].setting, "listen");
+        assert_eq!(items[1].actual, "127.0.0.1:9000");
+    }
+
+    #[test]
+    fn test_php_ini_drift() {
+        let expected = "error_log = \"/opt/campp/logs/php-errors.log\"\nmemory_limit = 256M\nsmtp_port = 1025\n";
+        let info = "error_log => /opt/campp/logs/php-errors.log => /opt/campp/logs/php-errors.log\nmemory_limit => 128M => 128M\nsmtp_port => 25 => 25\n";
+        // memory_limit is not one of the settings-driven directives
+        assert_eq!(php_ini_drift(expected, info), vec![DriftItem::new(ServiceType::PhpFpm, "smtp_port (php -i)", 1025, 25)]);
+    }
+
+    #[test]
+    fn test_port_drift() {
+        let settings = AppSettings { mysql_port: 3310, ..Default::default() };
+        let running = [
+            RunningService { service: ServiceType::MySQL, port: 3307, started_at: SystemTime::now() },
+            RunningService { service: ServiceType::Caddy, port: 8080, started_at: SystemTime::now() },
+        ];
+
+        let items = check_ports_and_files(&running, &settings, None);
+        assert_eq!(items, vec![DriftItem::new(ServiceType::MySQL, "port", 3310, 3307)]);
+    }
+}
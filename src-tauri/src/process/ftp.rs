@@ -0,0 +1,183 @@
+//! SFTP and FTP access to the projects directory
+//!
+//! For tools and courses that deploy over FTP. SFTPGo serves the projects
+//! directory to a single account, SFTP and plain FTP side by side. Like
+//! Memcached the binary comes from a package manager: the path in settings,
+//! or `sftpgo` on PATH and in the usual install locations. The account is
+//! CAMPP's: its password is generated on first start and kept in
+//! `config/sftpgo/credentials.json`, and can be regenerated.
+//!
+//! SFTPGo runs `serve` with a generated config directory rather than
+//! `portable`, which only takes the password on the command line, where the
+//! process list shows it. `sftpgo.json` binds both servers to loopback
+//! explicitly and turns the web admin off, the account is loaded from
+//! `users.json` by the in-memory data provider. Both files are private to
+//! the user.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// SFTP and FTP ports and the SFTPGo binary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtpSettings {
+    /// SFTP port, also the service port
+    #[serde(default = "default_sftp_port")]
+    pub sftp_port: u16,
+    /// Plain FTP port
+    #[serde(default = "default_ftp_port")]
+    pub ftp_port: u16,
+    /// sftpgo binary, looked up on PATH when unset
+    #[serde(default)]
+    pub binary: Option<PathBuf>,
+}
+
+impl Default for FtpSettings {
+    fn default() -> Self {
+        Self { sftp_port: default_sftp_port(), ftp_port: default_ftp_port(), binary: None }
+    }
+}
+
+fn default_sftp_port() -> u16 {
+    crate::process::ServiceType::Ftp.default_port()
+}
+
+fn default_ftp_port() -> u16 { 2121 }
+
+/// The account projects are uploaded with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtpCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// State of the SFTP/FTP server for the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct FtpStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub sftp_port: u16,
+    pub ftp_port: u16,
+    /// Directory the account sees as `/`
+    pub root: String,
+    /// None until the server first starts
+    pub credentials: Option<FtpCredentials>,
+}
+
+/// Binary started for the server: the one from settings, or the first found
+pub fn binary(settings: &FtpSettings) -> PathBuf {
+    settings.binary.clone()
+        .or_else(|| crate::runtime::system::candidates(&["sftpgo"]).into_iter().next())
+        .unwrap_or_else(|| PathBuf::from(if cfg!(windows) { "sftpgo.exe" } else { "sftpgo" }))
+}
+
+/// SFTPGo's directory in the config directory, with its host keys
+pub fn sftpgo_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("sftpgo")
+}
+
+fn credentials_path(sftpgo_dir: &Path) -> PathBuf {
+    sftpgo_dir.join("credentials.json")
+}
+
+/// Credentials in `sftpgo_dir`, if they were generated
+pub fn read_credentials(sftpgo_dir: &Path) -> Option<FtpCredentials> {
+    let content = fs::read_to_string(credentials_path(sftpgo_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Credentials in `sftpgo_dir`, generated and saved when there are none
+pub fn ensure_credentials(sftpgo_dir: &Path) -> Result<FtpCredentials, String> {
+    match read_credentials(sftpgo_dir) {
+        Some(credentials) => Ok(credentials),
+        None => reset_credentials(sftpgo_dir),
+    }
+}
+
+/// Replace the password with a new one
+pub fn reset_credentials(sftpgo_dir: &Path) -> Result<FtpCredentials, String> {
+    let credentials = FtpCredentials { username: "campp".to_string(), password: super::random_secret(24) };
+    let json = serde_json::to_string_pretty(&credentials).map_err(|e| e.to_string())?;
+    super::write_private_file(&credentials_path(sftpgo_dir), &json)?;
+    Ok(credentials)
+}
+
+/// Write `sftpgo.json` and `users.json` to `sftpgo_dir`, serving `root` to
+/// the account with every permission
+pub fn write_config(sftpgo_dir: &Path, settings: &FtpSettings, root: &Path, credentials: &FtpCredentials) -> Result<(), String> {
+    let users_path = sftpgo_dir.join("users.json");
+    let users = serde_json::json!({
+        "users": [{
+            "status": 1,
+            "username": credentials.username,
+            "password": credentials.password,
+            "home_dir": root,
+            "permissions": { "/": ["*"] },
+        }],
+    });
+    let config = serde_json::json!({
+        "sftpd": { "bindings": [{ "address": "127.0.0.1", "port": settings.sftp_port }] },
+        "ftpd": { "bindings": [{ "address": "127.0.0.1", "port": settings.ftp_port }] },
+        "httpd": { "bindings": [] },
+        "webdavd": { "bindings": [] },
+        "telemetry": { "bind_port": 0 },
+        "data_provider": { "driver": "memory", "name": users_path, "create_default_admin": false },
+    });
+    for (path, value) in [(&users_path, users), (&sftpgo_dir.join("sftpgo.json"), config)] {
+        let json = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+        super::write_private_file(path, &json)?;
+    }
+    Ok(())
+}
+
+/// Command-line arguments of `sftpgo serve` with the config written by
+/// [`write_config`]
+pub fn command_args(sftpgo_dir: &Path, log_file: &Path) -> Vec<String> {
+    vec![
+        "serve".into(),
+        "--config-dir".into(),
+        sftpgo_dir.to_string_lossy().to_string(),
+        "--log-file-path".into(),
+        log_file.to_string_lossy().to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let args = command_args(Path::new("/opt/campp/config/sftpgo"), Path::new("/var/log/ftp.log"));
+        assert_eq!(args.join(" "), "serve --config-dir /opt/campp/config/sftpgo --log-file-path /var/log/ftp.log");
+    }
+
+    #[test]
+    fn test_write_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = sftpgo_dir(temp_dir.path());
+        let credentials = FtpCredentials { username: "campp".into(), password: "secret".into() };
+        write_config(&dir, &FtpSettings::default(), Path::new("/srv/projects"), &credentials).unwrap();
+
+        let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.join("sftpgo.json")).unwrap()).unwrap();
+        assert_eq!(config["sftpd"]["bindings"][0], serde_json::json!({ "address": "127.0.0.1", "port": 2022 }));
+        assert_eq!(config["ftpd"]["bindings"][0], serde_json::json!({ "address": "127.0.0.1", "port": 2121 }));
+        assert_eq!(config["httpd"]["bindings"], serde_json::json!([]));
+        let users: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.join("users.json")).unwrap()).unwrap();
+        assert_eq!(users["users"][0]["password"], "secret");
+        assert_eq!(users["users"][0]["home_dir"], "/srv/projects");
+    }
+
+    #[test]
+    fn test_credentials() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = sftpgo_dir(temp_dir.path());
+        assert_eq!(read_credentials(&dir), None);
+
+        let credentials = ensure_credentials(&dir).unwrap();
+        assert_eq!(ensure_credentials(&dir).unwrap(), credentials);
+        let reset = reset_credentials(&dir).unwrap();
+        assert_ne!(reset.password, credentials.password);
+        assert_eq!(read_credentials(&dir), Some(reset));
+    }
+}
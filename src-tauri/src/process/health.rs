@@ -0,0 +1,292 @@
+//! Health checks
+//!
+//! Runs every readiness probe in one pass for a "Run diagnostics" button:
+//! binaries present, ports listening, Caddy answering HTTP, PHP answering a
+//! FastCGI handshake, the databases answering a ping and free disk space.
+//! Probes of services that are not running are reported as skipped.
+
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::{ServiceMap, ServiceState, ServiceType};
+use crate::config::AppSettings;
+use crate::runtime::locator::RuntimePaths;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Below this much free space services may fail to write, the check fails
+const MIN_FREE_BYTES: u64 = 100 * 1024 * 1024;
+/// Below this much free space the check warns
+const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    /// Probe kind: "binary", "port", "http", "fastcgi", "ping" or "disk"
+    pub name: String,
+    pub service: Option<ServiceType>,
+    pub status: CheckStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+    /// No check failed, warnings and skipped checks are fine
+    pub healthy: bool,
+}
+
+/// State the checks run against, taken from the process manager
+pub struct HealthContext {
+    pub paths: Option<RuntimePaths>,
+    pub settings: AppSettings,
+    pub services: ServiceMap,
+    /// Directory whose volume is checked for free space
+    pub data_dir: PathBuf,
+}
+
+/// Binary started for `service`
+pub fn service_binary(paths: &RuntimePaths, settings: &AppSettings, service: ServiceType) -> PathBuf {
+    match service {
+        ServiceType::Caddy => paths.caddy.clone(),
+        ServiceType::PhpFpm => paths.php_cgi.clone(),
+        ServiceType::MySQL => paths.mysql.clone(),
+        ServiceType::PostgreSQL => paths.pgsql_dir.join("bin").join(if cfg!(windows) { "postgres.exe" } else { "postgres" }),
+        ServiceType::Tunnel => crate::process::tunnel::binary(&settings.tunnel, &paths.cloudflared),
+        ServiceType::Mailpit => paths.mailpit.clone(),
+        ServiceType::Memcached => crate::process::memcached::binary(&settings.memcached),
+        ServiceType::Minio => paths.minio.clone(),
+        ServiceType::Ftp => crate::process::ftp::binary(&settings.ftp),
+    }
+}
+
+/// Run every check, blocking for up to a few seconds per probe
+pub fn run_checks(ctx: &HealthContext) -> HealthReport {
+    let mut checks = Vec::new();
+    let enabled: Vec<ServiceType> = ServiceType::start_order()
+        .into_iter()
+        .filter(|s| ctx.settings.is_enabled(*s))
+        .collect();
+
+    for &service in &enabled {
+        checks.push(timed("binary", Some(service), || match ctx.paths {
+            None => (CheckStatus::Fail, "Runtime binaries not located, is the runtime installed?".to_string()),
+            Some(ref paths) => {
+                let binary = service_binary(paths, &ctx.settings, service);
+                if binary.exists() {
+                    (CheckStatus::Pass, format!("Found {}", binary.display()))
+                } else {
+                    (CheckStatus::Fail, format!("Binary not found: {}", binary.display()))
+                }
+            }
+        }));
+    }
+
+    for &service in &enabled {
+        checks.push(running_check(ctx, "port", service, |port| {
+            if crate::config::is_port_in_use(port) {
+                Ok(format!("Listening on port {}", port))
+            } else {
+                Err(format!("Nothing is listening on port {}", port))
+            }
+        }));
+    }
+
+    checks.push(running_check(ctx, "http", ServiceType::Caddy, |port| {
+        match http_status(port)? {
+            status @ 200..=399 => Ok(format!("GET / returned {}", status)),
+            status => Err(format!("GET / returned {}", status)),
+        }
+    }));
+    checks.push(running_check(ctx, "fastcgi", ServiceType::PhpFpm, |port| {
+        fastcgi_handshake(port).map(|_| "FastCGI handshake succeeded".to_string())
+    }));
+    checks.push(running_check(ctx, "ping", ServiceType::MySQL, |port| {
+        let paths = ctx.paths.as_ref().ok_or("Runtime paths not initialized")?;
+        crate::database::mysql::ping(paths, port, &ctx.settings.mysql_root_password)
+            .map(|_| "mysqladmin ping succeeded".to_string())
+    }));
+    checks.push(running_check(ctx, "ping", ServiceType::PostgreSQL, |port| {
+        let paths = ctx.paths.as_ref().ok_or("Runtime paths not initialized")?;
+        crate::database::postgres::ping(&paths.pgsql_dir, port)
+            .map(|_| "pg_isready succeeded".to_string())
+    }));
+
+    checks.push(timed("disk", None, || disk_check(&ctx.data_dir)));
+
+    let healthy = !checks.iter().any(|c| c.status == CheckStatus::Fail);
+    HealthReport { checks, healthy }
+}
+
+fn timed(name: &str, service: Option<ServiceType>, check: impl FnOnce() -> (CheckStatus, String)) -> HealthCheck {
+    let begin = Instant::now();
+    let (status, message) = check();
+    HealthCheck {
+        name: name.to_string(),
+        service,
+        status,
+        message,
+        duration_ms: begin.elapsed().as_millis() as u64,
+    }
+}
+
+/// Probe the port of `service`, or skip when it is not running or disabled
+fn running_check(
+    ctx: &HealthContext,
+    name: &str,
+    service: ServiceType,
+    probe: impl FnOnce(u16) -> Result<String, String>,
+) -> HealthCheck {
+    timed(name, Some(service), || {
+        let info = ctx.services.get(&service);
+        match info {
+            Some(info) if info.state == ServiceState::Running => match probe(info.port) {
+                Ok(message) => (CheckStatus::Pass, message),
+                Err(message) => (CheckStatus::Fail, message),
+            },
+            _ if !ctx.settings.is_enabled(service) => (CheckStatus::Skipped, format!("{} is disabled", service.display_name())),
+            _ => (CheckStatus::Skipped, format!("{} is not running", service.display_name())),
+        }
+    })
+}
+
+fn disk_check(dir: &Path) -> (CheckStatus, String) {
+    match crate::runtime::disk_usage::free_space(dir) {
+        Ok(free) => {
+            let message = format!("{} MB free on the data volume", free / 1024 / 1024);
+            let status = match free {
+                free if free < MIN_FREE_BYTES => CheckStatus::Fail,
+                free if free < LOW_FREE_BYTES => CheckStatus::Warn,
+                _ => CheckStatus::Pass,
+            };
+            (status, message)
+        }
+        Err(e) => (CheckStatus::Warn, e),
+    }
+}
+
+fn connect(port: u16) -> Result<TcpStream, String> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let stream = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT)
+        .map_err(|e| format!("Failed to connect to port {}: {}", port, e))?;
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PROBE_TIMEOUT));
+    Ok(stream)
+}
+
+/// Status code of `GET /` on the local web server
+fn http_status(port: u16) -> Result<u16, String> {
+    let mut stream = connect(port)?;
+    stream
+        .write_all(b"GET / HTTP/1.0\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut response = [0u8; 64];
+    let mut len = 0;
+    while len < response.len() {
+        match stream.read(&mut response[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) => return Err(format!("No response: {}", e)),
+        }
+        if response[..len].contains(&b'\n') {
+            break;
+        }
+    }
+
+    // "HTTP/1.1 200 OK"
+    String::from_utf8_lossy(&response[..len])
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| "Response is not HTTP".to_string())
+}
+
+/// Send a FastCGI `GET_VALUES` record and expect a `GET_VALUES_RESULT` back
+///
+/// Both php-fpm and php-cgi answer management records without running a script.
+fn fastcgi_handshake(port: u16) -> Result<(), String> {
+    const FCGI_VERSION_1: u8 = 1;
+    const FCGI_GET_VALUES: u8 = 9;
+    const FCGI_GET_VALUES_RESULT: u8 = 10;
+
+    // One name-value pair with an empty value
+    let name = b"FCGI_MPXS_CONNS";
+    let mut content = vec![name.len() as u8, 0];
+    content.extend_from_slice(name);
+    let mut record = vec![FCGI_VERSION_1, FCGI_GET_VALUES, 0, 0, 0, content.len() as u8, 0, 0];
+    record.extend_from_slice(&content);
+
+    let mut stream = connect(port)?;
+    stream.write_all(&record).map_err(|e| format!("Failed to send FastCGI record: {}", e))?;
+    let mut header = [0u8; 8];
+    stream.read_exact(&mut header).map_err(|e| format!("No FastCGI response: {}", e))?;
+    if header[0] != FCGI_VERSION_1 || header[1] != FCGI_GET_VALUES_RESULT {
+        return Err(format!("Unexpected FastCGI record type {}", header[1]));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ServiceInfo;
+    use std::net::TcpListener;
+
+    /// Accept one connection, read a request and write `response`
+    fn serve_once(response: &'static [u8]) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 256];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(response);
+        });
+        port
+    }
+
+    #[test]
+    fn test_probes() {
+        let port = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+        assert_eq!(http_status(port).unwrap(), 200);
+
+        let port = serve_once(&[1, 10, 0, 0, 0, 0, 0, 0]);
+        assert!(fastcgi_handshake(port).is_ok());
+        let port = serve_once(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+        assert!(fastcgi_handshake(port).is_err());
+    }
+
+    #[test]
+    fn test_run_checks_skips_stopped_services() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut services = ServiceMap::new();
+        for service in ServiceType::start_order() {
+            services.insert(service, ServiceInfo::new(service));
+        }
+        let ctx = HealthContext {
+            paths: None,
+            settings: AppSettings::default(),
+            services,
+            data_dir: temp_dir.path().to_path_buf(),
+        };
+
+        let report = run_checks(&ctx);
+        assert!(!report.healthy);
+        assert!(report.checks.iter().filter(|c| c.name == "binary").all(|c| c.status == CheckStatus::Fail));
+        assert!(report.checks.iter().filter(|c| c.name == "port").all(|c| c.status == CheckStatus::Skipped));
+        assert!(report.checks.iter().any(|c| c.name == "disk"));
+    }
+}
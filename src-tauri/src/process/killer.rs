@@ -31,3 +31,51 @@ pub fn kill_existing_processes(process_name: &str) {
         std::thread::sleep(std::time::Duration::from_millis(500));
     }
 }
+
+/// Forcefully terminate the process `pid`
+pub fn kill_pid(pid: u32) -> Result<(), String> {
+    #[cfg(windows)]
+    let output = Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .output();
+
+    #[cfg(unix)]
+    let output = Command::new("kill")
+        .args(["-9", &pid.to_string()])
+        .output();
+
+    let output = output.map_err(|e| format!("Failed to kill process {}: {}", pid, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to kill process {}: {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Terminate the process `pid` with the processes it started
+///
+/// On Unix `pid` must lead its own process group.
+pub fn kill_tree(pid: u32) -> Result<(), String> {
+    #[cfg(windows)]
+    let output = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .output();
+
+    #[cfg(unix)]
+    let output = Command::new("kill")
+        .args(["-TERM", "--", &format!("-{}", pid)])
+        .output();
+
+    let output = output.map_err(|e| format!("Failed to stop process {}: {}", pid, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to stop process {}: {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
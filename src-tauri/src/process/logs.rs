@@ -0,0 +1,474 @@
+//! Service log files
+//!
+//! Each service writes stdout and stderr to one file in the logs directory.
+//! Tailing reads the end of that file and, when following, watches the logs
+//! directory and hands every newly written line to a callback until stopped.
+//! A file that shrinks was truncated or rotated and is read again from the
+//! start.
+
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use super::ServiceType;
+
+/// Lines returned when a tail starts
+pub const TAIL_LINES: usize = 200;
+
+/// How often a followed file is checked when no change event arrives,
+/// Windows does not report writes by other processes right away
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Followers currently running, one per service
+static FOLLOWERS: Mutex<Vec<(ServiceType, Arc<AtomicBool>)>> = Mutex::new(Vec::new());
+
+/// Emitted as "log-line" for every line a followed service writes
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub service: ServiceType,
+    pub line: String,
+}
+
+/// Name of the file `service` logs to
+pub fn log_file_name(service: ServiceType) -> &'static str {
+    match service {
+        ServiceType::Caddy => "caddy.log",
+        ServiceType::PhpFpm => "php-fpm.log",
+        ServiceType::MySQL => "mysql.log",
+        ServiceType::PostgreSQL => "postgresql.log",
+        ServiceType::Tunnel => "tunnel.log",
+        ServiceType::Mailpit => "mailpit.log",
+        ServiceType::Memcached => "memcached.log",
+        ServiceType::Minio => "minio.log",
+        ServiceType::Ftp => "ftp.log",
+    }
+}
+
+pub fn log_path(logs_dir: &Path, service: ServiceType) -> PathBuf {
+    logs_dir.join(log_file_name(service))
+}
+
+/// Last `count` lines of the file at `path`, empty if it does not exist
+pub fn last_lines(path: &Path, count: usize) -> Result<Vec<String>, String> {
+    const CHUNK: u64 = 8 * 1024;
+
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e)),
+    };
+    let len = file.metadata().map_err(|e| format!("Failed to read {}: {}", path.display(), e))?.len();
+
+    // Read backwards until the buffer holds enough lines
+    let mut start = len;
+    let mut buf = Vec::new();
+    while start > 0 && buf.iter().filter(|b| **b == b'\n').count() <= count {
+        let read_from = start.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (start - read_from) as usize];
+        file.seek(SeekFrom::Start(read_from))
+            .and_then(|_| file.read_exact(&mut chunk))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+        start = read_from;
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<&str> = text.lines().collect();
+    // The first line is cut off unless the file start was reached
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let first = lines.len().saturating_sub(count);
+    Ok(lines[first..].iter().map(|l| l.trim_end_matches('\r').to_string()).collect())
+}
+
+/// Largest number of bytes a `read_page` call returns or scans for matches
+pub const MAX_PAGE_BYTES: u64 = 1024 * 1024;
+const MAX_SCAN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Severity detected in a log line, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Level of a Caddy JSON line, a MariaDB `[Warning]` tag, a PHP
+    /// `PHP Fatal error:` prefix or a PostgreSQL `ERROR:` prefix
+    pub fn detect(line: &str, json: Option<&serde_json::Value>) -> Option<Self> {
+        if let Some(level) = json.and_then(|j| j.get("level")).and_then(|l| l.as_str()) {
+            return Self::from_name(level);
+        }
+        let upper = line.to_ascii_uppercase();
+        const MARKERS: &[(&str, LogLevel)] = &[
+            ("FATAL", LogLevel::Error),
+            ("PANIC", LogLevel::Error),
+            ("ERROR", LogLevel::Error),
+            ("WARN", LogLevel::Warn),
+            ("NOTICE", LogLevel::Info),
+            ("[NOTE]", LogLevel::Info),
+            ("INFO", LogLevel::Info),
+            ("LOG:", LogLevel::Info),
+            ("DEBUG", LogLevel::Debug),
+        ];
+        MARKERS.iter().find(|(marker, _)| upper.contains(marker)).map(|(_, level)| *level)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" | "notice" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" | "fatal" | "panic" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Which lines of a log `read_page` returns
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogQuery {
+    /// Matching lines to return, counted back from `before`
+    pub lines: Option<usize>,
+    /// Byte offset to read back from, the file end when unset. Pass the
+    /// `start` of the previous page to load older lines.
+    pub before: Option<u64>,
+    /// Read this byte range instead of the last lines
+    pub range: Option<(u64, u64)>,
+    /// Only lines at this level or above; lines without a level are dropped
+    pub min_level: Option<LogLevel>,
+    /// Only lines containing this text, case-insensitive
+    pub search: Option<String>,
+}
+
+impl LogQuery {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if let Some(min) = self.min_level {
+            if entry.level.is_none_or(|level| level < min) {
+                return false;
+            }
+        }
+        match &self.search {
+            Some(search) if !search.is_empty() => entry.text.to_lowercase().contains(&search.to_lowercase()),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Byte offset of the line in the file
+    pub offset: u64,
+    pub text: String,
+    pub level: Option<LogLevel>,
+    /// The line parsed, for JSON logs such as Caddy's access log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<serde_json::Value>,
+}
+
+impl LogEntry {
+    fn parse(offset: u64, text: &str) -> Self {
+        let text = text.trim_end_matches('\r').to_string();
+        let json = if text.starts_with('{') {
+            serde_json::from_str::<serde_json::Value>(&text).ok().filter(|j| j.is_object())
+        } else {
+            None
+        };
+        let level = LogLevel::detect(&text, json.as_ref());
+        Self { offset, text, level, json }
+    }
+}
+
+/// A slice of a log file returned by `read_page`
+#[derive(Debug, Clone, Serialize)]
+pub struct LogPage {
+    pub entries: Vec<LogEntry>,
+    /// Byte range that was scanned, `start` is 0 once the file start is reached
+    pub start: u64,
+    pub end: u64,
+    /// Size of the whole file
+    pub size: u64,
+}
+
+/// Read a page of the log at `path` without loading the whole file
+///
+/// With a byte range, the complete lines inside it are returned. Otherwise
+/// the file is scanned backwards from `before` until enough lines match the
+/// filters, or `MAX_SCAN_BYTES` were read.
+pub fn read_page(path: &Path, query: &LogQuery) -> Result<LogPage, String> {
+    const CHUNK: u64 = 64 * 1024;
+
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(LogPage { entries: Vec::new(), start: 0, end: 0, size: 0 });
+        }
+        Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e)),
+    };
+    let size = file.metadata().map_err(|e| format!("Failed to read {}: {}", path.display(), e))?.len();
+    let mut read_at = |from: u64, to: u64| -> Result<Vec<u8>, String> {
+        let mut buf = vec![0; (to - from) as usize];
+        file.seek(SeekFrom::Start(from))
+            .and_then(|_| file.read_exact(&mut buf))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(buf)
+    };
+
+    if let Some((from, to)) = query.range {
+        let to = to.min(size).min(from.saturating_add(MAX_PAGE_BYTES));
+        if from >= to {
+            return Ok(LogPage { entries: Vec::new(), start: from.min(size), end: from.min(size), size });
+        }
+        // Keep whole lines: skip the one cut at the start, drop the one cut at the end
+        let lead = if from > 0 { read_at(from - 1, from)?[0] } else { b'\n' };
+        let buf = read_at(from, to)?;
+        let first = if lead == b'\n' { 0 } else { buf.iter().position(|b| *b == b'\n').map_or(buf.len(), |p| p + 1) };
+        let last = if to == size { buf.len() } else { buf.iter().rposition(|b| *b == b'\n').map_or(first, |p| p + 1).max(first) };
+
+        let entries = split_entries(&buf[first..last], from + first as u64)
+            .into_iter()
+            .filter(|e| query.matches(e))
+            .collect();
+        return Ok(LogPage { entries, start: from + first as u64, end: from + last as u64, size });
+    }
+
+    let wanted = query.lines.unwrap_or(TAIL_LINES);
+    let end = query.before.unwrap_or(size).min(size);
+    let mut start = end;
+    let mut buf: Vec<u8> = Vec::new();
+    let mut entries: Vec<LogEntry> = Vec::new();
+    while start > 0 && entries.len() < wanted && end - start < MAX_SCAN_BYTES {
+        let from = start.saturating_sub(CHUNK);
+        let mut chunk = read_at(from, start)?;
+        chunk.extend_from_slice(&buf);
+        start = from;
+
+        // The first line is cut off unless the file start was reached
+        let cut = if start > 0 { chunk.iter().position(|b| *b == b'\n').map_or(chunk.len(), |p| p + 1) } else { 0 };
+        let found: Vec<LogEntry> = split_entries(&chunk[cut..], start + cut as u64)
+            .into_iter()
+            .filter(|e| query.matches(e))
+            .collect();
+        buf = chunk[..cut].to_vec();
+        entries.splice(0..0, found);
+    }
+    let start = start + buf.len() as u64;
+
+    let skip = entries.len().saturating_sub(wanted);
+    let entries = entries.split_off(skip);
+    // Older lines that matched are left for the next page
+    let start = entries.first().filter(|_| skip > 0).map_or(start, |e| e.offset);
+    Ok(LogPage { entries, start, end, size })
+}
+
+fn split_entries(bytes: &[u8], offset: u64) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+    let mut line_start = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        if *b == b'\n' {
+            let text = String::from_utf8_lossy(&bytes[line_start..i]);
+            entries.push(LogEntry::parse(offset + line_start as u64, &text));
+            line_start = i + 1;
+        }
+    }
+    if line_start < bytes.len() {
+        let text = String::from_utf8_lossy(&bytes[line_start..]);
+        entries.push(LogEntry::parse(offset + line_start as u64, &text));
+    }
+    entries
+}
+
+/// Position in a followed file and the unfinished last line
+#[derive(Debug, Default)]
+pub struct LogCursor {
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl LogCursor {
+    /// Start after the current end of `path`
+    pub fn at_end(path: &Path) -> Self {
+        let offset = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        Self { offset, partial: Vec::new() }
+    }
+
+    /// Complete lines written since the last call
+    pub fn read_new(&mut self, path: &Path) -> std::io::Result<Vec<String>> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            // Truncated or replaced by rotation
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut data = std::mem::take(&mut self.partial);
+        let read = file.take(len - self.offset).read_to_end(&mut data)?;
+        self.offset += read as u64;
+
+        let complete = match data.iter().rposition(|b| *b == b'\n') {
+            Some(pos) => pos + 1,
+            None => 0,
+        };
+        self.partial = data.split_off(complete);
+        Ok(String::from_utf8_lossy(&data)
+            .lines()
+            .map(|l| l.trim_end_matches('\r').to_string())
+            .collect())
+    }
+}
+
+/// Follow the log of `service` on a dedicated thread, calling `on_line` for
+/// every new line until `stop_following` is called
+///
+/// A service that is already followed keeps its existing follower.
+pub fn follow(logs_dir: &Path, service: ServiceType, on_line: impl Fn(LogLine) + Send + 'static) -> Result<(), String> {
+    let stop = {
+        let mut followers = FOLLOWERS.lock().map_err(|e| format!("Failed to lock log followers: {}", e))?;
+        if followers.iter().any(|(s, _)| *s == service) {
+            return Ok(());
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        followers.push((service, stop.clone()));
+        stop
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let watcher = notify::recommended_watcher(tx)
+        .and_then(|mut watcher| watcher.watch(logs_dir, RecursiveMode::NonRecursive).map(|_| watcher));
+    let watcher = match watcher {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            tracing::warn!("Log watcher unavailable, polling {} instead: {}", log_file_name(service), e);
+            None
+        }
+    };
+
+    let path = log_path(logs_dir, service);
+    std::thread::spawn(move || {
+        // Dropped with the thread, which ends the watch
+        let _watcher = watcher;
+        let mut cursor = LogCursor::at_end(&path);
+        while !stop.load(Ordering::Relaxed) {
+            // Any event or a timeout means the file may have grown
+            if let Err(mpsc::RecvTimeoutError::Disconnected) = rx.recv_timeout(POLL_INTERVAL) {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            while rx.try_recv().is_ok() {}
+
+            match cursor.read_new(&path) {
+                Ok(lines) => lines.into_iter().for_each(|line| on_line(LogLine { service, line })),
+                Err(e) => tracing::debug!("Failed to read {}: {}", path.display(), e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Stop following the log of `service`, if it is followed
+pub fn stop_following(service: ServiceType) {
+    if let Ok(mut followers) = FOLLOWERS.lock() {
+        followers.retain(|(s, stop)| {
+            if *s == service {
+                stop.store(true, Ordering::Relaxed);
+            }
+            *s != service
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_last_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("caddy.log");
+        assert!(last_lines(&path, 10).unwrap().is_empty());
+
+        let content: String = (1..=5000).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(&path, content).unwrap();
+        assert_eq!(last_lines(&path, 3).unwrap(), vec!["line 4998", "line 4999", "line 5000"]);
+        assert_eq!(last_lines(&path, 10_000).unwrap().len(), 5000);
+    }
+
+    #[test]
+    fn test_read_page_pages_back_with_filters() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mysql.log");
+        let content: String = (1..=30_000)
+            .map(|i| if i % 1000 == 0 { format!("[ERROR] failure {}\n", i) } else { format!("[Note] line {}\n", i) })
+            .collect();
+        std::fs::write(&path, &content).unwrap();
+
+        let page = read_page(&path, &LogQuery { lines: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(page.entries.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(), vec!["[Note] line 29999", "[ERROR] failure 30000"]);
+        assert_eq!(page.end, content.len() as u64);
+
+        let query = LogQuery { lines: Some(3), min_level: Some(LogLevel::Warn), ..Default::default() };
+        let page = read_page(&path, &query).unwrap();
+        let texts: Vec<&str> = page.entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["[ERROR] failure 28000", "[ERROR] failure 29000", "[ERROR] failure 30000"]);
+
+        let older = read_page(&path, &LogQuery { before: Some(page.start), ..query }).unwrap();
+        assert_eq!(older.entries.last().unwrap().text, "[ERROR] failure 27000");
+
+        let search = LogQuery { search: Some("LINE 12345".to_string()), lines: Some(5), ..Default::default() };
+        assert_eq!(read_page(&path, &search).unwrap().entries.len(), 1);
+    }
+
+    #[test]
+    fn test_read_page_byte_range_and_json() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("caddy-access.log");
+        std::fs::write(&path, "{\"level\":\"info\",\"status\":200}\n{\"level\":\"error\",\"status\":502}\n").unwrap();
+
+        // Starts mid-line, so only the second line is complete
+        let page = read_page(&path, &LogQuery { range: Some((5, 1000)), ..Default::default() }).unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].level, Some(LogLevel::Error));
+        assert_eq!(page.entries[0].json.as_ref().unwrap()["status"], 502);
+        assert_eq!(page.start, page.entries[0].offset);
+    }
+
+    #[test]
+    fn test_log_cursor_reads_new_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("php-fpm.log");
+        std::fs::write(&path, "old\n").unwrap();
+
+        let mut cursor = LogCursor::at_end(&path);
+        assert!(cursor.read_new(&path).unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "first\nsec").unwrap();
+        assert_eq!(cursor.read_new(&path).unwrap(), vec!["first"]);
+        write!(file, "ond\r\n").unwrap();
+        assert_eq!(cursor.read_new(&path).unwrap(), vec!["second"]);
+
+        // Truncation starts over
+        std::fs::write(&path, "fresh\n").unwrap();
+        assert_eq!(cursor.read_new(&path).unwrap(), vec!["fresh"]);
+    }
+}
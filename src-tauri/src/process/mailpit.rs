@@ -0,0 +1,102 @@
+//! Mailpit, the local SMTP catcher
+//!
+//! Mail sent by projects is caught instead of delivered and shown in
+//! Mailpit's web UI. php.ini points `mail()` at it while the service is
+//! enabled: through `mailpit sendmail` on macOS and Linux, and the built-in
+//! SMTP client on Windows, where `sendmail_path` would bypass it. Framework
+//! mailers use the SMTP port directly. Both ports are bound to loopback, and
+//! caught mail is kept in a temporary database that Mailpit removes on exit.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Mailpit ports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailpitSettings {
+    /// SMTP port mail is sent to, also the service port
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    /// Port of the web UI
+    #[serde(default = "default_ui_port")]
+    pub ui_port: u16,
+}
+
+impl Default for MailpitSettings {
+    fn default() -> Self {
+        Self { smtp_port: default_smtp_port(), ui_port: default_ui_port() }
+    }
+}
+
+fn default_smtp_port() -> u16 {
+    crate::process::ServiceType::Mailpit.default_port()
+}
+
+fn default_ui_port() -> u16 { 8025 }
+
+impl MailpitSettings {
+    /// Address of the web UI
+    pub fn ui_url(&self) -> String {
+        format!("http://127.0.0.1:{}/", self.ui_port)
+    }
+}
+
+/// State of Mailpit for the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct MailpitStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub smtp_port: u16,
+    pub ui_url: String,
+}
+
+/// Command-line arguments of Mailpit
+///
+/// Any SMTP credentials are accepted, so mailers configured with a username
+/// and password work unchanged.
+pub fn command_args(settings: &MailpitSettings) -> Vec<String> {
+    vec![
+        "--smtp".into(),
+        format!("127.0.0.1:{}", settings.smtp_port),
+        "--listen".into(),
+        format!("127.0.0.1:{}", settings.ui_port),
+        "--smtp-auth-accept-any".into(),
+        "--smtp-auth-allow-insecure".into(),
+    ]
+}
+
+/// php.ini directives sending `mail()` to Mailpit
+pub fn php_ini_mail(binary: &Path, settings: &MailpitSettings) -> String {
+    let mut ini = format!("\n; Mail - caught by Mailpit, open {} to read it\n", settings.ui_url());
+    if cfg!(windows) {
+        ini.push_str(&format!("SMTP = 127.0.0.1\nsmtp_port = {}\n", settings.smtp_port));
+    } else {
+        ini.push_str(&format!(
+            "sendmail_path = \"'{}' sendmail -S 127.0.0.1:{}\"\n",
+            binary.to_string_lossy(), settings.smtp_port
+        ));
+    }
+    ini
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let settings = MailpitSettings::default();
+        assert_eq!(
+            command_args(&settings)[..4].join(" "),
+            "--smtp 127.0.0.1:1025 --listen 127.0.0.1:8025"
+        );
+        assert_eq!(settings.ui_url(), "http://127.0.0.1:8025/");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_php_ini_mail() {
+        let settings = MailpitSettings { smtp_port: 2025, ui_port: 8025 };
+        let ini = php_ini_mail(Path::new("/opt/campp/runtime/mailpit/mailpit"), &settings);
+        assert!(ini.contains("sendmail_path = \"'/opt/campp/runtime/mailpit/mailpit' sendmail -S 127.0.0.1:2025\"\n"));
+    }
+}
@@ -1,13 +1,18 @@
-use super::{ServiceInfo, ServiceMap, ServiceState, ServiceType};
+use super::{RestartStrategy, ServiceInfo, ServiceMap, ServiceState, ServiceStateChange, ServiceType};
 use crate::runtime::locator::{locate_runtime_binaries, RuntimePaths};
 use std::collections::HashMap;
 use std::process::{Child, Command, Stdio};
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const DEFAULT_INDEX_PHP: &str = r#"<?php phpinfo(); ?>"#;
 
+/// How long a graceful restart waits for the process to exit on its own
+const GRACEFUL_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
 // Windows-specific: Constant to hide console window
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -32,9 +37,10 @@ fn open_log_file_with_retry(log_path: &PathBuf, service_name: &str) -> Result<Fi
 
     for attempt in 0..max_retries {
         // Try to open the file, truncating if it exists (for fresh logs)
-        // On subsequent retries, try to append in case another process has it open
+        // On subsequent retries, try to append in case another process has it open.
+        // Either way the handle appends, so rotation can truncate the file under it
         let result = if attempt == 0 {
-            File::create(log_path)
+            File::create(log_path).and_then(|_| OpenOptions::new().append(true).open(log_path))
         } else {
             OpenOptions::new()
                 .write(true)
@@ -65,6 +71,20 @@ fn open_log_file_with_retry(log_path: &PathBuf, service_name: &str) -> Result<Fi
     Err(format!("Failed to create {} log file: maximum retries exceeded", service_name))
 }
 
+/// Append the user's extra arguments from settings to a service command
+///
+/// They are written to the top of the service log so a misbehaving service
+/// can be traced back to a custom flag.
+fn apply_extra_args(cmd: &mut Command, service: ServiceType, settings: &crate::config::AppSettings, log_file: &mut File) -> Result<(), String> {
+    let args = settings.extra_args.checked(service)?;
+    if !args.is_empty() {
+        tracing::info!("Starting {} with custom arguments: {}", service.display_name(), args.join(" "));
+        let _ = writeln!(log_file, "[CAMPP] Custom arguments from settings: {}", args.join(" "));
+        cmd.args(args);
+    }
+    Ok(())
+}
+
 /// A running service process with its handle and configuration
 pub struct ServiceProcess {
     pub name: ServiceType,
@@ -77,6 +97,33 @@ pub struct ServiceProcess {
     pub error_message: Option<String>,
     /// Tracked PID for targeted process killing
     pid: Option<u32>,
+    /// Port and time of the last successful start, used for drift detection
+    started: Option<(u16, std::time::SystemTime)>,
+    /// Settings in effect in the running process, used to plan pending changes
+    applied: Option<crate::config::AppSettings>,
+}
+
+/// Receives every service state transition, e.g. to emit it to the frontend
+pub type StateEmitter = Arc<dyn Fn(ServiceStateChange) + Send + Sync>;
+
+impl ServiceProcess {
+    /// Move to `state`, reporting the transition with the current error message
+    ///
+    /// All state changes go through here so listeners never miss one.
+    fn set_state(&mut self, state: ServiceState, emitter: &Option<StateEmitter>) {
+        if self.state == state {
+            return;
+        }
+        let old = std::mem::replace(&mut self.state, state.clone());
+        if let Some(emit) = emitter {
+            emit(ServiceStateChange {
+                service: self.name,
+                old,
+                new: state,
+                error_message: self.error_message.clone(),
+            });
+        }
+    }
 }
 
 /// Process manager for CAMPP services
@@ -84,6 +131,63 @@ pub struct ProcessManager {
     services: HashMap<ServiceType, ServiceProcess>,
     runtime_paths: Option<RuntimePaths>,
     settings: crate::config::AppSettings,
+    emitter: Option<StateEmitter>,
+    /// Extra PHP versions, running while the PHP service runs
+    php_pool: super::php_pool::PhpPool,
+    /// Wildcard DNS for project domains, when enabled
+    dns: Option<super::dns::DnsServer>,
+    /// npm scripts of projects, running until stopped or CAMPP exits
+    npm_scripts: super::npm::NpmScripts,
+}
+
+/// Outcome of `ProcessManager::set_service_port`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PortChangeResult {
+    pub service: ServiceType,
+    pub old_port: u16,
+    pub new_port: u16,
+    /// Services that were restarted, in restart order
+    pub restarted: Vec<ServiceType>,
+    pub updated_env_files: Vec<String>,
+}
+
+/// Timing of a `restart_service` call, in milliseconds
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RestartReport {
+    pub service: ServiceType,
+    pub strategy: RestartStrategy,
+    pub stop_ms: u64,
+    pub start_ms: u64,
+    pub total_ms: u64,
+}
+
+/// Rewrite `DB_PORT=<old>` in each project's .env, returning the files changed
+fn update_project_env_ports(projects_dir: &Path, old_port: u16, new_port: u16) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(projects_dir) else { return Vec::new() };
+    let old_line = format!("DB_PORT={}", old_port);
+
+    let mut updated = Vec::new();
+    for entry in entries.flatten() {
+        let env_file = entry.path().join(".env");
+        let Ok(content) = fs::read_to_string(&env_file) else { continue };
+        if !content.lines().any(|l| l.trim() == old_line) {
+            continue;
+        }
+
+        let rewritten: Vec<String> = content.lines()
+            .map(|l| if l.trim() == old_line { format!("DB_PORT={}", new_port) } else { l.to_string() })
+            .collect();
+        let mut rewritten = rewritten.join("\n");
+        if content.ends_with('\n') {
+            rewritten.push('\n');
+        }
+
+        match fs::write(&env_file, rewritten) {
+            Ok(_) => updated.push(env_file.to_string_lossy().to_string()),
+            Err(e) => tracing::warn!("Failed to update {}: {}", env_file.display(), e),
+        }
+    }
+    updated
 }
 
 impl ProcessManager {
@@ -94,7 +198,7 @@ impl ProcessManager {
     pub fn with_settings(settings: crate::config::AppSettings) -> Self {
         let mut services = HashMap::new();
 
-        for service_type in [ServiceType::Caddy, ServiceType::PhpFpm, ServiceType::MySQL, ServiceType::PostgreSQL] {
+        for service_type in [ServiceType::Caddy, ServiceType::PhpFpm, ServiceType::MySQL, ServiceType::PostgreSQL, ServiceType::Tunnel, ServiceType::Mailpit, ServiceType::Memcached, ServiceType::Minio, ServiceType::Ftp] {
             services.insert(
                 service_type,
                 ServiceProcess {
@@ -105,21 +209,54 @@ impl ProcessManager {
                     log_file: None,
                     error_message: None,
                     pid: None,
+                    started: None,
+                    applied: None,
                 },
             );
         }
 
-        Self {
+        let mut manager = Self {
             services,
             runtime_paths: None,
             settings,
+            emitter: None,
+            php_pool: super::php_pool::PhpPool::default(),
+            dns: None,
+            npm_scripts: super::npm::NpmScripts::default(),
+        };
+        manager.sync_enabled();
+        manager
+    }
+
+    /// Mark stopped services that are disabled in settings as `Disabled`, and back
+    ///
+    /// A running service stays up until it is stopped.
+    fn sync_enabled(&mut self) {
+        for (service_type, service_process) in self.services.iter_mut() {
+            let enabled = self.settings.is_enabled(*service_type);
+            match service_process.state {
+                ServiceState::Stopped if !enabled => service_process.set_state(ServiceState::Disabled, &self.emitter),
+                ServiceState::Disabled if enabled => service_process.set_state(ServiceState::Stopped, &self.emitter),
+                _ => {}
+            }
         }
     }
 
+    /// Report every later state transition to `emitter`
+    pub fn set_emitter(&mut self, emitter: StateEmitter) {
+        self.emitter = Some(emitter);
+    }
+
     pub fn get_runtime_paths(&self) -> Option<RuntimePaths> {
         self.runtime_paths.clone()
     }
 
+    /// Forget the located binaries so the next start locates them again,
+    /// after components were installed or removed
+    pub fn invalidate_runtime_paths(&mut self) {
+        self.runtime_paths = None;
+    }
+
     pub fn get_settings(&self) -> &crate::config::AppSettings {
         &self.settings
     }
@@ -128,12 +265,53 @@ impl ProcessManager {
         self.services.get(&service).map(|s| s.port)
     }
 
-    fn port_for_service(service_type: ServiceType, settings: &crate::config::AppSettings) -> u16 {
+    /// Managed service whose process is `pid`
+    pub fn service_with_pid(&self, pid: u32) -> Option<ServiceType> {
+        self.services.iter()
+            .find(|(_, process)| process.pid == Some(pid))
+            .map(|(service, _)| *service)
+    }
+
+    /// Port a running service was started on, and when
+    pub fn get_started(&self, service: ServiceType) -> Option<(u16, std::time::SystemTime)> {
+        self.services.get(&service)
+            .filter(|s| s.state.is_running())
+            .and_then(|s| s.started)
+    }
+
+    /// Running services paired with the settings they were started with
+    pub fn get_applied_settings(&self) -> Vec<(ServiceType, crate::config::AppSettings)> {
+        ServiceType::start_order()
+            .into_iter()
+            .filter_map(|service| {
+                self.services.get(&service)
+                    .filter(|s| s.state.is_running())
+                    .and_then(|s| s.applied.clone())
+                    .map(|applied| (service, applied))
+            })
+            .collect()
+    }
+
+    /// Record that a running service now uses the current settings
+    pub fn mark_applied(&mut self, service: ServiceType) {
+        if let Some(service_process) = self.services.get_mut(&service) {
+            if service_process.state.is_running() {
+                service_process.applied = Some(self.settings.clone());
+            }
+        }
+    }
+
+    pub fn port_for_service(service_type: ServiceType, settings: &crate::config::AppSettings) -> u16 {
         match service_type {
             ServiceType::Caddy => settings.web_port,
             ServiceType::PhpFpm => settings.php_port,
             ServiceType::MySQL => settings.mysql_port,
             ServiceType::PostgreSQL => settings.postgres_port,
+            ServiceType::Tunnel => settings.tunnel.port,
+            ServiceType::Mailpit => settings.mailpit.smtp_port,
+            ServiceType::Memcached => settings.memcached.port,
+            ServiceType::Minio => settings.minio.port,
+            ServiceType::Ftp => settings.ftp.sftp_port,
         }
     }
 
@@ -142,6 +320,100 @@ impl ProcessManager {
         for (service_type, service_process) in self.services.iter_mut() {
             service_process.port = Self::port_for_service(*service_type, settings);
         }
+        // The project root is the only path that follows settings
+        if let Some(ref mut paths) = self.runtime_paths {
+            if let Ok(app_paths) = crate::runtime::locator::get_app_data_paths() {
+                paths.projects_dir = settings.projects_dir(&app_paths.projects_dir);
+            }
+        }
+        self.sync_enabled();
+    }
+
+    /// Move a service to a new port and restart everything that depends on it
+    ///
+    /// Runs entirely under the caller's lock on the manager, so no other command
+    /// can start or stop services halfway through. A PHP port change restarts
+    /// Caddy after PHP so the Caddyfile gets the new fastcgi target; a database
+    /// port change only rewrites the phpMyAdmin and Adminer configs, Caddy keeps
+    /// running. With `update_project_envs`, `DB_PORT=<old>` lines in
+    /// `<projects>/*/.env` are rewritten as well.
+    pub fn set_service_port(
+        &mut self,
+        service: ServiceType,
+        port: u16,
+        update_project_envs: bool,
+    ) -> Result<PortChangeResult, String> {
+        let old_port = self.get_service_port(service)
+            .ok_or_else(|| format!("Service {:?} not found", service))?;
+
+        if port < 1024 {
+            return Err(format!("Port {} is reserved, choose a port between 1024 and 65535", port));
+        }
+        if let Some((other, _)) = self.services.iter().find(|(ty, p)| **ty != service && p.port == port) {
+            return Err(format!("Port {} is already assigned to {}", port, other.display_name()));
+        }
+        if port == old_port {
+            return Ok(PortChangeResult { service, old_port, new_port: port, restarted: Vec::new(), updated_env_files: Vec::new() });
+        }
+        if !crate::config::is_port_available(port) {
+            return Err(format!("Port {} is already in use by another application", port));
+        }
+
+        // start() reloads settings from disk, so persist before restarting anything
+        let mut settings = crate::config::AppSettings::load();
+        match service {
+            ServiceType::Caddy => settings.web_port = port,
+            ServiceType::PhpFpm => settings.php_port = port,
+            ServiceType::MySQL => settings.mysql_port = port,
+            ServiceType::PostgreSQL => settings.postgres_port = port,
+            ServiceType::Tunnel => settings.tunnel.port = port,
+            ServiceType::Mailpit => settings.mailpit.smtp_port = port,
+            ServiceType::Memcached => settings.memcached.port = port,
+            ServiceType::Minio => settings.minio.port = port,
+            ServiceType::Ftp => settings.ftp.sftp_port = port,
+        }
+        settings.save()?;
+        self.update_ports(&settings);
+
+        // Stop dependents first so they never point at a backend that is gone.
+        // Only the Caddyfile names the PHP port, the database ports are only
+        // in the phpMyAdmin and Adminer configs, which are rewritten in place.
+        // The tunnel forwards to the web port, php.ini names the SMTP port.
+        let dependents: &[ServiceType] = match service {
+            ServiceType::PhpFpm => &[ServiceType::Caddy],
+            ServiceType::Caddy => &[ServiceType::Tunnel],
+            ServiceType::Mailpit => &[ServiceType::PhpFpm],
+            ServiceType::MySQL | ServiceType::PostgreSQL | ServiceType::Tunnel | ServiceType::Memcached | ServiceType::Minio | ServiceType::Ftp => &[],
+        };
+        let running_dependents: Vec<ServiceType> = dependents.iter()
+            .copied()
+            .filter(|d| self.status(*d).is_running())
+            .collect();
+        for dependent in &running_dependents {
+            self.stop(*dependent)?;
+        }
+
+        let mut restarted = Vec::new();
+        if self.status(service).is_running() {
+            self.restart(service)?;
+            restarted.push(service);
+        }
+        for dependent in running_dependents {
+            self.start(dependent)?;
+            restarted.push(dependent);
+        }
+        if let (Some(paths), ServiceType::MySQL | ServiceType::PostgreSQL) = (&self.runtime_paths, service) {
+            generate_db_client_configs(paths, &settings, settings.mysql_port)?;
+        }
+
+        let updated_env_files = match (&self.runtime_paths, service) {
+            (Some(paths), ServiceType::MySQL | ServiceType::PostgreSQL) if update_project_envs => {
+                update_project_env_ports(&paths.projects_dir, old_port, port)
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(PortChangeResult { service, old_port, new_port: port, restarted, updated_env_files })
     }
 
     /// Initialize the process manager with runtime paths
@@ -189,6 +461,10 @@ impl ProcessManager {
     pub fn start(&mut self, service: ServiceType) -> Result<(), String> {
         // Reload settings from disk to pick up any password/port changes
         self.settings = crate::config::AppSettings::load();
+        self.sync_enabled();
+        if !self.settings.is_enabled(service) {
+            return Err(format!("{} is disabled in settings", service.display_name()));
+        }
 
         // Ensure we have runtime paths
         if self.runtime_paths.is_none() {
@@ -213,26 +489,42 @@ impl ProcessManager {
         if service_process.state.is_running() {
             return Ok(());
         }
+        if service_process.state == ServiceState::Paused {
+            return self.resume(service);
+        }
 
-        service_process.state = ServiceState::Starting;
+        service_process.set_state(ServiceState::Starting, &self.emitter);
+        super::rotation::rotate_service_logs(&paths.logs_dir, service, &self.settings.log_rotation);
  
         // Spawn the appropriate service
         let result = match service {
             ServiceType::Caddy => start_caddy(service_process, &paths, self.settings.php_port, self.settings.mysql_port),
-            ServiceType::PhpFpm => start_php_fpm(service_process, &paths),
+            ServiceType::PhpFpm => start_php_fpm(service_process, &paths, &self.settings),
             ServiceType::MySQL => start_mysql(service_process, &paths, &self.settings),
             ServiceType::PostgreSQL => start_postgresql(service_process, &paths, &self.settings),
+            ServiceType::Tunnel => start_tunnel(service_process, &paths, &self.settings),
+            ServiceType::Mailpit => start_mailpit(service_process, &paths, &self.settings),
+            ServiceType::Memcached => start_memcached(service_process, &paths, &self.settings),
+            ServiceType::Minio => start_minio(service_process, &paths, &self.settings),
+            ServiceType::Ftp => start_ftp(service_process, &paths, &self.settings),
         };
 
         match result {
             Ok(_) => {
-                service_process.state = ServiceState::Running;
                 service_process.error_message = None;
+                service_process.set_state(ServiceState::Running, &self.emitter);
+                service_process.started = Some((service_process.port, std::time::SystemTime::now()));
+                service_process.applied = Some(self.settings.clone());
+                if service == ServiceType::PhpFpm {
+                    for (version, e) in self.php_pool.start(&paths, &self.settings, service_process.port) {
+                        tracing::warn!("PHP {} for pinned projects did not start: {}", version, e);
+                    }
+                }
                 Ok(())
             }
             Err(e) => {
-                service_process.state = ServiceState::Error;
                 service_process.error_message = Some(e.clone());
+                service_process.set_state(ServiceState::Error, &self.emitter);
                 Err(e)
             }
         }
@@ -245,11 +537,23 @@ impl ProcessManager {
             .get_mut(&service)
             .ok_or_else(|| format!("Service {:?} not found", service))?;
 
-        if !service_process.state.is_running() && service_process.state != ServiceState::Error {
+        if !service_process.state.is_running()
+            && !matches!(service_process.state, ServiceState::Error | ServiceState::Paused)
+        {
             return Ok(());
         }
 
-        service_process.state = ServiceState::Stopping;
+        // A frozen process cannot handle a graceful shutdown
+        if service_process.state == ServiceState::Paused {
+            if let Some(pid) = service_process.pid {
+                let _ = super::suspend::resume_process(pid);
+            }
+        }
+
+        service_process.set_state(ServiceState::Stopping, &self.emitter);
+        if service == ServiceType::PhpFpm {
+            self.php_pool.stop();
+        }
 
         // Kill the tracked child process by handle
         if let Some(ref mut child) = service_process.child {
@@ -260,22 +564,7 @@ impl ProcessManager {
         // For PostgreSQL, use pg_ctl for graceful shutdown before force-killing
         if service == ServiceType::PostgreSQL {
             if let Some(ref paths) = self.runtime_paths {
-                #[cfg(target_os = "windows")]
-                let pg_ctl = paths.pgsql_dir.join("bin").join("pg_ctl.exe");
-                #[cfg(not(target_os = "windows"))]
-                let pg_ctl = paths.pgsql_dir.join("bin").join("pg_ctl");
-
-                if pg_ctl.exists() {
-                    let _ = configure_no_window(Command::new(&pg_ctl))
-                        .arg("stop")
-                        .arg("-D")
-                        .arg(&paths.pgsql_data_dir)
-                        .arg("-m")
-                        .arg("fast")
-                        .stdout(Stdio::null())
-                        .stderr(Stdio::null())
-                        .output();
-                }
+                stop_postgresql_fast(paths);
             }
         }
 
@@ -293,9 +582,53 @@ impl ProcessManager {
 
         service_process.child = None;
         service_process.pid = None;
-        service_process.state = ServiceState::Stopped;
         service_process.error_message = None;
+        let state = if self.settings.is_enabled(service) {
+            ServiceState::Stopped
+        } else {
+            ServiceState::Disabled
+        };
+        service_process.set_state(state, &self.emitter);
+        service_process.started = None;
+        service_process.applied = None;
+
+        Ok(())
+    }
+
+    /// Suspend a running service without stopping it
+    pub fn pause(&mut self, service: ServiceType) -> Result<(), String> {
+        let service_process = self
+            .services
+            .get_mut(&service)
+            .ok_or_else(|| format!("Service {:?} not found", service))?;
+
+        if service_process.state == ServiceState::Paused {
+            return Ok(());
+        }
+        if !service_process.state.is_running() {
+            return Err(format!("{} is not running", service.display_name()));
+        }
+        let pid = service_process.pid.ok_or_else(|| format!("{} has no tracked process", service.display_name()))?;
+
+        super::suspend::suspend_process(pid)?;
+        service_process.set_state(ServiceState::Paused, &self.emitter);
+        Ok(())
+    }
+
+    /// Resume a service suspended with `pause`
+    pub fn resume(&mut self, service: ServiceType) -> Result<(), String> {
+        let service_process = self
+            .services
+            .get_mut(&service)
+            .ok_or_else(|| format!("Service {:?} not found", service))?;
 
+        if service_process.state != ServiceState::Paused {
+            return Ok(());
+        }
+        let pid = service_process.pid.ok_or_else(|| format!("{} has no tracked process", service.display_name()))?;
+
+        super::suspend::resume_process(pid)?;
+        service_process.set_state(ServiceState::Running, &self.emitter);
         Ok(())
     }
 
@@ -306,6 +639,163 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Restart a service with the given strategy, timing each phase
+    ///
+    /// `ReloadConfig` goes through the Caddy admin API, which is async, so it
+    /// is handled by the `restart_service` command rather than here.
+    pub fn restart_with(&mut self, service: ServiceType, strategy: RestartStrategy) -> Result<RestartReport, String> {
+        if !strategy.supports(service) {
+            return Err(format!("{:?} restart is not supported for {}", strategy, service.display_name()));
+        }
+
+        let begin = Instant::now();
+        let mut report = RestartReport { service, strategy, stop_ms: 0, start_ms: 0, total_ms: 0 };
+
+        match strategy {
+            RestartStrategy::Graceful | RestartStrategy::Force => {
+                if strategy == RestartStrategy::Graceful {
+                    self.stop_gracefully(service)?;
+                } else {
+                    self.stop(service)?;
+                }
+                report.stop_ms = elapsed_ms(begin);
+                let start = Instant::now();
+                self.start(service)?;
+                report.start_ms = elapsed_ms(start);
+            }
+            RestartStrategy::Rolling => {
+                self.reload_php_workers()?;
+                report.start_ms = elapsed_ms(begin);
+            }
+            RestartStrategy::ReloadConfig => {
+                return Err("Config reload must go through the Caddy admin API".to_string());
+            }
+        }
+
+        report.total_ms = elapsed_ms(begin);
+        Ok(report)
+    }
+
+    /// Ask a service to shut down cleanly before `stop` kills what is left
+    ///
+    /// PostgreSQL goes through `pg_ctl stop -m fast` and MySQL/MariaDB through
+    /// `mysqladmin shutdown`. On Unix the others get SIGTERM (SIGQUIT for
+    /// PHP-FPM, which finishes in-flight requests). On Windows there is no
+    /// signal to send, so for them this is the same as `stop`.
+    fn stop_gracefully(&mut self, service: ServiceType) -> Result<(), String> {
+        let service_process = self
+            .services
+            .get_mut(&service)
+            .ok_or_else(|| format!("Service {:?} not found", service))?;
+
+        if service_process.state.is_running() {
+            let mysql_shut_down = service == ServiceType::MySQL
+                && self.runtime_paths.as_ref().is_some_and(|paths| {
+                    // The server still runs with the port and password it was started with
+                    let settings = service_process.applied.as_ref().unwrap_or(&self.settings);
+                    crate::database::mysql::shutdown(paths, settings.mysql_port, &settings.mysql_root_password)
+                        .inspect_err(|e| tracing::warn!("{}: {}", service.display_name(), e))
+                        .is_ok()
+                });
+
+            if service == ServiceType::PostgreSQL {
+                if let Some(ref paths) = self.runtime_paths {
+                    stop_postgresql_fast(paths);
+                }
+            } else if !mysql_shut_down {
+                #[cfg(unix)]
+                if let Some(pid) = service_process.pid {
+                    let signal = if service == ServiceType::PhpFpm { "QUIT" } else { "TERM" };
+                    if let Err(e) = super::suspend::signal(pid, signal) {
+                        tracing::warn!("{}: {}", service.display_name(), e);
+                    }
+                }
+            }
+
+            if let Some(ref mut child) = service_process.child {
+                let deadline = Instant::now() + GRACEFUL_STOP_TIMEOUT;
+                while matches!(child.try_wait(), Ok(None)) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+            }
+        }
+
+        self.stop(service)
+    }
+
+    /// Gracefully replace the PHP-FPM workers (SIGUSR2), the master keeps its
+    /// listening socket so no request is refused
+    fn reload_php_workers(&mut self) -> Result<(), String> {
+        let is_fpm = self.runtime_paths.as_ref()
+            .and_then(|p| p.php_cgi.file_name())
+            .map(|n| n == "php-fpm")
+            .unwrap_or(false);
+        if !is_fpm {
+            return Err("Rolling restart needs PHP-FPM, PHP-CGI can only be restarted".to_string());
+        }
+
+        let service_process = self
+            .services
+            .get_mut(&ServiceType::PhpFpm)
+            .ok_or("Service PhpFpm not found")?;
+        if !service_process.state.is_running() {
+            return Err(format!("{} is not running", ServiceType::PhpFpm.display_name()));
+        }
+        let pid = service_process.pid.ok_or("PHP-FPM has no tracked process")?;
+
+        #[cfg(unix)]
+        {
+            super::suspend::signal(pid, "USR2")
+        }
+        #[cfg(not(unix))]
+        {
+            Err(format!("Cannot signal PHP-FPM process {} on this platform", pid))
+        }
+    }
+
+    /// Restart the extra PHP versions after `php_versions` changed in
+    /// settings, without touching the main PHP service
+    pub fn restart_php_pool(&mut self) -> Vec<(String, String)> {
+        self.settings = crate::config::AppSettings::load();
+        let port = self.get_service_port(ServiceType::PhpFpm).unwrap_or(self.settings.php_port);
+        match (&self.runtime_paths, self.status(ServiceType::PhpFpm).is_running()) {
+            (Some(paths), true) => self.php_pool.start(paths, &self.settings, port),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Start the wildcard DNS on the port and for the TLD in settings,
+    /// restarting it when either changed; returns the port it listens on
+    pub fn start_dns(&mut self) -> Result<u16, String> {
+        self.settings = crate::config::AppSettings::load();
+        let port = self.settings.dns.port;
+        let tld = self.settings.domain_tld.clone();
+        if let Some(dns) = self.dns.as_ref().filter(|dns| dns.port() == port && dns.tld() == tld) {
+            return Ok(dns.port());
+        }
+        self.stop_dns();
+        let dns = super::dns::DnsServer::start(port, &tld)?;
+        let port = dns.port();
+        self.dns = Some(dns);
+        Ok(port)
+    }
+
+    pub fn stop_dns(&mut self) {
+        if let Some(mut dns) = self.dns.take() {
+            dns.stop();
+        }
+    }
+
+    /// Port of the running wildcard DNS
+    pub fn dns_port(&self) -> Option<u16> {
+        self.dns.as_ref().map(|dns| dns.port())
+    }
+
+    /// npm scripts started for projects
+    pub fn npm_scripts(&mut self) -> &mut super::npm::NpmScripts {
+        &mut self.npm_scripts
+    }
+
     /// Get the status of a service
     pub fn status(&self, service: ServiceType) -> ServiceState {
         self.services
@@ -339,58 +829,87 @@ impl ProcessManager {
                 match child.try_wait() {
                     Ok(Some(status)) => {
                         // Process has exited
-                        service_process.state = ServiceState::Error;
                         service_process.error_message = Some(format!(
                             "Process exited unexpectedly with status: {:?}",
                             status
                         ));
+                        service_process.set_state(ServiceState::Error, &self.emitter);
                         service_process.child = None;
                         service_process.pid = None;
                     }
                     Ok(None) => {
-                        // Still running — only update state from Starting, preserve Error
+                        // Still running — only update state from Starting, preserve Error and Paused
                         if service_process.state == ServiceState::Starting {
-                            service_process.state = ServiceState::Running;
+                            service_process.set_state(ServiceState::Running, &self.emitter);
                         }
                     }
                     Err(_) => {
                         // Error checking status
-                        service_process.state = ServiceState::Error;
                         service_process.error_message = Some(
                             "Failed to check process status".to_string()
                         );
+                        service_process.set_state(ServiceState::Error, &self.emitter);
                     }
                 }
             }
         }
     }
 
+    /// Start every enabled service in dependency order
+    ///
+    /// Keeps going after a failure and returns the services that failed to start.
+    pub fn start_all(&mut self) -> Vec<(ServiceType, String)> {
+        self.settings = crate::config::AppSettings::load();
+        let mut failures = Vec::new();
+        for service in ServiceType::start_order() {
+            if !self.settings.is_enabled(service) {
+                continue;
+            }
+            if let Err(e) = self.start(service) {
+                failures.push((service, e));
+            }
+        }
+        if self.settings.dns.enabled {
+            // Domains still resolve through the hosts file without it
+            if let Err(e) = self.start_dns() {
+                tracing::warn!("Wildcard DNS not started: {}", e);
+            }
+        }
+        failures
+    }
+
     /// Stop all running services (called on app shutdown)
     pub fn stop_all(&mut self) -> Result<(), String> {
         let services_to_stop: Vec<ServiceType> = self
             .services
             .iter()
-            .filter(|(_, s)| s.state.is_running())
+            .filter(|(_, s)| s.state.is_running() || s.state == ServiceState::Paused)
             .map(|(ty, _)| *ty)
             .collect();
 
         for service in services_to_stop {
-            // Ignore errors during shutdown, just try to stop everything
-            let _ = self.stop(service);
+            // Ignore errors during shutdown, just try to stop everything.
+            // Killing MySQL/MariaDB risks crash recovery on the next start.
+            let _ = if service == ServiceType::MySQL {
+                self.stop_gracefully(service)
+            } else {
+                self.stop(service)
+            };
         }
+        self.stop_dns();
+        self.npm_scripts.stop_all();
 
         Ok(())
     }
 }
 
-/// Start Caddy web server
-fn start_caddy(service_process: &mut ServiceProcess, paths: &RuntimePaths, php_port: u16, mysql_port: u16) -> Result<(), String> {
-    // Kill any existing Caddy processes to avoid port conflicts
-    kill_existing_processes("caddy");
-
-    let settings = crate::config::AppSettings::load();
-    // Generate phpMyAdmin config only if phpMyAdmin is installed
-    if paths.phpmyadmin.join("index.php").exists() {
+/// Point phpMyAdmin and Adminer at the database ports
+///
+/// Both are read by PHP on every request, so rewriting them takes effect
+/// without restarting Caddy.
+pub(crate) fn generate_db_client_configs(paths: &RuntimePaths, settings: &crate::config::AppSettings, mysql_port: u16) -> Result<(), String> {
+    // Generate phpMyAdmin config only if phpMyAdmin is installed and MySQL is enabled
+    if paths.phpmyadmin.join("index.php").exists() && settings.is_enabled(ServiceType::MySQL) {
         crate::config::generator::generate_phpmyadmin_config(paths, mysql_port, &settings.mysql_root_password)?;
     }
     // Generate Adminer launcher only if Adminer is installed
@@ -400,19 +919,34 @@ fn start_caddy(service_process: &mut ServiceProcess, paths: &RuntimePaths, php_p
             settings.postgres_port, &settings.postgres_root_password,
         )?;
     }
-    // Always regenerate Caddyfile with current port settings
+    Ok(())
+}
+
+/// Start Caddy web server
+fn start_caddy(service_process: &mut ServiceProcess, paths: &RuntimePaths, php_port: u16, mysql_port: u16) -> Result<(), String> {
+    // Kill any existing Caddy processes to avoid port conflicts
+    kill_existing_processes("caddy");
+
+    let settings = crate::config::AppSettings::load();
+    generate_db_client_configs(paths, &settings, mysql_port)?;
+    // Regenerate Caddyfile with current port settings, unless hand edits are being kept
     let caddyfile_path = paths.config_dir.join("Caddyfile");
-    crate::config::generator::generate_caddyfile(&caddyfile_path, paths, service_process.port, php_port)?;
+    if !crate::config::generated::keep_hand_edited(&caddyfile_path, &settings) {
+        crate::config::generator::generate_caddyfile(&caddyfile_path, paths, service_process.port, php_port, &settings, &crate::projects::registered())?;
+    }
 
     // Open log file with retry logic for Windows file locking
     let log_path = paths.logs_dir.join("caddy.log");
-    let log_file = open_log_file_with_retry(&log_path, "Caddy")?;
+    let mut log_file = open_log_file_with_retry(&log_path, "Caddy")?;
 
     // Start Caddy
-    let mut child = configure_no_window(Command::new(&paths.caddy))
-        .arg("run")
+    let mut cmd = configure_no_window(Command::new(&paths.caddy));
+    cmd.arg("run")
         .arg("--config")
-        .arg(&caddyfile_path)
+        .arg(&caddyfile_path);
+    apply_extra_args(&mut cmd, ServiceType::Caddy, &settings, &mut log_file)?;
+
+    let mut child = cmd
         .current_dir(&paths.config_dir)
         .stdout(Stdio::from(log_file.try_clone().unwrap()))
         .stderr(Stdio::from(log_file))
@@ -437,17 +971,19 @@ fn start_caddy(service_process: &mut ServiceProcess, paths: &RuntimePaths, php_p
 }
 
 /// Start PHP-FPM (using PHP-CGI for simplicity in MVP)
-fn start_php_fpm(service_process: &mut ServiceProcess, paths: &RuntimePaths) -> Result<(), String> {
+fn start_php_fpm(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
     // Kill any existing PHP processes to avoid port conflicts
     kill_existing_processes("php-fpm");
     kill_existing_processes("php-cgi");
 
-    // Generate php.ini (always regenerate to keep extensions in sync)
-    crate::config::generator::generate_php_ini(&paths.php_ini, paths)?;
+    // Regenerate php.ini to keep extensions in sync, unless hand edits are being kept
+    if !crate::config::generated::keep_hand_edited(&paths.php_ini, settings) {
+        crate::config::generator::generate_php_ini(&paths.php_ini, paths, settings)?;
+    }
 
     // Open log file with retry logic
     let log_path = paths.logs_dir.join("php-fpm.log");
-    let log_file = open_log_file_with_retry(&log_path, "PHP-FPM")?;
+    let mut log_file = open_log_file_with_retry(&log_path, "PHP-FPM")?;
 
     // Check if we have php-fpm (static-php on Linux/macOS) or php-cgi (Windows)
     let is_fpm = paths.php_cgi.file_name()
@@ -455,7 +991,8 @@ fn start_php_fpm(service_process: &mut ServiceProcess, paths: &RuntimePaths) ->
         .map(|n| n == "php-fpm")
         .unwrap_or(false);
 
-    let mut child = if is_fpm {
+    let mut cmd = configure_no_window(Command::new(&paths.php_cgi));
+    if is_fpm {
         // Generate php-fpm.conf if it doesn't exist
         let fpm_conf_path = paths.config_dir.join("php-fpm.conf");
         if !fpm_conf_path.exists() {
@@ -466,30 +1003,27 @@ fn start_php_fpm(service_process: &mut ServiceProcess, paths: &RuntimePaths) ->
         }
 
         // PHP-FPM requires -F to run in foreground and -y for config
-        configure_no_window(Command::new(&paths.php_cgi))
-            .arg("-F")  // Don't daemonize
+        cmd.arg("-F")  // Don't daemonize
             .arg("-y")
             .arg(&fpm_conf_path)
             .arg("-c")
-            .arg(&paths.php_ini)
-            .current_dir(&paths.config_dir)
-            .stdout(Stdio::from(log_file.try_clone().unwrap()))
-            .stderr(Stdio::from(log_file))
-            .spawn()
-            .map_err(|e| format!("Failed to start PHP-FPM: {}", e))?
+            .arg(&paths.php_ini);
     } else {
         // PHP-CGI (Windows) uses -b for FastCGI mode
-        configure_no_window(Command::new(&paths.php_cgi))
-            .arg("-b")
+        cmd.arg("-b")
             .arg(format!("127.0.0.1:{}", service_process.port))
             .arg("-c")
-            .arg(&paths.php_ini)
-            .current_dir(&paths.config_dir)
-            .stdout(Stdio::from(log_file.try_clone().unwrap()))
-            .stderr(Stdio::from(log_file))
-            .spawn()
-            .map_err(|e| format!("Failed to start PHP-CGI: {}", e))?
-    };
+            .arg(&paths.php_ini);
+    }
+    apply_extra_args(&mut cmd, ServiceType::PhpFpm, settings, &mut log_file)?;
+
+    let php_name = if is_fpm { "PHP-FPM" } else { "PHP-CGI" };
+    let mut child = cmd
+        .current_dir(&paths.config_dir)
+        .stdout(Stdio::from(log_file.try_clone().unwrap()))
+        .stderr(Stdio::from(log_file))
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", php_name, e))?;
 
     // Give it a moment to start
     std::thread::sleep(std::time::Duration::from_millis(500));
@@ -508,29 +1042,38 @@ fn start_php_fpm(service_process: &mut ServiceProcess, paths: &RuntimePaths) ->
     }
 }
 
-/// Start MySQL/MariaDB database server
+/// `--bind-address` of the MySQL/MariaDB server at `binary`
 ///
-/// **IMPORTANT Platform Differences:**
-/// - **Linux**: Uses MariaDB 12.x (binary: mariadbd)
-/// - **Windows/macOS**: Uses MySQL 8.x (binary: mysqld)
+/// Several comma-separated addresses need MariaDB 10.11 or MySQL 8.0.13.
+/// Older servers, or one whose version cannot be read, get the wildcard
+/// address of the same family instead, which also covers loopback.
+pub(crate) fn mysql_bind_address(settings: &crate::config::AppSettings, binary: &Path) -> String {
+    let version = crate::runtime::validate::run_binary("MySQL", binary, &["--version"]).unwrap_or_default();
+    bind_address_for(&settings.listen_addresses(), &version)
+}
+
+/// `addresses` joined for a server whose `--version` printed `version_output`
+fn bind_address_for(addresses: &[String], version_output: &str) -> String {
+    let minimum = if version_output.contains("MariaDB") { "10.11" } else { "8.0.13" };
+    let multiple = crate::runtime::validate::parse_version(version_output)
+        .is_some_and(|version| crate::runtime::updates::compare_versions(&version, minimum).is_ge());
+    if addresses.len() < 2 || multiple {
+        return addresses.join(",");
+    }
+    if addresses.iter().any(|address| address.contains(':')) { "::" } else { "0.0.0.0" }.to_string()
+}
+
+/// Start MySQL/MariaDB database server
 ///
+/// MariaDB is the default on Linux (binary: mariadbd) and MySQL 8.x on
+/// Windows/macOS (binary: mysqld), the package selection can pick either.
 /// These are drop-in replacements for each other, but have different
 /// initialization requirements and binary names.
 fn start_mysql(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
     // Kill any existing database server processes to avoid port conflicts
-    #[cfg(target_os = "linux")]
-    {
-        // Linux: Kill MariaDB processes (mariadbd)
-        // Also kill mysqld in case of mixed installations
-        kill_existing_processes("mariadbd");
-        kill_existing_processes("mysqld");
-    }
-
-    #[cfg(not(target_os = "linux"))]
-    {
-        // Windows/macOS: Kill MySQL processes (mysqld)
-        kill_existing_processes("mysqld");
-    }
+    // MariaDB runs as mariadbd, MySQL (and older MariaDB) as mysqld
+    kill_existing_processes("mariadbd");
+    kill_existing_processes("mysqld");
 
     // Initialize MySQL data directory if needed
     initialize_mysql_data_dir(paths)?;
@@ -559,7 +1102,12 @@ fn start_mysql(service_process: &mut ServiceProcess, paths: &RuntimePaths, setti
 
     // Open log file with retry logic
     let log_path = paths.logs_dir.join("mysql.log");
-    let log_file = open_log_file_with_retry(&log_path, "MariaDB")?;
+    let mut log_file = open_log_file_with_retry(&log_path, "MariaDB")?;
+
+    let bind_address = mysql_bind_address(settings, &paths.mysql);
+    if bind_address != settings.listen_addresses().join(",") {
+        tracing::warn!("This MySQL/MariaDB binds one address only, listening on {} instead", bind_address);
+    }
 
     // Build MySQL command — always pass init file to keep password synced
     let mut cmd = configure_no_window(Command::new(&paths.mysql));
@@ -567,11 +1115,12 @@ fn start_mysql(service_process: &mut ServiceProcess, paths: &RuntimePaths, setti
         .arg(&data_dir_str)
         .arg("--port")
         .arg(service_process.port.to_string())
-        .arg("--bind-address=127.0.0.1")
+        .arg(format!("--bind-address={}", bind_address))
         .arg("--console")
         .arg("--skip-name-resolve")
         .arg("--init-file")
         .arg(&init_file);
+    apply_extra_args(&mut cmd, ServiceType::MySQL, settings, &mut log_file)?;
 
     let mut child = cmd
         .stdout(Stdio::from(log_file.try_clone().unwrap()))
@@ -612,6 +1161,132 @@ fn start_mysql(service_process: &mut ServiceProcess, paths: &RuntimePaths, setti
     }
 }
 
+/// Run `cmd` in `dir` with its output in `log_file`, and track it as
+/// `service_process` unless it exits within half a second
+///
+/// For the optional services, which need nothing but their own command line.
+fn spawn_service(service_process: &mut ServiceProcess, mut cmd: Command, name: &str, dir: &Path, log_path: PathBuf, log_file: File) -> Result<(), String> {
+    let mut child = cmd
+        .current_dir(dir)
+        .stdout(Stdio::from(log_file.try_clone().map_err(|e| e.to_string())?))
+        .stderr(Stdio::from(log_file))
+        .spawn()
+        .map_err(|e| format!("Failed to start {} from {}: {}", name, Path::new(cmd.get_program()).display(), e))?;
+
+    // Give it a moment to start
+    std::thread::sleep(std::time::Duration::from_millis(500));
+
+    match child.try_wait() {
+        Ok(Some(status)) => Err(format!("{} exited immediately with status: {:?}", name, status)),
+        Ok(None) => {
+            let pid = child.id();
+            service_process.child = Some(child);
+            service_process.log_file = Some(log_path);
+            service_process.pid = Some(pid);
+            Ok(())
+        }
+        Err(e) => Err(format!("Failed to check {} process: {}", name, e)),
+    }
+}
+
+/// Start the public tunnel to the project chosen in settings
+///
+/// The public URL is not known yet, it is read from the client's local API
+/// once the tunnel has connected.
+fn start_tunnel(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
+    let tunnel = &settings.tunnel;
+    let project = match &tunnel.project {
+        Some(name) => Some(
+            crate::projects::registered()
+                .into_iter()
+                .find(|p| &p.name == name)
+                .ok_or_else(|| format!("Project '{}' not found", name))?,
+        ),
+        None => None,
+    };
+    let origin = super::tunnel::Origin::new(project.as_ref(), settings);
+
+    let ngrok_config = paths.config_dir.join("ngrok.yml");
+    if tunnel.provider == super::tunnel::TunnelProvider::Ngrok {
+        let authtoken = super::tunnel::read_authtoken(&paths.config_dir)
+            .ok_or("ngrok needs an authtoken, add it in the tunnel settings")?;
+        super::write_private_file(&ngrok_config, &super::tunnel::ngrok_config(&authtoken, service_process.port))?;
+    }
+
+    let log_path = paths.logs_dir.join("tunnel.log");
+    let mut log_file = open_log_file_with_retry(&log_path, "Tunnel")?;
+    let _ = writeln!(log_file, "[CAMPP] Forwarding a public URL to {} (Host: {})", origin.url, origin.host);
+
+    let binary = super::tunnel::binary(tunnel, &paths.cloudflared);
+    let mut cmd = configure_no_window(Command::new(&binary));
+    cmd.args(super::tunnel::command_args(tunnel.provider, &origin, service_process.port, &ngrok_config));
+    apply_extra_args(&mut cmd, ServiceType::Tunnel, settings, &mut log_file)?;
+
+    spawn_service(service_process, cmd, tunnel.provider.display_name(), &paths.config_dir, log_path, log_file)
+}
+
+/// Start Mailpit on the SMTP and web UI ports from settings
+fn start_mailpit(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
+    let log_path = paths.logs_dir.join("mailpit.log");
+    let mut log_file = open_log_file_with_retry(&log_path, "Mailpit")?;
+
+    let mut cmd = configure_no_window(Command::new(&paths.mailpit));
+    cmd.args(super::mailpit::command_args(&settings.mailpit));
+    apply_extra_args(&mut cmd, ServiceType::Mailpit, settings, &mut log_file)?;
+
+    spawn_service(service_process, cmd, "Mailpit", &paths.config_dir, log_path, log_file)
+}
+
+/// Start Memcached from settings or PATH
+fn start_memcached(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
+    let log_path = paths.logs_dir.join("memcached.log");
+    let mut log_file = open_log_file_with_retry(&log_path, "Memcached")?;
+
+    let binary = super::memcached::binary(&settings.memcached);
+    let mut cmd = configure_no_window(Command::new(&binary));
+    cmd.args(super::memcached::command_args(&settings.memcached));
+    apply_extra_args(&mut cmd, ServiceType::Memcached, settings, &mut log_file)?;
+
+    spawn_service(service_process, cmd, "Memcached", &paths.config_dir, log_path, log_file)
+}
+
+/// Start MinIO on its data directory, generating the credentials on first start
+fn start_minio(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
+    let log_path = paths.logs_dir.join("minio.log");
+    let mut log_file = open_log_file_with_retry(&log_path, "MinIO")?;
+
+    let credentials = super::minio::ensure_credentials(&paths.minio_dir)?;
+    let data_dir = super::minio::data_dir(&paths.minio_dir);
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create MinIO data directory: {}", e))?;
+
+    let mut cmd = configure_no_window(Command::new(&paths.minio));
+    cmd.args(super::minio::command_args(&settings.minio, &paths.minio_dir))
+        .env("MINIO_ROOT_USER", &credentials.access_key)
+        .env("MINIO_ROOT_PASSWORD", &credentials.secret_key);
+    apply_extra_args(&mut cmd, ServiceType::Minio, settings, &mut log_file)?;
+
+    spawn_service(service_process, cmd, "MinIO", &paths.minio_dir, log_path, log_file)
+}
+
+/// Start SFTPGo serving the projects directory, generating the account on first start
+fn start_ftp(service_process: &mut ServiceProcess, paths: &RuntimePaths, settings: &crate::config::AppSettings) -> Result<(), String> {
+    let log_path = paths.logs_dir.join("ftp.log");
+    let mut log_file = open_log_file_with_retry(&log_path, "SFTP/FTP")?;
+
+    let sftpgo_dir = super::ftp::sftpgo_dir(&paths.config_dir);
+    let credentials = super::ftp::ensure_credentials(&sftpgo_dir)?;
+    super::ftp::write_config(&sftpgo_dir, &settings.ftp, &paths.projects_dir, &credentials)?;
+
+    let binary = super::ftp::binary(&settings.ftp);
+    let mut cmd = configure_no_window(Command::new(&binary));
+    cmd.args(super::ftp::command_args(&sftpgo_dir, &log_path));
+    apply_extra_args(&mut cmd, ServiceType::Ftp, settings, &mut log_file)?;
+
+    // Host keys are generated next to sftpgo.json
+    spawn_service(service_process, cmd, "SFTPGo", &sftpgo_dir, log_path, log_file)
+}
+
 fn initialize_mysql_data_dir(paths: &RuntimePaths) -> Result<(), String> {
     crate::database::mysql::initialize_mysql(paths)
 }
@@ -646,6 +1321,7 @@ fn start_postgresql(service_process: &mut ServiceProcess, paths: &RuntimePaths,
     crate::config::generator::generate_postgresql_conf(
         &paths.pgsql_data_dir,
         service_process.port,
+        &settings.listen_addresses(),
     )?;
 
     // Use "trust" auth on first run (password not yet set) so psql can connect
@@ -660,11 +1336,12 @@ fn start_postgresql(service_process: &mut ServiceProcess, paths: &RuntimePaths,
     crate::config::generator::generate_pg_hba_conf(
         &paths.pgsql_data_dir,
         pg_auth_method,
+        settings.is_lan_exposed(),
     )?;
 
     // Open log file
     let log_path = paths.logs_dir.join("postgresql.log");
-    let log_file = open_log_file_with_retry(&log_path, "PostgreSQL")?;
+    let mut log_file = open_log_file_with_retry(&log_path, "PostgreSQL")?;
 
     let data_dir_str = paths.pgsql_data_dir.to_string_lossy().to_string();
 
@@ -672,6 +1349,7 @@ fn start_postgresql(service_process: &mut ServiceProcess, paths: &RuntimePaths,
     let mut cmd = configure_no_window(Command::new(&postgres_bin));
     cmd.arg("-D").arg(&data_dir_str)
         .arg("-p").arg(service_process.port.to_string());
+    apply_extra_args(&mut cmd, ServiceType::PostgreSQL, settings, &mut log_file)?;
 
     // On Unix, set library path so postgres can find shared libraries
     #[cfg(unix)]
@@ -764,7 +1442,7 @@ fn start_postgresql(service_process: &mut ServiceProcess, paths: &RuntimePaths,
 
                                     // Switch to scram-sha-256 now that password is set
                                     if let Err(e) = crate::config::generator::generate_pg_hba_conf(
-                                        &paths.pgsql_data_dir, "scram-sha-256",
+                                        &paths.pgsql_data_dir, "scram-sha-256", settings.is_lan_exposed(),
                                     ) {
                                         tracing::warn!("Failed to update pg_hba.conf: {}", e);
                                     } else {
@@ -826,6 +1504,30 @@ fn wait_for_postgres_ready(psql_bin: &Path, port: u16, pgsql_dir: &Path, timeout
     false
 }
 
+/// Shut PostgreSQL down with `pg_ctl stop -m fast`, rolling back open transactions
+fn stop_postgresql_fast(paths: &RuntimePaths) {
+    #[cfg(target_os = "windows")]
+    let pg_ctl = paths.pgsql_dir.join("bin").join("pg_ctl.exe");
+    #[cfg(not(target_os = "windows"))]
+    let pg_ctl = paths.pgsql_dir.join("bin").join("pg_ctl");
+
+    if pg_ctl.exists() {
+        let _ = configure_no_window(Command::new(&pg_ctl))
+            .arg("stop")
+            .arg("-D")
+            .arg(&paths.pgsql_data_dir)
+            .arg("-m")
+            .arg("fast")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+    }
+}
+
+fn elapsed_ms(since: Instant) -> u64 {
+    since.elapsed().as_millis() as u64
+}
+
 /// Signal PostgreSQL to reload its configuration files
 fn reload_postgresql_conf(paths: &RuntimePaths) {
     #[cfg(target_os = "windows")]
@@ -864,7 +1566,7 @@ mod tests {
     fn test_process_manager_new() {
         let manager = ProcessManager::new();
 
-        assert_eq!(manager.services.len(), 4);
+        assert_eq!(manager.services.len(), 9);
 
         let caddy = manager.services.get(&ServiceType::Caddy).unwrap();
         assert_eq!(caddy.name, ServiceType::Caddy);
@@ -876,17 +1578,23 @@ mod tests {
     #[test]
     fn test_process_manager_default() {
         let manager = ProcessManager::default();
-        assert_eq!(manager.services.len(), 4);
+        assert_eq!(manager.services.len(), 9);
         assert!(manager.runtime_paths.is_none());
     }
 
     #[test]
     fn test_status_of_service() {
-        let manager = ProcessManager::new();
+        let manager = ProcessManager::with_settings(crate::config::AppSettings::default());
 
         assert_eq!(manager.status(ServiceType::Caddy), ServiceState::Stopped);
         assert_eq!(manager.status(ServiceType::PhpFpm), ServiceState::Stopped);
         assert_eq!(manager.status(ServiceType::MySQL), ServiceState::Stopped);
+        // Only enabled once a tunnel is started
+        assert_eq!(manager.status(ServiceType::Tunnel), ServiceState::Disabled);
+        assert_eq!(manager.status(ServiceType::Mailpit), ServiceState::Disabled);
+        assert_eq!(manager.status(ServiceType::Memcached), ServiceState::Disabled);
+        assert_eq!(manager.status(ServiceType::Minio), ServiceState::Disabled);
+        assert_eq!(manager.status(ServiceType::Ftp), ServiceState::Disabled);
     }
 
     #[test]
@@ -894,7 +1602,7 @@ mod tests {
         let manager = ProcessManager::new();
         let statuses = manager.get_all_statuses();
 
-        assert_eq!(statuses.len(), 4);
+        assert_eq!(statuses.len(), 9);
 
         let caddy_info = statuses.get(&ServiceType::Caddy).unwrap();
         assert_eq!(caddy_info.service_type, ServiceType::Caddy);
@@ -955,6 +1663,28 @@ mod tests {
         assert_eq!(pgsql.port, 5433);
     }
 
+    #[test]
+    fn test_state_transitions_are_emitted() {
+        let mut manager = ProcessManager::new();
+        let changes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = changes.clone();
+        manager.set_emitter(Arc::new(move |change| sink.lock().unwrap().push(change)));
+
+        let caddy = manager.services.get_mut(&ServiceType::Caddy).unwrap();
+        caddy.state = ServiceState::Error;
+        caddy.error_message = Some("Test error".to_string());
+        manager.stop(ServiceType::Caddy).unwrap();
+
+        let changes = changes.lock().unwrap();
+        let transitions: Vec<_> = changes.iter().map(|c| (c.old.clone(), c.new.clone())).collect();
+        assert_eq!(transitions, vec![
+            (ServiceState::Error, ServiceState::Stopping),
+            (ServiceState::Stopping, ServiceState::Stopped),
+        ]);
+        assert_eq!(changes[0].error_message.as_deref(), Some("Test error"));
+        assert_eq!(changes[1].error_message, None);
+    }
+
     #[test]
     fn test_multiple_services_have_independent_states() {
         let mut manager = ProcessManager::new();
@@ -982,6 +1712,48 @@ mod tests {
             assert_eq!(process.name.binary_name(), service_type.binary_name());
         }
     }
+
+    #[test]
+    fn test_set_service_port_rejects_conflicts() {
+        let mut manager = ProcessManager::with_settings(crate::config::AppSettings::default());
+
+        let result = manager.set_service_port(ServiceType::Caddy, 9000, false);
+        assert!(result.unwrap_err().contains("PHP"));
+
+        assert!(manager.set_service_port(ServiceType::MySQL, 80, false).is_err());
+        assert_eq!(manager.get_service_port(ServiceType::Caddy), Some(8080));
+        assert_eq!(manager.get_service_port(ServiceType::MySQL), Some(3307));
+    }
+
+    #[test]
+    fn test_bind_address_for() {
+        let addresses = vec!["127.0.0.1".to_string(), "192.168.1.20".to_string()];
+        assert_eq!(bind_address_for(&addresses, "mariadbd  Ver 11.4.5-MariaDB for Linux"), "127.0.0.1,192.168.1.20");
+        assert_eq!(bind_address_for(&addresses, "mysqld  Ver 8.0.13 for Win64"), "127.0.0.1,192.168.1.20");
+        assert_eq!(bind_address_for(&addresses, "mariadbd  Ver 10.6.21-MariaDB for Linux"), "0.0.0.0");
+        assert_eq!(bind_address_for(&addresses, "mysqld  Ver 8.0.12 for Linux"), "0.0.0.0");
+        assert_eq!(bind_address_for(&["127.0.0.1".to_string(), "fd00::20".to_string()], ""), "::");
+        assert_eq!(bind_address_for(&["127.0.0.1".to_string()], ""), "127.0.0.1");
+    }
+
+    #[test]
+    fn test_update_project_env_ports() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let projects = temp_dir.path();
+        fs::create_dir_all(projects.join("laravel")).unwrap();
+        fs::create_dir_all(projects.join("other")).unwrap();
+        fs::write(projects.join("laravel").join(".env"), "DB_HOST=127.0.0.1\nDB_PORT=3307\n").unwrap();
+        fs::write(projects.join("other").join(".env"), "DB_PORT=5433\n").unwrap();
+
+        let updated = update_project_env_ports(projects, 3307, 3310);
+
+        assert_eq!(updated.len(), 1);
+        assert_eq!(
+            fs::read_to_string(projects.join("laravel").join(".env")).unwrap(),
+            "DB_HOST=127.0.0.1\nDB_PORT=3310\n"
+        );
+        assert_eq!(fs::read_to_string(projects.join("other").join(".env")).unwrap(), "DB_PORT=5433\n");
+    }
 }
 
 // Integration tests - require actual runtime binaries installed
@@ -1057,6 +1829,11 @@ mod integration_tests {
                 ServiceType::PhpFpm => "php-fpm.log",
                 ServiceType::MySQL => "mysql.log",
                 ServiceType::PostgreSQL => "postgresql.log",
+                ServiceType::Tunnel => "tunnel.log",
+                ServiceType::Mailpit => "mailpit.log",
+                ServiceType::Memcached => "memcached.log",
+                ServiceType::Minio => "minio.log",
+                ServiceType::Ftp => "ftp.log",
             };
             let log_path = paths.logs_dir.join(log_name);
             if log_path.exists() {
@@ -0,0 +1,128 @@
+//! Memcached
+//!
+//! Optional cache server for apps that still use it. There are no official
+//! Memcached builds to download, so the binary comes from a package manager:
+//! the path in settings, or `memcached` on PATH and in the usual install
+//! locations. There is no maintained Memcached for Windows, so in practice it
+//! runs on macOS and Linux only.
+//!
+//! PHP talks to it through the memcached extension, which is not part of the
+//! bundled PHP builds and has to match the PHP version exactly. PHP loads it
+//! while the service is enabled, from the path in settings or from PHP's own
+//! extension directory, where a system PHP's package manager installs it.
+//! `get_memcached_status` reports when it is not found.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Memcached options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemcachedSettings {
+    #[serde(default = "default_memcached_port")]
+    pub port: u16,
+    /// Memory for items in megabytes
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u32,
+    /// memcached binary, looked up on PATH when unset
+    #[serde(default)]
+    pub binary: Option<PathBuf>,
+    /// memcached PHP extension built for CAMPP's PHP, looked up in PHP's
+    /// extension directory when unset
+    #[serde(default)]
+    pub php_extension: Option<PathBuf>,
+}
+
+impl Default for MemcachedSettings {
+    fn default() -> Self {
+        Self { port: default_memcached_port(), memory_mb: default_memory_mb(), binary: None, php_extension: None }
+    }
+}
+
+/// State of Memcached for the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct MemcachedStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    /// Binary that is started
+    pub binary: String,
+    pub binary_found: bool,
+    /// The PHP extension that is loaded, None when PHP cannot use Memcached
+    pub php_extension: Option<String>,
+}
+
+fn default_memcached_port() -> u16 {
+    crate::process::ServiceType::Memcached.default_port()
+}
+
+fn default_memory_mb() -> u32 { 64 }
+
+/// Binary started for Memcached: the one from settings, or the first found
+pub fn binary(settings: &MemcachedSettings) -> PathBuf {
+    settings.binary.clone()
+        .or_else(|| crate::runtime::system::candidates(&["memcached"]).into_iter().next())
+        .unwrap_or_else(|| PathBuf::from(if cfg!(windows) { "memcached.exe" } else { "memcached" }))
+}
+
+/// Command-line arguments of Memcached, listening on loopback only
+///
+/// UDP is off, it is only useful for amplification attacks.
+pub fn command_args(settings: &MemcachedSettings) -> Vec<String> {
+    vec![
+        "--listen=127.0.0.1".into(),
+        format!("--port={}", settings.port),
+        "--udp-port=0".into(),
+        format!("--memory-limit={}", settings.memory_mb),
+    ]
+}
+
+/// The memcached PHP extension: the one from settings, or the one in `ext_dir`
+pub fn php_extension(settings: &MemcachedSettings, ext_dir: &Path) -> Option<PathBuf> {
+    let file = if cfg!(windows) { "php_memcached.dll" } else { "memcached.so" };
+    settings.php_extension.clone()
+        .into_iter()
+        .chain(std::iter::once(ext_dir.join(file)))
+        .find(|path| path.is_file())
+}
+
+/// php.ini line loading the memcached extension, when it is found
+pub fn php_ini_extension(settings: &MemcachedSettings, ext_dir: &Path) -> Option<String> {
+    let path = php_extension(settings, ext_dir)?;
+    Some(format!("extension=\"{}\"\n", path.to_string_lossy().replace('\\', "/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let settings = MemcachedSettings { port: 11311, ..Default::default() };
+        assert_eq!(
+            command_args(&settings).join(" "),
+            "--listen=127.0.0.1 --port=11311 --udp-port=0 --memory-limit=64"
+        );
+        let settings = MemcachedSettings { binary: Some(PathBuf::from("/opt/memcached")), ..Default::default() };
+        assert_eq!(binary(&settings), PathBuf::from("/opt/memcached"));
+    }
+
+    #[test]
+    fn test_php_ini_extension() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let settings = MemcachedSettings::default();
+        assert_eq!(php_ini_extension(&settings, temp_dir.path()), None);
+
+        let file = if cfg!(windows) { "php_memcached.dll" } else { "memcached.so" };
+        let in_ext_dir = temp_dir.path().join(file);
+        std::fs::write(&in_ext_dir, b"").unwrap();
+        let line = php_ini_extension(&settings, temp_dir.path()).unwrap();
+        assert_eq!(line, format!("extension=\"{}\"\n", in_ext_dir.to_string_lossy().replace('\\', "/")));
+
+        // The one from settings comes first, unless it does not exist
+        let custom = temp_dir.path().join("custom.so");
+        let settings = MemcachedSettings { php_extension: Some(custom.clone()), ..Default::default() };
+        assert_eq!(php_extension(&settings, temp_dir.path()), Some(in_ext_dir));
+        std::fs::write(&custom, b"").unwrap();
+        assert_eq!(php_extension(&settings, temp_dir.path()), Some(custom));
+    }
+}
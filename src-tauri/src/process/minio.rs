@@ -0,0 +1,139 @@
+//! MinIO, local S3-compatible storage
+//!
+//! Apps written against S3 SDKs, e.g. a Laravel `s3` disk, point their
+//! endpoint at MinIO and work offline. Buckets are kept in `minio/data` under
+//! the CAMPP base directory. The root credentials are generated the first
+//! time MinIO starts and kept in `minio/credentials.json` next to the data,
+//! not in settings, so exported settings never carry them. The API and the
+//! web console are bound to loopback.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// MinIO ports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinioSettings {
+    /// S3 API port, also the service port
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// Port of the web console
+    #[serde(default = "default_console_port")]
+    pub console_port: u16,
+}
+
+impl Default for MinioSettings {
+    fn default() -> Self {
+        Self { port: default_api_port(), console_port: default_console_port() }
+    }
+}
+
+fn default_api_port() -> u16 {
+    crate::process::ServiceType::Minio.default_port()
+}
+
+fn default_console_port() -> u16 { 9901 }
+
+impl MinioSettings {
+    /// S3 endpoint for SDKs
+    pub fn endpoint(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    /// Address of the web console
+    pub fn console_url(&self) -> String {
+        format!("http://127.0.0.1:{}/", self.console_port)
+    }
+}
+
+/// Root credentials, the access key and secret key of S3 clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinioCredentials {
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// State of MinIO for the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct MinioStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub endpoint: String,
+    pub console_url: String,
+    /// None until MinIO first starts
+    pub credentials: Option<MinioCredentials>,
+}
+
+/// MinIO's directory under the CAMPP base directory
+pub fn minio_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join("minio")
+}
+
+/// Directory with the buckets
+pub fn data_dir(minio_dir: &Path) -> PathBuf {
+    minio_dir.join("data")
+}
+
+fn credentials_path(minio_dir: &Path) -> PathBuf {
+    minio_dir.join("credentials.json")
+}
+
+/// Credentials in `minio_dir`, if they were generated
+pub fn read_credentials(minio_dir: &Path) -> Option<MinioCredentials> {
+    let content = fs::read_to_string(credentials_path(minio_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Credentials in `minio_dir`, generated and saved when there are none
+pub fn ensure_credentials(minio_dir: &Path) -> Result<MinioCredentials, String> {
+    if let Some(credentials) = read_credentials(minio_dir) {
+        return Ok(credentials);
+    }
+    let credentials = MinioCredentials { access_key: "campp".to_string(), secret_key: super::random_secret(32) };
+    let json = serde_json::to_string_pretty(&credentials).map_err(|e| e.to_string())?;
+    super::write_private_file(&credentials_path(minio_dir), &json)?;
+    Ok(credentials)
+}
+
+/// Command-line arguments of `minio`
+///
+/// The credentials are passed in `MINIO_ROOT_USER` and
+/// `MINIO_ROOT_PASSWORD`, they would show in the process list as arguments.
+pub fn command_args(settings: &MinioSettings, minio_dir: &Path) -> Vec<String> {
+    vec![
+        "server".into(),
+        data_dir(minio_dir).to_string_lossy().to_string(),
+        "--address".into(),
+        format!("127.0.0.1:{}", settings.port),
+        "--console-address".into(),
+        format!("127.0.0.1:{}", settings.console_port),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let settings = MinioSettings::default();
+        let args = command_args(&settings, Path::new("/opt/campp/minio"));
+        assert_eq!(args[0], "server");
+        assert_eq!(PathBuf::from(&args[1]), Path::new("/opt/campp/minio").join("data"));
+        assert_eq!(args[2..].join(" "), "--address 127.0.0.1:9900 --console-address 127.0.0.1:9901");
+        assert_eq!(settings.endpoint(), "http://127.0.0.1:9900");
+    }
+
+    #[test]
+    fn test_ensure_credentials() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let minio_dir = temp_dir.path().join("minio");
+        assert_eq!(read_credentials(&minio_dir), None);
+
+        let credentials = ensure_credentials(&minio_dir).unwrap();
+        assert_eq!(credentials.secret_key.len(), 32);
+        // Kept across starts
+        assert_eq!(ensure_credentials(&minio_dir).unwrap(), credentials);
+        assert_eq!(read_credentials(&minio_dir), Some(credentials));
+    }
+}
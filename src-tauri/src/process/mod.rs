@@ -1,5 +1,21 @@
+pub mod caddy_admin;
+pub mod config_watch;
+pub mod dns;
+pub mod drift;
+pub mod ftp;
+pub mod health;
 pub mod killer;
+pub mod logs;
+pub mod mailpit;
 pub mod manager;
+pub mod memcached;
+pub mod minio;
+pub mod npm;
+pub mod php_pool;
+pub mod power;
+pub mod rotation;
+pub mod suspend;
+pub mod tunnel;
 
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +28,16 @@ pub enum ServiceType {
     MySQL,
     #[serde(rename = "postgresql")]
     PostgreSQL,
+    /// Public tunnel to a project, started on demand
+    Tunnel,
+    /// SMTP catcher for mail sent by projects
+    Mailpit,
+    /// Optional cache server, from a package manager
+    Memcached,
+    /// S3-compatible storage
+    Minio,
+    /// SFTP and FTP access to the projects directory
+    Ftp,
 }
 
 impl ServiceType {
@@ -21,6 +47,16 @@ impl ServiceType {
             ServiceType::PhpFpm => 9000,
             ServiceType::MySQL => 3307,
             ServiceType::PostgreSQL => 5433,
+            // The tunnel client's local API, where the public URL is read
+            ServiceType::Tunnel => 20241,
+            // SMTP, the web UI has a port of its own
+            ServiceType::Mailpit => 1025,
+            ServiceType::Memcached => 11211,
+            // The S3 API, the web console has a port of its own. MinIO's own
+            // 9000 is taken by PHP.
+            ServiceType::Minio => 9900,
+            // SFTP, plain FTP has a port of its own
+            ServiceType::Ftp => 2022,
         }
     }
 
@@ -30,6 +66,11 @@ impl ServiceType {
             ServiceType::PhpFpm => "PHP-FPM 8.5",
             ServiceType::MySQL => "MySQL",
             ServiceType::PostgreSQL => "PostgreSQL",
+            ServiceType::Tunnel => "Tunnel",
+            ServiceType::Mailpit => "Mailpit",
+            ServiceType::Memcached => "Memcached",
+            ServiceType::Minio => "MinIO",
+            ServiceType::Ftp => "SFTP/FTP",
         }
     }
 
@@ -39,6 +80,11 @@ impl ServiceType {
             ServiceType::PhpFpm => "PHP Runtime",
             ServiceType::MySQL => "Database Server",
             ServiceType::PostgreSQL => "Database Server",
+            ServiceType::Tunnel => "Public Tunnel",
+            ServiceType::Mailpit => "Mail Catcher",
+            ServiceType::Memcached => "Cache Server",
+            ServiceType::Minio => "Object Storage",
+            ServiceType::Ftp => "File Transfer",
         }
     }
 
@@ -48,8 +94,79 @@ impl ServiceType {
             ServiceType::PhpFpm => "php-cgi",
             ServiceType::MySQL => "mysqld",
             ServiceType::PostgreSQL => "postgres",
+            ServiceType::Tunnel => "cloudflared",
+            ServiceType::Mailpit => "mailpit",
+            ServiceType::Memcached => "memcached",
+            ServiceType::Minio => "minio",
+            ServiceType::Ftp => "sftpgo",
         }
     }
+
+    /// Add-on services turned on and off with `set_service_enabled`
+    pub fn is_optional(&self) -> bool {
+        matches!(self, ServiceType::Mailpit | ServiceType::Memcached | ServiceType::Minio | ServiceType::Ftp)
+    }
+
+    /// Services in the order they should be started: backends first, Caddy last
+    ///
+    /// The tunnel is left out, it exposes a project publicly and is only
+    /// started on request.
+    pub fn start_order() -> [ServiceType; 8] {
+        [
+            ServiceType::MySQL,
+            ServiceType::PostgreSQL,
+            ServiceType::Memcached,
+            ServiceType::Minio,
+            ServiceType::Ftp,
+            ServiceType::Mailpit,
+            ServiceType::PhpFpm,
+            ServiceType::Caddy,
+        ]
+    }
+}
+
+/// Random alphanumeric secret for credentials CAMPP generates
+pub(crate) fn random_secret(len: usize) -> String {
+    const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| CHARSET[rand::Rng::gen_range(&mut rng, 0..CHARSET.len())] as char).collect()
+}
+
+/// Run `program` quietly, whether it succeeded
+///
+/// For probing the system before a change; a missing program is a failure.
+pub(crate) fn run_quiet(program: &str, args: &[&str]) -> bool {
+    use std::process::Stdio;
+    manager::configure_no_window(std::process::Command::new(program))
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Write a file only the current user can read, for generated credentials
+pub(crate) fn write_private_file(path: &std::path::Path, contents: &str) -> Result<(), String> {
+    use std::io::Write;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // An existing file keeps its mode on open
+        if path.exists() {
+            let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+        }
+    }
+    options.open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -60,6 +177,10 @@ pub enum ServiceState {
     Running,
     Stopping,
     Error,
+    /// Turned off in settings, excluded from start_all and config generation
+    Disabled,
+    /// Process is suspended, memory and connections are kept
+    Paused,
 }
 
 impl ServiceState {
@@ -72,6 +193,31 @@ impl ServiceState {
     }
 }
 
+/// How `restart_service` brings a service back up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartStrategy {
+    /// Ask the process to shut down cleanly, then start it again
+    #[default]
+    Graceful,
+    /// Kill the process without waiting for a clean shutdown
+    Force,
+    /// Apply a regenerated config without restarting (Caddy only)
+    ReloadConfig,
+    /// Replace workers while the master keeps serving (PHP-FPM only)
+    Rolling,
+}
+
+impl RestartStrategy {
+    pub fn supports(&self, service: ServiceType) -> bool {
+        match self {
+            RestartStrategy::Graceful | RestartStrategy::Force => true,
+            RestartStrategy::ReloadConfig => service == ServiceType::Caddy,
+            RestartStrategy::Rolling => service == ServiceType::PhpFpm,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceInfo {
     pub service_type: ServiceType,
@@ -94,11 +240,37 @@ impl ServiceInfo {
 
 pub type ServiceMap = std::collections::HashMap<ServiceType, ServiceInfo>;
 
+/// Event emitted by `ProcessManager` on every service state transition
+pub const STATE_CHANGED_EVENT: &str = "service-state-changed";
+
+/// How often exited service processes are detected in the background
+pub const HEALTH_CHECK_INTERVAL_SECS: u64 = 2;
+
+/// Payload of `service-state-changed`
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ServiceStateChange {
+    pub service: ServiceType,
+    pub old: ServiceState,
+    pub new: ServiceState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json;
 
+    #[test]
+    fn test_start_order_puts_caddy_last() {
+        let order = ServiceType::start_order();
+        assert_eq!(order.len(), 8);
+        assert_eq!(order[7], ServiceType::Caddy);
+        let php = order.iter().position(|s| *s == ServiceType::PhpFpm).unwrap();
+        let mysql = order.iter().position(|s| *s == ServiceType::MySQL).unwrap();
+        assert!(mysql < php);
+    }
+
     #[test]
     fn test_service_type_default_ports() {
         assert_eq!(ServiceType::Caddy.default_port(), 8080);
@@ -155,6 +327,18 @@ mod tests {
         assert!(!ServiceState::Error.is_transitioning());
     }
 
+    #[test]
+    fn test_restart_strategy_supports() {
+        assert!(RestartStrategy::Graceful.supports(ServiceType::MySQL));
+        assert!(RestartStrategy::ReloadConfig.supports(ServiceType::Caddy));
+        assert!(!RestartStrategy::ReloadConfig.supports(ServiceType::PhpFpm));
+        assert!(RestartStrategy::Rolling.supports(ServiceType::PhpFpm));
+        assert!(!RestartStrategy::Rolling.supports(ServiceType::PostgreSQL));
+
+        let strategy: RestartStrategy = serde_json::from_str("\"reload_config\"").unwrap();
+        assert_eq!(strategy, RestartStrategy::ReloadConfig);
+    }
+
     #[test]
     fn test_service_info_new() {
         let info = ServiceInfo::new(ServiceType::Caddy);
@@ -0,0 +1,249 @@
+//! npm scripts of projects
+//!
+//! Frontend tooling runs on the downloaded Node.js: `run_npm_script` starts
+//! `npm run <script>` in the project folder as a child of the process
+//! manager, with its output in a log of its own, and stops it with the
+//! services when CAMPP exits. npm is started through `node npm-cli.js` with
+//! Node's directory first on PATH, so neither needs a system install. A
+//! script serving the whole site, e.g. `npm run dev` of a frontend project,
+//! can name its port. Once its dev server listens the port is recorded as
+//! the project's `dev_server_port` and the site block proxies to it; the
+//! port is cleared again when the script stops or exits, and at startup,
+//! when no script runs.
+
+use serde::Serialize;
+use std::fs;
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use super::manager::configure_no_window;
+use crate::projects::{Project, ProjectRegistry};
+use crate::runtime::locator::RuntimePaths;
+
+/// A script started with `run_npm_script`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NpmScript {
+    pub project: String,
+    pub script: String,
+    pub pid: u32,
+    pub log_file: String,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    /// Port of the dev server the site proxies to
+    pub port: Option<u16>,
+}
+
+/// Running npm scripts, at most one per project and script name
+#[derive(Default)]
+pub struct NpmScripts {
+    running: Vec<(NpmScript, Child)>,
+}
+
+impl NpmScripts {
+    /// Start `npm run <script>` in `project`'s folder
+    pub fn start(&mut self, paths: &RuntimePaths, project: &Project, script: &str, port: Option<u16>) -> Result<NpmScript, String> {
+        self.reap();
+        if self.running.iter().any(|(s, _)| s.project == project.name && s.script == script) {
+            return Err(format!("npm run {} is already running for project '{}'", script, project.name));
+        }
+        if !package_scripts(Path::new(&project.path))?.iter().any(|s| s == script) {
+            return Err(format!("package.json of project '{}' has no '{}' script", project.name, script));
+        }
+        let node = node_binary(&paths.node_dir);
+        if !node.is_file() {
+            return Err("Node.js is not installed, install it from the runtime components".to_string());
+        }
+
+        let log_path = paths.logs_dir.join(log_file_name(&project.name, script));
+        let log_file = fs::File::create(&log_path)
+            .map_err(|e| format!("Failed to create {}: {}", log_path.display(), e))?;
+
+        let search_path = std::env::var_os("PATH").unwrap_or_default();
+        let search_path = std::env::join_paths(
+            std::iter::once(bin_dir(&paths.node_dir)).chain(std::env::split_paths(&search_path)),
+        ).map_err(|e| format!("Failed to set PATH for npm: {}", e))?;
+
+        let mut cmd = configure_no_window(Command::new(&node));
+        cmd.arg(npm_cli(&paths.node_dir))
+            .args(["run", script])
+            .env("PATH", search_path)
+            .current_dir(&project.path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::from(log_file.try_clone().map_err(|e| e.to_string())?))
+            .stderr(Stdio::from(log_file));
+        // Its own process group, so stopping it also stops the dev server
+        // npm started
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        let child = cmd.spawn()
+            .map_err(|e| format!("Failed to start npm run {}: {}", script, e))?;
+
+        let started = NpmScript {
+            project: project.name.clone(),
+            script: script.to_string(),
+            pid: child.id(),
+            log_file: log_path.to_string_lossy().to_string(),
+            started_at: crate::util::now(),
+            port,
+        };
+        self.running.push((started.clone(), child));
+        Ok(started)
+    }
+
+    /// Stop a running script with the processes it started
+    pub fn stop(&mut self, project: &str, script: &str) -> Result<NpmScript, String> {
+        self.reap();
+        let index = self.running.iter()
+            .position(|(s, _)| s.project == project && s.script == script)
+            .ok_or_else(|| format!("npm run {} is not running for project '{}'", script, project))?;
+        let (stopped, child) = self.running.remove(index);
+        stop_child(child);
+        Ok(stopped)
+    }
+
+    /// Stop every running script
+    pub fn stop_all(&mut self) {
+        for (_, child) in self.running.drain(..) {
+            stop_child(child);
+        }
+    }
+
+    /// Scripts still running; finished ones are forgotten
+    pub fn list(&mut self) -> Vec<NpmScript> {
+        self.reap();
+        self.running.iter().map(|(s, _)| s.clone()).collect()
+    }
+
+    /// Whether the script started as process `pid` is still running
+    pub fn is_running(&mut self, pid: u32) -> bool {
+        self.reap();
+        self.running.iter().any(|(s, _)| s.pid == pid)
+    }
+
+    fn reap(&mut self) {
+        self.running.retain_mut(|(_, child)| matches!(child.try_wait(), Ok(None)));
+    }
+}
+
+impl Drop for NpmScripts {
+    fn drop(&mut self) {
+        self.stop_all();
+    }
+}
+
+fn stop_child(mut child: Child) {
+    if let Err(e) = super::killer::kill_tree(child.id()) {
+        tracing::warn!("{}", e);
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
+
+/// Names of the scripts in the project's package.json
+pub fn package_scripts(project_dir: &Path) -> Result<Vec<String>, String> {
+    let path = project_dir.join("package.json");
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    Ok(json["scripts"].as_object().map(|scripts| scripts.keys().cloned().collect()).unwrap_or_default())
+}
+
+/// Log of a script, e.g. `npm-shop-dev.log`
+pub fn log_file_name(project: &str, script: &str) -> String {
+    let clean = |s: &str| -> String {
+        s.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+    };
+    format!("npm-{}-{}.log", clean(project), clean(script))
+}
+
+/// Record the dev server the site of `project` proxies to, None to serve
+/// it with PHP again; returns whether the registry changed
+pub fn set_dev_server_port(registry: &ProjectRegistry, project: &str, port: Option<u16>) -> Result<bool, String> {
+    let mut project = registry.get(project)?;
+    if project.dev_server_port == port {
+        return Ok(false);
+    }
+    project.dev_server_port = port;
+    registry.insert(project)?;
+    Ok(true)
+}
+
+/// Clear the dev server ports left behind when CAMPP last exited
+pub fn forget_dev_servers(registry: &ProjectRegistry) -> Result<(), String> {
+    for project in registry.list()?.into_iter().filter(|p| p.dev_server_port.is_some()) {
+        set_dev_server_port(registry, &project.name, None)?;
+    }
+    Ok(())
+}
+
+/// Whether a dev server listens on `port` on loopback, over IPv4 or IPv6
+pub fn is_listening(port: u16) -> bool {
+    ["127.0.0.1", "::1"].iter().any(|ip| {
+        let addr = SocketAddr::new(ip.parse().unwrap(), port);
+        TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok()
+    })
+}
+
+/// Directory with the node binary, put first on PATH
+fn bin_dir(node_dir: &Path) -> PathBuf {
+    if cfg!(windows) { node_dir.to_path_buf() } else { node_dir.join("bin") }
+}
+
+fn node_binary(node_dir: &Path) -> PathBuf {
+    bin_dir(node_dir).join(if cfg!(windows) { "node.exe" } else { "node" })
+}
+
+fn npm_cli(node_dir: &Path) -> PathBuf {
+    let modules = if cfg!(windows) { node_dir.join("node_modules") } else { node_dir.join("lib").join("node_modules") };
+    modules.join("npm").join("bin").join("npm-cli.js")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_scripts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(package_scripts(temp_dir.path()).is_err());
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "shop", "scripts": {"dev": "vite", "build": "vite build"}}"#,
+        ).unwrap();
+        assert_eq!(package_scripts(temp_dir.path()).unwrap(), vec!["build".to_string(), "dev".to_string()]);
+    }
+
+    #[test]
+    fn test_log_file_name() {
+        assert_eq!(log_file_name("shop", "dev"), "npm-shop-dev.log");
+        assert_eq!(log_file_name("shop", "build:css"), "npm-shop-build_css.log");
+    }
+
+    #[test]
+    fn test_dev_server_port() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let registry = ProjectRegistry::at(temp_dir.path().join("projects.json"));
+        registry.insert(Project { name: "shop".to_string(), ..Default::default() }).unwrap();
+
+        assert!(set_dev_server_port(&registry, "shop", Some(5173)).unwrap());
+        assert!(!set_dev_server_port(&registry, "shop", Some(5173)).unwrap());
+        assert_eq!(registry.get("shop").unwrap().dev_server_port, Some(5173));
+        forget_dev_servers(&registry).unwrap();
+        assert_eq!(registry.get("shop").unwrap().dev_server_port, None);
+    }
+
+    #[test]
+    fn test_is_listening() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(is_listening(port));
+        drop(listener);
+        assert!(!is_listening(port));
+    }
+}
@@ -0,0 +1,206 @@
+//! Extra PHP versions for pinned projects
+//!
+//! Besides the main PHP service, settings can list more PHP binaries, e.g. a
+//! system `php-fpm8.1` next to the bundled 8.4. Each one runs as its own
+//! FastCGI listener and lives and dies with the main PHP service. Listeners
+//! take the first free port after `php_port`, skipping the ports of the other
+//! services, and write to their own log and session directory,
+//! `php-<version>.log` and `php-sessions-<version>`. A project pinned to one
+//! of their versions gets its site block routed to that listener; every other
+//! project keeps using the main PHP.
+//!
+//! The listeners are not watched by the health monitor: one that crashes
+//! stays down, with its sites answering 502, until PHP is restarted.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use super::manager::configure_no_window;
+use crate::config::AppSettings;
+use crate::runtime::locator::RuntimePaths;
+
+/// Ports the running listeners were started on, by version
+static PORTS: Mutex<Vec<(String, u16)>> = Mutex::new(Vec::new());
+
+/// An additional PHP FastCGI binary, stored in settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhpBackend {
+    /// Major and minor version the binary reported, e.g. "8.1"
+    pub version: String,
+    /// php-fpm, or php-cgi on Windows
+    pub binary: PathBuf,
+}
+
+/// Port of the backend serving `version`, with the main PHP on `php_port`
+///
+/// The port the listener runs on, or the one it would try first when it is
+/// not running. "8.1" and "8.1.30" both select the 8.1 backend.
+pub fn backend_port(settings: &AppSettings, php_port: u16, version: &str) -> Option<u16> {
+    let wanted = major_minor(version);
+    let i = settings.php_versions.iter().position(|backend| backend.version == wanted)?;
+    let running = PORTS.lock().ok()
+        .and_then(|ports| ports.iter().find(|(v, _)| *v == wanted).map(|(_, port)| *port));
+    Some(running.unwrap_or(php_port + 1 + i as u16))
+}
+
+/// Ports of the other services, never handed to a listener
+fn service_ports(settings: &AppSettings) -> Vec<u16> {
+    vec![
+        settings.web_port,
+        settings.https_port,
+        settings.php_port,
+        settings.mysql_port,
+        settings.postgres_port,
+        settings.tunnel.port,
+        settings.mailpit.smtp_port,
+        settings.mailpit.ui_port,
+        settings.memcached.port,
+        settings.minio.port,
+        settings.minio.console_port,
+        settings.ftp.sftp_port,
+        settings.ftp.ftp_port,
+    ]
+}
+
+/// First port after `after` that is free and not in `taken`
+fn free_port(after: u16, taken: &[u16]) -> Option<u16> {
+    (after.checked_add(1)?..u16::MAX)
+        .find(|port| !taken.contains(port) && crate::config::ports::is_port_available(*port))
+}
+
+/// "8.1" from "8.1.30"
+pub fn major_minor(version: &str) -> String {
+    version.trim().split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+/// Listeners of the extra PHP versions
+#[derive(Default)]
+pub struct PhpPool {
+    children: Vec<(String, Child)>,
+}
+
+impl PhpPool {
+    /// Start a listener per backend in `settings`
+    ///
+    /// Returns the versions that failed to start with the reason; the main
+    /// PHP service is up either way.
+    pub fn start(&mut self, paths: &RuntimePaths, settings: &AppSettings, php_port: u16) -> Vec<(String, String)> {
+        self.stop();
+        let mut taken = service_ports(settings);
+        taken.push(php_port);
+        let mut ports = Vec::new();
+        let mut failures = Vec::new();
+        let mut after = php_port;
+        for backend in &settings.php_versions {
+            let Some(port) = free_port(after, &taken) else {
+                failures.push((backend.version.clone(), format!("No free port after {}", after)));
+                continue;
+            };
+            taken.push(port);
+            after = port;
+            match start_backend(paths, backend, port) {
+                Ok(child) => {
+                    self.children.push((backend.version.clone(), child));
+                    ports.push((backend.version.clone(), port));
+                }
+                Err(e) => failures.push((backend.version.clone(), e)),
+            }
+        }
+        if let Ok(mut running) = PORTS.lock() {
+            *running = ports;
+        }
+        failures
+    }
+
+    pub fn stop(&mut self) {
+        if let Ok(mut running) = PORTS.lock() {
+            running.clear();
+        }
+        for (_, mut child) in self.children.drain(..) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for PhpPool {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Run `backend` in the foreground on `port`
+///
+/// CAMPP's php.ini is not passed, its `extension_dir` belongs to the main
+/// PHP; the binary reads its own, with the error log and session directory
+/// overridden.
+fn start_backend(paths: &RuntimePaths, backend: &PhpBackend, port: u16) -> Result<Child, String> {
+    let log_name = format!("php-{}.log", backend.version);
+    let log_path = paths.logs_dir.join(&log_name);
+    let log_file = File::create(&log_path)
+        .map_err(|e| format!("Failed to create {}: {}", log_path.display(), e))?;
+    let sessions_dir = format!("php-sessions-{}", backend.version);
+    let sessions_path = paths.logs_dir.join(&sessions_dir);
+    std::fs::create_dir_all(&sessions_path)
+        .map_err(|e| format!("Failed to create {}: {}", sessions_path.display(), e))?;
+
+    let is_fpm = backend.binary.file_name().is_some_and(|n| n.to_string_lossy().starts_with("php-fpm"));
+    let mut cmd = configure_no_window(Command::new(&backend.binary));
+    if is_fpm {
+        let conf = paths.config_dir.join(format!("php-fpm-{}.conf", backend.version));
+        let content = crate::config::generator::php_fpm_listener_conf(paths, port, &log_name, &sessions_dir);
+        std::fs::write(&conf, content)
+            .map_err(|e| format!("Failed to write {}: {}", conf.display(), e))?;
+        cmd.arg("-F").arg("-y").arg(&conf);
+    } else {
+        cmd.arg("-b").arg(format!("127.0.0.1:{}", port))
+            .arg("-d").arg(format!("error_log={}", log_path.display()))
+            .arg("-d").arg(format!("session.save_path={}", sessions_path.display()));
+    }
+
+    let mut child = cmd
+        .current_dir(&paths.config_dir)
+        .stdout(Stdio::from(log_file.try_clone().map_err(|e| e.to_string())?))
+        .stderr(Stdio::from(log_file))
+        .spawn()
+        .map_err(|e| format!("Failed to start PHP {}: {}", backend.version, e))?;
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    match child.try_wait() {
+        Ok(Some(status)) => Err(format!("PHP {} exited immediately with status: {:?}", backend.version, status)),
+        Ok(None) => Ok(child),
+        Err(e) => Err(format!("Failed to check PHP {} process: {}", backend.version, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_port() {
+        let settings = AppSettings {
+            php_versions: vec![
+                PhpBackend { version: "8.1".to_string(), binary: PathBuf::from("/usr/sbin/php-fpm8.1") },
+                PhpBackend { version: "7.4".to_string(), binary: PathBuf::from("/usr/sbin/php-fpm7.4") },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(backend_port(&settings, 9000, "8.1"), Some(9001));
+        assert_eq!(backend_port(&settings, 9000, "7.4.33"), Some(9002));
+        assert_eq!(backend_port(&settings, 9000, "8.4"), None);
+        assert_eq!(major_minor("8.3.14"), "8.3");
+    }
+
+    #[test]
+    fn test_free_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let busy = listener.local_addr().unwrap().port();
+        let port = free_port(busy - 1, &[]).unwrap();
+        assert!(port > busy);
+        assert_ne!(free_port(busy - 1, &[port]), Some(port));
+    }
+}
@@ -0,0 +1,112 @@
+//! Keep the machine awake during long jobs
+//!
+//! `SleepInhibitor::acquire` takes an OS sleep-inhibition lock that is held
+//! until the guard is dropped:
+//! - Windows: `SetThreadExecutionState` on a dedicated thread
+//! - macOS: a `caffeinate -i` child process
+//! - Linux: a `systemd-inhibit --what=sleep` child process
+//!
+//! Failing to inhibit sleep is never fatal, the job just runs unprotected.
+
+#[cfg(unix)]
+use std::process::{Child, Command, Stdio};
+
+pub struct SleepInhibitor {
+    reason: String,
+    #[cfg(unix)]
+    child: Option<Child>,
+    #[cfg(windows)]
+    release: Option<std::sync::mpsc::Sender<()>>,
+}
+
+impl SleepInhibitor {
+    /// Prevent system sleep until the returned guard is dropped
+    pub fn acquire(reason: &str) -> Self {
+        let mut inhibitor = Self {
+            reason: reason.to_string(),
+            #[cfg(unix)]
+            child: None,
+            #[cfg(windows)]
+            release: None,
+        };
+
+        match inhibitor.inhibit() {
+            Ok(()) => tracing::debug!("Sleep inhibited: {}", reason),
+            Err(e) => tracing::warn!("Could not prevent sleep during '{}': {}", reason, e),
+        }
+        inhibitor
+    }
+
+    #[cfg(target_os = "macos")]
+    fn inhibit(&mut self) -> Result<(), String> {
+        // -w exits caffeinate on its own if CAMPP dies
+        let child = Command::new("caffeinate")
+            .arg("-i")
+            .arg("-w")
+            .arg(std::process::id().to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to run caffeinate: {}", e))?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn inhibit(&mut self) -> Result<(), String> {
+        let child = Command::new("systemd-inhibit")
+            .arg("--what=sleep:idle")
+            .arg("--who=CAMPP")
+            .arg(format!("--why={}", self.reason))
+            .arg("--mode=block")
+            .arg("sleep")
+            .arg("infinity")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to run systemd-inhibit: {}", e))?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn inhibit(&mut self) -> Result<(), String> {
+        const ES_CONTINUOUS: u32 = 0x8000_0000;
+        const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn SetThreadExecutionState(flags: u32) -> u32;
+        }
+
+        // The execution state belongs to the calling thread, so hold it on a
+        // thread of our own rather than whichever worker runs the job
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        std::thread::Builder::new()
+            .name("campp-sleep-inhibitor".to_string())
+            .spawn(move || {
+                // SAFETY: plain Win32 call without pointers
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+                // Returns when the sender is dropped
+                let _ = rx.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            })
+            .map_err(|e| format!("Failed to spawn inhibitor thread: {}", e))?;
+        self.release = Some(tx);
+        Ok(())
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        #[cfg(windows)]
+        drop(self.release.take());
+
+        tracing::debug!("Sleep inhibition released: {}", self.reason);
+    }
+}
@@ -0,0 +1,323 @@
+//! Log rotation
+//!
+//! A log is rotated once it grows past the size limit or has been written to
+//! for longer than the age limit. Rotation copies the file to
+//! `<name>.<unix seconds>` (gzipped to `<name>.<unix seconds>.gz`) and
+//! truncates the original, so services keep writing through the handle they
+//! already hold. Only the newest `keep` rotated files of each log are kept.
+//!
+//! Service logs are rotated when their service starts, and every log in the
+//! logs directory by the scheduler in lib.rs.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::ServiceType;
+
+/// Interval between rotation checks of the whole logs directory
+pub const ROTATION_INTERVAL_SECS: u64 = 10 * 60;
+
+fn default_max_size_mb() -> u64 { 10 }
+fn default_max_age_days() -> u64 { 7 }
+fn default_keep() -> usize { 5 }
+
+/// Log rotation policy stored in settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRotationSettings {
+    #[serde(default = "crate::util::default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Rotate a log that has been written to for this long, 0 disables
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u64,
+    /// Rotated files kept per log
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+    #[serde(default = "crate::util::default_true")]
+    pub compress: bool,
+}
+
+impl Default for LogRotationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: default_max_size_mb(),
+            max_age_days: default_max_age_days(),
+            keep: default_keep(),
+            compress: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RotationReport {
+    pub rotated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Whether `file_name` is a log written by `service`
+fn belongs_to(file_name: &str, service: ServiceType) -> bool {
+    if file_name == super::logs::log_file_name(service) {
+        return true;
+    }
+    // Access logs are written by Caddy itself, shared or one per site
+    service == ServiceType::Caddy
+        && (file_name == "caddy-access.log" || (file_name.starts_with("access-") && file_name.ends_with(".log")))
+}
+
+/// Rotate the logs of `service`, called before it starts
+pub fn rotate_service_logs(logs_dir: &Path, service: ServiceType, policy: &LogRotationSettings) -> RotationReport {
+    rotate_matching(logs_dir, policy, SystemTime::now(), |name| belongs_to(name, service))
+}
+
+/// Rotate every log in the logs directory that is due
+pub fn rotate_all(logs_dir: &Path, policy: &LogRotationSettings) -> RotationReport {
+    rotate_matching(logs_dir, policy, SystemTime::now(), |name| name.ends_with(".log"))
+}
+
+fn rotate_matching(logs_dir: &Path, policy: &LogRotationSettings, now: SystemTime, matches: impl Fn(&str) -> bool) -> RotationReport {
+    let mut report = RotationReport::default();
+    if !policy.enabled {
+        return report;
+    }
+    let Ok(read_dir) = fs::read_dir(logs_dir) else { return report };
+
+    let logs: Vec<String> = read_dir
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| matches(name))
+        .collect();
+
+    for name in logs {
+        let path = logs_dir.join(&name);
+        match rotate_if_due(&path, policy, now) {
+            Ok(Some(rotated)) => report.rotated.push(rotated),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to rotate {}: {}", name, e),
+        }
+        report.removed.extend(prune(logs_dir, &name, policy.keep));
+    }
+    report
+}
+
+/// Empty the logs of `service`, returning the names of the files cleared
+///
+/// With `archive`, the current contents are rotated away under that policy
+/// instead of being discarded. Running services keep their append handles
+/// and continue at the start of the emptied file.
+pub fn clear_service_logs(logs_dir: &Path, service: ServiceType, archive: Option<&LogRotationSettings>) -> Result<Vec<String>, String> {
+    let read_dir = match fs::read_dir(logs_dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read logs directory: {}", e)),
+    };
+    let logs: Vec<String> = read_dir
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| belongs_to(name, service))
+        .collect();
+
+    let mut cleared = Vec::new();
+    for name in logs {
+        let path = logs_dir.join(&name);
+        if fs::metadata(&path).map(|m| m.len()).unwrap_or(0) == 0 {
+            continue;
+        }
+        match archive {
+            Some(policy) => {
+                rotate(&path, policy.compress, SystemTime::now())?;
+                prune(logs_dir, &name, policy.keep);
+            }
+            None => {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&path)
+                    .and_then(|file| file.set_len(0))
+                    .map_err(|e| format!("Failed to truncate {}: {}", name, e))?;
+            }
+        }
+        cleared.push(name);
+    }
+    Ok(cleared)
+}
+
+/// Rotate the log at `path` if it is over the size or age limit, returning
+/// the name of the rotated file
+fn rotate_if_due(path: &Path, policy: &LogRotationSettings, now: SystemTime) -> Result<Option<String>, String> {
+    let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+    if metadata.len() == 0 {
+        return Ok(None);
+    }
+
+    let too_big = metadata.len() > policy.max_size_mb.saturating_mul(1024 * 1024);
+    let too_old = policy.max_age_days > 0 && {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dir = path.parent().unwrap_or(Path::new("."));
+        // Truncating keeps the creation time, the last rotation marks the start instead
+        let since = archives(dir, &name)
+            .last()
+            .map(|(secs, _)| UNIX_EPOCH + Duration::from_secs(*secs))
+            .or_else(|| metadata.created().ok());
+        let max_age = Duration::from_secs(policy.max_age_days.saturating_mul(24 * 60 * 60));
+        since.is_some_and(|since| now.duration_since(since).is_ok_and(|age| age > max_age))
+    };
+    if !too_big && !too_old {
+        return Ok(None);
+    }
+
+    rotate(path, policy.compress, now).map(Some)
+}
+
+/// Copy the log aside and truncate it, gzipping the copy with `compress`
+fn rotate(path: &Path, compress: bool, now: SystemTime) -> Result<String, String> {
+    let secs = now.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let archive_name = if compress { format!("{}.{}.gz", name, secs) } else { format!("{}.{}", name, secs) };
+    let archive = path.with_file_name(&archive_name);
+    if archive.exists() {
+        return Err(format!("{} already exists", archive_name));
+    }
+
+    let result = if compress { gzip_to(path, &archive) } else { fs::copy(path, &archive).map(|_| ()) };
+    if let Err(e) = result {
+        let _ = fs::remove_file(&archive);
+        return Err(format!("Failed to write {}: {}", archive_name, e));
+    }
+
+    // Services append, so they continue at the start of the emptied file
+    OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_len(0))
+        .map_err(|e| format!("Failed to truncate {}: {}", name, e))?;
+
+    tracing::info!("Rotated {} to {}", name, archive_name);
+    Ok(archive_name)
+}
+
+fn gzip_to(src: &Path, dest: &Path) -> std::io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let mut reader = BufReader::new(File::open(src)?);
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(dest)?), Compression::default());
+    std::io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?.into_inner().map_err(|e| e.into_error())?.sync_all()
+}
+
+/// Rotated files of the log `name`, oldest first
+fn archives(dir: &Path, name: &str) -> Vec<(u64, PathBuf)> {
+    let Ok(read_dir) = fs::read_dir(dir) else { return Vec::new() };
+    let prefix = format!("{}.", name);
+
+    let mut found: Vec<(u64, PathBuf)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let rest = file_name.strip_prefix(&prefix)?;
+            let secs = rest.strip_suffix(".gz").unwrap_or(rest).parse().ok()?;
+            Some((secs, entry.path()))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// Remove all but the newest `keep` rotated files of the log `name`
+fn prune(dir: &Path, name: &str, keep: usize) -> Vec<String> {
+    let found = archives(dir, name);
+    let excess = found.len().saturating_sub(keep);
+
+    let mut removed = Vec::new();
+    for (_, path) in found.into_iter().take(excess) {
+        match fs::remove_file(&path) {
+            Ok(()) => removed.push(path.file_name().unwrap_or_default().to_string_lossy().to_string()),
+            Err(e) => tracing::warn!("Failed to remove {}: {}", path.display(), e),
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotate_by_size_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let log = dir.join("caddy.log");
+        let policy = LogRotationSettings { max_size_mb: 0, keep: 2, ..Default::default() };
+
+        let start = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+        for i in 0..3 {
+            fs::write(&log, format!("run {}\n", i)).unwrap();
+            let report = rotate_matching(dir, &policy, start + Duration::from_secs(i), |n| belongs_to(n, ServiceType::Caddy));
+            assert_eq!(report.rotated, vec![format!("caddy.log.{}.gz", 1_760_000_000 + i)]);
+        }
+        assert_eq!(fs::metadata(&log).unwrap().len(), 0);
+
+        let kept = archives(dir, "caddy.log");
+        assert_eq!(kept.len(), 2);
+        let mut content = String::new();
+        flate2::read::GzDecoder::new(File::open(&kept[1].1).unwrap()).read_to_string(&mut content).unwrap();
+        assert_eq!(content, "run 2\n");
+
+        // Empty logs are left alone
+        let report = rotate_all(dir, &policy);
+        assert!(report.rotated.is_empty());
+    }
+
+    #[test]
+    fn test_rotate_by_age_after_last_rotation() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let log = dir.join("php-fpm.log");
+        fs::write(&log, "small\n").unwrap();
+        fs::write(dir.join("php-fpm.log.1760000000"), "old\n").unwrap();
+        let policy = LogRotationSettings { compress: false, ..Default::default() };
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let rotated = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+        assert_eq!(rotate_if_due(&log, &policy, rotated + day).unwrap(), None);
+
+        let name = rotate_if_due(&log, &policy, rotated + day * 8).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(dir.join(name)).unwrap(), "small\n");
+    }
+
+    #[test]
+    fn test_clear_service_logs() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("caddy.log"), "started\n").unwrap();
+        fs::write(dir.join("caddy-access.log"), "{}\n").unwrap();
+        fs::write(dir.join("mysql.log"), "ready\n").unwrap();
+
+        let mut cleared = clear_service_logs(dir, ServiceType::Caddy, None).unwrap();
+        cleared.sort();
+        assert_eq!(cleared, vec!["caddy-access.log", "caddy.log"]);
+        assert_eq!(fs::metadata(dir.join("caddy.log")).unwrap().len(), 0);
+        assert_eq!(fs::read_to_string(dir.join("mysql.log")).unwrap(), "ready\n");
+
+        let policy = LogRotationSettings::default();
+        assert_eq!(clear_service_logs(dir, ServiceType::MySQL, Some(&policy)).unwrap(), vec!["mysql.log"]);
+        assert_eq!(archives(dir, "mysql.log").len(), 1);
+    }
+
+    #[test]
+    fn test_belongs_to() {
+        assert!(belongs_to("caddy-access.log", ServiceType::Caddy));
+        assert!(belongs_to("access-localhost.log", ServiceType::Caddy));
+        assert!(belongs_to("mysql.log", ServiceType::MySQL));
+        assert!(!belongs_to("caddy.log.1760000000.gz", ServiceType::Caddy));
+        assert!(!belongs_to("php-fpm.log", ServiceType::MySQL));
+    }
+}
@@ -0,0 +1,101 @@
+//! Suspend and resume service processes without stopping them
+//!
+//! On Unix the process and its direct children (php-fpm workers, postgres
+//! backends) receive SIGSTOP/SIGCONT. On Windows the whole process is
+//! suspended with `NtSuspendProcess`, which freezes every thread.
+
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+
+/// Freeze a process, keeping its memory and open connections
+pub fn suspend_process(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        // Children first, so the parent never sees a half-frozen pool
+        signal_children(pid, "STOP");
+        signal(pid, "STOP")
+    }
+
+    #[cfg(windows)]
+    {
+        windows::suspend(pid, true)
+    }
+}
+
+/// Thaw a process frozen with `suspend_process`
+pub fn resume_process(pid: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        signal(pid, "CONT")?;
+        signal_children(pid, "CONT");
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    {
+        windows::suspend(pid, false)
+    }
+}
+
+#[cfg(unix)]
+pub(super) fn signal(pid: u32, signal: &str) -> Result<(), String> {
+    let status = Command::new("kill")
+        .arg(format!("-{}", signal))
+        .arg(pid.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run kill: {}", e))?;
+    if !status.success() {
+        return Err(format!("Failed to send SIG{} to process {}", signal, pid));
+    }
+    Ok(())
+}
+
+/// Best effort, a process without children makes pkill exit non-zero
+#[cfg(unix)]
+fn signal_children(pid: u32, signal: &str) {
+    let _ = Command::new("pkill")
+        .arg(format!("-{}", signal))
+        .arg("-P")
+        .arg(pid.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+
+    const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(desired_access: u32, inherit_handle: i32, process_id: u32) -> *mut c_void;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSuspendProcess(handle: *mut c_void) -> i32;
+        fn NtResumeProcess(handle: *mut c_void) -> i32;
+    }
+
+    pub fn suspend(pid: u32, suspend: bool) -> Result<(), String> {
+        // SAFETY: the handle is checked for null and closed before returning
+        unsafe {
+            let handle = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+            if handle.is_null() {
+                return Err(format!("Failed to open process {}", pid));
+            }
+            let status = if suspend { NtSuspendProcess(handle) } else { NtResumeProcess(handle) };
+            CloseHandle(handle);
+            if status < 0 {
+                return Err(format!("Failed to {} process {}: NTSTATUS {:#x}",
+                    if suspend { "suspend" } else { "resume" }, pid, status));
+            }
+        }
+        Ok(())
+    }
+}
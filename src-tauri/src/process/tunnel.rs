@@ -0,0 +1,331 @@
+//! Public tunnels to a project
+//!
+//! Webhooks from Stripe, GitHub and the like need a URL on the internet. The
+//! Tunnel service forwards one to a project's site on Caddy, sending the
+//! project's host name so Caddy picks the right site. cloudflared opens a
+//! quick tunnel on a random `trycloudflare.com` name without an account and
+//! is downloaded like the other components; ngrok needs an authtoken and is
+//! taken from PATH. The authtoken is kept in `config/ngrok-authtoken`, readable
+//! only by the user, not in settings, so exports and settings events never
+//! carry it. Both report the public URL on a local API once the
+//! tunnel is up, which is polled after the start and sent to the frontend as
+//! an event.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::AppSettings;
+use crate::projects::Project;
+
+/// Emitted with a `TunnelUrl` once the public URL is known
+pub const TUNNEL_URL_EVENT: &str = "tunnel-url";
+
+/// How long a new tunnel may take to get its public URL
+pub const PUBLIC_URL_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelProvider {
+    #[default]
+    Cloudflared,
+    Ngrok,
+}
+
+impl TunnelProvider {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TunnelProvider::Cloudflared => "cloudflared",
+            TunnelProvider::Ngrok => "ngrok",
+        }
+    }
+}
+
+/// Public tunnel options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TunnelSettings {
+    #[serde(default)]
+    pub provider: TunnelProvider,
+    /// Project the tunnel forwards to, None for the localhost site
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Port of the client's local API on 127.0.0.1
+    #[serde(default = "default_tunnel_port")]
+    pub port: u16,
+    /// Authtoken saved in settings by earlier versions, moved to its own
+    /// file at startup
+    #[serde(default, rename = "ngrok_authtoken", skip_serializing)]
+    pub legacy_ngrok_authtoken: String,
+    /// ngrok binary, looked up on PATH when unset
+    #[serde(default)]
+    pub ngrok_binary: Option<PathBuf>,
+}
+
+impl Default for TunnelSettings {
+    fn default() -> Self {
+        Self {
+            provider: TunnelProvider::default(),
+            project: None,
+            port: default_tunnel_port(),
+            legacy_ngrok_authtoken: String::new(),
+            ngrok_binary: None,
+        }
+    }
+}
+
+fn default_tunnel_port() -> u16 {
+    crate::process::ServiceType::Tunnel.default_port()
+}
+
+/// Payload of `TUNNEL_URL_EVENT`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TunnelUrl {
+    pub project: Option<String>,
+    pub url: String,
+}
+
+/// State of the tunnel for the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct TunnelStatus {
+    pub provider: TunnelProvider,
+    pub project: Option<String>,
+    pub running: bool,
+    /// Public URL, None until the tunnel is up
+    pub url: Option<String>,
+    pub has_ngrok_authtoken: bool,
+}
+
+/// Site on Caddy a tunnel forwards to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub url: String,
+    /// Host header and, over HTTPS, the TLS server name
+    pub host: String,
+    pub https: bool,
+}
+
+impl Origin {
+    /// The project's site, or the localhost site without one
+    pub fn new(project: Option<&Project>, settings: &AppSettings) -> Self {
+        match project {
+            Some(project) if project.https => Self {
+                url: format!("https://127.0.0.1:{}", settings.https_port),
+                host: crate::projects::site_host(&project.name),
+                https: true,
+            },
+            Some(project) => Self {
+                url: format!("http://127.0.0.1:{}", settings.web_port),
+                host: crate::projects::site_host(&project.name),
+                https: false,
+            },
+            None => Self {
+                url: format!("http://127.0.0.1:{}", settings.web_port),
+                host: "localhost".to_string(),
+                https: false,
+            },
+        }
+    }
+}
+
+/// Binary started for the tunnel: the downloaded cloudflared, or ngrok from
+/// settings or PATH
+pub fn binary(settings: &TunnelSettings, cloudflared: &Path) -> PathBuf {
+    match settings.provider {
+        TunnelProvider::Cloudflared => cloudflared.to_path_buf(),
+        TunnelProvider::Ngrok => settings.ngrok_binary.clone()
+            .or_else(|| crate::runtime::system::candidates(&["ngrok"]).into_iter().next())
+            .unwrap_or_else(|| PathBuf::from(if cfg!(windows) { "ngrok.exe" } else { "ngrok" })),
+    }
+}
+
+fn authtoken_path(config_dir: &Path) -> PathBuf {
+    config_dir.join("ngrok-authtoken")
+}
+
+/// The saved ngrok authtoken, if there is one
+pub fn read_authtoken(config_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(authtoken_path(config_dir)).ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Save the ngrok authtoken, an empty one removes it
+pub fn save_authtoken(config_dir: &Path, token: &str) -> Result<(), String> {
+    let path = authtoken_path(config_dir);
+    match token.trim() {
+        "" => match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {}: {}", path.display(), e)),
+            _ => Ok(()),
+        },
+        token => crate::process::write_private_file(&path, token),
+    }
+}
+
+/// Move an authtoken saved in settings by an earlier version to its file
+pub fn migrate_authtoken(config_dir: &Path) -> Result<(), String> {
+    let mut settings = AppSettings::load();
+    if settings.tunnel.legacy_ngrok_authtoken.trim().is_empty() {
+        return Ok(());
+    }
+    if read_authtoken(config_dir).is_none() {
+        save_authtoken(config_dir, &settings.tunnel.legacy_ngrok_authtoken)?;
+    }
+    settings.tunnel.legacy_ngrok_authtoken.clear();
+    settings.save()
+}
+
+/// Command-line arguments of the tunnel client
+///
+/// `api_port` is where the client reports the public URL; ngrok reads it and
+/// the authtoken from `ngrok_config`.
+pub fn command_args(provider: TunnelProvider, origin: &Origin, api_port: u16, ngrok_config: &Path) -> Vec<String> {
+    let mut args: Vec<String> = match provider {
+        TunnelProvider::Cloudflared => vec![
+            "tunnel".into(),
+            "--no-autoupdate".into(),
+            "--url".into(),
+            origin.url.clone(),
+            "--http-host-header".into(),
+            origin.host.clone(),
+            "--metrics".into(),
+            format!("127.0.0.1:{}", api_port),
+        ],
+        TunnelProvider::Ngrok => vec![
+            "http".into(),
+            origin.url.clone(),
+            format!("--host-header={}", origin.host),
+            "--config".into(),
+            ngrok_config.to_string_lossy().to_string(),
+            "--log".into(),
+            "stdout".into(),
+        ],
+    };
+    // The site certificate comes from the local CA, which the tunnel
+    // client does not trust
+    if origin.https && provider == TunnelProvider::Cloudflared {
+        args.extend(["--origin-server-name".into(), origin.host.clone(), "--no-tls-verify".into()]);
+    }
+    args
+}
+
+/// ngrok configuration with the authtoken and the local API address
+pub fn ngrok_config(authtoken: &str, api_port: u16) -> String {
+    format!("version: \"2\"\nauthtoken: {}\nweb_addr: 127.0.0.1:{}\n", authtoken, api_port)
+}
+
+/// Path of the local API endpoint that reports the public URL
+fn api_path(provider: TunnelProvider) -> &'static str {
+    match provider {
+        TunnelProvider::Cloudflared => "/quicktunnel",
+        TunnelProvider::Ngrok => "/api/tunnels",
+    }
+}
+
+/// Public URL in a response of the local API
+pub fn parse_public_url(provider: TunnelProvider, body: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    match provider {
+        TunnelProvider::Cloudflared => json["hostname"].as_str()
+            .filter(|host| !host.is_empty())
+            .map(|host| format!("https://{}", host)),
+        TunnelProvider::Ngrok => {
+            let urls: Vec<&str> = json["tunnels"].as_array()?
+                .iter()
+                .filter_map(|tunnel| tunnel["public_url"].as_str())
+                .collect();
+            urls.iter().find(|url| url.starts_with("https://")).or(urls.first()).map(|url| url.to_string())
+        }
+    }
+}
+
+/// Public URL of the running tunnel, None while it is still connecting
+pub async fn public_url(provider: TunnelProvider, api_port: u16) -> Option<String> {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(2)).build().ok()?;
+    let response = client.get(format!("http://127.0.0.1:{}{}", api_port, api_path(provider))).send().await.ok()?;
+    parse_public_url(provider, &response.text().await.ok()?)
+}
+
+/// Poll the local API until the tunnel has a public URL
+pub async fn wait_for_public_url(provider: TunnelProvider, api_port: u16, timeout: Duration) -> Result<String, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(url) = public_url(provider, api_port).await {
+            return Ok(url);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "{} did not report a public URL within {}s, see the tunnel log",
+                provider.display_name(), timeout.as_secs()
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(https: bool) -> Project {
+        Project {
+            name: "shop".to_string(),
+            path: "/tmp/shop".to_string(),
+            framework: crate::projects::Framework::Php,
+            created_at: 0,
+            https,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_command_args() {
+        let settings = AppSettings::default();
+        let origin = Origin::new(Some(&project(false)), &settings);
+        let args = command_args(TunnelProvider::Cloudflared, &origin, 20241, Path::new("ngrok.yml"));
+        assert_eq!(args.join(" "), format!(
+            "tunnel --no-autoupdate --url http://127.0.0.1:{} --http-host-header {} --metrics 127.0.0.1:20241",
+            settings.web_port, crate::projects::site_host("shop")
+        ));
+
+        let origin = Origin::new(Some(&project(true)), &settings);
+        let args = command_args(TunnelProvider::Cloudflared, &origin, 20241, Path::new("ngrok.yml"));
+        assert!(args.contains(&format!("https://127.0.0.1:{}", settings.https_port)));
+        assert!(args.contains(&"--no-tls-verify".to_string()));
+
+        let origin = Origin::new(None, &settings);
+        let args = command_args(TunnelProvider::Ngrok, &origin, 4040, Path::new("ngrok.yml"));
+        assert_eq!(args[..3], ["http".to_string(), origin.url.clone(), "--host-header=localhost".to_string()]);
+        assert!(ngrok_config("token", 4040).contains("web_addr: 127.0.0.1:4040"));
+    }
+
+    #[test]
+    fn test_authtoken() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(read_authtoken(temp_dir.path()), None);
+        save_authtoken(temp_dir.path(), " 2abc \n").unwrap();
+        assert_eq!(read_authtoken(temp_dir.path()), Some("2abc".to_string()));
+        save_authtoken(temp_dir.path(), "").unwrap();
+        assert_eq!(read_authtoken(temp_dir.path()), None);
+
+        // Never written back to settings
+        let settings = TunnelSettings { legacy_ngrok_authtoken: "2abc".to_string(), ..Default::default() };
+        assert!(!serde_json::to_string(&settings).unwrap().contains("2abc"));
+    }
+
+    #[test]
+    fn test_parse_public_url() {
+        assert_eq!(
+            parse_public_url(TunnelProvider::Cloudflared, r#"{"hostname":"calm-sea-1234.trycloudflare.com"}"#),
+            Some("https://calm-sea-1234.trycloudflare.com".to_string())
+        );
+        assert_eq!(parse_public_url(TunnelProvider::Cloudflared, r#"{"hostname":""}"#), None);
+
+        let body = r#"{"tunnels":[
+            {"name":"command_line (http)","public_url":"http://ab12.ngrok-free.app","proto":"http"},
+            {"name":"command_line","public_url":"https://ab12.ngrok-free.app","proto":"https"}
+        ]}"#;
+        assert_eq!(parse_public_url(TunnelProvider::Ngrok, body), Some("https://ab12.ngrok-free.app".to_string()));
+        assert_eq!(parse_public_url(TunnelProvider::Ngrok, r#"{"tunnels":[]}"#), None);
+        assert_eq!(parse_public_url(TunnelProvider::Ngrok, "not json"), None);
+    }
+}
@@ -0,0 +1,113 @@
+//! Managed Composer
+//!
+//! composer.phar is downloaded into the runtime directory on first use and
+//! run with the bundled PHP CLI, so project scaffolding works without a
+//! system PHP or Composer. Its cache and global config live next to it.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::operations::Operation;
+use crate::process::manager::configure_no_window;
+use crate::runtime::locator::{php_cli_binary, RuntimePaths};
+
+const COMPOSER_URL: &str = "https://getcomposer.org/download/latest-stable/composer.phar";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn composer_dir(runtime_dir: &Path) -> PathBuf {
+    runtime_dir.join("composer")
+}
+
+/// Path of composer.phar, downloading it first if it is missing
+pub async fn ensure_composer(runtime_dir: &Path) -> Result<PathBuf, String> {
+    let phar = composer_dir(runtime_dir).join("composer.phar");
+    if phar.is_file() {
+        return Ok(phar);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let content = fetch(&client, COMPOSER_URL).await?;
+    let checksum = String::from_utf8_lossy(&fetch(&client, &format!("{}.sha256", COMPOSER_URL)).await?)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let actual = hex::encode(Sha256::digest(&content));
+    if actual != checksum {
+        return Err(format!("composer.phar checksum mismatch: expected {}, got {}", checksum, actual));
+    }
+
+    fs::create_dir_all(composer_dir(runtime_dir))
+        .map_err(|e| format!("Failed to create Composer directory: {}", e))?;
+    fs::write(&phar, &content)
+        .map_err(|e| format!("Failed to write composer.phar: {}", e))?;
+    tracing::info!("Downloaded composer.phar to {}", phar.display());
+    Ok(phar)
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP error {}: Failed to download {}", response.status().as_u16(), url));
+    }
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read {}: {}", url, e))
+}
+
+/// Run Composer in `cwd`, reporting each line it prints as progress of `step`
+pub fn run(paths: &RuntimePaths, phar: &Path, cwd: &Path, args: &[&str], op: &Operation, step: &str) -> Result<(), String> {
+    let php = php_cli_binary(paths)?;
+    let home = phar.parent().ok_or("Invalid composer.phar path")?.join("home");
+
+    let mut cmd = configure_no_window(Command::new(&php));
+    cmd.arg("-c").arg(&paths.php_ini)
+        .arg(phar)
+        .args(args)
+        .arg("--no-interaction")
+        .arg("--no-ansi")
+        .current_dir(cwd)
+        .env("COMPOSER_HOME", &home)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn()
+        .map_err(|e| format!("Failed to run Composer: {}", e))?;
+
+    // Composer reports progress on stderr; keep the tail for the error message
+    let mut tail = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            let line = line.trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            op.progress(step, None, line.clone());
+            tail.push(line);
+            if tail.len() > 10 {
+                tail.remove(0);
+            }
+        }
+    }
+
+    let status = child.wait()
+        .map_err(|e| format!("Failed to wait for Composer: {}", e))?;
+    if !status.success() {
+        return Err(format!("Composer failed: {}", tail.join("\n")));
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Framework detection
+//!
+//! Existing code is recognised by the files a framework always ships, which
+//! decides the document root the project is served from and the rewrite
+//! preset of its site block.
+
+use std::path::Path;
+
+use super::Framework;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detected {
+    pub framework: Framework,
+    /// Document root relative to the project folder
+    pub docroot: String,
+}
+
+/// Detect the framework of the project in `dir`
+pub fn detect(dir: &Path) -> Detected {
+    if dir.join("artisan").is_file() && dir.join("public").is_dir() {
+        return Detected { framework: Framework::Laravel, docroot: "public".to_string() };
+    }
+    if dir.join("bin").join("console").is_file() && dir.join("public").join("index.php").is_file() {
+        return Detected { framework: Framework::Symfony, docroot: "public".to_string() };
+    }
+    // Bedrock keeps WordPress in web/, next to its Composer setup
+    for docroot in ["", "web"] {
+        let root = dir.join(docroot);
+        if root.join("wp-config.php").is_file() || root.join("wp-load.php").is_file() {
+            return Detected { framework: Framework::WordPress, docroot: docroot.to_string() };
+        }
+    }
+    if !has_php(dir) {
+        // Frontend builds land in dist/ or build/
+        for docroot in ["", "dist", "build"] {
+            if dir.join(docroot).join("index.html").is_file() {
+                return Detected { framework: Framework::Static, docroot: docroot.to_string() };
+            }
+        }
+    }
+    Detected { framework: Framework::Php, docroot: String::new() }
+}
+
+/// Whether `dir` holds PHP code or a Composer project at its top level
+fn has_php(dir: &Path) -> bool {
+    dir.join("composer.json").is_file()
+        || std::fs::read_dir(dir).is_ok_and(|entries| {
+            entries.flatten().any(|entry| entry.path().extension().is_some_and(|ext| ext == "php"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detect() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        assert_eq!(detect(dir), Detected { framework: Framework::Php, docroot: String::new() });
+
+        fs::create_dir_all(dir.join("dist")).unwrap();
+        fs::write(dir.join("dist").join("index.html"), "<!doctype html>").unwrap();
+        assert_eq!(detect(dir), Detected { framework: Framework::Static, docroot: "dist".to_string() });
+        fs::write(dir.join("index.php"), "<?php").unwrap();
+        assert_eq!(detect(dir), Detected { framework: Framework::Php, docroot: String::new() });
+
+        fs::write(dir.join("artisan"), "#!/usr/bin/env php").unwrap();
+        fs::create_dir_all(dir.join("public")).unwrap();
+        assert_eq!(detect(dir), Detected { framework: Framework::Laravel, docroot: "public".to_string() });
+    }
+
+    #[test]
+    fn test_detect_wordpress_and_symfony() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("web")).unwrap();
+        fs::write(dir.join("composer.json"), "{}").unwrap();
+        fs::write(dir.join("web").join("wp-config.php"), "<?php").unwrap();
+        assert_eq!(detect(dir), Detected { framework: Framework::WordPress, docroot: "web".to_string() });
+        fs::write(dir.join("wp-load.php"), "<?php").unwrap();
+        assert_eq!(detect(dir), Detected { framework: Framework::WordPress, docroot: String::new() });
+
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::create_dir_all(dir.join("public")).unwrap();
+        fs::write(dir.join("bin").join("console"), "#!/usr/bin/env php").unwrap();
+        fs::write(dir.join("public").join("index.php"), "<?php").unwrap();
+        assert_eq!(detect(dir), Detected { framework: Framework::Symfony, docroot: "public".to_string() });
+    }
+}
@@ -0,0 +1,138 @@
+//! Project archives
+//!
+//! A project is exported as one zip holding its folder under `<name>/` and,
+//! optionally, SQL dumps of its databases under `database/`, so it can be
+//! submitted or moved to another machine as a single file.
+
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::Project;
+use crate::operations::Operation;
+
+/// Folders that are regenerated from lock files or belong to the VCS
+const EXCLUDED_DIRS: [&str; 2] = [".git", "node_modules"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    /// The written archive
+    pub path: String,
+    pub files: usize,
+    pub size_bytes: u64,
+    /// Databases dumped into the archive
+    pub databases: Vec<String>,
+}
+
+/// Write `project` and the SQL `dumps` (database name, dump file) to `dest`
+pub fn export_project(project: &Project, dest: &Path, dumps: &[(String, PathBuf)], op: &Operation) -> Result<ExportReport, String> {
+    let root = Path::new(&project.path);
+    let mut files = Vec::new();
+    collect_files(root, Path::new(&project.name), &mut files)?;
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let file = File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    let mut add = |name: &str, path: &Path| -> Result<(), String> {
+        let content = fs::read(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(&content).map_err(Into::into))
+            .map_err(|e| format!("Failed to write {} to archive: {}", name, e))
+    };
+
+    let total = files.len().max(1);
+    for (i, (path, name)) in files.iter().enumerate() {
+        if i % 50 == 0 {
+            op.progress("archiving", Some((100 * i / total) as u8), format!("Archiving {}", name));
+        }
+        add(name, path)?;
+    }
+    for (database, dump) in dumps {
+        add(&format!("database/{}.sql", database), dump)?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish archive: {}", e))?;
+
+    Ok(ExportReport {
+        path: dest.to_string_lossy().to_string(),
+        files: files.len(),
+        size_bytes: fs::metadata(dest).map(|m| m.len()).unwrap_or(0),
+        databases: dumps.iter().map(|(database, _)| database.clone()).collect(),
+    })
+}
+
+/// Regular files under `dir` with their archive names below `prefix`
+///
+/// Symlinks inside the project are skipped, they may point anywhere.
+fn collect_files(dir: &Path, prefix: &Path, files: &mut Vec<(PathBuf, String)>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let archive_path = prefix.join(&name);
+        match entry.file_type() {
+            Ok(t) if t.is_dir() && !EXCLUDED_DIRS.iter().any(|excluded| name == *excluded) => {
+                collect_files(&entry.path(), &archive_path, files)?;
+            }
+            Ok(t) if t.is_file() => {
+                // Zip entry names always use forward slashes
+                let archive_name = archive_path.to_string_lossy().replace('\\', "/");
+                files.push((entry.path(), archive_name));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::OperationKind;
+    use std::io::Read;
+
+    #[test]
+    fn test_export_project() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("shop");
+        fs::create_dir_all(dir.join("public")).unwrap();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::write(dir.join("public").join("index.php"), "<?php echo 1;").unwrap();
+        fs::write(dir.join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        let dump = temp_dir.path().join("shop.sql");
+        fs::write(&dump, "CREATE TABLE t (id INT);").unwrap();
+        let project = Project {
+            name: "shop".to_string(),
+            path: dir.to_string_lossy().to_string(),
+            docroot: "public".to_string(),
+            framework: crate::projects::Framework::Php,
+            created_at: 1,
+            ..Default::default()
+        };
+
+        let dest = temp_dir.path().join("exports").join("shop.zip");
+        let op = Operation::start(OperationKind::Export, false, "Exporting");
+        let report = export_project(&project, &dest, &[("shop".to_string(), dump)], &op).unwrap();
+        op.finish(&Ok::<(), String>(()));
+        assert_eq!(report.files, 1);
+        assert_eq!(report.databases, vec!["shop"]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        let mut index = String::new();
+        archive.by_name("shop/public/index.php").unwrap().read_to_string(&mut index).unwrap();
+        assert_eq!(index, "<?php echo 1;");
+        assert!(archive.by_name("database/shop.sql").is_ok());
+        assert!(archive.by_name("shop/.git/HEAD").is_err());
+    }
+}
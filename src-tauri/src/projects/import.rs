@@ -0,0 +1,280 @@
+//! Importing existing code as a project
+//!
+//! A folder is registered where it is, optionally linked into the projects
+//! directory so it also shows up under `localhost/<name>/`. A git URL is
+//! cloned into the projects directory with the system `git`. Folders copied
+//! into the projects directory by hand are registered by scanning it.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::{detect, manifest, validate_project_name, Project, ProjectRegistry};
+use crate::operations::Operation;
+use crate::process::manager::configure_no_window;
+
+/// Register the folder or clone the git repository at `source`
+///
+/// The project is named `name`, or after the folder or repository.
+pub fn import(
+    registry: &ProjectRegistry,
+    projects_dir: &Path,
+    source: &str,
+    name: Option<&str>,
+    link: bool,
+    op: &Operation,
+) -> Result<Project, String> {
+    let folder = Path::new(source.trim());
+    let (name, path) = if folder.is_dir() {
+        if !folder.is_absolute() {
+            return Err(format!("Folder path must be absolute: {}", folder.display()));
+        }
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => suggest_name(&folder.file_name().unwrap_or_default().to_string_lossy())?,
+        };
+        (name.clone(), register_folder(registry, projects_dir, folder, &name, link)?)
+    } else if is_git_url(source.trim()) {
+        let url = source.trim();
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => suggest_name(repository_name(url))?,
+        };
+        let dir = registry.check_new(projects_dir, &name)?;
+        fs::create_dir_all(projects_dir)
+            .map_err(|e| format!("Failed to create {}: {}", projects_dir.display(), e))?;
+        clone(url, &dir, op)?;
+        (name, dir)
+    } else {
+        return Err(format!("'{}' is neither an existing folder nor a git URL", source));
+    };
+
+    op.progress("detecting", None, "Detecting the framework");
+    let project = detected_project(name, &path)?;
+    registry.insert(project.clone())?;
+    Ok(project)
+}
+
+/// Register the folders in `projects_dir` that are not projects yet
+///
+/// Hidden folders, folders whose name is not a valid project name and
+/// folders with an invalid `campp.json` are skipped. Returns the newly
+/// registered projects.
+pub fn scan(registry: &ProjectRegistry, projects_dir: &Path) -> Result<Vec<Project>, String> {
+    let entries = match fs::read_dir(projects_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", projects_dir.display(), e)),
+    };
+    let known = registry.list()?;
+    let mut folders: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect();
+    folders.sort();
+
+    let mut registered = Vec::new();
+    for folder in folders {
+        let name = folder.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if name.starts_with('.') || validate_project_name(&name).is_err() {
+            continue;
+        }
+        if known.iter().any(|p| p.name == name || Path::new(&p.path) == folder) {
+            continue;
+        }
+        if let Err(e) = registry.check_name_free(&name) {
+            tracing::warn!("Not registering {}: {}", folder.display(), e);
+            continue;
+        }
+        let project = match detected_project(name, &folder) {
+            Ok(project) => project,
+            Err(e) => {
+                tracing::warn!("Not registering {}: {}", folder.display(), e);
+                continue;
+            }
+        };
+        registry.insert(project.clone())?;
+        registered.push(project);
+    }
+    Ok(registered)
+}
+
+/// Project for the code in `path`, with the detected framework and docroot
+///
+/// The docroot of its `campp.json` wins over the detected one.
+fn detected_project(name: String, path: &Path) -> Result<Project, String> {
+    let detected = detect::detect(path);
+    let manifest = manifest::load(path)?.unwrap_or_default();
+    Ok(Project {
+        name,
+        path: path.to_string_lossy().to_string(),
+        docroot: manifest.docroot.unwrap_or(detected.docroot),
+        framework: detected.framework,
+        created_at: crate::util::now(),
+        ..Default::default()
+    })
+}
+
+/// Path the project in `folder` is registered with
+fn register_folder(registry: &ProjectRegistry, projects_dir: &Path, folder: &Path, name: &str, link: bool) -> Result<PathBuf, String> {
+    registry.check_name_free(name)?;
+    let projects = registry.list()?;
+    if let Some(existing) = projects.iter().find(|p| Path::new(&p.path) == folder) {
+        return Err(format!("{} is already registered as '{}'", folder.display(), existing.name));
+    }
+
+    if !link || folder.parent() == Some(projects_dir) {
+        return Ok(folder.to_path_buf());
+    }
+
+    // Registering the link keeps delete_files from ever reaching the original
+    let link_path = projects_dir.join(name);
+    if link_path.exists() {
+        return Err(format!("Folder {} already exists", link_path.display()));
+    }
+    fs::create_dir_all(projects_dir)
+        .map_err(|e| format!("Failed to create {}: {}", projects_dir.display(), e))?;
+    symlink_dir(folder, &link_path)
+        .map_err(|e| format!("Failed to link {} into the projects directory: {}", folder.display(), e))?;
+    Ok(link_path)
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    // Needs Developer Mode or administrator rights
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+fn is_git_url(source: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "git@"].iter().any(|prefix| source.starts_with(prefix))
+}
+
+/// Last path segment of a repository URL, without `.git`
+fn repository_name(url: &str) -> &str {
+    let path = url.trim_end_matches('/');
+    let last = path.rsplit(['/', ':']).next().unwrap_or(path);
+    last.strip_suffix(".git").unwrap_or(last)
+}
+
+/// A valid project name derived from a folder or repository name
+fn suggest_name(raw: &str) -> Result<String, String> {
+    let name: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    let name = &name[..name.len().min(64)];
+    if name.is_empty() {
+        return Err(format!("Cannot derive a project name from '{}', choose one", raw));
+    }
+    Ok(name.to_string())
+}
+
+/// `git clone` `url` into `dest`, reporting git's progress lines
+fn clone(url: &str, dest: &Path, op: &Operation) -> Result<(), String> {
+    op.progress("cloning", None, format!("Cloning {}", url));
+    let mut child = configure_no_window(Command::new("git"))
+        .arg("clone")
+        .arg("--progress")
+        .arg("--")
+        .arg(url)
+        .arg(dest)
+        // Fail instead of waiting for credentials nobody can type
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git, is it installed? {}", e))?;
+
+    let mut last = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        // Progress lines are terminated by carriage returns
+        for chunk in BufReader::new(stderr).split(b'\r').map_while(Result::ok) {
+            for line in String::from_utf8_lossy(&chunk).lines().map(str::trim).filter(|l| !l.is_empty()) {
+                op.progress("cloning", None, line.to_string());
+                last = line.to_string();
+            }
+        }
+    }
+
+    let status = child.wait()
+        .map_err(|e| format!("Failed to wait for git: {}", e))?;
+    if !status.success() {
+        let _ = fs::remove_dir_all(dest);
+        return Err(format!("git clone failed: {}", last));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::OperationKind;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_source_names() {
+        assert!(is_git_url("git@github.com:acme/shop.git"));
+        assert!(!is_git_url("/home/me/shop"));
+        assert_eq!(repository_name("https://github.com/acme/shop.git"), "shop");
+        assert_eq!(repository_name("git@github.com:acme/blog"), "blog");
+        assert_eq!(suggest_name("My Site (old)").unwrap(), "My-Site--old");
+        assert!(suggest_name("...").is_err());
+    }
+
+    #[test]
+    fn test_import_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let code = temp_dir.path().join("code").join("My Shop");
+        fs::create_dir_all(code.join("public")).unwrap();
+        fs::write(code.join("artisan"), "").unwrap();
+        let registry = ProjectRegistry::at(temp_dir.path().join("projects.json"));
+        let op = Operation::start(OperationKind::Import, false, "Importing");
+
+        let project = import(&registry, &projects_dir, &code.to_string_lossy(), None, false, &op).unwrap();
+        assert_eq!(project.name, "My-Shop");
+        assert_eq!(project.path, code.to_string_lossy());
+        assert_eq!(project.docroot, "public");
+        assert!(import(&registry, &projects_dir, &code.to_string_lossy(), Some("again"), false, &op).is_err());
+
+        #[cfg(unix)]
+        {
+            let blog = temp_dir.path().join("code").join("blog");
+            fs::create_dir_all(&blog).unwrap();
+            fs::write(blog.join("index.php"), "").unwrap();
+            let project = import(&registry, &projects_dir, &blog.to_string_lossy(), None, true, &op).unwrap();
+            assert_eq!(PathBuf::from(&project.path), projects_dir.join("blog"));
+            assert!(projects_dir.join("blog").join("index.php").exists());
+            assert_eq!(project.docroot, "");
+        }
+        op.finish(&Ok::<(), String>(()));
+    }
+
+    #[test]
+    fn test_scan() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let registry = ProjectRegistry::at(temp_dir.path().join("projects.json"));
+        assert!(scan(&registry, &projects_dir).unwrap().is_empty());
+
+        registry.create(&projects_dir, "shop").unwrap();
+        fs::create_dir_all(projects_dir.join("blog")).unwrap();
+        fs::write(projects_dir.join("blog").join("wp-config.php"), "<?php").unwrap();
+        fs::create_dir_all(projects_dir.join(".cache")).unwrap();
+        fs::create_dir_all(projects_dir.join("not valid")).unwrap();
+        fs::create_dir_all(projects_dir.join("broken")).unwrap();
+        fs::write(projects_dir.join("broken").join("campp.json"), "{").unwrap();
+
+        let registered = scan(&registry, &projects_dir).unwrap();
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered[0].name, "blog");
+        assert_eq!(registered[0].framework, super::super::Framework::WordPress);
+        assert_eq!(registry.list().unwrap().len(), 2);
+        assert!(scan(&registry, &projects_dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Laravel projects
+//!
+//! Scaffolded with `composer create-project laravel/laravel`, served from
+//! `public/` and pointed at the bundled MySQL/MariaDB through `.env`.
+
+use std::fs;
+use std::path::Path;
+
+use super::{composer, site_host, Framework, Project, ProjectRegistry};
+use crate::config::AppSettings;
+use crate::database::dsn::{connection_string, ConnectionInfo, DsnFormat};
+use crate::operations::Operation;
+use crate::runtime::locator::RuntimePaths;
+
+/// Database a Laravel project is configured for
+///
+/// Hyphens are valid in MySQL names but need quoting in every query, so
+/// they become underscores.
+pub fn database_name(project: &str) -> String {
+    project.replace('-', "_")
+}
+
+/// Create `<projects_dir>/<name>` from the Laravel skeleton and register it
+pub fn create(
+    paths: &RuntimePaths,
+    phar: &Path,
+    registry: &ProjectRegistry,
+    projects_dir: &Path,
+    settings: &AppSettings,
+    name: &str,
+    op: &Operation,
+) -> Result<Project, String> {
+    let dir = registry.check_new(projects_dir, name)?;
+    fs::create_dir_all(projects_dir)
+        .map_err(|e| format!("Failed to create {}: {}", projects_dir.display(), e))?;
+
+    op.progress("installing", None, "Downloading the Laravel skeleton");
+    composer::run(paths, phar, projects_dir, &["create-project", "laravel/laravel", name, "--prefer-dist"], op, "installing")?;
+
+    op.progress("configuring", None, "Writing .env");
+    configure_env(&dir, settings, &database_name(name))?;
+
+    let project = Project {
+        name: name.to_string(),
+        path: dir.to_string_lossy().to_string(),
+        docroot: "public".to_string(),
+        framework: Framework::Laravel,
+        created_at: crate::util::now(),
+        ..Default::default()
+    };
+    registry.insert(project.clone())?;
+    Ok(project)
+}
+
+/// Point `.env` at the bundled MySQL/MariaDB and the project's local URL
+fn configure_env(dir: &Path, settings: &AppSettings, database: &str) -> Result<(), String> {
+    let env_path = dir.join(".env");
+    let current = match fs::read_to_string(&env_path) {
+        Ok(content) => content,
+        Err(_) => fs::read_to_string(dir.join(".env.example")).unwrap_or_default(),
+    };
+
+    let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut values = vec![("APP_URL".to_string(), format!("http://{}:{}", site_host(&name), settings.web_port))];
+    let db_block = connection_string(DsnFormat::LaravelEnv, &ConnectionInfo::mysql(settings), Some(database));
+    values.extend(
+        db_block
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string())),
+    );
+
+    fs::write(&env_path, set_env_values(&current, &values))
+        .map_err(|e| format!("Failed to write {}: {}", env_path.display(), e))
+}
+
+/// Set `values` in dotenv `content`
+///
+/// A key is written where it first appears, commented out or not, and later
+/// occurrences are dropped; keys that do not appear are appended.
+fn set_env_values(content: &str, values: &[(String, String)]) -> String {
+    let mut written = vec![false; values.len()];
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        let uncommented = line.trim_start().trim_start_matches('#').trim_start();
+        let key = uncommented.split_once('=').map(|(key, _)| key.trim());
+        match key.and_then(|key| values.iter().position(|(k, _)| k == key)) {
+            Some(i) if !written[i] => {
+                lines.push(format!("{}={}", values[i].0, values[i].1));
+                written[i] = true;
+            }
+            Some(_) => {}
+            None => lines.push(line.to_string()),
+        }
+    }
+
+    for (i, (key, value)) in values.iter().enumerate() {
+        if !written[i] {
+            lines.push(format!("{}={}", key, value));
+        }
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_env_values() {
+        let env = "APP_NAME=Laravel\nAPP_URL=http://localhost\n\nDB_CONNECTION=sqlite\n# DB_HOST=127.0.0.1\n# DB_PORT=3306\n# DB_DATABASE=laravel\n";
+        let values = [
+            ("DB_CONNECTION", "mysql"),
+            ("DB_HOST", "127.0.0.1"),
+            ("DB_PORT", "3307"),
+            ("DB_DATABASE", "shop"),
+            ("DB_PASSWORD", ""),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+
+        assert_eq!(
+            set_env_values(env, &values),
+            "APP_NAME=Laravel\nAPP_URL=http://localhost\n\nDB_CONNECTION=mysql\nDB_HOST=127.0.0.1\nDB_PORT=3307\nDB_DATABASE=shop\nDB_PASSWORD=\n"
+        );
+    }
+
+    #[test]
+    fn test_configure_env() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("my_shop");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".env.example"), "APP_URL=http://localhost\nDB_CONNECTION=sqlite\n").unwrap();
+
+        configure_env(&dir, &AppSettings::default(), &database_name("my-shop")).unwrap();
+        let env = fs::read_to_string(dir.join(".env")).unwrap();
+        assert!(env.starts_with("APP_URL=http://my-shop.localhost:8080\nDB_CONNECTION=mysql\n"));
+        assert!(env.contains("DB_PORT=3307\nDB_DATABASE=my_shop\nDB_USERNAME=root\n"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Per-project configuration, `campp.json`
+//!
+//! A project may commit a `campp.json` next to its code describing the local
+//! stack it expects. It is read whenever the project's site block is
+//! generated and when the project is provisioned, so edits take effect on the
+//! next reload without re-registering the project:
+//!
+//! ```json
+//! {
+//!   "docroot": "public",
+//!   "php": "8.3",
+//!   "env": { "APP_ENV": "local" },
+//!   "databases": ["shop", "shop_testing"]
+//! }
+//! ```
+//!
+//! php.ini overrides are not supported. The only way to hand them to a shared
+//! PHP is the `PHP_VALUE` FastCGI parameter, and php-fpm keeps such values in
+//! the worker for the requests that follow, whichever project they are for,
+//! while php-cgi on Windows ignores it. A `php_ini` object is still read so
+//! the user can be told it has no effect.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const MANIFEST_FILE: &str = "campp.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectManifest {
+    /// Document root relative to the project folder, overriding the registry
+    pub docroot: Option<String>,
+    /// PHP version the project expects, e.g. "8.3"
+    pub php: Option<String>,
+    /// php.ini directives, not applied, see the module docs
+    pub php_ini: BTreeMap<String, String>,
+    /// Environment variables passed to PHP for this project's requests
+    pub env: BTreeMap<String, String>,
+    /// MySQL databases created when the project is provisioned
+    pub databases: Vec<String>,
+}
+
+/// The manifest in project folder `dir`, None when there is none
+pub fn load(dir: &Path) -> Result<Option<ProjectManifest>, String> {
+    let path = dir.join(MANIFEST_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let manifest: ProjectManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    manifest.validate()?;
+    Ok(Some(manifest))
+}
+
+impl ProjectManifest {
+    /// Reject values that would escape the project folder or the Caddyfile
+    /// string they are written into
+    fn validate(&self) -> Result<(), String> {
+        if let Some(docroot) = &self.docroot {
+            super::validate_docroot(docroot)?;
+        }
+        for (key, value) in &self.env {
+            validate_setting(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Warning when the manifest has php.ini directives, which are ignored
+    pub fn php_ini_ignored(&self) -> Option<String> {
+        (!self.php_ini.is_empty()).then(|| format!(
+            "{} sets php.ini directives ({}), per-project php.ini is not supported; set them in CAMPP's php.ini",
+            MANIFEST_FILE,
+            self.php_ini.keys().cloned().collect::<Vec<_>>().join(", ")
+        ))
+    }
+
+    /// Warning when the `installed` PHP version is not the one asked for
+    ///
+    /// "8.3" accepts any 8.3.x release.
+    pub fn php_mismatch(&self, installed: &str) -> Option<String> {
+        let wanted = self.php.as_deref()?.trim();
+        let matches = installed == wanted
+            || installed.strip_prefix(wanted).is_some_and(|rest| rest.starts_with('.'));
+        (!matches).then(|| format!("{} asks for PHP {}, but PHP {} is installed", MANIFEST_FILE, wanted, installed))
+    }
+}
+
+/// A php.ini directive or environment variable written into the Caddyfile
+pub fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+        return Err(format!("Invalid setting name '{}'", key));
+    }
+    if value.contains(['"', '\\', '\n', '\r', '{', '}']) {
+        return Err(format!("Value of '{}' contains characters not allowed in Caddyfile", key));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(load(temp_dir.path()).unwrap(), None);
+
+        fs::write(
+            temp_dir.path().join(MANIFEST_FILE),
+            r#"{"docroot": "web", "php": "8.3", "php_ini": {"memory_limit": "512M", "display_errors": "On"}, "databases": ["shop"]}"#,
+        ).unwrap();
+        let manifest = load(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(manifest.docroot.as_deref(), Some("web"));
+        assert_eq!(manifest.databases, vec!["shop"]);
+        assert!(manifest.env.is_empty());
+        assert!(manifest.php_ini_ignored().unwrap().contains("display_errors, memory_limit"));
+
+        fs::write(temp_dir.path().join(MANIFEST_FILE), r#"{"docroot": "../other"}"#).unwrap();
+        assert!(load(temp_dir.path()).is_err());
+        fs::write(temp_dir.path().join(MANIFEST_FILE), r#"{"env": {"APP_KEY": "a\"b"}}"#).unwrap();
+        assert!(load(temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_php_mismatch() {
+        let manifest = ProjectManifest { php: Some("8.3".to_string()), ..Default::default() };
+        assert_eq!(manifest.php_mismatch("8.3.14"), None);
+        assert_eq!(manifest.php_mismatch("8.3"), None);
+        assert!(manifest.php_mismatch("8.30.1").is_some());
+        assert!(manifest.php_mismatch("8.4.1").is_some());
+        assert_eq!(ProjectManifest::default().php_mismatch("8.4.1"), None);
+    }
+}
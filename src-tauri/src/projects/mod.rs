@@ -0,0 +1,543 @@
+//! Projects
+//!
+//! A project is a site folder under the projects directory. Projects are
+//! recorded in `config/projects.json`, which is the list the frontend shows;
+//! folders dropped into the projects directory by hand are still served by
+//! Caddy but are not projects until they are registered.
+
+pub mod composer;
+pub mod detect;
+pub mod duplicate;
+pub mod export;
+pub mod import;
+pub mod laravel;
+pub mod manifest;
+pub mod share;
+pub mod summary;
+pub mod welcome;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::AppSettings;
+
+const REGISTRY_FILE: &str = "projects.json";
+
+/// Caddy directives a project can commit for its own site block
+pub const SNIPPET_FILE: &str = "Caddyfile.project";
+
+/// Page written into new projects so they show something when opened
+const STARTER_INDEX_PHP: &str = "<?php\n\necho '<h1>' . basename(__DIR__) . '</h1>';\n";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Framework {
+    /// Plain PHP or static files
+    #[default]
+    Php,
+    Laravel,
+    WordPress,
+    Symfony,
+    /// Static files only, served without PHP, e.g. docs or a built frontend
+    Static,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    /// Project folder
+    pub path: String,
+    /// Document root relative to `path`, empty for the folder itself
+    #[serde(default)]
+    pub docroot: String,
+    pub framework: Framework,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    /// `<name>.<tld>` once registered in the hosts file
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// PHP version the project is pinned to, e.g. "8.1"; None uses the
+    /// `php` of its `campp.json`, or the main PHP
+    #[serde(default)]
+    pub php_version: Option<String>,
+    /// Environment variables passed to PHP, over those of `campp.json`
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// For static sites, answer paths that are not files with `index.html`
+    /// so client-side routing works
+    #[serde(default)]
+    pub spa_fallback: bool,
+    /// Extra Caddy directives for the project's site block
+    #[serde(default)]
+    pub caddy_snippet: Option<String>,
+    /// SHA-256 of the `Caddyfile.project` the user allowed; the file comes
+    /// with the project's code and is only used while it still matches
+    #[serde(default)]
+    pub trusted_caddy_file: Option<String>,
+    /// More host names the project answers on, e.g. `api.shop.test`, each
+    /// with its own hosts file entry
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Serve the project over HTTPS with a certificate from the local CA
+    #[serde(default)]
+    pub https: bool,
+    /// Answer plain HTTP with a redirect to HTTPS; None follows the
+    /// `https_redirect` setting
+    #[serde(default)]
+    pub https_redirect: Option<bool>,
+    /// Port of the dev server of a running `run_npm_script`, the site
+    /// proxies to it
+    #[serde(default)]
+    pub dev_server_port: Option<u16>,
+    /// Set while the project is shared on the LAN
+    #[serde(default)]
+    pub lan_share: Option<share::LanShare>,
+}
+
+impl Project {
+    /// Directory Caddy serves for the project
+    pub fn docroot_path(&self) -> PathBuf {
+        let path = Path::new(&self.path);
+        if self.docroot.is_empty() {
+            path.to_path_buf()
+        } else {
+            path.join(&self.docroot)
+        }
+    }
+
+    /// Directory the project is served from, `docroot` of its `campp.json`
+    /// taking precedence over the registered one
+    pub fn site_root(&self, manifest: Option<&manifest::ProjectManifest>) -> PathBuf {
+        match manifest.and_then(|m| m.docroot.as_ref()) {
+            Some(docroot) => Path::new(&self.path).join(docroot),
+            None => self.docroot_path(),
+        }
+    }
+
+    /// The project's `campp.json`, None when it has none or it is invalid
+    pub fn manifest(&self) -> Option<manifest::ProjectManifest> {
+        manifest::load(Path::new(&self.path)).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring config of project '{}': {}", self.name, e);
+            None
+        })
+    }
+
+    /// PHP version the project asks for, the pin taking precedence over `campp.json`
+    pub fn wanted_php(&self, manifest: Option<&manifest::ProjectManifest>) -> Option<String> {
+        self.php_version.clone().or_else(|| manifest.and_then(|m| m.php.clone()))
+    }
+
+    /// Environment variables for PHP, `campp.json` ones overridden by the registry
+    pub fn php_env(&self, manifest: Option<&manifest::ProjectManifest>) -> BTreeMap<String, String> {
+        let mut env = manifest.map(|m| m.env.clone()).unwrap_or_default();
+        env.extend(self.env.clone());
+        env
+    }
+
+    /// Host names in the hosts file for the project, its domain and aliases
+    pub fn hostnames(&self) -> Vec<String> {
+        self.domain.iter().chain(&self.aliases).cloned().collect()
+    }
+
+    /// The project with its domain and aliases under `.<old>` moved to `.<new>`
+    pub fn moved_to_tld(&self, old: &str, new: &str) -> Project {
+        let moved = |host: &String| with_tld(host, old, new).unwrap_or_else(|| host.clone());
+        Project {
+            domain: self.domain.as_ref().map(moved),
+            aliases: self.aliases.iter().map(moved).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// The project's `Caddyfile.project`, if it has one
+    pub fn caddy_file(&self) -> Option<String> {
+        fs::read_to_string(Path::new(&self.path).join(SNIPPET_FILE)).ok()
+    }
+
+    /// Whether the project has a `Caddyfile.project` that is not allowed in
+    /// its current form
+    pub fn caddy_file_pending(&self) -> bool {
+        self.caddy_file()
+            .is_some_and(|content| !content.trim().is_empty() && self.trusted_caddy_file.as_deref() != Some(&snippet_hash(&content)))
+    }
+
+    /// Extra Caddy directives, `Caddyfile.project` first, with where they came from
+    ///
+    /// `Caddyfile.project` is left out unless the user allowed its current
+    /// content, a cloned repository must not reconfigure the web server.
+    pub fn caddy_snippets(&self) -> Vec<(String, String)> {
+        self.caddy_file()
+            .filter(|content| self.trusted_caddy_file.as_deref() == Some(&snippet_hash(content)))
+            .map(|content| (SNIPPET_FILE.to_string(), content))
+            .into_iter()
+            .chain(self.caddy_snippet.clone().map(|snippet| ("settings".to_string(), snippet)))
+            .filter(|(_, snippet)| !snippet.trim().is_empty())
+            .collect()
+    }
+
+    /// MySQL databases listed in `campp.json` or named by the project's config files
+    pub fn required_databases(&self) -> Vec<String> {
+        let mut databases = self.manifest().map(|m| m.databases).unwrap_or_default();
+        for database in crate::database::usage::project_mysql_databases(Path::new(&self.path)) {
+            if !databases.contains(&database) {
+                databases.push(database);
+            }
+        }
+        databases
+    }
+}
+
+/// Fingerprint `trusted_caddy_file` is compared with
+pub fn snippet_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Registered projects, empty when the registry cannot be read
+pub fn registered() -> Vec<Project> {
+    ProjectRegistry::open().and_then(|registry| registry.list()).unwrap_or_else(|e| {
+        tracing::warn!("Serving no projects: {}", e);
+        Vec::new()
+    })
+}
+
+/// Top-level domain of project domains unless settings choose another
+pub const DEFAULT_DOMAIN_TLD: &str = "test";
+
+/// Project name as a DNS label, underscores are not valid in host names
+fn host_label(name: &str) -> String {
+    name.to_ascii_lowercase().replace('_', "-")
+}
+
+/// Host name a project is served on, `<name>.localhost`
+///
+/// Browsers resolve `*.localhost` to loopback on their own, so no hosts file
+/// entry is needed.
+pub fn site_host(name: &str) -> String {
+    format!("{}.localhost", host_label(name))
+}
+
+/// Domain registered for a project in the hosts file, `<name>.<tld>`
+pub fn project_domain(name: &str, tld: &str) -> String {
+    format!("{}.{}", host_label(name), tld)
+}
+
+/// `host` moved from `.<old>` to `.<new>`, None when it is not under `.<old>`
+pub fn with_tld(host: &str, old: &str, new: &str) -> Option<String> {
+    host.strip_suffix(&format!(".{}", old)).map(|label| format!("{}.{}", label, new))
+}
+
+/// `https://<host>`, with the port unless it is 443
+pub fn https_origin(host: &str, port: u16) -> String {
+    if port == 443 {
+        format!("https://{}", host)
+    } else {
+        format!("https://{}:{}", host, port)
+    }
+}
+
+/// The projects registry, stored as JSON
+pub struct ProjectRegistry {
+    path: PathBuf,
+}
+
+impl ProjectRegistry {
+    /// The registry next to settings.json
+    pub fn open() -> Result<Self, String> {
+        AppSettings::config_dir()
+            .map(|dir| Self::at(dir.join(REGISTRY_FILE)))
+            .ok_or_else(|| "Cannot determine config directory".to_string())
+    }
+
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Registered projects, sorted by name
+    ///
+    /// A missing registry is empty. One that cannot be read or parsed is an
+    /// error, writing over it would lose every project.
+    pub fn list(&self) -> Result<Vec<Project>, String> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", self.path.display(), e)),
+        };
+        let mut projects: Vec<Project> = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid project registry {}: {}", self.path.display(), e))?;
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(projects)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Project, String> {
+        self.list()?
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| format!("Project '{}' does not exist", name))
+    }
+
+    /// Create `<projects_dir>/<name>` with a starter page and register it
+    pub fn create(&self, projects_dir: &Path, name: &str) -> Result<Project, String> {
+        let dir = self.check_new(projects_dir, name)?;
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        fs::write(dir.join("index.php"), STARTER_INDEX_PHP)
+            .map_err(|e| format!("Failed to write index.php: {}", e))?;
+
+        let project = Project {
+            name: name.to_string(),
+            path: dir.to_string_lossy().to_string(),
+            framework: Framework::Php,
+            created_at: crate::util::now(),
+            ..Default::default()
+        };
+        self.insert(project.clone())?;
+        Ok(project)
+    }
+
+    /// Fails unless `name` is a valid project name no other project uses
+    ///
+    /// Names differing only in case or in `_` versus `-`, such as `my_shop`
+    /// and `My-Shop`, get the same host name and database name, so they
+    /// count as taken too.
+    pub fn check_name_free(&self, name: &str) -> Result<(), String> {
+        validate_project_name(name)?;
+        let label = host_label(name);
+        match self.list()?.into_iter().find(|p| host_label(&p.name) == label) {
+            Some(p) if p.name == name => Err(format!("Project '{}' already exists", name)),
+            Some(p) => Err(format!("Project '{}' would get the same host name and database name as project '{}'", name, p.name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Folder for a new project called `name`, if neither it nor the project exist
+    pub fn check_new(&self, projects_dir: &Path, name: &str) -> Result<PathBuf, String> {
+        self.check_name_free(name)?;
+        let dir = projects_dir.join(name);
+        if dir.exists() {
+            return Err(format!("Folder {} already exists", dir.display()));
+        }
+        Ok(dir)
+    }
+
+    /// Fails when another project, or `name` itself, already answers on `host`
+    pub fn check_hostname(&self, host: &str) -> Result<(), String> {
+        match self.list()?.into_iter().find(|p| site_host(&p.name) == host || p.hostnames().iter().any(|h| h == host)) {
+            Some(project) => Err(format!("{} is already used by project '{}'", host, project.name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Unregister a project, with `delete_files` also removing its folder
+    ///
+    /// Only folders inside `projects_dir` are ever deleted.
+    pub fn delete(&self, projects_dir: &Path, name: &str, delete_files: bool) -> Result<Project, String> {
+        let mut projects = self.list()?;
+        let index = projects.iter().position(|p| p.name == name)
+            .ok_or_else(|| format!("Project '{}' does not exist", name))?;
+        let project = projects.remove(index);
+
+        let dir = PathBuf::from(&project.path);
+        if delete_files && dir.exists() {
+            if !dir.starts_with(projects_dir) || dir == projects_dir {
+                return Err(format!("Not deleting {}, it is outside the projects directory", dir.display()));
+            }
+            fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to delete {}: {}", dir.display(), e))?;
+        }
+
+        self.write(&projects)?;
+        Ok(project)
+    }
+
+    /// Add or replace a project
+    pub fn insert(&self, project: Project) -> Result<(), String> {
+        let mut projects = self.list()?;
+        projects.retain(|p| p.name != project.name);
+        projects.push(project);
+        self.write(&projects)
+    }
+
+    fn write(&self, projects: &[Project]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(projects)
+            .map_err(|e| format!("Failed to serialize projects: {}", e))?;
+        fs::write(&self.path, json)
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
+
+/// Project names become folder names and URL paths, keep them simple
+pub fn validate_project_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > 64
+        || name.starts_with('-')
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid project name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// A document root must be a folder inside the project, e.g. `public` or `web`
+pub fn validate_docroot(docroot: &str) -> Result<(), String> {
+    let valid = Path::new(docroot)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !valid {
+        return Err(format!("Document root '{}' must be a folder inside the project", docroot));
+    }
+    Ok(())
+}
+
+/// A TLD for project domains is one DNS label starting with a letter,
+/// returned lowercased without a leading dot
+pub fn validate_tld(tld: &str) -> Result<String, String> {
+    let tld = tld.trim().trim_start_matches('.').to_ascii_lowercase();
+    let valid = !tld.is_empty()
+        && tld.len() <= 63
+        && tld.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !tld.ends_with('-')
+        && tld.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(format!("Invalid TLD '{}': use a single label such as test or localhost", tld));
+    }
+    Ok(tld)
+}
+
+/// An alias must be a host name such as `api.shop.test`, returned lowercased
+pub fn validate_alias(alias: &str) -> Result<String, String> {
+    let alias = alias.trim().to_ascii_lowercase();
+    let labels: Vec<&str> = alias.split('.').collect();
+    let valid = alias.len() <= 253
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        // Rules out IP addresses
+        && labels.last().is_some_and(|tld| tld.chars().any(|c| c.is_ascii_alphabetic()));
+    if !valid {
+        return Err(format!("Invalid alias '{}': use a host name such as api.shop.test", alias));
+    }
+    Ok(alias)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_invalid_registry() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(REGISTRY_FILE);
+        fs::write(&path, "[{\"name\": ").unwrap();
+        let registry = ProjectRegistry::at(path.clone());
+
+        assert!(registry.list().is_err());
+        // The broken file is not replaced by a registry of one project
+        assert!(registry.create(&temp_dir.path().join("projects"), "shop").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[{\"name\": ");
+    }
+
+    #[test]
+    fn test_create_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let registry = ProjectRegistry::at(temp_dir.path().join("config").join(REGISTRY_FILE));
+
+        let project = registry.create(&projects_dir, "shop").unwrap();
+        assert!(projects_dir.join("shop").join("index.php").exists());
+        assert_eq!(project.docroot_path(), projects_dir.join("shop"));
+        assert_eq!(registry.list().unwrap(), vec![project]);
+
+        assert!(registry.create(&projects_dir, "shop").is_err());
+        assert!(registry.create(&projects_dir, "../etc").is_err());
+        // Same host name and database name
+        assert!(registry.check_new(&projects_dir, "Shop").is_err());
+        assert!(registry.create(&projects_dir, "my_shop").is_ok());
+        assert!(registry.check_new(&projects_dir, "my-shop").is_err());
+        registry.delete(&projects_dir, "my_shop", true).unwrap();
+
+        registry.delete(&projects_dir, "shop", false).unwrap();
+        assert!(registry.list().unwrap().is_empty());
+        assert!(projects_dir.join("shop").exists());
+
+        // The folder is left in place, so it has to be removed before creating again
+        assert!(registry.create(&projects_dir, "shop").is_err());
+        fs::remove_dir_all(projects_dir.join("shop")).unwrap();
+        registry.create(&projects_dir, "shop").unwrap();
+        registry.delete(&projects_dir, "shop", true).unwrap();
+        assert!(!projects_dir.join("shop").exists());
+    }
+
+    #[test]
+    fn test_aliases() {
+        assert_eq!(validate_alias(" API.Shop.test ").unwrap(), "api.shop.test");
+        assert!(validate_alias("shop").is_err());
+        assert!(validate_alias("127.0.0.1").is_err());
+        assert!(validate_alias("-shop.test").is_err());
+        assert!(validate_alias("shop..test").is_err());
+
+        let temp_dir = TempDir::new().unwrap();
+        let registry = ProjectRegistry::at(temp_dir.path().join(REGISTRY_FILE));
+        let mut project = registry.create(&temp_dir.path().join("projects"), "shop").unwrap();
+        project.domain = Some("shop.test".to_string());
+        project.aliases = vec!["api.shop.test".to_string()];
+        registry.insert(project.clone()).unwrap();
+
+        assert_eq!(project.hostnames(), vec!["shop.test", "api.shop.test"]);
+        assert!(registry.check_hostname("api.shop.test").is_err());
+        assert!(registry.check_hostname("shop.localhost").is_err());
+        assert!(registry.check_hostname("admin.shop.test").is_ok());
+    }
+
+    #[test]
+    fn test_domain_tld() {
+        assert_eq!(validate_tld(" .Localhost ").unwrap(), "localhost");
+        assert!(validate_tld("").is_err());
+        assert!(validate_tld("my.test").is_err());
+        assert!(validate_tld("1test").is_err());
+
+        let temp_dir = TempDir::new().unwrap();
+        let registry = ProjectRegistry::at(temp_dir.path().join(REGISTRY_FILE));
+        let mut project = registry.create(&temp_dir.path().join("projects"), "my_shop").unwrap();
+        project.domain = Some(project_domain("my_shop", "test"));
+        project.aliases = vec!["api.my-shop.test".to_string(), "shop.example.com".to_string()];
+
+        let moved = project.moved_to_tld("test", "localhost");
+        assert_eq!(moved.hostnames(), vec!["my-shop.localhost", "api.my-shop.localhost", "shop.example.com"]);
+        assert_eq!(with_tld("shop.testing", "test", "localhost"), None);
+    }
+
+    #[test]
+    fn test_delete_outside_projects_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = temp_dir.path().join("elsewhere");
+        fs::create_dir_all(&outside).unwrap();
+        let registry = ProjectRegistry::at(temp_dir.path().join(REGISTRY_FILE));
+        registry.insert(Project {
+            name: "elsewhere".to_string(),
+            path: outside.to_string_lossy().to_string(),
+            framework: Framework::Php,
+            created_at: 1,
+            ..Default::default()
+        }).unwrap();
+
+        assert!(registry.delete(&temp_dir.path().join("projects"), "elsewhere", true).is_err());
+        assert!(outside.exists());
+        assert_eq!(registry.list().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,271 @@
+//! Sharing a project on the LAN
+//!
+//! A shared project gets an extra site block on a port of its own, bound to
+//! the machine's LAN address and answering any Host, so a phone or a
+//! colleague can open `http://<lan ip>:<port>/` without hosts file entries.
+//! The port is opened in the firewall where inbound connections are blocked
+//! by default: Windows Defender Firewall, and firewalld or ufw on Linux. The
+//! macOS application firewall asks about Caddy itself.
+//!
+//! Sharing is temporary: every share is ended and its port closed when
+//! CAMPP exits, and any left over from a crash when it starts again.
+//!
+//! The URL of a shared or tunnelled project is also rendered as a QR code to
+//! open it on a phone without typing the address.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, TcpListener, UdpSocket};
+
+use super::{Project, ProjectRegistry};
+
+/// First port tried for shared projects
+pub const SHARE_BASE_PORT: u16 = 8100;
+
+/// Image format of a QR code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+/// A project URL rendered as a QR code
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareQr {
+    pub url: String,
+    pub format: QrFormat,
+    /// PNG or SVG bytes
+    pub data: Vec<u8>,
+}
+
+/// Where a shared project is reachable from the LAN
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanShare {
+    /// LAN address of this machine when the project was shared
+    pub address: String,
+    pub port: u16,
+}
+
+impl LanShare {
+    /// `http://<address>:<port>/`
+    pub fn url(&self) -> String {
+        match self.address.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => format!("http://[{}]:{}/", ip, self.port),
+            _ => format!("http://{}:{}/", self.address, self.port),
+        }
+    }
+
+    /// Whether the address still belongs to this machine; the site block
+    /// is left out otherwise so Caddy can start on another network
+    pub fn is_bindable(&self) -> bool {
+        self.address
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| TcpListener::bind((ip, 0)).is_ok())
+    }
+}
+
+/// This machine's address on the LAN
+///
+/// The interface of the default route; connecting a UDP socket sends no
+/// packets.
+pub fn lan_ip() -> Result<IpAddr, String> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0))
+        .map_err(|e| format!("Failed to detect the LAN address: {}", e))?;
+    socket
+        .connect(("192.0.2.1", 80))
+        .map_err(|_| "This machine is not connected to a network".to_string())?;
+    let ip = socket
+        .local_addr()
+        .map_err(|e| format!("Failed to detect the LAN address: {}", e))?
+        .ip();
+    if ip.is_loopback() || ip.is_unspecified() {
+        return Err("This machine is not connected to a network".to_string());
+    }
+    Ok(ip)
+}
+
+/// Share project `name` on the LAN, on a new port unless it is shared already
+///
+/// The port is opened in the firewall every time, the rule may have been
+/// removed since, e.g. by a firewalld reload.
+pub fn share(registry: &ProjectRegistry, name: &str) -> Result<Project, String> {
+    let mut project = registry.get(name)?;
+    let address = lan_ip()?.to_string();
+    let port = match &project.lan_share {
+        // Same port, possibly a new address after changing networks
+        Some(share) => share.port,
+        None => {
+            let taken: Vec<u16> = registry.list()?.iter().filter_map(|p| p.lan_share.as_ref()).map(|s| s.port).collect();
+            free_port(&taken)?
+        }
+    };
+    platform::open_port(port)?;
+    project.lan_share = Some(LanShare { address, port });
+    registry.insert(project.clone())?;
+    Ok(project)
+}
+
+/// Stop sharing project `name` and close its firewall port
+pub fn unshare(registry: &ProjectRegistry, name: &str) -> Result<Project, String> {
+    let mut project = registry.get(name)?;
+    let share = project.lan_share.take()
+        .ok_or_else(|| format!("Project '{}' is not shared on the LAN", name))?;
+    close_port(&share);
+    registry.insert(project.clone())?;
+    Ok(project)
+}
+
+/// Stop sharing every shared project, returns the names of those that were
+pub fn unshare_all(registry: &ProjectRegistry) -> Result<Vec<String>, String> {
+    let mut unshared = Vec::new();
+    for project in registry.list()?.into_iter().filter(|p| p.lan_share.is_some()) {
+        unshare(registry, &project.name)?;
+        unshared.push(project.name);
+    }
+    Ok(unshared)
+}
+
+/// Close the firewall port of `share`, a failure is only logged
+pub fn close_port(share: &LanShare) {
+    if let Err(e) = platform::close_port(share.port) {
+        tracing::warn!("Failed to close port {} in the firewall: {}", share.port, e);
+    }
+}
+
+/// First port from `SHARE_BASE_PORT` that no other share uses and nothing
+/// listens on
+fn free_port(taken: &[u16]) -> Result<u16, String> {
+    (SHARE_BASE_PORT..SHARE_BASE_PORT + 100)
+        .find(|port| !taken.contains(port) && crate::config::ports::is_port_available(*port))
+        .ok_or_else(|| format!("No free port between {} and {}", SHARE_BASE_PORT, SHARE_BASE_PORT + 99))
+}
+
+/// Render `url` as a QR code
+pub fn qr_code(url: &str, format: QrFormat) -> Result<ShareQr, String> {
+    let code = qrcode::QrCode::new(url.as_bytes())
+        .map_err(|e| format!("Failed to create a QR code for {}: {}", url, e))?;
+    let data = match format {
+        QrFormat::Png => {
+            let image = code.render::<image::Luma<u8>>().min_dimensions(256, 256).build();
+            let mut png = std::io::Cursor::new(Vec::new());
+            image.write_to(&mut png, image::ImageFormat::Png)
+                .map_err(|e| format!("Failed to encode the QR code: {}", e))?;
+            png.into_inner()
+        }
+        QrFormat::Svg => code.render::<qrcode::render::svg::Color>()
+            .min_dimensions(256, 256)
+            .build()
+            .into_bytes(),
+    };
+    Ok(ShareQr { url: url.to_string(), format, data })
+}
+
+/// Name of the firewall rule for `port`
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn rule_name(port: u16) -> String {
+    format!("CAMPP-LAN-share-{}", port)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::rule_name;
+    use crate::elevate;
+
+    // The rule is replaced, netsh would add a second one with the same name
+    pub fn open_port(port: u16) -> Result<(), String> {
+        elevate::run(
+            &format!(
+                "netsh advfirewall firewall delete rule name={name} | Out-Null; \
+                 netsh advfirewall firewall add rule name={name} dir=in action=allow protocol=TCP localport={port}",
+                name = rule_name(port),
+                port = port
+            ),
+            "opening the port in the firewall",
+        )
+    }
+
+    pub fn close_port(port: u16) -> Result<(), String> {
+        elevate::run(
+            &format!("netsh advfirewall firewall delete rule name={}", rule_name(port)),
+            "closing the port in the firewall",
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::rule_name;
+    use crate::elevate;
+    use crate::process::run_quiet;
+
+    // Only the runtime configuration of firewalld is changed, a reboot
+    // closes the port again
+    pub fn open_port(port: u16) -> Result<(), String> {
+        if run_quiet("firewall-cmd", &["--state"]) {
+            elevate::run(&format!("firewall-cmd --add-port={}/tcp", port), "opening the port in the firewall")
+        } else if run_quiet("systemctl", &["is-active", "--quiet", "ufw"]) {
+            elevate::run(
+                &format!("ufw allow {}/tcp comment {}", port, rule_name(port)),
+                "opening the port in the firewall",
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn close_port(port: u16) -> Result<(), String> {
+        if run_quiet("firewall-cmd", &["--state"]) {
+            elevate::run(&format!("firewall-cmd --remove-port={}/tcp", port), "closing the port in the firewall")
+        } else if run_quiet("systemctl", &["is-active", "--quiet", "ufw"]) {
+            elevate::run(&format!("ufw delete allow {}/tcp", port), "closing the port in the firewall")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub fn open_port(_port: u16) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn close_port(_port: u16) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_url() {
+        let share = LanShare { address: "192.168.1.20".to_string(), port: 8100 };
+        assert_eq!(share.url(), "http://192.168.1.20:8100/");
+        let share = LanShare { address: "fd00::20".to_string(), port: 8101 };
+        assert_eq!(share.url(), "http://[fd00::20]:8101/");
+        let share = LanShare { address: "127.0.0.1".to_string(), port: 8100 };
+        assert!(share.is_bindable());
+        let share = LanShare { address: "192.0.2.1".to_string(), port: 8100 };
+        assert!(!share.is_bindable());
+    }
+
+    #[test]
+    fn test_qr_code() {
+        let qr = qr_code("http://192.168.1.20:8100/", QrFormat::Png).unwrap();
+        assert!(qr.data.starts_with(b"\x89PNG"));
+        let image = image::load_from_memory(&qr.data).unwrap();
+        assert!(image.width() >= 256);
+
+        let qr = qr_code("https://calm-sea-1234.trycloudflare.com", QrFormat::Svg).unwrap();
+        assert!(String::from_utf8(qr.data).unwrap().contains("<svg"));
+    }
+
+    #[test]
+    fn test_free_port() {
+        let port = free_port(&[SHARE_BASE_PORT]).unwrap();
+        assert!(port > SHARE_BASE_PORT);
+    }
+}
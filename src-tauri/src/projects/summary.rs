@@ -0,0 +1,130 @@
+//! Project overview for the dashboard
+//!
+//! Everything the project grid shows is gathered here so it renders from a
+//! single `list_projects` call.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::{detect, Framework, Project};
+
+/// Folders whose churn says nothing about the project being worked on
+const IGNORED_DIRS: [&str; 3] = [".git", "node_modules", "vendor"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSummary {
+    #[serde(flatten)]
+    pub project: Project,
+    /// `http://<domain>:<port>` with a registered domain, otherwise
+    /// `http://localhost:<port>/<name>/`. HTTPS projects use
+    /// `https://<domain or name.localhost>:<https port>`
+    pub url: String,
+    /// Whether the project folder is still there
+    pub exists: bool,
+    /// Framework detected from the files currently in the folder
+    pub detected_framework: Framework,
+    /// MySQL databases the project needs
+    pub databases: Vec<String>,
+    /// Whether all of them exist; None without databases or when MySQL was
+    /// not queried
+    pub databases_exist: Option<bool>,
+    /// Newest modification of the folder or its top-level entries, seconds
+    /// since the Unix epoch
+    pub modified_at: Option<u64>,
+    /// Whether `Caddyfile.project` waits to be allowed
+    pub caddy_file_pending: bool,
+}
+
+/// Summarize `project`, with `existing` the databases on the running MySQL
+pub fn summarize(project: Project, web_port: u16, https_port: u16, existing: Option<&[String]>) -> ProjectSummary {
+    let dir = Path::new(&project.path);
+    let exists = dir.is_dir();
+    let url = match &project.domain {
+        Some(domain) if project.https => super::https_origin(domain, https_port),
+        None if project.https => super::https_origin(&super::site_host(&project.name), https_port),
+        Some(domain) => format!("http://{}:{}", domain, web_port),
+        None => format!("http://localhost:{}/{}/", web_port, project.name),
+    };
+    let databases = if exists { project.required_databases() } else { Vec::new() };
+    let databases_exist = match existing {
+        Some(existing) if !databases.is_empty() => Some(databases.iter().all(|d| existing.contains(d))),
+        _ => None,
+    };
+
+    ProjectSummary {
+        url,
+        exists,
+        detected_framework: if exists { detect::detect(dir).framework } else { project.framework },
+        databases,
+        databases_exist,
+        modified_at: if exists { modified_at(dir) } else { None },
+        caddy_file_pending: exists && project.caddy_file_pending(),
+        project,
+    }
+}
+
+/// Newest mtime of `dir` and its direct entries
+///
+/// Deeper files are not walked, saving a file in a subfolder still touches
+/// that subfolder.
+fn modified_at(dir: &Path) -> Option<u64> {
+    let mtime = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+    };
+    let entries = fs::read_dir(dir).ok()?;
+    entries
+        .flatten()
+        .filter(|entry| !IGNORED_DIRS.iter().any(|ignored| entry.file_name() == *ignored))
+        .filter_map(|entry| mtime(&entry.path()))
+        .chain(mtime(dir))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("shop");
+        fs::create_dir_all(dir.join("public")).unwrap();
+        fs::write(dir.join("artisan"), "").unwrap();
+        fs::write(dir.join(".env"), "DB_CONNECTION=mysql\nDB_DATABASE=shop\n").unwrap();
+        let project = Project {
+            name: "shop".to_string(),
+            path: dir.to_string_lossy().to_string(),
+            framework: Framework::Php,
+            created_at: 1,
+            ..Default::default()
+        };
+
+        let summary = summarize(project.clone(), 8080, 8443, Some(&["blog".to_string()]));
+        assert_eq!(summary.url, "http://localhost:8080/shop/");
+        assert_eq!(summary.detected_framework, Framework::Laravel);
+        assert_eq!(summary.databases, vec!["shop"]);
+        assert_eq!(summary.databases_exist, Some(false));
+        assert!(summary.modified_at.is_some());
+
+        let project = Project { domain: Some("shop.test".to_string()), ..project };
+        let summary = summarize(project.clone(), 8080, 8443, None);
+        assert_eq!(summary.url, "http://shop.test:8080");
+        assert_eq!(summary.databases_exist, None);
+
+        let secure = Project { https: true, ..project.clone() };
+        assert_eq!(summarize(secure, 8080, 8443, None).url, "https://shop.test:8443");
+        let secure = Project { https: true, domain: None, ..project };
+        assert_eq!(summarize(secure, 8080, 443, None).url, "https://shop.localhost");
+
+        let gone = Project { path: temp_dir.path().join("gone").to_string_lossy().to_string(), ..summary.project };
+        let summary = summarize(gone, 8080, 8443, None);
+        assert!(!summary.exists);
+        assert_eq!(summary.modified_at, None);
+    }
+}
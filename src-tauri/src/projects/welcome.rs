@@ -0,0 +1,179 @@
+//! The welcome project
+//!
+//! Created once after the first runtime install so the setup wizard ends on
+//! a page that proves PHP and the database work. The page reads its database
+//! settings from environment variables, which Caddy passes to PHP. They are
+//! filled in from settings whenever the Caddyfile is generated, so neither
+//! the project folder nor the project registry holds the password and a
+//! changed port or password is picked up.
+
+use std::fs;
+use std::path::Path;
+
+use super::{Framework, Project, ProjectRegistry};
+use crate::config::AppSettings;
+
+pub const WELCOME_PROJECT: &str = "welcome";
+
+const WELCOME_INDEX_PHP: &str = r#"<?php
+$checks = [];
+
+$host = getenv('DB_HOST') ?: '127.0.0.1';
+$port = (int) (getenv('DB_PORT') ?: 3306);
+$user = getenv('DB_USERNAME') ?: 'root';
+$password = getenv('DB_PASSWORD') ?: '';
+
+if (extension_loaded('mysqli')) {
+    mysqli_report(MYSQLI_REPORT_OFF);
+    $db = @new mysqli($host, $user, $password, '', $port);
+    $checks['Database'] = $db->connect_error
+        ? [false, 'Cannot connect to ' . $host . ':' . $port . ': ' . $db->connect_error]
+        : [true, $db->server_info . ' on ' . $host . ':' . $port];
+} else {
+    $checks['Database'] = [false, 'The mysqli extension is not loaded'];
+}
+
+$settings = [
+    'PHP version' => PHP_VERSION . ' (' . PHP_SAPI . ')',
+    'memory_limit' => ini_get('memory_limit'),
+    'upload_max_filesize' => ini_get('upload_max_filesize'),
+    'max_execution_time' => ini_get('max_execution_time') . 's',
+];
+$extensions = get_loaded_extensions();
+sort($extensions, SORT_NATURAL | SORT_FLAG_CASE);
+
+$phpmyadmin = '//localhost:' . $_SERVER['SERVER_PORT'] . '/phpmyadmin/';
+$e = fn($value) => htmlspecialchars((string) $value, ENT_QUOTES);
+?>
+<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Welcome to CAMPP</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 48rem; margin: 3rem auto; padding: 0 1rem; color: #222; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5rem; }
+th, td { text-align: left; padding: .4rem .6rem; border-bottom: 1px solid #ddd; }
+.ok { color: #15803d; } .fail { color: #b91c1c; }
+code { background: #f3f4f6; padding: .1rem .3rem; border-radius: 3px; }
+</style>
+</head>
+<body>
+<h1>CAMPP is running</h1>
+<p>This page is the <code>welcome</code> project. Create your own projects from the CAMPP window.</p>
+
+<h2>Checks</h2>
+<table>
+<?php foreach ($checks as $name => [$ok, $detail]): ?>
+<tr><th><?= $e($name) ?></th><td class="<?= $ok ? 'ok' : 'fail' ?>"><?= $ok ? '&#10003;' : '&#10007;' ?> <?= $e($detail) ?></td></tr>
+<?php endforeach; ?>
+</table>
+<p><a href="<?= $e($phpmyadmin) ?>">Open phpMyAdmin</a></p>
+
+<h2>PHP</h2>
+<table>
+<?php foreach ($settings as $name => $value): ?>
+<tr><th><?= $e($name) ?></th><td><?= $e($value) ?></td></tr>
+<?php endforeach; ?>
+</table>
+<p><strong>Extensions:</strong> <?= $e(implode(', ', $extensions)) ?></p>
+</body>
+</html>
+"#;
+
+/// Create the welcome project unless it was created before
+///
+/// Marks it as created in `settings`, which the caller saves. A project or
+/// folder already named `welcome` is left alone.
+pub fn ensure(registry: &ProjectRegistry, projects_dir: &Path, settings: &mut AppSettings) -> Result<Option<Project>, String> {
+    if settings.welcome_project_created {
+        return Ok(None);
+    }
+    settings.welcome_project_created = true;
+    if registry.check_new(projects_dir, WELCOME_PROJECT).is_err() {
+        return Ok(None);
+    }
+    create(registry, projects_dir).map(Some)
+}
+
+/// Create and register the welcome project in `projects_dir`
+pub fn create(registry: &ProjectRegistry, projects_dir: &Path) -> Result<Project, String> {
+    let dir = registry.check_new(projects_dir, WELCOME_PROJECT)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    fs::write(dir.join("index.php"), WELCOME_INDEX_PHP)
+        .map_err(|e| format!("Failed to write index.php: {}", e))?;
+
+    let project = Project {
+        name: WELCOME_PROJECT.to_string(),
+        path: dir.to_string_lossy().to_string(),
+        framework: Framework::Php,
+        created_at: crate::util::now(),
+        ..Default::default()
+    };
+    registry.insert(project.clone())?;
+    Ok(project)
+}
+
+/// Environment pointing the welcome page at the bundled MySQL/MariaDB
+pub fn database_env(settings: &AppSettings) -> [(String, String); 4] {
+    [
+        ("DB_HOST".to_string(), "127.0.0.1".to_string()),
+        ("DB_PORT".to_string(), settings.mysql_port.to_string()),
+        ("DB_USERNAME".to_string(), "root".to_string()),
+        ("DB_PASSWORD".to_string(), settings.mysql_root_password.clone()),
+    ]
+}
+
+/// Drop the database variables earlier versions copied into the welcome
+/// project's registry entry
+pub fn forget_stored_credentials(registry: &ProjectRegistry) -> Result<(), String> {
+    let Ok(mut project) = registry.get(WELCOME_PROJECT) else {
+        return Ok(());
+    };
+    let before = project.env.len();
+    project.env.retain(|key, _| !matches!(key.as_str(), "DB_HOST" | "DB_PORT" | "DB_USERNAME" | "DB_PASSWORD"));
+    if project.env.len() != before {
+        registry.insert(project)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_welcome() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let registry = ProjectRegistry::at(temp_dir.path().join("projects.json"));
+
+        let project = create(&registry, &projects_dir).unwrap();
+        assert!(project.env.is_empty());
+        assert!(fs::read_to_string(projects_dir.join("welcome").join("index.php")).unwrap().contains("getenv('DB_PORT')"));
+        assert_eq!(registry.get(WELCOME_PROJECT).unwrap(), project);
+        assert!(create(&registry, &projects_dir).is_err());
+
+        let stored = Project { env: database_env(&AppSettings::default()).into_iter().collect(), ..project };
+        registry.insert(stored).unwrap();
+        forget_stored_credentials(&registry).unwrap();
+        assert!(registry.get(WELCOME_PROJECT).unwrap().env.is_empty());
+    }
+
+    #[test]
+    fn test_ensure_once() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let projects_dir = temp_dir.path().join("projects");
+        let registry = ProjectRegistry::at(temp_dir.path().join("projects.json"));
+        let mut settings = AppSettings::default();
+
+        assert!(ensure(&registry, &projects_dir, &mut settings).unwrap().is_some());
+        assert!(settings.welcome_project_created);
+
+        // Deleted by the user, not recreated on the next install
+        registry.delete(&projects_dir, WELCOME_PROJECT, true).unwrap();
+        assert!(ensure(&registry, &projects_dir, &mut settings).unwrap().is_none());
+        assert!(!projects_dir.join(WELCOME_PROJECT).exists());
+    }
+}
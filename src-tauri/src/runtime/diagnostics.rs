@@ -0,0 +1,228 @@
+//! Diagnostics bundle
+//!
+//! Collects what is needed to debug a user's install into one zip: the
+//! system and service summary, settings, generated configs and the end of
+//! every current log. Passwords, the phpMyAdmin auth hash and the phpMyAdmin
+//! cookie secret are replaced with `[REDACTED]` before anything is written.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::AppSettings;
+use crate::process::ServiceInfo;
+
+/// Bytes kept from the end of each log
+const LOG_TAIL_BYTES: u64 = 2 * 1024 * 1024;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Summary written to system.json
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfo {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub portable: bool,
+    pub data_dir: String,
+    /// Installed component versions by component name
+    pub installed: HashMap<String, String>,
+    /// Service states, including the last error of each
+    pub services: Vec<ServiceInfo>,
+}
+
+/// Files and directories the bundle is built from
+#[derive(Debug, Clone)]
+pub struct BundleSources {
+    pub logs_dir: PathBuf,
+    /// Generated config files, stored under config/ by file name
+    pub configs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub path: String,
+    /// Entries written to the archive
+    pub files: Vec<String>,
+}
+
+/// Write the diagnostics zip to `dest`
+pub fn export_bundle(dest: &Path, sources: &BundleSources, settings: &AppSettings, system: &SystemInfo) -> Result<DiagnosticsReport, String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let file = File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let mut files = Vec::new();
+    let secrets = secrets(settings);
+
+    let mut add = |name: String, content: &[u8]| -> Result<(), String> {
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file(name.as_str(), options)
+            .and_then(|_| zip.write_all(content).map_err(Into::into))
+            .map_err(|e| format!("Failed to write {} to diagnostics bundle: {}", name, e))?;
+        files.push(name);
+        Ok(())
+    };
+
+    let system_json = serde_json::to_string_pretty(system)
+        .map_err(|e| format!("Failed to serialize system info: {}", e))?;
+    add("system.json".to_string(), system_json.as_bytes())?;
+
+    let settings_json = serde_json::to_string_pretty(&redact_settings(settings))
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    add("settings.json".to_string(), settings_json.as_bytes())?;
+
+    for config in &sources.configs {
+        let Ok(content) = fs::read(config) else { continue };
+        let name = config.file_name().unwrap_or_default().to_string_lossy();
+        let content = redact(&String::from_utf8_lossy(&content), &secrets);
+        add(format!("config/{}", name), content.as_bytes())?;
+    }
+
+    let mut logs: Vec<PathBuf> = fs::read_dir(&sources.logs_dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    logs.retain(|p| p.is_file() && p.extension().is_some_and(|e| e == "log"));
+    logs.sort();
+    for log in logs {
+        let content = match read_tail(&log, LOG_TAIL_BYTES) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Skipping {} in diagnostics bundle: {}", log.display(), e);
+                continue;
+            }
+        };
+        let name = log.file_name().unwrap_or_default().to_string_lossy();
+        let content = redact(&String::from_utf8_lossy(&content), &secrets);
+        add(format!("logs/{}", name), content.as_bytes())?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish diagnostics bundle: {}", e))?;
+    Ok(DiagnosticsReport { path: dest.to_string_lossy().to_string(), files })
+}
+
+/// Secret values that may appear verbatim in configs and logs
+fn secrets(settings: &AppSettings) -> Vec<String> {
+    let mut secrets = vec![settings.mysql_root_password.clone(), settings.postgres_root_password.clone()];
+    if let Some(ref auth) = settings.phpmyadmin_auth {
+        secrets.push(auth.password_hash.clone());
+    }
+    // Generated PHP files escape quotes and backslashes
+    let escaped: Vec<String> = secrets.iter()
+        .map(|s| s.replace('\\', "\\\\").replace('\'', "\\'"))
+        .collect();
+    secrets.extend(escaped);
+    secrets.retain(|s| !s.is_empty());
+    // Longest first, so an escaped form is not left half replaced
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    secrets.dedup();
+    secrets
+}
+
+fn redact(text: &str, secrets: &[String]) -> String {
+    let mut text = text.to_string();
+    for secret in secrets {
+        text = text.replace(secret.as_str(), REDACTED);
+    }
+    text.split('\n')
+        .map(|line| {
+            if line.contains("blowfish_secret") {
+                format!("$cfg['blowfish_secret'] = '{}';", REDACTED)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn redact_settings(settings: &AppSettings) -> AppSettings {
+    let mut settings = settings.clone();
+    for password in [&mut settings.mysql_root_password, &mut settings.postgres_root_password] {
+        if !password.is_empty() {
+            *password = REDACTED.to_string();
+        }
+    }
+    if let Some(ref mut auth) = settings.phpmyadmin_auth {
+        auth.password_hash = REDACTED.to_string();
+    }
+    settings
+}
+
+fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(max_bytes)))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BasicAuthCredentials;
+    use tempfile::TempDir;
+
+    fn settings_with_secrets() -> AppSettings {
+        AppSettings {
+            mysql_root_password: "it's-secret".to_string(),
+            phpmyadmin_auth: Some(BasicAuthCredentials {
+                username: "admin".to_string(),
+                password_hash: "$2b$12$hash".to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_redact() {
+        let secrets = secrets(&settings_with_secrets());
+        let config = "$cfg['blowfish_secret'] = 'abcdef';\n$cfg['Servers'][$i]['password'] = 'it\\'s-secret';\n    admin $2b$12$hash";
+        let redacted = redact(config, &secrets);
+        assert!(!redacted.contains("abcdef"));
+        assert!(!redacted.contains("s-secret"));
+        assert!(!redacted.contains("$2b$12$hash"));
+        assert!(redacted.contains("admin [REDACTED]"));
+
+        let settings = redact_settings(&settings_with_secrets());
+        assert_eq!(settings.mysql_root_password, REDACTED);
+        assert!(settings.postgres_root_password.is_empty());
+    }
+
+    #[test]
+    fn test_export_bundle() {
+        let temp_dir = TempDir::new().unwrap();
+        let logs_dir = temp_dir.path().join("logs");
+        fs::create_dir_all(&logs_dir).unwrap();
+        fs::write(logs_dir.join("mysql.log"), "login with it's-secret failed\n").unwrap();
+        fs::write(logs_dir.join("caddy.log.1760000000.gz"), "old").unwrap();
+        let caddyfile = temp_dir.path().join("Caddyfile");
+        fs::write(&caddyfile, ":8080 {\n}\n").unwrap();
+
+        let system = SystemInfo {
+            app_version: "0.0.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            portable: false,
+            data_dir: temp_dir.path().to_string_lossy().to_string(),
+            installed: HashMap::new(),
+            services: Vec::new(),
+        };
+        let sources = BundleSources { logs_dir, configs: vec![caddyfile, temp_dir.path().join("php.ini")] };
+        let dest = temp_dir.path().join("out").join("diagnostics.zip");
+        let report = export_bundle(&dest, &sources, &settings_with_secrets(), &system).unwrap();
+        assert_eq!(report.files, vec!["system.json", "settings.json", "config/Caddyfile", "logs/mysql.log"]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        let mut log = String::new();
+        archive.by_name("logs/mysql.log").unwrap().read_to_string(&mut log).unwrap();
+        assert_eq!(log, "login with [REDACTED] failed\n");
+    }
+}
@@ -0,0 +1,142 @@
+//! Disk usage report
+//!
+//! Sizes the installed components, database data directories, logs, backups
+//! and the download cache so users can see what is worth pruning.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use super::downloader::BinaryComponent;
+use super::locator::get_app_data_paths;
+use crate::util::dir_size;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiskUsageItem {
+    /// Stable key, e.g. "php" or "mysql_data"
+    pub name: String,
+    pub label: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    pub items: Vec<DiskUsageItem>,
+    pub total_bytes: u64,
+}
+
+/// Walk the runtime and data directories and size every part of the install
+pub fn get_disk_usage() -> Result<DiskUsage, String> {
+    let app_paths = get_app_data_paths()?;
+    let mut items = component_usage(&app_paths.runtime_dir);
+
+    let data = [
+        ("mysql_data", "MySQL/MariaDB data", app_paths.mysql_data_dir.clone()),
+        ("pgsql_data", "PostgreSQL data", app_paths.pgsql_data_dir.clone()),
+        ("minio_data", "MinIO buckets", crate::process::minio::data_dir(&crate::process::minio::minio_dir(&app_paths.base_dir))),
+        ("logs", "Logs", app_paths.logs_dir.clone()),
+        ("backups", "Backups", crate::database::backup::backups_dir()?),
+        ("download_cache", "Download cache", super::download_cache::download_dir()),
+    ];
+    for (name, label, path) in data {
+        items.push(DiskUsageItem { name: name.to_string(), label: label.to_string(), bytes: dir_size(&path) });
+    }
+
+    let total_bytes = items.iter().map(|item| item.bytes).sum();
+    Ok(DiskUsage { items, total_bytes })
+}
+
+/// Free bytes on the volume holding `path`
+pub fn free_space(path: &Path) -> Result<u64, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let script = format!("(Get-Item -LiteralPath '{}').PSDrive.Free", path.display().to_string().replace('\'', "''"));
+        let output = crate::process::manager::configure_no_window(std::process::Command::new("powershell"))
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .output()
+            .map_err(|e| format!("Failed to run powershell: {}", e))?;
+        String::from_utf8_lossy(&output.stdout).trim().parse::<u64>()
+            .map_err(|_| format!("Failed to read free space of {}", path.display()))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // POSIX output: filesystem, 1024-blocks, used, available, capacity, mount point
+        let output = std::process::Command::new("df")
+            .arg("-Pk")
+            .arg(path)
+            .output()
+            .map_err(|e| format!("Failed to run df: {}", e))?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .nth(1)
+            .and_then(|line| line.split_whitespace().nth(3))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+            .ok_or_else(|| format!("Failed to read free space of {}", path.display()))
+    }
+}
+
+/// Size of each installed component in `runtime_dir`, plus whatever is left
+fn component_usage(runtime_dir: &Path) -> Vec<DiskUsageItem> {
+    let mut items = Vec::new();
+    let mut counted = 0;
+    for component in BinaryComponent::ALL {
+        let bytes: u64 = super::upgrade::component_entries(runtime_dir, component)
+            .iter()
+            // MySQL and MariaDB share their entries for upgrades, split them by name
+            .filter(|path| match component {
+                BinaryComponent::MariaDB | BinaryComponent::MySQL => path
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().to_lowercase().starts_with(component.binary_name())),
+                _ => true,
+            })
+            .map(|path| entry_size(path))
+            .sum();
+        counted += bytes;
+        if bytes > 0 {
+            items.push(DiskUsageItem {
+                name: component.binary_name().to_string(),
+                label: component.name().to_string(),
+                bytes,
+            });
+        }
+    }
+
+    let other = dir_size(runtime_dir).saturating_sub(counted);
+    if other > 0 {
+        items.push(DiskUsageItem { name: "runtime_other".to_string(), label: "Other runtime files".to_string(), bytes: other });
+    }
+    items
+}
+
+fn entry_size(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => dir_size(path),
+        Ok(m) if m.is_file() => m.len(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_component_usage() {
+        let temp_dir = TempDir::new().unwrap();
+        let runtime_dir = temp_dir.path();
+        fs::create_dir_all(runtime_dir.join("mariadb-12.3.1-linux-systemd-x86_64").join("bin")).unwrap();
+        fs::write(runtime_dir.join("mariadb-12.3.1-linux-systemd-x86_64").join("bin").join("mariadbd"), vec![0u8; 100]).unwrap();
+        fs::write(runtime_dir.join("caddy"), vec![0u8; 40]).unwrap();
+        fs::write(runtime_dir.join("caddy_installed.txt"), vec![0u8; 5]).unwrap();
+
+        let items = component_usage(runtime_dir);
+        let size = |name: &str| items.iter().find(|i| i.name == name).map(|i| i.bytes);
+        assert_eq!(size("caddy"), Some(40));
+        assert_eq!(size("mariadb"), Some(100));
+        assert_eq!(size("mysql"), None);
+        // Marker files are not part of any component
+        assert_eq!(size("runtime_other"), Some(5));
+    }
+}
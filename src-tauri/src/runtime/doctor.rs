@@ -0,0 +1,262 @@
+//! Troubleshooting
+//!
+//! Looks for the failure modes behind most "it won't start" reports and
+//! explains each one with a suggested fix: a port held by another
+//! application, a missing Visual C++ runtime, a damaged MySQL data directory,
+//! a Caddyfile pointing at directories that moved, and binaries removed by
+//! an antivirus after they were installed.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::locator::{AppDataPaths, RuntimePaths};
+use crate::config::AppSettings;
+use crate::process::{ServiceMap, ServiceState, ServiceType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    /// Stable identifier, e.g. "port_conflict"
+    pub code: String,
+    pub severity: Severity,
+    pub service: Option<ServiceType>,
+    pub title: String,
+    pub detail: String,
+    /// What the user can do about it
+    pub fix: String,
+}
+
+/// State the checks run against
+pub struct DoctorContext {
+    pub app_paths: AppDataPaths,
+    pub paths: Option<RuntimePaths>,
+    pub settings: AppSettings,
+    pub services: ServiceMap,
+}
+
+/// Lines of mysql.log that mean the data directory is damaged
+const MYSQL_CORRUPTION_MARKERS: [&str; 5] = [
+    "Database page corruption",
+    "is marked as crashed",
+    "Can't open and lock privilege tables",
+    "Table 'mysql.",
+    "InnoDB: Plugin initialization aborted",
+];
+
+/// Run every check, most severe findings first
+pub fn run_doctor(ctx: &DoctorContext) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    findings.extend(port_conflicts(ctx));
+    findings.extend(missing_vc_runtime());
+    findings.extend(mysql_data_dir(&ctx.app_paths.mysql_data_dir, &ctx.app_paths.logs_dir));
+    if let Some(ref paths) = ctx.paths {
+        findings.extend(stale_caddyfile(&paths.config_dir.join("Caddyfile")));
+    }
+    findings.extend(quarantined_binaries(&ctx.app_paths.runtime_dir));
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    findings
+}
+
+/// Ports of enabled, stopped services that something else listens on
+fn port_conflicts(ctx: &DoctorContext) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (service, info) in &ctx.services {
+        if !ctx.settings.is_enabled(*service) || matches!(info.state, ServiceState::Running | ServiceState::Paused) {
+            continue;
+        }
+        if !crate::config::is_port_in_use(info.port) {
+            continue;
+        }
+        let owner = crate::config::port_owner(info.port);
+        let detail = match owner {
+            Some(ref owner) => format!("Port {} is held by {} (PID {})", info.port, owner.name, owner.pid),
+            None => format!("Port {} is held by another application", info.port),
+        };
+        let free = crate::config::find_available_port(info.port.saturating_add(1));
+        findings.push(Finding {
+            code: "port_conflict".to_string(),
+            severity: Severity::Error,
+            service: Some(*service),
+            title: format!("{} cannot bind its port", service.display_name()),
+            detail,
+            fix: format!("Stop that application, or change the {} port to {} in settings", service.display_name(), free),
+        });
+    }
+    findings
+}
+
+/// vcruntime140.dll and msvcp140.dll, needed by PHP and MariaDB on Windows
+#[cfg(target_os = "windows")]
+fn missing_vc_runtime() -> Vec<Finding> {
+    let system32 = std::env::var_os("SystemRoot")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(r"C:\Windows"))
+        .join("System32");
+    let missing: Vec<&str> = ["vcruntime140.dll", "vcruntime140_1.dll", "msvcp140.dll"]
+        .into_iter()
+        .filter(|dll| !system32.join(dll).exists())
+        .collect();
+    if missing.is_empty() {
+        return Vec::new();
+    }
+    vec![Finding {
+        code: "vc_runtime_missing".to_string(),
+        severity: Severity::Error,
+        service: None,
+        title: "Microsoft Visual C++ runtime is not installed".to_string(),
+        detail: format!("Missing from {}: {}", system32.display(), missing.join(", ")),
+        fix: "Install the Visual C++ Redistributable from https://aka.ms/vs/17/release/vc_redist.x64.exe".to_string(),
+    }]
+}
+
+#[cfg(not(target_os = "windows"))]
+fn missing_vc_runtime() -> Vec<Finding> {
+    Vec::new()
+}
+
+/// An initialization that never finished, or corruption reported in the log
+fn mysql_data_dir(data_dir: &Path, logs_dir: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let has_files = fs::read_dir(data_dir).is_ok_and(|mut entries| entries.next().is_some());
+    if has_files && !data_dir.join("mysql").is_dir() {
+        findings.push(Finding {
+            code: "mysql_data_incomplete".to_string(),
+            severity: Severity::Error,
+            service: Some(ServiceType::MySQL),
+            title: "MySQL data directory is incomplete".to_string(),
+            detail: format!("{} has no mysql system schema, the first initialization was likely interrupted", data_dir.display()),
+            fix: "Move the directory aside so it is initialized again on the next start, then restore your databases from a backup".to_string(),
+        });
+    }
+
+    let log = crate::process::logs::log_path(logs_dir, ServiceType::MySQL);
+    let lines = crate::process::logs::last_lines(&log, 200).unwrap_or_default();
+    if let Some(line) = lines.iter().rev().find(|l| MYSQL_CORRUPTION_MARKERS.iter().any(|m| l.contains(m))) {
+        findings.push(Finding {
+            code: "mysql_data_corrupted".to_string(),
+            severity: Severity::Error,
+            service: Some(ServiceType::MySQL),
+            title: "MySQL reported damaged tables".to_string(),
+            detail: line.trim().to_string(),
+            fix: "Restore the affected databases from a backup, or start MySQL with innodb_force_recovery to dump what is readable".to_string(),
+        });
+    }
+    findings
+}
+
+/// Site roots and log directories in the Caddyfile that no longer exist
+fn stale_caddyfile(caddyfile: &Path) -> Vec<Finding> {
+    let Ok(content) = fs::read_to_string(caddyfile) else {
+        return Vec::new();
+    };
+    let missing: Vec<String> = content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let quoted = PathBuf::from(line.split('"').nth(1)?);
+            // Log files are created on start, only their directory must exist
+            let dir = if line.starts_with("root ") {
+                quoted
+            } else if line.starts_with("output file ") {
+                quoted.parent()?.to_path_buf()
+            } else {
+                return None;
+            };
+            (dir.is_absolute() && !dir.exists()).then(|| dir.to_string_lossy().to_string())
+        })
+        .collect();
+    if missing.is_empty() {
+        return Vec::new();
+    }
+
+    let fix = if crate::config::generated::is_hand_edited(caddyfile) {
+        "The Caddyfile was edited by hand, update the paths or delete it so it is generated again".to_string()
+    } else {
+        "Restart Caddy to generate the Caddyfile from the current settings".to_string()
+    };
+    vec![Finding {
+        code: "stale_caddyfile".to_string(),
+        severity: Severity::Warning,
+        service: Some(ServiceType::Caddy),
+        title: "Caddyfile refers to paths that no longer exist".to_string(),
+        detail: missing.join(", "),
+        fix,
+    }]
+}
+
+/// Binaries that ran after installation and have since disappeared, the
+/// usual sign of an antivirus quarantine
+fn quarantined_binaries(runtime_dir: &Path) -> Vec<Finding> {
+    super::validate::load_installed_manifest(runtime_dir)
+        .into_iter()
+        .filter(|(_, binary)| !binary.path.exists())
+        .map(|(component, binary)| Finding {
+            code: "binary_removed".to_string(),
+            severity: Severity::Error,
+            service: None,
+            title: format!("{} binary was removed after installation", component),
+            detail: format!("{} is missing, an antivirus may have quarantined it", binary.path.display()),
+            fix: format!(
+                "Restore it from your antivirus quarantine and exclude {}, or reinstall {} with Repair Installation",
+                runtime_dir.display(),
+                component
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mysql_data_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_dir = temp_dir.path().join("mysql");
+        let logs_dir = temp_dir.path().join("logs");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::create_dir_all(&logs_dir).unwrap();
+        assert!(mysql_data_dir(&data_dir, &logs_dir).is_empty());
+
+        fs::write(data_dir.join("ibdata1"), "").unwrap();
+        fs::write(logs_dir.join("mysql.log"), "[ERROR] Fatal error: Can't open and lock privilege tables: Table 'mysql.db' doesn't exist\n").unwrap();
+        let codes: Vec<String> = mysql_data_dir(&data_dir, &logs_dir).into_iter().map(|f| f.code).collect();
+        assert_eq!(codes, vec!["mysql_data_incomplete", "mysql_data_corrupted"]);
+    }
+
+    #[test]
+    fn test_stale_caddyfile() {
+        let temp_dir = TempDir::new().unwrap();
+        let caddyfile = temp_dir.path().join("Caddyfile");
+        let existing = temp_dir.path().to_string_lossy().to_string();
+        let moved = temp_dir.path().join("old-projects").to_string_lossy().to_string();
+        fs::write(&caddyfile, format!(":8080 {{\n    root * \"{}\"\n    log {{\n        output file \"{}/x.log\"\n    }}\n    header X \"/a b\"\n}}\n", moved, existing)).unwrap();
+
+        let findings = stale_caddyfile(&caddyfile);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].detail, moved);
+    }
+
+    #[test]
+    fn test_quarantined_binaries() {
+        let temp_dir = TempDir::new().unwrap();
+        let mysqld = temp_dir.path().join("mysqld");
+        let manifest = format!(
+            r#"{{"MariaDB": {{"version": "12.3.1", "path": {:?}, "validated_at": 0}}}}"#,
+            mysqld.to_string_lossy()
+        );
+        fs::write(temp_dir.path().join("installed-manifest.json"), manifest).unwrap();
+        assert_eq!(quarantined_binaries(temp_dir.path()).len(), 1);
+
+        fs::write(&mysqld, "").unwrap();
+        assert!(quarantined_binaries(temp_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+//! Cleanup of the temporary download directory
+//!
+//! Downloaded archives are kept in `<temp>/campp-download` after installation.
+//! The cleanup policy removes entries older than the configured age, then the
+//! oldest remaining ones until the directory fits the size limit. Archives of
+//! the installed component versions are kept when offline reinstall is
+//! enabled, so a broken component can be reinstalled without a network.
+//! Interrupted downloads are kept as `.part` files until they are resumed or
+//! expire. With `keep_after_install` set, all but the newest archives are
+//! removed as soon as an install succeeds.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::util::dir_size;
+
+fn default_max_age_days() -> u64 { 7 }
+fn default_max_size_mb() -> u64 { 1024 }
+
+/// Download cache policy stored in settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadCacheSettings {
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u64,
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Keep the archives of installed versions for offline reinstall
+    #[serde(default)]
+    pub keep_for_offline_reinstall: bool,
+    /// Archives kept once an install succeeds, None leaves them to the age
+    /// and size limits
+    #[serde(default)]
+    pub keep_after_install: Option<usize>,
+}
+
+impl Default for DownloadCacheSettings {
+    fn default() -> Self {
+        Self {
+            max_age_days: default_max_age_days(),
+            max_size_mb: default_max_size_mb(),
+            keep_for_offline_reinstall: false,
+            keep_after_install: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+    /// Archives kept for offline reinstall
+    pub protected: Vec<String>,
+}
+
+/// Directory downloads are written to
+pub fn download_dir() -> PathBuf {
+    std::env::temp_dir().join("campp-download")
+}
+
+/// Suffix of an archive whose download was interrupted
+pub const PARTIAL_SUFFIX: &str = ".part";
+
+/// Where an archive is written until its download completes
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(PARTIAL_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Remove finished downloads, keeping interrupted ones so they can be resumed
+pub fn clear_download_dir() {
+    clear_dir(&download_dir());
+}
+
+fn clear_dir(dir: &Path) {
+    let Ok(read_dir) = fs::read_dir(dir) else { return };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().ends_with(PARTIAL_SUFFIX) {
+            continue;
+        }
+        let result = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        if let Err(e) = result {
+            tracing::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Apply the cleanup policy to the download directory
+///
+/// `installed` maps component names to installed versions, as returned by
+/// `RuntimeDownloader::get_installed_components`.
+pub fn cleanup_download_dir(policy: &DownloadCacheSettings, installed: &HashMap<String, String>) -> CleanupReport {
+    cleanup_dir(&download_dir(), policy, &protected_prefixes(policy, installed), SystemTime::now())
+}
+
+/// Remove finished downloads except the `keep` most recent ones
+///
+/// Interrupted downloads are kept so they can be resumed, and so are the
+/// archives of installed versions when offline reinstall is enabled.
+pub fn cleanup_downloads(keep: usize, policy: &DownloadCacheSettings, installed: &HashMap<String, String>) -> CleanupReport {
+    keep_newest(&download_dir(), keep, &protected_prefixes(policy, installed))
+}
+
+fn protected_prefixes(policy: &DownloadCacheSettings, installed: &HashMap<String, String>) -> Vec<String> {
+    if policy.keep_for_offline_reinstall {
+        // Archives are named <component>-<version>.<ext>
+        installed.iter().map(|(name, version)| format!("{}-{}.", name, version)).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+struct CacheEntry {
+    path: PathBuf,
+    name: String,
+    size: u64,
+    modified: SystemTime,
+}
+
+fn cleanup_dir(dir: &Path, policy: &DownloadCacheSettings, protected_prefixes: &[String], now: SystemTime) -> CleanupReport {
+    let mut report = CleanupReport::default();
+    let Ok(read_dir) = fs::read_dir(dir) else { return report };
+
+    let mut entries = Vec::new();
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if protected_prefixes.iter().any(|p| name.starts_with(p.as_str())) {
+            report.protected.push(name);
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        let size = if metadata.is_dir() { dir_size(&path) } else { metadata.len() };
+        entries.push(CacheEntry {
+            path,
+            name,
+            size,
+            modified: metadata.modified().unwrap_or(now),
+        });
+    }
+    // Oldest first, so the size pass removes the least recent downloads
+    entries.sort_by_key(|e| e.modified);
+
+    let max_age = Duration::from_secs(policy.max_age_days.saturating_mul(24 * 60 * 60));
+    let max_size = policy.max_size_mb.saturating_mul(1024 * 1024);
+    let mut total: u64 = entries.iter().map(|e| e.size).sum();
+
+    for entry in entries {
+        let expired = now.duration_since(entry.modified).is_ok_and(|age| age > max_age);
+        if !expired && total <= max_size {
+            continue;
+        }
+
+        let result = if entry.path.is_dir() { fs::remove_dir_all(&entry.path) } else { fs::remove_file(&entry.path) };
+        match result {
+            Ok(()) => {
+                total -= entry.size;
+                report.freed_bytes += entry.size;
+                report.removed.push(entry.name);
+            }
+            Err(e) => tracing::warn!("Failed to remove {}: {}", entry.path.display(), e),
+        }
+    }
+
+    report
+}
+
+fn keep_newest(dir: &Path, keep: usize, protected_prefixes: &[String]) -> CleanupReport {
+    let mut report = CleanupReport::default();
+    let Ok(read_dir) = fs::read_dir(dir) else { return report };
+
+    let mut entries = Vec::new();
+    for entry in read_dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(PARTIAL_SUFFIX) {
+            continue;
+        }
+        if protected_prefixes.iter().any(|p| name.starts_with(p.as_str())) {
+            report.protected.push(name);
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        let path = entry.path();
+        let size = if metadata.is_dir() { dir_size(&path) } else { metadata.len() };
+        entries.push(CacheEntry { path, name, size, modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH) });
+    }
+    // Newest first, everything past the first `keep` goes
+    entries.sort_by_key(|e| std::cmp::Reverse(e.modified));
+
+    for entry in entries.into_iter().skip(keep) {
+        let result = if entry.path.is_dir() { fs::remove_dir_all(&entry.path) } else { fs::remove_file(&entry.path) };
+        match result {
+            Ok(()) => {
+                report.freed_bytes += entry.size;
+                report.removed.push(entry.name);
+            }
+            Err(e) => tracing::warn!("Failed to remove {}: {}", entry.path.display(), e),
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cleanup_by_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("caddy-2.8.4.tar.gz"), vec![0u8; 10]).unwrap();
+        fs::write(dir.join("php-8.5.0.tar.gz"), vec![0u8; 10]).unwrap();
+
+        let policy = DownloadCacheSettings::default();
+        let report = cleanup_dir(dir, &policy, &[], SystemTime::now());
+        assert!(report.removed.is_empty());
+
+        // Eight days later, everything has expired except the protected archive
+        let later = SystemTime::now() + Duration::from_secs(8 * 24 * 60 * 60);
+        let report = cleanup_dir(dir, &policy, &["php-8.5.0.".to_string()], later);
+        assert_eq!(report.removed, vec!["caddy-2.8.4.tar.gz"]);
+        assert_eq!(report.protected, vec!["php-8.5.0.tar.gz"]);
+        assert_eq!(report.freed_bytes, 10);
+        assert!(dir.join("php-8.5.0.tar.gz").exists());
+    }
+
+    #[test]
+    fn test_cleanup_by_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("mariadb-12.0.tar.gz"), vec![0u8; 1024 * 1024]).unwrap();
+        fs::write(dir.join("adminer-5.0.zip"), vec![0u8; 10]).unwrap();
+
+        let policy = DownloadCacheSettings { max_size_mb: 0, ..Default::default() };
+        let report = cleanup_dir(dir, &policy, &[], SystemTime::now());
+        assert_eq!(report.removed.len(), 2);
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_clear_keeps_partial_downloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let archive = dir.join("mariadb-12.0.zip");
+        fs::write(&archive, vec![0u8; 10]).unwrap();
+        fs::write(partial_path(&dir.join("php-8.5.0.zip")), vec![0u8; 10]).unwrap();
+
+        clear_dir(dir);
+        assert!(!archive.exists());
+        assert!(dir.join("php-8.5.0.zip.part").exists());
+    }
+
+    #[test]
+    fn test_keep_newest() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let now = SystemTime::now();
+        for (i, name) in ["caddy-2.8.4.tar.gz", "php-8.5.0.tar.gz", "mariadb-12.0.tar.gz"].iter().enumerate() {
+            let file = fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(now - Duration::from_secs(60 * i as u64)).unwrap();
+        }
+        fs::write(partial_path(&dir.join("adminer-5.0.php")), vec![0u8; 10]).unwrap();
+
+        let report = keep_newest(dir, 1, &["mariadb-12.0.".to_string()]);
+        assert_eq!(report.removed, vec!["php-8.5.0.tar.gz"]);
+        assert_eq!(report.protected, vec!["mariadb-12.0.tar.gz"]);
+        assert!(dir.join("caddy-2.8.4.tar.gz").exists());
+        assert!(dir.join("adminer-5.0.php.part").exists());
+
+        let report = keep_newest(dir, 0, &[]);
+        assert_eq!(report.removed.len(), 2);
+    }
+}
@@ -2,10 +2,12 @@ use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use reqwest::Client;
+use futures_util::{StreamExt, TryStreamExt};
+use reqwest::{Client, StatusCode};
 
-use crate::runtime::locator::get_app_data_paths;
+use crate::runtime::locator::{get_app_data_paths, is_valid_binary, locate_runtime_binaries, RuntimePaths};
 use crate::runtime::packages::{PackageSelection, get_php_package, get_mysql_package, get_mariadb_package, get_phpmyadmin_package, get_postgresql_package, get_adminer_package, get_config};
 use sha2::{Digest, Sha256};
 
@@ -14,8 +16,9 @@ pub use crate::runtime::packages::{
     RuntimeConfig, BinariesConfig, BinaryConfig, PhpMyAdminConfig, VersionInfo, VersionInfoSingleUrl, Urls, Checksums
 };
 
-/// Binary component types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Binary component types, serialized by `binary_name`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BinaryComponent {
     Caddy,
     Php,
@@ -24,9 +27,33 @@ pub enum BinaryComponent {
     PhpMyAdmin,
     PostgreSQL,
     Adminer,
+    Cloudflared,
+    Mailpit,
+    Node,
+    Minio,
 }
 
 impl BinaryComponent {
+    /// Every component, in install order
+    pub const ALL: [BinaryComponent; 11] = [
+        BinaryComponent::Caddy,
+        BinaryComponent::Php,
+        BinaryComponent::MySQL,
+        BinaryComponent::MariaDB,
+        BinaryComponent::PhpMyAdmin,
+        BinaryComponent::PostgreSQL,
+        BinaryComponent::Adminer,
+        BinaryComponent::Cloudflared,
+        BinaryComponent::Mailpit,
+        BinaryComponent::Node,
+        BinaryComponent::Minio,
+    ];
+
+    /// Component whose `binary_name` is `name`
+    pub fn from_binary_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.binary_name() == name)
+    }
+
     pub fn name(&self) -> &str {
         match self {
             BinaryComponent::Caddy => "Caddy",
@@ -36,6 +63,10 @@ impl BinaryComponent {
             BinaryComponent::PhpMyAdmin => "phpMyAdmin",
             BinaryComponent::PostgreSQL => "PostgreSQL",
             BinaryComponent::Adminer => "Adminer",
+            BinaryComponent::Cloudflared => "cloudflared",
+            BinaryComponent::Mailpit => "Mailpit",
+            BinaryComponent::Node => "Node.js",
+            BinaryComponent::Minio => "MinIO",
         }
     }
 
@@ -87,6 +118,30 @@ impl BinaryComponent {
                     .or_else(|| config.binaries.adminer.as_ref().and_then(|ac| ac.versions.first().map(|v| v.version.clone())))
                     .unwrap_or_default()
             }
+            BinaryComponent::Cloudflared => {
+                config.binaries.cloudflared.as_ref()
+                    .and_then(|cc| cc.versions.iter().find(|v| v.selected).map(|v| v.version.clone()))
+                    .or_else(|| config.binaries.cloudflared.as_ref().and_then(|cc| cc.versions.first().map(|v| v.version.clone())))
+                    .unwrap_or_default()
+            }
+            BinaryComponent::Mailpit => {
+                config.binaries.mailpit.as_ref()
+                    .and_then(|mc| mc.versions.iter().find(|v| v.selected).map(|v| v.version.clone()))
+                    .or_else(|| config.binaries.mailpit.as_ref().and_then(|mc| mc.versions.first().map(|v| v.version.clone())))
+                    .unwrap_or_default()
+            }
+            BinaryComponent::Node => {
+                config.binaries.node.as_ref()
+                    .and_then(|nc| nc.versions.iter().find(|v| v.selected).map(|v| v.version.clone()))
+                    .or_else(|| config.binaries.node.as_ref().and_then(|nc| nc.versions.first().map(|v| v.version.clone())))
+                    .unwrap_or_default()
+            }
+            BinaryComponent::Minio => {
+                config.binaries.minio.as_ref()
+                    .and_then(|mc| mc.versions.iter().find(|v| v.selected).map(|v| v.version.clone()))
+                    .or_else(|| config.binaries.minio.as_ref().and_then(|mc| mc.versions.first().map(|v| v.version.clone())))
+                    .unwrap_or_default()
+            }
         }
     }
 
@@ -103,6 +158,10 @@ impl BinaryComponent {
             BinaryComponent::PhpMyAdmin => "phpmyadmin",
             BinaryComponent::PostgreSQL => "postgresql",
             BinaryComponent::Adminer => "adminer",
+            BinaryComponent::Cloudflared => "cloudflared",
+            BinaryComponent::Mailpit => "mailpit",
+            BinaryComponent::Node => "node",
+            BinaryComponent::Minio => "minio",
         }
     }
 }
@@ -142,8 +201,8 @@ impl RuntimeDownloader {
                         return pkg.version;
                     }
                 }
-                BinaryComponent::Caddy => {
-                    // Caddy uses default version
+                BinaryComponent::Caddy | BinaryComponent::Cloudflared | BinaryComponent::Mailpit | BinaryComponent::Node | BinaryComponent::Minio => {
+                    // Caddy, cloudflared, Mailpit, Node.js and MinIO use the default version
                 }
             }
         }
@@ -211,6 +270,22 @@ impl Platform {
         }
     }
 
+    /// Platform whose binaries run under emulation here: Windows 11 on ARM
+    /// runs x64 programs, Apple Silicon runs them with Rosetta 2. Linux has
+    /// no such layer.
+    pub fn emulated(&self) -> Option<Platform> {
+        match self {
+            Platform::WindowsArm64 => Some(Platform::WindowsX64),
+            Platform::MacOSArm64 => Some(Platform::MacOSX64),
+            _ => None,
+        }
+    }
+
+    /// URL keys of the builds that run here, native first
+    pub fn url_keys(&self) -> Vec<String> {
+        std::iter::once(*self).chain(self.emulated()).map(|p| p.url_key()).collect()
+    }
+
     /// Get the URL key for config lookup (matches JSON keys)
     pub fn url_key(&self) -> String {
         match self {
@@ -225,17 +300,52 @@ impl Platform {
 }
 
 /// Runtime manifest with binary URLs and checksums
+///
+/// Resolved from runtime-config.json for the current platform and package
+/// selection. Every archive is checked against it before extraction.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RuntimeManifest {
     pub version: String,
+    /// Keyed by component name, e.g. "php"
     pub binaries: HashMap<String, BinaryInfo>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BinaryInfo {
     pub url: String,
-    pub checksum: String,
-    pub size: u64,
+    /// Fallback URLs tried in order when `url` fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// SHA-256 of the archive, missing when runtime-config.json has none for this platform
+    pub checksum: Option<String>,
+}
+
+impl BinaryInfo {
+    /// Primary URL followed by the mirrors, without blanks and duplicates
+    pub fn candidate_urls(&self) -> Vec<&str> {
+        let mut urls: Vec<&str> = Vec::new();
+        for url in std::iter::once(&self.url).chain(&self.mirrors) {
+            let url = url.trim();
+            if !url.is_empty() && !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+}
+
+impl RuntimeManifest {
+    /// Components without a checksum, sorted by name
+    pub fn unverified(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .binaries
+            .iter()
+            .filter(|(_, info)| info.checksum.as_deref().is_none_or(|c| c.trim().is_empty()))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
 }
 
 /// Download progress information
@@ -250,6 +360,27 @@ pub struct DownloadProgress {
     pub total_components: u8,
     pub downloaded_bytes: u64,
     pub total_bytes: u64,
+    /// Progress of the whole install, across components
+    pub overall_percent: u8,
+    /// State of every component being installed
+    pub components: Vec<ComponentProgress>,
+    /// Download attempt of the current component, above 1 while retrying
+    /// and 0 outside of downloads
+    pub attempt: u8,
+}
+
+/// Progress of one component, components are downloaded concurrently
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentProgress {
+    pub component: String,
+    pub display: String,
+    pub version: String,
+    pub step: DownloadStep,
+    pub percent: u8,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+    pub attempt: u8,
 }
 
 /// Download step
@@ -265,6 +396,97 @@ pub enum DownloadStep {
 
 pub type ProgressCallback = Box<dyn Fn(DownloadProgress) + Send + Sync>;
 
+/// Folds per-component events into overall progress before passing them on
+struct ProgressTracker {
+    progress_cb: ProgressCallback,
+    components: std::sync::Mutex<Vec<ComponentProgress>>,
+}
+
+impl ProgressTracker {
+    fn new(progress_cb: ProgressCallback, components: Vec<ComponentProgress>) -> Self {
+        Self { progress_cb, components: std::sync::Mutex::new(components) }
+    }
+
+    fn report(&self, mut progress: DownloadProgress) {
+        // Held while calling back so concurrent downloads report in order
+        let mut components = self.components.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = components.iter_mut().find(|c| c.component == progress.current_component) {
+            entry.step = progress.step.clone();
+            entry.percent = progress.percent;
+            entry.downloaded_bytes = progress.downloaded_bytes;
+            entry.total_bytes = progress.total_bytes;
+            if progress.attempt > 0 {
+                entry.attempt = progress.attempt;
+            }
+        }
+        progress.total_components = components.len() as u8;
+        progress.overall_percent = match progress.step {
+            DownloadStep::Complete => 100,
+            _ => overall_percent(&components),
+        };
+        progress.components = components.clone();
+        (self.progress_cb)(progress);
+    }
+}
+
+/// Downloading counts for 90% of a component, extracting and installing for
+/// 5% each
+fn overall_percent(components: &[ComponentProgress]) -> u8 {
+    if components.is_empty() {
+        return 0;
+    }
+    let sum: usize = components
+        .iter()
+        .map(|c| match c.step {
+            DownloadStep::Downloading => c.percent as usize * 9 / 10,
+            DownloadStep::Extracting => 90 + c.percent as usize / 20,
+            DownloadStep::Installing => 95 + c.percent as usize / 20,
+            DownloadStep::Complete => 100,
+            DownloadStep::Error(_) => 0,
+        })
+        .sum();
+    (sum / components.len()) as u8
+}
+
+/// A mirror that does not answer in time is skipped for the next one
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest stall between two chunks before a download counts as interrupted
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Bytes between two progress events while downloading
+const PROGRESS_INTERVAL_BYTES: u64 = 256 * 1024;
+
+/// Attempts per URL before moving on to the next mirror
+const MAX_ATTEMPTS: u8 = 3;
+/// Delay before the first retry, doubled for every further one
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+/// Random delay added to every retry so parallel downloads do not retry in lockstep
+const RETRY_JITTER_MS: u64 = 1000;
+
+/// A failed download attempt; network hiccups are worth retrying, a 404 or
+/// a checksum mismatch is not
+struct DownloadError {
+    message: String,
+    retryable: bool,
+}
+
+impl DownloadError {
+    fn retryable(message: String) -> Self {
+        Self { message, retryable: true }
+    }
+}
+
+impl From<String> for DownloadError {
+    fn from(message: String) -> Self {
+        Self { message, retryable: false }
+    }
+}
+
+/// Backoff before retry number `attempt` (1 for the first retry)
+fn retry_delay(attempt: u8, jitter_ms: u64) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt.saturating_sub(1) as u32) + Duration::from_millis(jitter_ms)
+}
+
 /// Runtime binary downloader
 pub struct RuntimeDownloader {
     base_url: String,
@@ -277,6 +499,8 @@ impl RuntimeDownloader {
     /// Create a new runtime downloader
     pub fn new() -> Result<Self, String> {
         let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
             .build()
             .map_err(|e| format!("Failed to build HTTP client: {}. This usually means no TLS backend is available. Ensure the app was compiled with rustls-tls or native-tls enabled.", e))?;
         Ok(Self {
@@ -290,6 +514,8 @@ impl RuntimeDownloader {
     /// Create a new runtime downloader with custom package selection
     pub fn with_packages(package_selection: PackageSelection) -> Result<Self, String> {
         let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
             .build()
             .map_err(|e| format!("Failed to build HTTP client: {}. This usually means no TLS backend is available. Ensure the app was compiled with rustls-tls or native-tls enabled.", e))?;
         Ok(Self {
@@ -300,14 +526,36 @@ impl RuntimeDownloader {
         })
     }
 
-    /// Get the URL for a binary component from config
+    /// Get the URL for a binary component from config, using the native
+    /// build or the emulated one when there is none
     fn get_binary_url(&self, component: BinaryComponent) -> String {
+        self.get_binary_url_for(component, self.component_platform(component))
+    }
+
+    /// Platform whose build of `component` gets installed
+    ///
+    /// The native build wins. Without one, Windows on ARM and Apple Silicon
+    /// fall back to the x64 build, which runs under emulation.
+    pub fn component_platform(&self, component: BinaryComponent) -> Platform {
+        match self.platform.emulated() {
+            Some(fallback)
+                if self.get_binary_url_for(component, self.platform).is_empty()
+                    && !self.get_binary_url_for(component, fallback).is_empty() =>
+            {
+                fallback
+            }
+            _ => self.platform,
+        }
+    }
+
+    /// Get the URL for a binary component on `platform` from config
+    fn get_binary_url_for(&self, component: BinaryComponent, platform: Platform) -> String {
         // Use selected packages if available, otherwise fall back to default config
         if let Some(selection) = &self.package_selection {
             match component {
                 BinaryComponent::Php => {
                     if let Some(pkg) = get_php_package(&selection.php) {
-                        return match self.platform {
+                        return match platform {
                             Platform::WindowsX64 => pkg.windows_x64,
                             Platform::WindowsArm64 => pkg.windows_arm64,
                             Platform::MacOSX64 => pkg.macos_x64,
@@ -319,7 +567,7 @@ impl RuntimeDownloader {
                 }
                 BinaryComponent::MySQL => {
                     if let Some(pkg) = get_mysql_package(&selection.mysql) {
-                        return match self.platform {
+                        return match platform {
                             Platform::WindowsX64 => pkg.windows_x64,
                             Platform::WindowsArm64 => pkg.windows_arm64,
                             Platform::MacOSX64 => pkg.macos_x64,
@@ -331,7 +579,7 @@ impl RuntimeDownloader {
                 }
                 BinaryComponent::MariaDB => {
                     if let Some(pkg) = get_mariadb_package(&selection.mariadb) {
-                        return match self.platform {
+                        return match platform {
                             Platform::WindowsX64 => pkg.windows_x64,
                             Platform::WindowsArm64 => pkg.windows_arm64,
                             Platform::MacOSX64 => pkg.macos_x64,
@@ -348,7 +596,7 @@ impl RuntimeDownloader {
                 }
                 BinaryComponent::PostgreSQL => {
                     if let Some(pkg) = get_postgresql_package(&selection.postgresql) {
-                        return match self.platform {
+                        return match platform {
                             Platform::WindowsX64 => pkg.windows_x64,
                             Platform::WindowsArm64 => pkg.windows_arm64,
                             Platform::MacOSX64 => pkg.macos_x64,
@@ -363,8 +611,8 @@ impl RuntimeDownloader {
                         return pkg.url;
                     }
                 }
-                BinaryComponent::Caddy => {
-                    // Caddy doesn't have package selection, use default
+                BinaryComponent::Caddy | BinaryComponent::Cloudflared | BinaryComponent::Mailpit | BinaryComponent::Node | BinaryComponent::Minio => {
+                    // Caddy, cloudflared, Mailpit, Node.js and MinIO don't have package selection, use default
                 }
             }
         }
@@ -381,7 +629,7 @@ impl RuntimeDownloader {
                     .find(|v| v.selected)
                     .or_else(|| config.binaries.caddy.versions.first())
                     .unwrap();
-                match self.platform {
+                match platform {
                     Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
                     Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
                     Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
@@ -395,7 +643,7 @@ impl RuntimeDownloader {
                     .find(|v| v.selected)
                     .or_else(|| config.binaries.php.versions.first())
                     .unwrap();
-                match self.platform {
+                match platform {
                     Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
                     Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
                     Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
@@ -409,7 +657,7 @@ impl RuntimeDownloader {
                     .find(|v| v.selected)
                     .or_else(|| config.binaries.mysql.versions.first())
                     .unwrap();
-                match self.platform {
+                match platform {
                     Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
                     Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
                     Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
@@ -424,7 +672,7 @@ impl RuntimeDownloader {
                         .find(|v| v.selected)
                         .or_else(|| mc.versions.first())
                         .unwrap();
-                    match self.platform {
+                    match platform {
                         Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
                         Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
                         Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
@@ -449,7 +697,7 @@ impl RuntimeDownloader {
                         .find(|v| v.selected)
                         .or_else(|| pc.versions.first())
                         .unwrap();
-                    match self.platform {
+                    match platform {
                         Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
                         Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
                         Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
@@ -472,6 +720,78 @@ impl RuntimeDownloader {
                     String::new()
                 }
             }
+            BinaryComponent::Cloudflared => {
+                if let Some(cc) = &config.binaries.cloudflared {
+                    let version_info = cc.versions.iter()
+                        .find(|v| v.selected)
+                        .or_else(|| cc.versions.first())
+                        .unwrap();
+                    match platform {
+                        Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
+                        Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
+                        Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
+                        Platform::MacOSArm64 => version_info.urls.macos_arm64.clone().unwrap_or_default(),
+                        Platform::LinuxX64 => version_info.urls.linux_x64.clone().unwrap_or_default(),
+                        Platform::LinuxArm64 => version_info.urls.linux_arm64.clone().unwrap_or_default(),
+                    }
+                } else {
+                    String::new()
+                }
+            }
+            BinaryComponent::Mailpit => {
+                if let Some(mc) = &config.binaries.mailpit {
+                    let version_info = mc.versions.iter()
+                        .find(|v| v.selected)
+                        .or_else(|| mc.versions.first())
+                        .unwrap();
+                    match platform {
+                        Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
+                        Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
+                        Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
+                        Platform::MacOSArm64 => version_info.urls.macos_arm64.clone().unwrap_or_default(),
+                        Platform::LinuxX64 => version_info.urls.linux_x64.clone().unwrap_or_default(),
+                        Platform::LinuxArm64 => version_info.urls.linux_arm64.clone().unwrap_or_default(),
+                    }
+                } else {
+                    String::new()
+                }
+            }
+            BinaryComponent::Node => {
+                if let Some(nc) = &config.binaries.node {
+                    let version_info = nc.versions.iter()
+                        .find(|v| v.selected)
+                        .or_else(|| nc.versions.first())
+                        .unwrap();
+                    match platform {
+                        Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
+                        Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
+                        Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
+                        Platform::MacOSArm64 => version_info.urls.macos_arm64.clone().unwrap_or_default(),
+                        Platform::LinuxX64 => version_info.urls.linux_x64.clone().unwrap_or_default(),
+                        Platform::LinuxArm64 => version_info.urls.linux_arm64.clone().unwrap_or_default(),
+                    }
+                } else {
+                    String::new()
+                }
+            }
+            BinaryComponent::Minio => {
+                if let Some(mc) = &config.binaries.minio {
+                    let version_info = mc.versions.iter()
+                        .find(|v| v.selected)
+                        .or_else(|| mc.versions.first())
+                        .unwrap();
+                    match platform {
+                        Platform::WindowsX64 => version_info.urls.windows_x64.clone().unwrap_or_default(),
+                        Platform::WindowsArm64 => version_info.urls.windows_arm64.clone().unwrap_or_default(),
+                        Platform::MacOSX64 => version_info.urls.macos_x64.clone().unwrap_or_default(),
+                        Platform::MacOSArm64 => version_info.urls.macos_arm64.clone().unwrap_or_default(),
+                        Platform::LinuxX64 => version_info.urls.linux_x64.clone().unwrap_or_default(),
+                        Platform::LinuxArm64 => version_info.urls.linux_arm64.clone().unwrap_or_default(),
+                    }
+                } else {
+                    String::new()
+                }
+            }
         }
     }
 
@@ -483,32 +803,34 @@ impl RuntimeDownloader {
             if filename.ends_with(".tar.gz") {
                 return "tar.gz".to_string();
             }
-            // Otherwise get the extension after the last dot
-            if let Some(ext) = filename.split('.').last() {
-                return ext.to_string();
+            // Otherwise get the extension after the last dot, unless the dots
+            // are part of a release name, e.g. minio.RELEASE.2025-09-07T16-13-09Z
+            if let Some((_, ext)) = filename.rsplit_once('.') {
+                if ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return ext.to_string();
+                }
+            }
+            // A bare binary, e.g. cloudflared on Linux
+            if !filename.is_empty() {
+                return "bin".to_string();
             }
         }
         // Default to zip if we can't determine
         "zip".to_string()
     }
 
-    /// Download a single binary component
+    /// Download a single binary component, failing over to its mirrors
     async fn download_component(
         &self,
         component: BinaryComponent,
+        info: &BinaryInfo,
         dest_dir: &Path,
         progress_cb: &ProgressCallback,
-        current: u8,
-        total: u8,
     ) -> Result<PathBuf, String> {
-        let url = self.get_binary_url(component);
-        let extension = Self::get_extension_from_url(&url);
-
+        let urls = info.candidate_urls();
         tracing::debug!("Platform: {:?}", self.platform);
-        tracing::info!("Downloading {} from: {}", component.name(), url);
-        tracing::debug!("Full URL ({} chars): {}", url.len(), url);
 
-        if url.is_empty() {
+        if urls.is_empty() {
             let config_loaded = crate::runtime::packages::get_config().is_some();
             let runtime_dir = self.get_runtime_dir().map(|p| p.display().to_string()).unwrap_or_else(|e| e);
             let exe_path = std::env::current_exe().map(|p| p.display().to_string()).unwrap_or_else(|e| format!("ERROR: {}", e));
@@ -527,6 +849,68 @@ impl RuntimeDownloader {
             ));
         }
 
+        // Mirrors serve the same archive, so a partial download from one resumes on the next
+        let extension = Self::get_extension_from_url(urls[0]);
+        let file_path = dest_dir.join(format!(
+            "{}-{}.{}",
+            component.binary_name(),
+            self.get_component_version(&component),
+            extension
+        ));
+
+        let mut failures = Vec::new();
+        for (mirror, url) in urls.iter().enumerate() {
+            if mirror > 0 {
+                tracing::warn!("Trying mirror {} of {} for {}: {}", mirror, urls.len() - 1, component.name(), url);
+            }
+            let mut attempt = 1;
+            loop {
+                match self.download_from(component, info, url, &file_path, progress_cb, attempt).await {
+                    Ok(()) => return Ok(file_path),
+                    Err(e) if e.retryable && attempt < MAX_ATTEMPTS => {
+                        let delay = retry_delay(attempt, rand::Rng::gen_range(&mut rand::thread_rng(), 0..RETRY_JITTER_MS));
+                        tracing::warn!(
+                            "Download of {} from {} failed (attempt {} of {}), retrying in {:?}: {}",
+                            component.name(), url, attempt, MAX_ATTEMPTS, delay, e.message
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Download of {} from {} failed: {}", component.name(), url, e.message);
+                        failures.push((url, e.message));
+                        break;
+                    }
+                }
+            }
+        }
+
+        if failures.len() == 1 {
+            return Err(failures.remove(0).1);
+        }
+        let details: Vec<String> = failures.iter().map(|(url, e)| format!("{}\n  {}", url, e)).collect();
+        Err(format!(
+            "Failed to download {} from any of {} sources:\n{}",
+            component.name(),
+            failures.len(),
+            details.join("\n")
+        ))
+    }
+
+    /// Download one candidate URL of a component to `file_path`
+    async fn download_from(
+        &self,
+        component: BinaryComponent,
+        info: &BinaryInfo,
+        url: &str,
+        file_path: &Path,
+        progress_cb: &ProgressCallback,
+        attempt: u8,
+    ) -> Result<(), DownloadError> {
+        let extension = Self::get_extension_from_url(url);
+        tracing::info!("Downloading {} from: {}", component.name(), url);
+        tracing::debug!("Full URL ({} chars): {}", url.len(), url);
+
         // Set platform-appropriate User-Agent
         let user_agent = match self.platform {
             Platform::LinuxX64 | Platform::LinuxArm64 => "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
@@ -534,48 +918,47 @@ impl RuntimeDownloader {
             Platform::WindowsX64 | Platform::WindowsArm64 => "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
         };
 
-        // MySQL downloads require specific headers to bypass their gateway
-        let mut request = self.client.get(&url)
-            .header("User-Agent", user_agent);
+        let version = self.get_component_version(&component);
+        let part_path = super::download_cache::partial_path(file_path);
 
-        // Add Referer header for MySQL downloads (required by dev.mysql.com gateway)
-        if component == BinaryComponent::MySQL && url.contains("dev.mysql.com") {
-            request = request.header("Referer", "https://dev.mysql.com/downloads/mysql/")
-                .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8");
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
         }
 
-        let response = request
-            .send()
-            .await
-            .map_err(|e| {
-                let is_tls = e.is_connect() || e.to_string().contains("tls") || e.to_string().contains("certificate") || e.to_string().contains("builder");
-                if is_tls {
-                    format!(
-                        "Failed to fetch {} (TLS/HTTPS error): {}\n\
-                         Platform: {:?}\n\
-                         URL: {}\n\
-                         \n\
-                         This is a TLS/HTTPS connection error. The app needs a TLS library to download binaries.\n\
-                         Ensure reqwest was compiled with the 'rustls-tls' or 'native-tls' feature enabled.",
-                        component.name(), e, self.platform, url
-                    )
-                } else {
-                    format!(
-                        "Failed to fetch {}: {}\nPlatform: {:?}\nURL: {}",
-                        component.name(), e, self.platform, url
-                    )
-                }
-            })?;
+        // Resume an interrupted download of the same version
+        let mut resume_from = fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+        if resume_from > 0 {
+            tracing::info!("Resuming {} download at {} bytes", component.name(), resume_from);
+        }
+
+        let mut response = self.send_request(component, url, user_agent, resume_from).await?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && resume_from > 0 {
+            // The partial file does not match the archive on the server
+            tracing::warn!("Cannot resume {} download, starting over", component.name());
+            let _ = fs::remove_file(&part_path);
+            resume_from = 0;
+            response = self.send_request(component, url, user_agent, 0).await?;
+        }
 
         // Check status code
         let status = response.status();
         if !status.is_success() {
-            return Err(format!(
+            let message = format!(
                 "HTTP error {}: Failed to download {}\nURL: {}",
                 status.as_u16(),
                 component.name(),
                 url
-            ));
+            );
+            // Overloaded or rate limited servers may answer later, a 404 will not change
+            let transient = status.is_server_error()
+                || status == StatusCode::REQUEST_TIMEOUT
+                || status == StatusCode::TOO_MANY_REQUESTS;
+            return Err(DownloadError { message, retryable: transient });
+        }
+        // A plain 200 means the server ignored the range and sends everything
+        if status != StatusCode::PARTIAL_CONTENT {
+            resume_from = 0;
         }
 
         // Get final URL after redirects
@@ -591,99 +974,227 @@ impl RuntimeDownloader {
                     return Err(format!(
                         "Server returned HTML instead of binary. URL may be incorrect: {}",
                         final_url
-                    ));
+                    )
+                    .into());
                 }
             }
         }
 
-        let total_bytes = response.content_length().unwrap_or(0);
-        let version = self.get_component_version(&component);
-        let file_path = dest_dir.join(format!(
-            "{}-{}.{}",
-            component.binary_name(),
-            version,
-            extension
-        ));
+        // Content-Length of a 206 response only covers the remaining range
+        let total_bytes = response.content_length().map(|len| resume_from + len).unwrap_or(0);
 
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resume_from > 0)
+            .truncate(resume_from == 0)
+            .open(&part_path)
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+
+        let report = |downloaded_bytes: u64| {
+            let percent = downloaded_bytes
+                .saturating_mul(100)
+                .checked_div(total_bytes)
+                .map_or(0, |p| p.min(100) as u8);
+            progress_cb(DownloadProgress {
+                step: DownloadStep::Downloading,
+                percent,
+                current_component: component.name().to_string(),
+                component_display: component.display_name(),
+                version: version.clone(),
+                // Filled in by the ProgressTracker
+                total_components: 0,
+                downloaded_bytes,
+                total_bytes,
+                overall_percent: 0,
+                components: Vec::new(),
+                attempt,
+            });
+        };
+
+        // Stream chunks to disk so memory stays flat and an interrupted
+        // download keeps what it got
+        let mut downloaded_bytes = resume_from;
+        let mut reported_bytes = downloaded_bytes;
+        report(downloaded_bytes);
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| {
+                DownloadError::retryable(format!(
+                    "Download of {} was interrupted after {} bytes, it will resume on the next attempt: {}",
+                    component.name(),
+                    downloaded_bytes,
+                    e
+                ))
+            })?;
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write to file: {}", e))?;
+            downloaded_bytes += chunk.len() as u64;
+
+            if downloaded_bytes - reported_bytes >= PROGRESS_INTERVAL_BYTES {
+                reported_bytes = downloaded_bytes;
+                report(downloaded_bytes);
+            }
         }
+        file.flush().map_err(|e| format!("Failed to write to file: {}", e))?;
+        drop(file);
 
-        let mut file = File::create(&file_path)
-            .map_err(|e| format!("Failed to create file: {}", e))?;
+        if total_bytes > 0 && downloaded_bytes < total_bytes {
+            return Err(DownloadError::retryable(format!(
+                "Download of {} stopped at {} of {} bytes, it will resume on the next attempt",
+                component.name(),
+                downloaded_bytes,
+                total_bytes
+            )));
+        }
+
+        // A corrupt archive cannot be resumed, the next attempt starts over
+        if let Err(e) = self.verify_download(component, info, &extension, &part_path, total_bytes) {
+            let _ = fs::remove_file(&part_path);
+            return Err(e.into());
+        }
+        fs::rename(&part_path, file_path)
+            .map_err(|e| format!("Failed to move finished download: {}", e))?;
+
+        report(downloaded_bytes);
 
-        // Download using bytes() for simplicity
-        let bytes = response
-            .bytes()
+        Ok(())
+    }
+
+    /// Request `url`, asking for the bytes after `resume_from` when resuming
+    async fn send_request(
+        &self,
+        component: BinaryComponent,
+        url: &str,
+        user_agent: &str,
+        resume_from: u64,
+    ) -> Result<reqwest::Response, DownloadError> {
+        // MySQL downloads require specific headers to bypass their gateway
+        let mut request = self.client.get(url)
+            .header("User-Agent", user_agent);
+
+        // Add Referer header for MySQL downloads (required by dev.mysql.com gateway)
+        if component == BinaryComponent::MySQL && url.contains("dev.mysql.com") {
+            request = request.header("Referer", "https://dev.mysql.com/downloads/mysql/")
+                .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8");
+        }
+
+        if resume_from > 0 {
+            request = request.header("Range", format!("bytes={}-", resume_from));
+        }
+
+        request
+            .send()
             .await
-            .map_err(|e| format!("Failed to download bytes: {}", e))?;
+            .map_err(|e| {
+                // Timeouts and dropped connections are retried, a broken client is not
+                let retryable = !e.is_builder();
+                let is_tls = e.is_connect() || e.to_string().contains("tls") || e.to_string().contains("certificate") || e.to_string().contains("builder");
+                let message = if is_tls {
+                    format!(
+                        "Failed to fetch {} (TLS/HTTPS error): {}\n\
+                         Platform: {:?}\n\
+                         URL: {}\n\
+                         \n\
+                         This is a TLS/HTTPS connection error. The app needs a TLS library to download binaries.\n\
+                         Ensure reqwest was compiled with the 'rustls-tls' or 'native-tls' feature enabled.",
+                        component.name(), e, self.platform, url
+                    )
+                } else {
+                    format!(
+                        "Failed to fetch {}: {}\nPlatform: {:?}\nURL: {}",
+                        component.name(), e, self.platform, url
+                    )
+                };
+                DownloadError { message, retryable }
+            })
+    }
 
-        // Verify the file is valid by checking magic bytes
-        if bytes.len() < 4 {
+    /// Check size, magic bytes and checksum of a finished download
+    fn verify_download(
+        &self,
+        component: BinaryComponent,
+        info: &BinaryInfo,
+        extension: &str,
+        path: &Path,
+        expected_size: u64,
+    ) -> Result<(), String> {
+        let size = fs::metadata(path)
+            .map_err(|e| format!("Failed to read downloaded file: {}", e))?
+            .len();
+        if expected_size > 0 && size != expected_size {
             return Err(format!(
-                "Downloaded file is too small ({} bytes) to be a valid archive",
-                bytes.len()
+                "Downloaded {} has {} bytes, expected {}",
+                component.name(),
+                size,
+                expected_size
             ));
         }
 
+        // Verify the file is valid by checking magic bytes
+        let mut magic = [0u8; 4];
+        File::open(path)
+            .and_then(|mut f| f.read_exact(&mut magic))
+            .map_err(|_| format!(
+                "Downloaded file is too small ({} bytes) to be a valid archive",
+                size
+            ))?;
+
         // Check if it's a ZIP file (starts with PK)
-        let is_zip = bytes[0] == 0x50 && bytes[1] == 0x4B;
+        let is_zip = magic[0] == 0x50 && magic[1] == 0x4B;
         // Check if it's gzip (starts with 0x1f 0x8b)
-        let is_gzip = bytes[0] == 0x1f && bytes[1] == 0x8b;
+        let is_gzip = magic[0] == 0x1f && magic[1] == 0x8b;
 
         if extension == "zip" && !is_zip {
-            return Err(format!(
-                "Expected ZIP file but downloaded file doesn't have ZIP magic bytes. URL may have redirected to HTML page."
-            ));
+            return Err("Expected ZIP file but downloaded file doesn't have ZIP magic bytes. URL may have redirected to HTML page.".to_string());
         }
 
-        if (extension == "gz" || extension == "tar.gz") && !is_gzip {
-            return Err(format!(
-                "Expected gzip file but downloaded file doesn't have gzip magic bytes."
-            ));
+        if (extension == "gz" || extension == "tar.gz" || extension == "tgz") && !is_gzip {
+            return Err("Expected gzip file but downloaded file doesn't have gzip magic bytes.".to_string());
         }
 
-        let downloaded_bytes = bytes.len() as u64;
-        file.write_all(&bytes)
-            .map_err(|e| format!("Failed to write to file: {}", e))?;
-
-        // Verify checksum if available
-        if let Some(expected_checksum) = self.get_expected_checksum(&component, &url) {
-            let actual_checksum = self.calculate_checksum_from_bytes(&bytes)
-                .map_err(|e| format!("Failed to calculate checksum: {}", e))?;
-
-            if actual_checksum.to_lowercase() != expected_checksum.to_lowercase() {
-                return Err(format!(
-                    "Checksum verification failed for {}.\nExpected: {}\nActual: {}\n\nThe downloaded file may be corrupted or tampered with.",
-                    component.name(),
-                    expected_checksum,
-                    actual_checksum
-                ));
-            }
-            tracing::info!("Checksum verified for {}: {}", component.name(), actual_checksum);
-        } else {
+        // Archives without a checksum were allowed before the download started
+        let Some(expected_checksum) = info.checksum.as_deref().filter(|c| !c.trim().is_empty()) else {
             tracing::warn!("No checksum configured for {} — integrity not verified", component.name());
-        }
-
-        let percent = if total_bytes > 0 {
-            ((downloaded_bytes as f64 / total_bytes as f64) * 100.0) as u8
-        } else {
-            100
+            return Ok(());
         };
+        let actual_checksum = self.calculate_checksum(path)
+            .map_err(|e| format!("Failed to calculate checksum: {}", e))?;
 
-        progress_cb(DownloadProgress {
-            step: DownloadStep::Downloading,
-            percent,
-            current_component: component.name().to_string(),
-            component_display: component.display_name(),
-            version: self.get_component_version(&component),
-            total_components: total,
-            downloaded_bytes,
-            total_bytes,
-        });
+        if !actual_checksum.eq_ignore_ascii_case(expected_checksum.trim()) {
+            return Err(format!(
+                "Checksum verification failed for {}.\nExpected: {}\nActual: {}\n\nThe downloaded file may be corrupted or tampered with.",
+                component.name(),
+                expected_checksum,
+                actual_checksum
+            ));
+        }
+        tracing::info!("Checksum verified for {}: {}", component.name(), actual_checksum);
 
-        Ok(file_path)
+        Ok(())
+    }
+
+    /// Resolve download URLs and checksums of `components`
+    pub fn manifest(&self, components: &[BinaryComponent]) -> RuntimeManifest {
+        let binaries = components
+            .iter()
+            .map(|c| {
+                let platform = self.component_platform(*c);
+                if platform != self.platform {
+                    tracing::warn!("No {:?} build of {}, using the {:?} build under emulation", self.platform, c.name(), platform);
+                }
+                let info = BinaryInfo {
+                    url: self.get_binary_url(*c),
+                    mirrors: self.get_mirror_urls(c),
+                    checksum: self.get_expected_checksum(c),
+                };
+                (c.binary_name().to_string(), info)
+            })
+            .collect();
+        RuntimeManifest {
+            version: get_config().map(|c| c.version).unwrap_or_default(),
+            binaries,
+        }
     }
 
     /// Calculate SHA256 checksum of a file
@@ -705,102 +1216,91 @@ impl RuntimeDownloader {
         Ok(hex::encode(hasher.finalize()))
     }
 
-    /// Calculate SHA256 checksum from bytes
-    fn calculate_checksum_from_bytes(&self, bytes: &[u8]) -> Result<String, String> {
-        let mut hasher = Sha256::new();
-        hasher.update(bytes);
-        Ok(hex::encode(hasher.finalize()))
-    }
-
     /// Get the expected checksum for a component based on current platform
-    fn get_expected_checksum(&self, component: &BinaryComponent, _url: &str) -> Option<String> {
+    fn get_expected_checksum(&self, component: &BinaryComponent) -> Option<String> {
         let config = get_config()?;
-        let platform_key = self.platform.url_key();
-
         match component {
-            BinaryComponent::Php | BinaryComponent::MySQL | BinaryComponent::MariaDB | BinaryComponent::Caddy | BinaryComponent::PostgreSQL => {
-                let version_info: Box<dyn Iterator<Item = &VersionInfo>> = match component {
-                    BinaryComponent::Caddy => Box::new(config.binaries.caddy.versions.iter()),
-                    BinaryComponent::Php => Box::new(config.binaries.php.versions.iter()),
-                    BinaryComponent::MySQL => Box::new(config.binaries.mysql.versions.iter()),
-                    BinaryComponent::MariaDB => {
-                        match &config.binaries.mariadb {
-                            Some(mc) => Box::new(mc.versions.iter()),
-                            None => return None,
-                        }
-                    }
-                    BinaryComponent::PostgreSQL => {
-                        match &config.binaries.postgresql {
-                            Some(pc) => Box::new(pc.versions.iter()),
-                            None => return None,
-                        }
-                    }
-                    _ => return None,
-                };
-
-                // Determine which version ID to look for based on package selection
-                let target_id = if let Some(ref selection) = self.package_selection {
-                    match component {
-                        BinaryComponent::Php => Some(selection.php.as_str()),
-                        BinaryComponent::MySQL => Some(selection.mysql.as_str()),
-                        BinaryComponent::MariaDB => Some(selection.mariadb.as_str()),
-                        BinaryComponent::PostgreSQL => Some(selection.postgresql.as_str()),
-                        _ => None,
-                    }
-                } else {
-                    None
-                };
-
-                for version in version_info {
-                    if target_id.is_some() && version.id == target_id.unwrap() {
-                        // Use the package selection
-                        return match platform_key.as_str() {
-                            "windowsX64" => version.checksums.windows_x64.clone(),
-                            "windowsArm64" => version.checksums.windows_arm64.clone(),
-                            "linuxX64" => version.checksums.linux_x64.clone(),
-                            "linuxArm64" => version.checksums.linux_arm64.clone(),
-                            "macOSX64" => version.checksums.macos_x64.clone(),
-                            "macOSArm64" => version.checksums.macos_arm64.clone(),
-                            _ => None,
-                        };
-                    } else if target_id.is_none() && version.selected {
-                        // Fall back to selected flag
-                        return match platform_key.as_str() {
-                            "windowsX64" => version.checksums.windows_x64.clone(),
-                            "windowsArm64" => version.checksums.windows_arm64.clone(),
-                            "linuxX64" => version.checksums.linux_x64.clone(),
-                            "linuxArm64" => version.checksums.linux_arm64.clone(),
-                            "macOSX64" => version.checksums.macos_x64.clone(),
-                            "macOSArm64" => version.checksums.macos_arm64.clone(),
-                            _ => None,
-                        };
-                    }
-                }
-                None
-            }
-            BinaryComponent::PhpMyAdmin => {
-                let version = config.binaries.phpmyadmin.versions.iter()
-                    .find(|v| v.selected)?;
-                version.checksum.clone()
+            BinaryComponent::PhpMyAdmin | BinaryComponent::Adminer => {
+                Self::find_single_url_version(&config, component)?.checksum
             }
-            BinaryComponent::Adminer => {
-                if let Some(ac) = &config.binaries.adminer {
-                    let version = ac.versions.iter()
-                        .find(|v| v.selected)?;
-                    version.checksum.clone()
-                } else {
-                    None
-                }
+            _ => self
+                .find_version_info(&config, component)?
+                .checksums
+                .for_platform(&self.component_platform(*component).url_key()),
+        }
+    }
+
+    /// Get the fallback URLs for a component based on current platform
+    fn get_mirror_urls(&self, component: &BinaryComponent) -> Vec<String> {
+        let Some(config) = get_config() else { return Vec::new() };
+        match component {
+            BinaryComponent::PhpMyAdmin | BinaryComponent::Adminer => {
+                Self::find_single_url_version(&config, component).map(|v| v.mirrors).unwrap_or_default()
             }
+            _ => self
+                .find_version_info(&config, component)
+                .map(|v| v.mirrors.for_platform(&self.component_platform(*component).url_key()))
+                .unwrap_or_default(),
         }
     }
 
-    /// Extract a ZIP archive
-    fn extract_zip(&self, archive_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    /// Config entry of a platform-specific component, by package selection or `selected` flag
+    fn find_version_info(&self, config: &RuntimeConfig, component: &BinaryComponent) -> Option<VersionInfo> {
+        let versions = match component {
+            BinaryComponent::Caddy => &config.binaries.caddy.versions,
+            BinaryComponent::Php => &config.binaries.php.versions,
+            BinaryComponent::MySQL => &config.binaries.mysql.versions,
+            BinaryComponent::MariaDB => &config.binaries.mariadb.as_ref()?.versions,
+            BinaryComponent::PostgreSQL => &config.binaries.postgresql.as_ref()?.versions,
+            BinaryComponent::Cloudflared => &config.binaries.cloudflared.as_ref()?.versions,
+            BinaryComponent::Mailpit => &config.binaries.mailpit.as_ref()?.versions,
+            BinaryComponent::Node => &config.binaries.node.as_ref()?.versions,
+            BinaryComponent::Minio => &config.binaries.minio.as_ref()?.versions,
+            BinaryComponent::PhpMyAdmin | BinaryComponent::Adminer => return None,
+        };
+
+        // Determine which version ID to look for based on package selection
+        let target_id = self.package_selection.as_ref().and_then(|selection| match component {
+            BinaryComponent::Php => Some(selection.php.as_str()),
+            BinaryComponent::MySQL => Some(selection.mysql.as_str()),
+            BinaryComponent::MariaDB => Some(selection.mariadb.as_str()),
+            BinaryComponent::PostgreSQL => Some(selection.postgresql.as_str()),
+            _ => None,
+        });
+
+        versions
+            .iter()
+            .find(|v| match target_id {
+                Some(id) => v.id == id,
+                // Fall back to selected flag
+                None => v.selected,
+            })
+            .cloned()
+    }
+
+    /// Config entry of a platform-independent component (phpMyAdmin, Adminer)
+    fn find_single_url_version(config: &RuntimeConfig, component: &BinaryComponent) -> Option<VersionInfoSingleUrl> {
+        let versions = match component {
+            BinaryComponent::PhpMyAdmin => &config.binaries.phpmyadmin.versions,
+            BinaryComponent::Adminer => &config.binaries.adminer.as_ref()?.versions,
+            _ => return None,
+        };
+        versions.iter().find(|v| v.selected).cloned()
+    }
+
+    /// Extract a ZIP archive, reporting progress by entry count
+    fn extract_zip(&self, archive_path: &Path, dest_dir: &Path, on_progress: &dyn Fn(u8)) -> Result<(), String> {
         let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
         let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP: {}", e))?;
 
-        for i in 0..archive.len() {
+        let total = archive.len();
+        let mut last_percent = 0;
+        for i in 0..total {
+            let percent = (i * 100 / total) as u8;
+            if percent != last_percent {
+                last_percent = percent;
+                on_progress(percent);
+            }
             let mut file = archive
                 .by_index(i)
                 .map_err(|e| format!("Failed to get file: {}", e))?;
@@ -837,12 +1337,12 @@ impl RuntimeDownloader {
         Ok(())
     }
 
-    /// Extract a tar.gz archive
-    fn extract_tar_gz(&self, archive_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    /// Extract a tar.gz archive, reporting progress by compressed bytes read
+    fn extract_tar_gz(&self, archive_path: &Path, dest_dir: &Path, on_progress: &dyn Fn(u8)) -> Result<(), String> {
         use flate2::read::GzDecoder;
 
         let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
-        let decoder = GzDecoder::new(file);
+        let decoder = GzDecoder::new(ProgressReader::new(file, on_progress));
         let mut archive = tar::Archive::new(decoder);
 
         extract_tar_entries(&mut archive, dest_dir, archive_path)?;
@@ -851,11 +1351,11 @@ impl RuntimeDownloader {
         Ok(())
     }
 
-    fn extract_tar_xz(&self, archive_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    fn extract_tar_xz(&self, archive_path: &Path, dest_dir: &Path, on_progress: &dyn Fn(u8)) -> Result<(), String> {
         use xz2::read::XzDecoder;
 
         let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
-        let decoder = XzDecoder::new(file);
+        let decoder = XzDecoder::new(ProgressReader::new(file, on_progress));
         let mut archive = tar::Archive::new(decoder);
 
         extract_tar_entries(&mut archive, dest_dir, archive_path)?;
@@ -871,6 +1371,9 @@ impl RuntimeDownloader {
 
             let binary_paths = [
                 dest_dir.join("caddy"),
+                dest_dir.join("cloudflared"),
+                dest_dir.join("mailpit"),
+                dest_dir.join("minio"),
                 dest_dir.join("php-fpm"),
                 dest_dir.join("php-cgi"),
                 dest_dir.join("buildroot/bin/php-fpm"),
@@ -918,12 +1421,32 @@ impl RuntimeDownloader {
         }
     }
 
+    /// Components installed by the first-run wizard on this platform
+    pub fn default_components(&self) -> Vec<BinaryComponent> {
+        let flavor = self.package_selection.as_ref().and_then(|s| s.db_flavor.as_deref());
+        let db_component = match (flavor, self.platform) {
+            (Some("mariadb"), _) => BinaryComponent::MariaDB,
+            (Some("mysql"), _) => BinaryComponent::MySQL,
+            // On Linux, use MariaDB instead of MySQL
+            (_, Platform::LinuxX64 | Platform::LinuxArm64) => BinaryComponent::MariaDB,
+            _ => BinaryComponent::MySQL,
+        };
+        vec![
+            BinaryComponent::Caddy,
+            BinaryComponent::Php,
+            db_component,
+            BinaryComponent::PhpMyAdmin,
+            BinaryComponent::PostgreSQL,
+            BinaryComponent::Adminer,
+        ]
+    }
+
     /// Download and install all runtime binaries
     pub async fn download_all(
         &self,
         progress_cb: ProgressCallback,
     ) -> Result<Vec<PathBuf>, String> {
-        self.download_all_impl(progress_cb, &[]).await
+        self.download_components(progress_cb, &self.default_components()).await
     }
 
     /// Download and install runtime binaries with option to skip existing components
@@ -932,159 +1455,144 @@ impl RuntimeDownloader {
         progress_cb: ProgressCallback,
         skip_list: &[&str],
     ) -> Result<Vec<PathBuf>, String> {
-        self.download_all_impl(progress_cb, skip_list).await
+        let mut components = self.default_components();
+        // Never skip required components
+        components.retain(|component| {
+            let required = matches!(*component, BinaryComponent::Caddy | BinaryComponent::Php);
+            let skipped = !required && skip_list.contains(&component.binary_name());
+            if skipped {
+                tracing::info!("Skipping {} (already installed)", component.name());
+            }
+            !skipped
+        });
+        self.download_components(progress_cb, &components).await
     }
 
-    async fn download_all_impl(
+    /// Download and install the given components, leaving the others as they are
+    pub async fn download_components(
         &self,
         progress_cb: ProgressCallback,
-        skip_list: &[&str],
+        components: &[BinaryComponent],
     ) -> Result<Vec<PathBuf>, String> {
+        let _awake = crate::process::power::SleepInhibitor::acquire("Installing runtime components");
         // Kill any lingering service processes that may lock files in the runtime dir
         kill_runtime_processes();
-        // Clean up any stale temp downloads
-        let temp_dir = std::env::temp_dir().join("campp-download");
-        let _ = fs::remove_dir_all(&temp_dir);
-
-        // On Linux, use MariaDB instead of MySQL
-        let db_component = match self.platform {
-            Platform::LinuxX64 | Platform::LinuxArm64 => BinaryComponent::MariaDB,
-            _ => BinaryComponent::MySQL,
-        };
-
-        // Core components (always downloaded)
-        let mut components = vec![
-            BinaryComponent::Caddy,
-            BinaryComponent::Php,
-            db_component,
-            BinaryComponent::PhpMyAdmin,
-        ];
-
-        // Optional components (only if not in skip list)
-        if !skip_list.contains(&"postgresql") {
-            components.push(BinaryComponent::PostgreSQL);
+        // Read-only files cannot be overwritten during extraction
+        super::hardening::relax_if_hardened();
+        // Clean up stale temp downloads, keeping cached archives if offline reinstall is enabled
+        let cache_policy = crate::config::AppSettings::load().download_cache;
+        if cache_policy.keep_for_offline_reinstall {
+            super::download_cache::cleanup_download_dir(&cache_policy, &self.get_installed_components());
+        } else {
+            super::download_cache::clear_download_dir();
         }
-        if !skip_list.contains(&"adminer") {
-            components.push(BinaryComponent::Adminer);
+
+        // Components with a system binary in settings are not downloaded
+        let overrides = crate::config::AppSettings::load().binary_overrides;
+        let mut to_install: Vec<BinaryComponent> = Vec::new();
+        for component in components {
+            if let Some(path) = overrides.get(*component) {
+                tracing::info!("Using system {} at {}, skipping download", component.name(), path.display());
+            } else if !to_install.contains(component) {
+                to_install.push(*component);
+            }
         }
 
-        let total = components.len() as u8;
+        // Refuse archives that cannot be verified before downloading anything
+        let manifest = self.manifest(&to_install);
+        self.ensure_available(&manifest)?;
+        self.ensure_verified(&manifest)?;
 
         // Create temp directory for downloads
-        let temp_dir = std::env::temp_dir().join("campp-download");
+        let temp_dir = super::download_cache::download_dir();
         fs::create_dir_all(&temp_dir)
             .map_err(|e| format!("Failed to create temp directory: {}", e))?;
 
-        let mut downloaded_files = Vec::new();
-
-        for (i, component) in components.iter().enumerate() {
-            let component_name = component.binary_name();
-
-            // Skip if component is in skip list (but never skip required components)
-            let required = matches!(*component, BinaryComponent::Caddy | BinaryComponent::Php);
-            if !required && skip_list.contains(&component_name) {
-                tracing::info!("Skipping {} (already installed)", component.name());
-                continue;
-            }
-
-            let current = (i + 1) as u8;
-
-            // Download
-            let downloaded_path = self
-                .download_component(*component, &temp_dir, &progress_cb, current, total)
-                .await?;
+        let total = to_install.len() as u8;
+        let tracker = ProgressTracker::new(
+            progress_cb,
+            to_install
+                .iter()
+                .map(|c| ComponentProgress {
+                    component: c.name().to_string(),
+                    display: c.display_name(),
+                    version: self.get_component_version(c),
+                    step: DownloadStep::Downloading,
+                    percent: 0,
+                    downloaded_bytes: 0,
+                    total_bytes: 0,
+                    attempt: 1,
+                })
+                .collect(),
+        );
+        let progress_cb: ProgressCallback = Box::new(move |progress| tracker.report(progress));
+
+        // Download concurrently, the first failure cancels the others and
+        // leaves their partial files to resume
+        let concurrency = crate::config::AppSettings::load().download_concurrency.max(1);
+        let mut downloads: Vec<(usize, PathBuf)> = futures_util::stream::iter(to_install.iter().copied().enumerate())
+            .map(|(i, component)| {
+                let info = &manifest.binaries[component.binary_name()];
+                let (temp_dir, progress_cb) = (&temp_dir, &progress_cb);
+                async move {
+                    let path = self
+                        .download_component(component, info, temp_dir, progress_cb)
+                        .await?;
+                    Ok::<_, String>((i, path))
+                }
+            })
+            .buffer_unordered(concurrency)
+            .try_collect()
+            .await?;
+        downloads.sort_by_key(|(i, _)| *i);
 
-            // Checksum is verified during download_component
-            progress_cb(DownloadProgress {
-                step: DownloadStep::Extracting,
-                percent: 0,
+        // Size and checksum are verified during download_component, extract in order
+        let mut downloaded_files = Vec::new();
+        for (i, downloaded_path) in downloads {
+            let component = to_install[i];
+            let progress = |step: DownloadStep, percent: u8| DownloadProgress {
+                step,
+                percent,
                 current_component: component.name().to_string(),
                 component_display: component.display_name(),
                 version: self.get_component_version(&component),
                 total_components: total,
                 downloaded_bytes: 0,
                 total_bytes: 0,
-            });
-
-            let runtime_dir = self.get_runtime_dir()?;
-            fs::create_dir_all(&runtime_dir)
-                .map_err(|e| format!("Failed to create runtime directory: {}", e))?;
-
-            // Determine extraction method based on file extension
-            let extension = downloaded_path
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("");
-
-            let is_tar_gz = downloaded_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n.ends_with(".tar.gz"))
-                .unwrap_or(false);
-
-            let is_tar_xz = downloaded_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .map(|n| n.ends_with(".tar.xz"))
-                .unwrap_or(false);
-
-            if is_tar_gz || extension == "gz" {
-                self.extract_tar_gz(&downloaded_path, &runtime_dir)?;
-            } else if is_tar_xz || extension == "xz" {
-                self.extract_tar_xz(&downloaded_path, &runtime_dir)?;
-            } else if extension == "zip" {
-                self.extract_zip(&downloaded_path, &runtime_dir)?;
-            } else if extension == "php" {
-                // Single PHP file (e.g., Adminer) — copy to component directory
-                let component_dir = runtime_dir.join(component.binary_name());
-                fs::create_dir_all(&component_dir)
-                    .map_err(|e| format!("Failed to create {} directory: {}", component.name(), e))?;
-                let dest_path = component_dir.join(downloaded_path.file_name().unwrap_or_default());
-                fs::copy(&downloaded_path, &dest_path)
-                    .map_err(|e| format!("Failed to copy {}: {}", component.name(), e))?;
-                // Also create a generic adminer.php for easy routing
-                if *component == BinaryComponent::Adminer {
-                    let generic_dest = component_dir.join("adminer.php");
-                    let _ = fs::copy(&downloaded_path, &generic_dest);
-                }
-            } else if extension.is_empty() {
-                // Bare binary - copy directly to runtime directory
-                let binary_name = downloaded_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .ok_or("Invalid binary name")?;
-
-                let dest_path = runtime_dir.join(binary_name);
-                fs::copy(&downloaded_path, &dest_path)
-                    .map_err(|e| format!("Failed to copy binary: {}", e))?;
-
-                // Set executable permission on Unix
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    let mut perms = fs::metadata(&dest_path)
-                        .map_err(|e| format!("Failed to get metadata: {}", e))?
-                        .permissions();
-                    perms.set_mode(0o755);
-                    fs::set_permissions(&dest_path, perms)
-                        .map_err(|e| format!("Failed to set permissions: {}", e))?;
-                }
-            } else {
-                return Err(format!("Unsupported archive format: {}", extension));
-            }
-
-            // Create marker file to indicate component was installed with version
-            let version = self.get_component_version(&component);
-            let marker_file = runtime_dir.join(format!("{}_installed.txt", component.binary_name()));
-            if let Some(parent) = marker_file.parent() {
-                let _ = fs::create_dir_all(parent);
-            }
-            fs::write(&marker_file, format!("version={}\ninstalled_at={:?}", version, std::time::SystemTime::now()))
-                .map_err(|e| format!("Failed to create marker file: {}", e))?;
+                overall_percent: 0,
+                components: Vec::new(),
+                attempt: 0,
+            };
+
+            progress_cb(progress(DownloadStep::Extracting, 0));
+            self.install_archive(component, &downloaded_path, &|percent| {
+                progress_cb(progress(DownloadStep::Extracting, percent))
+            })?;
+            progress_cb(progress(DownloadStep::Installing, 100));
 
             downloaded_files.push(downloaded_path);
         }
 
+        // Locate and run the extracted binaries so a bad archive layout or a
+        // missing system library fails the install instead of the first
+        // service start
+        progress_cb(DownloadProgress {
+            step: DownloadStep::Installing,
+            percent: 100,
+            current_component: "All".to_string(),
+            component_display: "Checking binaries".to_string(),
+            version: String::new(),
+            total_components: total,
+            downloaded_bytes: 0,
+            total_bytes: 0,
+            overall_percent: 0,
+            components: Vec::new(),
+            attempt: 0,
+        });
+        let paths = locate_runtime_binaries()?;
+        check_located_binaries(&paths, &to_install)?;
+        super::validate::validate_installed(&paths, &to_install, &self.get_runtime_dir()?)?;
+
         // Create all application directories (config, logs, mysql/data, projects)
         if let Ok(app_paths) = get_app_data_paths() {
             if let Err(e) = app_paths.ensure_directories() {
@@ -1101,16 +1609,299 @@ impl RuntimeDownloader {
             total_components: total,
             downloaded_bytes: 0,
             total_bytes: 0,
+            overall_percent: 0,
+            components: Vec::new(),
+            attempt: 0,
         });
 
-        // Keep temp files for user to access if needed
-        // Uncomment to cleanup: let _ = fs::remove_dir_all(temp_dir);
+        self.cleanup_after_install();
+        super::hardening::harden_if_enabled();
 
         Ok(downloaded_files)
     }
 
+    /// Fail if a component in `manifest` has no build for this platform,
+    /// native or emulated
+    fn ensure_available(&self, manifest: &RuntimeManifest) -> Result<(), String> {
+        // Without a config every URL is missing, download_component reports that with diagnostics
+        if get_config().is_none() {
+            return Ok(());
+        }
+        let mut missing: Vec<&str> = manifest
+            .binaries
+            .iter()
+            .filter(|(_, info)| info.url.is_empty())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort();
+        let platforms = match self.platform.emulated() {
+            Some(fallback) => format!("{:?} or {:?}", self.platform, fallback),
+            None => format!("{:?}", self.platform),
+        };
+        Err(format!(
+            "No build of {} for {}.\n\
+             Pick another version, or add a URL for this platform to runtime-config.json.",
+            missing.join(", "),
+            platforms
+        ))
+    }
+
+    /// Fail unless every archive in `manifest` has a checksum, or unverified
+    /// downloads are allowed in settings
+    fn ensure_verified(&self, manifest: &RuntimeManifest) -> Result<(), String> {
+        let unverified = manifest.unverified();
+        if !unverified.is_empty() && !crate::config::AppSettings::load().allow_unverified_downloads {
+            return Err(format!(
+                "No SHA-256 checksum configured for {} on {:?}.\n\
+                 Add the checksums to runtime-config.json, or enable unverified downloads in settings to install anyway.",
+                unverified.join(", "),
+                self.platform
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replace an installed component with the version in the package selection
+    ///
+    /// The new version is downloaded and unpacked next to the old one, then
+    /// swapped in. The caller stops the component's service first.
+    pub async fn upgrade_component(
+        &self,
+        component: BinaryComponent,
+        progress_cb: ProgressCallback,
+    ) -> Result<(), String> {
+        let _awake = crate::process::power::SleepInhibitor::acquire("Upgrading runtime components");
+        let manifest = self.manifest(&[component]);
+        self.ensure_available(&manifest)?;
+        self.ensure_verified(&manifest)?;
+
+        let temp_dir = super::download_cache::download_dir();
+        fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+        let version = self.get_component_version(&component);
+        let tracker = ProgressTracker::new(
+            progress_cb,
+            vec![ComponentProgress {
+                component: component.name().to_string(),
+                display: component.display_name(),
+                version: version.clone(),
+                step: DownloadStep::Downloading,
+                percent: 0,
+                downloaded_bytes: 0,
+                total_bytes: 0,
+                attempt: 1,
+            }],
+        );
+        let progress_cb: ProgressCallback = Box::new(move |progress| tracker.report(progress));
+        let progress = |step: DownloadStep, percent: u8, display: String| DownloadProgress {
+            step,
+            percent,
+            current_component: component.name().to_string(),
+            component_display: display,
+            version: version.clone(),
+            total_components: 1,
+            downloaded_bytes: 0,
+            total_bytes: 0,
+            overall_percent: 0,
+            components: Vec::new(),
+            attempt: 0,
+        };
+
+        let info = &manifest.binaries[component.binary_name()];
+        let downloaded_path = self.download_component(component, info, &temp_dir, &progress_cb).await?;
+
+        progress_cb(progress(DownloadStep::Extracting, 0, component.display_name()));
+        let runtime_dir = self.get_runtime_dir()?;
+        let staging = super::upgrade::staging_dir(&runtime_dir, component);
+        if staging.exists() {
+            fs::remove_dir_all(&staging)
+                .map_err(|e| format!("Failed to remove {}: {}", staging.display(), e))?;
+        }
+        let on_progress = |percent| progress_cb(progress(DownloadStep::Extracting, percent, component.display_name()));
+        let unpacked = self.unpack_archive(component, &downloaded_path, &staging, &on_progress).and_then(|()| {
+            match locate_runtime_binaries() {
+                Ok(paths) if component == BinaryComponent::PhpMyAdmin && paths.phpmyadmin.is_dir() => {
+                    super::upgrade::migrate_phpmyadmin_files(&paths.phpmyadmin, &staging)
+                }
+                _ => Ok(()),
+            }
+        });
+        if let Err(e) = unpacked {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+
+        progress_cb(progress(DownloadStep::Installing, 50, component.display_name()));
+        let marker_file = runtime_dir.join(format!("{}_installed.txt", component.binary_name()));
+        let previous_marker = fs::read_to_string(&marker_file).ok();
+        super::hardening::relax_if_hardened();
+        let swapped = super::upgrade::swap_component(&runtime_dir, component, &staging)
+            .and_then(|()| self.write_marker(component, &runtime_dir));
+        super::hardening::harden_if_enabled();
+        if let Err(e) = swapped {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+
+        // The previous version stays in place until the new one passes
+        // validation, and is put back if it does not
+        let validated = locate_runtime_binaries().and_then(|paths| {
+            check_located_binaries(&paths, &[component])?;
+            super::validate::validate_installed(&paths, &[component], &runtime_dir)
+        });
+        if let Err(e) = validated {
+            super::hardening::relax_if_hardened();
+            let restored = super::upgrade::restore_previous(&runtime_dir, component).and_then(|()| match &previous_marker {
+                Some(marker) => fs::write(&marker_file, marker).map_err(|e| format!("Failed to restore marker file: {}", e)),
+                None => Ok(()),
+            });
+            super::hardening::harden_if_enabled();
+            return Err(match restored {
+                Ok(()) => format!("{} failed validation and the previous version was restored: {}", component.display_name(), e),
+                Err(restore_err) => format!("{} failed validation: {}; restoring the previous version also failed: {}", component.display_name(), e, restore_err),
+            });
+        }
+        super::upgrade::finish_swap(&runtime_dir, component);
+        progress_cb(progress(DownloadStep::Complete, 100, format!("{} upgraded", component.display_name())));
+        self.cleanup_after_install();
+        Ok(())
+    }
+
+    /// Remove downloaded archives once they are installed, if settings ask for it
+    fn cleanup_after_install(&self) {
+        let policy = crate::config::AppSettings::load().download_cache;
+        let Some(keep) = policy.keep_after_install else { return };
+        let report = super::download_cache::cleanup_downloads(keep, &policy, &self.get_installed_components());
+        if !report.removed.is_empty() {
+            tracing::info!(
+                "Removed {} installed archives ({} MB)",
+                report.removed.len(),
+                report.freed_bytes / (1024 * 1024)
+            );
+        }
+    }
+
+    /// Extract or copy a verified download into the runtime directory and
+    /// record its version
+    fn install_archive(&self, component: BinaryComponent, downloaded_path: &Path, on_progress: &dyn Fn(u8)) -> Result<(), String> {
+        let runtime_dir = self.get_runtime_dir()?;
+        self.unpack_archive(component, downloaded_path, &runtime_dir, on_progress)?;
+        self.write_marker(component, &runtime_dir)
+    }
+
+    /// Extract or copy a verified download into `runtime_dir`, reporting the
+    /// extracted percentage to `on_progress`
+    fn unpack_archive(
+        &self,
+        component: BinaryComponent,
+        downloaded_path: &Path,
+        runtime_dir: &Path,
+        on_progress: &dyn Fn(u8),
+    ) -> Result<(), String> {
+        // cloudflared, Mailpit and MinIO are single binaries, kept in directories of their own
+        let component_dir = runtime_dir.join(component.binary_name());
+        let runtime_dir = if matches!(component, BinaryComponent::Cloudflared | BinaryComponent::Mailpit | BinaryComponent::Minio) {
+            &component_dir
+        } else {
+            runtime_dir
+        };
+        fs::create_dir_all(runtime_dir)
+            .map_err(|e| format!("Failed to create runtime directory: {}", e))?;
+
+        // Determine extraction method based on file extension
+        let extension = downloaded_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+
+        let is_tar_gz = downloaded_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.ends_with(".tar.gz"))
+            .unwrap_or(false);
+
+        let is_tar_xz = downloaded_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.ends_with(".tar.xz"))
+            .unwrap_or(false);
+
+        if is_tar_gz || extension == "gz" || extension == "tgz" {
+            self.extract_tar_gz(downloaded_path, runtime_dir, on_progress)?;
+        } else if is_tar_xz || extension == "xz" {
+            self.extract_tar_xz(downloaded_path, runtime_dir, on_progress)?;
+        } else if extension == "zip" {
+            self.extract_zip(downloaded_path, runtime_dir, on_progress)?;
+        } else if extension == "php" {
+            // Single PHP file (e.g., Adminer) — copy to component directory
+            let component_dir = runtime_dir.join(component.binary_name());
+            fs::create_dir_all(&component_dir)
+                .map_err(|e| format!("Failed to create {} directory: {}", component.name(), e))?;
+            let dest_path = component_dir.join(downloaded_path.file_name().unwrap_or_default());
+            fs::copy(downloaded_path, &dest_path)
+                .map_err(|e| format!("Failed to copy {}: {}", component.name(), e))?;
+            // Also create a generic adminer.php for easy routing
+            if component == BinaryComponent::Adminer {
+                let generic_dest = component_dir.join("adminer.php");
+                let _ = fs::copy(downloaded_path, &generic_dest);
+            }
+        } else if extension.is_empty() || extension == "bin" || extension == "exe" {
+            // Bare binary - copy directly to runtime directory
+            let binary_name = match component {
+                BinaryComponent::Cloudflared => format!("cloudflared{}", std::env::consts::EXE_SUFFIX),
+                // Downloaded under its release name
+                BinaryComponent::Minio => format!("minio{}", std::env::consts::EXE_SUFFIX),
+                _ => downloaded_path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .ok_or("Invalid binary name")?
+                    .to_string(),
+            };
+
+            let dest_path = runtime_dir.join(binary_name);
+            fs::copy(downloaded_path, &dest_path)
+                .map_err(|e| format!("Failed to copy binary: {}", e))?;
+
+            // Set executable permission on Unix
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mut perms = fs::metadata(&dest_path)
+                    .map_err(|e| format!("Failed to get metadata: {}", e))?
+                    .permissions();
+                perms.set_mode(0o755);
+                fs::set_permissions(&dest_path, perms)
+                    .map_err(|e| format!("Failed to set permissions: {}", e))?;
+            }
+        } else {
+            return Err(format!("Unsupported archive format: {}", extension));
+        }
+
+        Ok(())
+    }
+
+    /// Record the installed version of `component`
+    fn write_marker(&self, component: BinaryComponent, runtime_dir: &Path) -> Result<(), String> {
+        let version = self.get_component_version(&component);
+        let marker_file = runtime_dir.join(format!("{}_installed.txt", component.binary_name()));
+        if let Some(parent) = marker_file.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        fs::write(&marker_file, format!("version={}\ninstalled_at={:?}", version, std::time::SystemTime::now()))
+            .map_err(|e| format!("Failed to create marker file: {}", e))?;
+
+        Ok(())
+    }
+
     /// Get the runtime directory
     pub fn get_runtime_dir(&self) -> Result<PathBuf, String> {
+        if let Some(data_dir) = crate::config::data_dir::data_dir_override() {
+            return Ok(data_dir.join("runtime"));
+        }
+
         #[cfg(target_os = "windows")]
         {
             // On Windows, use the installation folder (where the exe is located)
@@ -1129,40 +1920,14 @@ impl RuntimeDownloader {
     }
 
     /// Check if runtime binaries are already installed
+    ///
+    /// Marker files only record versions, the stack counts as installed once
+    /// the Caddy and PHP binaries can be located and executed.
     pub fn is_installed(&self) -> bool {
-        let runtime_dir = match self.get_runtime_dir() {
-            Ok(dir) => dir,
-            Err(_) => return false,
-        };
-
-        // Check for marker files created during simulation or actual binaries
-        let caddy_marker = runtime_dir.join("caddy_installed.txt");
-        let php_marker = runtime_dir.join("php_installed.txt");
-        let mysql_marker = runtime_dir.join("mysql_installed.txt");
-        let mariadb_marker = runtime_dir.join("mariadb_installed.txt");
-        let phpmyadmin_marker = runtime_dir.join("phpmyadmin_installed.txt");
-
-        // Also check for actual binaries
-        #[cfg(target_os = "windows")]
-        let bin_check = {
-            let caddy_exe = runtime_dir.join("caddy").join("caddy.exe");
-            let php_exe = runtime_dir.join("php").join("php.exe");
-            let mysql_exe = runtime_dir.join("mysql").join("bin").join("mysqld.exe");
-            caddy_exe.exists() || php_exe.exists() || mysql_exe.exists()
-        };
-        #[cfg(not(target_os = "windows"))]
-        let bin_check = {
-            let caddy_bin = runtime_dir.join("caddy");
-            let php_fpm = runtime_dir.join("php-fpm");
-            let php_cgi = runtime_dir.join("php-cgi");
-            let mysql_bin = runtime_dir.join("mysql").join("bin").join("mysqld");
-            let mariadbd_bin = runtime_dir.join("mariadb").join("bin").join("mariadbd");
-            caddy_bin.exists() || php_fpm.exists() || php_cgi.exists()
-                || mysql_bin.exists() || mariadbd_bin.exists()
-        };
-
-        caddy_marker.exists() || php_marker.exists() || mysql_marker.exists()
-            || mariadb_marker.exists() || phpmyadmin_marker.exists() || bin_check
+        match locate_runtime_binaries() {
+            Ok(paths) => check_located_binaries(&paths, &[BinaryComponent::Caddy, BinaryComponent::Php]).is_ok(),
+            Err(_) => false,
+        }
     }
 
     /// Check which components are already installed with their versions
@@ -1173,7 +1938,7 @@ impl RuntimeDownloader {
             Err(_) => return installed,
         };
 
-        for component in ["caddy", "php", "mysql", "mariadb", "phpmyadmin", "postgresql", "adminer"] {
+        for component in ["caddy", "php", "mysql", "mariadb", "phpmyadmin", "postgresql", "adminer", "cloudflared", "mailpit", "node", "minio"] {
             let marker_file = runtime_dir.join(format!("{}_installed.txt", component));
             if let Ok(content) = fs::read_to_string(&marker_file) {
                 // Parse version from format: "version=1.2.3\ninstalled_at=..."
@@ -1191,8 +1956,7 @@ impl RuntimeDownloader {
 
     /// Uninstall a specific component by removing its marker file and binary files
     pub fn uninstall_component(&self, component: &str) -> Result<(), String> {
-        let valid_components = ["caddy", "php", "mysql", "mariadb", "phpmyadmin", "postgresql", "adminer"];
-        if !valid_components.contains(&component) {
+        if BinaryComponent::from_binary_name(component).is_none() {
             return Err(format!("Invalid component: {}", component));
         }
 
@@ -1292,6 +2056,18 @@ impl RuntimeDownloader {
                         .map_err(|e| format!("Failed to remove postgresql dir: {}", e))?;
                 }
             }
+            "cloudflared" => {
+                Self::remove_entries(&runtime_dir, &["cloudflared"])?;
+            }
+            "mailpit" => {
+                Self::remove_entries(&runtime_dir, &["mailpit"])?;
+            }
+            "node" => {
+                Self::remove_versioned_dirs(&runtime_dir, "node-v")?;
+            }
+            "minio" => {
+                Self::remove_entries(&runtime_dir, &["minio"])?;
+            }
             "adminer" => {
                 let adminer_dir = runtime_dir.join("adminer");
                 if adminer_dir.exists() {
@@ -1376,6 +2152,22 @@ fn is_executable(name: &str) -> bool {
         || name.contains("bin/")
 }
 
+/// Make sure the executables of `components` were installed and can run
+fn check_located_binaries(paths: &RuntimePaths, components: &[BinaryComponent]) -> Result<(), String> {
+    for component in components {
+        let (name, path) = match component {
+            BinaryComponent::Caddy => ("Caddy", &paths.caddy),
+            BinaryComponent::Php => ("PHP", &paths.php_cgi),
+            BinaryComponent::MySQL | BinaryComponent::MariaDB => ("MySQL/MariaDB", &paths.mysql),
+            _ => continue,
+        };
+        if !is_valid_binary(path) {
+            return Err(format!("{} binary not found or not executable after install: {}", name, path.display()));
+        }
+    }
+    Ok(())
+}
+
 /// Kill service processes that may hold file locks in the runtime directory
 fn kill_runtime_processes() {
     #[cfg(windows)]
@@ -1425,6 +2217,38 @@ fn create_file_with_retry(path: &Path) -> Result<File, String> {
     }
 }
 
+/// Reads an archive file and reports the percentage read so far, decoders
+/// wrap it to give tar extraction a progress measure
+struct ProgressReader<'a, R> {
+    inner: R,
+    read: u64,
+    total: u64,
+    last_percent: u8,
+    on_progress: &'a dyn Fn(u8),
+}
+
+impl<'a> ProgressReader<'a, File> {
+    fn new(file: File, on_progress: &'a dyn Fn(u8)) -> Self {
+        let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Self { inner: file, read: 0, total, last_percent: 0, on_progress }
+    }
+}
+
+impl<R: io::Read> io::Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        if let Some(percent) = (self.read * 100).checked_div(self.total) {
+            let percent = percent.min(100) as u8;
+            if percent != self.last_percent {
+                self.last_percent = percent;
+                (self.on_progress)(percent);
+            }
+        }
+        Ok(n)
+    }
+}
+
 /// Extract tar archive entries with retry on file creation (handles Windows file locking)
 fn extract_tar_entries<R: std::io::Read>(
     archive: &mut tar::Archive<R>,
@@ -0,0 +1,327 @@
+//! Read-only hardening of the runtime directory
+//!
+//! When enabled, every file under the runtime directory is made read-only
+//! after installation and a manifest of file sizes and SHA-256 hashes is
+//! written alongside. `verify_runtime` compares the tree against the manifest
+//! to detect tampering or accidental modification. The phpMyAdmin and Adminer
+//! configs are rewritten on every start, so they stay writable and are
+//! checked against the fingerprint CAMPP recorded when it last wrote them.
+//! Installs, upgrades and uninstalls relax the permissions first and
+//! re-harden afterwards.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use super::locator::{get_app_data_paths, locate_runtime_binaries};
+
+/// Manifest written to the root of the runtime directory
+const MANIFEST_FILE: &str = ".campp-integrity.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    sha256: String,
+}
+
+/// Result of comparing the runtime directory with its manifest
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IntegrityReport {
+    /// False when the runtime has never been hardened (no manifest)
+    pub hardened: bool,
+    pub modified: Vec<String>,
+    pub added: Vec<String>,
+    pub missing: Vec<String>,
+    /// Files that are writable again although they should be read-only
+    pub writable: Vec<String>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.added.is_empty() && self.missing.is_empty() && self.writable.is_empty()
+    }
+}
+
+/// Make the runtime directory read-only and record its manifest
+///
+/// Returns the number of files hardened.
+pub fn harden_runtime() -> Result<usize, String> {
+    let runtime_dir = get_app_data_paths()?.runtime_dir;
+    harden_dir(&runtime_dir, &regenerated_paths())
+}
+
+/// Restore write permission on the runtime directory
+///
+/// The manifest is removed, as the tree is expected to change.
+pub fn relax_runtime() -> Result<usize, String> {
+    let runtime_dir = get_app_data_paths()?.runtime_dir;
+    relax_dir(&runtime_dir)
+}
+
+/// Compare the runtime directory with its manifest
+pub fn verify_runtime() -> Result<IntegrityReport, String> {
+    let runtime_dir = get_app_data_paths()?.runtime_dir;
+    verify_dir(&runtime_dir, &regenerated_paths(), &generated_configs())
+}
+
+/// Whether the runtime directory is currently hardened
+pub fn is_hardened() -> bool {
+    get_app_data_paths()
+        .map(|p| p.runtime_dir.join(MANIFEST_FILE).exists())
+        .unwrap_or(false)
+}
+
+/// Relax permissions before modifying the runtime, if it is hardened
+pub fn relax_if_hardened() {
+    if is_hardened() {
+        if let Err(e) = relax_runtime() {
+            tracing::warn!("Failed to relax runtime permissions: {}", e);
+        }
+    }
+}
+
+/// Harden the runtime after modifying it, if enabled in settings
+pub fn harden_if_enabled() {
+    if crate::config::AppSettings::load().harden_runtime {
+        match harden_runtime() {
+            Ok(count) => tracing::info!("Hardened {} runtime files", count),
+            Err(e) => tracing::warn!("Failed to harden runtime: {}", e),
+        }
+    }
+}
+
+/// Files inside the runtime directory that CAMPP rewrites on every service start
+fn regenerated_paths() -> Vec<PathBuf> {
+    let mut paths = generated_configs();
+    if let Ok(runtime) = locate_runtime_binaries() {
+        paths.push(runtime.phpmyadmin.join("tmp"));
+    }
+    let fingerprints: Vec<PathBuf> = paths.iter().filter_map(|p| crate::config::generated::fingerprints_path(p)).collect();
+    paths.extend(fingerprints);
+    paths
+}
+
+/// Configs inside the runtime directory that CAMPP generates
+fn generated_configs() -> Vec<PathBuf> {
+    match locate_runtime_binaries() {
+        Ok(paths) => vec![
+            paths.phpmyadmin.join("config.inc.php"),
+            paths.adminer.join("index.php"),
+        ],
+        Err(_) => Vec::new(),
+    }
+}
+
+fn harden_dir(runtime_dir: &Path, skip: &[PathBuf]) -> Result<usize, String> {
+    if !runtime_dir.exists() {
+        return Err(format!("Runtime directory not found: {}", runtime_dir.display()));
+    }
+
+    // The old manifest is read-only, it has to go before a new one can be written
+    let manifest_path = runtime_dir.join(MANIFEST_FILE);
+    if manifest_path.exists() {
+        set_writable(&manifest_path)?;
+    }
+
+    let mut files = Vec::new();
+    collect_files(runtime_dir, skip, &mut files)?;
+
+    let mut manifest = BTreeMap::new();
+    for file in &files {
+        set_readonly(file)?;
+        manifest.insert(relative_key(runtime_dir, file), stamp(file)?);
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize integrity manifest: {}", e))?;
+    fs::write(&manifest_path, json)
+        .map_err(|e| format!("Failed to write integrity manifest: {}", e))?;
+    set_readonly(&manifest_path)?;
+
+    Ok(files.len())
+}
+
+fn relax_dir(runtime_dir: &Path) -> Result<usize, String> {
+    if !runtime_dir.exists() {
+        return Ok(0);
+    }
+
+    let mut files = Vec::new();
+    collect_files(runtime_dir, &[], &mut files)?;
+    for file in &files {
+        set_writable(file)?;
+    }
+
+    let manifest_path = runtime_dir.join(MANIFEST_FILE);
+    if manifest_path.exists() {
+        set_writable(&manifest_path)?;
+        fs::remove_file(&manifest_path)
+            .map_err(|e| format!("Failed to remove integrity manifest: {}", e))?;
+    }
+
+    Ok(files.len())
+}
+
+fn verify_dir(runtime_dir: &Path, skip: &[PathBuf], configs: &[PathBuf]) -> Result<IntegrityReport, String> {
+    let manifest_path = runtime_dir.join(MANIFEST_FILE);
+    let Ok(content) = fs::read_to_string(&manifest_path) else {
+        return Ok(IntegrityReport::default());
+    };
+    let mut expected: BTreeMap<String, FileStamp> = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid integrity manifest: {}", e))?;
+
+    let mut report = IntegrityReport { hardened: true, ..Default::default() };
+
+    let mut files = Vec::new();
+    collect_files(runtime_dir, skip, &mut files)?;
+    for file in &files {
+        let key = relative_key(runtime_dir, file);
+        match expected.remove(&key) {
+            Some(recorded) => {
+                if stamp(file)? != recorded {
+                    report.modified.push(key.clone());
+                }
+                let readonly = fs::metadata(file)
+                    .map(|m| m.permissions().readonly())
+                    .unwrap_or(true);
+                if !readonly {
+                    report.writable.push(key);
+                }
+            }
+            None => report.added.push(key),
+        }
+    }
+    report.missing = expected.into_keys().collect();
+    for config in configs {
+        if crate::config::generated::is_hand_edited(config) {
+            report.modified.push(relative_key(runtime_dir, config));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Recursively collect regular files, skipping symlinks, the manifest and `skip`
+fn collect_files(dir: &Path, skip: &[PathBuf], out: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if skip.iter().any(|s| path.starts_with(s)) || path.file_name().is_some_and(|n| n == MANIFEST_FILE) {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            collect_files(&path, skip, out)?;
+        } else if file_type.is_file() {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn relative_key(root: &Path, file: &Path) -> String {
+    file.strip_prefix(root)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn stamp(file: &Path) -> Result<FileStamp, String> {
+    let mut reader = File::open(file)
+        .map_err(|e| format!("Failed to open {}: {}", file.display(), e))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut reader, &mut hasher)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+
+    Ok(FileStamp { size, sha256: hex::encode(hasher.finalize()) })
+}
+
+fn set_readonly(file: &Path) -> Result<(), String> {
+    let mut perms = fs::metadata(file)
+        .map_err(|e| format!("Failed to get metadata: {}", e))?
+        .permissions();
+    perms.set_readonly(true);
+    fs::set_permissions(file, perms)
+        .map_err(|e| format!("Failed to set permissions on {}: {}", file.display(), e))
+}
+
+fn set_writable(file: &Path) -> Result<(), String> {
+    let mut perms = fs::metadata(file)
+        .map_err(|e| format!("Failed to get metadata: {}", e))?
+        .permissions();
+
+    // set_readonly(false) would make the file world-writable on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        perms.set_mode(perms.mode() | 0o200);
+    }
+    #[cfg(not(unix))]
+    {
+        perms.set_readonly(false);
+    }
+
+    fs::set_permissions(file, perms)
+        .map_err(|e| format!("Failed to set permissions on {}: {}", file.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_runtime(temp_dir: &TempDir) -> PathBuf {
+        let runtime = temp_dir.path().join("runtime");
+        fs::create_dir_all(runtime.join("php")).unwrap();
+        fs::write(runtime.join("caddy"), "caddy").unwrap();
+        fs::write(runtime.join("php").join("php-cgi"), "php").unwrap();
+        runtime
+    }
+
+    #[test]
+    fn test_harden_and_verify_clean() {
+        let temp_dir = TempDir::new().unwrap();
+        let runtime = create_runtime(&temp_dir);
+
+        assert_eq!(harden_dir(&runtime, &[]).unwrap(), 2);
+        assert!(fs::metadata(runtime.join("caddy")).unwrap().permissions().readonly());
+
+        let report = verify_dir(&runtime, &[], &[]).unwrap();
+        assert!(report.hardened);
+        assert!(report.is_clean());
+
+        relax_dir(&runtime).unwrap();
+        assert!(!fs::metadata(runtime.join("caddy")).unwrap().permissions().readonly());
+        assert!(!verify_dir(&runtime, &[], &[]).unwrap().hardened);
+    }
+
+    #[test]
+    fn test_verify_detects_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let runtime = create_runtime(&temp_dir);
+        let skipped = runtime.join("php").join("config.inc.php");
+        let fingerprints = crate::config::generated::fingerprints_path(&skipped).unwrap();
+        crate::config::generated::write_generated(&skipped, "<?php").unwrap();
+        harden_dir(&runtime, &[skipped.clone(), fingerprints.clone()]).unwrap();
+
+        set_writable(&runtime.join("caddy")).unwrap();
+        fs::write(runtime.join("caddy"), "tampered caddy").unwrap();
+        fs::write(runtime.join("extra"), "new").unwrap();
+        fs::write(&skipped, "<?php // edited").unwrap();
+        set_writable(&runtime.join("php").join("php-cgi")).unwrap();
+        fs::remove_file(runtime.join("php").join("php-cgi")).unwrap();
+
+        let report = verify_dir(&runtime, &[skipped.clone(), fingerprints], &[skipped]).unwrap();
+        assert_eq!(report.modified, vec!["caddy", "php/config.inc.php"]);
+        assert_eq!(report.writable, vec!["caddy"]);
+        assert_eq!(report.added, vec!["extra"]);
+        assert_eq!(report.missing, vec!["php/php-cgi"]);
+
+        relax_dir(&runtime).unwrap();
+    }
+}
@@ -1,6 +1,12 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::fs;
 
+use crate::config::AppSettings;
+use crate::process::logs::log_path;
+use crate::process::ServiceType;
+
 /// Runtime binary paths
 #[derive(Debug, Clone)]
 pub struct RuntimePaths {
@@ -13,6 +19,16 @@ pub struct RuntimePaths {
     pub pgsql_dir: PathBuf,
     /// Adminer directory (contains adminer.php)
     pub adminer: PathBuf,
+    /// cloudflared binary for public tunnels, optional
+    pub cloudflared: PathBuf,
+    /// Mailpit binary, optional
+    pub mailpit: PathBuf,
+    /// Node.js root directory (contains bin/ on macOS and Linux), optional
+    pub node_dir: PathBuf,
+    /// MinIO binary, optional
+    pub minio: PathBuf,
+    /// MinIO data and credentials, under the base directory
+    pub minio_dir: PathBuf,
     /// Directory where PHP extensions are located (same as php_cgi)
     pub php_ext_dir: PathBuf,
     /// Data directory for MySQL
@@ -46,6 +62,75 @@ pub struct AppDataPaths {
     pub projects_dir: PathBuf,
 }
 
+/// Every resolved path the UI links to, as strings
+#[derive(Debug, Clone, Serialize)]
+pub struct PathsReport {
+    pub base_dir: String,
+    pub runtime_dir: String,
+    pub config_dir: String,
+    pub logs_dir: String,
+    pub projects_dir: String,
+    pub mysql_data_dir: String,
+    pub pgsql_data_dir: String,
+    /// Config files by name, e.g. "Caddyfile"
+    pub configs: BTreeMap<String, String>,
+    /// Log file of each service
+    pub logs: HashMap<ServiceType, String>,
+    /// Service binaries, None until the runtime is downloaded
+    pub binaries: Option<BTreeMap<String, String>>,
+}
+
+impl PathsReport {
+    pub fn new(app_paths: &AppDataPaths, runtime: Option<&RuntimePaths>, settings: &AppSettings) -> Self {
+        let display = |path: &Path| path.to_string_lossy().to_string();
+
+        let mut configs = BTreeMap::new();
+        configs.insert("Caddyfile", app_paths.config_dir.join("Caddyfile"));
+        configs.insert("php.ini", runtime.map_or_else(|| app_paths.config_dir.join("php.ini"), |r| r.php_ini.clone()));
+        configs.insert("php-fpm.conf", app_paths.config_dir.join("php-fpm.conf"));
+        configs.insert("postgresql.conf", app_paths.pgsql_data_dir.join("postgresql.conf"));
+        configs.insert("pg_hba.conf", app_paths.pgsql_data_dir.join("pg_hba.conf"));
+        if let Some(settings_dir) = AppSettings::config_dir() {
+            configs.insert("settings.json", settings_dir.join("settings.json"));
+        }
+        if let Some(runtime) = runtime {
+            configs.insert("config.inc.php", runtime.phpmyadmin.join("config.inc.php"));
+        }
+
+        let logs = [ServiceType::Caddy, ServiceType::PhpFpm, ServiceType::MySQL, ServiceType::PostgreSQL]
+            .into_iter()
+            .map(|service| (service, display(&log_path(&app_paths.logs_dir, service))))
+            .collect();
+
+        let binaries = runtime.map(|r| {
+            [
+                ("caddy", &r.caddy),
+                ("php", &r.php_cgi),
+                ("mysqld", &r.mysql),
+                ("postgresql", &r.pgsql_dir),
+                ("phpmyadmin", &r.phpmyadmin),
+                ("adminer", &r.adminer),
+            ]
+            .into_iter()
+            .map(|(name, path)| (name.to_string(), display(path)))
+            .collect()
+        });
+
+        Self {
+            base_dir: display(&app_paths.base_dir),
+            runtime_dir: display(&app_paths.runtime_dir),
+            config_dir: display(&app_paths.config_dir),
+            logs_dir: display(&app_paths.logs_dir),
+            projects_dir: display(&settings.projects_dir(&app_paths.projects_dir)),
+            mysql_data_dir: display(&app_paths.mysql_data_dir),
+            pgsql_data_dir: display(&app_paths.pgsql_data_dir),
+            configs: configs.into_iter().map(|(name, path)| (name.to_string(), display(&path))).collect(),
+            logs,
+            binaries,
+        }
+    }
+}
+
 impl AppDataPaths {
     /// Create all necessary directories
     pub fn ensure_directories(&self) -> Result<(), String> {
@@ -61,6 +146,18 @@ impl AppDataPaths {
 
 /// Get the application data directory paths
 pub fn get_app_data_paths() -> Result<AppDataPaths, String> {
+    if let Some(data_dir) = crate::config::data_dir::data_dir_override() {
+        return Ok(AppDataPaths {
+            base_dir: data_dir.clone(),
+            runtime_dir: data_dir.join("runtime"),
+            config_dir: data_dir.join("config"),
+            mysql_data_dir: data_dir.join("mysql").join("data"),
+            pgsql_data_dir: data_dir.join("pgsql").join("data"),
+            logs_dir: data_dir.join("logs"),
+            projects_dir: data_dir.join("projects"),
+        });
+    }
+
     #[cfg(target_os = "windows")]
     {
         // On Windows, use the installation folder (where the exe is located)
@@ -103,9 +200,11 @@ pub fn get_app_data_paths() -> Result<AppDataPaths, String> {
 pub fn locate_runtime_binaries() -> Result<RuntimePaths, String> {
     let app_paths = get_app_data_paths()?;
     let runtime_dir = &app_paths.runtime_dir;
+    let settings = crate::config::AppSettings::load();
+    let overrides = &settings.binary_overrides;
 
-    // Ensure runtime directory exists
-    if !runtime_dir.exists() {
+    // Ensure runtime directory exists, unless the required binaries come from the system
+    if !runtime_dir.exists() && (overrides.caddy.is_none() || overrides.php.is_none()) {
         return Err(format!(
             "Runtime directory not found. Please download runtime binaries first. Expected: {}",
             runtime_dir.display()
@@ -133,6 +232,13 @@ pub fn locate_runtime_binaries() -> Result<RuntimePaths, String> {
             runtime_dir.join("adminer")
         });
 
+    // Detect Node.js directory (optional — nodejs.org archives extract to node-vVERSION-PLATFORM/)
+    let node_dir = detect_node_directory(runtime_dir)
+        .unwrap_or_else(|e| {
+            tracing::info!("Node.js not detected (optional): {}", e);
+            runtime_dir.join("node")
+        });
+
     // Detect MySQL/MariaDB (optional — user may not install a database)
     let mysql_path = detect_mysql_binary(runtime_dir)
         .unwrap_or_else(|e| {
@@ -140,21 +246,50 @@ pub fn locate_runtime_binaries() -> Result<RuntimePaths, String> {
             runtime_dir.join("mysql").join("bin").join("mysqld")
         });
 
-    Ok(RuntimePaths {
-        caddy: detect_caddy_binary(runtime_dir)?,
-        php_cgi: detect_php_binary(runtime_dir)?,
+    let mut paths = RuntimePaths {
+        caddy: match &overrides.caddy {
+            Some(caddy) => caddy.clone(),
+            None => detect_caddy_binary(runtime_dir)?,
+        },
+        php_cgi: match &overrides.php {
+            Some(php) => php.clone(),
+            None => detect_php_binary(runtime_dir)?,
+        },
         php_ini: detect_php_ini(runtime_dir)?,
         php_ext_dir: detect_php_ext_dir(runtime_dir)?,
         mysql: mysql_path,
         phpmyadmin: phpmyadmin_path,
         pgsql_dir,
         adminer: adminer_path,
+        cloudflared: runtime_dir.join("cloudflared").join(if cfg!(windows) { "cloudflared.exe" } else { "cloudflared" }),
+        mailpit: runtime_dir.join("mailpit").join(if cfg!(windows) { "mailpit.exe" } else { "mailpit" }),
+        node_dir,
+        minio: runtime_dir.join("minio").join(if cfg!(windows) { "minio.exe" } else { "minio" }),
+        minio_dir: crate::process::minio::minio_dir(&app_paths.base_dir),
         mysql_data_dir: app_paths.mysql_data_dir.clone(),
         pgsql_data_dir: app_paths.pgsql_data_dir.clone(),
         logs_dir: app_paths.logs_dir.clone(),
         config_dir: app_paths.config_dir.clone(),
-        projects_dir: app_paths.projects_dir.clone(),
-    })
+        projects_dir: settings.projects_dir(&app_paths.projects_dir),
+    };
+    overrides.apply(&mut paths);
+    Ok(paths)
+}
+
+/// PHP CLI next to the PHP binary the web server uses, for running scripts
+/// such as Composer
+pub fn php_cli_binary(paths: &RuntimePaths) -> Result<PathBuf, String> {
+    let dir = paths.php_cgi.parent().ok_or("Failed to get PHP binary directory")?;
+    #[cfg(target_os = "windows")]
+    let cli = dir.join("php.exe");
+    #[cfg(not(target_os = "windows"))]
+    let cli = dir.join("php");
+
+    if cli.is_file() {
+        Ok(cli)
+    } else {
+        Err(format!("PHP CLI not found in {}", dir.display()))
+    }
 }
 
 /// Detect Caddy binary based on platform
@@ -206,22 +341,17 @@ fn detect_php_binary(runtime_dir: &Path) -> Result<PathBuf, String> {
         // - runtime/php/php-cgi.exe (renamed/extracted)
         // - runtime/php-cgi.exe (direct in runtime dir)
 
-        // First, look for versioned PHP directories (like php-8.4.16-Win32-vs17-x64)
-        if let Ok(entries) = fs::read_dir(runtime_dir) {
-            for entry in entries.flatten() {
-                if let Ok(name) = entry.file_name().into_string() {
-                    if name.starts_with("php-") && name.contains("Win32") && entry.path().is_dir() {
-                        let php_cgi_path = entry.path().join("php-cgi.exe");
-                        if php_cgi_path.exists() {
-                            return Ok(php_cgi_path);
-                        }
-                        // Also check for php.exe as fallback
-                        let php_exe_path = entry.path().join("php.exe");
-                        if php_exe_path.exists() {
-                            return Ok(php_exe_path);
-                        }
-                    }
-                }
+        // First, look for versioned PHP directories (like php-8.4.16-Win32-vs17-x64),
+        // native builds before emulated ones
+        for dir in versioned_dirs(runtime_dir, |name| name.starts_with("php-") && name.contains("Win32")) {
+            let php_cgi_path = dir.join("php-cgi.exe");
+            if php_cgi_path.exists() {
+                return Ok(php_cgi_path);
+            }
+            // Also check for php.exe as fallback
+            let php_exe_path = dir.join("php.exe");
+            if php_exe_path.exists() {
+                return Ok(php_exe_path);
             }
         }
 
@@ -315,16 +445,10 @@ fn detect_php_ext_dir(runtime_dir: &Path) -> Result<PathBuf, String> {
     #[cfg(target_os = "windows")]
     {
         // First, look for versioned PHP directories (like php-8.4.16-Win32-vs17-x64)
-        if let Ok(entries) = fs::read_dir(runtime_dir) {
-            for entry in entries.flatten() {
-                if let Ok(name) = entry.file_name().into_string() {
-                    if name.starts_with("php-") && name.contains("Win32") && entry.path().is_dir() {
-                        let ext_path = entry.path().join("ext");
-                        if ext_path.exists() {
-                            return Ok(ext_path);
-                        }
-                    }
-                }
+        for dir in versioned_dirs(runtime_dir, |name| name.starts_with("php-") && name.contains("Win32")) {
+            let ext_path = dir.join("ext");
+            if ext_path.exists() {
+                return Ok(ext_path);
             }
         }
 
@@ -364,154 +488,119 @@ fn detect_php_ext_dir(runtime_dir: &Path) -> Result<PathBuf, String> {
     }
 }
 
-/// Detect MySQL/MariaDB server binary based on platform
+/// Detect the MySQL/MariaDB server binary
 ///
-/// **IMPORTANT Platform Differences:**
+/// Archives extract to versioned directories:
+/// - MariaDB: mariadb-XX.X.X-linux-systemd-x86_64/bin/mariadbd
+/// - MySQL: mysql-X.X.X-winx64/bin/mysqld.exe, mysql-X.X.X-macos14-arm64/bin/mysqld
 ///
-/// **Linux:**
-/// - Uses MariaDB 12.x (binary name: mariadbd)
-/// - Extracted from: mariadb-XX.X.X-linux-systemd-x86_64.tar.gz
-/// - Directory: mariadb-XX.X.X-linux-systemd-x86_64/bin/mariadbd
-///
-/// **Windows/macOS:**
-/// - Uses MySQL 8.x (binary name: mysqld)
-/// - Extracted from platform-specific archives
-/// - Directory: mysql-X.X.X/bin/mysqld
+/// Several series can be installed side by side, the directory matching the
+/// most recently installed version marker wins. Without markers the
+/// platform's default flavor (MariaDB on Linux, MySQL elsewhere) is preferred.
 fn detect_mysql_binary(runtime_dir: &Path) -> Result<PathBuf, String> {
-    #[cfg(target_os = "windows")]
-    {
-        // ============================================================
-        // WINDOWS: MySQL Binary Detection
-        // ============================================================
-        // Binary: mysqld.exe
-        // Source: https://dev.mysql.com/downloads/mysql/
-        // Archive: mysql-VERSION-winx64.zip
-        // Extracts to: mysql-VERSION/
-        // Binary path: .../bin/mysqld.exe
-        // ============================================================
-
-        // Look for any directory starting with "mysql"
-        if let Ok(entries) = fs::read_dir(runtime_dir) {
-            for entry in entries.flatten() {
-                if let Ok(name) = entry.file_name().into_string() {
-                    if name.starts_with("mysql") && entry.path().is_dir() {
-                        let mysqld_path = entry.path().join("bin").join("mysqld.exe");
-                        if mysqld_path.exists() {
-                            return Ok(mysqld_path);
-                        }
-                    }
-                }
-            }
-        }
-
-        // Fallback paths
-        let paths_to_check = vec![
-            runtime_dir.join("mysql").join("bin").join("mysqld.exe"),
-            runtime_dir.join("bin").join("mysqld.exe"),
-            runtime_dir.join("mysqld.exe"),
-        ];
-
-        for path in paths_to_check {
-            if path.exists() {
-                return Ok(path);
-            }
+    let exe = |name: &str| if cfg!(target_os = "windows") { format!("{}.exe", name) } else { name.to_string() };
+    // mariadbd since MariaDB 10.2, mysqld for MySQL and older MariaDB
+    let server_in = |dir: &Path| {
+        ["mariadbd", "mysqld"]
+            .iter()
+            .map(|name| dir.join(exe(name)))
+            .find(|path| path.exists())
+    };
+
+    let mut dirs: Vec<String> = fs::read_dir(runtime_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name.starts_with("mariadb") || name.starts_with("mysql"))
+                .collect()
+        })
+        .unwrap_or_default();
+    rank_database_dirs(&mut dirs, installed_database(runtime_dir).as_ref());
+    for name in &dirs {
+        if let Some(path) = server_in(&runtime_dir.join(name).join("bin")) {
+            return Ok(path);
         }
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        // ============================================================
-        // MACOS: MySQL Binary Detection
-        // ============================================================
-        // Binary: mysqld
-        // Source: https://dev.mysql.com/downloads/mysql/
-        // Archive: mysql-VERSION-macos14-x86_64.tar.gz
-        // Extracts to: mysql-VERSION/
-        // Binary path: .../bin/mysqld
-        // ============================================================
-
-        if let Ok(entries) = fs::read_dir(runtime_dir) {
-            for entry in entries.flatten() {
-                if let Ok(name) = entry.file_name().into_string() {
-                    if name.starts_with("mysql") && entry.path().is_dir() {
-                        let mysqld_path = entry.path().join("bin").join("mysqld");
-                        if mysqld_path.exists() {
-                            return Ok(mysqld_path);
-                        }
-                    }
-                }
-            }
+    // Fallback paths
+    for dir in [runtime_dir.join("bin"), runtime_dir.to_path_buf()] {
+        if let Some(path) = server_in(&dir) {
+            return Ok(path);
         }
+    }
 
-        let paths_to_check = vec![
-            runtime_dir.join("mysql").join("bin").join("mysqld"),
-            runtime_dir.join("mysql").join("bin").join("mysqld"),
-            runtime_dir.join("bin").join("mysqld"),
-            runtime_dir.join("mysqld"),
-        ];
+    Err(format!(
+        "MySQL/MariaDB binary not found in {}. Please ensure runtime binaries are downloaded.",
+        runtime_dir.display()
+    ))
+}
 
-        for path in paths_to_check {
-            if path.exists() {
-                return Ok(path);
-            }
-        }
-    }
+/// Flavor ("mariadb" or "mysql") and version of the most recently installed
+/// database server, from the downloader's marker files
+fn installed_database(runtime_dir: &Path) -> Option<(String, String)> {
+    ["mariadb", "mysql"]
+        .iter()
+        .filter_map(|flavor| {
+            let marker = runtime_dir.join(format!("{}_installed.txt", flavor));
+            let modified = fs::metadata(&marker).and_then(|m| m.modified()).ok()?;
+            let content = fs::read_to_string(&marker).ok()?;
+            let version = content.lines().find_map(|line| line.strip_prefix("version="))?.trim().to_string();
+            Some((modified, flavor.to_string(), version))
+        })
+        .max_by_key(|(modified, _, _)| *modified)
+        .map(|(_, flavor, version)| (flavor, version))
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        // ============================================================
-        // LINUX: MariaDB Binary Detection
-        // ============================================================
-        // Binary: mariadbd (MariaDB 10.2+)
-        // Source: https://archive.mariadb.org/
-        // Archive: mariadb-XX.X.X-linux-systemd-x86_64.tar.gz
-        // Extracts to: mariadb-XX.X.X-linux-systemd-x86_64/
-        // Binary path: .../bin/mariadbd
-        //
-        // Note: Older MariaDB versions (< 10.2) used mysqld,
-        // so we check for both as a fallback.
-        // ============================================================
-
-        // First, look for MariaDB directories
-        if let Ok(entries) = fs::read_dir(runtime_dir) {
-            for entry in entries.flatten() {
-                if let Ok(name) = entry.file_name().into_string() {
-                    // Check for MariaDB directories (mariadb-XX.X.X-*)
-                    if name.starts_with("mariadb") && entry.path().is_dir() {
-                        // Try mariadbd first (MariaDB 10.2+)
-                        let mariadbd_path = entry.path().join("bin").join("mariadbd");
-                        if mariadbd_path.exists() {
-                            return Ok(mariadbd_path);
-                        }
-                        // Fallback to mysqld for older MariaDB versions
-                        let mysqld_path = entry.path().join("bin").join("mysqld");
-                        if mysqld_path.exists() {
-                            return Ok(mysqld_path);
-                        }
-                    }
-                }
-            }
-        }
+/// Order database directories so the installed one comes first, then builds
+/// for this CPU, then the platform's default flavor, newest version first
+fn rank_database_dirs(dirs: &mut [String], installed: Option<&(String, String)>) {
+    let default_flavor = if cfg!(target_os = "linux") { "mariadb" } else { "mysql" };
+    dirs.sort_by(|a, b| b.cmp(a));
+    dirs.sort_by_key(|name| {
+        let is_installed = installed
+            .is_some_and(|(flavor, version)| name.starts_with(&format!("{}-{}", flavor, version)));
+        (!is_installed, arch_rank(name, std::env::consts::ARCH), !name.starts_with(default_flavor))
+    });
+}
 
-        // Fallback paths - check both mariadbd and mysqld
-        let paths_to_check = vec![
-            runtime_dir.join("mariadb").join("bin").join("mariadbd"),
-            runtime_dir.join("bin").join("mariadbd"),
-            runtime_dir.join("mariadbd"),
-            runtime_dir.join("mysqld"),  // Fallback for older versions
-        ];
+/// CPU architecture an archive directory was built for, when its name says
+fn dir_arch(name: &str) -> Option<&'static str> {
+    name.to_lowercase().split('-').find_map(|token| match token {
+        "aarch64" | "arm64" => Some("aarch64"),
+        "x86_64" | "amd64" | "x64" | "winx64" => Some("x86_64"),
+        "x86" | "i686" | "i386" => Some("x86"),
+        _ => None,
+    })
+}
 
-        for path in paths_to_check {
-            if path.exists() {
-                return Ok(path);
-            }
-        }
+/// Builds for `arch` first, then directories that do not name one, then
+/// builds that only run under emulation
+fn arch_rank(name: &str, arch: &str) -> u8 {
+    match dir_arch(name) {
+        Some(built_for) if built_for == arch => 0,
+        None => 1,
+        Some(_) => 2,
     }
+}
 
-    Err(format!(
-        "MariaDB binary not found in {}. Please ensure runtime binaries are downloaded.",
-        runtime_dir.display()
-    ))
+/// Subdirectories of `runtime_dir` whose name matches, ordered by `arch_rank`
+fn versioned_dirs(runtime_dir: &Path, matches: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let mut dirs: Vec<(u8, String, PathBuf)> = fs::read_dir(runtime_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| {
+                    let name = entry.file_name().into_string().ok()?;
+                    matches(&name).then(|| (arch_rank(&name, std::env::consts::ARCH), name, entry.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs.into_iter().map(|(_, _, path)| path).collect()
 }
 
 /// Detect phpMyAdmin directory (may be versioned like phpMyAdmin-5.2.2-all-languages)
@@ -540,30 +629,26 @@ fn detect_phpmyadmin_directory(runtime_dir: &Path) -> Result<PathBuf, String> {
     ))
 }
 
+/// Detect Node.js root directory (nodejs.org archives extract to node-vVERSION-PLATFORM/)
+fn detect_node_directory(runtime_dir: &Path) -> Result<PathBuf, String> {
+    let node = if cfg!(target_os = "windows") { "node.exe" } else { "bin/node" };
+    versioned_dirs(runtime_dir, |name| name.starts_with("node-v"))
+        .into_iter()
+        .find(|dir| dir.join(node).exists())
+        .ok_or_else(|| format!("Node.js directory not found in {}", runtime_dir.display()))
+}
+
 /// Detect PostgreSQL root directory (theseus-rs archives extract to postgresql-VERSION-TARGET/)
 fn detect_postgresql_directory(runtime_dir: &Path) -> Result<PathBuf, String> {
     // Look for versioned postgresql directories (e.g., postgresql-18.3.0-x86_64-pc-windows-msvc)
-    if let Ok(entries) = fs::read_dir(runtime_dir) {
-        for entry in entries.flatten() {
-            if let Ok(name) = entry.file_name().into_string() {
-                if name.starts_with("postgresql-") && entry.path().is_dir() {
-                    let bin_dir = entry.path().join("bin");
-                    if bin_dir.exists() {
-                        #[cfg(target_os = "windows")]
-                        {
-                            if bin_dir.join("pg_ctl.exe").exists() {
-                                return Ok(entry.path());
-                            }
-                        }
-                        #[cfg(not(target_os = "windows"))]
-                        {
-                            if bin_dir.join("pg_ctl").exists() {
-                                return Ok(entry.path());
-                            }
-                        }
-                    }
-                }
-            }
+    for dir in versioned_dirs(runtime_dir, |name| name.starts_with("postgresql-")) {
+        let bin_dir = dir.join("bin");
+        #[cfg(target_os = "windows")]
+        let pg_ctl = bin_dir.join("pg_ctl.exe");
+        #[cfg(not(target_os = "windows"))]
+        let pg_ctl = bin_dir.join("pg_ctl");
+        if pg_ctl.exists() {
+            return Ok(dir);
         }
     }
 
@@ -672,6 +757,32 @@ mod tests {
         fs::set_permissions(path, perms).unwrap();
     }
 
+    #[test]
+    fn test_arch_rank() {
+        assert_eq!(arch_rank("mysql-8.4.0-macos14-arm64", "aarch64"), 0);
+        assert_eq!(arch_rank("mysql-8.4.0-macos14-x86_64", "aarch64"), 2);
+        assert_eq!(arch_rank("php-8.5.1-Win32-vs17-x64", "x86_64"), 0);
+        assert_eq!(arch_rank("php-8.5.1-Win32-vs17-x86", "x86_64"), 2);
+        assert_eq!(arch_rank("postgresql-18.3.0-aarch64-unknown-linux-gnu", "aarch64"), 0);
+        assert_eq!(arch_rank("phpMyAdmin-5.2.2-all-languages", "aarch64"), 1);
+    }
+
+    #[test]
+    fn test_rank_database_dirs() {
+        let mut dirs = vec![
+            "mysql-8.4.0-linux-glibc2.28-x86_64".to_string(),
+            "mariadb-11.4.5-linux-systemd-x86_64".to_string(),
+            "mariadb-12.3.1-linux-systemd-x86_64".to_string(),
+        ];
+        let installed = ("mariadb".to_string(), "11.4.5".to_string());
+        rank_database_dirs(&mut dirs, Some(&installed));
+        assert_eq!(dirs[0], "mariadb-11.4.5-linux-systemd-x86_64");
+
+        let installed = ("mysql".to_string(), "8.4.0".to_string());
+        rank_database_dirs(&mut dirs, Some(&installed));
+        assert_eq!(dirs[0], "mysql-8.4.0-linux-glibc2.28-x86_64");
+    }
+
     #[test]
     fn test_app_data_paths_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -736,6 +847,11 @@ mod tests {
             phpmyadmin: temp_dir.path().join("phpmyadmin"),
             pgsql_dir: temp_dir.path().join("postgresql"),
             adminer: temp_dir.path().join("adminer"),
+            cloudflared: temp_dir.path().join("cloudflared").join("cloudflared"),
+            mailpit: temp_dir.path().join("mailpit").join("mailpit"),
+            node_dir: temp_dir.path().join("node"),
+            minio: temp_dir.path().join("minio").join("minio"),
+            minio_dir: temp_dir.path().join("minio-data"),
             mysql_data_dir: temp_dir.path().join("mysql").join("data"),
             pgsql_data_dir: temp_dir.path().join("pgsql").join("data"),
             logs_dir: temp_dir.path().join("logs"),
@@ -763,6 +879,11 @@ mod tests {
             phpmyadmin: temp_dir.path().join("phpmyadmin"),
             pgsql_dir: temp_dir.path().join("postgresql"),
             adminer: temp_dir.path().join("adminer"),
+            cloudflared: temp_dir.path().join("cloudflared").join("cloudflared"),
+            mailpit: temp_dir.path().join("mailpit").join("mailpit"),
+            node_dir: temp_dir.path().join("node"),
+            minio: temp_dir.path().join("minio").join("minio"),
+            minio_dir: temp_dir.path().join("minio-data"),
             mysql_data_dir: temp_dir.path().join("mysql").join("data"),
             pgsql_data_dir: temp_dir.path().join("pgsql").join("data"),
             logs_dir: temp_dir.path().join("logs"),
@@ -798,4 +919,26 @@ mod tests {
         assert_eq!(paths1.runtime_dir, paths2.runtime_dir);
         assert_eq!(paths1.config_dir, paths2.config_dir);
     }
+
+    #[test]
+    fn test_paths_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("campp");
+        let app_paths = AppDataPaths {
+            base_dir: base_dir.clone(),
+            runtime_dir: base_dir.join("runtime"),
+            config_dir: base_dir.join("config"),
+            mysql_data_dir: base_dir.join("mysql").join("data"),
+            pgsql_data_dir: base_dir.join("pgsql").join("data"),
+            logs_dir: base_dir.join("logs"),
+            projects_dir: base_dir.join("projects"),
+        };
+
+        let report = PathsReport::new(&app_paths, None, &AppSettings::default());
+        assert!(report.binaries.is_none());
+        assert_eq!(report.projects_dir, base_dir.join("projects").to_string_lossy());
+        assert_eq!(report.configs["Caddyfile"], base_dir.join("config").join("Caddyfile").to_string_lossy());
+        assert!(!report.configs.contains_key("config.inc.php"));
+        assert_eq!(report.logs[&ServiceType::MySQL], base_dir.join("logs").join("mysql.log").to_string_lossy());
+    }
 }
@@ -0,0 +1,28 @@
+//! Periodic housekeeping
+//!
+//! `run_maintenance` is called by the scheduler in lib.rs shortly after
+//! startup and then every `MAINTENANCE_INTERVAL_SECS`.
+
+use super::download_cache;
+use super::downloader::RuntimeDownloader;
+use crate::config::AppSettings;
+
+/// Interval between maintenance runs
+pub const MAINTENANCE_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// Run every maintenance task once
+pub fn run_maintenance() {
+    let settings = AppSettings::load();
+
+    let installed = RuntimeDownloader::new()
+        .map(|d| d.get_installed_components())
+        .unwrap_or_default();
+    let report = download_cache::cleanup_download_dir(&settings.download_cache, &installed);
+    if !report.removed.is_empty() {
+        tracing::info!(
+            "Removed {} cached downloads ({} MB)",
+            report.removed.len(),
+            report.freed_bytes / (1024 * 1024)
+        );
+    }
+}
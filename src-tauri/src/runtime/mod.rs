@@ -1,7 +1,19 @@
 pub mod deps;
+pub mod diagnostics;
+pub mod disk_usage;
+pub mod doctor;
+pub mod download_cache;
 pub mod downloader;
+pub mod hardening;
 pub mod locator;
+pub mod maintenance;
 pub mod packages;
+pub mod repair;
+pub mod signature;
+pub mod system;
+pub mod updates;
+pub mod upgrade;
+pub mod validate;
 
 // Re-exports
 pub use deps::{Dependency, DependencyCheckResult, InstallCommand};
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::sync::RwLock;
 
@@ -95,6 +96,10 @@ pub struct PackageSelection {
     pub postgresql: String,
     #[serde(default = "default_adminer")]
     pub adminer: String,
+    /// Database server to install, "mariadb" or "mysql". None installs the
+    /// platform default (MariaDB on Linux, MySQL elsewhere)
+    #[serde(default)]
+    pub db_flavor: Option<String>,
 }
 
 fn default_mariadb() -> String {
@@ -118,6 +123,7 @@ impl Default for PackageSelection {
             phpmyadmin: "phpmyadmin-5.2".to_string(),
             postgresql: "postgresql-18.3".to_string(),
             adminer: "adminer-5.1".to_string(),
+            db_flavor: None,
         }
     }
 }
@@ -148,6 +154,18 @@ pub struct BinariesConfig {
     #[serde(default)]
     #[serde(rename = "adminer")]
     pub adminer: Option<PhpMyAdminConfig>,
+    #[serde(default)]
+    #[serde(rename = "cloudflared")]
+    pub cloudflared: Option<BinaryConfig>,
+    #[serde(default)]
+    #[serde(rename = "mailpit")]
+    pub mailpit: Option<BinaryConfig>,
+    #[serde(default)]
+    #[serde(rename = "node")]
+    pub node: Option<BinaryConfig>,
+    #[serde(default)]
+    #[serde(rename = "minio")]
+    pub minio: Option<BinaryConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -173,6 +191,13 @@ pub struct VersionInfo {
     #[serde(default)]
     pub checksums: Checksums,
     pub urls: Urls,
+    #[serde(default)]
+    pub mirrors: Mirrors,
+    /// Archive sizes in bytes, keyed by platform (e.g. "linuxX64")
+    #[serde(default)]
+    pub sizes: HashMap<String, u64>,
+    #[serde(default)]
+    pub changelog_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -191,6 +216,21 @@ pub struct Checksums {
     pub macos_arm64: Option<String>,
 }
 
+impl Checksums {
+    /// Checksum for a platform key as returned by `Platform::url_key`
+    pub fn for_platform(&self, platform_key: &str) -> Option<String> {
+        match platform_key {
+            "windowsX64" => self.windows_x64.clone(),
+            "windowsArm64" => self.windows_arm64.clone(),
+            "linuxX64" => self.linux_x64.clone(),
+            "linuxArm64" => self.linux_arm64.clone(),
+            "macOSX64" => self.macos_x64.clone(),
+            "macOSArm64" => self.macos_arm64.clone(),
+            _ => None,
+        }
+    }
+}
+
 impl Default for Checksums {
     fn default() -> Self {
         Self {
@@ -217,6 +257,14 @@ pub struct VersionInfoSingleUrl {
     #[serde(default)]
     pub checksum: Option<String>,
     pub url: String,
+    /// Fallback URLs tried in order when `url` fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Archive size in bytes
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub changelog_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -235,6 +283,55 @@ pub struct Urls {
     pub macos_arm64: Option<String>,
 }
 
+impl Urls {
+    /// Download URL for a platform key as returned by `Platform::url_key`
+    pub fn for_platform(&self, platform_key: &str) -> Option<&str> {
+        let url = match platform_key {
+            "windowsX64" => &self.windows_x64,
+            "windowsArm64" => &self.windows_arm64,
+            "linuxX64" => &self.linux_x64,
+            "linuxArm64" => &self.linux_arm64,
+            "macOSX64" => &self.macos_x64,
+            "macOSArm64" => &self.macos_arm64,
+            _ => &None,
+        };
+        url.as_deref().filter(|u| !u.is_empty())
+    }
+}
+
+/// Fallback download URLs per platform, tried in order when the primary URL
+/// fails (GitHub mirror, archive.org, ...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Mirrors {
+    #[serde(rename = "windowsX64", default)]
+    pub windows_x64: Vec<String>,
+    #[serde(rename = "windowsArm64", default)]
+    pub windows_arm64: Vec<String>,
+    #[serde(rename = "linuxX64", default)]
+    pub linux_x64: Vec<String>,
+    #[serde(rename = "linuxArm64", default)]
+    pub linux_arm64: Vec<String>,
+    #[serde(rename = "macOSX64", default)]
+    pub macos_x64: Vec<String>,
+    #[serde(rename = "macOSArm64", default)]
+    pub macos_arm64: Vec<String>,
+}
+
+impl Mirrors {
+    /// Mirrors for a platform key as returned by `Platform::url_key`
+    pub fn for_platform(&self, platform_key: &str) -> Vec<String> {
+        match platform_key {
+            "windowsX64" => self.windows_x64.clone(),
+            "windowsArm64" => self.windows_arm64.clone(),
+            "linuxX64" => self.linux_x64.clone(),
+            "linuxArm64" => self.linux_arm64.clone(),
+            "macOSX64" => self.macos_x64.clone(),
+            "macOSArm64" => self.macos_arm64.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Global runtime config cache
 static RUNTIME_CONFIG: RwLock<Option<RuntimeConfig>> = RwLock::new(None);
 
@@ -460,12 +557,65 @@ pub fn get_selected_package_ids() -> PackageSelection {
             adminer: cfg.binaries.adminer.as_ref()
                 .and_then(|ac| ac.versions.iter().find(|v| v.selected).map(|v| v.id.clone()))
                 .unwrap_or_else(|| "adminer-5.1".to_string()),
+            db_flavor: None,
         }
     } else {
         PackageSelection::default()
     }
 }
 
+/// A version of a component that can be installed on this platform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableVersion {
+    pub id: String,
+    pub version: String,
+    pub display_name: String,
+    pub eol: bool,
+    pub lts: bool,
+    /// Selected by default in runtime-config.json
+    pub selected: bool,
+}
+
+/// The `versions` that have a download for one of `platform_keys`
+pub fn available_versions(versions: &[VersionInfo], platform_keys: &[String]) -> Vec<AvailableVersion> {
+    versions
+        .iter()
+        .filter(|v| platform_keys.iter().any(|key| v.urls.for_platform(key).is_some()))
+        .map(|v| AvailableVersion {
+            id: v.id.clone(),
+            version: v.version.clone(),
+            display_name: v.display_name.clone(),
+            eol: v.eol,
+            lts: v.lts,
+            selected: v.selected,
+        })
+        .collect()
+}
+
+/// MariaDB and MySQL versions that have a download for one of
+/// `platform_keys`, MariaDB first
+pub fn available_database_versions(config: &RuntimeConfig, platform_keys: &[String]) -> Vec<AvailableVersion> {
+    let mut versions = config.binaries.mariadb.as_ref()
+        .map(|c| available_versions(&c.versions, platform_keys))
+        .unwrap_or_default();
+    versions.extend(available_versions(&config.binaries.mysql.versions, platform_keys));
+    versions
+}
+
+/// Install the MariaDB or MySQL version `version_id` as the database server
+pub fn select_database(selection: &mut PackageSelection, version_id: &str) -> Result<(), String> {
+    if get_mariadb_package(version_id).is_some() {
+        selection.mariadb = version_id.to_string();
+        selection.db_flavor = Some("mariadb".to_string());
+    } else if get_mysql_package(version_id).is_some() {
+        selection.mysql = version_id.to_string();
+        selection.db_flavor = Some("mysql".to_string());
+    } else {
+        return Err(format!("Unknown database version: {}", version_id));
+    }
+    Ok(())
+}
+
 /// Get PHP package by ID
 pub fn get_php_package(id: &str) -> Option<PhpPackage> {
     get_available_packages()
@@ -520,6 +670,12 @@ pub fn reload_runtime_config() {
     *guard = load_runtime_config_from_file();
 }
 
+/// Use a runtime config fetched at runtime, e.g. a published manifest
+pub fn set_runtime_config(config: RuntimeConfig) {
+    let mut guard = RUNTIME_CONFIG.write().unwrap();
+    *guard = Some(config);
+}
+
 /// Load runtime config from an explicit resource directory path (e.g. Tauri's resource_dir)
 pub fn load_config_from_resource_dir(resource_dir: &std::path::Path) {
     let config_path = resource_dir.join("runtime-config.json");
@@ -583,7 +739,8 @@ fn get_default_packages() -> PackagesConfig {
                 version: "8.5.1".to_string(),
                 display_name: "PHP 8.5.1 (Latest)".to_string(),
                 windows_x64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/php-8.5.1/php-8.5.1-Win32-vs17-x64.zip".to_string(),
-                windows_arm64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/php-8.5.1/php-8.5.1-Win32-vs17-x86.zip".to_string(),
+                // x64 build under emulation, see Platform::emulated
+                windows_arm64: String::new(),
                 linux_x64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/php-8.5.1/php-8.4.18-fpm-linux-x86_64.tar.gz".to_string(),
                 linux_arm64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/php-8.5.1/php-8.4.18-fpm-linux-aarch64.tar.gz".to_string(),
                 macos_x64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/php-8.5.1/php-8.4.18-fpm-macos-x86_64.tar.gz".to_string(),
@@ -599,7 +756,8 @@ fn get_default_packages() -> PackagesConfig {
                 version: "8.4.0".to_string(),
                 display_name: "MySQL 8.4.0 LTS (Recommended)".to_string(),
                 windows_x64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/mysql-8.4.0/mysql-8.4.0-winx64.zip".to_string(),
-                windows_arm64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/mysql-8.4.0/mysql-8.4.0-winx64.zip".to_string(),
+                // x64 build under emulation, see Platform::emulated
+                windows_arm64: String::new(),
                 linux_x64: String::new(),
                 linux_arm64: String::new(),
                 macos_x64: "https://github.com/KarnYong/campp-runtime-binaries/releases/download/mysql-8.4.0/mysql-8.4.0-macos14-x86_64.tar.gz".to_string(),
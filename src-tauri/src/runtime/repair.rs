@@ -0,0 +1,160 @@
+//! Installation repair
+//!
+//! Brings an installation back to a consistent state without touching user
+//! data: re-locates binaries, recreates directories, regenerates configs that
+//! went missing and restores executable bits.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::hardening::{self, IntegrityReport};
+use super::locator::{get_app_data_paths, RuntimePaths};
+use crate::config::generator;
+use crate::process::manager::ProcessManager;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepairReport {
+    /// Integrity check result, when the runtime is hardened
+    pub integrity: Option<IntegrityReport>,
+    /// What was fixed
+    pub actions: Vec<String>,
+    /// Problems that could not be fixed automatically
+    pub errors: Vec<String>,
+}
+
+/// Repair the installation managed by `manager`
+pub fn repair_installation(manager: &mut ProcessManager) -> RepairReport {
+    let mut report = RepairReport::default();
+
+    if hardening::is_hardened() {
+        match hardening::verify_runtime() {
+            Ok(integrity) => {
+                if !integrity.is_clean() {
+                    report.errors.push(
+                        "Runtime files were modified since installation, reinstall the affected components".to_string(),
+                    );
+                }
+                report.integrity = Some(integrity);
+            }
+            Err(e) => report.errors.push(format!("Integrity check failed: {}", e)),
+        }
+    }
+
+    match get_app_data_paths().and_then(|p| p.ensure_directories()) {
+        Ok(()) => report.actions.push("Verified application directories".to_string()),
+        Err(e) => report.errors.push(e),
+    }
+
+    // initialize() re-locates binaries and recreates the runtime directories
+    if let Err(e) = manager.initialize() {
+        report.errors.push(format!("Failed to locate runtime binaries: {}", e));
+        return report;
+    }
+    report.actions.push("Re-located runtime binaries".to_string());
+
+    let Some(paths) = manager.get_runtime_paths() else {
+        return report;
+    };
+    let settings = crate::config::AppSettings::load();
+
+    regenerate_missing_configs(&paths, &settings, &mut report);
+
+    #[cfg(unix)]
+    fix_executable_permissions(&paths, &mut report);
+
+    report
+}
+
+fn regenerate_missing_configs(paths: &RuntimePaths, settings: &crate::config::AppSettings, report: &mut RepairReport) {
+    let mut regenerate = |file: &Path, result: &dyn Fn() -> Result<(), String>| {
+        if file.exists() {
+            return;
+        }
+        match result() {
+            Ok(()) => report.actions.push(format!("Regenerated {}", file.display())),
+            Err(e) => report.errors.push(format!("Failed to regenerate {}: {}", file.display(), e)),
+        }
+    };
+
+    let caddyfile = paths.config_dir.join("Caddyfile");
+    regenerate(&caddyfile, &|| {
+        generator::generate_caddyfile(&caddyfile, paths, settings.web_port, settings.php_port, settings, &crate::projects::registered())
+    });
+
+    regenerate(&paths.php_ini, &|| generator::generate_php_ini(&paths.php_ini, paths, settings));
+
+    let is_fpm = paths.php_cgi.file_name().is_some_and(|n| n == "php-fpm");
+    if is_fpm {
+        let fpm_conf = paths.config_dir.join("php-fpm.conf");
+        regenerate(&fpm_conf, &|| generator::generate_php_fpm_conf(&fpm_conf, paths, settings.php_port));
+    }
+
+    if paths.phpmyadmin.join("index.php").exists() {
+        regenerate(&paths.phpmyadmin.join("config.inc.php"), &|| {
+            generator::generate_phpmyadmin_config(paths, settings.mysql_port, &settings.mysql_root_password)
+        });
+    }
+
+    if paths.adminer.join("adminer.php").exists() {
+        regenerate(&paths.adminer.join("index.php"), &|| {
+            generator::generate_adminer_config(
+                paths, settings.mysql_port, &settings.mysql_root_password,
+                settings.postgres_port, &settings.postgres_root_password,
+            )
+        });
+    }
+
+    // Only an initialized cluster has a place for these
+    if paths.pgsql_data_dir.join("PG_VERSION").exists() {
+        regenerate(&paths.pgsql_data_dir.join("postgresql.conf"), &|| {
+            generator::generate_postgresql_conf(&paths.pgsql_data_dir, settings.postgres_port, &settings.listen_addresses())
+        });
+        let auth_method = if paths.pgsql_data_dir.join(".password_set").exists() {
+            "scram-sha-256"
+        } else {
+            "trust"
+        };
+        regenerate(&paths.pgsql_data_dir.join("pg_hba.conf"), &|| {
+            generator::generate_pg_hba_conf(&paths.pgsql_data_dir, auth_method, settings.is_lan_exposed())
+        });
+    }
+}
+
+/// Restore the executable bit on service binaries and their bin/ directories
+#[cfg(unix)]
+fn fix_executable_permissions(paths: &RuntimePaths, report: &mut RepairReport) {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    let mut candidates: Vec<PathBuf> = vec![paths.caddy.clone(), paths.php_cgi.clone()];
+    for bin_dir in [paths.mysql.parent().map(Path::to_path_buf), Some(paths.pgsql_dir.join("bin"))]
+        .into_iter()
+        .flatten()
+    {
+        if let Ok(entries) = fs::read_dir(&bin_dir) {
+            candidates.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_file()));
+        }
+    }
+
+    let mut fixed = 0;
+    for file in candidates {
+        let Ok(metadata) = fs::metadata(&file) else { continue };
+        if !metadata.is_file() {
+            continue;
+        }
+        let mut perms = metadata.permissions();
+        if perms.mode() & 0o111 == 0o111 {
+            continue;
+        }
+        perms.set_mode(perms.mode() | 0o111);
+        match fs::set_permissions(&file, perms) {
+            Ok(()) => fixed += 1,
+            Err(e) => report.errors.push(format!("Failed to make {} executable: {}", file.display(), e)),
+        }
+    }
+
+    if fixed > 0 {
+        report.actions.push(format!("Restored executable permission on {} files", fixed));
+    }
+}
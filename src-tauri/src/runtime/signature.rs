@@ -0,0 +1,110 @@
+//! Runtime manifest signatures
+//!
+//! Published manifests are signed with minisign and the signature is served
+//! next to the manifest as `runtime-config.json.minisig`. Manifests are signed
+//! with `minisign -S -l`: the legacy Ed25519 format signs the file itself,
+//! the default prehashed format needs BLAKE2b which is not available here.
+//! The trusted comment is covered by the second (global) signature, so it is
+//! checked as well.
+
+use base64::Engine;
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+/// Public key of the campp-runtime-binaries release manifests, in minisign
+/// format
+///
+/// Release builds set it from `CAMPP_MANIFEST_PUBLIC_KEY`, the public half of
+/// the key the manifests are signed with. Without it no published manifest
+/// verifies and fetching them is off unless unsigned manifests are allowed.
+pub const MANIFEST_PUBLIC_KEY: Option<&str> = option_env!("CAMPP_MANIFEST_PUBLIC_KEY");
+
+const KEY_ID_LEN: usize = 8;
+const PUBLIC_KEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+fn decode(data: &str, what: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Invalid {}: {}", what, e))
+}
+
+/// Check a minisign `signature` of `content` against a minisign `public_key`
+pub fn verify(content: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+    let key = decode(public_key, "public key")?;
+    if key.len() != 2 + KEY_ID_LEN + PUBLIC_KEY_LEN || &key[..2] != b"Ed" {
+        return Err("Invalid public key: not a minisign Ed25519 key".to_string());
+    }
+    let (key_id, key) = key[2..].split_at(KEY_ID_LEN);
+
+    let mut lines = signature.lines().map(str::trim).filter(|l| !l.is_empty());
+    let _untrusted = lines.next().filter(|l| l.starts_with("untrusted comment:"));
+    let sig = decode(lines.next().unwrap_or_default(), "signature")?;
+    let trusted = lines
+        .next()
+        .and_then(|l| l.strip_prefix("trusted comment: "))
+        .ok_or("Invalid signature: missing trusted comment")?;
+    let global_sig = decode(lines.next().unwrap_or_default(), "signature")?;
+
+    if sig.len() != 2 + KEY_ID_LEN + SIGNATURE_LEN || global_sig.len() != SIGNATURE_LEN {
+        return Err("Invalid signature: unexpected length".to_string());
+    }
+    match &sig[..2] {
+        b"Ed" => {}
+        b"ED" => return Err("Prehashed minisign signatures are not supported, sign with `minisign -S -l`".to_string()),
+        _ => return Err("Invalid signature: unknown algorithm".to_string()),
+    }
+    let (sig_key_id, sig) = sig[2..].split_at(KEY_ID_LEN);
+    if sig_key_id != key_id {
+        return Err("Signature was made with a different key".to_string());
+    }
+
+    let key = UnparsedPublicKey::new(&ED25519, key);
+    key.verify(content, sig).map_err(|_| "Signature does not match the manifest".to_string())?;
+    let mut signed_comment = sig.to_vec();
+    signed_comment.extend_from_slice(trusted.as_bytes());
+    key.verify(&signed_comment, &global_sig)
+        .map_err(|_| "Trusted comment signature does not match".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    const KEY_ID: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn keypair() -> (Ed25519KeyPair, String) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = [b"Ed".as_slice(), &KEY_ID, pair.public_key().as_ref()].concat();
+        (pair, base64::engine::general_purpose::STANDARD.encode(public_key))
+    }
+
+    fn sign(pair: &Ed25519KeyPair, content: &[u8], trusted: &str) -> String {
+        let encode = |data: &[u8]| base64::engine::general_purpose::STANDARD.encode(data);
+        let sig = pair.sign(content);
+        let global = pair.sign(&[sig.as_ref(), trusted.as_bytes()].concat());
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {}\n{}\n",
+            encode(&[b"Ed".as_slice(), &KEY_ID, sig.as_ref()].concat()),
+            trusted,
+            encode(global.as_ref())
+        )
+    }
+
+    #[test]
+    fn test_verify() {
+        let (pair, public_key) = keypair();
+        let manifest = br#"{"version": "1.0"}"#;
+        let signature = sign(&pair, manifest, "timestamp:1760000000\tfile:runtime-config.json");
+
+        assert!(verify(manifest, &signature, &public_key).is_ok());
+        assert!(verify(br#"{"version": "1.1"}"#, &signature, &public_key).is_err());
+        // A tampered trusted comment breaks the global signature
+        let tampered = signature.replace("timestamp:1760000000", "timestamp:1760000001");
+        assert!(verify(manifest, &tampered, &public_key).is_err());
+        // Signed with another key
+        let (_, other_key) = keypair();
+        assert!(verify(manifest, &signature, &other_key).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! System-installed binaries
+//!
+//! PHP, Caddy and MariaDB/MySQL may already be installed with Homebrew, the
+//! distribution's package manager or winget. Discovery looks in the usual
+//! install locations and on PATH, runs each candidate to read its version,
+//! and reports whether CAMPP can manage it. A chosen binary is stored as an
+//! override in settings and replaces the downloaded one in `RuntimePaths`;
+//! its component is then skipped by the installer.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::downloader::BinaryComponent;
+use super::locator::RuntimePaths;
+use super::updates::compare_versions;
+
+/// Binaries used instead of the downloaded ones, stored in settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinaryOverrides {
+    #[serde(default)]
+    pub caddy: Option<PathBuf>,
+    /// php-cgi on Windows, php-fpm elsewhere
+    #[serde(default)]
+    pub php: Option<PathBuf>,
+    /// mariadbd or mysqld
+    #[serde(default)]
+    pub mysql: Option<PathBuf>,
+}
+
+impl BinaryOverrides {
+    /// Override of `component`, MySQL and MariaDB share one
+    pub fn get(&self, component: BinaryComponent) -> Option<&PathBuf> {
+        match component {
+            BinaryComponent::Caddy => self.caddy.as_ref(),
+            BinaryComponent::Php => self.php.as_ref(),
+            BinaryComponent::MySQL | BinaryComponent::MariaDB => self.mysql.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, component: BinaryComponent, path: Option<PathBuf>) -> Result<(), String> {
+        let field = match component {
+            BinaryComponent::Caddy => &mut self.caddy,
+            BinaryComponent::Php => &mut self.php,
+            BinaryComponent::MySQL | BinaryComponent::MariaDB => &mut self.mysql,
+            _ => return Err(format!("{} cannot use a system binary", component.name())),
+        };
+        *field = path;
+        Ok(())
+    }
+
+    /// Point `paths` at the overridden binaries
+    pub fn apply(&self, paths: &mut RuntimePaths) {
+        if let Some(caddy) = &self.caddy {
+            paths.caddy = caddy.clone();
+        }
+        if let Some(php) = &self.php {
+            paths.php_cgi = php.clone();
+            // The bundled extension directory does not match a system PHP
+            match php_extension_dir(php) {
+                Some(dir) => paths.php_ext_dir = dir,
+                None => tracing::warn!("Could not read the extension directory of {}", php.display()),
+            }
+        }
+        if let Some(mysql) = &self.mysql {
+            paths.mysql = mysql.clone();
+        }
+    }
+}
+
+/// A binary found on the system
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SystemBinary {
+    pub component: BinaryComponent,
+    pub path: PathBuf,
+    pub version: String,
+    /// Why CAMPP cannot use it, None when it can
+    pub incompatible: Option<String>,
+}
+
+/// Find every Caddy, PHP and MariaDB/MySQL binary installed on the system
+pub fn detect_system_binaries() -> Vec<SystemBinary> {
+    let mut found = Vec::new();
+    for (component, names) in [
+        (BinaryComponent::Caddy, &["caddy"][..]),
+        (BinaryComponent::Php, php_names()),
+        (BinaryComponent::MariaDB, &["mariadbd", "mysqld"][..]),
+    ] {
+        for path in candidates(names) {
+            match inspect(component, &path) {
+                Ok(binary) if !found.contains(&binary) => found.push(binary),
+                Ok(_) => {}
+                Err(e) => tracing::debug!("Skipping {}: {}", path.display(), e),
+            }
+        }
+    }
+    found
+}
+
+/// Run the binary at `path` and check that CAMPP can manage it as `component`
+pub fn inspect(component: BinaryComponent, path: &Path) -> Result<SystemBinary, String> {
+    let args: &[&str] = match component {
+        BinaryComponent::Caddy => &["version"],
+        BinaryComponent::Php => &["-v"],
+        BinaryComponent::MySQL | BinaryComponent::MariaDB => &["--version"],
+        _ => return Err(format!("{} cannot use a system binary", component.name())),
+    };
+    let output = super::validate::run_binary(component.name(), path, args)?;
+    let version = super::validate::parse_version(&output)
+        .ok_or_else(|| format!("{} did not report a version", path.display()))?;
+
+    // mysqld is MariaDB on many distributions, the version string tells
+    let component = match component {
+        BinaryComponent::MySQL | BinaryComponent::MariaDB if output.contains("MariaDB") => BinaryComponent::MariaDB,
+        BinaryComponent::MySQL | BinaryComponent::MariaDB => BinaryComponent::MySQL,
+        other => other,
+    };
+    Ok(SystemBinary {
+        component,
+        path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+        incompatible: check_compatible(component, &version).err(),
+        version,
+    })
+}
+
+/// Oldest versions CAMPP generates configs for
+fn check_compatible(component: BinaryComponent, version: &str) -> Result<(), String> {
+    let minimum = match component {
+        BinaryComponent::Caddy => "2.0",
+        BinaryComponent::Php => "7.4",
+        BinaryComponent::MariaDB => "10.4",
+        BinaryComponent::MySQL => "8.0",
+        _ => return Ok(()),
+    };
+    if compare_versions(version, minimum).is_lt() {
+        return Err(format!("{} {} is too old, CAMPP needs {} or newer", component.name(), version, minimum));
+    }
+    Ok(())
+}
+
+/// Extension directory the PHP at `php` was built with, from its `-i`
+/// output or from `php-config`
+pub fn php_extension_dir(php: &Path) -> Option<PathBuf> {
+    let from_info = super::validate::run_binary("PHP", php, &["-i"]).ok().and_then(|info| parse_extension_dir(&info));
+    from_info.or_else(|| {
+        candidates(&["php-config"]).into_iter().find_map(|config| {
+            let output = super::validate::run_binary("php-config", &config, &["--extension-dir"]).ok()?;
+            let dir = output.trim();
+            (!dir.is_empty()).then(|| PathBuf::from(dir))
+        })
+    })
+}
+
+/// `extension_dir` in phpinfo output
+fn parse_extension_dir(info: &str) -> Option<PathBuf> {
+    phpinfo_value(info, "extension_dir").map(PathBuf::from)
+}
+
+/// Local value of the `name` directive in phpinfo output, plain text from
+/// php/php-fpm or HTML from php-cgi. None when it has no value
+pub(crate) fn phpinfo_value(info: &str, name: &str) -> Option<String> {
+    let cell = format!(">{}</td><td class=\"v\">", name);
+    let value = if let Some(start) = info.find(&cell) {
+        info[start + cell.len()..].split('<').next()?
+    } else {
+        let prefix = format!("{} =>", name);
+        let line = info.lines().find(|line| line.trim_start().starts_with(&prefix))?;
+        line.split(" => ").nth(1)?
+    };
+    let value = value.trim();
+    (!value.is_empty() && value != "no value").then(|| value.to_string())
+}
+
+/// PHP binary names, the FastCGI server CAMPP runs on this platform
+fn php_names() -> &'static [&'static str] {
+    if cfg!(target_os = "windows") {
+        &["php-cgi"]
+    } else {
+        // Debian and Ubuntu version the name, e.g. php-fpm8.3
+        &["php-fpm", "php-fpm8.5", "php-fpm8.4", "php-fpm8.3", "php-fpm8.2", "php-fpm8.1", "php-fpm7.4"]
+    }
+}
+
+/// Existing files named like one of `names` in PATH and the usual install locations
+pub(crate) fn candidates(names: &[&str]) -> Vec<PathBuf> {
+    let mut search: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    search.extend(install_dirs());
+
+    let mut found = Vec::new();
+    for dir in search {
+        for name in names {
+            let path = dir.join(if cfg!(target_os = "windows") { format!("{}.exe", name) } else { name.to_string() });
+            if path.is_file() && !found.contains(&path) {
+                found.push(path);
+            }
+        }
+    }
+    found
+}
+
+/// Where package managers put server binaries that are not always on PATH
+fn install_dirs() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut found = Vec::new();
+        if let Some(local) = dirs::data_local_dir() {
+            found.push(local.join("Microsoft").join("WinGet").join("Links"));
+        }
+        found.push(PathBuf::from(r"C:\php"));
+        found.push(PathBuf::from(r"C:\ProgramData\chocolatey\bin"));
+        // MariaDB and MySQL installers use versioned directories
+        for (root, prefix) in [(r"C:\Program Files", "MariaDB"), (r"C:\Program Files\MySQL", "MySQL Server")] {
+            if let Ok(entries) = std::fs::read_dir(root) {
+                for entry in entries.flatten() {
+                    if entry.file_name().to_string_lossy().starts_with(prefix) {
+                        found.push(entry.path().join("bin"));
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        [
+            "/opt/homebrew/bin",
+            "/opt/homebrew/sbin",
+            "/usr/local/bin",
+            "/usr/local/sbin",
+            "/usr/bin",
+            "/usr/sbin",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_compatible() {
+        assert!(check_compatible(BinaryComponent::Php, "8.3.6").is_ok());
+        assert!(check_compatible(BinaryComponent::Php, "7.2.34").is_err());
+        assert!(check_compatible(BinaryComponent::MariaDB, "10.11.6").is_ok());
+        assert!(check_compatible(BinaryComponent::MySQL, "5.7.44").is_err());
+    }
+
+    #[test]
+    fn test_parse_extension_dir() {
+        let text = "PHP Version => 8.3.6\n\nextension_dir => /usr/lib/php/20230831 => /usr/lib/php/20230831\n";
+        assert_eq!(parse_extension_dir(text), Some(PathBuf::from("/usr/lib/php/20230831")));
+        let html = r#"<tr><td class="e">extension_dir</td><td class="v">C:/php/ext</td><td class="v">C:/php/ext</td></tr>"#;
+        assert_eq!(parse_extension_dir(html), Some(PathBuf::from("C:/php/ext")));
+        assert_eq!(parse_extension_dir("extension_dir => no value => no value"), None);
+    }
+}
@@ -0,0 +1,262 @@
+//! Runtime update checker
+//!
+//! Runtime manifests use the runtime-config.json format and are published as
+//! release assets, one release per manifest version. The latest manifest is
+//! compared with the installed component versions to offer upgrades, and a
+//! specific one can be installed instead of the bundled config. Every fetched
+//! manifest must carry a valid signature (see `signature`).
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::packages::{RuntimeConfig, VersionInfo, VersionInfoSingleUrl};
+
+const MANIFEST_RELEASES_URL: &str = "https://github.com/KarnYong/campp-runtime-binaries/releases";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An installed component with a newer version in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuntimeUpdate {
+    /// Component name, e.g. "php"
+    pub component: String,
+    pub installed_version: String,
+    /// Version id in the manifest, e.g. "php-8.5"
+    pub version_id: String,
+    pub available_version: String,
+    pub display_name: String,
+    /// Archive size in bytes for this platform, when the manifest lists it
+    pub size: Option<u64>,
+    pub changelog_url: Option<String>,
+}
+
+/// URL of a published manifest, the latest one when `version` is None
+pub fn manifest_url(version: Option<&str>) -> String {
+    match version {
+        Some(version) => format!("{}/download/manifest-{}/runtime-config.json", MANIFEST_RELEASES_URL, version),
+        None => format!("{}/latest/download/runtime-config.json", MANIFEST_RELEASES_URL),
+    }
+}
+
+/// Download and parse a published manifest
+pub async fn fetch_manifest(version: Option<&str>) -> Result<RuntimeConfig, String> {
+    let url = manifest_url(version);
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch runtime manifest: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP error {}: Failed to fetch runtime manifest\nURL: {}", response.status().as_u16(), url));
+    }
+    let content = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read runtime manifest: {}", e))?;
+
+    // The manifest decides what gets downloaded, check it before trusting any URL or checksum
+    let verified = match super::signature::MANIFEST_PUBLIC_KEY {
+        Some(public_key) => match fetch_signature(&client, &url).await {
+            Ok(signature) => super::signature::verify(&content, &signature, public_key),
+            Err(e) => Err(e),
+        },
+        None => Err("this build has no manifest signing key".to_string()),
+    };
+    if let Err(e) = verified {
+        if !crate::config::AppSettings::load().allow_unsigned_manifests {
+            return Err(format!(
+                "Runtime manifest {} failed signature verification: {}\n\
+                 Allow unsigned manifests in settings to use it anyway.",
+                url, e
+            ));
+        }
+        tracing::warn!("Using unverified runtime manifest {}: {}", url, e);
+    }
+
+    serde_json::from_slice(&content).map_err(|e| format!("Invalid runtime manifest {}: {}", url, e))
+}
+
+/// Download the minisign signature published next to the manifest at `url`
+async fn fetch_signature(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let url = format!("{}.minisig", url);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch manifest signature: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP error {}: Failed to fetch manifest signature\nURL: {}", response.status().as_u16(), url));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read manifest signature: {}", e))
+}
+
+/// Upgrades for the `installed` components (name to version, as returned by
+/// `RuntimeDownloader::get_installed_components`)
+///
+/// The newest release on the installed major.minor line wins, so PHP 7.4
+/// users are offered 7.4 patches rather than a jump to PHP 8. Components
+/// without such a line fall back to the manifest's selected version.
+pub fn available_updates(config: &RuntimeConfig, installed: &HashMap<String, String>, platform_key: &str) -> Vec<RuntimeUpdate> {
+    let mut updates = Vec::new();
+    for (component, installed_version) in installed {
+        let candidates = candidates(config, component, platform_key);
+        let same_line = candidates
+            .iter()
+            .filter(|c| release_line(&c.available_version) == release_line(installed_version))
+            .max_by(|a, b| compare_versions(&a.available_version, &b.available_version));
+        let best = same_line.or_else(|| candidates.iter().find(|c| c.selected));
+
+        if let Some(best) = best {
+            if compare_versions(&best.available_version, installed_version).is_gt() {
+                updates.push(RuntimeUpdate {
+                    component: component.clone(),
+                    installed_version: installed_version.clone(),
+                    version_id: best.version_id.clone(),
+                    available_version: best.available_version.clone(),
+                    display_name: best.display_name.clone(),
+                    size: best.size,
+                    changelog_url: best.changelog_url.clone(),
+                });
+            }
+        }
+    }
+    updates.sort_by(|a, b| a.component.cmp(&b.component));
+    updates
+}
+
+struct Candidate {
+    version_id: String,
+    available_version: String,
+    display_name: String,
+    size: Option<u64>,
+    changelog_url: Option<String>,
+    selected: bool,
+}
+
+/// Versions of a component that can be downloaded on this platform
+fn candidates(config: &RuntimeConfig, component: &str, platform_key: &str) -> Vec<Candidate> {
+    let binaries = &config.binaries;
+    let multi = |versions: &[VersionInfo]| -> Vec<Candidate> {
+        versions
+            .iter()
+            .filter(|v| v.urls.for_platform(platform_key).is_some())
+            .map(|v| Candidate {
+                version_id: v.id.clone(),
+                available_version: v.version.clone(),
+                display_name: v.display_name.clone(),
+                size: v.sizes.get(platform_key).copied(),
+                changelog_url: v.changelog_url.clone(),
+                selected: v.selected,
+            })
+            .collect()
+    };
+    let single = |versions: &[VersionInfoSingleUrl]| -> Vec<Candidate> {
+        versions
+            .iter()
+            .map(|v| Candidate {
+                version_id: v.id.clone(),
+                available_version: v.version.clone(),
+                display_name: v.display_name.clone(),
+                size: v.size,
+                changelog_url: v.changelog_url.clone(),
+                selected: v.selected,
+            })
+            .collect()
+    };
+
+    match component {
+        "caddy" => multi(&binaries.caddy.versions),
+        "php" => multi(&binaries.php.versions),
+        "mysql" => multi(&binaries.mysql.versions),
+        "mariadb" => binaries.mariadb.as_ref().map(|c| multi(&c.versions)).unwrap_or_default(),
+        "postgresql" => binaries.postgresql.as_ref().map(|c| multi(&c.versions)).unwrap_or_default(),
+        "phpmyadmin" => single(&binaries.phpmyadmin.versions),
+        "adminer" => binaries.adminer.as_ref().map(|c| single(&c.versions)).unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// "8.4.18" -> [8, 4]
+fn release_line(version: &str) -> Vec<u64> {
+    version_parts(version).into_iter().take(2).collect()
+}
+
+/// Numeric comparison of dotted versions, "8.4.10" is newer than "8.4.9"
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    version_parts(a).cmp(&version_parts(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RuntimeConfig {
+        serde_json::from_value(serde_json::json!({
+            "version": "1.1",
+            "binaries": {
+                "caddy": { "versions": [
+                    { "id": "caddy-2.11", "version": "2.11.4", "selected": true, "display_name": "Caddy 2.11.4",
+                      "urls": { "linuxX64": "https://example.com/caddy.tar.gz" }, "sizes": { "linuxX64": 1234 } }
+                ]},
+                "php": { "versions": [
+                    { "id": "php-8.5", "version": "8.5.2", "selected": true, "display_name": "PHP 8.5.2",
+                      "urls": { "linuxX64": "https://example.com/php85.tar.gz" },
+                      "changelog_url": "https://www.php.net/ChangeLog-8.php#8.5.2" },
+                    { "id": "php-7.4", "version": "7.4.33", "selected": false, "display_name": "PHP 7.4.33",
+                      "urls": { "windowsX64": "https://example.com/php74.zip" } }
+                ]},
+                "mysql": { "versions": [] },
+                "phpmyadmin": { "versions": [
+                    { "id": "phpmyadmin-5.2", "version": "5.2.2", "selected": true, "display_name": "phpMyAdmin 5.2.2",
+                      "url": "https://example.com/pma.zip" }
+                ]}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_available_updates() {
+        let installed: HashMap<String, String> = [
+            ("caddy", "2.11.3"),
+            ("php", "8.5.1"),
+            ("phpmyadmin", "5.2.2"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let updates = available_updates(&config(), &installed, "linuxX64");
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].component, "caddy");
+        assert_eq!(updates[0].size, Some(1234));
+        assert_eq!(updates[1].available_version, "8.5.2");
+        assert!(updates[1].changelog_url.is_some());
+
+        // PHP 7.4 is not published for Linux, the selected line is offered instead
+        let installed = HashMap::from([("php".to_string(), "7.4.30".to_string())]);
+        let updates = available_updates(&config(), &installed, "linuxX64");
+        assert_eq!(updates[0].version_id, "php-8.5");
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert!(compare_versions("8.4.10", "8.4.9").is_gt());
+        assert!(compare_versions("12.3.1", "12.3.1").is_eq());
+        assert!(compare_versions("2.11", "2.11.3").is_lt());
+    }
+}
@@ -0,0 +1,315 @@
+//! In-place component upgrades
+//!
+//! The new version is unpacked into a staging directory next to the runtime,
+//! then the component's entries are swapped for it with renames. Data
+//! directories and generated configs live outside `runtime/` and are left
+//! alone; configs pointing at versioned paths are regenerated on next start.
+//! phpMyAdmin keeps its config and tmp directory inside its own directory,
+//! those are carried over to the new version before the swap.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::downloader::BinaryComponent;
+use super::packages::{self, PackageSelection, RuntimeConfig, VersionInfoSingleUrl};
+use crate::util::copy_dir;
+
+/// Staging directory the new version is unpacked into
+pub fn staging_dir(runtime_dir: &Path, component: BinaryComponent) -> PathBuf {
+    runtime_dir.join(format!(".upgrade-{}", component.binary_name()))
+}
+
+/// Point `selection` at `version_id` for `component`
+///
+/// Caddy, cloudflared, Mailpit, Node.js and MinIO are not part of the
+/// package selection, they can only move to the version selected in
+/// runtime-config.json.
+pub fn select_version(selection: &mut PackageSelection, component: BinaryComponent, version_id: &str) -> Result<(), String> {
+    let known = match component {
+        BinaryComponent::Caddy => packages::get_config()
+            .and_then(|c| c.binaries.caddy.versions.into_iter().find(|v| v.selected))
+            .is_some_and(|v| v.id == version_id),
+        BinaryComponent::Cloudflared => packages::get_config()
+            .and_then(|c| c.binaries.cloudflared?.versions.into_iter().find(|v| v.selected))
+            .is_some_and(|v| v.id == version_id),
+        BinaryComponent::Mailpit => packages::get_config()
+            .and_then(|c| c.binaries.mailpit?.versions.into_iter().find(|v| v.selected))
+            .is_some_and(|v| v.id == version_id),
+        BinaryComponent::Node => packages::get_config()
+            .and_then(|c| c.binaries.node?.versions.into_iter().find(|v| v.selected))
+            .is_some_and(|v| v.id == version_id),
+        BinaryComponent::Minio => packages::get_config()
+            .and_then(|c| c.binaries.minio?.versions.into_iter().find(|v| v.selected))
+            .is_some_and(|v| v.id == version_id),
+        BinaryComponent::Php => packages::get_php_package(version_id).is_some(),
+        BinaryComponent::MySQL => packages::get_mysql_package(version_id).is_some(),
+        BinaryComponent::MariaDB => packages::get_mariadb_package(version_id).is_some(),
+        BinaryComponent::PhpMyAdmin => packages::get_phpmyadmin_package(version_id).is_some(),
+        BinaryComponent::PostgreSQL => packages::get_postgresql_package(version_id).is_some(),
+        BinaryComponent::Adminer => packages::get_adminer_package(version_id).is_some(),
+    };
+    if !known {
+        return Err(format!("Version {} is not available for {}", version_id, component.name()));
+    }
+
+    let field = match component {
+        BinaryComponent::Caddy | BinaryComponent::Cloudflared | BinaryComponent::Mailpit | BinaryComponent::Node | BinaryComponent::Minio => return Ok(()),
+        BinaryComponent::Php => &mut selection.php,
+        BinaryComponent::MySQL => &mut selection.mysql,
+        BinaryComponent::MariaDB => &mut selection.mariadb,
+        BinaryComponent::PhpMyAdmin => &mut selection.phpmyadmin,
+        BinaryComponent::PostgreSQL => &mut selection.postgresql,
+        BinaryComponent::Adminer => &mut selection.adminer,
+    };
+    *field = version_id.to_string();
+    Ok(())
+}
+
+/// PostgreSQL data directories only open with the major version that created them
+pub fn check_data_compatible(component: BinaryComponent, installed: &str, target: &str) -> Result<(), String> {
+    let major = |v: &str| v.split('.').next().unwrap_or_default().to_string();
+    if component == BinaryComponent::PostgreSQL && major(installed) != major(target) {
+        return Err(format!(
+            "Upgrading PostgreSQL from {} to {} needs pg_upgrade. Back up your databases, \
+             uninstall PostgreSQL and install the new version instead.",
+            installed, target
+        ));
+    }
+    Ok(())
+}
+
+/// Newest phpMyAdmin release listed in `config`
+pub fn latest_phpmyadmin(config: &RuntimeConfig) -> Option<&VersionInfoSingleUrl> {
+    config
+        .binaries
+        .phpmyadmin
+        .versions
+        .iter()
+        .filter(|v| !v.url.is_empty())
+        .max_by(|a, b| super::updates::compare_versions(&a.version, &b.version))
+}
+
+/// Copy `config.inc.php` and the tmp directory of the phpMyAdmin install at
+/// `current` into the version unpacked in `staging`
+///
+/// The twig template cache is left behind, it belongs to the old templates.
+pub fn migrate_phpmyadmin_files(current: &Path, staging: &Path) -> Result<(), String> {
+    let target = fs::read_dir(staging)
+        .map_err(|e| format!("Failed to read {}: {}", staging.display(), e))?
+        .flatten()
+        .find(|entry| entry.path().is_dir() && entry.file_name().to_string_lossy().to_lowercase().starts_with("phpmyadmin"))
+        .map(|entry| entry.path())
+        .ok_or("The phpMyAdmin archive does not contain a phpMyAdmin directory")?;
+
+    let config = current.join("config.inc.php");
+    if config.exists() {
+        fs::copy(&config, target.join("config.inc.php"))
+            .map_err(|e| format!("Failed to copy {}: {}", config.display(), e))?;
+    }
+    let tmp = current.join("tmp");
+    if tmp.is_dir() {
+        copy_dir(&tmp, &target.join("tmp"), &["twig"])?;
+    }
+    Ok(())
+}
+
+/// Files and directories in `runtime_dir` that belong to `component`
+pub(crate) fn component_entries(runtime_dir: &Path, component: BinaryComponent) -> Vec<PathBuf> {
+    let owned = |name: &str, is_dir: bool| -> bool {
+        let lower = name.to_lowercase();
+        let stem = lower.strip_suffix(".exe").unwrap_or(&lower);
+        match component {
+            BinaryComponent::Caddy => stem == "caddy",
+            BinaryComponent::Cloudflared => stem == "cloudflared",
+            BinaryComponent::Mailpit => stem == "mailpit",
+            BinaryComponent::Node => is_dir && lower.starts_with("node-v"),
+            BinaryComponent::Minio => stem == "minio",
+            BinaryComponent::Php => {
+                matches!(stem, "php" | "php-fpm" | "php-cgi" | "buildroot") || (is_dir && lower.starts_with("php-"))
+            }
+            BinaryComponent::MySQL | BinaryComponent::MariaDB => {
+                matches!(stem, "mysql" | "mariadb" | "mysqld" | "mariadbd")
+                    || (is_dir && (lower.starts_with("mysql-") || lower.starts_with("mariadb-")))
+            }
+            BinaryComponent::PhpMyAdmin => is_dir && lower.starts_with("phpmyadmin"),
+            BinaryComponent::PostgreSQL => is_dir && (lower == "postgresql" || lower.starts_with("postgresql-")),
+            BinaryComponent::Adminer => matches!(lower.as_str(), "adminer" | "adminer.php" | "adminer-mysql.php"),
+        }
+    };
+
+    let Ok(entries) = fs::read_dir(runtime_dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Staging/backup dirs and marker files are not part of the install
+            !name.starts_with('.') && !name.ends_with("_installed.txt") && owned(&name, entry.path().is_dir())
+        })
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn previous_dir(runtime_dir: &Path, component: BinaryComponent) -> PathBuf {
+    runtime_dir.join(format!(".previous-{}", component.binary_name()))
+}
+
+/// Replace the installed entries of `component` with the contents of `staging`
+///
+/// The old entries are moved aside first and moved back if anything fails,
+/// so the component is never left half replaced. They stay in
+/// `.previous-<component>` until [`finish_swap`] or [`restore_previous`].
+pub fn swap_component(runtime_dir: &Path, component: BinaryComponent, staging: &Path) -> Result<(), String> {
+    let backup = previous_dir(runtime_dir, component);
+    if backup.exists() {
+        fs::remove_dir_all(&backup).map_err(|e| format!("Failed to remove {}: {}", backup.display(), e))?;
+    }
+    fs::create_dir_all(&backup).map_err(|e| format!("Failed to create {}: {}", backup.display(), e))?;
+
+    let mut moved_out: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut moved_in: Vec<PathBuf> = Vec::new();
+    let rollback = |moved_out: &[(PathBuf, PathBuf)], moved_in: &[PathBuf]| {
+        for path in moved_in {
+            let _ = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+        }
+        for (from, to) in moved_out {
+            let _ = fs::rename(to, from);
+        }
+        let _ = fs::remove_dir_all(&backup);
+    };
+
+    for path in component_entries(runtime_dir, component) {
+        let dest = backup.join(path.file_name().unwrap_or_default());
+        if let Err(e) = fs::rename(&path, &dest) {
+            rollback(&moved_out, &moved_in);
+            return Err(format!("Failed to move {} aside: {}", path.display(), e));
+        }
+        moved_out.push((path, dest));
+    }
+
+    let entries = fs::read_dir(staging).map_err(|e| {
+        rollback(&moved_out, &moved_in);
+        format!("Failed to read {}: {}", staging.display(), e)
+    })?;
+    for entry in entries.flatten() {
+        let dest = runtime_dir.join(entry.file_name());
+        let result = if dest.exists() {
+            Err(format!("{} already exists", dest.display()))
+        } else {
+            fs::rename(entry.path(), &dest).map_err(|e| e.to_string())
+        };
+        if let Err(e) = result {
+            rollback(&moved_out, &moved_in);
+            return Err(format!("Failed to install {}: {}", component.name(), e));
+        }
+        moved_in.push(dest);
+    }
+
+    let _ = fs::remove_dir_all(staging);
+    Ok(())
+}
+
+/// Drop the previous version kept by [`swap_component`], once the new one
+/// is validated
+pub fn finish_swap(runtime_dir: &Path, component: BinaryComponent) {
+    let backup = previous_dir(runtime_dir, component);
+    if let Err(e) = fs::remove_dir_all(&backup) {
+        tracing::warn!("Failed to remove previous {} files: {}", component.name(), e);
+    }
+}
+
+/// Put back the previous version kept by [`swap_component`], removing the
+/// new entries
+pub fn restore_previous(runtime_dir: &Path, component: BinaryComponent) -> Result<(), String> {
+    let backup = previous_dir(runtime_dir, component);
+    let entries = fs::read_dir(&backup).map_err(|e| format!("Failed to read {}: {}", backup.display(), e))?;
+    for path in component_entries(runtime_dir, component) {
+        let removed = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        removed.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+    for entry in entries.flatten() {
+        let dest = runtime_dir.join(entry.file_name());
+        fs::rename(entry.path(), &dest).map_err(|e| format!("Failed to restore {}: {}", dest.display(), e))?;
+    }
+    let _ = fs::remove_dir_all(&backup);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_swap_component() {
+        let temp_dir = TempDir::new().unwrap();
+        let runtime_dir = temp_dir.path();
+        fs::create_dir_all(runtime_dir.join("php-8.4.1-Win32-vs17-x64")).unwrap();
+        fs::write(runtime_dir.join("php-8.4.1-Win32-vs17-x64").join("php-cgi.exe"), "old").unwrap();
+        fs::create_dir_all(runtime_dir.join("phpMyAdmin-5.2.2-all-languages")).unwrap();
+        fs::write(runtime_dir.join("php_installed.txt"), "version=8.4.1").unwrap();
+
+        let staging = staging_dir(runtime_dir, BinaryComponent::Php);
+        fs::create_dir_all(staging.join("php-8.5.1-Win32-vs17-x64")).unwrap();
+        fs::write(staging.join("php-8.5.1-Win32-vs17-x64").join("php-cgi.exe"), "new").unwrap();
+
+        swap_component(runtime_dir, BinaryComponent::Php, &staging).unwrap();
+
+        assert!(!runtime_dir.join("php-8.4.1-Win32-vs17-x64").exists());
+        assert!(runtime_dir.join("php-8.5.1-Win32-vs17-x64").join("php-cgi.exe").exists());
+        // Other components and the version marker are left alone
+        assert!(runtime_dir.join("phpMyAdmin-5.2.2-all-languages").exists());
+        assert!(runtime_dir.join("php_installed.txt").exists());
+        assert!(!staging.exists());
+        // The previous version is kept until the new one is validated
+        assert!(runtime_dir.join(".previous-php").join("php-8.4.1-Win32-vs17-x64").exists());
+        finish_swap(runtime_dir, BinaryComponent::Php);
+        assert!(!runtime_dir.join(".previous-php").exists());
+    }
+
+    #[test]
+    fn test_restore_previous() {
+        let temp_dir = TempDir::new().unwrap();
+        let runtime_dir = temp_dir.path();
+        fs::create_dir_all(runtime_dir.join("mariadb-11.8.2-winx64")).unwrap();
+        fs::write(runtime_dir.join("mariadb-11.8.2-winx64").join("mariadbd"), "old").unwrap();
+
+        let staging = staging_dir(runtime_dir, BinaryComponent::MariaDB);
+        fs::create_dir_all(staging.join("mariadb-12.3.1-winx64")).unwrap();
+        swap_component(runtime_dir, BinaryComponent::MariaDB, &staging).unwrap();
+        restore_previous(runtime_dir, BinaryComponent::MariaDB).unwrap();
+
+        assert!(!runtime_dir.join("mariadb-12.3.1-winx64").exists());
+        assert_eq!(fs::read_to_string(runtime_dir.join("mariadb-11.8.2-winx64").join("mariadbd")).unwrap(), "old");
+        assert!(!runtime_dir.join(".previous-mariadb").exists());
+    }
+
+    #[test]
+    fn test_migrate_phpmyadmin_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let current = temp_dir.path().join("phpMyAdmin-5.2.2-all-languages");
+        fs::create_dir_all(current.join("tmp").join("twig")).unwrap();
+        fs::create_dir_all(current.join("tmp").join("sessions")).unwrap();
+        fs::write(current.join("tmp").join("sessions").join("sess_1"), "data").unwrap();
+        fs::write(current.join("config.inc.php"), "<?php $cfg['blowfish_secret'] = 'x';").unwrap();
+
+        let staging = staging_dir(temp_dir.path(), BinaryComponent::PhpMyAdmin);
+        let target = staging.join("phpMyAdmin-5.2.3-all-languages");
+        fs::create_dir_all(&target).unwrap();
+
+        migrate_phpmyadmin_files(&current, &staging).unwrap();
+        assert!(target.join("config.inc.php").exists());
+        assert!(target.join("tmp").join("sessions").join("sess_1").exists());
+        assert!(!target.join("tmp").join("twig").exists());
+    }
+
+    #[test]
+    fn test_check_data_compatible() {
+        assert!(check_data_compatible(BinaryComponent::PostgreSQL, "18.1.0", "18.3.0").is_ok());
+        assert!(check_data_compatible(BinaryComponent::PostgreSQL, "17.6.0", "18.3.0").is_err());
+        assert!(check_data_compatible(BinaryComponent::MariaDB, "11.8.2", "12.3.1").is_ok());
+    }
+}
@@ -0,0 +1,154 @@
+//! Post-install validation
+//!
+//! A binary that is present can still fail to start: a missing Visual C++
+//! runtime on Windows, a missing shared library on Linux, or an archive
+//! built for the wrong architecture. Each installed server is run with its
+//! version flag right after installation so these show up in the wizard
+//! instead of at first service start. The reported versions are recorded in
+//! `installed-manifest.json` in the runtime directory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::downloader::BinaryComponent;
+use super::locator::RuntimePaths;
+use crate::process::manager::configure_no_window;
+
+const INSTALLED_MANIFEST_FILE: &str = "installed-manifest.json";
+
+/// Windows status codes a process exits with when it cannot be loaded
+const STATUS_DLL_NOT_FOUND: i32 = 0xC000_0135_u32 as i32;
+const STATUS_INVALID_IMAGE_FORMAT: i32 = 0xC000_007B_u32 as i32;
+
+/// A binary that ran successfully after installation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledBinary {
+    /// Version reported by the binary itself
+    pub version: String,
+    pub path: PathBuf,
+    /// Seconds since the Unix epoch
+    pub validated_at: u64,
+}
+
+/// Contents of `installed-manifest.json`, keyed by component name
+pub type InstalledManifest = BTreeMap<String, InstalledBinary>;
+
+/// Run the binaries of `components` and record the versions they report
+pub fn validate_installed(paths: &RuntimePaths, components: &[BinaryComponent], runtime_dir: &Path) -> Result<(), String> {
+    let mut manifest = load_installed_manifest(runtime_dir);
+    for component in components {
+        let (binary, args): (&Path, &[&str]) = match component {
+            BinaryComponent::Caddy => (&paths.caddy, &["version"]),
+            BinaryComponent::Php => (&paths.php_cgi, &["-v"]),
+            BinaryComponent::MySQL | BinaryComponent::MariaDB => (&paths.mysql, &["--version"]),
+            _ => continue,
+        };
+        let version = run_version(component.name(), binary, args)
+            .map_err(|e| format!("Post-install check failed: {}", e))?;
+        tracing::info!("{} {} runs from {}", component.name(), version, binary.display());
+        manifest.insert(
+            component.name().to_string(),
+            InstalledBinary { version, path: binary.to_path_buf(), validated_at: crate::util::now() },
+        );
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize installed manifest: {}", e))?;
+    fs::write(runtime_dir.join(INSTALLED_MANIFEST_FILE), json)
+        .map_err(|e| format!("Failed to write installed manifest: {}", e))
+}
+
+/// Versions recorded by the last validation, empty if there was none
+pub fn load_installed_manifest(runtime_dir: &Path) -> InstalledManifest {
+    fs::read_to_string(runtime_dir.join(INSTALLED_MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Run `binary` with `args` and return the version it prints
+fn run_version(name: &str, binary: &Path, args: &[&str]) -> Result<String, String> {
+    let output = run_binary(name, binary, args)?;
+    parse_version(&output).ok_or_else(|| format!("{} did not report a version: {}", name, output.trim()))
+}
+
+/// Run `binary` with `args` and return what it printed, stdout first
+pub(crate) fn run_binary(name: &str, binary: &Path, args: &[&str]) -> Result<String, String> {
+    let output = configure_no_window(Command::new(binary))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("{} failed to start ({}): {}", name, binary.display(), e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        let hint = diagnose(output.status.code(), &stderr);
+        return Err(format!("{} failed to run ({}): {}", name, binary.display(), hint));
+    }
+    Ok(format!("{}{}", stdout, stderr))
+}
+
+/// Turn a failed run into something the user can act on
+fn diagnose(code: Option<i32>, stderr: &str) -> String {
+    match code {
+        Some(STATUS_DLL_NOT_FOUND) => {
+            return "a required DLL is missing. Install the Microsoft Visual C++ Redistributable \
+                    (https://aka.ms/vs/17/release/vc_redist.x64.exe) and retry."
+                .to_string()
+        }
+        Some(STATUS_INVALID_IMAGE_FORMAT) => {
+            return "the binary was built for a different architecture. Check the URL for your platform in runtime-config.json."
+                .to_string()
+        }
+        _ => {}
+    }
+    if let Some(line) = stderr.lines().find(|l| l.contains("error while loading shared libraries")) {
+        let library = line.split(':').nth(2).map(str::trim).unwrap_or("a shared library");
+        return format!("{} is missing. Install it with your package manager and retry.", library);
+    }
+    if stderr.contains("Exec format error") || stderr.contains("Bad CPU type") {
+        return "the binary was built for a different architecture. Check the URL for your platform in runtime-config.json."
+            .to_string();
+    }
+    match stderr.lines().rev().find(|l| !l.trim().is_empty()) {
+        Some(line) => line.trim().to_string(),
+        None => format!("exited with code {}", code.map(|c| c.to_string()).unwrap_or_else(|| "unknown".to_string())),
+    }
+}
+
+/// First dotted version number in `output`, e.g. "8.5.1" from
+/// "PHP 8.5.1 (cgi-fcgi)" or "12.3.1" from "mariadbd  Ver 12.3.1-MariaDB"
+pub(crate) fn parse_version(output: &str) -> Option<String> {
+    output.split_whitespace().find_map(|word| {
+        let word = word.trim_start_matches('v');
+        let version: String = word.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+        let version = version.trim_end_matches('.');
+        (version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit())).then(|| version.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v2.10.2 h1:kYLRtYuN0gaYw6ZtIdJUcxO7t8PUfaCasGe4MJxYbQU=").as_deref(), Some("2.10.2"));
+        assert_eq!(parse_version("PHP 8.5.1 (cgi-fcgi) (built: Dec 16 2025)").as_deref(), Some("8.5.1"));
+        assert_eq!(parse_version("mariadbd  Ver 12.3.1-MariaDB for Linux on x86_64").as_deref(), Some("12.3.1"));
+        assert_eq!(parse_version("/usr/sbin/mysqld  Ver 8.4.0 for Linux on x86_64 (MySQL Community Server - GPL)").as_deref(), Some("8.4.0"));
+        assert_eq!(parse_version("no version here"), None);
+    }
+
+    #[test]
+    fn test_diagnose() {
+        assert!(diagnose(Some(STATUS_DLL_NOT_FOUND), "").contains("Visual C++"));
+        let stderr = "./php-cgi: error while loading shared libraries: libonig.so.5: cannot open shared object file";
+        assert!(diagnose(Some(127), stderr).starts_with("libonig.so.5 is missing"));
+        assert_eq!(diagnose(Some(1), "warning\nfatal: bad config\n"), "fatal: bad config");
+    }
+}
@@ -0,0 +1,89 @@
+//! Small helpers shared across modules
+
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Serde default of flags that are on unless turned off
+pub fn default_true() -> bool { true }
+
+/// Total size of the files under `path`, symlinks are not followed
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Copy `src` into `dest`, leaving out the top-level entries named in `skip`
+///
+/// Symlinks are recreated as links on Unix and left out on Windows, sockets
+/// and pipes are not worth preserving.
+pub fn copy_dir(src: &Path, dest: &Path, skip: &[&str]) -> Result<(), String> {
+    fs::create_dir_all(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let entries = fs::read_dir(src)
+        .map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+    for entry in entries.flatten() {
+        if skip.iter().any(|name| entry.file_name() == *name) {
+            continue;
+        }
+        let from = entry.path();
+        let to = dest.join(entry.file_name());
+        let file_type = entry.file_type()
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        if file_type.is_dir() {
+            copy_dir(&from, &to, &[])?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            {
+                let target = fs::read_link(&from)
+                    .map_err(|e| format!("Failed to read link {}: {}", from.display(), e))?;
+                std::os::unix::fs::symlink(target, &to)
+                    .map_err(|e| format!("Failed to link {}: {}", to.display(), e))?;
+            }
+        } else if file_type.is_file() {
+            fs::copy(&from, &to)
+                .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_dir_and_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("data");
+        fs::create_dir_all(src.join("db1")).unwrap();
+        fs::create_dir_all(src.join("cache")).unwrap();
+        fs::write(src.join("ibdata1"), vec![0u8; 100]).unwrap();
+        fs::write(src.join("db1").join("t.ibd"), vec![0u8; 50]).unwrap();
+        fs::write(src.join("cache").join("c"), vec![0u8; 10]).unwrap();
+
+        assert_eq!(dir_size(&src), 160);
+
+        let dest = temp_dir.path().join("copy");
+        copy_dir(&src, &dest, &["cache"]).unwrap();
+        assert_eq!(dir_size(&dest), 150);
+        assert!(dest.join("db1").join("t.ibd").exists());
+        assert!(!dest.join("cache").exists());
+    }
+}
@@ -16,17 +16,8 @@ function App() {
       setIsFirstRun(true);
     });
 
-    // Cleanup all services when window closes
-    const handleBeforeUnload = () => {
-      invoke("cleanup_all_services").catch((error) => {
-        console.error("Failed to cleanup services:", error);
-      });
-    };
-
-    window.addEventListener("beforeunload", handleBeforeUnload);
-
+    // Services are stopped by the backend when the app exits
     return () => {
-      window.removeEventListener("beforeunload", handleBeforeUnload);
       unlisten.then((fn) => fn());
     };
   }, []);
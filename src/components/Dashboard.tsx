@@ -1,12 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
-import { openUrl, revealItemInDir } from "@tauri-apps/plugin-opener";
+import { listen } from "@tauri-apps/api/event";
+import { revealItemInDir } from "@tauri-apps/plugin-opener";
 import { useState, useEffect, useCallback } from "react";
-import { ServiceMap, ServiceType, ServiceState, getDatabaseDisplayName } from "../types/services";
+import { ServiceMap, ServiceType, ServiceState, ServiceStateChange, SiteTarget, getDatabaseDisplayName } from "../types/services";
 import { ServiceCard } from "./ServiceCard";
 import { StatusBar } from "./StatusBar";
 import { SettingsPanel } from "./SettingsPanel";
 import { DebugMenu } from "./DebugMenu";
 import { detectPlatform } from "../utils/platform";
+import { errorMessage, isCamppError } from "../utils/errors";
 
 export function Dashboard() {
   const [services, setServices] = useState<Partial<ServiceMap>>({});
@@ -19,7 +21,7 @@ export function Dashboard() {
   const currentPlatform = detectPlatform();
   const dbName = getDatabaseDisplayName(currentPlatform);
 
-  // Get Caddy port from services
+  // Get Caddy port from services, for tooltips only: the backend builds the URLs it opens
   const caddyPort = services[ServiceType.Caddy]?.port || 8080;
   const webServerUrl = `http://localhost:${caddyPort}`;
   const phpMyAdminUrl = `${webServerUrl}/phpmyadmin`;
@@ -48,8 +50,17 @@ export function Dashboard() {
 
   useEffect(() => {
     refreshStatuses();
-    const interval = setInterval(refreshStatuses, 2000);
-    return () => clearInterval(interval);
+    // State transitions are pushed by the backend, including crashes
+    const unlisten = listen<ServiceStateChange>("service-state-changed", ({ payload }) => {
+      setServices((prev) => {
+        const info = prev[payload.service];
+        if (!info) return prev;
+        return { ...prev, [payload.service]: { ...info, state: payload.new, error_message: payload.error_message } };
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, [refreshStatuses]);
 
   useEffect(() => {
@@ -84,18 +95,37 @@ export function Dashboard() {
       await refreshStatuses();
     } catch (error) {
       console.error(`Failed to start ${serviceType}:`, error);
-      alert(`Failed to start ${serviceType}:\n${error}`);
+      if (isCamppError(error) && error.kind === "port_in_use") {
+        await resolvePortConflict(serviceType, error.port, error.message);
+      } else {
+        alert(`Failed to start ${serviceType}:\n${errorMessage(error)}`);
+      }
       await refreshStatuses();
     }
   };
 
+  /** Offer to kill the process holding the port, or move the service to a free port */
+  const resolvePortConflict = async (serviceType: ServiceType, port: number, message: string) => {
+    try {
+      if (confirm(`${message}\n\nOK kills that process, Cancel picks another port for ${serviceType}.`)) {
+        await invoke("kill_port_owner", { port });
+      } else {
+        const freePort = await invoke<number>("suggest_free_port", { service: serviceType });
+        await invoke("set_service_port", { service: serviceType, port: freePort });
+      }
+      await invoke("start_service", { service: serviceType });
+    } catch (error) {
+      alert(`Failed to start ${serviceType}:\n${errorMessage(error)}`);
+    }
+  };
+
   const stopService = async (serviceType: ServiceType) => {
     try {
       await invoke("stop_service", { service: serviceType });
       await refreshStatuses();
     } catch (error) {
       console.error(`Failed to stop ${serviceType}:`, error);
-      alert(`Failed to stop ${serviceType}:\n${error}`);
+      alert(`Failed to stop ${serviceType}:\n${errorMessage(error)}`);
       await refreshStatuses();
     }
   };
@@ -106,14 +136,16 @@ export function Dashboard() {
       await refreshStatuses();
     } catch (error) {
       console.error(`Failed to restart ${serviceType}:`, error);
-      alert(`Failed to restart ${serviceType}:\n${error}`);
+      alert(`Failed to restart ${serviceType}:\n${errorMessage(error)}`);
       await refreshStatuses();
     }
   };
 
+  const openInBrowser = (target: SiteTarget) => invoke<string>("open_in_browser", { target });
+
   const openWebServer = async () => {
     try {
-      await openUrl(webServerUrl);
+      await openInBrowser({ kind: "site" });
     } catch (error) {
       console.error("Failed to open web server URL:", error);
     }
@@ -121,7 +153,7 @@ export function Dashboard() {
 
   const openPhpMyAdmin = async () => {
     try {
-      await openUrl(phpMyAdminUrl);
+      await openInBrowser({ kind: "phpmyadmin" });
     } catch (error) {
       console.error("Failed to open phpMyAdmin URL:", error);
     }
@@ -214,7 +246,7 @@ export function Dashboard() {
               </button>
               <button
                 className="btn-quick-action"
-                onClick={async () => { try { await openUrl(adminerUrl); } catch (error) { console.error("Failed to open Adminer URL:", error); } }}
+                onClick={async () => { try { await openInBrowser({ kind: "adminer" }); } catch (error) { console.error("Failed to open Adminer URL:", error); } }}
                 disabled={!isCaddyRunning || !installedVersions.adminer}
                 title={!installedVersions.adminer ? "Adminer not installed" : isCaddyRunning ? `Open ${adminerUrl}` : "Start Caddy to enable"}
               >
@@ -451,7 +483,7 @@ export function Dashboard() {
                 await invoke("open_folder", { path: runtimeDir });
               } catch (error) {
                 console.error("Failed to open folder:", error);
-                alert("Failed to open folder: " + error);
+                alert("Failed to open folder: " + errorMessage(error));
               }
             }}
             onOpenDownloadFolder={async () => {
@@ -460,7 +492,31 @@ export function Dashboard() {
                 await invoke("open_folder", { path: downloadDir });
               } catch (error) {
                 console.error("Failed to open download folder:", error);
-                alert("Failed to open download folder: " + error);
+                alert("Failed to open download folder: " + errorMessage(error));
+              }
+            }}
+            onOpenConfigFolder={async () => {
+              try {
+                await invoke("open_config_folder");
+              } catch (error) {
+                console.error("Failed to open config folder:", error);
+                alert("Failed to open config folder: " + errorMessage(error));
+              }
+            }}
+            onOpenLogsFolder={async () => {
+              try {
+                await invoke("open_logs_folder");
+              } catch (error) {
+                console.error("Failed to open logs folder:", error);
+                alert("Failed to open logs folder: " + errorMessage(error));
+              }
+            }}
+            onOpenDatabaseFolder={async () => {
+              try {
+                await invoke("open_database_folder", { service: ServiceType.MySQL });
+              } catch (error) {
+                console.error("Failed to open database folder:", error);
+                alert("Failed to open database folder: " + errorMessage(error));
               }
             }}
             onResetInstallation={async () => {
@@ -471,7 +527,7 @@ export function Dashboard() {
                 window.location.reload();
               } catch (error) {
                 console.error("Failed to reset:", error);
-                alert("Failed to reset: " + error);
+                alert("Failed to reset: " + errorMessage(error));
               }
             }}
           />
@@ -2,10 +2,13 @@ interface DebugMenuProps {
   onClose: () => void;
   onOpenRuntimeFolder: () => void;
   onOpenDownloadFolder: () => void;
+  onOpenConfigFolder: () => void;
+  onOpenLogsFolder: () => void;
+  onOpenDatabaseFolder: () => void;
   onResetInstallation: () => void;
 }
 
-export function DebugMenu({ onClose, onOpenRuntimeFolder, onOpenDownloadFolder, onResetInstallation }: DebugMenuProps) {
+export function DebugMenu({ onClose, onOpenRuntimeFolder, onOpenDownloadFolder, onOpenConfigFolder, onOpenLogsFolder, onOpenDatabaseFolder, onResetInstallation }: DebugMenuProps) {
   return (
     <div className="debug-menu">
       <div className="debug-menu-header">
@@ -21,6 +24,15 @@ export function DebugMenu({ onClose, onOpenRuntimeFolder, onOpenDownloadFolder,
         <button className="debug-menu-btn" onClick={onOpenDownloadFolder}>
           View Download Folder
         </button>
+        <button className="debug-menu-btn" onClick={onOpenConfigFolder}>
+          Open Config Folder
+        </button>
+        <button className="debug-menu-btn" onClick={onOpenLogsFolder}>
+          Open Logs Folder
+        </button>
+        <button className="debug-menu-btn" onClick={onOpenDatabaseFolder}>
+          Open Database Folder
+        </button>
         <button className="debug-menu-btn" onClick={onResetInstallation}>
           Reset Installation
         </button>
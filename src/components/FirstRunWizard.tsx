@@ -2,6 +2,7 @@ import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import {
+  BinaryComponent,
   DownloadProgress as DownloadProgressType,
   PackageSelection,
   DependencyCheckResult,
@@ -9,6 +10,7 @@ import {
 } from "../types/services";
 import { PackageSelector } from "./PackageSelector";
 import { detectPlatform } from "../utils/platform";
+import { errorMessage } from "../utils/errors";
 
 // Version injected by Vite at build time from package.json
 const APP_VERSION = __APP_VERSION__;
@@ -66,6 +68,8 @@ export function FirstRunWizard({ onComplete, ...props }: FirstRunWizardProps) {
     totalComponents: 4,
     downloadedBytes: 0,
     totalBytes: 0,
+    overallPercent: 0,
+    components: [],
   });
   const [error, setError] = useState<string | null>(null);
   const [packageSelection, setPackageSelection] = useState<PackageSelection>({
@@ -252,19 +256,25 @@ export function FirstRunWizard({ onComplete, ...props }: FirstRunWizardProps) {
     proceedWithDownload([]);
   };
 
-  const getDisabledComponents = (): string[] => {
+  const getSelectedComponents = (existingSkipList: string[]): BinaryComponent[] => {
+    const dbKey = currentPlatform === "linux" ? "mariadb" : "mysql";
+    const otherDbKey = currentPlatform === "linux" ? "mysql" : "mariadb";
+    // The database toggle is keyed by the platform default, install the chosen flavor
+    const dbFlavor = packageSelection.db_flavor ?? dbKey;
     return Object.entries(enabledComponents)
-      .filter(([_, v]) => !v)
-      .map(([k]) => k);
+      .filter(([k, v]) => v && k !== otherDbKey)
+      .map(([k]) => (k === dbKey ? dbFlavor : k))
+      // Caddy and PHP are always reinstalled
+      .filter((k) => k === "caddy" || k === "php" || !existingSkipList.includes(k))
+      .map((k) => k as BinaryComponent);
   };
 
   const proceedWithDownload = async (existingSkipList: string[]) => {
     setError(null);
     setStep("download");
 
-    // Merge existing skip list + user-disabled components
-    const disabled = getDisabledComponents();
-    const skipList = [...new Set([...existingSkipList, ...disabled])];
+    // Only install the enabled components that are not already installed
+    const components = getSelectedComponents(existingSkipList);
 
     try {
       // Save the package selection and database passwords to settings
@@ -274,19 +284,14 @@ export function FirstRunWizard({ onComplete, ...props }: FirstRunWizardProps) {
         postgresPassword,
       });
 
-      if (skipList.length > 0) {
-        const result = await invoke<string>("download_runtime_with_skip", {
-          packageSelection,
-          skipList,
-        });
-        console.log(result);
-      } else {
-        const result = await invoke<string>("download_runtime_with_packages", { packageSelection });
-        console.log(result);
-      }
+      const result = await invoke<string>("download_runtime_components", {
+        packageSelection,
+        components,
+      });
+      console.log(result);
     } catch (err) {
       console.error("Download error:", err);
-      setError(err as string);
+      setError(errorMessage(err));
       setStep("confirm");
     }
   };
@@ -766,7 +771,7 @@ export function FirstRunWizard({ onComplete, ...props }: FirstRunWizardProps) {
                 </div>
                 {(progress.step === "downloading" || progress.step === "extracting") && (
                   <span style={{ fontSize: "1.25rem", fontWeight: 700, color: "var(--color-primary)" }}>
-                    {progress.percent}%
+                    {progress.overallPercent}%
                   </span>
                 )}
               </div>
@@ -806,16 +811,28 @@ export function FirstRunWizard({ onComplete, ...props }: FirstRunWizardProps) {
 
               {/* Progress Bar */}
               <div className="progress-container">
-                <div
-                  className={progress.step === "extracting" || progress.step === "installing" ? "progress-bar-animated" : "progress-bar"}
-                  style={{
-                    width: progress.step === "extracting" || progress.step === "installing"
-                      ? "100%"
-                      : `${progress.percent}%`,
-                  }}
-                />
+                <div className="progress-bar" style={{ width: `${progress.overallPercent}%` }} />
               </div>
 
+              {/* Per-component Progress */}
+              {progress.components.length > 1 && (
+                <div style={{ display: "flex", flexDirection: "column", gap: "0.25rem", fontSize: "0.8125rem" }}>
+                  {progress.components.map((c) => (
+                    <div key={c.component} style={{ display: "flex", justifyContent: "space-between", color: "var(--text-secondary)" }}>
+                      <span>
+                        {c.display} {c.version}
+                      </span>
+                      <span>
+                        {c.step === "downloading" && c.attempt > 1 && `Retry ${c.attempt - 1} · `}
+                        {c.step === "downloading" && `${c.percent}%`}
+                        {c.step === "extracting" && `Extracting ${c.percent}%`}
+                        {c.step === "installing" && (c.percent === 100 ? "Installed" : "Installing")}
+                      </span>
+                    </div>
+                  ))}
+                </div>
+              )}
+
               {/* Download Details */}
               {progress.step === "downloading" && progress.totalBytes > 0 && (
                 <div style={{ textAlign: "center", fontSize: "0.875rem", color: "var(--text-secondary)" }}>
@@ -1,6 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { PackagesConfig, PackageSelection, PhpPackage, MySQLPackage, PhpMyAdminPackage, getDatabaseDisplayName } from "../types/services";
+import { AvailableVersion, PackagesConfig, PackageSelection, MySQLPackage, PhpMyAdminPackage, getDatabaseDisplayName } from "../types/services";
 import { detectPlatform } from "../utils/platform";
 
 interface PackageSelectorProps {
@@ -16,11 +16,13 @@ interface PackageSelectorProps {
 
 export function PackageSelector({ onSelectionChange, initialSelection, initialEnabled, onEnabledChange, mysqlPassword, onMysqlPasswordChange, postgresPassword, onPostgresPasswordChange }: PackageSelectorProps) {
   const [packages, setPackages] = useState<PackagesConfig | null>(null);
+  const [phpVersions, setPhpVersions] = useState<AvailableVersion[]>([]);
+  const [dbVersions, setDbVersions] = useState<AvailableVersion[]>([]);
   const [loading, setLoading] = useState(true);
   const [currentPlatform, setCurrentPlatform] = useState<string>("");
   const [selection, setSelection] = useState<PackageSelection>(
     initialSelection || {
-      php: "php-8.4",
+      php: "php-8.5",
       mysql: "mysql-8.4",
       mariadb: "mariadb-12.3",
       phpmyadmin: "phpmyadmin-5.2",
@@ -64,6 +66,15 @@ export function PackageSelector({ onSelectionChange, initialSelection, initialEn
   const loadPackages = async () => {
     try {
       const data = await invoke<PackagesConfig>("get_available_packages_cmd");
+      // Only offer PHP builds published for this platform
+      const php = await invoke<AvailableVersion[]>("list_available_php_versions");
+      setPhpVersions(php);
+      // MariaDB series and MySQL versions published for this platform
+      setDbVersions(await invoke<AvailableVersion[]>("list_available_database_versions"));
+      if (php.length > 0 && !php.some((v) => v.id === selection.php)) {
+        const fallback = php.find((v) => v.selected) ?? php[0];
+        setSelection((prev) => ({ ...prev, php: fallback.id }));
+      }
       setPackages(data);
     } catch (err) {
       console.error("Failed to load packages:", err);
@@ -173,9 +184,9 @@ export function PackageSelector({ onSelectionChange, initialSelection, initialEn
           className="input"
           style={selectStyle(enabled.php)}
         >
-          {packages.php.map((pkg: PhpPackage) => (
-            <option key={pkg.id} value={pkg.id}>
-              {pkg.display_name}{pkg.eol && " (EOL)"}{pkg.recommended && " ★"}
+          {phpVersions.map((v: AvailableVersion) => (
+            <option key={v.id} value={v.id}>
+              {v.display_name}{v.eol && " (EOL)"}{v.selected && " ★"}
             </option>
           ))}
         </select>
@@ -193,21 +204,21 @@ export function PackageSelector({ onSelectionChange, initialSelection, initialEn
           {dbName}
         </label>
         <select
-          value={dbKey === "mariadb" ? selection.mariadb : selection.mysql}
+          value={(selection.db_flavor ?? dbKey) === "mariadb" ? selection.mariadb : selection.mysql}
           onChange={(e) => {
-            if (dbKey === "mariadb") {
-              setSelection({ ...selection, mariadb: e.target.value });
+            if (e.target.value.startsWith("mariadb")) {
+              setSelection({ ...selection, mariadb: e.target.value, db_flavor: "mariadb" });
             } else {
-              setSelection({ ...selection, mysql: e.target.value });
+              setSelection({ ...selection, mysql: e.target.value, db_flavor: "mysql" });
             }
           }}
           disabled={!isDbEnabled}
           className="input"
           style={selectStyle(isDbEnabled)}
         >
-          {(dbKey === "mariadb" ? packages.mariadb : packages.mysql).map((pkg: MySQLPackage) => (
-            <option key={pkg.id} value={pkg.id}>
-              {pkg.display_name}{pkg.lts && " (LTS)"}{pkg.recommended && " ★"}
+          {dbVersions.map((v: AvailableVersion) => (
+            <option key={v.id} value={v.id}>
+              {v.display_name}{v.lts && " (LTS)"}{v.selected && " ★"}
             </option>
           ))}
         </select>
@@ -2,6 +2,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { useState, useEffect, useCallback } from "react";
 import { AppSettings, PackageSelection, getDatabaseDisplayName } from "../types/services";
 import { detectPlatform } from "../utils/platform";
+import { errorMessage } from "../utils/errors";
 
 interface SettingsPanelProps {
   onClose: () => void;
@@ -52,7 +53,7 @@ export function SettingsPanel({ onClose, onSettingsChanged, ...props }: Settings
       const loaded = await invoke<AppSettings>("get_settings");
       setSettings(loaded);
     } catch (e) {
-      setError(`Failed to load settings: ${e}`);
+      setError(`Failed to load settings: ${errorMessage(e)}`);
     } finally {
       setLoading(false);
     }
@@ -100,7 +101,7 @@ export function SettingsPanel({ onClose, onSettingsChanged, ...props }: Settings
       onSettingsChanged?.();
       setTimeout(() => onClose(), 2000);
     } catch (e) {
-      setError(`Failed to save settings: ${e}`);
+      setError(`Failed to save settings: ${errorMessage(e)}`);
     } finally {
       setSaving(false);
     }
@@ -127,7 +128,7 @@ export function SettingsPanel({ onClose, onSettingsChanged, ...props }: Settings
       await invoke("uninstall_component", { component: componentKey });
       await refreshComponents();
     } catch (e) {
-      alert(`Failed to uninstall: ${e}`);
+      alert(`Failed to uninstall: ${errorMessage(e)}`);
     } finally {
       setComponentAction(null);
     }
@@ -157,7 +158,7 @@ export function SettingsPanel({ onClose, onSettingsChanged, ...props }: Settings
       });
       await refreshComponents();
     } catch (e) {
-      alert(`Failed to reinstall: ${e}`);
+      alert(`Failed to reinstall: ${errorMessage(e)}`);
     } finally {
       setComponentAction(null);
     }
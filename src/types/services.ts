@@ -7,6 +7,11 @@ export enum ServiceType {
   PhpFpm = "php-fpm",
   MySQL = "mysql",
   PostgreSQL = "postgresql",
+  Tunnel = "tunnel",
+  Mailpit = "mailpit",
+  Memcached = "memcached",
+  Minio = "minio",
+  Ftp = "ftp",
 }
 
 export enum ServiceState {
@@ -15,6 +20,18 @@ export enum ServiceState {
   Running = "running",
   Stopping = "stopping",
   Error = "error",
+  Disabled = "disabled",
+  Paused = "paused",
+}
+
+export type RestartStrategy = "graceful" | "force" | "reload_config" | "rolling";
+
+export interface RestartReport {
+  service: ServiceType;
+  strategy: RestartStrategy;
+  stop_ms: number;
+  start_ms: number;
+  total_ms: number;
 }
 
 export interface ServiceInfo {
@@ -26,15 +43,206 @@ export interface ServiceInfo {
 
 export type ServiceMap = Record<ServiceType, ServiceInfo>;
 
+/** Payload of the "log-line" event sent while a service log is followed */
+export interface LogLine {
+  service: ServiceType;
+  line: string;
+}
+
+export type LogLevel = "debug" | "info" | "warn" | "error";
+
+/** Arguments of `get_service_logs`, every field is optional */
+export interface LogQuery {
+  /** Matching lines to return, counted back from `before` (default 200) */
+  lines?: number;
+  /** Byte offset to read back from; pass the previous page's `start` to load older lines */
+  before?: number;
+  /** Byte range [start, end) to read instead of the last lines */
+  range?: [number, number];
+  min_level?: LogLevel;
+  /** Case-insensitive substring */
+  search?: string;
+}
+
+export interface LogEntry {
+  offset: number;
+  text: string;
+  level: LogLevel | null;
+  /** Parsed line, for JSON logs such as Caddy's access log */
+  json?: Record<string, unknown>;
+}
+
+export interface LogPage {
+  entries: LogEntry[];
+  start: number;
+  end: number;
+  size: number;
+}
+
+export type ApplyAction = "update_password" | "reload_config" | "restart" | "stop";
+
+export interface PendingChange {
+  service: ServiceType;
+  action: ApplyAction;
+  fields: string[];
+}
+
+export interface AppliedChange extends PendingChange {
+  error: string | null;
+}
+
+export interface ApplyReport {
+  changes: AppliedChange[];
+}
+
+export interface SettingsChanged {
+  old: AppSettings;
+  new: AppSettings;
+  changed_fields: string[];
+  pending: PendingChange[];
+  restart_required: ServiceType[];
+}
+
+export interface ApplyProgress {
+  step: number;
+  total: number;
+  service: ServiceType;
+  action: ApplyAction;
+}
+
+/** Extra command-line arguments per service; dangerous flags are rejected */
+export interface ServiceArgs {
+  caddy: string[];
+  php: string[];
+  mysql: string[];
+  postgres: string[];
+  tunnel?: string[];
+  mailpit?: string[];
+  memcached?: string[];
+  minio?: string[];
+  ftp?: string[];
+}
+
+/** Cleanup policy for the temporary download directory */
+export interface DownloadCacheSettings {
+  max_age_days: number;
+  max_size_mb: number;
+  /** Keep archives of installed versions for offline reinstall */
+  keep_for_offline_reinstall: boolean;
+  /** Archives kept once an install succeeds, null leaves them to the limits above */
+  keep_after_install?: number | null;
+}
+
+export interface LogRotationSettings {
+  enabled: boolean;
+  max_size_mb: number;
+  /** Rotate a log written to for this many days, 0 disables */
+  max_age_days: number;
+  /** Rotated files kept per log */
+  keep: number;
+  compress: boolean;
+}
+
+export interface CleanupReport {
+  removed: string[];
+  freed_bytes: number;
+  /** Archives kept for offline reinstall */
+  protected: string[];
+}
+
 export interface AppSettings {
   web_port: number;
   mysql_port: number;
   php_port: number;
   postgres_port: number;
+  /** Port of the HTTPS project sites */
+  https_port: number;
+  /** Redirect plain HTTP to HTTPS for HTTPS projects without their own choice */
+  https_redirect?: boolean;
   project_root: string;
   mysql_root_password: string;
   postgres_root_password: string;
   package_selection?: PackageSelection;
+  phpmyadmin_auth?: BasicAuthCredentials | null;
+  security_headers?: SecurityHeader[];
+  harden_runtime?: boolean;
+  auto_backup?: boolean;
+  auto_backup_max_mb?: number;
+  access_log?: AccessLogSettings;
+  log_rotation?: LogRotationSettings;
+  active_profile?: string;
+  extra_args?: ServiceArgs;
+  download_cache?: DownloadCacheSettings;
+  /** Install runtime archives that have no checksum in runtime-config.json */
+  allow_unverified_downloads?: boolean;
+  /** Use published runtime manifests without a valid signature */
+  allow_unsigned_manifests?: boolean;
+  /** Runtime components downloaded at the same time (default 3) */
+  download_concurrency?: number;
+  enabled_services?: ServiceType[];
+  /** 127.0.0.1 (default), 0.0.0.0 / :: for all interfaces, or one interface address */
+  bind_address?: string;
+  watch_config_files?: boolean;
+  binary_overrides?: BinaryOverrides;
+  /** Extra PHP versions projects can be pinned to */
+  php_versions?: PhpBackend[];
+  /** Editor projects are opened in, null for the first one installed */
+  editor?: Editor | null;
+  /** The welcome project was created after the first runtime install */
+  welcome_project_created?: boolean;
+  /** Wildcard DNS answering every project domain with loopback */
+  dns?: DnsSettings;
+  /** TLD of project domains, e.g. "test" for shop.test; change it with `set_domain_tld` */
+  domain_tld?: string;
+  /** Public tunnel to a project */
+  tunnel?: TunnelSettings;
+  /** Mailpit SMTP and web UI ports */
+  mailpit?: MailpitSettings;
+  /** Memcached port, memory and binary */
+  memcached?: MemcachedSettings;
+  /** MinIO API and console ports */
+  minio?: MinioSettings;
+  /** SFTP and FTP ports and binary */
+  ftp?: FtpSettings;
+}
+
+/** System-installed binaries used instead of downloaded ones */
+export interface BinaryOverrides {
+  caddy?: string | null;
+  php?: string | null;
+  mysql?: string | null;
+}
+
+export interface SystemBinary {
+  component: BinaryComponent;
+  path: string;
+  version: string;
+  /** Why CAMPP cannot use it, null when it can */
+  incompatible: string | null;
+}
+
+export interface EnvOverride {
+  variable: string;
+  field: keyof AppSettings;
+  value: unknown;
+}
+
+export type AccessLogFormat = "json" | "console";
+
+export interface AccessLogSettings {
+  enabled: boolean;
+  format: AccessLogFormat;
+  per_site: boolean;
+}
+
+export interface BasicAuthCredentials {
+  username: string;
+  password_hash: string;
+}
+
+export interface SecurityHeader {
+  name: string;
+  value: string;
 }
 
 export interface DownloadProgress {
@@ -46,8 +254,61 @@ export interface DownloadProgress {
   totalComponents: number;
   downloadedBytes: number;
   totalBytes: number;
+  /** Current download attempt, 0 outside of downloads */
+  attempt: number;
+  /** Progress of the whole install, across components */
+  overallPercent: number;
+  /** Components are downloaded concurrently */
+  components: ComponentProgress[];
+}
+
+export interface RuntimeUpdate {
+  component: string;
+  installed_version: string;
+  version_id: string;
+  available_version: string;
+  display_name: string;
+  size: number | null;
+  changelog_url: string | null;
 }
 
+export interface ComponentProgress {
+  component: string;
+  display: string;
+  version: string;
+  step: DownloadProgress["step"];
+  percent: number;
+  downloadedBytes: number;
+  totalBytes: number;
+  attempt: number;
+}
+
+export interface DiskUsageItem {
+  /** Stable key, e.g. "php" or "mysql_data" */
+  name: string;
+  label: string;
+  bytes: number;
+}
+
+export interface DiskUsage {
+  items: DiskUsageItem[];
+  total_bytes: number;
+}
+
+/** Runtime components that can be installed or uninstalled individually */
+export type BinaryComponent =
+  | "caddy"
+  | "php"
+  | "mysql"
+  | "mariadb"
+  | "phpmyadmin"
+  | "postgresql"
+  | "adminer"
+  | "cloudflared"
+  | "mailpit"
+  | "node"
+  | "minio";
+
 // Package selection types
 export interface PhpPackage {
   id: string;
@@ -98,6 +359,16 @@ export interface PackagesConfig {
   adminer: PhpMyAdminPackage[];
 }
 
+/** A component version that can be installed on this platform */
+export interface AvailableVersion {
+  id: string;
+  version: string;
+  display_name: string;
+  eol: boolean;
+  lts: boolean;
+  selected: boolean;
+}
+
 export interface PackageSelection {
   php: string;
   mysql: string;
@@ -105,6 +376,8 @@ export interface PackageSelection {
   phpmyadmin: string;
   postgresql: string;
   adminer: string;
+  /** Database server to install, the platform default when unset */
+  db_flavor?: "mariadb" | "mysql" | null;
 }
 
 export const DEFAULT_PORTS = {
@@ -112,6 +385,11 @@ export const DEFAULT_PORTS = {
   [ServiceType.PhpFpm]: 9000,
   [ServiceType.MySQL]: 3307,
   [ServiceType.PostgreSQL]: 5433,
+  [ServiceType.Tunnel]: 20241,
+  [ServiceType.Mailpit]: 1025,
+  [ServiceType.Memcached]: 11211,
+  [ServiceType.Minio]: 9900,
+  [ServiceType.Ftp]: 2022,
 } as const;
 
 export const SERVICE_DISPLAY_NAMES = {
@@ -119,6 +397,11 @@ export const SERVICE_DISPLAY_NAMES = {
   [ServiceType.PhpFpm]: "PHP-FPM",
   [ServiceType.MySQL]: "MariaDB",
   [ServiceType.PostgreSQL]: "PostgreSQL",
+  [ServiceType.Tunnel]: "Tunnel",
+  [ServiceType.Mailpit]: "Mailpit",
+  [ServiceType.Memcached]: "Memcached",
+  [ServiceType.Minio]: "MinIO",
+  [ServiceType.Ftp]: "SFTP/FTP",
 } as const;
 
 // Platform-specific display name for MySQL/MariaDB
@@ -142,6 +425,11 @@ export const SERVICE_DESCRIPTIONS = {
   [ServiceType.PhpFpm]: "PHP Runtime",
   [ServiceType.MySQL]: "Database Server",
   [ServiceType.PostgreSQL]: "Database Server",
+  [ServiceType.Tunnel]: "Public Tunnel",
+  [ServiceType.Mailpit]: "Mail Catcher",
+  [ServiceType.Memcached]: "Cache Server",
+  [ServiceType.Minio]: "Object Storage",
+  [ServiceType.Ftp]: "File Transfer",
 } as const;
 
 // System dependency types
@@ -170,3 +458,504 @@ export interface ComponentStatus {
 }
 
 export type ComponentStatusMap = Record<string, ComponentStatus>;
+
+export type SecuritySeverity = "info" | "low" | "medium" | "high";
+
+export interface SecurityFinding {
+  id: string;
+  title: string;
+  severity: SecuritySeverity;
+  passed: boolean;
+  detail: string;
+  fix: string | null;
+  fix_command: string | null;
+}
+
+export interface SecurityReport {
+  findings: SecurityFinding[];
+  worst: SecuritySeverity | null;
+}
+
+export interface VhostRoute {
+  host: string;
+  root: string;
+}
+
+export interface IntegrityReport {
+  hardened: boolean;
+  modified: string[];
+  added: string[];
+  missing: string[];
+  writable: string[];
+}
+
+export type BackupKind = "database_dump" | "snapshot";
+
+export interface BackupRecord {
+  id: string;
+  kind: BackupKind;
+  source: string;
+  reason: string;
+  path: string;
+  size_bytes: number;
+  created_at: number;
+}
+
+export interface DestructiveOpResult {
+  message: string;
+  backup: BackupRecord | null;
+}
+
+export type DatabaseEngine = "mysql" | "postgresql";
+
+export interface ProjectReference {
+  project: string;
+  config_file: string;
+  port: number | null;
+}
+
+export interface DatabaseUsage {
+  engine: DatabaseEngine;
+  database: string;
+  projects: ProjectReference[];
+  /** null when the server was not running */
+  exists: boolean | null;
+  active_connections: number;
+  longest_query_secs: number;
+}
+
+export interface PortChangeResult {
+  service: ServiceType;
+  old_port: number;
+  new_port: number;
+  restarted: ServiceType[];
+  updated_env_files: string[];
+}
+
+export interface DriftItem {
+  service: ServiceType;
+  setting: string;
+  expected: string;
+  actual: string;
+  action: string;
+}
+
+export interface RepairReport {
+  integrity: IntegrityReport | null;
+  actions: string[];
+  errors: string[];
+}
+
+export interface ValidationIssue {
+  field: keyof AppSettings;
+  code: string;
+  message: string;
+}
+
+export interface ValidationResult {
+  errors: ValidationIssue[];
+  warnings: ValidationIssue[];
+}
+
+export interface ImportSummary {
+  settings: AppSettings;
+  vhosts_added: string[];
+  /** Non-fatal problems: services that failed to restart, vhosts not added */
+  errors: string[];
+}
+
+/** Result of `export_diagnostics` */
+export interface DiagnosticsReport {
+  path: string;
+  /** Entries written to the zip, e.g. "logs/caddy.log" */
+  files: string[];
+}
+
+/** Page opened by `open_in_browser`, URL computed from current settings */
+export type SiteTarget =
+  | { kind: "site" }
+  | { kind: "phpmyadmin" }
+  | { kind: "adminer" }
+  | { kind: "project"; name: string }
+  | { kind: "vhost"; host: string };
+
+/** Payload of the `service-state-changed` event */
+export interface ServiceStateChange {
+  service: ServiceType;
+  old: ServiceState;
+  new: ServiceState;
+  error_message?: string;
+}
+
+/** Error returned by every Tauri command, discriminated on `kind` */
+export type CamppError =
+  | { kind: "port_in_use"; message: string; port: number; owner: string | null }
+  | { kind: "binary_missing"; message: string; path: string }
+  | { kind: "init_failed"; message: string; service: ServiceType; log_excerpt: string[] }
+  | { kind: "service_not_running"; message: string; service: ServiceType }
+  | { kind: "invalid_input"; message: string }
+  | { kind: "internal"; message: string }
+  | { kind: "other"; message: string };
+
+export type CheckStatus = "pass" | "warn" | "fail" | "skipped";
+
+export interface HealthCheck {
+  /** "binary", "port", "http", "fastcgi", "ping" or "disk" */
+  name: string;
+  service: ServiceType | null;
+  status: CheckStatus;
+  message: string;
+  duration_ms: number;
+}
+
+/** Result of `health_check` */
+export interface HealthReport {
+  checks: HealthCheck[];
+  /** No check failed */
+  healthy: boolean;
+}
+
+export interface PortOwner {
+  pid: number;
+  name: string;
+  /** Full path of the executable, when it could be read */
+  path: string | null;
+}
+
+/** One problem found by `doctor` */
+export interface Finding {
+  /** e.g. "port_conflict", "vc_runtime_missing", "stale_caddyfile" */
+  code: string;
+  severity: "warning" | "error";
+  service: ServiceType | null;
+  title: string;
+  detail: string;
+  fix: string;
+}
+
+export type OperationKind = "download" | "database_init" | "backup" | "import" | "create_project" | "export" | "duplicate";
+
+/** Payload of the `operation-progress` event */
+export interface OperationProgress {
+  /** Id passed to `cancel_operation` */
+  id: string;
+  kind: OperationKind;
+  /** Current step, "done", "failed" or "cancelled" once finished */
+  step: string;
+  /** null while the length of the step is unknown */
+  percent: number | null;
+  message: string;
+  cancellable: boolean;
+}
+
+/** Snippet produced by `copy_connection_string` */
+export type DsnFormat = "mysqli" | "pdo" | "laravel_env" | "jdbc";
+
+/** Live traffic from `get_web_server_stats`, counters reset when Caddy restarts */
+export interface WebServerStats {
+  total_requests: number;
+  /** Responses by status code, e.g. "200" or "404" */
+  status_codes: Record<string, number>;
+  /** Requests being served right now */
+  active_connections: number;
+}
+
+/** A config file rendered by `preview_configs` */
+export interface ConfigPreview {
+  /** e.g. "Caddyfile", "php.ini", "my.cnf" */
+  name: string;
+  /** null for options passed on the command line (my.cnf) */
+  path: string | null;
+  content: string;
+  /** Unified diff against the current file, empty when unchanged */
+  diff: string;
+  changed: boolean;
+  /** The current file was edited by hand and is kept instead of regenerated */
+  hand_edited: boolean;
+}
+
+/** Resolved locations from `get_paths` */
+export interface AppPaths {
+  base_dir: string;
+  runtime_dir: string;
+  config_dir: string;
+  logs_dir: string;
+  projects_dir: string;
+  mysql_data_dir: string;
+  pgsql_data_dir: string;
+  /** Config files by name, e.g. "Caddyfile", "php.ini", "settings.json" */
+  configs: Record<string, string>;
+  /** Log file of each service */
+  logs: Record<ServiceType, string>;
+  /** Service binaries, null until the runtime is downloaded */
+  binaries: Record<string, string> | null;
+}
+
+export type Framework = "php" | "laravel" | "wordpress" | "symfony" | "static";
+
+/** A project from `list_projects` */
+export interface Project {
+  name: string;
+  /** Project folder */
+  path: string;
+  /** Document root relative to `path`, empty for the folder itself */
+  docroot: string;
+  framework: Framework;
+  /** Seconds since the Unix epoch */
+  created_at: number;
+  /** `<name>.test` once registered in the hosts file */
+  domain: string | null;
+  /** Pinned PHP version, e.g. "8.1"; null uses campp.json or the main PHP */
+  php_version: string | null;
+  /** Environment variables passed to PHP, over those of campp.json */
+  env: Record<string, string>;
+  /** Static sites only: serve index.html for paths that are not files */
+  spa_fallback: boolean;
+  /** Extra Caddy directives for the site block, besides Caddyfile.project */
+  caddy_snippet: string | null;
+  /** SHA-256 of the allowed Caddyfile.project; the file is ignored unless it matches */
+  trusted_caddy_file: string | null;
+  /** More host names, e.g. `api.shop.test`, each in the hosts file */
+  aliases: string[];
+  /** Served over HTTPS with a certificate from the local CA */
+  https: boolean;
+  /** Redirect plain HTTP to HTTPS; null follows the `https_redirect` setting */
+  https_redirect: boolean | null;
+  /** Port of the dev server of a running `run_npm_script`, the site proxies to it */
+  dev_server_port: number | null;
+  /** Set while the project is shared on the LAN */
+  lan_share: LanShare | null;
+}
+
+/** Where a project shared with `share_on_lan` is reachable */
+export interface LanShare {
+  /** LAN address of this machine when the project was shared */
+  address: string;
+  port: number;
+}
+
+export type QrFormat = "png" | "svg";
+
+/** Result of `get_share_qr`: the LAN share or tunnel URL as a QR code */
+export interface ShareQr {
+  url: string;
+  format: QrFormat;
+  /** PNG or SVG bytes */
+  data: number[];
+}
+
+/** Entry of `list_projects` */
+export interface ProjectSummary extends Project {
+  /** Custom domain URL, or `http://localhost:<port>/<name>/`; `https://` for HTTPS projects */
+  url: string;
+  /** Whether the project folder is still there */
+  exists: boolean;
+  /** Framework detected from the files currently in the folder */
+  detected_framework: Framework;
+  /** MySQL databases the project needs */
+  databases: string[];
+  /** null without databases or while MySQL is stopped */
+  databases_exist: boolean | null;
+  /** Seconds since the Unix epoch */
+  modified_at: number | null;
+  /** Caddyfile.project exists but waits to be allowed with `set_project_caddy_file_trusted` */
+  caddy_file_pending: boolean;
+}
+
+/** Archive written by `export_project` */
+export interface ExportReport {
+  path: string;
+  files: number;
+  size_bytes: number;
+  /** Databases dumped into the archive */
+  databases: string[];
+}
+
+/** Code editors `open_in_editor` can launch */
+export type Editor = "vscode" | "phpstorm" | "sublime";
+
+/** Additional PHP binary, served on the ports after `php_port` */
+export interface PhpBackend {
+  /** Major and minor version, e.g. "8.1" */
+  version: string;
+  binary: string;
+}
+
+/** A line of the CAMPP block in the hosts file */
+export interface HostsEntry {
+  ip: string;
+  host: string;
+}
+
+/** Wildcard DNS options */
+export interface DnsSettings {
+  enabled: boolean;
+  /** UDP port on 127.0.0.1 */
+  port: number;
+}
+
+/** From `get_dns_status` */
+export interface DnsStatus {
+  enabled: boolean;
+  /** Port it listens on, null when it is not running */
+  running_port: number | null;
+  port: number;
+  tld: string;
+  /** The OS resolver sends queries for the TLD to CAMPP */
+  resolver_configured: boolean;
+}
+
+export type TunnelProvider = "cloudflared" | "ngrok";
+
+export interface TunnelSettings {
+  provider: TunnelProvider;
+  /** Project the tunnel forwards to, null for the localhost site */
+  project: string | null;
+  /** Port of the client's local API on 127.0.0.1 */
+  port: number;
+  /** ngrok binary, looked up on PATH when null */
+  ngrok_binary: string | null;
+}
+
+/** From `get_tunnel_status` */
+export interface TunnelStatus {
+  provider: TunnelProvider;
+  project: string | null;
+  running: boolean;
+  /** Public URL, null until the tunnel is up */
+  url: string | null;
+  /** Set with `set_ngrok_authtoken`, kept out of settings */
+  has_ngrok_authtoken: boolean;
+}
+
+/** Payload of the "tunnel-url" event */
+export interface TunnelUrl {
+  project: string | null;
+  url: string;
+}
+
+export interface MailpitSettings {
+  /** SMTP port mail is sent to */
+  smtp_port: number;
+  ui_port: number;
+}
+
+export interface MemcachedSettings {
+  port: number;
+  /** Memory for items in megabytes */
+  memory_mb: number;
+  /** memcached binary, looked up on PATH when null */
+  binary: string | null;
+  /** memcached PHP extension built for CAMPP's PHP, looked up in PHP's extension directory when null */
+  php_extension: string | null;
+}
+
+/** From `get_memcached_status`, turned on and off with `set_service_enabled`; macOS and Linux only */
+export interface MemcachedStatus {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  /** Binary that is started */
+  binary: string;
+  binary_found: boolean;
+  /** The PHP extension that is loaded, null when PHP cannot use Memcached */
+  php_extension: string | null;
+}
+
+export interface MinioSettings {
+  /** S3 API port */
+  port: number;
+  console_port: number;
+}
+
+export interface MinioCredentials {
+  access_key: string;
+  secret_key: string;
+}
+
+/** From `get_minio_status`, turned on and off with `set_service_enabled` */
+export interface MinioStatus {
+  enabled: boolean;
+  running: boolean;
+  /** S3 endpoint for SDKs */
+  endpoint: string;
+  console_url: string;
+  /** null until MinIO first starts */
+  credentials: MinioCredentials | null;
+}
+
+export interface FtpSettings {
+  /** SFTP port */
+  sftp_port: number;
+  ftp_port: number;
+  /** sftpgo binary, looked up on PATH when null */
+  binary: string | null;
+}
+
+export interface FtpCredentials {
+  username: string;
+  password: string;
+}
+
+/** From `get_ftp_status` and `reset_ftp_password`, turned on and off with `set_service_enabled` */
+export interface FtpStatus {
+  enabled: boolean;
+  running: boolean;
+  sftp_port: number;
+  ftp_port: number;
+  /** Directory the account sees as `/` */
+  root: string;
+  /** null until the server first starts */
+  credentials: FtpCredentials | null;
+}
+
+/** From `get_mailpit_status`, turned on and off with `set_service_enabled` */
+export interface MailpitStatus {
+  enabled: boolean;
+  running: boolean;
+  smtp_port: number;
+  /** Address of the web UI */
+  ui_url: string;
+}
+
+/** From `run_npm_script` and `list_npm_scripts` */
+export interface NpmScript {
+  project: string;
+  script: string;
+  pid: number;
+  log_file: string;
+  /** Seconds since the Unix epoch */
+  started_at: number;
+  /** Port of the dev server the site proxies to */
+  port: number | null;
+}
+
+/** From `get_ca_status` and the local CA commands */
+export interface CaStatus {
+  /** The root certificate exists */
+  created: boolean;
+  root_cert_path: string;
+  common_name: string | null;
+  /** Expiry of the root, seconds since the Unix epoch */
+  expires_at: number | null;
+  /** SHA-256 of the root certificate, colon separated hex */
+  fingerprint: string | null;
+  /** The system trust store holds this root */
+  trusted: boolean;
+}
+
+/** Entry of `get_certificates`, a certificate Caddy issued for a site */
+export interface SiteCertificate {
+  /** The `.crt` file in Caddy's storage */
+  path: string;
+  subject_cn: string | null;
+  /** DNS names and IP addresses the certificate is valid for */
+  subject_alt_names: string[];
+  issuer_cn: string | null;
+  /** Seconds since the Unix epoch */
+  not_before: number;
+  not_after: number;
+  /** Issued by the CAMPP local CA */
+  local_ca: boolean;
+  expired: boolean;
+}
@@ -0,0 +1,13 @@
+import { CamppError } from "../types/services";
+
+export const isCamppError = (error: unknown): error is CamppError =>
+  typeof error === "object" && error !== null && "kind" in error && "message" in error;
+
+/** Text to show for a rejected `invoke`, with the log excerpt of a failed start */
+export const errorMessage = (error: unknown): string => {
+  if (!isCamppError(error)) return String(error);
+  if (error.kind === "init_failed" && error.log_excerpt.length > 0) {
+    return `${error.message}\n\n${error.log_excerpt.join("\n")}`;
+  }
+  return error.message;
+};